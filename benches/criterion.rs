@@ -0,0 +1,21 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+#[macro_use]
+extern crate criterion;
+extern crate threshold_secret_sharing as tss;
+
+use criterion::Criterion;
+use tss::bench_utils;
+
+fn sweeps(c: &mut Criterion) {
+    bench_utils::bench_all(c);
+}
+
+criterion_group!(benches, sweeps);
+criterion_main!(benches);
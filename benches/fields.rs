@@ -43,6 +43,14 @@ where
     });
 }
 
+#[cfg(not(feature = "largefield"))]
+benchmark_group!(
+    add,
+    bench_add<NaturalPrimeField<i64>>,
+    bench_add<MontgomeryField32>
+);
+
+#[cfg(feature = "largefield")]
 benchmark_group!(
     add,
     bench_add<NaturalPrimeField<i64>>,
@@ -50,6 +58,14 @@ benchmark_group!(
     bench_add<LargePrimeField>
 );
 
+#[cfg(not(feature = "largefield"))]
+benchmark_group!(
+    mul,
+    bench_mul<NaturalPrimeField<i64>>,
+    bench_mul<MontgomeryField32>
+);
+
+#[cfg(feature = "largefield")]
 benchmark_group!(
     mul,
     bench_mul<NaturalPrimeField<i64>>,
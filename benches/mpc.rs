@@ -90,14 +90,14 @@ pub fn bench_share<C: Config>(b: &mut Bencher) {
     let m = C::m();
     let secret_count = C::secret_count();
 
-    let pss = PackedSecretSharing {
-        secret_count: secret_count,
-        threshold: n - secret_count - 1,
-        share_count: m - 1,
-        omega_secrets: omega_n,
-        omega_shares: omega_m,
-        field: field.clone(),
-    };
+    let pss = PackedSecretSharing::with_roots(
+        n - secret_count - 1,
+        secret_count,
+        m - 1,
+        field.clone(),
+        omega_n,
+        omega_m,
+    );
 
     let secrets = field.encode_slice((0..secret_count).collect::<Vec<_>>());
 
@@ -111,12 +111,7 @@ pub fn bench_share<C: Config>(b: &mut Bencher) {
     //
     // println!("Reconstructing..");
     // let indices = (0..shares_sum.len() as u32).collect::<Vec<_>>();
-    // let secrets_sum = pss_B.fully_reconstruct(&*indices, &shares_sum).into_iter()
-    //     .enumerate()
-    //     .filter(|&(i,_)| i % 2 == 1)
-    //     .map(|(_,s)| s)
-    //     .take(secret_count)
-    //     .collect::<Vec<_>>();
+    // let secrets_sum = pss_B.reconstruct(&indices, &shares_sum);
     // println!("{:?}", secrets_sum);
     //
 }
@@ -22,9 +22,9 @@ pub fn bench_euclidean_egcd(b: &mut Bencher) {
 benchmark_group!(egcd, bench_euclidean_egcd, bench_binary_egcd);
 
 pub fn bench_weighted_sum_two_step(b: &mut Bencher) {
-    let ref field = MontgomeryField32::new(746497_u32.into());
-    let ref values = field.encode_slice(vec![5; 100]);
-    let ref weights = field.encode_slice(vec![2; 100]);
+    let field = &MontgomeryField32::new(746497_u32);
+    let values = &field.encode_slice(vec![5; 100]);
+    let weights = &field.encode_slice(vec![2; 100]);
 
     b.iter(|| {
         let _ = values
@@ -36,9 +36,9 @@ pub fn bench_weighted_sum_two_step(b: &mut Bencher) {
 }
 
 pub fn bench_weighted_sum_one_step(b: &mut Bencher) {
-    let ref field = MontgomeryField32::new(746497_u32.into());
-    let ref values = field.encode_slice(vec![5; 100]);
-    let ref weights = field.encode_slice(vec![2; 100]);
+    let field = &MontgomeryField32::new(746497_u32);
+    let values = &field.encode_slice(vec![5; 100]);
+    let weights = &field.encode_slice(vec![2; 100]);
 
     b.iter(|| {
         let _ = values
@@ -49,9 +49,9 @@ pub fn bench_weighted_sum_one_step(b: &mut Bencher) {
 }
 
 pub fn bench_weighted_sum_for(b: &mut Bencher) {
-    let ref field = MontgomeryField32::new(746497_u32.into());
-    let ref values = field.encode_slice(vec![5; 100]);
-    let ref weights = field.encode_slice(vec![2; 100]);
+    let field = &MontgomeryField32::new(746497_u32);
+    let values = &field.encode_slice(vec![5; 100]);
+    let weights = &field.encode_slice(vec![2; 100]);
 
     b.iter(|| {
         let mut sum = field.zero();
@@ -70,4 +70,57 @@ benchmark_group!(
     bench_weighted_sum_for
 );
 
-benchmark_main!(egcd, weighted_sum);
+pub fn bench_fft_plan_in_place(b: &mut Bencher) {
+    let field = &MontgomeryField32::new(5038849_u32);
+    let omega = field.encode(1814687);
+    let plan = tss::numtheory::fft::FftPlan::with_mixed_radix(field, 19683, &omega);
+    let mut data = field.encode_slice((0..19683_u32).collect::<Vec<_>>());
+
+    b.iter(|| {
+        plan.forward(&mut data);
+    })
+}
+
+pub fn bench_fft_plan_four_step(b: &mut Bencher) {
+    let field = &MontgomeryField32::new(5038849_u32);
+    let omega = field.encode(1814687);
+    let plan = tss::numtheory::fft::FftPlan::with_four_step(field, 19683, &omega);
+    let mut data = field.encode_slice((0..19683_u32).collect::<Vec<_>>());
+
+    b.iter(|| {
+        plan.forward(&mut data);
+    })
+}
+
+// 2^20 points over the field of 15.2^27 + 1
+pub fn bench_fft_plan_in_place_large(b: &mut Bencher) {
+    let field = &MontgomeryField32::new(2013265921_u32);
+    let omega = tss::math::find_root_of_unity(field, 1 << 20).unwrap();
+    let plan = tss::numtheory::fft::FftPlan::with_mixed_radix(field, 1 << 20, &omega);
+    let mut data = field.encode_slice((0..1 << 20).collect::<Vec<u32>>());
+
+    b.iter(|| {
+        plan.forward(&mut data);
+    })
+}
+
+pub fn bench_fft_plan_four_step_large(b: &mut Bencher) {
+    let field = &MontgomeryField32::new(2013265921_u32);
+    let omega = tss::math::find_root_of_unity(field, 1 << 20).unwrap();
+    let plan = tss::numtheory::fft::FftPlan::with_four_step(field, 1 << 20, &omega);
+    let mut data = field.encode_slice((0..1 << 20).collect::<Vec<u32>>());
+
+    b.iter(|| {
+        plan.forward(&mut data);
+    })
+}
+
+benchmark_group!(
+    fft_plan,
+    bench_fft_plan_in_place,
+    bench_fft_plan_four_step,
+    bench_fft_plan_in_place_large,
+    bench_fft_plan_four_step_large
+);
+
+benchmark_main!(egcd, weighted_sum, fft_plan);
@@ -10,34 +10,61 @@
 extern crate bencher;
 extern crate threshold_secret_sharing as tss;
 
-use tss::*;
-
 mod packed {
 
     use bencher::Bencher;
     use tss::packed::*;
 
     pub fn bench_large_secret_count(b: &mut Bencher) {
-        let ref pss = PSS_155_728_100;
+        let pss = &PSS_155_728_100;
         let all_secrets = vec![5; pss.secret_count * 100];
         b.iter(|| {
             let _shares: Vec<Vec<i64>> = all_secrets
                 .chunks(pss.secret_count)
-                .map(|secrets| pss.share(&secrets))
+                .map(|secrets| pss.share(secrets))
                 .collect();
         });
     }
 
+    pub fn bench_large_secret_count_batch(b: &mut Bencher) {
+        let pss = &PSS_155_728_100;
+        let all_secrets = vec![5; pss.secret_count * 100];
+        let chunks: Vec<&[i64]> = all_secrets.chunks(pss.secret_count).collect();
+        b.iter(|| {
+            let _shares = pss.share_batch(&chunks);
+        });
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn bench_large_secret_count_parallel(b: &mut Bencher) {
+        let pss = &PSS_155_728_100;
+        let all_secrets = vec![5; pss.secret_count * 100];
+        let chunks: Vec<&[i64]> = all_secrets.chunks(pss.secret_count).collect();
+        let thread_count = ::std::thread::available_parallelism().map_or(1, |n| n.get());
+        b.iter(|| {
+            let _shares = pss.share_batch_parallel(&chunks, thread_count);
+        });
+    }
+
     pub fn bench_large_share_count(b: &mut Bencher) {
-        let ref pss = PSS_155_19682_100;
+        let pss = &PSS_155_19682_100;
         let secrets = vec![5; pss.secret_count];
         b.iter(|| {
             let _shares = pss.share(&secrets);
         });
     }
 
+    pub fn bench_large_share_count_context(b: &mut Bencher) {
+        let pss = &PSS_155_19682_100;
+        let secrets = vec![5; pss.secret_count];
+        let mut context = PackedSharingContext::new(pss);
+        b.iter(|| {
+            let _shares = context.share(&secrets);
+        });
+    }
+
     pub fn bench_large_reconstruct(b: &mut Bencher) {
-        let ref pss = PSS_155_19682_100;
+        let pss = &PSS_155_19682_100;
         let secrets = vec![5; pss.secret_count];
         let all_shares = pss.share(&secrets);
 
@@ -46,7 +73,22 @@ mod packed {
         let shares = &all_shares[0..pss.reconstruct_limit()];
 
         b.iter(|| {
-            let _recovered_secrets = pss.reconstruct(&indices, &shares);
+            let _recovered_secrets = pss.reconstruct(&indices, shares);
+        });
+    }
+
+    pub fn bench_large_reconstruct_cached(b: &mut Bencher) {
+        let pss = &PSS_155_19682_100;
+        let secrets = vec![5; pss.secret_count];
+        let all_shares = pss.share(&secrets);
+
+        // reconstruct using minimum number of shares required, by the same committee
+        let indices: Vec<_> = (0..pss.reconstruct_limit() as u32).collect();
+        let shares = &all_shares[0..pss.reconstruct_limit()];
+        let cache = tss::InterpolationCache::new(1);
+
+        b.iter(|| {
+            let _recovered_secrets = pss.reconstruct_cached(&cache, &indices, shares);
         });
     }
 }
@@ -54,8 +96,17 @@ mod packed {
 benchmark_group!(
     packed,
     packed::bench_large_secret_count,
+    packed::bench_large_secret_count_batch,
     packed::bench_large_share_count,
-    packed::bench_large_reconstruct
+    packed::bench_large_share_count_context,
+    packed::bench_large_reconstruct,
+    packed::bench_large_reconstruct_cached
 );
 
+#[cfg(feature = "parallel")]
+benchmark_group!(parallel, packed::bench_large_secret_count_parallel);
+
+#[cfg(not(feature = "parallel"))]
 benchmark_main!(packed);
+#[cfg(feature = "parallel")]
+benchmark_main!(packed, parallel);
@@ -138,18 +138,18 @@ where
     F::P: From<u32>,
     F::E: Clone,
 {
-    let ref field = F::new(C::prime());
-    let ref omega_secrets = field.encode(C::omega_secrets());
-    let ref omega_shares = field.encode(C::omega_shares());
+    let field = &F::new(C::prime());
+    let omega_secrets = &field.encode(C::omega_secrets());
+    let omega_shares = &field.encode(C::omega_shares());
 
-    let ref values = field.encode_slice(vec![5; C::order_secrets()]);
+    let values = &field.encode_slice(vec![5; C::order_secrets()]);
 
     b.iter(|| {
         let mut data = values.clone();
-        ::numtheory::fft::fft2_inverse(field, &mut *data, omega_secrets);
+        ::numtheory::fft::fft2_inverse(field, &mut data, omega_secrets);
 
         data.extend(vec![field.zero(); C::order_shares() - C::order_secrets()]);
-        ::numtheory::fft::fft3(field, &mut *data, omega_shares);
+        ::numtheory::fft::fft3(field, &mut data, omega_shares);
     });
 }
 
@@ -159,17 +159,17 @@ where
     F::P: From<u32>,
     F::E: Clone,
 {
-    let ref field = F::new(C::prime());
-    let ref omega_secrets = field.encode(C::omega_secrets());
+    let field = &F::new(C::prime());
+    let omega_secrets = &field.encode(C::omega_secrets());
 
-    let ref values = field.encode_slice(vec![5; C::order_secrets()]);
+    let values = &field.encode_slice(vec![5; C::order_secrets()]);
 
     b.iter(|| {
         let mut data = values.clone();
-        ::numtheory::fft::fft2_inverse(field, &mut *data, &omega_secrets);
+        ::numtheory::fft::fft2_inverse(field, &mut data, omega_secrets);
 
         let _shares = (1..C::shares() as u32 + 1)
-            .map(|p| ::numtheory::mod_evaluate_polynomial(&data, &field.encode(p), field))
+            .map(|p| ::numtheory::mod_evaluate_polynomial(&data, field.encode(p), field))
             .collect::<Vec<_>>();
     });
 }
@@ -180,10 +180,10 @@ where
     F::P: From<u32>,
     F::E: Clone,
 {
-    let ref field = F::new(C::prime());
+    let field = &F::new(C::prime());
 
-    let ref values = field.encode_slice(vec![5; C::privacy() + C::secrets()]);
-    let ref points = (1..C::privacy() + C::secrets() + 1)
+    let values = &field.encode_slice(vec![5; C::privacy() + C::secrets()]);
+    let points = &(1..C::privacy() + C::secrets() + 1)
         .map(|p| field.sub(field.zero(), field.encode(p as u32)))
         .collect::<Vec<_>>();
 
@@ -202,10 +202,10 @@ where
     F::P: From<u32>,
     F::E: Clone,
 {
-    let ref field = F::new(C::prime());
+    let field = &F::new(C::prime());
 
-    let ref values = field.encode_slice(vec![5; C::privacy() + C::secrets()]);
-    let ref points = (1..C::privacy() + C::secrets() + 1)
+    let values = &field.encode_slice(vec![5; C::privacy() + C::secrets()]);
+    let points = &(1..C::privacy() + C::secrets() + 1)
         .map(|p| field.sub(field.zero(), field.encode(p as u32)))
         .collect::<Vec<_>>();
 
@@ -225,10 +225,10 @@ where
     F::P: From<u32>,
     F::E: Clone,
 {
-    let ref field = F::new(C::prime());
+    let field = &F::new(C::prime());
 
-    let ref values = field.encode_slice(vec![5; C::privacy() + C::secrets()]);
-    let ref points = (1..C::privacy() + C::secrets() + 1)
+    let values = &field.encode_slice(vec![5; C::privacy() + C::secrets()]);
+    let points = &(1..C::privacy() + C::secrets() + 1)
         .map(|p| field.sub(field.zero(), field.encode(p as u32)))
         .collect::<Vec<_>>();
 
@@ -252,15 +252,15 @@ where
     F::P: From<u32>,
     F::E: Clone,
 {
-    let ref field = F::new(C::prime());
+    let field = &F::new(C::prime());
 
-    let ref values = field.encode_slice(vec![5; C::privacy() + C::secrets()]);
-    let ref points = (1..C::privacy() + C::secrets() + 1)
+    let values = &field.encode_slice(vec![5; C::privacy() + C::secrets()]);
+    let points = &(1..C::privacy() + C::secrets() + 1)
         .map(|p| field.sub(field.zero(), field.encode(p as u32)))
         .collect::<Vec<_>>();
 
     // this could be precomputed since it's independent of the values and the points are fixed
-    let ref constants = (1..C::shares() as u32 + 1)
+    let constants = &(1..C::shares() as u32 + 1)
         .map(|p| ::numtheory::LagrangeConstants::compute(&field.encode(p), points, field))
         .collect::<Vec<_>>();
 
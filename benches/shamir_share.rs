@@ -96,32 +96,64 @@ impl Config for Huge {
     }
 }
 
+struct Full;
+impl Config for Full {
+    fn privacy() -> usize {
+        728
+    }
+    fn shares() -> usize {
+        2186
+    }
+    fn prime() -> u32 {
+        17497
+    }
+    fn omega() -> u32 {
+        256
+    }
+}
+
 pub fn share_fft<C: Config, F>(b: &mut Bencher)
 where
     F: PrimeField + New<u32> + Encode<u32>,
     F::P: From<u32>,
     F::E: Clone,
 {
-    let ref field = F::new(C::prime());
-    let ref omega_shares = field.encode(C::omega());
-    let ref values = field.encode_slice(vec![5; C::privacy() + 1]);
+    let field = &F::new(C::prime());
+    let omega_shares = &field.encode(C::omega());
+    let values = &field.encode_slice(vec![5; C::privacy() + 1]);
 
     b.iter(|| {
         let mut data = values.clone();
         data.extend(vec![field.zero(); C::shares() - C::privacy()]);
-        ::numtheory::fft::fft3(field, &mut *data, omega_shares);
+        ::numtheory::fft::fft3(field, &mut data, omega_shares);
         let _shares = data;
     });
 }
 
+pub fn share_scheme<C: Config, F>(b: &mut Bencher)
+where
+    F: PrimeField + New<u32> + Encode<u32> + Clone,
+    F::P: From<u32>,
+    F::E: Clone,
+{
+    let field = F::new(C::prime());
+    let omega_shares = field.encode(C::omega());
+    let secret = field.encode(5);
+    let tss = FftShamirSecretSharing::new(C::privacy(), C::shares(), field, omega_shares);
+
+    b.iter(|| {
+        let _shares = tss.share(secret.clone());
+    });
+}
+
 pub fn share_horner<C: Config, F>(b: &mut Bencher)
 where
     F: PrimeField + New<u32> + Encode<u32>,
     F::P: From<u32>,
     F::E: Clone,
 {
-    let ref field = F::new(C::prime());
-    let ref values = field.encode_slice(vec![5; C::privacy() + 1]);
+    let field = &F::new(C::prime());
+    let values = &field.encode_slice(vec![5; C::privacy() + 1]);
 
     b.iter(|| {
         let _shares = (1..C::shares() as u32 + 1)
@@ -130,17 +162,60 @@ where
     });
 }
 
+pub fn reconstruct_fft<C: Config, F>(b: &mut Bencher)
+where
+    F: PrimeField + New<u32> + Encode<u32> + Clone,
+    F::P: From<u32>,
+    F::E: Clone,
+{
+    let field = F::new(C::prime());
+    let omega_shares = field.encode(C::omega());
+    let tss = FftShamirSecretSharing::new(C::privacy(), C::shares(), field, omega_shares);
+    let shares = tss.share(tss.field().encode(5));
+    let indices: Vec<usize> = (0..C::shares()).collect();
+    // build the plan outside of the measurements
+    tss.reconstruct(&indices, &shares);
+
+    b.iter(|| {
+        let _secret = tss.reconstruct(&indices, &shares);
+    });
+}
+
+pub fn reconstruct_lagrange<C: Config, F>(b: &mut Bencher)
+where
+    F: PrimeField + New<u32> + Encode<u32> + Clone,
+    F::P: From<u32>,
+    F::E: Clone,
+{
+    let field = F::new(C::prime());
+    let omega_shares = field.encode(C::omega());
+    let tss = FftShamirSecretSharing::new(C::privacy(), C::shares(), field, omega_shares);
+    let shares = tss.share(tss.field().encode(5));
+    let points = tss.share_positions();
+
+    b.iter(|| {
+        let _secret = ::numtheory::lagrange_interpolation_at_zero(&points, &shares, tss.field());
+    });
+}
+
 benchmark_group!(group
     , share_fft    <Tiny, MontgomeryField32>
+    , share_scheme <Tiny, MontgomeryField32>
     , share_horner <Tiny, MontgomeryField32>
     , share_fft    <Small, MontgomeryField32>
+    , share_scheme <Small, MontgomeryField32>
     , share_horner <Small, MontgomeryField32>
     , share_fft    <Medium, MontgomeryField32>
+    , share_scheme <Medium, MontgomeryField32>
     , share_horner <Medium, MontgomeryField32>
     , share_fft    <Large, MontgomeryField32>
+    , share_scheme <Large, MontgomeryField32>
     , share_horner <Large, MontgomeryField32>
     , share_fft    <Huge, MontgomeryField32>
+    , share_scheme <Huge, MontgomeryField32>
     , share_horner <Huge, MontgomeryField32>
+    , reconstruct_fft      <Full, MontgomeryField32>
+    , reconstruct_lagrange <Full, MontgomeryField32>
 );
 
 benchmark_main!(group);
@@ -49,14 +49,14 @@ mod shamir_vs_packed {
     {
         let field = F::new(746497);
 
-        let pss = PackedSecretSharing {
-            threshold: 155,
-            share_count: 728,
-            secret_count: 100,
-            omega_secrets: field.encode(95660),
-            omega_shares: field.encode(610121),
-            field: field.clone(),
-        };
+        let pss = PackedSecretSharing::with_roots(
+            155,
+            100,
+            728,
+            field.clone(),
+            field.encode(95660),
+            field.encode(610121),
+        );
 
         let all_secrets = field.encode_slice(vec![5; 100]);
 
@@ -0,0 +1,43 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Print the latest criterion results as JSON lines, and fail if any benchmark got
+//! slower than in a saved baseline.
+//!
+//! Usage: `bench_report [<baseline> [<tolerance>]]`, run from the root of the
+//! crate after `cargo bench --bench criterion --features bench_utils`.
+extern crate threshold_secret_sharing as tss;
+
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process;
+
+use tss::bench_utils::{find_regressions, read_baseline, write_json_lines};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let tolerance: f64 = args
+        .get(1)
+        .map_or(0.1, |t| t.parse().expect("invalid tolerance"));
+    let criterion_dir = Path::new("target/criterion");
+
+    let current = read_baseline(criterion_dir, "new").expect("cannot read criterion results");
+    write_json_lines(&current, &mut io::stdout()).unwrap();
+
+    if let Some(baseline) = args.first() {
+        let baseline = read_baseline(criterion_dir, baseline).expect("cannot read baseline");
+        let regressions = find_regressions(&baseline, &current, tolerance);
+        for regression in &regressions {
+            eprintln!("regression in {}", regression);
+        }
+        if !regressions.is_empty() {
+            process::exit(1);
+        }
+    }
+}
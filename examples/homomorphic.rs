@@ -10,7 +10,7 @@ extern crate threshold_secret_sharing as tss;
 use tss::*;
 
 fn main() {
-    let ref pss = tss::packed::PSS_4_26_3;
+    let pss = &tss::packed::PSS_4_26_3;
     println!(
         "\
     Using parameters that: \n \
@@ -56,16 +56,13 @@ fn main() {
     );
 
     // multiply shares_1 and shares_2 point-wise
-    let shares_12: Vec<i64> = shares_1
-        .iter()
-        .zip(&shares_2)
-        .map(|(a, b)| pss.field.mul(a, b))
-        .collect();
+    let product_12 = pss.mul_shares(&shares_1, &shares_2);
+    let shares_12 = product_12.shares;
     // ... and reconstruct product, using double reconstruction limit
-    let shares_12_reconstruct_limit = pss.reconstruct_limit() * 2;
-    let foo: Vec<u32> = (0..shares_12_reconstruct_limit as u32).collect();
-    let bar = &shares_12[0..shares_12_reconstruct_limit];
-    let secrets_12 = pss.field.decode_slice(pss.reconstruct(&foo, bar));
+    let shares_12_reconstruct_limit = product_12.reconstruct_limit;
+    let indices: Vec<u32> = (0..shares_12_reconstruct_limit as u32).collect();
+    let shares = &shares_12[0..shares_12_reconstruct_limit];
+    let secrets_12: Vec<u32> = pss.reconstruct_decoded(&indices, shares);
     println!(
         "\nMultiplying shares S1 and S2 point-wise gives new shares S12 which \
         can be reconstructed (using {} of them) to give output vector: {:?}",
@@ -74,16 +71,13 @@ fn main() {
     assert_eq!(secrets_12, [4, 10, 18]);
 
     // multiply shares_3 and shares_4 point-wise
-    let shares_34: Vec<i64> = shares_3
-        .iter()
-        .zip(&shares_4)
-        .map(|(a, b)| pss.field.mul(a, b))
-        .collect();
+    let product_34 = pss.mul_shares(&shares_3, &shares_4);
+    let shares_34 = product_34.shares;
     // ... and reconstruct product, using double reconstruction limit
-    let shares_34_reconstruct_limit = pss.reconstruct_limit() * 2;
-    let foo: Vec<u32> = (0..shares_34_reconstruct_limit as u32).collect();
-    let bar = &shares_34[0..shares_34_reconstruct_limit];
-    let secrets_34 = pss.field.decode_slice(pss.reconstruct(&foo, bar));
+    let shares_34_reconstruct_limit = product_34.reconstruct_limit;
+    let indices: Vec<u32> = (0..shares_34_reconstruct_limit as u32).collect();
+    let shares = &shares_34[0..shares_34_reconstruct_limit];
+    let secrets_34: Vec<u32> = pss.reconstruct_decoded(&indices, shares);
     println!(
         "\nLikewise, multiplying shares S3 and S4 point-wise gives new shares S34 \
         which can be reconstructed (using {} of them) to give output vector: {:?}",
@@ -99,9 +93,9 @@ fn main() {
         .collect();
     // ... and reconstruct product, using double reconstruction limit
     let shares_1234product_reconstruct_limit = shares_1234product.len();
-    let foo: Vec<u32> = (0..shares_1234product_reconstruct_limit as u32).collect();
-    let bar = &shares_1234product[0..shares_1234product_reconstruct_limit];
-    let secrets_1234product = pss.field.decode_slice(pss.reconstruct(&foo, bar));
+    let indices: Vec<u32> = (0..shares_1234product_reconstruct_limit as u32).collect();
+    let shares = &shares_1234product[0..shares_1234product_reconstruct_limit];
+    let secrets_1234product: Vec<u32> = pss.reconstruct_decoded(&indices, shares);
     println!(
         "\nIf we continue multiplying these new shares S12 and S34 then we no longer \
         have enough shares to reconstruct correctly; using all {} shares gives incorrect (random) \
@@ -110,16 +104,12 @@ fn main() {
     );
 
     // add shares_12 and shares_34 point-wise
-    let shares_1234sum: Vec<i64> = shares_12
-        .iter()
-        .zip(&shares_34)
-        .map(|(a, b)| pss.field.add(a, b))
-        .collect();
+    let shares_1234sum = pss.add_shares(&shares_12, &shares_34);
     // ... and reconstruct sum, using same reconstruction limit as inputs
     let shares_1234sum_reconstruct_limit = pss.reconstruct_limit() * 2;
-    let foo: Vec<u32> = (0..shares_1234sum_reconstruct_limit as u32).collect();
-    let bar = &shares_1234sum[0..shares_1234sum_reconstruct_limit];
-    let secrets_1234sum = pss.field.decode_slice(pss.reconstruct(&foo, bar));
+    let indices: Vec<u32> = (0..shares_1234sum_reconstruct_limit as u32).collect();
+    let shares = &shares_1234sum[0..shares_1234sum_reconstruct_limit];
+    let secrets_1234sum: Vec<u32> = pss.reconstruct_decoded(&indices, shares);
     println!(
         "\nHowever, adding shares S12 and S34 point-wise doesn't increase the \
         reconstruction limit and hence using {} shares we can still recover their sum: {:?}",
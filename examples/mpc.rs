@@ -1,14 +1,13 @@
 // Copyright (c) 2017 rust-threshold-secret-sharing developers
 
-extern crate rand;
 extern crate threshold_secret_sharing as tss;
 
-#[cfg(not(all(feature = "largefield", feature = "safety_override")))]
+#[cfg(not(feature = "largefield"))]
 fn main() {
-    println!("Please run with '--features \"largefield safety_override\"'");
+    println!("Please run with '--features largefield'");
 }
 
-#[cfg(all(feature = "largefield", feature = "safety_override"))]
+#[cfg(feature = "largefield")]
 #[allow(non_snake_case)]
 fn main() {
     use tss::*;
@@ -49,23 +48,20 @@ fn main() {
     assert![group_n_B.intersection(&group_m).count() == 0];
     assert![group_n_A.intersection(&group_n_B).count() as u32 == n / 2 - 1];
 
-    let pss_A = PackedSecretSharing {
-        secret_count: secret_count,
-        threshold: n_A as usize - secret_count - 1,
-        share_count: m as usize - 1,
-        omega_secrets: omega_secrets_A,
-        omega_shares: omega_shares.clone(),
-        field: field.clone(),
-    };
+    let pss_A = PackedSecretSharing::with_roots(n_A as usize - secret_count - 1, secret_count, m as usize - 1, field.clone(), omega_secrets_A, omega_shares.clone());
 
-    let pss_B = PackedSecretSharing {
-        secret_count: secret_count,
-        threshold: n_B as usize - secret_count - 1,
-        share_count: m as usize - 1,
-        omega_secrets: omega_secrets_B,
-        omega_shares: omega_shares.clone(),
-        field: field.clone(),
-    };
+    let pss_B = PackedSecretSharing::with_roots(n_B as usize - secret_count - 1, secret_count, m as usize - 1, field.clone(), omega_secrets_B, omega_shares.clone())
+        // secrets at the powers of omega_secrets_A, like those of pss_A
+        .with_layout(SecretLayout::Strided(2))
+        .unwrap();
+
+    // - remaining constraints of each scheme, including primality of the field
+    assert![pss_A.validate().is_ok()];
+    assert![pss_B.validate().is_ok()];
+    // - shares of both schemes live at the same points, so they can be summed
+    assert![pss_A.share_positions() == pss_B.share_positions()];
+    // - and so do their secrets, thanks to the layout of pss_B
+    assert![pss_A.secret_positions() == pss_B.secret_positions()];
 
     println!("Sharing for A..");
     let secrets_A = field.encode_slice((0..secret_count as u32).collect::<Vec<_>>());
@@ -75,16 +71,7 @@ fn main() {
     println!("Sharing for B..");
     let secrets_B = field.encode_slice((0..secret_count as u32).collect::<Vec<_>>());
     println!("{:?}", secrets_B);
-    let randomness_B = {
-        use rand;
-        let mut rng = rand::OsRng::new().unwrap();
-        field.sample_with_replacement(pss_B.threshold, &mut rng)
-    };
-    let mut secrets_and_randomess_B = randomness_B;
-    for (i, s) in secrets_B.into_iter().enumerate() {
-        secrets_and_randomess_B.insert(2 * i + 1, s);
-    }
-    let shares_B = pss_B.deterministic_share(&secrets_and_randomess_B);
+    let shares_B = pss_B.share(&secrets_B);
 
     println!("Summing..");
     let shares_sum = shares_A
@@ -95,13 +82,6 @@ fn main() {
 
     println!("Reconstructing..");
     let indices = (0..shares_sum.len() as u32).collect::<Vec<_>>();
-    let secrets_sum = pss_B
-        .fully_reconstruct(&*indices, &shares_sum)
-        .into_iter()
-        .enumerate()
-        .filter(|&(i, _)| i % 2 == 1)
-        .map(|(_, s)| s)
-        .take(secret_count)
-        .collect::<Vec<_>>();
+    let secrets_sum = pss_B.reconstruct(&indices, &shares_sum);
     println!("{:?}", secrets_sum);
 }
@@ -35,15 +35,13 @@ fn main() {
     // };
     // assert_eq!(pss, expected_pss);
 
-    let secrets = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
-    let shares = pss.share(&pss.field.encode_slice(&secrets));
+    let secrets: Vec<u32> = (0..10).collect();
+    let shares = pss.share_values(&secrets);
 
     let indices: Vec<u32> = (0..shares.len() as u32).collect();
-    let reconstructed_secrets = pss.reconstruct(&indices, &shares);
+    let reconstructed_secrets: Vec<u32> = pss.reconstruct_decoded(&indices, &shares);
+    // decoding gives the canonical representation of numbers in `[0, prime)`, where
+    // the raw field elements may be `x - prime` instead of `x`
 
-    // assert_eq!(secrets, reconstructed_secrets);
-    // above will likely fail since some values may be `x - prime` instead of `x`
-
-    assert_eq!(secrets, pss.field.decode_slice(reconstructed_secrets));
-    // the above ensures a canonical representation of numbers in `[0, prime)`
+    assert_eq!(secrets, reconstructed_secrets);
 }
@@ -13,10 +13,10 @@ fn main() {
     type MyField = NaturalPrimeField<i64>;
     let field = MyField::new(41); // any large enough prime will do
 
-    let ref tss = tss::ShamirSecretSharing {
+    let tss = &tss::ShamirSecretSharing {
         threshold: 9,
         share_count: 20,
-        field: field,
+        field,
     };
 
     let secret = 5;
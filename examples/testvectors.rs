@@ -0,0 +1,43 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Print the test vectors of all schemes and fields as JSON, or check the vectors
+//! of a file against the crate.
+//!
+//! Usage: `testvectors [--check <path>]`. The fixtures in `testvectors/` are
+//! regenerated with `cargo run --example testvectors --features testvectors >
+//! testvectors/vectors.json`.
+extern crate threshold_secret_sharing as tss;
+
+use std::env;
+use std::fs::File;
+use std::io;
+use std::process;
+
+use tss::testvectors::{generate, read_json, replay_all, write_json};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None => write_json(&generate(), &mut io::stdout()).unwrap(),
+        Some("--check") => {
+            let path = args.get(1).expect("missing path of the vectors");
+            let file = File::open(path).expect("cannot open vectors");
+            let vectors = read_json(file).expect("cannot read vectors");
+            if let Err((position, err)) = replay_all(&vectors) {
+                eprintln!("vector {} failed: {}", position, err);
+                process::exit(1);
+            }
+            println!("{} vectors passed", vectors.len());
+        }
+        Some(arg) => {
+            eprintln!("unknown argument {}", arg);
+            process::exit(2);
+        }
+    }
+}
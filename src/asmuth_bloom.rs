@@ -0,0 +1,301 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! [Asmuth-Bloom secret sharing](https://en.wikipedia.org/wiki/Secret_sharing_using_the_Chinese_remainder_theorem)
+//! based on the Chinese remainder theorem.
+
+use fields::ForwardSecureRng;
+use numtheory::Primality;
+use rand::Rng;
+
+/// Parameters for the Asmuth-Bloom scheme, specifying privacy threshold and the moduli
+/// of the secret and of each share.
+///
+/// The moduli must satisfy the following constraints:
+///
+/// * `secret_modulus` and all of `moduli` are pairwise coprime
+/// * `moduli` is increasing, and each is larger than `secret_modulus`
+/// * the product of the `threshold + 1` smallest `moduli` is larger than `secret_modulus`
+///   times the product of the `threshold` largest, and fits in a `u128`
+///
+/// Suitable parameters can be found with `new`.
+///
+/// # Example:
+///
+/// ```
+///    use threshold_secret_sharing::*;
+///    let abss = AsmuthBloomSecretSharing::new(1000, 2, 5).unwrap();
+///
+///    let secret = 123;
+///    let all_shares = abss.share(secret);
+///
+///    let indices: Vec<usize> = vec![0, 2, 4];
+///    let shares: Vec<u64> = indices.iter().map(|&i| all_shares[i]).collect();
+///    assert_eq!(abss.reconstruct(&indices, &shares), secret);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsmuthBloomSecretSharing {
+    /// Maximum number of shares that can be known without exposing the secret.
+    pub threshold: usize,
+    /// Secrets must be smaller than this modulus.
+    pub secret_modulus: u64,
+    /// Modulus of each share; the number of shares is given by their count.
+    pub moduli: Vec<u64>,
+}
+
+impl AsmuthBloomSecretSharing {
+    /// Find suitable moduli for sharing secrets smaller than `secret_modulus`.
+    ///
+    /// The moduli are consecutive primes just large enough to satisfy the
+    /// Asmuth-Bloom condition, or `None` if these do not fit in the supported sizes.
+    pub fn new(
+        secret_modulus: u64,
+        threshold: usize,
+        share_count: usize,
+    ) -> Option<AsmuthBloomSecretSharing> {
+        assert!(secret_modulus >= 2);
+        assert!(share_count > threshold);
+        let mut base = secret_modulus;
+        loop {
+            let moduli = coprime_sequence(base, secret_modulus, share_count)?;
+            let scheme = AsmuthBloomSecretSharing {
+                threshold,
+                secret_modulus,
+                moduli,
+            };
+            // the smallest product does not fit so larger moduli will not either
+            scheme.reconstruct_bound()?;
+            if scheme.is_valid() {
+                return Some(scheme);
+            }
+            base = base.checked_mul(2)?;
+        }
+    }
+
+    /// Number of shares.
+    pub fn share_count(&self) -> usize {
+        self.moduli.len()
+    }
+
+    /// Minimum number of shares required to reconstruct secret.
+    ///
+    /// For this scheme this is always `threshold + 1`.
+    pub fn reconstruct_limit(&self) -> usize {
+        self.threshold + 1
+    }
+
+    /// Check that the moduli satisfy the constraints listed above.
+    pub fn is_valid(&self) -> bool {
+        let limit = self.reconstruct_limit();
+        if self.moduli.len() < limit
+            || self.moduli[0] <= self.secret_modulus
+            || self.moduli.windows(2).any(|pair| pair[0] >= pair[1])
+        {
+            return false;
+        }
+        let all = ::std::iter::once(&self.secret_modulus).chain(&self.moduli);
+        for (i, &a) in all.clone().enumerate() {
+            if all.clone().skip(i + 1).any(|&b| gcd(a, b) != 1) {
+                return false;
+            }
+        }
+        let lower = match self.reconstruct_bound() {
+            Some(lower) => lower,
+            None => return false,
+        };
+        let upper = self.moduli[self.moduli.len() - self.threshold..]
+            .iter()
+            .try_fold(self.secret_modulus as u128, |acc, &m| {
+                acc.checked_mul(m as u128)
+            });
+        upper.is_some_and(|upper| upper < lower)
+    }
+
+    /// Product of the `reconstruct_limit` smallest moduli, bounding the shared value.
+    fn reconstruct_bound(&self) -> Option<u128> {
+        self.moduli[..self.reconstruct_limit()]
+            .iter()
+            .try_fold(1_u128, |acc, &m| acc.checked_mul(m as u128))
+    }
+
+    /// Generate `share_count` shares from `secret`.
+    pub fn share(&self, secret: u64) -> Vec<u64> {
+        assert!(secret < self.secret_modulus);
+        let bound = self.reconstruct_bound().unwrap();
+        // mask the secret by a random multiple of its modulus, keeping below `bound`
+        let multiples = (bound - secret as u128 - 1) / self.secret_modulus as u128 + 1;
+        let mut rng = ForwardSecureRng::new();
+        let masked =
+            secret as u128 + sample_below(multiples, &mut rng) * self.secret_modulus as u128;
+        self.moduli
+            .iter()
+            .map(|&m| (masked % m as u128) as u64)
+            .collect()
+    }
+
+    /// Reconstruct `secret` from a large enough subset of the shares.
+    ///
+    /// `indices` are the ranks of the known shares as output by the `share` method,
+    /// while `values` are the actual values of these shares.
+    /// Both must have the same number of elements, and at least `reconstruct_limit`.
+    pub fn reconstruct(&self, indices: &[usize], shares: &[u64]) -> u64 {
+        assert!(shares.len() == indices.len());
+        assert!(shares.len() >= self.reconstruct_limit());
+        // combine the first `reconstruct_limit` shares by Garner's algorithm; their
+        // product is at least `reconstruct_bound` so the masked secret is recovered
+        let mut value: u128 = 0;
+        let mut modulus: u128 = 1;
+        for (&index, &share) in indices.iter().zip(shares).take(self.reconstruct_limit()) {
+            let m = self.moduli[index];
+            let difference = (share as u128 + m as u128 - value % m as u128) % m as u128;
+            let inverse = inverse_mod((modulus % m as u128) as u64, m);
+            let factor = mul_mod(difference as u64, inverse, m);
+            value += factor as u128 * modulus;
+            modulus *= m as u128;
+        }
+        (value % self.secret_modulus as u128) as u64
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// Inverse of `a` modulo `m`, which must be coprime.
+///
+/// The remainders and Bezout coefficients of the extended Euclidean algorithm
+/// are bounded by `m` in absolute value, so they fit in an `i128` for any `u64`.
+fn inverse_mod(a: u64, m: u64) -> u64 {
+    let (mut r0, mut r1) = (m as i128, a as i128);
+    let (mut t0, mut t1) = (0_i128, 1_i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    debug_assert_eq!(r0, 1, "not coprime");
+    t0.rem_euclid(m as i128) as u64
+}
+
+/// The `count` smallest primes larger than `base` not dividing `coprime_to`.
+fn coprime_sequence(base: u64, coprime_to: u64, count: usize) -> Option<Vec<u64>> {
+    let mut sequence = Vec::with_capacity(count);
+    let mut candidate = base;
+    while sequence.len() < count {
+        candidate = candidate.checked_add(1)?;
+        if candidate.is_probable_prime() && coprime_to % candidate != 0 {
+            sequence.push(candidate);
+        }
+    }
+    Some(sequence)
+}
+
+/// Uniformly random value in `[0, bound)`, using rejection sampling.
+fn sample_below<R: Rng>(bound: u128, rng: &mut R) -> u128 {
+    let mask = u128::MAX >> bound.leading_zeros();
+    loop {
+        let x = ((rng.next_u64() as u128) << 64 | rng.next_u64() as u128) & mask;
+        if x < bound {
+            return x;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_wikipedia_example() {
+        // 2-of-3 sharing with secret modulus 3
+        let abss = AsmuthBloomSecretSharing {
+            threshold: 1,
+            secret_modulus: 3,
+            moduli: vec![11, 13, 17],
+        };
+        assert!(abss.is_valid());
+        // masked secret 2 + 3 * 40
+        let shares = [122 % 11, 122 % 13, 122 % 17];
+        assert_eq!(abss.reconstruct(&[0, 1], &shares[0..2]), 2);
+        assert_eq!(abss.reconstruct(&[1, 2], &shares[1..3]), 2);
+        assert_eq!(abss.reconstruct(&[2, 0], &[shares[2], shares[0]]), 2);
+    }
+
+    #[test]
+    fn test_is_valid() {
+        let valid = AsmuthBloomSecretSharing {
+            threshold: 1,
+            secret_modulus: 3,
+            moduli: vec![11, 13, 17],
+        };
+        assert!(valid.is_valid());
+        let not_coprime = AsmuthBloomSecretSharing {
+            moduli: vec![11, 13, 22],
+            ..valid.clone()
+        };
+        assert!(!not_coprime.is_valid());
+        let too_unbalanced = AsmuthBloomSecretSharing {
+            moduli: vec![5, 7, 37],
+            ..valid.clone()
+        };
+        assert!(!too_unbalanced.is_valid());
+    }
+
+    #[test]
+    fn test_share_reconstruct() {
+        let abss = AsmuthBloomSecretSharing::new(1 << 20, 3, 7).unwrap();
+        assert!(abss.is_valid());
+        assert_eq!(abss.share_count(), 7);
+        for &secret in &[0, 1, 12345, (1 << 20) - 1] {
+            let shares = abss.share(secret);
+            assert_eq!(abss.reconstruct(&[0, 1, 2, 3], &shares[0..4]), secret);
+            assert_eq!(abss.reconstruct(&[3, 4, 5, 6], &shares[3..7]), secret);
+            assert_eq!(
+                abss.reconstruct(
+                    &[6, 1, 4, 2, 0],
+                    &[shares[6], shares[1], shares[4], shares[2], shares[0]]
+                ),
+                secret
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_moduli() {
+        // moduli above 2^63 do not fit in an `i64`
+        let abss = AsmuthBloomSecretSharing {
+            threshold: 1,
+            secret_modulus: 1000,
+            moduli: coprime_sequence(1 << 63, 1000, 3).unwrap(),
+        };
+        assert!(abss.is_valid());
+        for &secret in &[0, 1, 999] {
+            let shares = abss.share(secret);
+            assert_eq!(abss.reconstruct(&[0, 1], &shares[0..2]), secret);
+            assert_eq!(abss.reconstruct(&[2, 0], &[shares[2], shares[0]]), secret);
+        }
+        assert_eq!(inverse_mod(2, (1 << 62) + 1), (1 << 61) + 1);
+    }
+
+    #[test]
+    fn test_new_too_large() {
+        // 5 moduli above 2^40 cannot have their product fit in 128 bits
+        assert_eq!(AsmuthBloomSecretSharing::new(1 << 40, 4, 6), None);
+        assert!(AsmuthBloomSecretSharing::new(1 << 40, 1, 6).is_some());
+    }
+}
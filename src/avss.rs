@@ -0,0 +1,437 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Asynchronous verifiable secret sharing (AVSS) from bivariate polynomials.
+//!
+//! The dealer hides the secret as the value at zero of a random bivariate
+//! polynomial `phi(x, y)` of degree `threshold` in both variables, and sends every
+//! party `i` its row `phi(x_i, y)` and column `phi(x, x_i)`, where `x_i = i + 1`.
+//! The parties then agree on the sharing in the style of Bracha's broadcast:
+//!
+//! 1. on receiving its polynomials, a party sends every party `j` an echo holding
+//!    the values of its row and column at `x_j`, which are points of the column
+//!    and row of `j`;
+//! 2. once it has echoes from more than `(share_count + threshold) / 2` parties, or
+//!    ready messages from `threshold + 1` of them, a party sends ready messages
+//!    holding the same points;
+//! 3. once it has ready messages from `2 * threshold + 1` parties, a party
+//!    completes with its share `phi(x_i, 0)` of the secret.
+//!
+//! With a live dealer, all live parties complete despite up to `threshold` crashed
+//! parties. If the dealer crashes midway, either no live party completes or all of
+//! them do: a party that never hears from the dealer interpolates its row and
+//! column from the points in `threshold + 1` echo or ready messages. Points that
+//! disagree with the polynomials of a party are ignored, but the protocol does not
+//! attempt to tolerate Byzantine parties.
+//!
+//! The state machines here do not do any networking: every incoming message is
+//! passed to `AvssReceiver::handle`, which returns the messages to send in reply,
+//! addressed by the index of their recipient. The channels must be authenticated.
+
+use bivariate::BivariatePolynomial;
+use fields::ForwardSecureRng;
+use fields::{Encode, Field};
+use numtheory::linalg::{mul_vector, vandermonde_inverse};
+use numtheory::mod_evaluate_polynomial;
+use rand::Rng;
+use shamir::ShamirSecretSharing;
+use share::{index_u32, ShareIndex};
+
+/// Message of the protocol.
+pub enum AvssMessage<F: Field> {
+    /// Row and column polynomial of the recipient, sent by the dealer.
+    Send { row: Vec<F::E>, column: Vec<F::E> },
+    /// Values of the row and column polynomial of the sender at the point of the
+    /// recipient.
+    Echo { row: F::E, column: F::E },
+    /// Values as in `Echo`, sent once the sender is sure that the sharing will
+    /// complete.
+    Ready { row: F::E, column: F::E },
+}
+
+element_impls!(Clone, PartialEq, Debug for enum AvssMessage {
+    Send { row, column },
+    Echo { row, column },
+    Ready { row, column }
+});
+
+/// Point of the party with the given index.
+fn party_point<F: Field + Encode<u32>>(field: &F, index: ShareIndex) -> F::E {
+    field.encode(index_u32(index) + 1)
+}
+
+/// Dealer of a sharing.
+pub struct AvssDealer<F: Field>
+where
+    F::E: Clone,
+{
+    tss: ShamirSecretSharing<F>,
+    polynomial: BivariatePolynomial<F>,
+}
+
+impl<F> AvssDealer<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Dealer of `secret` among the parties of `tss`.
+    ///
+    /// The randomness is sampled from a `ForwardSecureRng` seeded from the operating
+    /// system.
+    pub fn new(tss: &ShamirSecretSharing<F>, secret: F::E) -> AvssDealer<F> {
+        AvssDealer::with_rng(tss, secret, &mut ForwardSecureRng::new())
+    }
+
+    /// Dealer of `secret` among the parties of `tss`, sampling the randomness from
+    /// `rng`.
+    pub fn with_rng<R: Rng>(
+        tss: &ShamirSecretSharing<F>,
+        secret: F::E,
+        rng: &mut R,
+    ) -> AvssDealer<F> {
+        let polynomial =
+            BivariatePolynomial::sample(&tss.field, secret, tss.threshold, tss.threshold, rng);
+        AvssDealer {
+            tss: tss.clone(),
+            polynomial,
+        }
+    }
+
+    /// Messages to send to every party to start the protocol, addressed by the
+    /// index of their recipient.
+    pub fn messages(&self) -> Vec<(ShareIndex, AvssMessage<F>)> {
+        (0..self.tss.share_count)
+            .map(|index| {
+                let point = party_point(&self.tss.field, index);
+                let message = AvssMessage::Send {
+                    row: self.polynomial.row(&self.tss.field, &point),
+                    column: self.polynomial.column(&self.tss.field, &point),
+                };
+                (index, message)
+            })
+            .collect()
+    }
+}
+
+/// State of a party receiving a sharing.
+pub struct AvssReceiver<F: Field>
+where
+    F::E: Clone,
+{
+    tss: ShamirSecretSharing<F>,
+    index: ShareIndex,
+    row: Option<Vec<F::E>>,
+    column: Option<Vec<F::E>>,
+    /// Values of the row of this party at the point of every other party.
+    row_points: Vec<Option<F::E>>,
+    /// Values of the column of this party at the point of every other party.
+    column_points: Vec<Option<F::E>>,
+    echoes: Vec<bool>,
+    readies: Vec<bool>,
+    sent_echo: bool,
+    sent_ready: bool,
+    share: Option<F::E>,
+}
+
+impl<F> AvssReceiver<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// State of the party with index `index` among the parties of `tss`.
+    ///
+    /// There must be more than three times `threshold` parties.
+    pub fn new(tss: &ShamirSecretSharing<F>, index: ShareIndex) -> AvssReceiver<F> {
+        assert!(tss.share_count > 3 * tss.threshold);
+        assert!(index < tss.share_count);
+        AvssReceiver {
+            tss: tss.clone(),
+            index,
+            row: None,
+            column: None,
+            row_points: vec![None; tss.share_count],
+            column_points: vec![None; tss.share_count],
+            echoes: vec![false; tss.share_count],
+            readies: vec![false; tss.share_count],
+            sent_echo: false,
+            sent_ready: false,
+            share: None,
+        }
+    }
+
+    /// Index of this party.
+    pub fn index(&self) -> ShareIndex {
+        self.index
+    }
+
+    /// Share of the secret, once the sharing has completed.
+    pub fn share(&self) -> Option<&F::E> {
+        self.share.as_ref()
+    }
+
+    /// Whether the sharing has completed for this party.
+    pub fn is_complete(&self) -> bool {
+        self.share.is_some()
+    }
+
+    /// Handle `message` from the party with index `from`, returning the messages to
+    /// send in reply, addressed by the index of their recipient.
+    ///
+    /// `from` is ignored for `Send` messages, which must come from the dealer, and
+    /// other messages are ignored if `from` is not the index of a party.
+    pub fn handle(
+        &mut self,
+        from: ShareIndex,
+        message: AvssMessage<F>,
+    ) -> Vec<(ShareIndex, AvssMessage<F>)> {
+        let mut outgoing = vec![];
+        match message {
+            AvssMessage::Send { row, column } => {
+                let size = self.tss.threshold + 1;
+                if self.sent_echo || row.len() != size || column.len() != size {
+                    return outgoing;
+                }
+                // polynomials already interpolated from the points of others are the same
+                if self.row.is_none() {
+                    self.row = Some(row);
+                    self.column = Some(column);
+                }
+                self.sent_echo = true;
+                outgoing
+                    .extend(self.points_for_all(|row, column| AvssMessage::Echo { row, column }));
+            }
+            AvssMessage::Echo { row, column } => {
+                if self.echoes.get(from) != Some(&false) || !self.record_points(from, row, column) {
+                    return outgoing;
+                }
+                self.echoes[from] = true;
+            }
+            AvssMessage::Ready { row, column } => {
+                if self.readies.get(from) != Some(&false) || !self.record_points(from, row, column)
+                {
+                    return outgoing;
+                }
+                self.readies[from] = true;
+            }
+        }
+        self.advance(&mut outgoing);
+        outgoing
+    }
+
+    /// Record the values of the row and column of the party `from` at the point of
+    /// this party, unless they disagree with the polynomials of this party.
+    fn record_points(&mut self, from: ShareIndex, row: F::E, column: F::E) -> bool {
+        let field = &self.tss.field;
+        let point = party_point(field, from);
+        if let (Some(own_row), Some(own_column)) = (self.row.as_ref(), self.column.as_ref()) {
+            // phi(x_from, x_self) is on our column, and phi(x_self, x_from) on our row
+            if field.neq(mod_evaluate_polynomial(own_column, &point, field), &row)
+                || field.neq(mod_evaluate_polynomial(own_row, &point, field), &column)
+            {
+                return false;
+            }
+        }
+        self.column_points[from] = Some(row);
+        self.row_points[from] = Some(column);
+        true
+    }
+
+    /// Messages with the values of the polynomials of this party at the point of
+    /// every party.
+    fn points_for_all<M>(&self, message: M) -> Vec<(ShareIndex, AvssMessage<F>)>
+    where
+        M: Fn(F::E, F::E) -> AvssMessage<F>,
+    {
+        let field = &self.tss.field;
+        let row = self.row.as_ref().expect("polynomials must be known");
+        let column = self.column.as_ref().expect("polynomials must be known");
+        (0..self.tss.share_count)
+            .map(|index| {
+                let point = party_point(field, index);
+                let message = message(
+                    mod_evaluate_polynomial(row, &point, field),
+                    mod_evaluate_polynomial(column, &point, field),
+                );
+                (index, message)
+            })
+            .collect()
+    }
+
+    /// Interpolate the polynomials of this party from the points received from
+    /// `threshold + 1` parties, if there are enough of them.
+    fn interpolate(&mut self) {
+        let field = &self.tss.field;
+        let senders: Vec<ShareIndex> = (0..self.tss.share_count)
+            .filter(|&i| self.row_points[i].is_some())
+            .take(self.tss.threshold + 1)
+            .collect();
+        if senders.len() <= self.tss.threshold {
+            return;
+        }
+        let points: Vec<F::E> = senders.iter().map(|&i| party_point(field, i)).collect();
+        let inverse = vandermonde_inverse(&points, field).expect("party points are distinct");
+        let values = |known: &[Option<F::E>]| -> Vec<F::E> {
+            senders
+                .iter()
+                .map(|&i| known[i].clone().expect("point is known"))
+                .collect()
+        };
+        self.row = Some(mul_vector(&inverse, &values(&self.row_points), field));
+        self.column = Some(mul_vector(&inverse, &values(&self.column_points), field));
+    }
+
+    fn advance(&mut self, outgoing: &mut Vec<(ShareIndex, AvssMessage<F>)>) {
+        if self.row.is_none() {
+            self.interpolate();
+        }
+        if self.row.is_none() {
+            return;
+        }
+        let threshold = self.tss.threshold;
+        let echoes = self.echoes.iter().filter(|&&echo| echo).count();
+        let readies = self.readies.iter().filter(|&&ready| ready).count();
+        if !self.sent_ready
+            && (2 * echoes > self.tss.share_count + threshold || readies > threshold)
+        {
+            self.sent_ready = true;
+            outgoing.extend(self.points_for_all(|row, column| AvssMessage::Ready { row, column }));
+        }
+        if self.share.is_none() && readies > 2 * threshold {
+            let row = self.row.as_ref().expect("polynomials are known");
+            self.share = Some(row[0].clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::Fp61;
+    use rand::{ChaChaRng, SeedableRng};
+    use std::collections::VecDeque;
+
+    /// Deliver all messages in a random order between the parties that have not
+    /// crashed, starting with the messages of the dealer to `reached`.
+    fn run(
+        tss: &ShamirSecretSharing<Fp61>,
+        secret: u64,
+        crashed: &[ShareIndex],
+        reached: &[ShareIndex],
+        seed: u8,
+    ) -> Vec<AvssReceiver<Fp61>> {
+        let mut rng = ChaChaRng::from_seed(&[seed as u32]);
+        let dealer = AvssDealer::with_rng(tss, secret, &mut rng);
+        let mut parties: Vec<AvssReceiver<Fp61>> = (0..tss.share_count)
+            .map(|i| AvssReceiver::new(tss, i))
+            .collect();
+        let mut queue: VecDeque<(ShareIndex, ShareIndex, AvssMessage<Fp61>)> = dealer
+            .messages()
+            .into_iter()
+            .filter(|&(to, _)| reached.contains(&to))
+            .map(|(to, message)| (0, to, message))
+            .collect();
+        while !queue.is_empty() {
+            let position = rng.gen_range(0, queue.len());
+            let (from, to, message) = queue.remove(position).unwrap();
+            if crashed.contains(&to) {
+                continue;
+            }
+            for (recipient, reply) in parties[to].handle(from, message) {
+                queue.push_back((to, recipient, reply));
+            }
+        }
+        parties
+    }
+
+    #[test]
+    fn test_avss() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 7,
+            field: Fp61,
+        };
+        let all: Vec<ShareIndex> = (0..7).collect();
+        let parties = run(&tss, 12345, &[], &all, 1);
+        assert!(parties.iter().all(|party| party.is_complete()));
+        let shares: Vec<u64> = parties
+            .iter()
+            .map(|party| *party.share().unwrap())
+            .collect();
+        assert_eq!(
+            tss.reconstruct(&[1, 3, 6], &[shares[1], shares[3], shares[6]]),
+            12345
+        );
+        assert_eq!(tss.reconstruct(&all, &shares), 12345);
+    }
+
+    #[test]
+    fn test_crashes() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 7,
+            field: Fp61,
+        };
+        let all: Vec<ShareIndex> = (0..7).collect();
+        let parties = run(&tss, 777, &[0, 5], &all, 2);
+        let live = [1, 2, 3, 4, 6];
+        assert!(live.iter().all(|&i| parties[i].is_complete()));
+        assert!(!parties[0].is_complete());
+        let shares: Vec<u64> = live.iter().map(|&i| *parties[i].share().unwrap()).collect();
+        assert_eq!(tss.reconstruct(&live, &shares), 777);
+
+        // the dealer crashes before reaching party 4, which still completes
+        let parties = run(&tss, 777, &[5], &[0, 1, 2, 3, 5, 6], 3);
+        assert!(parties[4].is_complete());
+        let live = [0, 2, 4];
+        let shares: Vec<u64> = live.iter().map(|&i| *parties[i].share().unwrap()).collect();
+        assert_eq!(tss.reconstruct(&live, &shares), 777);
+    }
+
+    #[test]
+    fn test_too_many_crashes() {
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 4,
+            field: Fp61,
+        };
+        let parties = run(&tss, 5, &[0, 3], &[0, 1, 2, 3], 4);
+        assert!(parties.iter().all(|party| !party.is_complete()));
+    }
+
+    #[test]
+    fn test_inconsistent_points() {
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 4,
+            field: Fp61,
+        };
+        let mut rng = ChaChaRng::from_seed(&[5]);
+        let dealer = AvssDealer::with_rng(&tss, 5, &mut rng);
+        let mut party = AvssReceiver::new(&tss, 2);
+        let (_, send) = dealer.messages().remove(2);
+        let echoes = party.handle(0, send.clone());
+        assert_eq!(echoes.len(), 4);
+        // a second copy of the polynomials is ignored
+        assert_eq!(party.handle(0, send), []);
+        let (_, echo) = echoes[2].clone();
+        assert_eq!(party.handle(2, echo.clone()), []);
+        // points disagreeing with the polynomials of the party are ignored
+        if let AvssMessage::Echo { row, column } = echo {
+            let wrong = AvssMessage::Echo {
+                row: Fp61.add(row, 1),
+                column,
+            };
+            party.handle(1, wrong);
+        }
+        assert!(!party.echoes[1]);
+        // messages from unknown parties are ignored
+        assert_eq!(party.handle(4, echoes[3].1.clone()), []);
+        assert_eq!(party.handle(100, echoes[1].1.clone()), []);
+    }
+}
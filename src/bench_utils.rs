@@ -0,0 +1,463 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Criterion benchmarks sweeping over field backends and scheme sizes, available
+//! with the `bench_utils` feature.
+//!
+//! The `bench_*` functions register benchmark groups with a `Criterion` instance,
+//! so that downstream backends can be measured with the same sweeps as the
+//! backends of this crate, which `benches/criterion.rs` runs. The results saved by
+//! criterion are read back with `read_baseline`, written out as JSON lines with
+//! `write_json_lines`, and compared between runs with `find_regressions`.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use serde_json::{self, Value};
+
+use fields::NativePrimeField;
+use fields::{
+    Encode, Field, Fp61, KnownPrime, MontgomeryField32, NaturalPrimeField, New, U64Field,
+};
+use numtheory::fft::FftPlan;
+use numtheory::{find_root_of_unity, PrimeModulus};
+use packed::{PackedSecretSharing, ReconstructStrategy};
+
+/// The prime `2^10.3^6 + 1`, small enough for all backends and supporting the FFTs
+/// needed by all `SCHEME_SIZES`.
+pub const BENCH_PRIME: u32 = 746497;
+
+/// Lengths of the FFTs measured by `bench_fft`.
+pub const FFT_LENGTHS: [usize; 6] = [27, 64, 243, 256, 729, 1024];
+
+/// Parameters of a packed scheme measured by `bench_interpolation` and `bench_packed`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchemeSize {
+    pub threshold: usize,
+    pub share_count: usize,
+    pub secret_count: usize,
+}
+
+/// Scheme sizes swept over, from a handful of parties to several hundred.
+pub const SCHEME_SIZES: [SchemeSize; 3] = [
+    SchemeSize {
+        threshold: 4,
+        share_count: 26,
+        secret_count: 3,
+    },
+    SchemeSize {
+        threshold: 20,
+        share_count: 80,
+        secret_count: 11,
+    },
+    SchemeSize {
+        threshold: 155,
+        share_count: 728,
+        secret_count: 100,
+    },
+];
+
+impl SchemeSize {
+    /// Packed scheme of this size over `field`, which must have roots of unity of
+    /// orders `threshold + secret_count + 1` and `share_count + 1`.
+    pub fn scheme<F>(&self, field: F) -> PackedSecretSharing<F>
+    where
+        F: KnownPrime,
+        F::P: PrimeModulus,
+    {
+        let omega_secrets = find_root_of_unity(&field, self.threshold + self.secret_count + 1)
+            .expect("no root of unity of order secret_count + threshold + 1");
+        let omega_shares = find_root_of_unity(&field, self.share_count + 1)
+            .expect("no root of unity of order share_count + 1");
+        PackedSecretSharing::with_roots(
+            self.threshold,
+            self.secret_count,
+            self.share_count,
+            field,
+            omega_secrets,
+            omega_shares,
+        )
+    }
+}
+
+/// Written like the names of the instances in `packed`, eg. `4_26_3`.
+impl fmt::Display for SchemeSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}_{}_{}",
+            self.threshold, self.share_count, self.secret_count
+        )
+    }
+}
+
+/// Measure `add`, `mul` and `inv`, as well as `batch_inv` over 1024 elements, in
+/// the group `field_ops/<backend>`.
+pub fn bench_field_ops<F>(c: &mut Criterion, backend: &str, field: &F)
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    let mut group = c.benchmark_group(format!("field_ops/{}", backend));
+    let a = field.encode(1231231);
+    let b = field.encode(423421);
+    group.bench_function("add", |bencher| bencher.iter(|| field.add(&a, &b)));
+    group.bench_function("mul", |bencher| bencher.iter(|| field.mul(&a, &b)));
+    group.bench_function("inv", |bencher| bencher.iter(|| field.inv(&a)));
+    let elements: Vec<F::E> = (1..1025).map(|x| field.encode(x)).collect();
+    group.throughput(Throughput::Elements(elements.len() as u64));
+    group.bench_function("batch_inv", |bencher| {
+        bencher.iter(|| {
+            let mut elements = elements.clone();
+            field.batch_inv(&mut elements);
+            elements
+        })
+    });
+    group.finish();
+}
+
+/// Measure forward and inverse FFTs of all `FFT_LENGTHS` supported by `field`, in
+/// the group `fft/<backend>`.
+pub fn bench_fft<F>(c: &mut Criterion, backend: &str, field: &F)
+where
+    F: KnownPrime + Encode<u32> + Clone,
+    F::P: PrimeModulus,
+    F::E: Clone,
+{
+    let mut group = c.benchmark_group(format!("fft/{}", backend));
+    for &len in &FFT_LENGTHS {
+        let omega = match find_root_of_unity(field, len) {
+            Some(omega) => omega,
+            None => continue,
+        };
+        let plan = FftPlan::new(field, len, &omega);
+        let data: Vec<F::E> = (0..len as u32).map(|x| field.encode(x)).collect();
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("forward", len), &data, |bencher, data| {
+            bencher.iter(|| {
+                let mut data = data.clone();
+                plan.forward(&mut data);
+                data
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("inverse", len), &data, |bencher, data| {
+            bencher.iter(|| {
+                let mut data = data.clone();
+                plan.inverse(&mut data);
+                data
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Measure reconstruction from `reconstruct_limit` shares with every explicit
+/// `ReconstructStrategy` for all `SCHEME_SIZES`, in the group
+/// `interpolation/<backend>`.
+///
+/// The shares are spread over all indices, so that none of the strategies can
+/// take shortcuts for consecutive ones.
+pub fn bench_interpolation<F>(c: &mut Criterion, backend: &str, field: &F)
+where
+    F: KnownPrime + Encode<u32> + Clone,
+    F::P: PrimeModulus,
+    F::E: Clone,
+{
+    let mut group = c.benchmark_group(format!("interpolation/{}", backend));
+    for size in &SCHEME_SIZES {
+        let pss = size.scheme(field.clone());
+        let secrets: Vec<F::E> = (0..pss.secret_count as u32)
+            .map(|x| field.encode(x))
+            .collect();
+        let shares = pss.share(&secrets);
+        let limit = pss.reconstruct_limit();
+        let indices: Vec<u32> = (0..limit)
+            .map(|i| (i * pss.share_count / limit) as u32)
+            .collect();
+        let values: Vec<F::E> = indices
+            .iter()
+            .map(|&i| shares[i as usize].clone())
+            .collect();
+        for &strategy in &ReconstructStrategy::EXPLICIT {
+            let id = BenchmarkId::new(format!("{:?}", strategy), size);
+            group.bench_function(id, |bencher| {
+                bencher.iter(|| pss.reconstruct_with(strategy, &indices, &values))
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Measure sharing, and reconstruction from all shares, for all `SCHEME_SIZES`, in
+/// the group `packed/<backend>`.
+pub fn bench_packed<F>(c: &mut Criterion, backend: &str, field: &F)
+where
+    F: KnownPrime + Encode<u32> + Clone,
+    F::P: PrimeModulus,
+    F::E: Clone,
+{
+    let mut group = c.benchmark_group(format!("packed/{}", backend));
+    for size in &SCHEME_SIZES {
+        let pss = size.scheme(field.clone());
+        let secrets: Vec<F::E> = (0..pss.secret_count as u32)
+            .map(|x| field.encode(x))
+            .collect();
+        let shares = pss.share(&secrets);
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+        group.bench_with_input(
+            BenchmarkId::new("share", size),
+            &secrets,
+            |bencher, secrets| bencher.iter(|| pss.share(secrets)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("reconstruct", size),
+            &shares,
+            |bencher, shares| bencher.iter(|| pss.reconstruct(&indices, shares)),
+        );
+    }
+    group.finish();
+}
+
+/// Run all sweeps for the backend `field`, whose prime should be `BENCH_PRIME` for
+/// the results to be comparable with those of other backends.
+pub fn bench_backend<F>(c: &mut Criterion, backend: &str, field: &F)
+where
+    F: KnownPrime + Encode<u32> + Clone,
+    F::P: PrimeModulus,
+    F::E: Clone,
+{
+    bench_field_ops(c, backend, field);
+    bench_fft(c, backend, field);
+    bench_interpolation(c, backend, field);
+    bench_packed(c, backend, field);
+}
+
+/// Run all sweeps for the backends of this crate.
+///
+/// `Fp61` has a fixed prime without large smooth subgroups, so only its field
+/// operations are measured.
+pub fn bench_all(c: &mut Criterion) {
+    bench_backend(c, "natural", &NaturalPrimeField::new(BENCH_PRIME));
+    bench_backend(c, "montgomery", &MontgomeryField32::new(BENCH_PRIME));
+    bench_backend(c, "u64", &U64Field::new(BENCH_PRIME));
+    bench_backend(c, "native", &NativePrimeField::new(BENCH_PRIME));
+    bench_field_ops(c, "fp61", &Fp61);
+}
+
+/// Mean running time of a benchmark, as saved by criterion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    /// Full identifier of the benchmark, eg. `fft/montgomery/forward/729`.
+    pub id: String,
+    /// Mean time of one iteration, in nanoseconds.
+    pub mean_ns: f64,
+}
+
+/// The measurements of `baseline` saved under `criterion_dir`, usually
+/// `target/criterion`, sorted by identifier.
+///
+/// Criterion saves the latest run as the baseline `new`, and the runs made with
+/// `--save-baseline <name>` under their name.
+pub fn read_baseline(criterion_dir: &Path, baseline: &str) -> io::Result<Vec<Measurement>> {
+    let mut measurements = vec![];
+    collect_measurements(criterion_dir, baseline, &mut measurements)?;
+    measurements.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(measurements)
+}
+
+fn collect_measurements(
+    dir: &Path,
+    baseline: &str,
+    measurements: &mut Vec<Measurement>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if path.file_name().is_some_and(|name| name == baseline) {
+            let benchmark = path.join("benchmark.json");
+            let estimates = path.join("estimates.json");
+            if benchmark.is_file() && estimates.is_file() {
+                let id = read_json(&benchmark)?["full_id"].as_str().map(String::from);
+                let mean_ns = read_json(&estimates)?["mean"]["point_estimate"].as_f64();
+                match (id, mean_ns) {
+                    (Some(id), Some(mean_ns)) => measurements.push(Measurement { id, mean_ns }),
+                    _ => return Err(invalid_data("unexpected criterion output")),
+                }
+                continue;
+            }
+        }
+        collect_measurements(&path, baseline, measurements)?;
+    }
+    Ok(())
+}
+
+fn read_json(path: &Path) -> io::Result<Value> {
+    serde_json::from_slice(&fs::read(path)?).map_err(invalid_data)
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Write `measurements` as JSON objects with fields `id` and `mean_ns`, one per line.
+pub fn write_json_lines<W: io::Write>(measurements: &[Measurement], out: &mut W) -> io::Result<()> {
+    for measurement in measurements {
+        let line = serde_json::json!({
+            "id": measurement.id,
+            "mean_ns": measurement.mean_ns,
+        });
+        writeln!(out, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// Benchmark that got slower between two runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    pub id: String,
+    pub baseline_ns: f64,
+    pub current_ns: f64,
+}
+
+impl Regression {
+    /// Ratio of the current time to the baseline time.
+    pub fn slowdown(&self) -> f64 {
+        self.current_ns / self.baseline_ns
+    }
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.1}ns -> {:.1}ns ({:+.1}%)",
+            self.id,
+            self.baseline_ns,
+            self.current_ns,
+            (self.slowdown() - 1.0) * 100.0
+        )
+    }
+}
+
+/// Benchmarks of `current` more than `tolerance` slower than in `baseline`, eg.
+/// more than 10% slower for a tolerance of `0.1`.
+///
+/// Benchmarks missing from either run are ignored.
+pub fn find_regressions(
+    baseline: &[Measurement],
+    current: &[Measurement],
+    tolerance: f64,
+) -> Vec<Regression> {
+    current
+        .iter()
+        .filter_map(|measurement| {
+            let before = baseline.iter().find(|before| before.id == measurement.id)?;
+            if measurement.mean_ns > before.mean_ns * (1.0 + tolerance) {
+                Some(Regression {
+                    id: measurement.id.clone(),
+                    baseline_ns: before.mean_ns,
+                    current_ns: measurement.mean_ns,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::env;
+
+    fn measurement(id: &str, mean_ns: f64) -> Measurement {
+        Measurement {
+            id: id.to_string(),
+            mean_ns,
+        }
+    }
+
+    #[test]
+    fn test_scheme_sizes() {
+        let field = MontgomeryField32::new(BENCH_PRIME);
+        for size in &SCHEME_SIZES {
+            let pss = size.scheme(field.clone());
+            assert_eq!(pss.validate(), Ok(()));
+        }
+        assert_eq!(SCHEME_SIZES[0].to_string(), "4_26_3");
+    }
+
+    #[test]
+    fn test_find_regressions() {
+        let baseline = [measurement("a", 100.0), measurement("b", 100.0)];
+        let current = [
+            measurement("a", 105.0),
+            measurement("b", 120.0),
+            measurement("c", 500.0),
+        ];
+        let regressions = find_regressions(&baseline, &current, 0.1);
+        assert_eq!(
+            regressions,
+            [Regression {
+                id: "b".to_string(),
+                baseline_ns: 100.0,
+                current_ns: 120.0,
+            }]
+        );
+        assert_eq!(regressions[0].to_string(), "b: 100.0ns -> 120.0ns (+20.0%)");
+        assert!(find_regressions(&baseline, &current, 0.5).is_empty());
+    }
+
+    #[test]
+    fn test_read_baseline() {
+        let root = env::temp_dir().join(format!("tss-bench-utils-{}", ::std::process::id()));
+        for &(dir, id, mean) in &[
+            (
+                "fft_montgomery/forward/729",
+                "fft/montgomery/forward/729",
+                1500.5,
+            ),
+            ("field_ops_natural/add", "field_ops/natural/add", 2.25),
+        ] {
+            for &(baseline, scale) in &[("new", 1.0), ("main", 2.0)] {
+                let path = root.join(dir).join(baseline);
+                fs::create_dir_all(&path).unwrap();
+                let benchmark = serde_json::json!({ "full_id": id });
+                let estimates = serde_json::json!({ "mean": { "point_estimate": mean * scale } });
+                fs::write(path.join("benchmark.json"), benchmark.to_string()).unwrap();
+                fs::write(path.join("estimates.json"), estimates.to_string()).unwrap();
+            }
+        }
+
+        let current = read_baseline(&root, "new").unwrap();
+        assert_eq!(
+            current,
+            [
+                measurement("fft/montgomery/forward/729", 1500.5),
+                measurement("field_ops/natural/add", 2.25),
+            ]
+        );
+        let main = read_baseline(&root, "main").unwrap();
+        assert!(find_regressions(&current, &main, 0.1).len() == 2);
+
+        let mut out = vec![];
+        write_json_lines(&current, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"id\":\"fft/montgomery/forward/729\",\"mean_ns\":1500.5}\n\
+             {\"id\":\"field_ops/natural/add\",\"mean_ns\":2.25}\n"
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
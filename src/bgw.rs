@@ -0,0 +1,235 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Degree reduction of products of packed sharings, following BGW.
+//!
+//! Multiplying two packed sharings pointwise with `mul_shares` doubles the degree
+//! of the sharing polynomial, and with it the number of shares needed to
+//! reconstruct, so that a second multiplication would need a committee of four
+//! times the size, and so on. A `DegreeReduction` turns the product shares back
+//! into a fresh sharing of the products of the secrets with the degree of the
+//! scheme:
+//!
+//! 1. the parties holding the product shares with the given `indices` each
+//!    `reshare` their product share, weighted by its column of the recombination
+//!    matrix, with a fresh packed sharing, and send one subshare to every party;
+//! 2. each party then `recombine`s the subshares it received into its new share.
+//!
+//! The recombination matrix holds the Lagrange coefficients for interpolating the
+//! product polynomial at the positions of the secrets from the positions of the
+//! product shares, so that by linearity the sum of the resharings is a sharing of
+//! the products. At least `2 * reconstruct_limit` parties must take part, and the
+//! result is only private if at most `threshold` of them are corrupted.
+//!
+//! Products of Shamir sharings can instead be reduced following Damgård and
+//! Nielsen, with the double sharings of `ShamirSecretSharing::share_double_random`.
+
+use fields::{Encode, Field};
+use numtheory::LagrangeConstants;
+use packed::PackedSecretSharing;
+use rand::Rng;
+use share::{check_indices, IndexError, ProductShares};
+
+/// Recombination matrix of a degree reduction for a fixed set of parties.
+pub struct DegreeReduction<F: Field> {
+    indices: Vec<u32>,
+    /// Weight of the product share of each party in each product of secrets,
+    /// indexed by secret then by party.
+    matrix: Vec<Vec<F::E>>,
+}
+
+element_impls!(Clone, Debug for DegreeReduction { indices, matrix });
+
+impl<F> DegreeReduction<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Degree reduction of the products of two sharings with `pss` between the
+    /// parties holding the product shares with the given `indices`.
+    ///
+    /// The indices must be distinct ranks of shares, at least twice the
+    /// `reconstruct_limit` of the scheme of them.
+    pub fn new(
+        pss: &PackedSecretSharing<F>,
+        indices: &[u32],
+    ) -> Result<DegreeReduction<F>, IndexError> {
+        check_indices(
+            pss.share_count,
+            2 * pss.reconstruct_limit(),
+            indices.iter().map(|&i| i as usize),
+        )?;
+        // every sharing polynomial, and hence their product, is zero at one
+        let share_positions = pss.share_positions();
+        let mut points = vec![pss.field.one()];
+        points.extend(indices.iter().map(|&i| share_positions[i as usize].clone()));
+        let matrix = pss
+            .secret_positions()
+            .iter()
+            .map(|position| {
+                LagrangeConstants::compute(position, &points, &pss.field).constants()[1..].to_vec()
+            })
+            .collect();
+        Ok(DegreeReduction {
+            indices: indices.to_vec(),
+            matrix,
+        })
+    }
+
+    /// Ranks of the parties taking part, in the order of the columns of the matrix.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Weight of the product share of each party in each product of secrets,
+    /// indexed by secret then by the position of the party in `indices`.
+    pub fn matrix(&self) -> &[Vec<F::E>] {
+        &self.matrix
+    }
+
+    /// Subshares for every party of the product share of the party at `position`
+    /// in `indices`, to be sent to them.
+    pub fn reshare(
+        &self,
+        pss: &PackedSecretSharing<F>,
+        position: usize,
+        product_share: &F::E,
+    ) -> Vec<F::E> {
+        let secrets = self.weighted_secrets(pss, position, product_share);
+        pss.share(&secrets)
+    }
+
+    /// Like `reshare`, sampling the randomness from `rng`.
+    pub fn reshare_with_rng<R: Rng>(
+        &self,
+        pss: &PackedSecretSharing<F>,
+        position: usize,
+        product_share: &F::E,
+        rng: &mut R,
+    ) -> Vec<F::E> {
+        let secrets = self.weighted_secrets(pss, position, product_share);
+        pss.share_with_rng(&secrets, rng)
+    }
+
+    fn weighted_secrets(
+        &self,
+        pss: &PackedSecretSharing<F>,
+        position: usize,
+        product_share: &F::E,
+    ) -> Vec<F::E> {
+        self.matrix
+            .iter()
+            .map(|row| pss.field.mul(&row[position], product_share))
+            .collect()
+    }
+
+    /// New share of a party, from the subshares it received from every party in the
+    /// order of `indices`.
+    pub fn recombine(&self, pss: &PackedSecretSharing<F>, subshares: &[F::E]) -> F::E {
+        assert_eq!(subshares.len(), self.indices.len());
+        subshares.iter().fold(pss.field.zero(), |sum, subshare| {
+            pss.field.add(sum, subshare)
+        })
+    }
+
+    /// Carry out the whole degree reduction of `product` at once, returning all
+    /// `share_count` shares of the products of the secrets.
+    ///
+    /// The number of parties must be at least the `reconstruct_limit` of the product.
+    pub fn reduce(&self, pss: &PackedSecretSharing<F>, product: &ProductShares<F>) -> Vec<F::E> {
+        assert!(self.indices.len() >= product.reconstruct_limit);
+        let resharings: Vec<Vec<F::E>> = self
+            .indices
+            .iter()
+            .enumerate()
+            .map(|(position, &index)| self.reshare(pss, position, &product.shares[index as usize]))
+            .collect();
+        (0..pss.share_count)
+            .map(|recipient| {
+                let subshares: Vec<F::E> = resharings
+                    .iter()
+                    .map(|resharing| resharing[recipient].clone())
+                    .collect();
+                self.recombine(pss, &subshares)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::SliceDecode;
+    use packed::PSS_4_26_3;
+
+    #[test]
+    fn test_reduce() {
+        let pss = &PSS_4_26_3;
+        let a = pss.share(&[1, 2, 3]);
+        let b = pss.share(&[4, 5, 6]);
+        let c = pss.share(&[7, 8, 9]);
+
+        let indices: Vec<u32> = (0..2 * pss.reconstruct_limit() as u32)
+            .map(|i| 25 - i)
+            .collect();
+        let reduction = DegreeReduction::new(pss, &indices).unwrap();
+        let ab = reduction.reduce(pss, &pss.mul_shares(&a, &b));
+        let limit = pss.reconstruct_limit();
+        let indices: Vec<u32> = (0..limit as u32).collect();
+        let secrets = pss.reconstruct(&indices, &ab[..limit]);
+        assert_eq!(pss.field.decode_slice(secrets), [4, 10, 18]);
+
+        // the reduced shares can be multiplied again with the same number of parties
+        let abc = reduction.reduce(pss, &pss.mul_shares(&ab, &c));
+        let secrets = pss.reconstruct(&indices, &abc[..limit]);
+        assert_eq!(pss.field.decode_slice(secrets), [28, 80, 162]);
+    }
+
+    #[test]
+    fn test_distributed() {
+        let pss = &PSS_4_26_3;
+        let product = pss.mul_shares(&pss.share(&[1, 2, 3]), &pss.share(&[4, 5, 6]));
+        let indices: Vec<u32> = (0..20).collect();
+        let reduction = DegreeReduction::new(pss, &indices).unwrap();
+        assert_eq!(reduction.matrix().len(), pss.secret_count);
+        assert_eq!(reduction.matrix()[0].len(), indices.len());
+
+        // every party reshares its product share ...
+        let resharings: Vec<Vec<i64>> = indices
+            .iter()
+            .enumerate()
+            .map(|(position, &i)| reduction.reshare(pss, position, &product.shares[i as usize]))
+            .collect();
+        // ... and only some of them recombine their subshares
+        let recipients = [3_u32, 5, 8, 13, 17, 21, 24];
+        let shares: Vec<i64> = recipients
+            .iter()
+            .map(|&i| {
+                let subshares: Vec<i64> = resharings.iter().map(|r| r[i as usize]).collect();
+                reduction.recombine(pss, &subshares)
+            })
+            .collect();
+        let secrets = pss.reconstruct(&recipients, &shares);
+        assert_eq!(pss.field.decode_slice(secrets), [4, 10, 18]);
+    }
+
+    #[test]
+    fn test_too_few_parties() {
+        let pss = &PSS_4_26_3;
+        let indices: Vec<u32> = (0..13).collect();
+        assert_eq!(
+            DegreeReduction::new(pss, &indices).unwrap_err(),
+            IndexError::TooFewShares(13)
+        );
+        assert_eq!(
+            DegreeReduction::new(pss, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 12]).unwrap_err(),
+            IndexError::DuplicateShare(12)
+        );
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Bivariate polynomials, as used by verifiable and proactive sharing.
+//!
+//! Sharing with a bivariate polynomial `phi(x, y)` gives party `i` its row
+//! `phi(x_i, y)` and column `phi(x, x_i)`, so that any two parties can check the
+//! values `phi(x_i, x_j)` and `phi(x_j, x_i)` they have in common. With a symmetric
+//! polynomial rows and columns coincide.
+
+use rand::Rng;
+
+use fields::{Encode, Field};
+use numtheory::fft::{is_mixed_radix_len, FftPlan};
+use numtheory::mod_evaluate_polynomial;
+
+/// Polynomial in two variables `x` and `y`.
+pub struct BivariatePolynomial<F: Field> {
+    /// Coefficients of `x^a.y^b` at `[a][b]`.
+    coefficients: Vec<Vec<F::E>>,
+}
+
+element_impls!(Clone, PartialEq, Debug for BivariatePolynomial { coefficients });
+
+impl<F> BivariatePolynomial<F>
+where
+    F: Field,
+    F::E: Clone,
+{
+    /// Polynomial with the coefficient of `x^a.y^b` at `coefficients[a][b]`.
+    ///
+    /// There must be at least one coefficient, and all rows must have the same
+    /// length.
+    pub fn from_coefficients(coefficients: Vec<Vec<F::E>>) -> BivariatePolynomial<F> {
+        assert!(!coefficients.is_empty() && !coefficients[0].is_empty());
+        assert!(coefficients
+            .iter()
+            .all(|c| c.len() == coefficients[0].len()));
+        BivariatePolynomial { coefficients }
+    }
+
+    /// Uniformly random polynomial of degree `x_degree` in `x` and `y_degree` in
+    /// `y`, with value `secret` at `(0, 0)`.
+    pub fn sample<R: Rng>(
+        field: &F,
+        secret: F::E,
+        x_degree: usize,
+        y_degree: usize,
+        rng: &mut R,
+    ) -> BivariatePolynomial<F> {
+        let mut coefficients: Vec<Vec<F::E>> = (0..=x_degree)
+            .map(|_| field.sample_with_replacement(y_degree + 1, rng))
+            .collect();
+        coefficients[0][0] = secret;
+        BivariatePolynomial { coefficients }
+    }
+
+    /// Uniformly random symmetric polynomial of degree `degree` in both variables,
+    /// with value `secret` at `(0, 0)`.
+    pub fn sample_symmetric<R: Rng>(
+        field: &F,
+        secret: F::E,
+        degree: usize,
+        rng: &mut R,
+    ) -> BivariatePolynomial<F> {
+        let mut polynomial = BivariatePolynomial::sample(field, secret, degree, degree, rng);
+        for a in 0..=degree {
+            for b in 0..a {
+                polynomial.coefficients[a][b] = polynomial.coefficients[b][a].clone();
+            }
+        }
+        polynomial
+    }
+
+    /// Coefficients of the polynomial, the one of `x^a.y^b` at `[a][b]`.
+    pub fn coefficients(&self) -> &[Vec<F::E>] {
+        &self.coefficients
+    }
+
+    /// Bound on the degree of the polynomial in `x`.
+    pub fn x_degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    /// Bound on the degree of the polynomial in `y`.
+    pub fn y_degree(&self) -> usize {
+        self.coefficients[0].len() - 1
+    }
+
+    /// Whether `phi(x, y) = phi(y, x)`.
+    pub fn is_symmetric(&self, field: &F) -> bool {
+        self.x_degree() == self.y_degree()
+            && (0..self.coefficients.len()).all(|a| {
+                (0..a).all(|b| field.eq(&self.coefficients[a][b], &self.coefficients[b][a]))
+            })
+    }
+
+    /// Value `phi(x, y)`.
+    pub fn evaluate(&self, field: &F, x: &F::E, y: &F::E) -> F::E {
+        mod_evaluate_polynomial(&self.row(field, x), y, field)
+    }
+
+    /// Coefficients of the row `phi(x, y)` for the given `x`, as a polynomial in `y`.
+    pub fn row(&self, field: &F, x: &F::E) -> Vec<F::E> {
+        (0..=self.y_degree())
+            .map(|b| {
+                let coefficients: Vec<F::E> =
+                    self.coefficients.iter().map(|c| c[b].clone()).collect();
+                mod_evaluate_polynomial(&coefficients, x, field)
+            })
+            .collect()
+    }
+
+    /// Coefficients of the column `phi(x, y)` for the given `y`, as a polynomial in
+    /// `x`.
+    pub fn column(&self, field: &F, y: &F::E) -> Vec<F::E> {
+        self.coefficients
+            .iter()
+            .map(|c| mod_evaluate_polynomial(c, y, field))
+            .collect()
+    }
+}
+
+impl<F> BivariatePolynomial<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Rows for `x` at all `count` powers of `omega`, which must be a principal root
+    /// of unity of order `count`.
+    ///
+    /// If `count` is of the form `2^a.3^b` the rows are computed with one FFT per
+    /// coefficient in `y`, and otherwise one by one.
+    pub fn rows_at_powers(&self, field: &F, omega: &F::E, count: usize) -> Vec<Vec<F::E>> {
+        let y_coefficients: Vec<Vec<F::E>> = (0..=self.y_degree())
+            .map(|b| self.coefficients.iter().map(|c| c[b].clone()).collect())
+            .collect();
+        transpose(evaluate_at_powers(&y_coefficients, field, omega, count))
+    }
+
+    /// Columns for `y` at all `count` powers of `omega`, which must be a principal
+    /// root of unity of order `count`, computed like `rows_at_powers`.
+    pub fn columns_at_powers(&self, field: &F, omega: &F::E, count: usize) -> Vec<Vec<F::E>> {
+        transpose(evaluate_at_powers(&self.coefficients, field, omega, count))
+    }
+}
+
+/// Values of every polynomial in `polynomials` at all `count` powers of `omega`.
+fn evaluate_at_powers<F>(
+    polynomials: &[Vec<F::E>],
+    field: &F,
+    omega: &F::E,
+    count: usize,
+) -> Vec<Vec<F::E>>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    if !is_mixed_radix_len(count) {
+        let points: Vec<F::E> = (0..count).map(|i| field.pow(omega, i as u32)).collect();
+        return polynomials
+            .iter()
+            .map(|p| {
+                points
+                    .iter()
+                    .map(|point| mod_evaluate_polynomial(p, point, field))
+                    .collect()
+            })
+            .collect();
+    }
+    let plan = FftPlan::new(field, count, omega);
+    polynomials
+        .iter()
+        .map(|p| {
+            // the powers of omega are roots of `x^count - 1`, so higher coefficients
+            // can be folded onto lower ones
+            let mut values = vec![field.zero(); count];
+            for (i, coefficient) in p.iter().enumerate() {
+                values[i % count] = field.add(&values[i % count], coefficient);
+            }
+            plan.forward_pruned(&mut values, p.len().min(count));
+            values
+        })
+        .collect()
+}
+
+fn transpose<T: Clone>(matrix: Vec<Vec<T>>) -> Vec<Vec<T>> {
+    let columns = matrix.first().map_or(0, |row| row.len());
+    (0..columns)
+        .map(|j| matrix.iter().map(|row| row[j].clone()).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{NaturalPrimeField, New};
+    use rand::{ChaChaRng, SeedableRng};
+
+    #[test]
+    fn test_rows_and_columns() {
+        let field = NaturalPrimeField::new(433);
+        let mut rng = ChaChaRng::from_seed(&[52]);
+        let phi = BivariatePolynomial::sample(&field, 42, 2, 3, &mut rng);
+        assert_eq!((phi.x_degree(), phi.y_degree()), (2, 3));
+        assert_eq!(phi.evaluate(&field, &0, &0), 42);
+        assert!(!phi.is_symmetric(&field));
+
+        let row = phi.row(&field, &5);
+        assert_eq!(row.len(), 4);
+        let column = phi.column(&field, &7);
+        assert_eq!(column.len(), 3);
+        // rows and columns cross at the value of the polynomial
+        let value = phi.evaluate(&field, &5, &7);
+        assert_eq!(mod_evaluate_polynomial(&row, 7, &field), value);
+        assert_eq!(mod_evaluate_polynomial(&column, 5, &field), value);
+
+        let phi = BivariatePolynomial::from_coefficients(vec![vec![1, 2], vec![3, 4]]);
+        // 1 + 2y + 3x + 4xy at (2, 3)
+        assert_eq!(phi.evaluate(&field, &2, &3), 37);
+    }
+
+    #[test]
+    fn test_symmetric() {
+        let field = NaturalPrimeField::new(433);
+        let mut rng = ChaChaRng::from_seed(&[53]);
+        let phi = BivariatePolynomial::sample_symmetric(&field, 42, 3, &mut rng);
+        assert!(phi.is_symmetric(&field));
+        assert_eq!(phi.evaluate(&field, &0, &0), 42);
+        assert_eq!(phi.row(&field, &9), phi.column(&field, &9));
+        assert_eq!(phi.evaluate(&field, &2, &11), phi.evaluate(&field, &11, &2));
+    }
+
+    #[test]
+    fn test_at_powers() {
+        let field = NaturalPrimeField::new(433);
+        let mut rng = ChaChaRng::from_seed(&[54]);
+        // more coefficients in x than powers, which are folded
+        let phi = BivariatePolynomial::sample(&field, 42, 9, 2, &mut rng);
+        // 354 has order 8
+        let rows = phi.rows_at_powers(&field, &354, 8);
+        let columns = phi.columns_at_powers(&field, &354, 8);
+        assert_eq!(rows.len(), 8);
+        for i in 0..8 {
+            let point = field.pow(354, i as u32);
+            assert_eq!(rows[i], phi.row(&field, &point));
+            assert_eq!(columns[i], phi.column(&field, &point));
+        }
+
+        // 3 has order 5 in Z_11, which is not suitable for FFTs
+        let field = NaturalPrimeField::new(11);
+        let phi = BivariatePolynomial::sample(&field, 4, 2, 2, &mut rng);
+        let rows = phi.rows_at_powers(&field, &3, 5);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(*row, phi.row(&field, &field.pow(3, i as u32)));
+        }
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Dealing and collecting shares over `tokio` channels.
+//!
+//! `deal` sends every share to the channel of its recipient, while a
+//! `ShareCollector` is a future feeding the shares it receives into a
+//! `ReconstructionSession`, and resolving to the secrets once enough of them have
+//! arrived. Shares typically reach the channel from tasks reading them off the
+//! network, each holding a clone of its sender.
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+use fields::{Encode, Field};
+use packed::PackedSecretSharing;
+use scheme::ThresholdScheme;
+use session::ReconstructionSession;
+use shamir::ShamirSecretSharing;
+use share::{IndexError, Share, ShareIndex};
+
+/// Share `secret` and send every share to the recipient with the same index in
+/// `recipients`.
+///
+/// Fails with the indices of the recipients whose channel is closed, after sending
+/// to all others.
+pub fn deal<S: ThresholdScheme>(
+    scheme: &S,
+    secret: &S::Secret,
+    recipients: &[UnboundedSender<Share<S::Field>>],
+) -> Result<(), Vec<ShareIndex>> {
+    assert_eq!(recipients.len(), scheme.share_count());
+    let closed: Vec<ShareIndex> = scheme
+        .share(secret)
+        .into_iter()
+        .zip(recipients)
+        .filter_map(|(share, recipient)| {
+            let index = share.index;
+            recipient.send(share).err().map(|_| index)
+        })
+        .collect();
+    if closed.is_empty() {
+        Ok(())
+    } else {
+        Err(closed)
+    }
+}
+
+/// Failure of a `ShareCollector`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectorError {
+    /// All senders were dropped before enough shares arrived.
+    Closed,
+    /// So many shares disagree with the first `reconstruct_limit` ones that the
+    /// confirmations can no longer be reached, which means that one of the first
+    /// shares is faulty unless more than `share_count - reconstruct_limit -
+    /// confirmations` of the others are.
+    Inconsistent,
+}
+
+impl fmt::Display for CollectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CollectorError::Closed => f.write_str("channel closed before enough shares arrived"),
+            CollectorError::Inconsistent => {
+                f.write_str("too many shares are inconsistent with the first ones")
+            }
+        }
+    }
+}
+
+impl Error for CollectorError {}
+
+/// Future reconstructing the secrets from the shares received on a channel.
+///
+/// Resolves once the session holds `reconstruct_limit` shares, plus the number of
+/// confirmations set with `with_confirmations`; since shares beyond the limit are
+/// checked against the others, confirmations guard against faulty shares at the
+/// cost of waiting for more of them. Shares rejected by the session, for instance
+/// duplicates or shares inconsistent with the others, are recorded and do not stop
+/// the collection, until too few shares remain to reach the confirmations.
+///
+/// A faulty share among the first `reconstruct_limit` makes most later shares
+/// inconsistent, in which case the collector fails with
+/// `CollectorError::Inconsistent` rather than waiting for the channel to close.
+pub struct ShareCollector<'a, S: 'a + ThresholdScheme> {
+    session: ReconstructionSession<'a, S>,
+    receiver: UnboundedReceiver<Share<S::Field>>,
+    confirmations: usize,
+    rejected: Vec<(ShareIndex, IndexError)>,
+}
+
+impl<'a, S> ShareCollector<'a, S>
+where
+    S: 'a + ThresholdScheme,
+    <S::Field as Field>::E: Clone,
+{
+    /// Collect the shares received on `receiver` into `session`, which may already
+    /// hold some, for instance the share of the collecting party itself.
+    pub fn new(
+        session: ReconstructionSession<'a, S>,
+        receiver: UnboundedReceiver<Share<S::Field>>,
+    ) -> ShareCollector<'a, S> {
+        ShareCollector {
+            session,
+            receiver,
+            confirmations: 0,
+            rejected: Vec::new(),
+        }
+    }
+
+    /// Wait for `confirmations` shares beyond `reconstruct_limit` before resolving.
+    pub fn with_confirmations(mut self, confirmations: usize) -> ShareCollector<'a, S> {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// The session the shares are collected into.
+    pub fn session(&self) -> &ReconstructionSession<'a, S> {
+        &self.session
+    }
+
+    /// Indices of the received shares rejected by the session so far, together with
+    /// the reason, in the order they arrived.
+    pub fn rejected(&self) -> &[(ShareIndex, IndexError)] {
+        &self.rejected
+    }
+
+    fn poll_collect<T, A, R>(
+        &mut self,
+        cx: &mut Context,
+        add_share: A,
+        try_finish: R,
+    ) -> Poll<Result<T, CollectorError>>
+    where
+        A: Fn(
+            &mut ReconstructionSession<'a, S>,
+            ShareIndex,
+            <S::Field as Field>::E,
+        ) -> Result<(), IndexError>,
+        R: Fn(&ReconstructionSession<'a, S>) -> Option<T>,
+    {
+        loop {
+            if self.session.len() >= self.session.reconstruct_limit() + self.confirmations {
+                return Poll::Ready(Ok(try_finish(&self.session).unwrap()));
+            }
+            match self.receiver.poll_recv(cx) {
+                Poll::Ready(Some(share)) => {
+                    if let Err(err) = add_share(&mut self.session, share.index, share.value) {
+                        self.rejected.push((share.index, err));
+                        if self.session.inconsistent_indices().len()
+                            + self.session.reconstruct_limit()
+                            + self.confirmations
+                            > self.session.share_count()
+                        {
+                            return Poll::Ready(Err(CollectorError::Inconsistent));
+                        }
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(Err(CollectorError::Closed)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<'a, F> Future for ShareCollector<'a, ShamirSecretSharing<F>>
+where
+    F: Field + Encode<u32>,
+    F::E: Clone + Unpin,
+{
+    type Output = Result<F::E, CollectorError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        self.get_mut().poll_collect(
+            cx,
+            |session, index, value| session.add_share(index, value),
+            |session| session.try_finish(),
+        )
+    }
+}
+
+impl<'a, F> Future for ShareCollector<'a, PackedSecretSharing<F>>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone + Unpin,
+{
+    type Output = Result<Vec<F::E>, CollectorError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        self.get_mut().poll_collect(
+            cx,
+            |session, index, value| session.add_share(index, value),
+            |session| session.try_finish(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Fp61, SliceEncode};
+    use packed::PSS_4_26_3;
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+    use tokio::sync::mpsc::unbounded_channel;
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn poll<C: Future + Unpin>(collector: &mut C) -> Poll<C::Output> {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        Pin::new(collector).poll(&mut Context::from_waker(&waker))
+    }
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            field: Fp61,
+        };
+        let (senders, mut receivers): (Vec<_>, Vec<_>) =
+            (0..5).map(|_| unbounded_channel()).unzip();
+        assert_eq!(deal(&tss, &777, &senders), Ok(()));
+
+        let (sender, receiver) = unbounded_channel();
+        let mut collector =
+            ShareCollector::new(tss.reconstruction_session(), receiver).with_confirmations(1);
+        assert_eq!(poll(&mut collector), Poll::Pending);
+        for &i in &[4, 0, 2] {
+            sender.send(receivers[i].try_recv().unwrap()).unwrap();
+        }
+        assert_eq!(poll(&mut collector), Poll::Pending);
+        assert_eq!(collector.session().indices(), [4, 0, 2]);
+        sender.send(receivers[1].try_recv().unwrap()).unwrap();
+        assert_eq!(poll(&mut collector), Poll::Ready(Ok(777)));
+
+        drop(receivers.pop());
+        assert_eq!(deal(&tss, &777, &senders), Err(vec![4]));
+    }
+
+    #[test]
+    fn test_packed() {
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([5, 6, 7]);
+        let (sender, receiver) = unbounded_channel();
+        let mut collector = ShareCollector::new(pss.reconstruction_session(), receiver);
+        for share in pss.share_indexed(&secrets).into_iter().rev().take(7) {
+            sender.send(share).unwrap();
+        }
+        drop(sender);
+        assert_eq!(poll(&mut collector), Poll::Ready(Ok(secrets)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 4,
+            field: Fp61,
+        };
+        let shares = tss.share_indexed(10);
+
+        let (sender, receiver) = unbounded_channel();
+        let mut collector =
+            ShareCollector::new(tss.reconstruction_session(), receiver).with_confirmations(1);
+        sender.send(Share::new(0, shares[0].value)).unwrap();
+        sender.send(Share::new(1, shares[1].value)).unwrap();
+        sender
+            .send(Share::new(2, Fp61.add(shares[2].value, 1)))
+            .unwrap();
+        sender.send(Share::new(0, shares[0].value)).unwrap();
+        // rejected shares do not stop the collection
+        assert_eq!(poll(&mut collector), Poll::Pending);
+        assert_eq!(
+            collector.rejected(),
+            [
+                (2, IndexError::InconsistentShare(2)),
+                (0, IndexError::DuplicateShare(0))
+            ]
+        );
+        sender.send(Share::new(3, shares[3].value)).unwrap();
+        assert_eq!(poll(&mut collector), Poll::Ready(Ok(10)));
+
+        let (sender, receiver) = unbounded_channel();
+        let mut collector = ShareCollector::new(tss.reconstruction_session(), receiver);
+        sender.send(Share::new(3, shares[3].value)).unwrap();
+        drop(sender);
+        assert_eq!(
+            poll(&mut collector),
+            Poll::Ready(Err(CollectorError::Closed))
+        );
+
+        // a faulty first share makes the honest ones inconsistent
+        let (sender, receiver) = unbounded_channel();
+        let mut collector =
+            ShareCollector::new(tss.reconstruction_session(), receiver).with_confirmations(1);
+        sender
+            .send(Share::new(0, Fp61.add(shares[0].value, 1)))
+            .unwrap();
+        sender.send(Share::new(1, shares[1].value)).unwrap();
+        sender.send(Share::new(2, shares[2].value)).unwrap();
+        assert_eq!(poll(&mut collector), Poll::Pending);
+        sender.send(Share::new(3, shares[3].value)).unwrap();
+        assert_eq!(
+            poll(&mut collector),
+            Poll::Ready(Err(CollectorError::Inconsistent))
+        );
+        assert_eq!(
+            collector.rejected(),
+            [
+                (2, IndexError::InconsistentShare(2)),
+                (3, IndexError::InconsistentShare(3))
+            ]
+        );
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Compatibility with `libgfshare`.
+//!
+//! Every byte of the secret is shared separately over GF(256), defined by the
+//! polynomial `x^8 + x^4 + x^3 + x^2 + 1`, so shares have the length of the secret.
+//! Shares are identified by their share number, a non-zero byte which is the point
+//! at which they are evaluated; `gfsplit` picks them at random and appends them to
+//! the names of the files holding the shares, which contain nothing else.
+
+use fields::ForwardSecureRng;
+use rand::Rng;
+
+/// Logarithms and exponentials in base 2, with the exponentials repeated so that
+/// sums of two logarithms can be looked up directly.
+struct Tables {
+    logs: [u8; 256],
+    exps: [u8; 510],
+}
+
+const TABLES: Tables = tables();
+
+const fn tables() -> Tables {
+    let mut logs = [0; 256];
+    let mut exps = [0; 510];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exps[i] = x as u8;
+        exps[i + 255] = x as u8;
+        logs[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11d;
+        }
+        i += 1;
+    }
+    Tables { logs, exps }
+}
+
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        TABLES.exps[TABLES.logs[a as usize] as usize + TABLES.logs[b as usize] as usize]
+    }
+}
+
+/// Parameters for sharing as with `gfsplit`, specifying privacy threshold and the
+/// share numbers.
+///
+/// `gfsplit -n` takes the number of shares needed to reconstruct, which is
+/// `threshold + 1`.
+///
+/// # Example:
+///
+/// ```
+///    use threshold_secret_sharing::compat::GfShare;
+///    let gfshare = GfShare::new(2, 5);
+///    let shares = gfshare.share(b"correct horse");
+///    let recovered = gfshare.reconstruct(&[5, 2, 3], &[&shares[4], &shares[1], &shares[2]]);
+///    assert_eq!(recovered, b"correct horse");
+///    assert_eq!(GfShare::file_name("secret.txt", 5), "secret.txt.005");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GfShare {
+    /// Maximum number of shares that can be known without exposing the secret.
+    pub threshold: usize,
+    /// Share number of each share, which must be non-zero and distinct.
+    pub share_numbers: Vec<u8>,
+}
+
+impl GfShare {
+    /// Sharing with share numbers `1, 2, .., share_count`.
+    pub fn new(threshold: usize, share_count: usize) -> GfShare {
+        assert!(share_count < 256, "too many shares");
+        GfShare {
+            threshold,
+            share_numbers: (1..share_count as u16 + 1).map(|i| i as u8).collect(),
+        }
+    }
+
+    /// Minimum number of shares required to reconstruct secret.
+    ///
+    /// For this scheme this is always `threshold + 1`.
+    pub fn reconstruct_limit(&self) -> usize {
+        self.threshold + 1
+    }
+
+    /// Generate a share for every share number from `secret`.
+    pub fn share(&self, secret: &[u8]) -> Vec<Vec<u8>> {
+        let mut rng = ForwardSecureRng::new();
+        self.share_with_rng(secret, &mut rng)
+    }
+
+    /// Generate a share for every share number from `secret`, sampling the
+    /// polynomials with `rng`.
+    pub fn share_with_rng<R: Rng>(&self, secret: &[u8], rng: &mut R) -> Vec<Vec<u8>> {
+        // highest degree first, as laid out by libgfshare
+        let mut coefficients: Vec<Vec<u8>> = (0..self.threshold)
+            .map(|_| {
+                let mut row = vec![0; secret.len()];
+                rng.fill_bytes(&mut row);
+                row
+            })
+            .collect();
+        coefficients.push(secret.to_vec());
+        self.evaluate(&coefficients)
+    }
+
+    fn evaluate(&self, coefficients: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        assert!(self.threshold < self.share_numbers.len());
+        assert!(self.share_numbers.iter().all(|&x| x != 0));
+        for (i, x) in self.share_numbers.iter().enumerate() {
+            assert!(
+                !self.share_numbers[..i].contains(x),
+                "duplicate share number"
+            );
+        }
+        self.share_numbers
+            .iter()
+            .map(|&x| {
+                // Horner's rule on every byte
+                coefficients[1..]
+                    .iter()
+                    .fold(coefficients[0].clone(), |acc, row| {
+                        acc.iter().zip(row).map(|(&a, &c)| mul(a, x) ^ c).collect()
+                    })
+            })
+            .collect()
+    }
+
+    /// Reconstruct the secret from a large enough subset of the shares, as with
+    /// `gfcombine`.
+    ///
+    /// `share_numbers` are the share numbers of the known shares, while `shares`
+    /// are their contents. Both must have the same number of elements, and at least
+    /// `reconstruct_limit`.
+    pub fn reconstruct<S: AsRef<[u8]>>(&self, share_numbers: &[u8], shares: &[S]) -> Vec<u8> {
+        assert!(shares.len() == share_numbers.len());
+        assert!(shares.len() >= self.reconstruct_limit());
+        let len = shares[0].as_ref().len();
+        assert!(shares.iter().all(|share| share.as_ref().len() == len));
+        let logs = &TABLES.logs;
+        let mut secret = vec![0; len];
+        for (i, (&xi, share)) in share_numbers.iter().zip(shares).enumerate() {
+            assert!(xi != 0);
+            // logarithm of prod_{j != i} x_j / (x_j - x_i)
+            let mut log_constant = 0;
+            for (j, &xj) in share_numbers.iter().enumerate() {
+                if i != j {
+                    assert!(xi != xj, "duplicate share number");
+                    log_constant +=
+                        255 + logs[xj as usize] as usize - logs[(xi ^ xj) as usize] as usize;
+                }
+            }
+            let constant = TABLES.exps[log_constant % 255];
+            for (s, &y) in secret.iter_mut().zip(share.as_ref()) {
+                *s ^= mul(constant, y);
+            }
+        }
+        secret
+    }
+
+    /// Name of the file in which `gfsplit` writes the share numbered `share_number`
+    /// of the file `stem`.
+    pub fn file_name(stem: &str, share_number: u8) -> String {
+        format!("{}.{:03}", stem, share_number)
+    }
+
+    /// Share number of a file written by `gfsplit`, read from the three digits after
+    /// the last dot of its name, or `None` if there are none.
+    pub fn share_number_from_file_name(name: &str) -> Option<u8> {
+        let suffix = &name[name.rfind('.')? + 1..];
+        if suffix.len() != 3 || !suffix.bytes().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        match suffix.parse() {
+            Ok(0) | Err(_) => None,
+            Ok(x) => Some(x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_tables() {
+        assert_eq!(TABLES.exps[8], 0x1d);
+        assert_eq!(mul(0x80, 2), 0x1d);
+        for a in 1..=255 {
+            assert_eq!(TABLES.exps[TABLES.logs[a] as usize], a as u8);
+            assert!((1..=255).any(|b| mul(a as u8, b) == 1));
+        }
+    }
+
+    #[test]
+    fn test_vectors() {
+        // coefficients as laid out by libgfshare, values computed independently
+        let gfshare = GfShare {
+            threshold: 2,
+            share_numbers: vec![1, 7, 200, 255],
+        };
+        let coefficients = vec![
+            vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06],
+            vec![0xff, 0x80, 0x00, 0x10, 0x20, 0x30],
+            b"secret".to_vec(),
+        ];
+        let shares = gfshare.evaluate(&coefficients);
+        assert_eq!(
+            shares,
+            [
+                [0x8d, 0xe7, 0x60, 0x66, 0x40, 0x42],
+                [0xa1, 0xe8, 0x5c, 0x56, 0xc4, 0x9a],
+                [0x15, 0xa4, 0xdc, 0x2c, 0x81, 0x33],
+                [0x73, 0xde, 0x58, 0x96, 0xbe, 0xdf],
+            ]
+        );
+        assert_eq!(gfshare.reconstruct(&[7, 200, 255], &shares[1..]), b"secret");
+        assert_eq!(gfshare.reconstruct(&[1, 7, 200, 255], &shares), b"secret");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let gfshare = GfShare::new(3, 10);
+        let secret: Vec<u8> = (0..=255).collect();
+        let shares = gfshare.share(&secret);
+        assert_eq!(shares.len(), 10);
+        assert!(shares.iter().all(|share| share.len() == 256));
+        let subset = [&shares[2], &shares[5], &shares[8], &shares[9]];
+        assert_eq!(gfshare.reconstruct(&[3, 6, 9, 10], &subset), secret);
+        assert_eq!(gfshare.reconstruct(&[1, 2, 3, 4], &shares[..4]), secret);
+        assert_ne!(gfshare.reconstruct(&[1, 2, 3, 5], &shares[..4]), secret);
+
+        // no randomness below the threshold
+        let gfshare = GfShare::new(0, 2);
+        assert_eq!(gfshare.share(b"ab"), [b"ab", b"ab"]);
+    }
+
+    #[test]
+    fn test_file_names() {
+        assert_eq!(GfShare::file_name("secret.txt", 42), "secret.txt.042");
+        assert_eq!(GfShare::file_name("secret", 255), "secret.255");
+        assert_eq!(
+            GfShare::share_number_from_file_name("secret.txt.042"),
+            Some(42)
+        );
+        assert_eq!(
+            GfShare::share_number_from_file_name("/tmp/a.b/secret.255"),
+            Some(255)
+        );
+        assert_eq!(GfShare::share_number_from_file_name("secret.txt"), None);
+        assert_eq!(GfShare::share_number_from_file_name("secret.000"), None);
+        assert_eq!(GfShare::share_number_from_file_name("secret.256"), None);
+        assert_eq!(GfShare::share_number_from_file_name("secret.+42"), None);
+        assert_eq!(GfShare::share_number_from_file_name("secret"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate share number")]
+    fn test_duplicate_share_numbers() {
+        let gfshare = GfShare {
+            threshold: 1,
+            share_numbers: vec![3, 5, 3],
+        };
+        gfshare.share(b"secret");
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Interoperability with existing secret sharing tools, producing and consuming
+//! shares byte-identical to theirs so that share sets can be migrated.
+//!
+//! Both tools work over binary fields rather than prime fields, and follow their own
+//! conventions for evaluation points and polynomials, so they are implemented here
+//! on their own rather than through `Field`:
+//!
+//! - `GfShare` matches `libgfshare` and its `gfsplit` and `gfcombine` tools, which
+//!   share every byte of the secret separately over GF(256);
+//! - `Ssss` matches the `ssss-split` and `ssss-combine` tools, which share the
+//!   whole secret as a single element of GF(2^d).
+
+mod gfshare;
+mod ssss;
+
+pub use self::gfshare::GfShare;
+pub use self::ssss::{Ssss, SsssError};
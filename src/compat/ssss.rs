@@ -0,0 +1,639 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Compatibility with `ssss`.
+//!
+//! The secret is read as a big-endian integer and shared as a single element of
+//! GF(2^d), where the security level `d` is a multiple of 8 up to 1024 and defaults
+//! to the bit length of the secret. The sharing polynomial is monic of degree
+//! `threshold + 1`, rather than having a random leading coefficient, and share `i`
+//! is its value at the element whose binary representation is `i`, starting from 1.
+//! For security levels of at least 64 bits the secret first goes through a
+//! diffusion layer built on XTEA, unless disabled.
+//!
+//! Shares are written as their number, padded with zeros to the width of the
+//! largest one, followed by their value in hexadecimal on `d / 4` digits, and may
+//! be prefixed by a token: `token-03-89ab..`.
+
+use fields::ForwardSecureRng;
+use rand::Rng;
+use std::error::Error;
+use std::fmt;
+
+/// Middle terms `a > b > c` of the pentanomial `x^d + x^a + x^b + x^c + 1` defining
+/// GF(2^d), in turn for `d = 8, 16, .., 1024`.
+#[rustfmt::skip]
+const IRREDUCIBLE: [u8; 384] = [
+    4, 3, 1, 5, 3, 1, 4, 3, 1, 7, 3, 2, 5, 4, 3, 5, 3, 2, 7, 4, 2, 4, 3, 1,
+    10, 9, 3, 9, 4, 2, 7, 6, 2, 10, 9, 6, 4, 3, 1, 5, 4, 3, 4, 3, 1, 7, 2, 1,
+    5, 3, 2, 7, 4, 2, 6, 3, 2, 5, 3, 2, 15, 3, 2, 11, 3, 2, 9, 8, 7, 7, 2, 1,
+    5, 3, 2, 9, 3, 1, 7, 3, 1, 9, 8, 3, 9, 4, 2, 8, 5, 3, 15, 14, 10, 10, 5, 2,
+    9, 6, 2, 9, 3, 2, 9, 5, 2, 11, 10, 1, 7, 3, 2, 11, 2, 1, 9, 7, 4, 4, 3, 1,
+    8, 3, 1, 7, 4, 1, 7, 2, 1, 13, 11, 6, 5, 3, 2, 7, 3, 2, 8, 7, 5, 12, 3, 2,
+    13, 10, 6, 5, 3, 2, 5, 3, 2, 9, 5, 2, 9, 7, 2, 13, 4, 3, 4, 3, 1, 11, 6, 4,
+    18, 9, 6, 19, 18, 13, 11, 3, 2, 15, 9, 6, 4, 3, 1, 16, 5, 2, 15, 14, 6, 8, 5, 2,
+    15, 11, 2, 11, 6, 2, 7, 5, 3, 8, 3, 1, 19, 16, 9, 11, 9, 6, 15, 7, 6, 13, 4, 3,
+    14, 13, 3, 13, 6, 3, 9, 5, 2, 19, 13, 6, 19, 10, 3, 11, 6, 5, 9, 2, 1, 14, 3, 2,
+    13, 3, 1, 7, 5, 4, 11, 9, 8, 11, 6, 5, 23, 16, 9, 19, 14, 6, 23, 10, 2, 8, 3, 2,
+    5, 4, 3, 9, 6, 4, 4, 3, 2, 13, 8, 6, 13, 11, 1, 13, 10, 3, 11, 6, 5, 19, 17, 4,
+    15, 14, 7, 13, 9, 6, 9, 7, 3, 9, 7, 1, 14, 3, 2, 11, 8, 2, 11, 6, 4, 13, 5, 2,
+    11, 5, 1, 11, 4, 1, 19, 10, 3, 21, 10, 6, 13, 3, 1, 15, 7, 5, 19, 18, 10, 7, 5, 3,
+    12, 7, 2, 7, 5, 1, 14, 9, 6, 10, 3, 2, 15, 13, 12, 12, 11, 9, 16, 9, 7, 12, 9, 3,
+    9, 5, 2, 17, 10, 6, 24, 9, 3, 17, 15, 13, 5, 4, 3, 19, 17, 8, 15, 6, 3, 19, 6, 1,
+];
+
+/// Smallest security level at which the diffusion layer is applied.
+const DIFFUSION_MIN_DEGREE: usize = 64;
+
+/// Failure to share or reconstruct as with `ssss`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SsssError {
+    /// The security level is not a multiple of 8 between 8 and 1024.
+    InvalidSecurity(usize),
+    /// The secret does not fit in the security level.
+    SecretTooLong,
+    /// There are too many shares to number them within the field.
+    TooManyShares,
+    /// A share is not of the form `[token-]number-hex`, or its number is zero or
+    /// does not fit in the field.
+    MalformedShare,
+    /// Shares are of different security levels.
+    MismatchedShares,
+    /// Fewer shares than `reconstruct_limit` were given, or some were given twice.
+    NotEnoughShares,
+}
+
+impl fmt::Display for SsssError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SsssError::InvalidSecurity(d) => write!(f, "invalid security level {}", d),
+            SsssError::SecretTooLong => write!(f, "secret too long for the security level"),
+            SsssError::TooManyShares => write!(f, "too many shares for the security level"),
+            SsssError::MalformedShare => write!(f, "malformed share"),
+            SsssError::MismatchedShares => write!(f, "shares have different security levels"),
+            SsssError::NotEnoughShares => write!(f, "not enough distinct shares"),
+        }
+    }
+}
+
+impl Error for SsssError {}
+
+/// Parameters for sharing as with `ssss-split`, specifying privacy threshold and
+/// total number of shares.
+///
+/// `ssss-split -t` takes the number of shares needed to reconstruct, which is
+/// `threshold + 1`, while `-s`, `-w` and `-D` correspond to `security`, `token`
+/// and a disabled `diffusion`.
+///
+/// # Example:
+///
+/// ```
+///    use threshold_secret_sharing::compat::Ssss;
+///    let ssss = Ssss::new(2, 5);
+///    let shares = ssss.share(b"correct horse").unwrap();
+///    assert_eq!(shares[3].len(), "4-".len() + 2 * 13);
+///    let recovered = ssss.reconstruct(&[&shares[4], &shares[0], &shares[2]]).unwrap();
+///    assert_eq!(recovered, b"correct horse");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ssss {
+    /// Maximum number of shares that can be known without exposing the secret.
+    pub threshold: usize,
+    /// Number of shares to split the secret into.
+    pub share_count: usize,
+    /// Security level in bits, or `None` for the bit length of the secret.
+    pub security: Option<usize>,
+    /// Token prefixed to the shares.
+    pub token: Option<String>,
+    /// Whether to apply the diffusion layer, for security levels of at least 64
+    /// bits.
+    pub diffusion: bool,
+}
+
+impl Ssss {
+    /// Parameters with the defaults of `ssss-split`.
+    pub fn new(threshold: usize, share_count: usize) -> Ssss {
+        Ssss {
+            threshold,
+            share_count,
+            security: None,
+            token: None,
+            diffusion: true,
+        }
+    }
+
+    /// Minimum number of shares required to reconstruct secret.
+    ///
+    /// For this scheme this is always `threshold + 1`.
+    pub fn reconstruct_limit(&self) -> usize {
+        self.threshold + 1
+    }
+
+    /// Generate `share_count` shares from `secret`, as printed by `ssss-split`.
+    pub fn share(&self, secret: &[u8]) -> Result<Vec<String>, SsssError> {
+        let mut rng = ForwardSecureRng::new();
+        self.share_with_rng(secret, &mut rng)
+    }
+
+    /// Generate `share_count` shares from `secret`, sampling the polynomial with
+    /// `rng`.
+    pub fn share_with_rng<R: Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<String>, SsssError> {
+        let field = BinaryField::new(self.security.unwrap_or(8 * secret.len()))?;
+        if secret.len() > field.degree / 8 {
+            return Err(SsssError::SecretTooLong);
+        }
+        let mut coefficients = vec![self.encode_secret(&field, secret)];
+        coefficients.extend((0..self.threshold).map(|_| {
+            let mut bytes = vec![0; field.degree / 8];
+            rng.fill_bytes(&mut bytes);
+            field.read_bytes(&bytes)
+        }));
+        self.evaluate(&field, &coefficients)
+    }
+
+    fn encode_secret(&self, field: &BinaryField, secret: &[u8]) -> Vec<u64> {
+        let mut bytes = vec![0; field.degree / 8 - secret.len()];
+        bytes.extend_from_slice(secret);
+        if self.diffusion && field.degree >= DIFFUSION_MIN_DEGREE {
+            bytes = diffuse(&bytes, false);
+        }
+        field.read_bytes(&bytes)
+    }
+
+    /// Shares of the polynomial `x^(threshold + 1)` plus the one with the given
+    /// coefficients, lowest degree first.
+    fn evaluate(
+        &self,
+        field: &BinaryField,
+        coefficients: &[Vec<u64>],
+    ) -> Result<Vec<String>, SsssError> {
+        assert!(coefficients.len() == self.reconstruct_limit());
+        assert!(self.threshold < self.share_count);
+        if field.degree < 64 && self.share_count >> field.degree != 0 {
+            return Err(SsssError::TooManyShares);
+        }
+        let width = self.share_count.to_string().len();
+        let shares = (1..self.share_count + 1)
+            .map(|i| {
+                let x = field.embed(i as u64);
+                // Horner's rule, starting from the implicit leading one
+                let y = coefficients[1..]
+                    .iter()
+                    .rev()
+                    .fold(x.clone(), |acc, c| field.mul(&field.add(&acc, c), &x));
+                let y = field.add(&y, &coefficients[0]);
+                let hex: String = field
+                    .write_bytes(&y)
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+                match self.token {
+                    Some(ref token) => format!("{}-{:0width$}-{}", token, i, hex, width = width),
+                    None => format!("{:0width$}-{}", i, hex, width = width),
+                }
+            })
+            .collect();
+        Ok(shares)
+    }
+
+    /// Reconstruct the secret from at least `reconstruct_limit` shares, as printed
+    /// by `ssss-combine`, ie. without leading zero bytes.
+    ///
+    /// The security level is given by the length of the shares, and their tokens
+    /// are ignored.
+    pub fn reconstruct<S: AsRef<str>>(&self, shares: &[S]) -> Result<Vec<u8>, SsssError> {
+        let mut degree = None;
+        let mut points = vec![];
+        let mut values = vec![];
+        for share in shares {
+            let (index, hex) = parse_share(share.as_ref())?;
+            if *degree.get_or_insert(4 * hex.len()) != 4 * hex.len() {
+                return Err(SsssError::MismatchedShares);
+            }
+            if points.contains(&index) {
+                return Err(SsssError::NotEnoughShares);
+            }
+            points.push(index);
+            values.push(hex);
+        }
+        if points.len() < self.reconstruct_limit() {
+            return Err(SsssError::NotEnoughShares);
+        }
+        let field = BinaryField::new(degree.unwrap()).map_err(|_| SsssError::MalformedShare)?;
+        if field.degree < 64 && points.iter().any(|&i| i >> field.degree != 0) {
+            return Err(SsssError::MalformedShare);
+        }
+        let points: Vec<Vec<u64>> = points.iter().map(|&i| field.embed(i)).collect();
+
+        // values of the polynomial without its leading term, of degree `threshold`
+        let mut secret = field.zero();
+        for (i, (xi, hex)) in points.iter().zip(&values).enumerate() {
+            let mut yi = field.read_bytes(&parse_hex(hex).ok_or(SsssError::MalformedShare)?);
+            let mut leading = field.one();
+            for _ in 0..self.reconstruct_limit() {
+                leading = field.mul(&leading, xi);
+            }
+            yi = field.add(&yi, &leading);
+            // prod_{j != i} x_j / (x_j - x_i)
+            let mut numerator = field.one();
+            let mut denominator = field.one();
+            for (j, xj) in points.iter().enumerate() {
+                if i != j {
+                    numerator = field.mul(&numerator, xj);
+                    denominator = field.mul(&denominator, &field.add(xj, xi));
+                }
+            }
+            let constant = field.mul(&numerator, &field.inv(&denominator));
+            secret = field.add(&secret, &field.mul(&constant, &yi));
+        }
+
+        let mut bytes = field.write_bytes(&secret);
+        if self.diffusion && field.degree >= DIFFUSION_MIN_DEGREE {
+            bytes = diffuse(&bytes, true);
+        }
+        let leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+        Ok(bytes.split_off(leading_zeros))
+    }
+}
+
+/// Number and hexadecimal value of a share.
+fn parse_share(share: &str) -> Result<(u64, &str), SsssError> {
+    let mut parts = share.trim().rsplitn(3, '-');
+    let hex = parts.next().ok_or(SsssError::MalformedShare)?;
+    let number = parts.next().ok_or(SsssError::MalformedShare)?;
+    if number.is_empty() || !number.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(SsssError::MalformedShare);
+    }
+    match number.parse() {
+        Ok(0) | Err(_) => Err(SsssError::MalformedShare),
+        Ok(index) => Ok((index, hex)),
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.as_bytes();
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    hex.chunks(2)
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            Some((high << 4 | low) as u8)
+        })
+        .collect()
+}
+
+/// GF(2^d) as defined by `ssss`, with elements as little-endian 64-bit limbs.
+struct BinaryField {
+    degree: usize,
+    /// Terms of the defining polynomial below `x^64`.
+    low: u64,
+}
+
+impl BinaryField {
+    fn new(degree: usize) -> Result<BinaryField, SsssError> {
+        if degree == 0 || degree % 8 != 0 || 3 * degree / 8 > IRREDUCIBLE.len() {
+            return Err(SsssError::InvalidSecurity(degree));
+        }
+        let i = 3 * (degree / 8 - 1);
+        let low = IRREDUCIBLE[i..i + 3].iter().fold(1, |acc, &a| acc | 1 << a);
+        Ok(BinaryField { degree, low })
+    }
+
+    fn zero(&self) -> Vec<u64> {
+        vec![0; self.degree.div_ceil(64)]
+    }
+
+    fn one(&self) -> Vec<u64> {
+        self.embed(1)
+    }
+
+    fn embed(&self, x: u64) -> Vec<u64> {
+        let mut e = self.zero();
+        e[0] = x;
+        e
+    }
+
+    /// Element represented by big-endian `bytes`, of at most `degree / 8` bytes.
+    fn read_bytes(&self, bytes: &[u8]) -> Vec<u64> {
+        let mut e = self.zero();
+        for (i, &byte) in bytes.iter().rev().enumerate() {
+            e[i / 8] |= (byte as u64) << (8 * (i % 8));
+        }
+        e
+    }
+
+    /// Big-endian representation of `e` on `degree / 8` bytes.
+    fn write_bytes(&self, e: &[u64]) -> Vec<u8> {
+        (0..self.degree / 8)
+            .rev()
+            .map(|i| (e[i / 8] >> (8 * (i % 8))) as u8)
+            .collect()
+    }
+
+    fn add(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+    }
+
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut acc = self.zero();
+        let mut x = a.to_vec();
+        for i in 0..self.degree {
+            if (b[i / 64] >> (i % 64)) & 1 == 1 {
+                for (s, t) in acc.iter_mut().zip(&x) {
+                    *s ^= t;
+                }
+            }
+            self.mul_by_x(&mut x);
+        }
+        acc
+    }
+
+    fn mul_by_x(&self, x: &mut [u64]) {
+        let top = self.degree - 1;
+        let carry = (x[top / 64] >> (top % 64)) & 1;
+        for i in (0..x.len()).rev() {
+            x[i] = x[i] << 1 | if i > 0 { x[i - 1] >> 63 } else { 0 };
+        }
+        if self.degree % 64 != 0 {
+            x[top / 64] &= (1 << (self.degree % 64)) - 1;
+        }
+        if carry == 1 {
+            x[0] ^= self.low;
+        }
+    }
+
+    /// Inverse of non-zero `a` as `a^(2^d - 2) = a^2 . a^4 .. a^(2^(d - 1))`.
+    fn inv(&self, a: &[u64]) -> Vec<u64> {
+        let mut square = a.to_vec();
+        let mut acc = self.one();
+        for _ in 1..self.degree {
+            square = self.mul(&square, &square);
+            acc = self.mul(&acc, &square);
+        }
+        acc
+    }
+}
+
+const XTEA_DELTA: u32 = 0x9e37_79b9;
+
+/// XTEA encryption of a block with an all-zero key.
+fn encipher(v: &mut [u32; 2]) {
+    let mut sum: u32 = 0;
+    for _ in 0..32 {
+        v[0] = v[0].wrapping_add((((v[1] << 4) ^ (v[1] >> 5)).wrapping_add(v[1])) ^ sum);
+        sum = sum.wrapping_add(XTEA_DELTA);
+        v[1] = v[1].wrapping_add((((v[0] << 4) ^ (v[0] >> 5)).wrapping_add(v[0])) ^ sum);
+    }
+}
+
+fn decipher(v: &mut [u32; 2]) {
+    let mut sum = XTEA_DELTA.wrapping_mul(32);
+    for _ in 0..32 {
+        v[1] = v[1].wrapping_sub((((v[0] << 4) ^ (v[0] >> 5)).wrapping_add(v[0])) ^ sum);
+        sum = sum.wrapping_sub(XTEA_DELTA);
+        v[0] = v[0].wrapping_sub((((v[1] << 4) ^ (v[1] >> 5)).wrapping_add(v[1])) ^ sum);
+    }
+}
+
+/// Process the block of 8 bytes of `data` starting at `index`, wrapping around.
+fn process_block(data: &mut [u8], index: usize, cipher: fn(&mut [u32; 2])) {
+    let len = data.len();
+    let mut v = [0; 2];
+    for (i, word) in v.iter_mut().enumerate() {
+        *word = (0..4).fold(0, |acc, j| {
+            acc << 8 | data[(index + 4 * i + j) % len] as u32
+        });
+    }
+    cipher(&mut v);
+    for (i, word) in v.iter().enumerate() {
+        for j in 0..4 {
+            data[(index + 4 * i + j) % len] = (word >> (24 - 8 * j)) as u8;
+        }
+    }
+}
+
+/// The diffusion layer of `ssss` on the big-endian representation of an element,
+/// or its inverse: blocks of 8 bytes are encrypted in turn, sliding by 2 bytes and
+/// wrapping around the element 40 times.
+///
+/// `ssss` lays out elements as 16-bit big-endian words, the least significant
+/// first, and for odd lengths moves the most significant byte into the otherwise
+/// empty last word.
+fn diffuse(bytes: &[u8], inverse: bool) -> Vec<u8> {
+    let len = bytes.len();
+    let mut v = vec![0; len + len % 2];
+    for (i, &byte) in bytes.iter().rev().enumerate() {
+        v[i ^ 1] = byte;
+    }
+    if len % 2 == 1 {
+        v[len - 1] = v[len];
+    }
+    let starts = (0..40 * len).step_by(2);
+    if inverse {
+        for index in starts.rev() {
+            process_block(&mut v[..len], index, decipher);
+        }
+    } else {
+        for index in starts {
+            process_block(&mut v[..len], index, encipher);
+        }
+    }
+    if len % 2 == 1 {
+        v[len] = v[len - 1];
+        v[len - 1] = 0;
+    }
+    (0..len).rev().map(|i| v[i ^ 1]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn test_field() {
+        // the AES field
+        let field = BinaryField::new(8).unwrap();
+        let a = field.embed(0x53);
+        assert_eq!(field.mul(&a, &field.embed(0xca)), field.one());
+        assert_eq!(field.inv(&a), field.embed(0xca));
+        assert_eq!(
+            field.mul(&field.embed(0x80), &field.embed(2)),
+            field.embed(0x1b)
+        );
+
+        let field = BinaryField::new(128).unwrap();
+        let x = field.embed(2);
+        let mut e = field.one();
+        for _ in 0..128 {
+            e = field.mul(&e, &x);
+        }
+        assert_eq!(e, field.embed(0x87));
+        let a = field.read_bytes(b"some element");
+        assert_eq!(field.mul(&a, &field.inv(&a)), field.one());
+        assert_eq!(field.write_bytes(&a)[4..], b"some element"[..]);
+
+        assert!(BinaryField::new(1024).is_ok());
+        for &degree in &[0, 12, 1032] {
+            assert_eq!(
+                BinaryField::new(degree).err(),
+                Some(SsssError::InvalidSecurity(degree))
+            );
+        }
+    }
+
+    #[test]
+    fn test_diffusion() {
+        // values computed independently
+        let cases: [(&[u8], &str); 3] = [
+            (b"abcdefgh", "732d5aea0e676da9"),
+            (b"hello world", "82eb2d9738fa861a432bc9"),
+            (b"hello world!", "f8185f44f4926293919470a4"),
+        ];
+        for &(secret, expected) in &cases {
+            let encoded = diffuse(secret, false);
+            assert_eq!(hex(&encoded), expected);
+            assert_eq!(diffuse(&encoded, true), secret);
+        }
+    }
+
+    #[test]
+    fn test_vectors() {
+        // values computed independently for the given coefficients
+        let ssss = Ssss {
+            diffusion: false,
+            ..Ssss::new(1, 3)
+        };
+        let field = BinaryField::new(8).unwrap();
+        let coefficients = [ssss.encode_secret(&field, b"a"), field.embed(0x5c)];
+        let shares = ssss.evaluate(&field, &coefficients).unwrap();
+        assert_eq!(shares, ["1-3c", "2-dd", "3-80"]);
+        assert_eq!(ssss.reconstruct(&shares[1..]).unwrap(), b"a");
+
+        let ssss = Ssss::new(2, 5);
+        let field = BinaryField::new(96).unwrap();
+        let coefficients = [
+            ssss.encode_secret(&field, b"hello world!"),
+            field.read_bytes(&[
+                1, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 1, 0x23, 0x45, 0x67,
+            ]),
+            field.read_bytes(&[
+                0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10, 0xfe, 0xdc, 0xba, 0x98,
+            ]),
+        ];
+        let shares = ssss.evaluate(&field, &coefficients).unwrap();
+        assert_eq!(
+            shares,
+            [
+                "1-07e7a0bb0b6d9d6c6e6b8f5a",
+                "2-012c3fea3e95310e68a01ac1",
+                "3-fed3c015c16acef1975fe539",
+                "4-115ee35db77e742078d2ed37",
+                "5-eea11ca248818bdf872d12dd",
+            ]
+        );
+        assert_eq!(ssss.reconstruct(&shares[2..]).unwrap(), b"hello world!");
+
+        let ssss = Ssss {
+            token: Some("foo".to_string()),
+            ..Ssss::new(1, 12)
+        };
+        let field = BinaryField::new(88).unwrap();
+        let coefficients = [
+            ssss.encode_secret(&field, b"hello world"),
+            field.read_bytes(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]),
+        ];
+        let shares = ssss.evaluate(&field, &coefficients).unwrap();
+        assert_eq!(shares[0], "foo-01-83e92e933dfc81124a21c3");
+        assert_eq!(shares[11], "foo-12-8ef339a704d2a27a2f53ed");
+        assert_eq!(
+            ssss.reconstruct(&[&shares[11], &shares[4]]).unwrap(),
+            b"hello world"
+        );
+
+        // shorter secret than the security level
+        let ssss = Ssss {
+            security: Some(128),
+            diffusion: false,
+            ..Ssss::new(1, 3)
+        };
+        let field = BinaryField::new(128).unwrap();
+        let mut high = field.embed(5);
+        high[1] = 1 << 63;
+        let coefficients = [ssss.encode_secret(&field, b"pw"), high];
+        let shares = ssss.evaluate(&field, &coefficients).unwrap();
+        assert_eq!(
+            shares,
+            [
+                "1-80000000000000000000000000007073",
+                "2-000000000000000000000000000070fe",
+                "3-800000000000000000000000000070fa",
+            ]
+        );
+        assert_eq!(ssss.reconstruct(&shares[..2]).unwrap(), b"pw");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for &(security, diffusion) in &[(None, true), (Some(88), true), (Some(256), false)] {
+            let ssss = Ssss {
+                security,
+                diffusion,
+                ..Ssss::new(3, 6)
+            };
+            let shares = ssss.share(b"opensesame").unwrap();
+            let degree = security.unwrap_or(80);
+            assert!(shares.iter().all(|share| share.len() == 2 + degree / 4));
+            assert_eq!(ssss.reconstruct(&shares[1..5]).unwrap(), b"opensesame");
+            assert_eq!(ssss.reconstruct(&shares).unwrap(), b"opensesame");
+            // the leading term depends on the threshold
+            let wrong = Ssss {
+                threshold: 2,
+                ..ssss.clone()
+            };
+            assert_ne!(wrong.reconstruct(&shares[..4]), Ok(b"opensesame".to_vec()));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let ssss = Ssss::new(1, 3);
+        assert_eq!(ssss.share(b"").err(), Some(SsssError::InvalidSecurity(0)));
+        let short = Ssss {
+            security: Some(16),
+            ..ssss.clone()
+        };
+        assert_eq!(short.share(b"abc").err(), Some(SsssError::SecretTooLong));
+        let many = Ssss::new(1, 256);
+        assert_eq!(many.share(b"a").err(), Some(SsssError::TooManyShares));
+        assert_eq!(Ssss::new(1, 255).share(b"a").unwrap()[254].len(), 6);
+
+        let shares = ssss.share(b"secret").unwrap();
+        let first = shares[0].as_str();
+        let check = |shares: &[&str], error| assert_eq!(ssss.reconstruct(shares), Err(error));
+        check(&[first], SsssError::NotEnoughShares);
+        check(&[first, first], SsssError::NotEnoughShares);
+        check(&[first, "2-00"], SsssError::MismatchedShares);
+        check(&["1-00", "2-0g"], SsssError::MalformedShare);
+        check(&["1-00", "0-00"], SsssError::MalformedShare);
+        check(&["1-00", "256-00"], SsssError::MalformedShare);
+        check(&["1-00", "00"], SsssError::MalformedShare);
+        check(&["1-000", "2-000"], SsssError::MalformedShare);
+        check(&["1-", "2-"], SsssError::MalformedShare);
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Binding of seeded sharings to the context they are made in.
+//!
+//! A `ShareContext` names a session and a purpose. Sharing in a context derives
+//! the randomness from the seed and the context together, so that reusing a seed
+//! across contexts gives independent sharings, and tags every share with a public
+//! identifier of the context, so that reconstruction refuses shares from different
+//! contexts instead of silently returning garbage.
+
+use fields::{Encode, Field};
+use hybrid::KeyDerivation;
+use packed::PackedSecretSharing;
+use seed::Seed;
+use shamir::ShamirSecretSharing;
+use share::{index_u32, InvalidTags, TaggedShare};
+
+/// Prefix of all inputs derived from, separating them from other uses of the KDF.
+const DOMAIN: &[u8] = b"threshold-secret-sharing share context";
+
+/// Public identifier of a context, carried by every share made in it.
+pub type ContextId = [u8; 16];
+
+/// Session and purpose to which seeded sharings are bound, together with the key
+/// derivation function used for binding them.
+///
+/// Both the session and the purpose are arbitrary, but should be unique: typically
+/// a random session identifier agreed on by the parties, and a description of
+/// what is shared.
+#[derive(Clone, Debug)]
+pub struct ShareContext<K: KeyDerivation> {
+    session: Vec<u8>,
+    purpose: Vec<u8>,
+    kdf: K,
+}
+
+impl<K: KeyDerivation> ShareContext<K> {
+    pub fn new<S: AsRef<[u8]>>(kdf: K, session: S, purpose: &str) -> ShareContext<K> {
+        ShareContext {
+            session: session.as_ref().to_vec(),
+            purpose: purpose.as_bytes().to_vec(),
+            kdf,
+        }
+    }
+
+    /// Unambiguous encoding of the context, followed by `label`.
+    fn info(&self, label: &[u8]) -> Vec<u8> {
+        let mut info = DOMAIN.to_vec();
+        for part in &[&self.session, &self.purpose] {
+            info.extend_from_slice(&(part.len() as u32).to_le_bytes());
+            info.extend_from_slice(part);
+        }
+        info.extend_from_slice(label);
+        info
+    }
+
+    /// Public identifier of the context.
+    pub fn id(&self) -> ContextId {
+        let mut id = [0; 16];
+        id.copy_from_slice(&self.kdf.derive(&[], &self.info(b" id"), 16));
+        id
+    }
+
+    /// Seed for sharing in this context, derived from `seed`.
+    pub fn derive_seed(&self, seed: &Seed) -> Seed {
+        let mut derived = [0; 32];
+        derived.copy_from_slice(&self.kdf.derive(seed, &self.info(b" seed"), 32));
+        derived
+    }
+}
+
+/// A share together with its index, tagged with the identifier of its context.
+pub type ContextShare<F> = TaggedShare<F, ContextId>;
+
+impl<F> ShamirSecretSharing<F>
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    /// Generate `share_count` shares from `secret` like `share_seeded`, deriving all
+    /// randomness from `seed` and `context`, and tagging every share with the
+    /// identifier of `context`.
+    pub fn share_in_context<K: KeyDerivation>(
+        &self,
+        secret: F::E,
+        seed: &Seed,
+        context: &ShareContext<K>,
+    ) -> Vec<ContextShare<F>> {
+        let id = context.id();
+        TaggedShare::tag_all(
+            self.share_seeded(secret, &context.derive_seed(seed)),
+            |_, _| id,
+        )
+    }
+
+    /// Reconstruct the secret from shares made in `context` as with
+    /// `reconstruct_indexed`.
+    ///
+    /// Fails with the indices of the shares made in another context.
+    pub fn reconstruct_in_context<K: KeyDerivation>(
+        &self,
+        shares: &[ContextShare<F>],
+        context: &ShareContext<K>,
+    ) -> Result<F::E, InvalidTags> {
+        let id = context.id();
+        let (indices, values) = TaggedShare::check_all(shares, |share| share.tag == id)?;
+        Ok(self.reconstruct(&indices, &values))
+    }
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Generate `share_count` shares for the `secrets` vector like `share_seeded`,
+    /// deriving all randomness from `seed` and `context`, and tagging every share
+    /// with the identifier of `context`.
+    pub fn share_in_context<K: KeyDerivation>(
+        &self,
+        secrets: &[F::E],
+        seed: &Seed,
+        context: &ShareContext<K>,
+    ) -> Vec<ContextShare<F>> {
+        let id = context.id();
+        TaggedShare::tag_all(
+            self.share_seeded(secrets, &context.derive_seed(seed)),
+            |_, _| id,
+        )
+    }
+
+    /// Reconstruct the secrets from shares made in `context` as with
+    /// `reconstruct_indexed`.
+    ///
+    /// Fails with the indices of the shares made in another context.
+    pub fn reconstruct_in_context<K: KeyDerivation>(
+        &self,
+        shares: &[ContextShare<F>],
+        context: &ShareContext<K>,
+    ) -> Result<Vec<F::E>, InvalidTags> {
+        let id = context.id();
+        let (indices, values) = TaggedShare::check_all(shares, |share| share.tag == id)?;
+        let indices: Vec<u32> = indices.into_iter().map(index_u32).collect();
+        Ok(self.reconstruct(&indices, &values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Fp61, SliceEncode};
+    use packed::PSS_4_26_3;
+
+    /// Insecure stand-in for a real KDF, mixing all its input into the output.
+    #[derive(Clone, Debug)]
+    struct ToyKdf;
+
+    impl KeyDerivation for ToyKdf {
+        fn derive(&self, material: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+            let mut state: u64 = 0xcbf29ce484222325;
+            let mut absorb = |b: u8| {
+                state = (state ^ b as u64).wrapping_mul(0x100000001b3);
+                state
+            };
+            for &b in material.iter().chain(info) {
+                absorb(b);
+            }
+            (0..len).map(|i| (absorb(i as u8) >> 32) as u8).collect()
+        }
+    }
+
+    #[test]
+    fn test_context() {
+        let context = ShareContext::new(ToyKdf, b"session 1", "backup");
+        assert_eq!(
+            context.id(),
+            ShareContext::new(ToyKdf, "session 1", "backup").id()
+        );
+        // the encoding separates the session from the purpose
+        assert_ne!(
+            context.id(),
+            ShareContext::new(ToyKdf, "session 1b", "ackup").id()
+        );
+        assert_ne!(
+            context.id(),
+            ShareContext::new(ToyKdf, "session 2", "backup").id()
+        );
+
+        let seed = [7; 32];
+        assert_ne!(context.derive_seed(&seed), seed);
+        assert_ne!(
+            context.derive_seed(&seed),
+            ShareContext::new(ToyKdf, "session 2", "backup").derive_seed(&seed)
+        );
+    }
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            field: Fp61,
+        };
+        let seed = [3; 32];
+        let first = ShareContext::new(ToyKdf, "session 1", "backup");
+        let second = ShareContext::new(ToyKdf, "session 2", "backup");
+
+        let shares = tss.share_in_context(42, &seed, &first);
+        assert_eq!(shares, tss.share_in_context(42, &seed, &first));
+        assert!(shares.iter().all(|share| share.tag == first.id()));
+        assert_eq!(tss.reconstruct_in_context(&shares[2..], &first), Ok(42));
+
+        // the same seed gives independent sharings in other contexts
+        let other = tss.share_in_context(42, &seed, &second);
+        assert_ne!(other[0].value, shares[0].value);
+        let mixed = vec![shares[0].clone(), other[1].clone(), shares[2].clone()];
+        assert_eq!(
+            tss.reconstruct_in_context(&mixed, &first),
+            Err(InvalidTags(vec![1]))
+        );
+        assert_eq!(
+            tss.reconstruct_in_context(&shares[..3], &second),
+            Err(InvalidTags(vec![0, 1, 2]))
+        );
+    }
+
+    #[test]
+    fn test_packed() {
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([1, 2, 3]);
+        let context = ShareContext::new(ToyKdf, [1, 2, 3, 4], "packed");
+        let shares = pss.share_in_context(&secrets, &[9; 32], &context);
+        assert_eq!(shares.len(), pss.share_count);
+        assert_eq!(
+            pss.reconstruct_in_context(&shares[5..12], &context),
+            Ok(secrets)
+        );
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_hkdf() {
+        use hybrid::HkdfSha256;
+
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 3,
+            field: Fp61,
+        };
+        let context = ShareContext::new(HkdfSha256, "session", "test");
+        let shares = tss.share_in_context(5, &[0; 32], &context);
+        assert_eq!(tss.reconstruct_in_context(&shares[1..], &context), Ok(5));
+    }
+}
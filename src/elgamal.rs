@@ -0,0 +1,116 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Threshold ElGamal, with the private key split with Shamir's scheme so that any
+//! `threshold + 1` holders can decrypt together without reconstructing it.
+//!
+//! Messages are elements of a prime-order group `G`, such as the Ristretto group
+//! or a subgroup of BLS12-381, and the key is shared over its scalars using a
+//! `ShamirSecretSharing<ScalarField<G::Scalar>>` as given by `for_scalars`.
+//!
+//! Each holder computes a partial decryption of a ciphertext with its share, and
+//! these are combined with the coefficients of `lagrange_coefficients_at_zero`
+//! into the mask hiding the message. Partial decryptions are not proven correct,
+//! so a single holder can make decryption fail without being noticed; protocols
+//! facing malicious holders must add proofs of discrete log equality on top.
+
+extern crate group;
+
+use self::group::Group;
+use fields::{Field, ForwardSecureRng, ScalarField};
+use rand::Rng;
+use shamir::ShamirSecretSharing;
+use threshold_sig::combine_in_exponent;
+
+/// ElGamal encryption `(r.B, M + r.Y)` of a message `M` under public key `Y`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ciphertext<G> {
+    /// The generator multiplied by the randomness.
+    pub ephemeral: G,
+    /// The message, masked by the public key multiplied by the randomness.
+    pub masked: G,
+}
+
+/// Public key of `private_key`, or verification key of a share of it.
+pub fn public_key<G: Group>(private_key: &G::Scalar) -> G {
+    G::generator() * private_key
+}
+
+/// Encrypt `message` under `public_key`.
+pub fn encrypt<G: Group>(public_key: &G, message: &G) -> Ciphertext<G> {
+    encrypt_with_rng(public_key, message, &mut ForwardSecureRng::new())
+}
+
+/// Like `encrypt`, sampling the randomness from `rng`.
+pub fn encrypt_with_rng<G: Group, R: Rng>(
+    public_key: &G,
+    message: &G,
+    rng: &mut R,
+) -> Ciphertext<G> {
+    let r = ScalarField::<G::Scalar>::new().sample_nonzero(rng);
+    Ciphertext {
+        ephemeral: G::generator() * r,
+        masked: *message + *public_key * r,
+    }
+}
+
+/// Partial decryption of `ciphertext` by the holder of `share`.
+pub fn partial_decrypt<G: Group>(share: &G::Scalar, ciphertext: &Ciphertext<G>) -> G {
+    ciphertext.ephemeral * share
+}
+
+/// Decrypt `ciphertext` from the partial decryptions of at least `threshold + 1`
+/// holders, with `indices` their ranks as output by `share`.
+pub fn decrypt<G: Group>(
+    tss: &ShamirSecretSharing<ScalarField<G::Scalar>>,
+    ciphertext: &Ciphertext<G>,
+    indices: &[usize],
+    partial_decryptions: &[G],
+) -> G {
+    ciphertext.masked - combine_in_exponent(tss, indices, partial_decryptions)
+}
+
+#[cfg(all(test, feature = "ed25519"))]
+mod tests {
+
+    extern crate curve25519_dalek;
+
+    use self::curve25519_dalek::{RistrettoPoint, Scalar};
+    use super::*;
+    use rand::{ChaChaRng, SeedableRng};
+
+    #[test]
+    fn test_threshold_decryption() {
+        let tss = ShamirSecretSharing::<ScalarField<Scalar>>::for_scalars(2, 5);
+        let mut rng = ChaChaRng::from_seed(&[3]);
+        let private_key = tss.field.sample(&mut rng);
+        let shares = tss.share_with_rng(private_key, &mut rng);
+        let pk: RistrettoPoint = public_key(&private_key);
+
+        let message = RistrettoPoint::generator() * tss.field.sample(&mut rng);
+        let ciphertext = encrypt_with_rng(&pk, &message, &mut rng);
+        assert_ne!(ciphertext.masked, message);
+
+        let indices = [3, 0, 4];
+        let partials: Vec<RistrettoPoint> = indices
+            .iter()
+            .map(|&i| partial_decrypt(&shares[i], &ciphertext))
+            .collect();
+        assert_eq!(decrypt(&tss, &ciphertext, &indices, &partials), message);
+        let indices = [0, 1, 2, 3, 4];
+        let partials: Vec<RistrettoPoint> = shares
+            .iter()
+            .map(|share| partial_decrypt(share, &ciphertext))
+            .collect();
+        assert_eq!(decrypt(&tss, &ciphertext, &indices, &partials), message);
+
+        // partial decryptions for another ciphertext are useless
+        let other = encrypt(&pk, &message);
+        assert_ne!(decrypt(&tss, &other, &indices, &partials), message);
+    }
+}
@@ -0,0 +1,292 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Shares tagged with a fingerprint of the parameters of their scheme.
+//!
+//! Reconstructing with parameters other than those used for sharing, such as a
+//! different threshold or root of unity, silently gives garbage. A `ShareEnvelope`
+//! carries a fingerprint of the scheme it was made with and the version of its
+//! format, so that reconstruction rejects shares of mismatched schemes instead.
+//!
+//! The fingerprint is that of the `SchemeId` of the scheme, and does not depend on
+//! the field backend.
+
+use std::error::Error;
+use std::fmt;
+
+use fields::{ElementBytes, Encode, Field};
+use fingerprint::Fingerprint;
+use packed::PackedSecretSharing;
+use shamir::ShamirSecretSharing;
+use share::{index_u32, IndexError, ShareIndex};
+
+/// Version of the envelope format written by this crate.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// A share together with its index, the fingerprint of its scheme and the version
+/// of its format.
+pub struct ShareEnvelope<F: Field> {
+    /// Version of the envelope format.
+    pub version: u8,
+    /// Fingerprint of the scheme the share was made with.
+    pub fingerprint: Fingerprint,
+    /// Rank of the share as output by the `share` methods.
+    pub index: ShareIndex,
+    /// Value of the share.
+    pub value: F::E,
+}
+
+element_impls!(Clone, PartialEq, Debug for ShareEnvelope { version, fingerprint, index, value });
+
+impl<F: ElementBytes> ShareEnvelope<F> {
+    /// Serialize the envelope as its version, its fingerprint as a little-endian
+    /// `u64`, its index as a little-endian `u32`, and its value using `ElementBytes`.
+    pub fn to_bytes(&self, field: &F) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(13 + field.element_len());
+        bytes.push(self.version);
+        bytes.extend_from_slice(&self.fingerprint.to_le_bytes());
+        bytes.extend_from_slice(&index_u32(self.index).to_le_bytes());
+        field.write_element(&self.value, &mut bytes);
+        bytes
+    }
+
+    /// Envelope serialized by `to_bytes`, or `None` if `bytes` is malformed.
+    ///
+    /// Envelopes of unknown versions are read as long as they have the same layout,
+    /// and only rejected on reconstruction.
+    pub fn from_bytes(field: &F, bytes: &[u8]) -> Option<ShareEnvelope<F>> {
+        if bytes.len() != 13 + field.element_len() {
+            return None;
+        }
+        let mut fingerprint = [0; 8];
+        fingerprint.copy_from_slice(&bytes[1..9]);
+        let mut index = [0; 4];
+        index.copy_from_slice(&bytes[9..13]);
+        Some(ShareEnvelope {
+            version: bytes[0],
+            fingerprint: u64::from_le_bytes(fingerprint),
+            index: u32::from_le_bytes(index) as usize,
+            value: field.read_element(&bytes[13..])?,
+        })
+    }
+}
+
+/// Failure to reconstruct from envelopes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The share with this index has an unsupported format version.
+    UnsupportedVersion(ShareIndex),
+    /// The share with this index was made with a scheme of different parameters.
+    WrongScheme(ShareIndex),
+    /// The indices of the shares are invalid.
+    Index(IndexError),
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EnvelopeError::UnsupportedVersion(index) => {
+                write!(f, "share with index {} has an unsupported version", index)
+            }
+            EnvelopeError::WrongScheme(index) => write!(
+                f,
+                "share with index {} was made with different parameters",
+                index
+            ),
+            EnvelopeError::Index(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for EnvelopeError {}
+
+impl From<IndexError> for EnvelopeError {
+    fn from(err: IndexError) -> EnvelopeError {
+        EnvelopeError::Index(err)
+    }
+}
+
+/// Check the versions and fingerprints of `envelopes`, returning their indices and
+/// values.
+fn open_envelopes<F: Field>(
+    fingerprint: Fingerprint,
+    envelopes: &[ShareEnvelope<F>],
+) -> Result<(Vec<ShareIndex>, Vec<F::E>), EnvelopeError>
+where
+    F::E: Clone,
+{
+    for envelope in envelopes {
+        if envelope.version != ENVELOPE_VERSION {
+            return Err(EnvelopeError::UnsupportedVersion(envelope.index));
+        }
+        if envelope.fingerprint != fingerprint {
+            return Err(EnvelopeError::WrongScheme(envelope.index));
+        }
+    }
+    Ok(envelopes
+        .iter()
+        .map(|envelope| (envelope.index, envelope.value.clone()))
+        .unzip())
+}
+
+/// Wrap `values` in envelopes, the value at position `i` having index `i`.
+fn seal<F: Field>(fingerprint: Fingerprint, values: Vec<F::E>) -> Vec<ShareEnvelope<F>> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| ShareEnvelope {
+            version: ENVELOPE_VERSION,
+            fingerprint,
+            index,
+            value,
+        })
+        .collect()
+}
+
+impl<F> ShamirSecretSharing<F>
+where
+    F: Field + Encode<u32> + ElementBytes,
+    F::E: Clone,
+{
+    /// Wrap all `shares` output by one of the `share` methods in envelopes.
+    pub fn envelope_shares(&self, shares: Vec<F::E>) -> Vec<ShareEnvelope<F>> {
+        assert_eq!(shares.len(), self.share_count);
+        seal(self.fingerprint(), shares)
+    }
+
+    /// Reconstruct the secret from `envelopes` as with `try_reconstruct`, after
+    /// checking that they were all made with this scheme.
+    pub fn reconstruct_enveloped(
+        &self,
+        envelopes: &[ShareEnvelope<F>],
+    ) -> Result<F::E, EnvelopeError> {
+        let (indices, values) = open_envelopes(self.fingerprint(), envelopes)?;
+        Ok(self.try_reconstruct(&indices, &values)?)
+    }
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field + Encode<u32> + ElementBytes + Clone,
+    F::E: Clone,
+{
+    /// Wrap all `shares` output by one of the `share` methods in envelopes.
+    pub fn envelope_shares(&self, shares: Vec<F::E>) -> Vec<ShareEnvelope<F>> {
+        assert_eq!(shares.len(), self.share_count);
+        seal(self.fingerprint(), shares)
+    }
+
+    /// Reconstruct the secrets from `envelopes` as with `try_reconstruct`, after
+    /// checking that they were all made with this scheme.
+    pub fn reconstruct_enveloped(
+        &self,
+        envelopes: &[ShareEnvelope<F>],
+    ) -> Result<Vec<F::E>, EnvelopeError> {
+        let (indices, values) = open_envelopes(self.fingerprint(), envelopes)?;
+        let indices: Vec<u32> = indices.into_iter().map(index_u32).collect();
+        Ok(self.try_reconstruct(&indices, &values)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{MontgomeryField32, NaturalPrimeField, New, U64Field};
+    use packed::{SecretLayout, PSS_4_26_3};
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(41),
+        };
+        let envelopes = tss.envelope_shares(tss.share(17));
+        assert_eq!(envelopes[4].index, 4);
+        assert_eq!(tss.reconstruct_enveloped(&envelopes[2..5]), Ok(17));
+        let bytes = envelopes[3].to_bytes(&tss.field);
+        assert_eq!(
+            ShareEnvelope::from_bytes(&tss.field, &bytes),
+            Some(envelopes[3].clone())
+        );
+        assert_eq!(ShareEnvelope::from_bytes(&tss.field, &bytes[1..]), None);
+
+        // any change of parameters is noticed
+        let other = ShamirSecretSharing {
+            threshold: 3,
+            share_count: 6,
+            field: NaturalPrimeField(41),
+        };
+        assert_eq!(
+            other.reconstruct_enveloped(&envelopes[1..5]),
+            Err(EnvelopeError::WrongScheme(1))
+        );
+        let other = ShamirSecretSharing {
+            share_count: 6,
+            threshold: 2,
+            field: NaturalPrimeField(43),
+        };
+        assert_ne!(other.fingerprint(), tss.fingerprint());
+        // but not the field backend
+        let other = ShamirSecretSharing {
+            share_count: 6,
+            threshold: 2,
+            field: MontgomeryField32::new(41),
+        };
+        assert_eq!(other.fingerprint(), tss.fingerprint());
+        let other = ShamirSecretSharing {
+            share_count: 6,
+            threshold: 2,
+            field: U64Field(41),
+        };
+        assert_eq!(other.fingerprint(), tss.fingerprint());
+    }
+
+    #[test]
+    fn test_packed() {
+        let pss = &PSS_4_26_3;
+        let envelopes = pss.envelope_shares(pss.share(&[5, 6, 7]));
+        assert_eq!(
+            pss.reconstruct_enveloped(&envelopes[3..10]),
+            Ok(vec![5, 6, 7])
+        );
+
+        let strided = PSS_4_26_3
+            .clone()
+            .with_layout(SecretLayout::Strided(2))
+            .unwrap();
+        assert_eq!(
+            strided.reconstruct_enveloped(&envelopes[3..10]),
+            Err(EnvelopeError::WrongScheme(3))
+        );
+        let other_root =
+            PackedSecretSharing::with_roots(4, 3, 26, NaturalPrimeField(433), 354, 289);
+        assert_ne!(other_root.fingerprint(), pss.fingerprint());
+    }
+
+    #[test]
+    fn test_errors() {
+        let pss = &PSS_4_26_3;
+        let mut envelopes = pss.envelope_shares(pss.share(&[5, 6, 7]));
+        assert_eq!(
+            pss.reconstruct_enveloped(&envelopes[3..9]),
+            Err(EnvelopeError::Index(IndexError::TooFewShares(6)))
+        );
+        envelopes[5].index = 4;
+        assert_eq!(
+            pss.reconstruct_enveloped(&envelopes[3..10]),
+            Err(EnvelopeError::Index(IndexError::DuplicateShare(4)))
+        );
+        envelopes[6].version = 2;
+        assert_eq!(
+            pss.reconstruct_enveloped(&envelopes[3..10]),
+            Err(EnvelopeError::UnsupportedVersion(6))
+        );
+    }
+}
@@ -0,0 +1,330 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Shamir secret sharing with shares positioned on powers of a root of unity, so
+//! that they can be generated using the FFT.
+
+use fields::ForwardSecureRng;
+use fields::{Encode, Field, KnownPrime};
+use numtheory::fft::{is_mixed_radix_len, FftPlan};
+use numtheory::PrimeModulus;
+use scheme::ThresholdScheme;
+use share::Share;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Variant of `ShamirSecretSharing` generating shares in time `O(n.log(n))` instead of
+/// `O(threshold.n)` in the number of shares `n`.
+///
+/// The secret is still the value of the sharing polynomial at zero, but the share
+/// with index `i` is its value at `omega_shares^(i + 1)` instead of `i + 1`, where
+/// `omega_shares` is a `(share_count + 1)`-th principal root of unity and
+/// `share_count + 1` must be of the form `2^a.3^b`. Shares of the two variants can
+/// therefore not be mixed.
+///
+/// The parameters are only exposed through getters, since the cached FFT plan
+/// depends on them.
+pub struct FftShamirSecretSharing<F: Field> {
+    threshold: usize,
+    share_count: usize,
+    field: F,
+    omega_shares: F::E,
+    plan: OnceLock<FftPlan<F>>,
+}
+
+impl<F> FftShamirSecretSharing<F>
+where
+    F: Field,
+    F: Encode<u32>,
+    F: Clone,
+    F::E: Clone,
+{
+    /// Scheme with the given parameters, with `omega_shares` a `(share_count + 1)`-th
+    /// principal root of unity.
+    pub fn new(
+        threshold: usize,
+        share_count: usize,
+        field: F,
+        omega_shares: F::E,
+    ) -> FftShamirSecretSharing<F> {
+        assert!(share_count > threshold);
+        assert!(
+            is_mixed_radix_len(share_count + 1),
+            "share_count + 1 must be of the form 2^a.3^b"
+        );
+        FftShamirSecretSharing {
+            threshold,
+            share_count,
+            field,
+            omega_shares,
+            plan: OnceLock::new(),
+        }
+    }
+
+    /// Scheme with the given parameters and a root of unity derived using
+    /// `numtheory::find_root_of_unity`, or `None` if the parameters are invalid or
+    /// `share_count + 1` does not divide `prime - 1`.
+    pub fn derive(
+        threshold: usize,
+        share_count: usize,
+        field: F,
+    ) -> Option<FftShamirSecretSharing<F>>
+    where
+        F: KnownPrime,
+        F::P: PrimeModulus,
+    {
+        if share_count <= threshold || !is_mixed_radix_len(share_count + 1) {
+            return None;
+        }
+        let omega_shares = ::numtheory::find_root_of_unity(&field, share_count + 1)?;
+        Some(FftShamirSecretSharing::new(
+            threshold,
+            share_count,
+            field,
+            omega_shares,
+        ))
+    }
+
+    /// Maximum number of shares that can be known without exposing the secret.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Number of shares to split the secret into.
+    pub fn share_count(&self) -> usize {
+        self.share_count
+    }
+
+    /// Finite field in which computation takes place.
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    /// `(share_count + 1)`-th principal root of unity in the field.
+    pub fn omega_shares(&self) -> &F::E {
+        &self.omega_shares
+    }
+
+    /// Minimum number of shares required to reconstruct secret.
+    ///
+    /// For this scheme this is always `threshold + 1`.
+    pub fn reconstruct_limit(&self) -> usize {
+        self.threshold + 1
+    }
+
+    /// Points at which the shares live, ie. the powers `omega_shares^1` up to
+    /// `omega_shares^share_count`.
+    pub fn share_positions(&self) -> Vec<F::E> {
+        let mut point = self.field.one();
+        (0..self.share_count)
+            .map(|_| {
+                point = self.field.mul(&point, &self.omega_shares);
+                point.clone()
+            })
+            .collect()
+    }
+
+    /// Generate `share_count` shares from `secret`.
+    pub fn share(&self, secret: F::E) -> Vec<F::E> {
+        let mut rng = ForwardSecureRng::new();
+        let mut coefficients = vec![secret];
+        coefficients.extend(self.field.sample_with_replacement(self.threshold, &mut rng));
+        self.evaluate_polynomial(coefficients)
+    }
+
+    /// Reconstruct `secret` from a large enough subset of the shares.
+    ///
+    /// `indices` are the ranks of the known shares as output by the `share` method,
+    /// while `values` are the actual values of these shares.
+    /// Both must have the same number of elements, and at least `reconstruct_limit`.
+    ///
+    /// When all `share_count` shares are given an inverse FFT is used instead of
+    /// Lagrange interpolation.
+    pub fn reconstruct(&self, indices: &[usize], shares: &[F::E]) -> F::E {
+        assert!(shares.len() == indices.len());
+        assert!(shares.len() >= self.reconstruct_limit());
+        if indices.len() == self.share_count {
+            return self.reconstruct_all(indices, shares);
+        }
+        let points: Vec<F::E> = indices
+            .iter()
+            .map(|&i| self.field.pow(&self.omega_shares, i as u32 + 1))
+            .collect();
+        ::numtheory::lagrange_interpolation_at_zero(&points, shares, &self.field)
+    }
+
+    /// Reconstruct `secret` from all shares using an inverse FFT.
+    ///
+    /// The value at `omega_shares^0` is not a share, but since the sharing polynomial
+    /// has degree less than `share_count` its coefficient of that degree is zero, ie.
+    /// the sum of its values at `omega_shares^j` times `omega_shares^j` vanishes.
+    fn reconstruct_all(&self, indices: &[usize], shares: &[F::E]) -> F::E {
+        let field = &self.field;
+        let mut values = vec![field.zero(); self.share_count + 1];
+        for (&i, share) in indices.iter().zip(shares) {
+            values[i + 1] = share.clone();
+        }
+        values[0] = self
+            .share_positions()
+            .iter()
+            .zip(&values[1..])
+            .fold(field.zero(), |acc, (point, value)| {
+                field.sub(acc, field.mul(point, value))
+            });
+        self.plan().inverse(&mut values);
+        values.swap_remove(0)
+    }
+
+    /// Values of the polynomial with the given `coefficients` at the share positions.
+    fn evaluate_polynomial(&self, mut coefficients: Vec<F::E>) -> Vec<F::E> {
+        let nonzero = coefficients.len();
+        coefficients.resize(self.share_count + 1, self.field.zero());
+        self.plan().forward_pruned(&mut coefficients, nonzero);
+        // the value at `omega_shares^0` is not a share
+        coefficients.remove(0);
+        coefficients
+    }
+
+    /// Plan for transforms of length `share_count + 1`.
+    fn plan(&self) -> &FftPlan<F> {
+        self.plan
+            .get_or_init(|| FftPlan::new(&self.field, self.share_count + 1, &self.omega_shares))
+    }
+}
+
+impl<F> ThresholdScheme for FftShamirSecretSharing<F>
+where
+    F: Field,
+    F: Encode<u32>,
+    F: Clone,
+    F::E: Clone,
+{
+    type Field = F;
+    type Secret = F::E;
+
+    fn share_count(&self) -> usize {
+        self.share_count
+    }
+
+    fn reconstruct_limit(&self) -> usize {
+        self.threshold + 1
+    }
+
+    fn share(&self, secret: &F::E) -> Vec<Share<F>> {
+        Share::enumerate(self.share(secret.clone()))
+    }
+
+    fn reconstruct(&self, shares: &[Share<F>]) -> F::E {
+        let (indices, values) = Share::unzip(shares);
+        self.reconstruct(&indices, &values)
+    }
+}
+
+/// Cloning gives an empty plan, to be rebuilt on first use.
+impl<F> Clone for FftShamirSecretSharing<F>
+where
+    F: Field + Clone,
+    F::E: Clone,
+{
+    fn clone(&self) -> FftShamirSecretSharing<F> {
+        FftShamirSecretSharing {
+            threshold: self.threshold,
+            share_count: self.share_count,
+            field: self.field.clone(),
+            omega_shares: self.omega_shares.clone(),
+            plan: OnceLock::new(),
+        }
+    }
+}
+
+impl<F> fmt::Debug for FftShamirSecretSharing<F>
+where
+    F: Field + fmt::Debug,
+    F::E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FftShamirSecretSharing")
+            .field("threshold", &self.threshold)
+            .field("share_count", &self.share_count)
+            .field("field", &self.field)
+            .field("omega_shares", &self.omega_shares)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{MontgomeryField32, NaturalPrimeField, New};
+
+    #[test]
+    fn test_share_reconstruct() {
+        let tss = FftShamirSecretSharing::new(2, 8, NaturalPrimeField(433), 150);
+        let shares = tss.share(17);
+        assert_eq!(shares.len(), 8);
+        assert_eq!(tss.reconstruct(&[0, 1, 2], &shares[0..3]), 17);
+        assert_eq!(
+            tss.reconstruct(&[7, 3, 5], &[shares[7], shares[3], shares[5]]),
+            17
+        );
+
+        // shares are the values of a polynomial of degree threshold at the positions
+        let points = tss.share_positions();
+        assert_eq!(points[..2], [150, 417]);
+        for i in 3..8 {
+            let value = ::numtheory::lagrange_interpolation_at_point(
+                &points[i],
+                &points[0..3],
+                &shares[0..3],
+                &tss.field,
+            );
+            assert_eq!(value, shares[i]);
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_all() {
+        let tss = FftShamirSecretSharing::new(7, 26, NaturalPrimeField(433), 17);
+        let shares = tss.share(123);
+        let mut indices: Vec<usize> = (0..26).rev().collect();
+        indices.swap(3, 20);
+        let values: Vec<i64> = indices.iter().map(|&i| shares[i]).collect();
+        assert_eq!(tss.reconstruct(&indices, &values), 123);
+
+        // interpolation is used when a share is missing
+        let tss = FftShamirSecretSharing::new(6, 8, NaturalPrimeField(433), 150);
+        let shares = tss.share(99);
+        let indices: Vec<usize> = (0..8).collect();
+        assert_eq!(tss.reconstruct(&indices, &shares), 99);
+        assert_eq!(tss.reconstruct(&indices[1..], &shares[1..]), 99);
+    }
+
+    #[test]
+    fn test_derive() {
+        let tss = FftShamirSecretSharing::derive(7, 26, NaturalPrimeField(433)).unwrap();
+        assert_eq!(tss.field.pow(tss.omega_shares, 27), 1);
+        assert_eq!(tss.field.pow(tss.omega_shares, 9), 198);
+        let shares = tss.share(5);
+        let indices: Vec<usize> = (10..18).collect();
+        assert_eq!(tss.reconstruct(&indices, &shares[10..18]), 5);
+        assert!(FftShamirSecretSharing::derive(7, 25, NaturalPrimeField(433)).is_none());
+        // 15 divides 420 but is not of the form 2^a.3^b
+        assert!(FftShamirSecretSharing::derive(2, 14, NaturalPrimeField(421)).is_none());
+        assert!(FftShamirSecretSharing::derive(8, 8, NaturalPrimeField(433)).is_none());
+
+        let field = MontgomeryField32::new(746497);
+        let tss = FftShamirSecretSharing::derive(61, 242, field.clone()).unwrap();
+        let shares = tss.share(field.encode(1234));
+        let indices: Vec<usize> = (100..162).collect();
+        assert!(field.eq(
+            tss.reconstruct(&indices, &shares[100..162]),
+            field.encode(1234)
+        ));
+    }
+}
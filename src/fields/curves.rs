@@ -0,0 +1,139 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Scalar fields of popular elliptic curves, as ready-made `U256Field` instances.
+
+use fields::{Encode, New, U256Field};
+
+/// Order of the secp256k1 group (Bitcoin, Ethereum).
+pub const SECP256K1_ORDER: &str =
+    "115792089237316195423570985008687907852837564279074904382605163141518161494337";
+
+/// Order of the NIST P-256 (secp256r1) group.
+pub const P256_ORDER: &str =
+    "115792089210356248762697446949407573529996955224135760342422259061068512044369";
+
+/// Order of the prime-order subgroup of Curve25519 (ed25519, ristretto255).
+pub const ED25519_ORDER: &str =
+    "7237005577332262213973186563042994240857116359379907606001950938285454250989";
+
+/// Order of the prime-order subgroups of BLS12-381.
+pub const BLS12_381_ORDER: &str =
+    "52435875175126190479447740508185965837690552500527637822603658699938581184513";
+
+/// Elliptic curves for which scalar field presets are provided.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    Secp256k1,
+    P256,
+    Ed25519,
+    Bls12_381,
+}
+
+impl Curve {
+    /// Order of the (prime-order) group of the curve, in decimal.
+    pub fn order(&self) -> &'static str {
+        match *self {
+            Curve::Secp256k1 => SECP256K1_ORDER,
+            Curve::P256 => P256_ORDER,
+            Curve::Ed25519 => ED25519_ORDER,
+            Curve::Bls12_381 => BLS12_381_ORDER,
+        }
+    }
+
+    /// Field in which scalars of the curve live.
+    pub fn scalar_field(&self) -> U256Field {
+        U256Field::new(self.order())
+    }
+
+    /// Length in bytes of the canonical scalar encoding.
+    pub fn scalar_len(&self) -> usize {
+        32
+    }
+
+    /// Whether the canonical scalar encoding is little-endian.
+    ///
+    /// SEC1 curves (secp256k1 and P-256) use big-endian scalars, while ed25519
+    /// and BLS12-381 use little-endian ones.
+    pub fn is_little_endian(&self) -> bool {
+        match *self {
+            Curve::Secp256k1 | Curve::P256 => false,
+            Curve::Ed25519 | Curve::Bls12_381 => true,
+        }
+    }
+
+    /// Turn a scalar in its canonical byte encoding into a field element.
+    ///
+    /// Values larger than the group order are reduced; panics on more than 32 bytes.
+    pub fn encode_scalar(&self, field: &U256Field, bytes: &[u8]) -> [u64; 4] {
+        let mut limbs = [0_u64; 4];
+        let len = bytes.len();
+        for (i, byte) in bytes.iter().enumerate() {
+            let position = if self.is_little_endian() {
+                i
+            } else {
+                len - 1 - i
+            };
+            assert!(position < 32, "scalars have at most 32 bytes");
+            limbs[position / 8] |= u64::from(*byte) << (8 * (position % 8));
+        }
+        field.encode(limbs)
+    }
+
+    /// Turn a field element into the canonical byte encoding of scalars.
+    pub fn decode_scalar(&self, element: &[u64; 4]) -> Vec<u8> {
+        let mut bytes: Vec<u8> = (0..self.scalar_len())
+            .rev()
+            .map(|i| (element[i / 8] >> (8 * (i % 8))) as u8)
+            .collect();
+        if self.is_little_endian() {
+            bytes.reverse();
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Decode, Field, KnownPrime};
+
+    static CURVES: [Curve; 4] = [
+        Curve::Secp256k1,
+        Curve::P256,
+        Curve::Ed25519,
+        Curve::Bls12_381,
+    ];
+
+    #[test]
+    fn test_order_is_zero() {
+        for curve in CURVES.iter() {
+            let field = curve.scalar_field();
+            assert!(Field::eq(&field, field.encode(field.prime()), field.zero()));
+        }
+    }
+
+    #[test]
+    fn test_scalar_endianness() {
+        for curve in CURVES.iter() {
+            let field = curve.scalar_field();
+            let mut bytes = vec![0_u8; 32];
+            if curve.is_little_endian() {
+                bytes[0] = 7;
+                bytes[1] = 1;
+            } else {
+                bytes[31] = 7;
+                bytes[30] = 1;
+            }
+            let x = curve.encode_scalar(&field, &bytes);
+            assert_eq!(Decode::<u32>::decode(&field, x), 263);
+            assert_eq!(curve.decode_scalar(&x), bytes);
+        }
+    }
+}
@@ -7,12 +7,46 @@ extern crate framp as ramp;
 use rand;
 use std::borrow::Borrow;
 
-use fields::{Decode, Encode, Field, New, PrimeField};
+use fields::sample_below;
+use fields::{
+    Decode, ElementBytes, Encode, Exponent, Field, IntegerCoding, KnownPrime, New, OpCosts,
+    PrimeField,
+};
 use numtheory::generic_mod_pow;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct LargePrimeField(ramp::Int);
 
+/// Non-negative integers as exponents, allowing any `(p - 1) / n` to be used.
+impl Exponent for ramp::Int {
+    fn bit_length(&self) -> usize {
+        ramp::Int::bit_length(self) as usize
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        ramp::Int::bit(self, i as u32)
+    }
+}
+
+/// Little-endian 32-bit limbs of the non-negative `x`.
+fn to_limbs(x: &ramp::Int) -> Vec<u32> {
+    let radix = ramp::Int::from(1_u64 << 32);
+    let mut x = x.clone();
+    let mut limbs = vec![];
+    while x > 0 {
+        limbs.push(u32::from(&(&x % &radix)));
+        x = x >> 32;
+    }
+    limbs
+}
+
+/// Integer with the little-endian 32-bit `limbs`.
+fn from_limbs(limbs: &[u32]) -> ramp::Int {
+    limbs.iter().rev().fold(ramp::Int::zero(), |x, &limb| {
+        (x << 32) + ramp::Int::from(limb)
+    })
+}
+
 impl Field for LargePrimeField {
     /// Invariant is that numbers are stored in canonical form [0..prime).
     type E = ramp::Int;
@@ -42,7 +76,7 @@ impl Field for LargePrimeField {
         (a.borrow() * b.borrow()) % &self.0
     }
 
-    fn pow<A: Borrow<Self::E>>(&self, a: A, e: u32) -> Self::E {
+    fn pow<A: Borrow<Self::E>, X: Exponent>(&self, a: A, e: X) -> Self::E {
         generic_mod_pow(self, a.borrow().clone(), e)
     }
 
@@ -75,13 +109,39 @@ impl Field for LargePrimeField {
         }
     }
 
+    fn op_costs(&self) -> OpCosts {
+        // every operation allocates, multiplication and reduction are quadratic in
+        // the number of words, and the extended Euclidean algorithm performs a
+        // number of steps linear in the number of bits
+        let bits = self.0.bit_length() as u64;
+        let words = bits.div_ceil(64);
+        let add = 40_000 + 2000 * words;
+        OpCosts {
+            add,
+            mul: 60_000 + 3000 * words * words,
+            inv: 4 * bits * add,
+        }
+    }
+
     fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool {
         lhs.borrow() == rhs.borrow()
     }
 
-    fn sample_with_replacement<R: rand::Rng>(&self, count: usize, rng: &mut R) -> Vec<Self::E> {
-        use self::ramp::RandomInt;
-        (0..count).map(|_| rng.gen_uint_below(&self.0)).collect()
+    fn normalize<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        let c = a.borrow() % &self.0;
+        if c >= 0 {
+            c
+        } else {
+            c + &self.0
+        }
+    }
+
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        from_limbs(&sample_below(&to_limbs(&self.0), rng))
+    }
+
+    fn sample_nonzero<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        from_limbs(&sample_below(&to_limbs(&(&self.0 - 1)), rng)) + 1
     }
 }
 
@@ -89,6 +149,12 @@ impl PrimeField for LargePrimeField {
     type P = ramp::Int;
 }
 
+impl KnownPrime for LargePrimeField {
+    fn prime(&self) -> ramp::Int {
+        self.0.clone()
+    }
+}
+
 impl New<ramp::Int> for LargePrimeField {
     fn new(prime: ramp::Int) -> Self {
         LargePrimeField(prime)
@@ -200,12 +266,76 @@ impl Encode<u64> for LargePrimeField {
 //     }
 // }
 
-impl<U> Decode<U> for LargePrimeField
-where
-    for<'a> U: From<&'a ramp::Int>,
-{
-    fn decode<E: Borrow<Self::E>>(&self, x: E) -> U {
-        U::from(x.borrow())
+macro_rules! impl_decode {
+    ($($t:ty),*) => {
+        $(
+            impl Decode<$t> for LargePrimeField {
+                fn decode<E: Borrow<Self::E>>(&self, x: E) -> $t {
+                    <$t>::from(&self.normalize(x))
+                }
+            }
+        )*
+    };
+}
+
+impl_decode!(usize, u8, u16, u32, u64);
+
+impl Decode<ramp::Int> for LargePrimeField {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> ramp::Int {
+        self.normalize(x)
+    }
+}
+
+impl IntegerCoding for LargePrimeField {
+    fn encode_signed(&self, x: i64) -> Self::E {
+        self.encode(ramp::Int::from(x))
+    }
+
+    fn decode_signed<E: Borrow<Self::E>>(&self, x: E) -> i64 {
+        let x = self.normalize(x);
+        if x > (&self.0 >> 1) {
+            -i64::from(&(&self.0 - &x))
+        } else {
+            i64::from(&x)
+        }
+    }
+
+    fn decode_u128<E: Borrow<Self::E>>(&self, x: E) -> u128 {
+        let word = ramp::Int::from(1) << 64;
+        let x = self.normalize(x);
+        assert!(x.bit_length() <= 128, "element does not fit in a u128");
+        let low = u64::from(&(&x % &word));
+        let high = u64::from(&(&x >> 64));
+        (high as u128) << 64 | low as u128
+    }
+}
+
+impl ElementBytes for LargePrimeField {
+    /// Enough bytes for the prime.
+    fn element_len(&self) -> usize {
+        (self.0.bit_length() as usize).div_ceil(8)
+    }
+
+    fn write_element<E: Borrow<Self::E>>(&self, e: E, bytes: &mut Vec<u8>) {
+        let radix = ramp::Int::from(256_u32);
+        let mut x = self.normalize(e);
+        for _ in 0..self.element_len() {
+            bytes.push(u32::from(&(&x % &radix)) as u8);
+            x = x >> 8;
+        }
+    }
+
+    fn read_element(&self, bytes: &[u8]) -> Option<Self::E> {
+        assert_eq!(bytes.len(), self.element_len());
+        let mut x = ramp::Int::zero();
+        for byte in bytes.iter().rev() {
+            x = (x << 8) + ramp::Int::from(*byte);
+        }
+        if x < self.0 {
+            Some(x)
+        } else {
+            None
+        }
     }
 }
 
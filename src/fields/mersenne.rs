@@ -0,0 +1,291 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Prime field over the Mersenne prime `2^61 - 1`.
+
+use rand;
+use std::borrow::Borrow;
+
+use super::{
+    Decode, ElementBytes, Encode, Exponent, Field, IntegerCoding, KnownPrime, OpCosts, PrimeField,
+};
+use numtheory::generic_mod_pow;
+
+/// Prime field over the Mersenne prime `p = 2^61 - 1`.
+///
+/// Reduction modulo a Mersenne prime only needs shifts, masks and a single
+/// conditional subtraction, making this a fast choice for MPC applications
+/// where secrets fit in 61 bits. All values are kept in canonical form, in
+/// the `0..p` range, and represented by a u64.
+///
+/// Note that `p - 1 = 2 * 3^2 * ...` so the field only has roots of unity of
+/// order 2 and 9 suitable for the radix-2 and radix-3 FFTs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fp61;
+
+impl Fp61 {
+    /// The modulus `2^61 - 1`.
+    pub const MODULUS: u64 = (1 << 61) - 1;
+
+    /// Reduce any value below `2^64` into the `0..p` range.
+    fn reduce(x: u64) -> u64 {
+        let x = (x & Self::MODULUS) + (x >> 61);
+        // x < 2p so a single conditional subtraction suffices
+        x - Self::MODULUS * ((x >= Self::MODULUS) as u64)
+    }
+
+    /// Reduce a product of two canonical values into the `0..p` range.
+    fn reduce_wide(x: u128) -> u64 {
+        let lo = (x as u64) & Self::MODULUS;
+        let hi = (x >> 61) as u64;
+        Self::reduce(lo + hi)
+    }
+
+    fn pow_u64(&self, mut x: u64, mut e: u64) -> u64 {
+        let mut acc = 1;
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = Self::reduce_wide(acc as u128 * x as u128);
+            }
+            x = Self::reduce_wide(x as u128 * x as u128);
+            e >>= 1;
+        }
+        acc
+    }
+}
+
+impl Field for Fp61 {
+    type E = u64;
+
+    fn zero(&self) -> Self::E {
+        0
+    }
+
+    fn one(&self) -> Self::E {
+        1
+    }
+
+    fn add<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        Self::reduce(a.borrow() + b.borrow())
+    }
+
+    fn sub<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        Self::reduce(a.borrow() + (Self::MODULUS - b.borrow()))
+    }
+
+    fn mul<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        Self::reduce_wide(*a.borrow() as u128 * *b.borrow() as u128)
+    }
+
+    fn pow<A: Borrow<Self::E>, X: Exponent>(&self, a: A, e: X) -> Self::E {
+        generic_mod_pow(self, *a.borrow(), e)
+    }
+
+    fn inv<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        // Fermat's little theorem
+        self.pow_u64(*a.borrow(), Self::MODULUS - 2)
+    }
+
+    fn op_costs(&self) -> OpCosts {
+        OpCosts {
+            add: 700,
+            mul: 3000,
+            inv: 470000,
+        }
+    }
+
+    fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool {
+        lhs.borrow() == rhs.borrow()
+    }
+
+    fn normalize<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        Self::reduce(*a.borrow())
+    }
+
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        rng.gen_range(0, Self::MODULUS)
+    }
+
+    fn sample_nonzero<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        rng.gen_range(1, Self::MODULUS)
+    }
+}
+
+impl PrimeField for Fp61 {
+    type P = u64;
+}
+
+impl KnownPrime for Fp61 {
+    fn prime(&self) -> u64 {
+        Self::MODULUS
+    }
+}
+
+impl Encode<u32> for Fp61 {
+    fn encode(&self, x: u32) -> Self::E {
+        x as u64
+    }
+}
+
+impl Encode<u64> for Fp61 {
+    fn encode(&self, x: u64) -> Self::E {
+        Self::reduce(x)
+    }
+}
+
+impl Decode<u32> for Fp61 {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> u32 {
+        ::std::convert::TryFrom::try_from(*x.borrow()).expect("element does not fit in a u32")
+    }
+}
+
+impl Decode<u64> for Fp61 {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> u64 {
+        *x.borrow()
+    }
+}
+
+impl IntegerCoding for Fp61 {
+    fn encode_signed(&self, x: i64) -> Self::E {
+        x.rem_euclid(Self::MODULUS as i64) as u64
+    }
+
+    fn decode_signed<E: Borrow<Self::E>>(&self, x: E) -> i64 {
+        let x = *x.borrow();
+        if x > Self::MODULUS / 2 {
+            x as i64 - Self::MODULUS as i64
+        } else {
+            x as i64
+        }
+    }
+
+    fn decode_u128<E: Borrow<Self::E>>(&self, x: E) -> u128 {
+        *x.borrow() as u128
+    }
+}
+
+impl ElementBytes for Fp61 {
+    fn element_len(&self) -> usize {
+        8
+    }
+
+    fn write_element<E: Borrow<Self::E>>(&self, e: E, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.normalize(e).to_le_bytes());
+    }
+
+    fn read_element(&self, bytes: &[u8]) -> Option<Self::E> {
+        assert_eq!(bytes.len(), 8);
+        let mut word = [0; 8];
+        word.copy_from_slice(bytes);
+        let x = u64::from_le_bytes(word);
+        if x < Self::MODULUS {
+            Some(x)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{SliceDecode, SliceEncode};
+    use numtheory::fft;
+    use ShamirSecretSharing;
+
+    const P: u64 = Fp61::MODULUS;
+
+    #[test]
+    fn test_reduction() {
+        let field = Fp61;
+        assert_eq!(field.encode(P), 0);
+        assert_eq!(field.encode(P + 5), 5);
+        assert_eq!(field.encode(u64::MAX), 7);
+        assert_eq!(field.add(P - 1, 1_u64), 0);
+        assert_eq!(field.add(P - 1, P - 1), P - 2);
+        assert_eq!(field.sub(3_u64, 5_u64), P - 2);
+        assert_eq!(field.mul(P - 1, P - 1), 1);
+        assert_eq!(field.mul(1_u64 << 60, 2_u64), 1);
+    }
+
+    #[test]
+    fn test_signed() {
+        let field = Fp61;
+        assert_eq!(field.encode_signed(-1), P - 1);
+        assert_eq!(
+            field.encode_signed(i64::MIN),
+            field.encode(P - (1 << 63) % P)
+        );
+        assert_eq!(field.decode_signed(P - 1), -1);
+        assert_eq!(field.decode_signed(P / 2), (P / 2) as i64);
+        assert_eq!(field.decode_signed(P / 2 + 1), -((P / 2) as i64));
+    }
+
+    #[test]
+    fn test_inv() {
+        let field = Fp61;
+        for &x in &[1, 2, 3, 12345, P - 1, P - 2] {
+            assert_eq!(field.mul(x, field.inv(x)), 1);
+        }
+    }
+
+    #[test]
+    fn test_field_laws() {
+        use rand::{ChaChaRng, SeedableRng};
+        let mut rng = ChaChaRng::from_seed(&[61]);
+        ::test_utils::check_field_laws(&Fp61, 50, &mut rng);
+        ::test_utils::check_encode_decode(&Fp61, &[0, 1, P - 1]);
+    }
+
+    #[test]
+    fn test_element_bytes() {
+        let field = Fp61;
+        let mut bytes = vec![];
+        field.write_element(P - 1, &mut bytes);
+        assert_eq!(bytes, [0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x1f]);
+        assert_eq!(field.read_element(&bytes), Some(P - 1));
+        assert_eq!(field.read_element(&P.to_le_bytes()), None);
+    }
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 3,
+            share_count: 8,
+            field: Fp61,
+        };
+        let secret = P - 42;
+        let shares = tss.share(secret);
+        let subset: Vec<u64> = [0, 2, 4, 6].iter().map(|&i| shares[i]).collect();
+        assert_eq!(tss.reconstruct(&[0, 2, 4, 6], &subset), secret);
+        assert_eq!(tss.reconstruct(&[1, 2, 3, 4, 5], &shares[1..6]), secret);
+    }
+
+    #[test]
+    fn test_fft() {
+        let field = Fp61;
+
+        let omega = P - 1; // 2nd root of unity
+        let mut data = field.encode_slice([7_u32, 5]);
+        fft::fft2(&field, &mut data, &omega);
+        assert_eq!(data, [12, 2]);
+        fft::fft2_inverse(&field, &mut data, &omega);
+        let decoded: Vec<u32> = field.decode_slice(data);
+        assert_eq!(decoded, [7, 5]);
+
+        let omega = 1102844585000305877; // 9th root of unity
+        assert_eq!(field.pow(omega, 9), 1);
+        assert!(field.pow(omega, 3) != 1);
+        let values: Vec<u64> = (1..10).map(|x| P - x).collect();
+        let mut data = values.clone();
+        fft::fft3(&field, &mut data, &omega);
+        fft::fft3_inverse(&field, &mut data, &omega);
+        assert_eq!(data, values);
+    }
+}
@@ -26,23 +26,275 @@ pub trait Field {
 
     fn mul<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E;
 
-    fn pow<A: Borrow<Self::E>>(&self, a: A, e: u32) -> Self::E;
+    /// `a` to the power of `e`, which may be a `u32` or a `BigExponent`.
+    fn pow<A: Borrow<Self::E>, X: Exponent>(&self, a: A, e: X) -> Self::E;
 
     fn inv<A: Borrow<Self::E>>(&self, a: A) -> Self::E;
 
     fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool;
 
+    /// Canonical representative of `a`.
+    ///
+    /// All operations already return canonical representatives, corresponding to values
+    /// in `[0, p)`, so this is only needed for elements constructed by other means, such
+    /// as negative values for `NaturalPrimeField`.
+    fn normalize<A: Borrow<Self::E>>(&self, a: A) -> Self::E;
+
     fn neq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool {
         !self.eq(lhs, rhs)
     }
 
-    fn sample_with_replacement<R: rand::Rng>(&self, count: usize, rng: &mut R) -> Vec<Self::E>;
+    /// Element of this field representing the element `e` of `other`, which must
+    /// have the same modulus but may use another backend.
+    ///
+    /// This goes through the byte representations of the elements, which are in
+    /// standard form for all backends. Panics if `e` is not below the modulus of
+    /// this field.
+    fn convert_from<G, E>(&self, other: &G, e: E) -> Self::E
+    where
+        Self: ElementBytes + Sized,
+        G: ElementBytes,
+        E: Borrow<G::E>,
+    {
+        let mut bytes = vec![];
+        other.write_element(e, &mut bytes);
+        let len = self.element_len();
+        assert!(
+            bytes.iter().skip(len).all(|&byte| byte == 0),
+            "element is out of range"
+        );
+        bytes.resize(len, 0);
+        self.read_element(&bytes).expect("element is out of range")
+    }
+
+    /// Uniformly random element.
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E;
+
+    /// Uniformly random non-zero element.
+    ///
+    /// The default implementation samples until a non-zero element is found.
+    fn sample_nonzero<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        loop {
+            let x = self.sample(rng);
+            if self.neq(&x, self.zero()) {
+                return x;
+            }
+        }
+    }
+
+    /// `count` independent uniformly random elements.
+    fn sample_with_replacement<R: rand::Rng>(&self, count: usize, rng: &mut R) -> Vec<Self::E> {
+        (0..count).map(|_| self.sample(rng)).collect()
+    }
+
+    /// `count` independent uniformly random non-zero elements.
+    fn sample_nonzero_with_replacement<R: rand::Rng>(
+        &self,
+        count: usize,
+        rng: &mut R,
+    ) -> Vec<Self::E> {
+        (0..count).map(|_| self.sample_nonzero(rng)).collect()
+    }
+
+    /// Pointwise addition, `a[i] = a[i] + b[i]`.
+    fn add_assign_slice(&self, a: &mut [Self::E], b: &[Self::E]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter_mut().zip(b) {
+            *x = self.add(&*x, y);
+        }
+    }
+
+    /// Scaling by a constant, `a[i] = a[i] * s`.
+    fn mul_scalar_slice(&self, a: &mut [Self::E], s: &Self::E) {
+        for x in a.iter_mut() {
+            *x = self.mul(&*x, s);
+        }
+    }
+
+    /// Pointwise multiplication, `a[i] = a[i] * b[i]`.
+    fn mul_pairwise_slice(&self, a: &mut [Self::E], b: &[Self::E]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter_mut().zip(b) {
+            *x = self.mul(&*x, y);
+        }
+    }
+
+    /// Pointwise inversion, `a[i] = 1 / a[i]`.
+    ///
+    /// Panics if any element is zero. The default implementation performs a
+    /// single inversion, see `numtheory::batch_inverse`.
+    fn batch_inv(&self, a: &mut [Self::E]) {
+        let inverses = ::numtheory::batch_inverse(a, self);
+        for (x, inverse) in a.iter_mut().zip(inverses) {
+            *x = inverse;
+        }
+    }
+
+    /// Approximate cost of the operations of this backend, used by the schemes to
+    /// choose between algorithms.
+    ///
+    /// The default is typical of backends on machine words; the backends of this
+    /// crate override it with their own measurements.
+    fn op_costs(&self) -> OpCosts {
+        OpCosts {
+            add: 2000,
+            mul: 4000,
+            inv: 200000,
+        }
+    }
+
+    /// Radix-2 FFT butterflies, applied pointwise to `lo` and `hi`.
+    ///
+    /// Each pair `(x, y)` is replaced by `(x + t.y, x - t.y)` where `t` is the
+    /// corresponding element of `twiddles`. Backends with vectorised arithmetic
+    /// may override this.
+    fn fft2_butterflies(&self, lo: &mut [Self::E], hi: &mut [Self::E], twiddles: &[Self::E])
+    where
+        Self::E: Clone,
+    {
+        for ((x, y), t) in lo.iter_mut().zip(hi.iter_mut()).zip(twiddles) {
+            let ty = self.mul(&*y, t);
+            *y = self.sub(&*x, &ty);
+            *x = self.add(&*x, &ty);
+        }
+    }
+
+    /// Radix-3 FFT butterflies, applied pointwise to `a`, `b`, and `c`.
+    ///
+    /// With `y = t.b` and `z = t^2.c`, each triple `(x, b, c)` is replaced by
+    /// `(x + y + z, x + w.y + w^2.z, x + w^2.y + w.z)` where `t` and `t^2` are
+    /// the corresponding elements of `twiddles` and `twiddles_sq`, and `w` is a
+    /// principal third root of unity. Backends with vectorised arithmetic may
+    /// override this.
+    #[allow(clippy::too_many_arguments)]
+    fn fft3_butterflies(
+        &self,
+        a: &mut [Self::E],
+        b: &mut [Self::E],
+        c: &mut [Self::E],
+        twiddles: &[Self::E],
+        twiddles_sq: &[Self::E],
+        omega: &Self::E,
+        omega_sq: &Self::E,
+    ) where
+        Self::E: Clone,
+    {
+        for i in 0..a.len() {
+            let x = a[i].clone();
+            let y = self.mul(&b[i], &twiddles[i]);
+            let z = self.mul(&c[i], &twiddles_sq[i]);
+            a[i] = self.add(self.add(&x, &y), &z);
+            b[i] = self.add(self.add(&x, self.mul(omega, &y)), self.mul(omega_sq, &z));
+            c[i] = self.add(self.add(&x, self.mul(omega_sq, &y)), self.mul(omega, &z));
+        }
+    }
+}
+
+/// Approximate cost of the field operations of a backend, in picoseconds.
+///
+/// Costs are measured over many independent operations, as found in the inner loops
+/// of interpolation algorithms, rather than over chains of dependent ones. Only
+/// the ratios between the costs matter, as they are used to compare algorithms
+/// performing the same computation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpCosts {
+    /// Cost of `add` and `sub`.
+    pub add: u64,
+    /// Cost of `mul`.
+    pub mul: u64,
+    /// Cost of `inv`.
+    pub inv: u64,
+}
+
+/// Non-negative exponents accepted by `Field::pow`.
+///
+/// Only `u32` is implemented among the primitive integers, so that literal exponents
+/// need no annotation; larger ones are given as `BigExponent`.
+pub trait Exponent {
+    /// Number of bits up to and including the most significant one set.
+    fn bit_length(&self) -> usize;
+
+    /// Whether bit `i` is set, counting from the least significant.
+    fn bit(&self, i: usize) -> bool;
+}
+
+impl Exponent for u32 {
+    fn bit_length(&self) -> usize {
+        (32 - self.leading_zeros()) as usize
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        (self >> i) & 1 == 1
+    }
+}
+
+impl<X: Exponent + ?Sized> Exponent for &X {
+    fn bit_length(&self) -> usize {
+        (**self).bit_length()
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        (**self).bit(i)
+    }
+}
+
+/// Exponent of arbitrary size, such as `(p - 1) / n` for large primes `p`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigExponent {
+    /// Little-endian bits, without trailing zeros.
+    bits: Vec<bool>,
+}
+
+impl BigExponent {
+    /// Exponent with the given little-endian `bits`.
+    pub fn from_bits(mut bits: Vec<bool>) -> BigExponent {
+        while bits.last() == Some(&false) {
+            bits.pop();
+        }
+        BigExponent { bits }
+    }
+
+    /// Little-endian bits of the exponent, up to the most significant one set.
+    pub fn bits(&self) -> &[bool] {
+        &self.bits
+    }
+}
+
+impl From<u64> for BigExponent {
+    fn from(x: u64) -> BigExponent {
+        BigExponent::from_bits((0..64).map(|i| (x >> i) & 1 == 1).collect())
+    }
+}
+
+impl From<u32> for BigExponent {
+    fn from(x: u32) -> BigExponent {
+        BigExponent::from(x as u64)
+    }
+}
+
+impl Exponent for BigExponent {
+    fn bit_length(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        self.bits.get(i).cloned().unwrap_or(false)
+    }
 }
 
 pub trait PrimeField: Field {
     type P;
 }
 
+/// Prime field able to report its prime.
+///
+/// This is separate from `PrimeField` so that existing implementations of the
+/// latter keep compiling.
+pub trait KnownPrime: PrimeField {
+    /// The prime defining the field.
+    fn prime(&self) -> Self::P;
+}
+
 pub trait New<T>
 where
     Self: Field,
@@ -57,6 +309,8 @@ where
     fn encode(&self, x: T) -> Self::E;
 }
 
+/// Conversion of field elements to values, taking the canonical representative in
+/// `0..p` and panicking on elements that do not fit.
 pub trait Decode<U>
 where
     Self: Field,
@@ -64,6 +318,46 @@ where
     fn decode<E: Borrow<Self::E>>(&self, e: E) -> U;
 }
 
+/// Conversions between elements and integers offered by every backend, for code
+/// generic over the field.
+///
+/// These are methods rather than more `Encode` and `Decode` impls, since a second
+/// impl for an integer type leaves the type of literals passed to `encode`, and of
+/// values returned by `decode`, to be annotated at every call.
+pub trait IntegerCoding: Field {
+    /// Element congruent to `x`, so that negative values map to `p - |x|`.
+    fn encode_signed(&self, x: i64) -> Self::E;
+
+    /// Representative of `e` centered around zero, panicking if it does not fit.
+    fn decode_signed<E: Borrow<Self::E>>(&self, e: E) -> i64;
+
+    /// Canonical representative of `e` in `0..p`, panicking if it does not fit.
+    fn decode_u128<E: Borrow<Self::E>>(&self, e: E) -> u128;
+
+    /// Canonical representative of `e` in `0..p`, panicking if it does not fit.
+    fn decode_u64<E: Borrow<Self::E>>(&self, e: E) -> u64 {
+        ::std::convert::TryFrom::try_from(self.decode_u128(e))
+            .expect("element does not fit in a u64")
+    }
+}
+
+/// Fixed-length little-endian byte representation of field elements, for sending
+/// shares over the wire.
+pub trait ElementBytes
+where
+    Self: Field,
+{
+    /// Number of bytes used for every element.
+    fn element_len(&self) -> usize;
+
+    /// Append the representation of `e` to `bytes`.
+    fn write_element<E: Borrow<Self::E>>(&self, e: E, bytes: &mut Vec<u8>);
+
+    /// Element represented by `bytes`, which must be of length `element_len`, or
+    /// `None` if it does not represent an element.
+    fn read_element(&self, bytes: &[u8]) -> Option<Self::E>;
+}
+
 /// Helper trait for encoding values to field elements.
 pub trait SliceEncode<T>
 where
@@ -93,7 +387,6 @@ where
 impl<F, U> SliceDecode<U> for F
 where
     F: Field + Decode<U>,
-    F::E: Clone,
 {
     fn decode_slice<E: AsRef<[F::E]>>(&self, elements: E) -> Vec<U> {
         elements.as_ref().iter().map(|e| self.decode(e)).collect()
@@ -124,6 +417,31 @@ macro_rules! all_fields_test {
             ::fields::test::test_pow::<$field>();
         }
         #[test]
+        fn test_slice_ops() {
+            ::fields::test::test_slice_ops::<$field>();
+        }
+        #[test]
+        fn test_sample() {
+            ::fields::test::test_sample::<$field>();
+        }
+        #[test]
+        fn test_signed() {
+            ::fields::test::test_signed::<$field>();
+        }
+        #[test]
+        fn test_decode_wide() {
+            ::fields::test::test_decode_wide::<$field>();
+        }
+        #[test]
+        fn test_field_laws() {
+            ::fields::test::test_field_laws::<$field>();
+        }
+
+        #[test]
+        fn test_element_bytes() {
+            ::fields::test::test_element_bytes::<$field>();
+        }
+        #[test]
         fn test_fft2() {
             ::numtheory::fft::test::test_fft2::<$field>();
         }
@@ -147,6 +465,34 @@ macro_rules! all_fields_test {
         fn test_fft3_big() {
             ::numtheory::fft::test::test_fft3_big::<$field>();
         }
+        #[test]
+        fn test_fft_mixed() {
+            ::numtheory::fft::test::test_fft_mixed::<$field>();
+        }
+        #[test]
+        fn test_fft_mixed_big() {
+            ::numtheory::fft::test::test_fft_mixed_big::<$field>();
+        }
+        #[test]
+        fn test_fft_bluestein() {
+            ::numtheory::fft::test::test_fft_bluestein::<$field>();
+        }
+        #[test]
+        fn test_fft_plan() {
+            ::numtheory::fft::test::test_fft_plan::<$field>();
+        }
+        #[test]
+        fn test_fft_plan_pruned() {
+            ::numtheory::fft::test::test_fft_plan_pruned::<$field>();
+        }
+        #[test]
+        fn test_fft_plan_four_step() {
+            ::numtheory::fft::test::test_fft_plan_four_step::<$field>();
+        }
+        #[test]
+        fn test_fft_negacyclic_plan() {
+            ::numtheory::fft::test::test_fft_negacyclic_plan::<$field>();
+        }
     };
 }
 
@@ -165,6 +511,108 @@ pub mod test {
         }
     }
 
+    pub fn test_element_bytes<F>()
+    where
+        F: Field + PrimeField + New<u32> + Encode<u32> + Decode<u32> + ElementBytes,
+        F::P: From<u32>,
+    {
+        let zp = F::new(17);
+        let len = zp.element_len();
+        let mut bytes = vec![];
+        for i in 0_u32..17 {
+            zp.write_element(zp.encode(i), &mut bytes);
+        }
+        zp.write_element(zp.sub(zp.zero(), zp.one()), &mut bytes);
+        assert_eq!(bytes.len(), 18 * len);
+        assert_eq!(bytes[3 * len], 3);
+        let decoded: Vec<u32> = bytes
+            .chunks(len)
+            .map(|chunk| zp.decode(zp.read_element(chunk).unwrap()))
+            .collect();
+        assert_eq!(decoded[..17], (0..17).collect::<Vec<u32>>()[..]);
+        assert_eq!(decoded[17], 16);
+
+        // values beyond the prime are rejected
+        let mut bytes = vec![0; len];
+        bytes[0] = 17;
+        assert!(zp.read_element(&bytes).is_none());
+    }
+
+    pub fn test_sample<F>()
+    where
+        F: Field + PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+    {
+        use rand::{ChaChaRng, SeedableRng};
+        let zp = F::new(17);
+        let mut rng = ChaChaRng::from_seed(&[17]);
+        // all elements are hit, and only non-zero ones by `sample_nonzero`
+        let mut counts = [0; 17];
+        for _ in 0..1700 {
+            counts[zp.decode(zp.sample(&mut rng)) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&count| count > 50));
+        let mut counts = [0; 17];
+        for x in zp.sample_nonzero_with_replacement(1600, &mut rng) {
+            counts[zp.decode(x) as usize] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        assert!(counts[1..].iter().all(|&count| count > 50));
+        assert_eq!(zp.sample_with_replacement(5, &mut rng).len(), 5);
+    }
+
+    pub fn test_signed<F>()
+    where
+        F: Field + PrimeField + New<u32> + IntegerCoding,
+        F::P: From<u32>,
+    {
+        let zp = F::new(17);
+        for i in -8..9 {
+            assert_eq!(zp.decode_signed(zp.encode_signed(i)), i);
+        }
+        assert_eq!(zp.decode_signed(zp.encode_signed(9)), -8);
+        assert_eq!(zp.decode_signed(zp.encode_signed(-9)), 8);
+        assert_eq!(zp.decode_signed(zp.encode_signed(-35)), -1);
+        let difference = zp.sub(zp.encode_signed(3), zp.encode_signed(5));
+        assert_eq!(zp.decode_signed(difference), -2);
+        let product = zp.mul(zp.encode_signed(-3), zp.encode_signed(2));
+        assert_eq!(zp.decode_signed(product), -6);
+        assert_eq!(
+            zp.decode_signed(zp.encode_signed(i64::MIN)),
+            zp.decode_signed(zp.encode_signed(i64::MIN % 17))
+        );
+    }
+
+    pub fn test_decode_wide<F>()
+    where
+        F: Field + PrimeField + New<u32> + Encode<u32> + Decode<u32> + IntegerCoding,
+    {
+        let zp = F::new(433);
+        let elements = zp.encode_slice([0, 1, 216, 217, 432, 433, 500]);
+        let wide: Vec<u64> = elements.iter().map(|e| zp.decode_u64(e)).collect();
+        assert_eq!(wide, [0, 1, 216, 217, 432, 0, 67]);
+        let wider: Vec<u128> = elements.iter().map(|e| zp.decode_u128(e)).collect();
+        assert_eq!(wider, [0, 1, 216, 217, 432, 0, 67]);
+        assert_eq!(zp.decode_slice(&elements), [0, 1, 216, 217, 432, 0, 67]);
+        let signed: Vec<i64> = elements.iter().map(|e| zp.decode_signed(e)).collect();
+        assert_eq!(signed, [0, 1, 216, -216, -1, 0, 67]);
+        let minus_one = zp.sub(zp.zero(), zp.one());
+        assert_eq!(zp.decode_u128(minus_one), 432);
+    }
+
+    pub fn test_field_laws<F>()
+    where
+        F: Field + KnownPrime + New<u32> + Encode<u32> + Decode<u32> + ElementBytes,
+        F::P: ::numtheory::PrimeModulus,
+        F::E: Clone,
+    {
+        use rand::{ChaChaRng, SeedableRng};
+        let mut rng = ChaChaRng::from_seed(&[433]);
+        let zp = F::new(433);
+        ::test_utils::check_field_laws(&zp, 50, &mut rng);
+        ::test_utils::check_encode_decode(&zp, &[0_u32, 1, 2, 431, 432]);
+    }
+
     pub fn test_add<F>()
     where
         F: Field + PrimeField + New<u32> + Encode<u32> + Decode<u32>,
@@ -191,7 +639,7 @@ pub mod test {
         F::P: From<u32>,
     {
         let zp = F::new(17);
-        assert_eq!(zp.decode(zp.mul(zp.encode(8), zp.encode(2))), (8 * 2) % 17);
+        assert_eq!(zp.decode(zp.mul(zp.encode(8), zp.encode(2))), (8 * 2));
         assert_eq!(zp.decode(zp.mul(zp.encode(8), zp.encode(5))), (8 * 5) % 17);
     }
 
@@ -204,18 +652,81 @@ pub mod test {
         assert_eq!(zp.decode(zp.pow(zp.encode(2), 0)), 1);
         assert_eq!(zp.decode(zp.pow(zp.encode(2), 3)), 8);
         assert_eq!(zp.decode(zp.pow(zp.encode(2), 6)), 13);
+        // exponents beyond `u32`, reduced modulo 16 by Fermat's little theorem
+        let e = BigExponent::from((16_u64 << 40) + 3);
+        assert_eq!(zp.decode(zp.pow(zp.encode(2), &e)), 8);
+        assert_eq!(zp.decode(zp.pow(zp.encode(2), e)), 8);
+    }
+
+    pub fn test_slice_ops<F>()
+    where
+        F: Field + PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        let zp = F::new(17);
+        let b = zp.encode_slice([3, 4, 5, 16]);
+
+        let mut a = zp.encode_slice([1, 2, 13, 9]);
+        zp.add_assign_slice(&mut a, &b);
+        assert_eq!(zp.decode_slice(&a), [4, 6, 1, 8]);
+
+        let mut a = zp.encode_slice([1, 2, 13, 9]);
+        zp.mul_scalar_slice(&mut a, &zp.encode(3));
+        assert_eq!(zp.decode_slice(&a), [3, 6, 5, 10]);
+
+        let mut a = zp.encode_slice([1, 2, 13, 9]);
+        zp.mul_pairwise_slice(&mut a, &b);
+        assert_eq!(zp.decode_slice(&a), [3, 8, 14, 8]);
+
+        let mut a = zp.encode_slice([1, 2, 13, 9]);
+        zp.batch_inv(&mut a);
+        assert_eq!(zp.decode_slice(&a), [1, 9, 4, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "prime does not fit in a u32")]
+    fn test_natural_prime_too_large() {
+        NaturalPrimeField((1 << 32) + 15).prime();
     }
 }
 
+mod rng;
+pub use self::rng::ForwardSecureRng;
+
+mod sampling;
+pub use self::sampling::sample_below;
+
 mod natural;
 pub use self::natural::NaturalPrimeField;
 
 mod montgomery;
+mod montgomery_simd;
 pub use self::montgomery::MontgomeryField32;
 
+mod mersenne;
+pub use self::mersenne::Fp61;
+
+mod u64field;
+pub use self::u64field::U64Field;
+
+mod u256field;
+pub use self::u256field::U256Field;
+
 #[cfg(feature = "largefield")]
 mod large;
 #[cfg(feature = "largefield")]
 pub use self::large::LargePrimeField;
 
-// pub mod native;
+#[cfg(feature = "curves")]
+mod curves;
+#[cfg(feature = "curves")]
+pub use self::curves::*;
+
+#[cfg(feature = "ff")]
+mod scalars;
+#[cfg(feature = "ff")]
+pub use self::scalars::ScalarField;
+
+mod native;
+pub use self::native::NativePrimeField;
@@ -11,12 +11,27 @@
 use rand;
 use std::borrow::Borrow;
 
-use super::{Decode, Encode, Field, New, PrimeField};
+use super::montgomery_simd as simd;
+use super::{
+    Decode, ElementBytes, Encode, Exponent, Field, IntegerCoding, KnownPrime, New, OpCosts,
+    PrimeField,
+};
 
 /// MontgomeryField32 Value (wraps an u32 for type-safety).
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Hash)]
+#[repr(transparent)]
 pub struct Value(u32);
 
+fn raw(values: &[Value]) -> &[u32] {
+    // safe since Value is a transparent wrapper
+    unsafe { ::std::slice::from_raw_parts(values.as_ptr() as *const u32, values.len()) }
+}
+
+fn raw_mut(values: &mut [Value]) -> &mut [u32] {
+    // safe since Value is a transparent wrapper
+    unsafe { ::std::slice::from_raw_parts_mut(values.as_mut_ptr() as *mut u32, values.len()) }
+}
+
 /// Implementation of finite field with Montgomery modular multiplication.
 ///
 /// See https://en.wikipedia.org/wiki/Montgomery_modular_multiplication
@@ -41,12 +56,55 @@ impl MontgomeryField32 {
         let t: u32 = ((a + m * (self.n as u64)) >> 32) as u32;
         Value(if t >= (self.n) { t - (self.n) } else { t })
     }
+
+    fn simd_params(&self) -> simd::Params {
+        simd::Params {
+            n: self.n,
+            n_quote: self.n_quote,
+        }
+    }
+
+    /// Pointwise multiplication, `a[i] = a[i] * b[i]`.
+    ///
+    /// Vectorised when the `simd` feature is enabled and supported by the CPU.
+    pub fn mul_slice(&self, a: &mut [Value], b: &[Value]) {
+        assert_eq!(a.len(), b.len());
+        let done = if simd::available(self.n) {
+            unsafe { simd::mul_slice(&self.simd_params(), raw_mut(a), raw(b)) }
+        } else {
+            0
+        };
+        for (x, y) in a[done..].iter_mut().zip(&b[done..]) {
+            *x = self.mul(*x, y);
+        }
+    }
+
+    /// Pointwise addition, `a[i] = a[i] + b[i]`.
+    ///
+    /// Vectorised when the `simd` feature is enabled and supported by the CPU.
+    pub fn add_slice(&self, a: &mut [Value], b: &[Value]) {
+        assert_eq!(a.len(), b.len());
+        let done = if simd::available(self.n) {
+            unsafe { simd::add_slice(&self.simd_params(), raw_mut(a), raw(b)) }
+        } else {
+            0
+        };
+        for (x, y) in a[done..].iter_mut().zip(&b[done..]) {
+            *x = self.add(*x, y);
+        }
+    }
 }
 
 impl PrimeField for MontgomeryField32 {
     type P = u32;
 }
 
+impl KnownPrime for MontgomeryField32 {
+    fn prime(&self) -> u32 {
+        self.n
+    }
+}
+
 impl New<u32> for MontgomeryField32 {
     fn new(prime: u32) -> Self {
         let r = 1u64 << 32;
@@ -80,22 +138,62 @@ impl Decode<u32> for MontgomeryField32 {
     }
 }
 
+impl IntegerCoding for MontgomeryField32 {
+    fn encode_signed(&self, a: i64) -> Self::E {
+        self.encode(a.rem_euclid(self.n as i64) as u32)
+    }
+
+    fn decode_signed<E: Borrow<Self::E>>(&self, a: E) -> i64 {
+        let a: u32 = self.decode(a);
+        if a > self.n / 2 {
+            a as i64 - self.n as i64
+        } else {
+            a as i64
+        }
+    }
+
+    fn decode_u128<E: Borrow<Self::E>>(&self, a: E) -> u128 {
+        Decode::<u32>::decode(self, a) as u128
+    }
+}
+
+impl ElementBytes for MontgomeryField32 {
+    fn element_len(&self) -> usize {
+        4
+    }
+
+    /// Elements are written in standard rather than Montgomery form.
+    fn write_element<E: Borrow<Self::E>>(&self, e: E, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&Decode::<u32>::decode(self, e).to_le_bytes());
+    }
+
+    fn read_element(&self, bytes: &[u8]) -> Option<Self::E> {
+        assert_eq!(bytes.len(), 4);
+        let x = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        if x < self.n {
+            Some(self.encode(x))
+        } else {
+            None
+        }
+    }
+}
+
 impl Field for MontgomeryField32 {
     type E = Value;
 
     /// Get the Zero value.
     fn zero(&self) -> Self::E {
-        self.encode(0)
+        self.encode(0_u32)
     }
 
     /// Get the One value.
     fn one(&self) -> Self::E {
-        self.encode(1)
+        self.encode(1_u32)
     }
 
     fn add<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
         let sum = a.borrow().0 as u64 + b.borrow().0 as u64;
-        if sum > self.n as u64 {
+        if sum >= self.n as u64 {
             Value((sum - self.n as u64) as u32)
         } else {
             Value(sum as u32)
@@ -103,7 +201,7 @@ impl Field for MontgomeryField32 {
     }
 
     fn sub<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
-        if a.borrow().0 > b.borrow().0 {
+        if a.borrow().0 >= b.borrow().0 {
             Value(a.borrow().0 - b.borrow().0)
         } else {
             Value((a.borrow().0 as u64 + self.n as u64 - b.borrow().0 as u64) as u32)
@@ -119,33 +217,119 @@ impl Field for MontgomeryField32 {
         self.redc((ar_modn_inv as u64).wrapping_mul(self.r_cube as u64))
     }
 
+    fn op_costs(&self) -> OpCosts {
+        // the extended Euclidean algorithm takes a number of steps linear in the
+        // size of the prime
+        let bits = u64::from(32 - self.n.leading_zeros());
+        OpCosts {
+            add: 500,
+            mul: 600,
+            inv: 2000 + 3000 * bits,
+        }
+    }
+
     fn eq<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, lhs: A, rhs: B) -> bool {
         (lhs.borrow().0 % self.n) == (rhs.borrow().0 % self.n) // TODO is this enough?
     }
 
-    fn pow<A: Borrow<Self::E>>(&self, a: A, e: u32) -> Self::E {
+    fn normalize<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        Value(a.borrow().0 % self.n)
+    }
+
+    fn pow<A: Borrow<Self::E>, X: Exponent>(&self, a: A, e: X) -> Self::E {
         // TODO implement more efficient generic GCD
         let mut x = *a.borrow();
-        let mut e = e;
         let mut acc = self.one();
-        while e > 0 {
-            if e % 2 == 0 {
-                // even
-                // no-op
-            } else {
-                // odd
+        for i in 0..e.bit_length() {
+            if e.bit(i) {
+                // bit i is set
                 acc = self.mul(acc, x);
             }
             x = self.mul(x, x); // waste one of these by having it here but code is simpler (tiny bit)
-            e >>= 1;
         }
         acc
     }
 
-    fn sample_with_replacement<R: rand::Rng>(&self, count: usize, rng: &mut R) -> Vec<Self::E> {
-        use rand::distributions::Sample;
-        let mut range = rand::distributions::range::Range::new(0, self.n);
-        (0..count).map(|_| self.encode(range.sample(rng))).collect()
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        self.encode(rng.gen_range(0, self.n))
+    }
+
+    fn sample_nonzero<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        self.encode(rng.gen_range(1, self.n))
+    }
+
+    fn add_assign_slice(&self, a: &mut [Value], b: &[Value]) {
+        self.add_slice(a, b)
+    }
+
+    fn mul_scalar_slice(&self, a: &mut [Value], s: &Value) {
+        let done = if simd::available(self.n) {
+            unsafe { simd::mul_scalar_slice(&self.simd_params(), raw_mut(a), s.0) }
+        } else {
+            0
+        };
+        for x in a[done..].iter_mut() {
+            *x = self.mul(*x, s);
+        }
+    }
+
+    fn mul_pairwise_slice(&self, a: &mut [Value], b: &[Value]) {
+        self.mul_slice(a, b)
+    }
+
+    fn fft2_butterflies(&self, lo: &mut [Value], hi: &mut [Value], twiddles: &[Value]) {
+        let done = if simd::available(self.n) {
+            let p = self.simd_params();
+            unsafe { simd::fft2_butterflies(&p, raw_mut(lo), raw_mut(hi), raw(twiddles)) }
+        } else {
+            0
+        };
+        for ((x, y), t) in lo[done..]
+            .iter_mut()
+            .zip(hi[done..].iter_mut())
+            .zip(&twiddles[done..])
+        {
+            let ty = self.mul(*y, t);
+            *y = self.sub(*x, ty);
+            *x = self.add(*x, ty);
+        }
+    }
+
+    fn fft3_butterflies(
+        &self,
+        a: &mut [Value],
+        b: &mut [Value],
+        c: &mut [Value],
+        twiddles: &[Value],
+        twiddles_sq: &[Value],
+        omega: &Value,
+        omega_sq: &Value,
+    ) {
+        let done = if simd::available(self.n) {
+            let p = self.simd_params();
+            unsafe {
+                simd::fft3_butterflies(
+                    &p,
+                    raw_mut(a),
+                    raw_mut(b),
+                    raw_mut(c),
+                    raw(twiddles),
+                    raw(twiddles_sq),
+                    omega.0,
+                    omega_sq.0,
+                )
+            }
+        } else {
+            0
+        };
+        for i in done..a.len() {
+            let x = a[i];
+            let y = self.mul(b[i], twiddles[i]);
+            let z = self.mul(c[i], twiddles_sq[i]);
+            a[i] = self.add(self.add(x, y), z);
+            b[i] = self.add(self.add(x, self.mul(omega, y)), self.mul(omega_sq, z));
+            c[i] = self.add(self.add(x, self.mul(omega_sq, y)), self.mul(omega, z));
+        }
     }
 }
 
@@ -261,3 +445,68 @@ impl Field for MontgomeryField32 {
 
 #[cfg(test)]
 all_fields_test!(MontgomeryField32);
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::{ChaChaRng, SeedableRng};
+
+    fn random_values(field: &MontgomeryField32, count: usize, rng: &mut ChaChaRng) -> Vec<Value> {
+        field.sample_with_replacement(count, rng)
+    }
+
+    #[test]
+    fn test_slice_ops() {
+        let field = MontgomeryField32::new(2013265921);
+        let mut rng = ChaChaRng::from_seed(&[32]);
+        let a = random_values(&field, 37, &mut rng);
+        let b = random_values(&field, 37, &mut rng);
+
+        let mut products = a.clone();
+        field.mul_slice(&mut products, &b);
+        let mut sums = a.clone();
+        field.add_slice(&mut sums, &b);
+        let mut scaled = a.clone();
+        field.mul_scalar_slice(&mut scaled, &b[0]);
+
+        for i in 0..a.len() {
+            assert!(field.eq(products[i], field.mul(a[i], b[i])));
+            assert!(field.eq(sums[i], field.add(a[i], b[i])));
+            assert!(field.eq(scaled[i], field.mul(a[i], b[0])));
+        }
+    }
+
+    #[test]
+    fn test_butterflies() {
+        let field = MontgomeryField32::new(2013265921);
+        let w = field.pow(field.encode(31_u32), (2013265921 - 1) / 3);
+        let w_sq = field.mul(w, w);
+        let mut rng = ChaChaRng::from_seed(&[33]);
+        let a = random_values(&field, 19, &mut rng);
+        let b = random_values(&field, 19, &mut rng);
+        let c = random_values(&field, 19, &mut rng);
+        let t = random_values(&field, 19, &mut rng);
+        let t_sq: Vec<Value> = t.iter().map(|x| field.mul(x, x)).collect();
+
+        let (mut lo, mut hi) = (a.clone(), b.clone());
+        field.fft2_butterflies(&mut lo, &mut hi, &t);
+        for i in 0..a.len() {
+            let y = field.mul(b[i], t[i]);
+            assert!(field.eq(lo[i], field.add(a[i], y)));
+            assert!(field.eq(hi[i], field.sub(a[i], y)));
+        }
+
+        let (mut x, mut y, mut z) = (a.clone(), b.clone(), c.clone());
+        field.fft3_butterflies(&mut x, &mut y, &mut z, &t, &t_sq, &w, &w_sq);
+        for i in 0..a.len() {
+            // evaluations at t, w.t, w^2.t of the polynomial a + b.X + c.X^2
+            for (k, result) in [x[i], y[i], z[i]].iter().enumerate() {
+                let point = field.mul(t[i], field.pow(w, k as u32));
+                let expected =
+                    ::numtheory::mod_evaluate_polynomial(&[a[i], b[i], c[i]], point, &field);
+                assert!(field.eq(result, expected));
+            }
+        }
+    }
+}
@@ -0,0 +1,438 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Vectorised kernels for `MontgomeryField32` (AVX2 or NEON), selected at runtime.
+//!
+//! The kernels work on raw Montgomery representations and process the longest
+//! prefix of their inputs that is a multiple of the vector width, returning its
+//! length; callers are responsible for the remaining tail.
+//!
+//! Both the Montgomery reduction and the final conditional subtractions rely on
+//! intermediate values not overflowing, which is only guaranteed for moduli
+//! below `2^31`. Larger moduli always use the scalar code.
+
+/// Constants of the field needed by the kernels.
+pub struct Params {
+    pub n: u32,
+    pub n_quote: u32,
+}
+
+/// Whether vectorised kernels can be used for modulus `n` on this machine.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+pub fn available(n: u32) -> bool {
+    n < 1 << 31 && is_x86_feature_detected!("avx2")
+}
+
+/// Whether vectorised kernels can be used for modulus `n` on this machine.
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+pub fn available(n: u32) -> bool {
+    n < 1 << 31 && ::std::arch::is_aarch64_feature_detected!("neon")
+}
+
+/// Whether vectorised kernels can be used for modulus `n` on this machine.
+#[cfg(not(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+pub fn available(_n: u32) -> bool {
+    false
+}
+
+#[cfg(target_arch = "x86_64")]
+use self::avx2 as arch;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+use self::fallback as arch;
+#[cfg(target_arch = "aarch64")]
+use self::neon as arch;
+
+/// `a[i] = a[i] * b[i]`.
+///
+/// Callers must check `available` first.
+pub unsafe fn mul_slice(p: &Params, a: &mut [u32], b: &[u32]) -> usize {
+    assert_eq!(a.len(), b.len());
+    arch::mul_slice(p, a, b)
+}
+
+/// `a[i] = a[i] * s`.
+///
+/// Callers must check `available` first.
+pub unsafe fn mul_scalar_slice(p: &Params, a: &mut [u32], s: u32) -> usize {
+    arch::mul_scalar_slice(p, a, s)
+}
+
+/// `a[i] = a[i] + b[i]`.
+///
+/// Callers must check `available` first.
+pub unsafe fn add_slice(p: &Params, a: &mut [u32], b: &[u32]) -> usize {
+    assert_eq!(a.len(), b.len());
+    arch::add_slice(p, a, b)
+}
+
+/// Radix-2 butterflies, see `Field::fft2_butterflies`.
+///
+/// Callers must check `available` first.
+pub unsafe fn fft2_butterflies(p: &Params, lo: &mut [u32], hi: &mut [u32], tw: &[u32]) -> usize {
+    assert_eq!(lo.len(), hi.len());
+    assert_eq!(lo.len(), tw.len());
+    arch::fft2_butterflies(p, lo, hi, tw)
+}
+
+/// Radix-3 butterflies, see `Field::fft3_butterflies`.
+///
+/// Callers must check `available` first.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn fft3_butterflies(
+    p: &Params,
+    a: &mut [u32],
+    b: &mut [u32],
+    c: &mut [u32],
+    tw: &[u32],
+    tw_sq: &[u32],
+    w: u32,
+    w_sq: u32,
+) -> usize {
+    assert_eq!(a.len(), b.len());
+    assert_eq!(a.len(), c.len());
+    assert_eq!(a.len(), tw.len());
+    assert_eq!(a.len(), tw_sq.len());
+    arch::fft3_butterflies(p, a, b, c, tw, tw_sq, w, w_sq)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+
+    use super::Params;
+    use std::arch::x86_64::*;
+
+    const LANES: usize = 8;
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(s: &[u32]) -> __m256i {
+        _mm256_loadu_si256(s.as_ptr() as *const __m256i)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn store(s: &mut [u32], v: __m256i) {
+        _mm256_storeu_si256(s.as_mut_ptr() as *mut __m256i, v)
+    }
+
+    /// Montgomery reduction of the 64-bit lanes of `a`, leaving results in the low halves.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn redc(a: __m256i, n: __m256i, nq: __m256i) -> __m256i {
+        let m = _mm256_mul_epu32(a, nq);
+        _mm256_srli_epi64::<32>(_mm256_add_epi64(a, _mm256_mul_epu32(m, n)))
+    }
+
+    /// Conditional subtraction of `n` for lanes in `[0, 2n)`.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn canonical(t: __m256i, n: __m256i) -> __m256i {
+        _mm256_min_epu32(t, _mm256_sub_epi32(t, n))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mul(x: __m256i, y: __m256i, n: __m256i, nq: __m256i) -> __m256i {
+        let even = redc(_mm256_mul_epu32(x, y), n, nq);
+        let odd = redc(
+            _mm256_mul_epu32(_mm256_srli_epi64::<32>(x), _mm256_srli_epi64::<32>(y)),
+            n,
+            nq,
+        );
+        let t = _mm256_blend_epi32::<0b1010_1010>(even, _mm256_slli_epi64::<32>(odd));
+        canonical(t, n)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn add(x: __m256i, y: __m256i, n: __m256i) -> __m256i {
+        canonical(_mm256_add_epi32(x, y), n)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sub(x: __m256i, y: __m256i, n: __m256i) -> __m256i {
+        let d = _mm256_sub_epi32(x, y);
+        _mm256_min_epu32(d, _mm256_add_epi32(d, n))
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mul_slice(p: &Params, a: &mut [u32], b: &[u32]) -> usize {
+        let n = _mm256_set1_epi32(p.n as i32);
+        let nq = _mm256_set1_epi32(p.n_quote as i32);
+        let len = a.len() - a.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&a[i..]);
+            let y = load(&b[i..]);
+            store(&mut a[i..], mul(x, y, n, nq));
+        }
+        len
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mul_scalar_slice(p: &Params, a: &mut [u32], s: u32) -> usize {
+        let n = _mm256_set1_epi32(p.n as i32);
+        let nq = _mm256_set1_epi32(p.n_quote as i32);
+        let s = _mm256_set1_epi32(s as i32);
+        let len = a.len() - a.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&a[i..]);
+            store(&mut a[i..], mul(x, s, n, nq));
+        }
+        len
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn add_slice(p: &Params, a: &mut [u32], b: &[u32]) -> usize {
+        let n = _mm256_set1_epi32(p.n as i32);
+        let len = a.len() - a.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&a[i..]);
+            let y = load(&b[i..]);
+            store(&mut a[i..], add(x, y, n));
+        }
+        len
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn fft2_butterflies(
+        p: &Params,
+        lo: &mut [u32],
+        hi: &mut [u32],
+        tw: &[u32],
+    ) -> usize {
+        let n = _mm256_set1_epi32(p.n as i32);
+        let nq = _mm256_set1_epi32(p.n_quote as i32);
+        let len = lo.len() - lo.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&lo[i..]);
+            let y = mul(load(&hi[i..]), load(&tw[i..]), n, nq);
+            store(&mut lo[i..], add(x, y, n));
+            store(&mut hi[i..], sub(x, y, n));
+        }
+        len
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn fft3_butterflies(
+        p: &Params,
+        a: &mut [u32],
+        b: &mut [u32],
+        c: &mut [u32],
+        tw: &[u32],
+        tw_sq: &[u32],
+        w: u32,
+        w_sq: u32,
+    ) -> usize {
+        let n = _mm256_set1_epi32(p.n as i32);
+        let nq = _mm256_set1_epi32(p.n_quote as i32);
+        let w = _mm256_set1_epi32(w as i32);
+        let w_sq = _mm256_set1_epi32(w_sq as i32);
+        let len = a.len() - a.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&a[i..]);
+            let y = mul(load(&b[i..]), load(&tw[i..]), n, nq);
+            let z = mul(load(&c[i..]), load(&tw_sq[i..]), n, nq);
+            store(&mut a[i..], add(add(x, y, n), z, n));
+            store(
+                &mut b[i..],
+                add(add(x, mul(w, y, n, nq), n), mul(w_sq, z, n, nq), n),
+            );
+            store(
+                &mut c[i..],
+                add(add(x, mul(w_sq, y, n, nq), n), mul(w, z, n, nq), n),
+            );
+        }
+        len
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+
+    use super::Params;
+    use std::arch::aarch64::*;
+
+    const LANES: usize = 4;
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn load(s: &[u32]) -> uint32x4_t {
+        vld1q_u32(s.as_ptr())
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn store(s: &mut [u32], v: uint32x4_t) {
+        vst1q_u32(s.as_mut_ptr(), v)
+    }
+
+    /// Montgomery reduction of two 64-bit products.
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn redc(a: uint64x2_t, n: uint32x2_t, nq: uint32x2_t) -> uint32x2_t {
+        let m = vmul_u32(vmovn_u64(a), nq);
+        vshrn_n_u64::<32>(vaddq_u64(a, vmull_u32(m, n)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn canonical(t: uint32x4_t, n: uint32x4_t) -> uint32x4_t {
+        vminq_u32(t, vsubq_u32(t, n))
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn mul(x: uint32x4_t, y: uint32x4_t, n: uint32x4_t, nq: uint32x2_t) -> uint32x4_t {
+        let n_half = vget_low_u32(n);
+        let lo = redc(vmull_u32(vget_low_u32(x), vget_low_u32(y)), n_half, nq);
+        let hi = redc(vmull_high_u32(x, y), n_half, nq);
+        canonical(vcombine_u32(lo, hi), n)
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn add(x: uint32x4_t, y: uint32x4_t, n: uint32x4_t) -> uint32x4_t {
+        canonical(vaddq_u32(x, y), n)
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn sub(x: uint32x4_t, y: uint32x4_t, n: uint32x4_t) -> uint32x4_t {
+        let d = vsubq_u32(x, y);
+        vminq_u32(d, vaddq_u32(d, n))
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn mul_slice(p: &Params, a: &mut [u32], b: &[u32]) -> usize {
+        let n = vdupq_n_u32(p.n);
+        let nq = vdup_n_u32(p.n_quote);
+        let len = a.len() - a.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&a[i..]);
+            let y = load(&b[i..]);
+            store(&mut a[i..], mul(x, y, n, nq));
+        }
+        len
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn mul_scalar_slice(p: &Params, a: &mut [u32], s: u32) -> usize {
+        let n = vdupq_n_u32(p.n);
+        let nq = vdup_n_u32(p.n_quote);
+        let s = vdupq_n_u32(s);
+        let len = a.len() - a.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&a[i..]);
+            store(&mut a[i..], mul(x, s, n, nq));
+        }
+        len
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn add_slice(p: &Params, a: &mut [u32], b: &[u32]) -> usize {
+        let n = vdupq_n_u32(p.n);
+        let len = a.len() - a.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&a[i..]);
+            let y = load(&b[i..]);
+            store(&mut a[i..], add(x, y, n));
+        }
+        len
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn fft2_butterflies(
+        p: &Params,
+        lo: &mut [u32],
+        hi: &mut [u32],
+        tw: &[u32],
+    ) -> usize {
+        let n = vdupq_n_u32(p.n);
+        let nq = vdup_n_u32(p.n_quote);
+        let len = lo.len() - lo.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&lo[i..]);
+            let y = mul(load(&hi[i..]), load(&tw[i..]), n, nq);
+            store(&mut lo[i..], add(x, y, n));
+            store(&mut hi[i..], sub(x, y, n));
+        }
+        len
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[target_feature(enable = "neon")]
+    pub unsafe fn fft3_butterflies(
+        p: &Params,
+        a: &mut [u32],
+        b: &mut [u32],
+        c: &mut [u32],
+        tw: &[u32],
+        tw_sq: &[u32],
+        w: u32,
+        w_sq: u32,
+    ) -> usize {
+        let n = vdupq_n_u32(p.n);
+        let nq = vdup_n_u32(p.n_quote);
+        let w = vdupq_n_u32(w);
+        let w_sq = vdupq_n_u32(w_sq);
+        let len = a.len() - a.len() % LANES;
+        for i in (0..len).step_by(LANES) {
+            let x = load(&a[i..]);
+            let y = mul(load(&b[i..]), load(&tw[i..]), n, nq);
+            let z = mul(load(&c[i..]), load(&tw_sq[i..]), n, nq);
+            store(&mut a[i..], add(add(x, y, n), z, n));
+            store(
+                &mut b[i..],
+                add(add(x, mul(w, y, n, nq), n), mul(w_sq, z, n, nq), n),
+            );
+            store(
+                &mut c[i..],
+                add(add(x, mul(w_sq, y, n, nq), n), mul(w, z, n, nq), n),
+            );
+        }
+        len
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod fallback {
+
+    use super::Params;
+
+    pub unsafe fn mul_slice(_: &Params, _: &mut [u32], _: &[u32]) -> usize {
+        0
+    }
+
+    pub unsafe fn mul_scalar_slice(_: &Params, _: &mut [u32], _: u32) -> usize {
+        0
+    }
+
+    pub unsafe fn add_slice(_: &Params, _: &mut [u32], _: &[u32]) -> usize {
+        0
+    }
+
+    pub unsafe fn fft2_butterflies(_: &Params, _: &mut [u32], _: &mut [u32], _: &[u32]) -> usize {
+        0
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn fft3_butterflies(
+        _: &Params,
+        _: &mut [u32],
+        _: &mut [u32],
+        _: &mut [u32],
+        _: &[u32],
+        _: &[u32],
+        _: u32,
+        _: u32,
+    ) -> usize {
+        0
+    }
+}
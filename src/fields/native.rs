@@ -0,0 +1,295 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Reference implementation of the field traits.
+//!
+//! `NativePrimeField` favours obviously correct code over speed: every operation is
+//! computed on native 128-bit integers and reduced straight away, and inverses and
+//! powers use textbook algorithms independent of those in `numtheory`. It serves as
+//! an example for implementing new backends and as the oracle the optimised
+//! backends are tested against.
+
+use rand;
+use std::borrow::Borrow;
+
+use super::{
+    Decode, ElementBytes, Encode, Exponent, Field, IntegerCoding, KnownPrime, New, OpCosts,
+    PrimeField,
+};
+
+/// Prime field over a prime `p < 2^64`, with elements stored as their
+/// canonical representative in `0..p`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NativePrimeField {
+    /// The prime defining the field.
+    pub prime: u64,
+}
+
+impl NativePrimeField {
+    /// Reduce any value into the `0..p` range.
+    fn reduce(&self, x: u128) -> u64 {
+        (x % self.prime as u128) as u64
+    }
+}
+
+impl Field for NativePrimeField {
+    type E = u64;
+
+    fn zero(&self) -> Self::E {
+        0
+    }
+
+    fn one(&self) -> Self::E {
+        self.reduce(1)
+    }
+
+    fn add<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        self.reduce(*a.borrow() as u128 + *b.borrow() as u128)
+    }
+
+    fn sub<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        let b = self.reduce(*b.borrow() as u128);
+        self.reduce(*a.borrow() as u128 + (self.prime - b) as u128)
+    }
+
+    fn mul<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        self.reduce(*a.borrow() as u128 * *b.borrow() as u128)
+    }
+
+    /// Left-to-right square-and-multiply.
+    fn pow<A: Borrow<Self::E>, X: Exponent>(&self, a: A, e: X) -> Self::E {
+        let a = self.reduce(*a.borrow() as u128);
+        let mut acc = self.one();
+        for i in (0..e.bit_length()).rev() {
+            acc = self.mul(acc, acc);
+            if e.bit(i) {
+                acc = self.mul(acc, a);
+            }
+        }
+        acc
+    }
+
+    /// Extended Euclidean algorithm; `a` must be non-zero.
+    fn inv<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        let a = self.reduce(*a.borrow() as u128);
+        assert!(a != 0, "zero has no inverse");
+        // invariant: r_i = s_i * a mod p
+        let (mut r0, mut r1) = (self.prime as i128, a as i128);
+        let (mut s0, mut s1) = (0_i128, 1_i128);
+        while r1 != 0 {
+            let q = r0 / r1;
+            let (r, s) = (r0 - q * r1, s0 - q * s1);
+            r0 = r1;
+            s0 = s1;
+            r1 = r;
+            s1 = s;
+        }
+        s0.rem_euclid(self.prime as i128) as u64
+    }
+
+    fn op_costs(&self) -> OpCosts {
+        // the extended Euclidean algorithm takes a number of steps linear in the
+        // size of the prime
+        let bits = u64::from(64 - self.prime.leading_zeros());
+        OpCosts {
+            add: 5000,
+            mul: 5000,
+            inv: 5000 + 5000 * bits,
+        }
+    }
+
+    fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool {
+        self.normalize(lhs) == self.normalize(rhs)
+    }
+
+    fn normalize<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        self.reduce(*a.borrow() as u128)
+    }
+
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        rng.gen_range(0, self.prime)
+    }
+}
+
+impl PrimeField for NativePrimeField {
+    type P = u64;
+}
+
+impl KnownPrime for NativePrimeField {
+    fn prime(&self) -> u64 {
+        self.prime
+    }
+}
+
+impl New<u32> for NativePrimeField {
+    fn new(prime: u32) -> Self {
+        NativePrimeField {
+            prime: prime as u64,
+        }
+    }
+}
+
+impl Encode<u32> for NativePrimeField {
+    fn encode(&self, x: u32) -> Self::E {
+        self.reduce(x as u128)
+    }
+}
+
+impl Encode<u64> for NativePrimeField {
+    fn encode(&self, x: u64) -> Self::E {
+        self.reduce(x as u128)
+    }
+}
+
+impl Decode<u32> for NativePrimeField {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> u32 {
+        ::std::convert::TryFrom::try_from(self.normalize(x)).expect("element does not fit in a u32")
+    }
+}
+
+impl Decode<u64> for NativePrimeField {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> u64 {
+        self.normalize(x)
+    }
+}
+
+impl IntegerCoding for NativePrimeField {
+    fn encode_signed(&self, x: i64) -> Self::E {
+        (x as i128).rem_euclid(self.prime as i128) as u64
+    }
+
+    fn decode_signed<E: Borrow<Self::E>>(&self, x: E) -> i64 {
+        let x = self.normalize(x);
+        if x > self.prime / 2 {
+            -((self.prime - x) as i64)
+        } else {
+            x as i64
+        }
+    }
+
+    fn decode_u128<E: Borrow<Self::E>>(&self, x: E) -> u128 {
+        self.normalize(x) as u128
+    }
+}
+
+impl ElementBytes for NativePrimeField {
+    fn element_len(&self) -> usize {
+        8
+    }
+
+    fn write_element<E: Borrow<Self::E>>(&self, e: E, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.normalize(e).to_le_bytes());
+    }
+
+    fn read_element(&self, bytes: &[u8]) -> Option<Self::E> {
+        assert_eq!(bytes.len(), 8);
+        let mut word = [0; 8];
+        word.copy_from_slice(bytes);
+        let x = u64::from_le_bytes(word);
+        if x < self.prime {
+            Some(x)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+all_fields_test!(NativePrimeField);
+
+#[cfg(test)]
+mod oracle {
+
+    use super::*;
+    use fields::{BigExponent, Fp61, MontgomeryField32, NaturalPrimeField, U64Field};
+    use rand::{ChaChaRng, Rng, SeedableRng};
+    use std::convert::TryFrom;
+    use std::fmt::Debug;
+
+    /// Compare random operations in `field` with those of the reference field over
+    /// the same prime, going through values of type `T`.
+    fn check_against_oracle<F, T>(field: &F, seed: u32)
+    where
+        F: KnownPrime + Encode<T> + Decode<T>,
+        F::P: Into<u64>,
+        T: Into<u64> + TryFrom<u64>,
+        <T as TryFrom<u64>>::Error: Debug,
+    {
+        let oracle = NativePrimeField {
+            prime: field.prime().into(),
+        };
+        let encode = |x: u64| field.encode(T::try_from(x).unwrap());
+        let decode = |e: &F::E| -> u64 { field.decode(e).into() };
+
+        let mut rng = ChaChaRng::from_seed(&[seed]);
+        let mut values = vec![0, 1, 2, oracle.prime - 1, oracle.prime - 2];
+        values.extend(oracle.sample_with_replacement(50, &mut rng));
+        for &x in &values {
+            let a = encode(x);
+            assert_eq!(decode(&a), x);
+            let y = oracle.sample(&mut rng);
+            let b = encode(y);
+            assert_eq!(decode(&field.add(&a, &b)), oracle.add(x, y));
+            assert_eq!(decode(&field.sub(&a, &b)), oracle.sub(x, y));
+            assert_eq!(decode(&field.mul(&a, &b)), oracle.mul(x, y));
+            assert_eq!(field.eq(&a, &b), x == y);
+
+            let e: u32 = rng.gen();
+            assert_eq!(decode(&field.pow(&a, e)), oracle.pow(x, e));
+            let e = BigExponent::from(rng.gen::<u64>());
+            assert_eq!(decode(&field.pow(&a, &e)), oracle.pow(x, &e));
+
+            if x != 0 {
+                assert_eq!(decode(&field.inv(&a)), oracle.inv(x));
+                // Fermat's little theorem
+                let order = BigExponent::from(oracle.prime - 1);
+                assert_eq!(decode(&field.pow(&a, order)), 1);
+            }
+        }
+
+        values.retain(|&x| x != 0);
+        let mut elements: Vec<F::E> = values.iter().map(|&x| encode(x)).collect();
+        field.batch_inv(&mut elements);
+        for (e, &x) in elements.iter().zip(&values) {
+            assert_eq!(decode(e), oracle.inv(x));
+        }
+    }
+
+    #[test]
+    fn test_oracle() {
+        let field = NativePrimeField { prime: 17 };
+        assert_eq!(field.inv(3_u64), 6);
+        assert_eq!(field.pow(3_u64, 16), 1);
+        assert_eq!(field.sub(3_u64, 20_u64), 0);
+        assert_eq!(field.one(), 1);
+        assert_eq!(NativePrimeField { prime: 2 }.inv(1_u64), 1);
+    }
+
+    #[test]
+    fn test_natural() {
+        check_against_oracle::<_, u32>(&NaturalPrimeField(433), 1);
+        check_against_oracle::<_, u32>(&NaturalPrimeField(2147483647), 2);
+    }
+
+    #[test]
+    fn test_montgomery() {
+        check_against_oracle::<_, u32>(&MontgomeryField32::new(433), 3);
+        check_against_oracle::<_, u32>(&MontgomeryField32::new(2147483647), 4);
+    }
+
+    #[test]
+    fn test_mersenne() {
+        check_against_oracle::<_, u64>(&Fp61, 5);
+    }
+
+    #[test]
+    fn test_u64() {
+        check_against_oracle::<_, u64>(&U64Field(433), 6);
+        check_against_oracle::<_, u64>(&U64Field((1 << 63) - 25), 7);
+    }
+}
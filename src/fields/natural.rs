@@ -3,7 +3,10 @@
 use rand;
 use std::borrow::Borrow;
 
-use fields::{Decode, Encode, Field, New, PrimeField};
+use fields::{
+    Decode, ElementBytes, Encode, Exponent, Field, IntegerCoding, KnownPrime, New, OpCosts,
+    PrimeField,
+};
 use numtheory::{mod_inverse, mod_pow};
 
 #[derive(Clone, Debug, PartialEq)]
@@ -21,50 +24,51 @@ impl Field for NaturalPrimeField<i64> {
     }
 
     fn add<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
-        (a.borrow() + b.borrow()) % self.0
+        (a.borrow() + b.borrow()).rem_euclid(self.0)
     }
 
     fn sub<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
-        let c = (a.borrow() - b.borrow()) % self.0;
-        if c >= 0 {
-            c
-        } else {
-            c + self.0
-        }
+        (a.borrow() - b.borrow()).rem_euclid(self.0)
     }
 
     fn mul<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
-        (a.borrow() * b.borrow()) % self.0
+        (a.borrow() * b.borrow()).rem_euclid(self.0)
     }
 
-    fn pow<A: Borrow<Self::E>>(&self, a: A, e: u32) -> Self::E {
-        let c = mod_pow(*a.borrow(), e, self.0);
-        c
-        // if c >= 0 {
-        //     c
-        // } else {
-        //     c + self.0
-        // }
+    fn pow<A: Borrow<Self::E>, X: Exponent>(&self, a: A, e: X) -> Self::E {
+        mod_pow(*a.borrow(), e, self.0).rem_euclid(self.0)
     }
 
     fn inv<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
-        let inv = mod_inverse(*a.borrow(), self.0);
-        inv
-        // if inv >= 0 {
-        //     inv
-        // } else {
-        //     inv + self.0
-        // }
+        // already in the `0..p` range
+        mod_inverse(*a.borrow(), self.0)
+    }
+
+    fn op_costs(&self) -> OpCosts {
+        // the extended Euclidean algorithm takes a number of steps linear in the
+        // size of the prime
+        let bits = u64::from(64 - self.0.leading_zeros());
+        OpCosts {
+            add: 2900,
+            mul: 6000,
+            inv: 2000 + 3000 * bits,
+        }
     }
 
     fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool {
-        (lhs.borrow() % self.0) == (rhs.borrow() % self.0)
+        self.normalize(lhs) == self.normalize(rhs)
     }
 
-    fn sample_with_replacement<R: rand::Rng>(&self, count: usize, rng: &mut R) -> Vec<Self::E> {
-        use rand::distributions::Sample;
-        let mut range = rand::distributions::range::Range::new(0, self.0);
-        (0..count).map(|_| range.sample(rng)).collect()
+    fn normalize<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        a.borrow().rem_euclid(self.0)
+    }
+
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        rng.gen_range(0, self.0)
+    }
+
+    fn sample_nonzero<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        rng.gen_range(1, self.0)
     }
 }
 
@@ -72,6 +76,12 @@ impl PrimeField for NaturalPrimeField<i64> {
     type P = u32;
 }
 
+impl KnownPrime for NaturalPrimeField<i64> {
+    fn prime(&self) -> u32 {
+        ::std::convert::TryFrom::try_from(self.0).expect("prime does not fit in a u32")
+    }
+}
+
 impl New<u32> for NaturalPrimeField<i64> {
     fn new(prime: u32) -> Self {
         NaturalPrimeField(prime as i64)
@@ -86,9 +96,79 @@ impl Encode<u32> for NaturalPrimeField<i64> {
 
 impl Decode<u32> for NaturalPrimeField<i64> {
     fn decode<E: Borrow<Self::E>>(&self, x: E) -> u32 {
-        *x.borrow() as u32
+        ::std::convert::TryFrom::try_from(self.normalize(x)).expect("element does not fit in a u32")
+    }
+}
+
+impl IntegerCoding for NaturalPrimeField<i64> {
+    fn encode_signed(&self, x: i64) -> Self::E {
+        x.rem_euclid(self.0)
+    }
+
+    fn decode_signed<E: Borrow<Self::E>>(&self, x: E) -> i64 {
+        let x = self.normalize(x);
+        if x > self.0 / 2 {
+            x - self.0
+        } else {
+            x
+        }
+    }
+
+    fn decode_u128<E: Borrow<Self::E>>(&self, x: E) -> u128 {
+        self.normalize(x) as u128
+    }
+}
+
+impl ElementBytes for NaturalPrimeField<i64> {
+    fn element_len(&self) -> usize {
+        4
+    }
+
+    fn write_element<E: Borrow<Self::E>>(&self, e: E, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&(self.normalize(e) as u32).to_le_bytes());
+    }
+
+    fn read_element(&self, bytes: &[u8]) -> Option<Self::E> {
+        assert_eq!(bytes.len(), 4);
+        let x = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64;
+        if x < self.0 {
+            Some(x)
+        } else {
+            None
+        }
     }
 }
 
 #[cfg(test)]
 all_fields_test!(NaturalPrimeField<i64>);
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::MontgomeryField32;
+
+    #[test]
+    fn test_canonical() {
+        let zp = NaturalPrimeField(17);
+        assert_eq!(zp.normalize(-3), 14);
+        assert_eq!(zp.normalize(20), 3);
+        assert_eq!(zp.add(-3, 1), 15);
+        assert_eq!(zp.mul(-3, 2), 11);
+        assert_eq!(zp.pow(-3, 15), 11);
+        assert_eq!(zp.decode(-3), 14);
+        assert!(Field::eq(&zp, -3, 14));
+    }
+
+    #[test]
+    fn test_convert_from() {
+        let zp = NaturalPrimeField(433);
+        let montgomery = MontgomeryField32::new(433);
+        for x in [0, 1, 5, 432] {
+            let y = montgomery.convert_from(&zp, x);
+            assert_eq!(montgomery.decode(y), x as u32);
+            assert_eq!(zp.convert_from(&montgomery, y), x);
+        }
+        assert_eq!(zp.convert_from(&zp, -3), 430);
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Forward-secure generator for the randomness of sharings.
+
+use rand::{ChaChaRng, OsRng, Rng};
+use seed::{seeded_rng, Seed};
+use std::ptr;
+
+/// Length of the key of each block.
+const KEY_LEN: usize = 32;
+/// Number of bytes generated from each key, including the next key.
+const BLOCK_LEN: usize = 512;
+
+/// ChaCha20 generator erasing its past states, so that compromising its state does
+/// not reveal the output it already handed out.
+///
+/// Following the fast key erasure construction, every block of output starts with
+/// the key of the next block, which replaces the current key before any of the
+/// output is used, and bytes are erased from the buffer as they are handed out. At
+/// any time the state hence only determines output still to come.
+///
+/// This only covers the generator itself: the field elements sampled from it, such
+/// as the coefficients of sharing polynomials, are not erased when dropped, and
+/// may remain in memory after a sharing.
+///
+/// This is the generator behind the `share` methods of the schemes, which seed a
+/// fresh one from the OS for every sharing, and of `PackedSharingContext` which
+/// keeps one across sharings. Other generators can still be given to the methods
+/// taking one, such as `share_with_rng`.
+pub struct ForwardSecureRng {
+    key: Seed,
+    buffer: [u8; BLOCK_LEN],
+    position: usize,
+}
+
+impl ForwardSecureRng {
+    /// Generator seeded from the OS entropy source.
+    pub fn new() -> ForwardSecureRng {
+        let mut seed = [0; KEY_LEN];
+        OsRng::new().unwrap().fill_bytes(&mut seed);
+        let rng = ForwardSecureRng::from_seed(&seed);
+        erase(&mut seed);
+        rng
+    }
+
+    /// Generator deterministically derived from `seed`, which is not kept.
+    pub fn from_seed(seed: &Seed) -> ForwardSecureRng {
+        let mut rng = ForwardSecureRng {
+            key: *seed,
+            buffer: [0; BLOCK_LEN],
+            position: BLOCK_LEN,
+        };
+        rng.refill();
+        rng
+    }
+
+    /// Generate the block of the current key and replace the key by the next one.
+    fn refill(&mut self) {
+        let mut chacha = seeded_rng(&self.key);
+        chacha.fill_bytes(&mut self.buffer);
+        // the generator holds the expanded key, so overwrite it before dropping it
+        unsafe { ptr::write_volatile(&mut chacha, ChaChaRng::new_unseeded()) };
+        self.key.copy_from_slice(&self.buffer[..KEY_LEN]);
+        erase(&mut self.buffer[..KEY_LEN]);
+        self.position = KEY_LEN;
+    }
+}
+
+impl Default for ForwardSecureRng {
+    fn default() -> ForwardSecureRng {
+        ForwardSecureRng::new()
+    }
+}
+
+impl Rng for ForwardSecureRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut filled = 0;
+        while filled < dest.len() {
+            if self.position == BLOCK_LEN {
+                self.refill();
+            }
+            let count = (dest.len() - filled).min(BLOCK_LEN - self.position);
+            let output = &mut self.buffer[self.position..self.position + count];
+            dest[filled..filled + count].copy_from_slice(output);
+            erase(output);
+            self.position += count;
+            filled += count;
+        }
+    }
+}
+
+impl Drop for ForwardSecureRng {
+    fn drop(&mut self) {
+        erase(&mut self.key);
+        erase(&mut self.buffer);
+    }
+}
+
+/// Overwrite `bytes` with zeros, in a way the compiler cannot optimise away.
+fn erase(bytes: &mut [u8]) {
+    for byte in bytes {
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = ForwardSecureRng::from_seed(&[1; 32]);
+        let mut b = ForwardSecureRng::from_seed(&[1; 32]);
+        let mut c = ForwardSecureRng::from_seed(&[2; 32]);
+        // across several blocks and in uneven pieces
+        let mut x = vec![0; 3 * BLOCK_LEN];
+        let mut y = vec![0; 3 * BLOCK_LEN];
+        a.fill_bytes(&mut x);
+        for chunk in y.chunks_mut(37) {
+            b.fill_bytes(chunk);
+        }
+        assert_eq!(x, y);
+        assert_eq!(a.next_u64(), b.next_u64());
+        assert_ne!(a.next_u32(), c.next_u32());
+    }
+
+    #[test]
+    fn test_erases_past_state() {
+        let seed = [3; 32];
+        let mut rng = ForwardSecureRng::from_seed(&seed);
+        assert_ne!(rng.key, seed);
+        let mut output = [0; 16];
+        rng.fill_bytes(&mut output);
+        assert!(rng.buffer[..KEY_LEN + 16].iter().all(|&byte| byte == 0));
+
+        // the key is replaced when moving on to the next block
+        let mut rest = vec![0; BLOCK_LEN - KEY_LEN - 16];
+        let key = rng.key;
+        rng.fill_bytes(&mut rest);
+        assert_eq!(rng.key, key);
+        rng.next_u32();
+        assert_ne!(rng.key, key);
+        assert!(rng.buffer[..KEY_LEN + 4].iter().all(|&byte| byte == 0));
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Uniform sampling of arbitrarily large integers, as used by the bignum fields.
+//!
+//! Integers are given as little-endian 32-bit limbs so that the routine does not
+//! depend on any particular bignum library.
+
+use rand::Rng;
+
+/// Uniformly random integer in `[0, bound)`, with both given as little-endian
+/// 32-bit limbs; the result has as many limbs as `bound`.
+///
+/// Candidates are drawn uniformly among the integers with at most the bit length of
+/// `bound` and rejected until one is below it. Every accepted value is hence equally
+/// likely, and since `bound` is at least half of that range each candidate is
+/// accepted with probability above one half.
+///
+/// Panics if `bound` is zero.
+///
+/// # Example:
+///
+/// ```
+///    extern crate rand;
+///    extern crate threshold_secret_sharing;
+///    use threshold_secret_sharing::sample_below;
+///    let mut rng = rand::OsRng::new().unwrap();
+///    // below 3.2^32
+///    let x = sample_below(&[0, 3], &mut rng);
+///    assert_eq!(x.len(), 2);
+///    assert!(x[1] < 3);
+/// ```
+pub fn sample_below<R: Rng>(bound: &[u32], rng: &mut R) -> Vec<u32> {
+    let len = bound
+        .iter()
+        .rposition(|&limb| limb != 0)
+        .expect("bound must be positive")
+        + 1;
+    let top_mask = u32::MAX >> bound[len - 1].leading_zeros();
+    let mut candidate = vec![0; bound.len()];
+    loop {
+        for limb in candidate[..len].iter_mut() {
+            *limb = rng.next_u32();
+        }
+        candidate[len - 1] &= top_mask;
+        if candidate[..len].iter().rev().lt(bound[..len].iter().rev()) {
+            return candidate;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::{ChaChaRng, SeedableRng};
+
+    /// Pearson's chi-squared statistic of `counts` against the uniform distribution.
+    fn chi_squared(counts: &[usize]) -> f64 {
+        let total: usize = counts.iter().sum();
+        let expected = total as f64 / counts.len() as f64;
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn test_sample_below_uniform() {
+        let mut rng = ChaChaRng::from_seed(&[4830]);
+
+        let mut counts = [0; 17];
+        for _ in 0..17000 {
+            counts[sample_below(&[17], &mut rng)[0] as usize] += 1;
+        }
+        // 0.1% critical value for 16 degrees of freedom
+        assert!(chi_squared(&counts) < 39.25);
+
+        // the top limb of a multi-limb bound, and the top bits of the lower limb
+        let mut high = [0; 3];
+        let mut low = [0; 4];
+        for _ in 0..6000 {
+            let x = sample_below(&[0, 3], &mut rng);
+            high[x[1] as usize] += 1;
+            low[(x[0] >> 30) as usize] += 1;
+        }
+        // 0.1% critical values for 2 and 3 degrees of freedom
+        assert!(chi_squared(&high) < 13.82);
+        assert!(chi_squared(&low) < 16.27);
+    }
+
+    #[test]
+    fn test_sample_below_bounds() {
+        let mut rng = ChaChaRng::from_seed(&[4830]);
+        assert_eq!(sample_below(&[1], &mut rng), [0]);
+        assert_eq!(sample_below(&[1, 0, 0], &mut rng), [0, 0, 0]);
+        for _ in 0..100 {
+            let x = sample_below(&[5, 1, 0], &mut rng);
+            assert_eq!(x[2], 0);
+            assert!(x[1] == 0 || x[0] < 5);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_below_zero() {
+        let mut rng = ChaChaRng::from_seed(&[4830]);
+        sample_below(&[0, 0], &mut rng);
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Scalar fields of curve backends implementing the `ff` traits.
+
+extern crate ff;
+extern crate rand_core;
+
+use rand;
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use self::ff::PrimeField;
+use super::{Encode, Exponent, Field};
+use numtheory::generic_mod_pow;
+
+/// Field whose elements are the scalars `S` of a curve backend, such as
+/// `bls12_381::Scalar` or `curve25519_dalek::Scalar`.
+///
+/// This lets the schemes share the secret keys of threshold signature schemes
+/// directly, with shares that can be used for signing without any conversion.
+#[derive(Debug)]
+pub struct ScalarField<S>(PhantomData<S>);
+
+impl<S> ScalarField<S> {
+    pub fn new() -> ScalarField<S> {
+        ScalarField(PhantomData)
+    }
+}
+
+impl<S> Default for ScalarField<S> {
+    fn default() -> ScalarField<S> {
+        ScalarField::new()
+    }
+}
+
+impl<S> Clone for ScalarField<S> {
+    fn clone(&self) -> ScalarField<S> {
+        *self
+    }
+}
+
+impl<S> Copy for ScalarField<S> {}
+
+/// All instances describe the same field.
+impl<S> PartialEq for ScalarField<S> {
+    fn eq(&self, _other: &ScalarField<S>) -> bool {
+        true
+    }
+}
+
+/// Adapter letting the `ff` traits sample from the generators of this crate.
+struct RngAdapter<'a, R: 'a>(&'a mut R);
+
+impl<'a, R: rand::Rng> rand_core::RngCore for RngAdapter<'a, R> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.0.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl<S: PrimeField> Field for ScalarField<S> {
+    type E = S;
+
+    fn zero(&self) -> S {
+        S::ZERO
+    }
+
+    fn one(&self) -> S {
+        S::ONE
+    }
+
+    fn add<A: Borrow<S>, B: Borrow<S>>(&self, a: A, b: B) -> S {
+        *a.borrow() + b.borrow()
+    }
+
+    fn sub<A: Borrow<S>, B: Borrow<S>>(&self, a: A, b: B) -> S {
+        *a.borrow() - b.borrow()
+    }
+
+    fn mul<A: Borrow<S>, B: Borrow<S>>(&self, a: A, b: B) -> S {
+        *a.borrow() * b.borrow()
+    }
+
+    fn pow<A: Borrow<S>, X: Exponent>(&self, a: A, e: X) -> S {
+        generic_mod_pow(self, *a.borrow(), e)
+    }
+
+    fn inv<A: Borrow<S>>(&self, a: A) -> S {
+        a.borrow().invert().expect("zero is not invertible")
+    }
+
+    fn eq<L: Borrow<S>, R: Borrow<S>>(&self, lhs: L, rhs: R) -> bool {
+        lhs.borrow() == rhs.borrow()
+    }
+
+    fn normalize<A: Borrow<S>>(&self, a: A) -> S {
+        *a.borrow()
+    }
+
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> S {
+        S::random(RngAdapter(rng))
+    }
+}
+
+impl<S: PrimeField> Encode<u32> for ScalarField<S> {
+    fn encode(&self, x: u32) -> S {
+        S::from(u64::from(x))
+    }
+}
+
+impl<S: PrimeField> Encode<u64> for ScalarField<S> {
+    fn encode(&self, x: u64) -> S {
+        S::from(x)
+    }
+}
+
+#[cfg(all(test, feature = "ed25519"))]
+mod tests {
+
+    extern crate curve25519_dalek;
+
+    use self::curve25519_dalek::Scalar;
+    use super::*;
+    use shamir::ShamirSecretSharing;
+
+    #[test]
+    fn test_arithmetic() {
+        let field = ScalarField::<Scalar>::new();
+        let a = field.encode(7_u32);
+        let b = field.encode(5_u32);
+        assert_eq!(field.sub(&b, &a), -Scalar::from(2_u64));
+        assert_eq!(field.mul(field.inv(a), a), field.one());
+        assert_eq!(field.pow(b, 3_u32), Scalar::from(125_u64));
+    }
+
+    #[test]
+    fn test_share_reconstruct() {
+        let tss = ShamirSecretSharing::<ScalarField<Scalar>>::for_scalars(2, 5);
+        let secret = tss.field.sample(&mut rand::OsRng::new().unwrap());
+        let shares = tss.share(secret);
+        assert_eq!(tss.reconstruct(&[1, 2, 3], &shares[1..4]), secret);
+    }
+}
@@ -0,0 +1,485 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Natural prime field for moduli up to 256 bits.
+
+use rand;
+use std::borrow::Borrow;
+
+use super::{
+    BigExponent, Decode, ElementBytes, Encode, Exponent, Field, IntegerCoding, KnownPrime, New,
+    OpCosts, PrimeField,
+};
+use numtheory::generic_mod_pow;
+
+/// Little-endian 64-bit limbs of a 256-bit integer.
+type Limbs = [u64; 4];
+
+/// Prime field over an arbitrary odd prime `p < 2^256`, such as the scalar fields of
+/// elliptic curves, in natural representation.
+///
+/// Elements are the canonical values in the `0..p` range, as little-endian 64-bit
+/// limbs. Products are computed with two Montgomery reductions, so that no
+/// arbitrary-precision arithmetic is involved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct U256Field {
+    modulus: Limbs,
+    /// `-p^-1 mod 2^64`.
+    inv: u64,
+    /// `2^512 mod p`.
+    r2: Limbs,
+}
+
+/// `a + b + carry`, and the carry out.
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let sum = a as u128 + b as u128 + carry as u128;
+    (sum as u64, (sum >> 64) as u64)
+}
+
+/// `a - b - borrow`, and the borrow out.
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let diff = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (diff as u64, (diff >> 127) as u64)
+}
+
+fn add_limbs(a: &Limbs, b: &Limbs) -> (Limbs, u64) {
+    let mut sum = [0; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        let (s, c) = adc(a[i], b[i], carry);
+        sum[i] = s;
+        carry = c;
+    }
+    (sum, carry)
+}
+
+fn sub_limbs(a: &Limbs, b: &Limbs) -> (Limbs, u64) {
+    let mut diff = [0; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        let (d, b) = sbb(a[i], b[i], borrow);
+        diff[i] = d;
+        borrow = b;
+    }
+    (diff, borrow)
+}
+
+fn less_than(a: &Limbs, b: &Limbs) -> bool {
+    sub_limbs(a, b).1 == 1
+}
+
+fn bit_length(a: &Limbs) -> usize {
+    (0..4)
+        .rev()
+        .find(|&i| a[i] != 0)
+        .map_or(0, |i| 64 * i + 64 - a[i].leading_zeros() as usize)
+}
+
+impl U256Field {
+    /// Field modulo the odd prime `modulus`, given as little-endian 64-bit limbs.
+    ///
+    /// The primality of `modulus` is not checked.
+    pub fn from_limbs(modulus: Limbs) -> U256Field {
+        assert!(
+            modulus[0] & 1 == 1 && modulus != [1, 0, 0, 0],
+            "modulus must be an odd prime"
+        );
+        // Newton iteration doubling the number of correct low bits of p^-1
+        let mut inv = 1_u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2_u64.wrapping_sub(modulus[0].wrapping_mul(inv)));
+        }
+        let mut field = U256Field {
+            modulus,
+            inv: inv.wrapping_neg(),
+            r2: [0; 4],
+        };
+        // 2^512 mod p by doubling 1 modulo p
+        let mut r2 = [1, 0, 0, 0];
+        for _ in 0..512 {
+            r2 = field.add(r2, r2);
+        }
+        field.r2 = r2;
+        field
+    }
+
+    /// `a.b.2^-256 mod p`, for `a.b < p.2^256`.
+    fn mont_mul(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        let p = &self.modulus;
+        let mut t = [0_u64; 6];
+        for &bi in b.iter() {
+            let mut carry = 0;
+            for j in 0..4 {
+                let s = t[j] as u128 + a[j] as u128 * bi as u128 + carry as u128;
+                t[j] = s as u64;
+                carry = (s >> 64) as u64;
+            }
+            let (s, c) = adc(t[4], carry, 0);
+            t[4] = s;
+            t[5] = c;
+
+            let m = t[0].wrapping_mul(self.inv);
+            let s = t[0] as u128 + m as u128 * p[0] as u128;
+            let mut carry = (s >> 64) as u64;
+            for j in 1..4 {
+                let s = t[j] as u128 + m as u128 * p[j] as u128 + carry as u128;
+                t[j - 1] = s as u64;
+                carry = (s >> 64) as u64;
+            }
+            let (s, c) = adc(t[4], carry, 0);
+            t[3] = s;
+            t[4] = t[5] + c;
+        }
+        let result = [t[0], t[1], t[2], t[3]];
+        if t[4] != 0 || !less_than(&result, p) {
+            sub_limbs(&result, p).0
+        } else {
+            result
+        }
+    }
+
+    /// `a mod p`, for any `a < 2^256`.
+    fn reduce(&self, a: &Limbs) -> Limbs {
+        self.mont_mul(&self.mont_mul(a, &self.r2), &[1, 0, 0, 0])
+    }
+}
+
+impl Field for U256Field {
+    type E = Limbs;
+
+    fn zero(&self) -> Self::E {
+        [0; 4]
+    }
+
+    fn one(&self) -> Self::E {
+        [1, 0, 0, 0]
+    }
+
+    fn add<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        let (sum, carry) = add_limbs(a.borrow(), b.borrow());
+        if carry == 1 || !less_than(&sum, &self.modulus) {
+            sub_limbs(&sum, &self.modulus).0
+        } else {
+            sum
+        }
+    }
+
+    fn sub<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        let (diff, borrow) = sub_limbs(a.borrow(), b.borrow());
+        if borrow == 1 {
+            add_limbs(&diff, &self.modulus).0
+        } else {
+            diff
+        }
+    }
+
+    fn mul<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        // a.b.2^-256 followed by a multiplication with 2^512 gives a.b
+        self.mont_mul(&self.mont_mul(a.borrow(), b.borrow()), &self.r2)
+    }
+
+    fn pow<A: Borrow<Self::E>, X: Exponent>(&self, a: A, e: X) -> Self::E {
+        generic_mod_pow(self, *a.borrow(), e)
+    }
+
+    fn inv<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        // Fermat's little theorem
+        let exponent = sub_limbs(&self.modulus, &[2, 0, 0, 0]).0;
+        let bits = (0..256).map(|i| (exponent[i / 64] >> (i % 64)) & 1 == 1);
+        self.pow(a, BigExponent::from_bits(bits.collect()))
+    }
+
+    fn op_costs(&self) -> OpCosts {
+        OpCosts {
+            add: 6000,
+            mul: 60_000,
+            inv: 30_000_000,
+        }
+    }
+
+    fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool {
+        lhs.borrow() == rhs.borrow()
+    }
+
+    fn normalize<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        self.reduce(a.borrow())
+    }
+
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        // rejection sampling below the smallest power of two above p
+        let bits = bit_length(&self.modulus);
+        loop {
+            let mut x: Limbs = [rng.gen(), rng.gen(), rng.gen(), rng.gen()];
+            for (i, limb) in x.iter_mut().enumerate() {
+                let kept = bits.saturating_sub(64 * i).min(64);
+                *limb = if kept == 0 { 0 } else { *limb >> (64 - kept) };
+            }
+            if less_than(&x, &self.modulus) {
+                return x;
+            }
+        }
+    }
+}
+
+impl PrimeField for U256Field {
+    type P = Limbs;
+}
+
+impl KnownPrime for U256Field {
+    fn prime(&self) -> Limbs {
+        self.modulus
+    }
+}
+
+impl New<u32> for U256Field {
+    fn new(prime: u32) -> Self {
+        U256Field::from_limbs([prime as u64, 0, 0, 0])
+    }
+}
+
+impl New<u64> for U256Field {
+    fn new(prime: u64) -> Self {
+        U256Field::from_limbs([prime, 0, 0, 0])
+    }
+}
+
+impl New<Limbs> for U256Field {
+    fn new(prime: Limbs) -> Self {
+        U256Field::from_limbs(prime)
+    }
+}
+
+/// Prime given in decimal.
+impl<'a> New<&'a str> for U256Field {
+    fn new(prime: &'a str) -> Self {
+        let mut limbs = [0_u64; 4];
+        for digit in prime.chars() {
+            let digit = digit.to_digit(10).expect("prime must be given in decimal");
+            let mut carry = digit as u64;
+            for limb in limbs.iter_mut() {
+                let x = *limb as u128 * 10 + carry as u128;
+                *limb = x as u64;
+                carry = (x >> 64) as u64;
+            }
+            assert!(carry == 0, "prime does not fit in 256 bits");
+        }
+        U256Field::from_limbs(limbs)
+    }
+}
+
+impl Encode<u32> for U256Field {
+    fn encode(&self, x: u32) -> Self::E {
+        self.reduce(&[x as u64, 0, 0, 0])
+    }
+}
+
+impl Encode<u64> for U256Field {
+    fn encode(&self, x: u64) -> Self::E {
+        self.reduce(&[x, 0, 0, 0])
+    }
+}
+
+/// Values given as little-endian 64-bit limbs, reduced modulo the prime.
+impl Encode<Limbs> for U256Field {
+    fn encode(&self, x: Limbs) -> Self::E {
+        self.reduce(&x)
+    }
+}
+
+impl Decode<u32> for U256Field {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> u32 {
+        ::std::convert::TryFrom::try_from(Decode::<u64>::decode(self, x))
+            .expect("element does not fit in a u32")
+    }
+}
+
+impl Decode<u64> for U256Field {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> u64 {
+        let x = x.borrow();
+        assert!(x[1..] == [0, 0, 0], "element does not fit in a u64");
+        x[0]
+    }
+}
+
+impl Decode<Limbs> for U256Field {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> Limbs {
+        *x.borrow()
+    }
+}
+
+impl IntegerCoding for U256Field {
+    fn encode_signed(&self, x: i64) -> Self::E {
+        let magnitude = self.encode(x.unsigned_abs());
+        if x < 0 {
+            self.sub(self.zero(), magnitude)
+        } else {
+            magnitude
+        }
+    }
+
+    fn decode_signed<E: Borrow<Self::E>>(&self, x: E) -> i64 {
+        let x = x.borrow();
+        let negated = self.sub(self.zero(), x);
+        // x is negative if it is larger than p - x
+        if less_than(&negated, x) {
+            let magnitude: u64 = self.decode(negated);
+            assert!(magnitude <= 1 << 63, "element does not fit in an i64");
+            (magnitude as i64).wrapping_neg()
+        } else {
+            let value: u64 = self.decode(x);
+            ::std::convert::TryFrom::try_from(value).expect("element does not fit in an i64")
+        }
+    }
+
+    fn decode_u128<E: Borrow<Self::E>>(&self, x: E) -> u128 {
+        let x = x.borrow();
+        assert!(x[2..] == [0, 0], "element does not fit in a u128");
+        (x[1] as u128) << 64 | x[0] as u128
+    }
+}
+
+impl ElementBytes for U256Field {
+    fn element_len(&self) -> usize {
+        32
+    }
+
+    fn write_element<E: Borrow<Self::E>>(&self, e: E, bytes: &mut Vec<u8>) {
+        for limb in self.normalize(e).iter() {
+            bytes.extend_from_slice(&limb.to_le_bytes());
+        }
+    }
+
+    fn read_element(&self, bytes: &[u8]) -> Option<Self::E> {
+        assert_eq!(bytes.len(), 32);
+        let mut x = [0; 4];
+        for (limb, chunk) in x.iter_mut().zip(bytes.chunks(8)) {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            *limb = u64::from_le_bytes(word);
+        }
+        if less_than(&x, &self.modulus) {
+            Some(x)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{SliceDecode, SliceEncode};
+    use rand::{ChaChaRng, SeedableRng};
+
+    /// Order of the secp256k1 group, close to `2^256`.
+    const SECP256K1_ORDER: Limbs = [
+        0xbfd25e8cd0364141,
+        0xbaaedce6af48a03b,
+        0xfffffffffffffffe,
+        0xffffffffffffffff,
+    ];
+
+    #[test]
+    fn test_small_prime() {
+        let zp = U256Field::new(17_u32);
+        let decoded: Vec<u32> = zp.decode_slice(zp.encode_slice([8_u32, 13]));
+        assert_eq!(decoded, [8, 13]);
+        assert_eq!(
+            Decode::<u32>::decode(&zp, zp.add(zp.encode(8_u32), zp.encode(13_u32))),
+            4
+        );
+        assert_eq!(
+            Decode::<u32>::decode(&zp, zp.sub(zp.encode(8_u32), zp.encode(13_u32))),
+            12
+        );
+        assert_eq!(
+            Decode::<u32>::decode(&zp, zp.mul(zp.encode(8_u32), zp.encode(5_u32))),
+            6
+        );
+        assert_eq!(Decode::<u32>::decode(&zp, zp.inv(zp.encode(2_u32))), 9);
+        assert_eq!(Decode::<u32>::decode(&zp, zp.pow(zp.encode(2_u32), 6)), 13);
+        assert_eq!(zp.normalize([35, 0, 0, 0]), [1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_field_laws() {
+        let mut rng = ChaChaRng::from_seed(&[256]);
+        ::test_utils::check_field_laws(&U256Field::new(433_u32), 50, &mut rng);
+        ::test_utils::check_field_laws(&U256Field::new(SECP256K1_ORDER), 50, &mut rng);
+        // agrees with the specialised Mersenne field
+        let field = U256Field::new(::fields::Fp61::MODULUS);
+        for &(a, b) in &[
+            (3, 5),
+            (1 << 60, 1 << 59),
+            (::fields::Fp61::MODULUS - 1, 987654321),
+        ] {
+            let product = field.mul(field.encode(a), field.encode(b));
+            assert_eq!(
+                Decode::<u64>::decode(&field, product),
+                ::fields::Fp61.mul(a, b)
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_prime() {
+        let field = U256Field::new(
+            "115792089237316195423570985008687907852837564279074904382605163141518161494337",
+        );
+        assert_eq!(field.prime(), SECP256K1_ORDER);
+        let minus_one = field.sub(field.zero(), field.one());
+        assert_eq!(field.mul(minus_one, minus_one), field.one());
+        assert_eq!(field.add(minus_one, [2, 0, 0, 0]), field.one());
+        assert_eq!(
+            field.encode([u64::MAX; 4]),
+            [0x402da1732fc9bebe, 0x4551231950b75fc4, 1, 0,]
+        );
+        let x = field.sample(&mut ChaChaRng::from_seed(&[1]));
+        assert_eq!(field.mul(x, field.inv(x)), field.one());
+    }
+
+    #[test]
+    fn test_signed() {
+        let zp = U256Field::new(17_u32);
+        for i in -8..9 {
+            assert_eq!(zp.decode_signed(zp.encode_signed(i)), i);
+        }
+        let field = U256Field::new(SECP256K1_ORDER);
+        for &i in &[-8, -1, 0, 1, 8, i64::MIN, i64::MAX] {
+            assert_eq!(field.decode_signed(field.encode_signed(i)), i);
+        }
+        let zp = U256Field::new(433_u32);
+        let elements = zp.encode_slice([0_u32, 216, 217, 432]);
+        let signed: Vec<i64> = elements.iter().map(|e| zp.decode_signed(e)).collect();
+        assert_eq!(signed, [0, 216, -216, -1]);
+        assert_eq!(zp.decode_u128(zp.encode(432_u32)), 432);
+    }
+
+    #[test]
+    fn test_element_bytes() {
+        let field = U256Field::new(SECP256K1_ORDER);
+        let mut bytes = vec![];
+        field.write_element(field.sub(field.zero(), field.one()), &mut bytes);
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(bytes[0], 0x40);
+        assert_eq!(bytes[31], 0xff);
+        assert_eq!(
+            field.read_element(&bytes),
+            Some(field.sub(field.zero(), field.one()))
+        );
+        bytes[0] += 1;
+        assert_eq!(field.read_element(&bytes), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be an odd prime")]
+    fn test_even_modulus() {
+        U256Field::new(16_u32);
+    }
+}
@@ -0,0 +1,225 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Natural prime field for moduli up to 63 bits.
+
+use rand;
+use std::borrow::Borrow;
+
+use super::{
+    BigExponent, Decode, ElementBytes, Encode, Exponent, Field, IntegerCoding, KnownPrime, New,
+    OpCosts, PrimeField,
+};
+use numtheory::generic_mod_pow;
+
+/// Prime field over an arbitrary prime `p < 2^63`, in natural representation.
+///
+/// Like `NaturalPrimeField` no encoding is involved, but products are computed
+/// with 128-bit intermediates so that moduli beyond `2^31` do not overflow.
+/// All values are kept in canonical form, in the `0..p` range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct U64Field(pub u64);
+
+impl U64Field {
+    fn reduce_wide(&self, x: u128) -> u64 {
+        (x % self.0 as u128) as u64
+    }
+}
+
+impl Field for U64Field {
+    type E = u64;
+
+    fn zero(&self) -> Self::E {
+        0
+    }
+
+    fn one(&self) -> Self::E {
+        1
+    }
+
+    fn add<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        // p < 2^63 so the sum of two canonical values does not overflow
+        (a.borrow() + b.borrow()) % self.0
+    }
+
+    fn sub<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        (a.borrow() + (self.0 - b.borrow())) % self.0
+    }
+
+    fn mul<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        self.reduce_wide(*a.borrow() as u128 * *b.borrow() as u128)
+    }
+
+    fn pow<A: Borrow<Self::E>, X: Exponent>(&self, a: A, e: X) -> Self::E {
+        generic_mod_pow(self, *a.borrow(), e)
+    }
+
+    fn inv<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        // Fermat's little theorem
+        self.pow(a, BigExponent::from(self.0 - 2))
+    }
+
+    fn op_costs(&self) -> OpCosts {
+        // exponentiation takes a number of steps linear in the size of the prime
+        let bits = u64::from(64 - self.0.leading_zeros());
+        OpCosts {
+            add: 2800,
+            mul: 4800,
+            inv: 100_000 + 14_000 * bits,
+        }
+    }
+
+    fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool {
+        lhs.borrow() == rhs.borrow()
+    }
+
+    fn normalize<A: Borrow<Self::E>>(&self, a: A) -> Self::E {
+        a.borrow() % self.0
+    }
+
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        rng.gen_range(0, self.0)
+    }
+
+    fn sample_nonzero<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        rng.gen_range(1, self.0)
+    }
+}
+
+impl PrimeField for U64Field {
+    type P = u64;
+}
+
+impl KnownPrime for U64Field {
+    fn prime(&self) -> u64 {
+        self.0
+    }
+}
+
+impl New<u32> for U64Field {
+    fn new(prime: u32) -> Self {
+        U64Field(prime as u64)
+    }
+}
+
+impl New<u64> for U64Field {
+    fn new(prime: u64) -> Self {
+        U64Field(prime)
+    }
+}
+
+impl Encode<u32> for U64Field {
+    fn encode(&self, x: u32) -> Self::E {
+        x as u64 % self.0
+    }
+}
+
+impl Encode<u64> for U64Field {
+    fn encode(&self, x: u64) -> Self::E {
+        x % self.0
+    }
+}
+
+impl Decode<u32> for U64Field {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> u32 {
+        ::std::convert::TryFrom::try_from(*x.borrow()).expect("element does not fit in a u32")
+    }
+}
+
+impl Decode<u64> for U64Field {
+    fn decode<E: Borrow<Self::E>>(&self, x: E) -> u64 {
+        *x.borrow()
+    }
+}
+
+impl IntegerCoding for U64Field {
+    fn encode_signed(&self, x: i64) -> Self::E {
+        // p < 2^63 fits in an i64
+        x.rem_euclid(self.0 as i64) as u64
+    }
+
+    fn decode_signed<E: Borrow<Self::E>>(&self, x: E) -> i64 {
+        let x = *x.borrow();
+        if x > self.0 / 2 {
+            x as i64 - self.0 as i64
+        } else {
+            x as i64
+        }
+    }
+
+    fn decode_u128<E: Borrow<Self::E>>(&self, x: E) -> u128 {
+        *x.borrow() as u128
+    }
+}
+
+impl ElementBytes for U64Field {
+    fn element_len(&self) -> usize {
+        8
+    }
+
+    fn write_element<E: Borrow<Self::E>>(&self, e: E, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.normalize(e).to_le_bytes());
+    }
+
+    fn read_element(&self, bytes: &[u8]) -> Option<Self::E> {
+        assert_eq!(bytes.len(), 8);
+        let mut word = [0; 8];
+        word.copy_from_slice(bytes);
+        let x = u64::from_le_bytes(word);
+        if x < self.0 {
+            Some(x)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+all_fields_test!(U64Field);
+
+#[test]
+fn test_large_prime() {
+    use fields::Fp61;
+    use numtheory::Primality;
+    use ShamirSecretSharing;
+
+    // largest prime below 2^63
+    const P: u64 = (1 << 63) - 25;
+    assert!(P.is_probable_prime());
+    let field = U64Field(P);
+    assert_eq!(field.add(P - 1, P - 1), P - 2);
+    assert_eq!(field.sub(3_u64, 5_u64), P - 2);
+    assert_eq!(field.mul(P - 1, P - 1), 1);
+    assert_eq!(field.mul(1_u64 << 62, 2_u64), 25);
+    for &x in &[1, 2, 12345, P - 1, P - 2] {
+        assert_eq!(field.mul(x, field.inv(x)), 1);
+    }
+
+    // agrees with the specialised Mersenne field
+    let field = U64Field(Fp61::MODULUS);
+    for &(a, b) in &[(3, 5), (1 << 60, 1 << 59), (Fp61::MODULUS - 1, 987654321)] {
+        assert_eq!(field.mul(a, b), Fp61.mul(a, b));
+        assert_eq!(field.inv(a), Fp61.inv(a));
+    }
+
+    let tss = ShamirSecretSharing {
+        threshold: 3,
+        share_count: 8,
+        field: U64Field(P),
+    };
+    let secret = P - 42;
+    let shares = tss.share(secret);
+    assert_eq!(tss.reconstruct(&[1, 2, 3, 4, 5], &shares[1..6]), secret);
+}
+
+#[test]
+#[should_panic(expected = "element does not fit in a u32")]
+fn test_decode_too_large() {
+    let field = U64Field((1 << 63) - 25);
+    Decode::<u32>::decode(&field, 1_u64 << 32);
+}
@@ -0,0 +1,223 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Stable identifiers of the parameters of schemes.
+//!
+//! Parties of a distributed deployment each instantiate their scheme, possibly
+//! over different field backends. Comparing the `SchemeId`s of their schemes
+//! before exchanging any shares checks that they agree on all parameters, since
+//! shares of mismatched schemes silently reconstruct to garbage.
+//!
+//! The identifier is a 64-bit FNV-1a hash of the normalized parameters: field
+//! elements are hashed in their canonical byte representation without trailing
+//! zeros, so that it does not depend on the field backend, and layouts are
+//! reduced to the exponents they place the secrets at. It catches accidents but
+//! not deliberate forgeries.
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use fields::ElementBytes;
+use packed::PackedSecretSharing;
+use shamir::ShamirSecretSharing;
+
+/// Fingerprint of the parameters of a scheme.
+pub type Fingerprint = u64;
+
+/// Identifier of the parameters of a scheme, equal for schemes with identical
+/// parameters over any field backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SchemeId(pub Fingerprint);
+
+impl fmt::Display for SchemeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// FNV-1a hash of the parameters of a scheme.
+struct FingerprintHasher<'a, F: ElementBytes + 'a> {
+    field: &'a F,
+    state: u64,
+}
+
+impl<'a, F: ElementBytes> FingerprintHasher<'a, F> {
+    /// Hasher for a scheme of the given `kind` over `field`, starting with the
+    /// modulus as `-1`.
+    fn new(field: &'a F, kind: &str) -> FingerprintHasher<'a, F> {
+        let mut hasher = FingerprintHasher {
+            field,
+            state: 0xcbf2_9ce4_8422_2325,
+        };
+        hasher.bytes(kind.as_bytes());
+        hasher.element(&field.sub(field.zero(), field.one()));
+        hasher
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        // length first, so that consecutive inputs cannot be confused
+        for &byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.state = (self.state ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn count(&mut self, count: usize) {
+        self.bytes(&(count as u64).to_le_bytes());
+    }
+
+    /// Hash `element` without the trailing zeros of its representation, which
+    /// depend on the backend.
+    fn element(&mut self, element: &F::E) {
+        let mut bytes = vec![];
+        self.field.write_element(element, &mut bytes);
+        while bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        self.bytes(&bytes);
+    }
+}
+
+impl<F> ShamirSecretSharing<F>
+where
+    F: ElementBytes,
+    F::E: Clone,
+{
+    /// Fingerprint of the threshold, share count and prime of the scheme.
+    pub fn fingerprint(&self) -> Fingerprint {
+        let mut hasher = FingerprintHasher::new(&self.field, "shamir");
+        hasher.count(self.threshold);
+        hasher.count(self.share_count);
+        hasher.state
+    }
+
+    /// Identifier of the parameters of the scheme.
+    pub fn scheme_id(&self) -> SchemeId {
+        SchemeId(self.fingerprint())
+    }
+
+    /// Whether `other` has the same parameters as this scheme, possibly over
+    /// another field backend, so that their shares can be mixed.
+    pub fn is_compatible<G>(&self, other: &ShamirSecretSharing<G>) -> bool
+    where
+        G: ElementBytes,
+        G::E: Clone,
+    {
+        self.scheme_id() == other.scheme_id()
+    }
+}
+
+impl<F: ElementBytes> PackedSecretSharing<F> {
+    /// Fingerprint of the threshold, share and secret counts, prime, roots of unity
+    /// and layout of the scheme.
+    pub fn fingerprint(&self) -> Fingerprint {
+        let mut hasher = FingerprintHasher::new(&self.field, "packed");
+        hasher.count(self.threshold);
+        hasher.count(self.share_count);
+        hasher.count(self.secret_count);
+        hasher.element(&self.omega_secrets);
+        hasher.element(&self.omega_shares);
+        for exponent in self.layout().exponents(self.secret_count) {
+            hasher.count(exponent);
+        }
+        hasher.state
+    }
+
+    /// Identifier of the parameters of the scheme.
+    pub fn scheme_id(&self) -> SchemeId {
+        SchemeId(self.fingerprint())
+    }
+
+    /// Whether `other` has the same parameters as this scheme, possibly over
+    /// another field backend, so that their shares can be mixed.
+    pub fn is_compatible<G: ElementBytes>(&self, other: &PackedSecretSharing<G>) -> bool {
+        self.scheme_id() == other.scheme_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Encode, Fp61, MontgomeryField32, NaturalPrimeField, New, U64Field};
+    use packed::{SecretLayout, PSS_4_26_3};
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(41),
+        };
+        let other = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: MontgomeryField32::new(41),
+        };
+        assert!(tss.is_compatible(&other));
+        assert_eq!(other.scheme_id(), tss.scheme_id());
+        let other = ShamirSecretSharing {
+            threshold: 3,
+            share_count: 6,
+            field: U64Field(41),
+        };
+        assert!(!tss.is_compatible(&other));
+        let other = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: U64Field(43),
+        };
+        assert!(!tss.is_compatible(&other));
+    }
+
+    #[test]
+    fn test_packed() {
+        let pss = &PSS_4_26_3;
+        let field = MontgomeryField32::new(433);
+        let montgomery = PackedSecretSharing::with_roots(
+            4,
+            3,
+            26,
+            field.clone(),
+            field.encode(354),
+            field.encode(17),
+        );
+        assert!(pss.is_compatible(&montgomery));
+        assert!(montgomery.is_compatible(pss));
+
+        // layouts are compared by the positions of the secrets
+        let strided = PSS_4_26_3
+            .clone()
+            .with_layout(SecretLayout::Strided(1))
+            .unwrap();
+        assert!(pss.is_compatible(&strided));
+        let custom = PSS_4_26_3
+            .clone()
+            .with_layout(SecretLayout::Custom(vec![1, 2, 4]))
+            .unwrap();
+        assert!(!pss.is_compatible(&custom));
+        let other_root =
+            PackedSecretSharing::with_roots(4, 3, 26, NaturalPrimeField(433), 354, 289);
+        assert!(!pss.is_compatible(&other_root));
+    }
+
+    #[test]
+    fn test_scheme_id() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: Fp61,
+        };
+        let id = tss.scheme_id();
+        assert_eq!(id, SchemeId(tss.fingerprint()));
+        assert_eq!(id.to_string(), format!("{:016x}", id.0));
+        assert_eq!(id.to_string().len(), 16);
+    }
+}
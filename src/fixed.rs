@@ -0,0 +1,322 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Fixed-point encoding of signed real numbers as field elements.
+//!
+//! A value `x` is scaled by `2^fractional_bits` and rounded to an integer, which is
+//! embedded like in two's complement: non-negative integers as themselves and
+//! negative ones as `p - |x|`. Elements up to `(p - 1) / 2` thus decode to
+//! non-negative values and the others to negative ones.
+//!
+//! The encoding is additive, so sums of shares decode as expected as long as the
+//! result stays in range. Products carry twice as many fractional bits and must be
+//! decoded with `FixedPoint::product`.
+//!
+//! Only the byte representation of elements is used, so this works for every field
+//! implementing `ElementBytes`.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+use fields::{ElementBytes, Encode, Field};
+use packed::PackedSecretSharing;
+use shamir::ShamirSecretSharing;
+
+/// Failure to encode or decode a fixed-point value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedPointError {
+    /// The value to encode is infinite or NaN.
+    NotFinite,
+    /// The scaled value does not fit in the field, or does not fit in 64 bits.
+    Overflow,
+}
+
+impl fmt::Display for FixedPointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FixedPointError::NotFinite => write!(f, "value is not finite"),
+            FixedPointError::Overflow => write!(f, "value is out of range"),
+        }
+    }
+}
+
+impl Error for FixedPointError {}
+
+/// Fixed-point encoding with `fractional_bits` bits after the binary point.
+///
+/// # Example:
+///
+/// ```
+///    use threshold_secret_sharing::*;
+///    let field = NaturalPrimeField(2147483647);
+///    let encoding = FixedPoint::new(16);
+///    let x = encoding.encode(&field, -1.25).unwrap();
+///    assert_eq!(x, 2147483647 - 81920);
+///    assert_eq!(encoding.decode(&field, x), Ok(-1.25));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedPoint {
+    /// Number of bits after the binary point.
+    pub fractional_bits: u32,
+}
+
+impl FixedPoint {
+    pub fn new(fractional_bits: u32) -> FixedPoint {
+        FixedPoint { fractional_bits }
+    }
+
+    /// Encoding of the product of two values with this encoding.
+    pub fn product(&self) -> FixedPoint {
+        FixedPoint::new(2 * self.fractional_bits)
+    }
+
+    fn scale(&self) -> f64 {
+        (self.fractional_bits as f64).exp2()
+    }
+
+    /// Element representing `x`, rounded to the nearest multiple of
+    /// `2^-fractional_bits`.
+    ///
+    /// Fails if the scaled value is not below `p / 2` in absolute value.
+    pub fn encode<F>(&self, field: &F, x: f64) -> Result<F::E, FixedPointError>
+    where
+        F: Field + ElementBytes,
+    {
+        if !x.is_finite() {
+            return Err(FixedPointError::NotFinite);
+        }
+        let scaled = (x * self.scale()).round();
+        if scaled.abs() >= 64_f64.exp2() {
+            return Err(FixedPointError::Overflow);
+        }
+        let mut bytes = (scaled.abs() as u64).to_le_bytes().to_vec();
+        let len = field.element_len();
+        if bytes.iter().skip(len).any(|&byte| byte != 0) {
+            return Err(FixedPointError::Overflow);
+        }
+        bytes.resize(len, 0);
+        let magnitude = field
+            .read_element(&bytes)
+            .ok_or(FixedPointError::Overflow)?;
+        let negated = field.sub(field.zero(), &magnitude);
+        if compare(field, &magnitude, &negated) == Ordering::Greater {
+            return Err(FixedPointError::Overflow);
+        }
+        if scaled < 0.0 {
+            Ok(negated)
+        } else {
+            Ok(magnitude)
+        }
+    }
+
+    /// Value represented by `e`.
+    ///
+    /// Fails if its absolute value, once scaled, does not fit in 64 bits, which can
+    /// only happen for fields larger than that.
+    pub fn decode<F, E>(&self, field: &F, e: E) -> Result<f64, FixedPointError>
+    where
+        F: Field + ElementBytes,
+        E: Borrow<F::E>,
+    {
+        let e = e.borrow();
+        let negated = field.sub(field.zero(), e);
+        let (magnitude, sign) = if compare(field, e, &negated) == Ordering::Greater {
+            (negated, -1.0)
+        } else {
+            (field.normalize(e), 1.0)
+        };
+        let mut bytes = vec![];
+        field.write_element(magnitude, &mut bytes);
+        if bytes.iter().skip(8).any(|&byte| byte != 0) {
+            return Err(FixedPointError::Overflow);
+        }
+        bytes.resize(8, 0);
+        let mut word = [0; 8];
+        word.copy_from_slice(&bytes);
+        Ok(sign * u64::from_le_bytes(word) as f64 / self.scale())
+    }
+}
+
+/// Order of `a` and `b` as integers in `0..p`.
+fn compare<F>(field: &F, a: &F::E, b: &F::E) -> Ordering
+where
+    F: Field + ElementBytes,
+{
+    let (mut a_bytes, mut b_bytes) = (vec![], vec![]);
+    field.write_element(a, &mut a_bytes);
+    field.write_element(b, &mut b_bytes);
+    a_bytes.iter().rev().cmp(b_bytes.iter().rev())
+}
+
+impl<F> ShamirSecretSharing<F>
+where
+    F: Field + Encode<u32> + ElementBytes,
+    F::E: Clone,
+{
+    /// Generate `share_count` shares of the real number `secret` encoded with
+    /// `encoding`.
+    pub fn share_fixed(
+        &self,
+        secret: f64,
+        encoding: &FixedPoint,
+    ) -> Result<Vec<F::E>, FixedPointError> {
+        Ok(self.share(encoding.encode(&self.field, secret)?))
+    }
+
+    /// Reconstruct a real number shared with `share_fixed`, as with `reconstruct`.
+    pub fn reconstruct_fixed(
+        &self,
+        indices: &[usize],
+        shares: &[F::E],
+        encoding: &FixedPoint,
+    ) -> Result<f64, FixedPointError> {
+        encoding.decode(&self.field, self.reconstruct(indices, shares))
+    }
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field + Encode<u32> + ElementBytes + Clone,
+    F::E: Clone,
+{
+    /// Generate `share_count` shares of the real numbers `secrets` encoded with
+    /// `encoding`, where the length of `secrets` must be `secret_count`.
+    pub fn share_fixed(
+        &self,
+        secrets: &[f64],
+        encoding: &FixedPoint,
+    ) -> Result<Vec<F::E>, FixedPointError> {
+        let secrets = secrets
+            .iter()
+            .map(|&x| encoding.encode(&self.field, x))
+            .collect::<Result<Vec<F::E>, FixedPointError>>()?;
+        Ok(self.share(&secrets))
+    }
+
+    /// Reconstruct real numbers shared with `share_fixed`, as with `reconstruct`.
+    pub fn reconstruct_fixed(
+        &self,
+        indices: &[u32],
+        shares: &[F::E],
+        encoding: &FixedPoint,
+    ) -> Result<Vec<f64>, FixedPointError> {
+        self.reconstruct(indices, shares)
+            .iter()
+            .map(|e| encoding.decode(&self.field, e))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Decode, Fp61, MontgomeryField32, NaturalPrimeField, New};
+    use packed::PSS_4_26_3;
+
+    #[test]
+    fn test_roundtrip() {
+        let encoding = FixedPoint::new(8);
+        let field = NaturalPrimeField(2147483647);
+        for &x in &[0.0, 1.0, -1.0, 3.5, -1000.25, 4194303.0, -4194303.0] {
+            let e = encoding.encode(&field, x).unwrap();
+            assert_eq!(encoding.decode(&field, e), Ok(x));
+        }
+        // rounding to the nearest multiple of 2^-8
+        let e = encoding.encode(&field, 0.3).unwrap();
+        assert_eq!(e, 77);
+        assert_eq!(encoding.decode(&field, e), Ok(77.0 / 256.0));
+
+        let field = MontgomeryField32::new(433);
+        let encoding = FixedPoint::new(2);
+        let e = encoding.encode(&field, -0.75).unwrap();
+        assert_eq!(field.decode(e), 430);
+        assert_eq!(encoding.decode(&field, e), Ok(-0.75));
+
+        let encoding = FixedPoint::new(20);
+        let e = encoding.encode(&Fp61, -123456.789).unwrap();
+        let x = encoding.decode(&Fp61, e).unwrap();
+        assert!((x + 123456.789).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_errors() {
+        let field = NaturalPrimeField(433);
+        let encoding = FixedPoint::new(1);
+        assert_eq!(encoding.encode(&field, 108.0), Ok(216));
+        assert_eq!(encoding.encode(&field, -108.0), Ok(217));
+        assert_eq!(
+            encoding.encode(&field, 108.5),
+            Err(FixedPointError::Overflow)
+        );
+        assert_eq!(
+            encoding.encode(&field, -1e30),
+            Err(FixedPointError::Overflow)
+        );
+        assert_eq!(
+            encoding.encode(&field, f64::NAN),
+            Err(FixedPointError::NotFinite)
+        );
+        assert_eq!(
+            encoding.encode(&field, f64::INFINITY),
+            Err(FixedPointError::NotFinite)
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let field = NaturalPrimeField(2147483647);
+        let encoding = FixedPoint::new(8);
+        let a = encoding.encode(&field, -2.5).unwrap();
+        let b = encoding.encode(&field, 4.25).unwrap();
+        assert_eq!(encoding.decode(&field, field.add(a, b)), Ok(1.75));
+        assert_eq!(encoding.decode(&field, field.sub(a, b)), Ok(-6.75));
+        assert_eq!(
+            encoding.product().decode(&field, field.mul(a, b)),
+            Ok(-10.625)
+        );
+    }
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            field: NaturalPrimeField(2147483647),
+        };
+        let encoding = FixedPoint::new(10);
+        let shares = tss.share_fixed(-3.140625, &encoding).unwrap();
+        assert_eq!(
+            tss.reconstruct_fixed(&[0, 2, 4], &[shares[0], shares[2], shares[4]], &encoding),
+            Ok(-3.140625)
+        );
+        assert_eq!(
+            tss.share_fixed(f64::NAN, &encoding),
+            Err(FixedPointError::NotFinite)
+        );
+    }
+
+    #[test]
+    fn test_packed() {
+        let pss = &PSS_4_26_3;
+        let encoding = FixedPoint::new(2);
+        let secrets = [-1.5, 0.25, 50.0];
+        let shares = pss.share_fixed(&secrets, &encoding).unwrap();
+        let indices: Vec<u32> = (0..8).collect();
+        assert_eq!(
+            pss.reconstruct_fixed(&indices, &shares[..8], &encoding),
+            Ok(secrets.to_vec())
+        );
+        assert_eq!(
+            pss.share_fixed(&[0.0, 60.0, 0.0], &encoding),
+            Err(FixedPointError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,499 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Hybrid sharing of large secrets, encrypting them under a fresh key of which only
+//! the key material is shared.
+//!
+//! Sharing a payload of several kilobytes directly would take one field element per
+//! few bytes in every share. Instead, as in `age`, random key material is sampled
+//! and shared with the Shamir scheme, a key is derived from it with a key derivation
+//! function such as HKDF, and the payload is encrypted under that key with an AEAD.
+//! Every recipient receives an `EncryptedShareBundle` holding its shares of the key
+//! material together with the ciphertext, so that any `reconstruct_limit` bundles
+//! suffice to recover the payload.
+//!
+//! Both primitives are pluggable through the `Aead` and `KeyDerivation` traits; an
+//! HKDF-SHA256 implementation of the latter is included when the `sha2` feature is
+//! activated.
+
+use fields::ForwardSecureRng;
+use rand::Rng;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use fields::{ElementBytes, Encode, Field};
+use shamir::ShamirSecretSharing;
+use share::{index_u32, ShareIndex};
+
+/// Context string from which keys are derived.
+const KEY_INFO: &[u8] = b"threshold-secret-sharing hybrid payload key";
+
+/// Bits of entropy of the key material.
+const KEY_MATERIAL_BITS: usize = 256;
+
+/// Authenticated encryption with associated data, used for encrypting payloads.
+///
+/// Every key is freshly derived and used for a single encryption, so
+/// implementations may use a fixed nonce.
+pub trait Aead {
+    /// Length of keys in bytes.
+    fn key_len(&self) -> usize;
+
+    /// Encryption of `plaintext` under `key`, also authenticating `associated_data`.
+    fn seal(&self, key: &[u8], plaintext: &[u8], associated_data: &[u8]) -> Vec<u8>;
+
+    /// Decryption of `ciphertext` under `key`, or `None` if it or
+    /// `associated_data` is not authentic.
+    fn open(&self, key: &[u8], ciphertext: &[u8], associated_data: &[u8]) -> Option<Vec<u8>>;
+}
+
+/// Key derivation function, used for deriving keys from shared key material.
+pub trait KeyDerivation {
+    /// Key of `len` bytes derived from `material` for the context `info`.
+    fn derive(&self, material: &[u8], info: &[u8], len: usize) -> Vec<u8>;
+}
+
+/// HKDF with SHA-256 as a `KeyDerivation`, with an empty salt.
+#[cfg(feature = "sha2")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HkdfSha256;
+
+#[cfg(feature = "sha2")]
+impl KeyDerivation for HkdfSha256 {
+    fn derive(&self, material: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+        hkdf_sha256(&[], material, info, len)
+    }
+}
+
+#[cfg(feature = "sha2")]
+fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+    let mut block = [0; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.iter().map(|b| b ^ 0x36).collect::<Vec<u8>>());
+    for part in data {
+        inner.update(part);
+    }
+    let mut outer = Sha256::new();
+    outer.update(block.iter().map(|b| b ^ 0x5c).collect::<Vec<u8>>());
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// HKDF as specified in RFC 5869.
+#[cfg(feature = "sha2")]
+fn hkdf_sha256(salt: &[u8], material: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+    assert!(len <= 255 * 32);
+    let prk = hmac_sha256(salt, &[material]);
+    let mut okm = Vec::with_capacity(len);
+    let mut block: Vec<u8> = vec![];
+    for counter in 1..=len.div_ceil(32) {
+        block = hmac_sha256(&prk, &[&block, info, &[counter as u8]]).to_vec();
+        okm.extend_from_slice(&block);
+    }
+    okm.truncate(len);
+    okm
+}
+
+/// Failure to recover a payload from bundles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HybridError {
+    /// Only `got` bundles were given, while `required` are needed.
+    NotEnoughShares { required: usize, got: usize },
+    /// The bundles carry different ciphertexts or numbers of key shares, or some
+    /// index is repeated.
+    InconsistentBundles,
+    /// The ciphertext could not be decrypted, because the bundles were tampered with
+    /// or do not all come from the same sharing.
+    Decryption,
+}
+
+impl fmt::Display for HybridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HybridError::NotEnoughShares { required, got } => {
+                write!(f, "{} bundles are needed but only {} given", required, got)
+            }
+            HybridError::InconsistentBundles => write!(f, "bundles are inconsistent"),
+            HybridError::Decryption => write!(f, "payload could not be decrypted"),
+        }
+    }
+}
+
+impl Error for HybridError {}
+
+/// Shares of the key material and encrypted payload given to a single recipient.
+pub struct EncryptedShareBundle<F: Field> {
+    /// Index of the recipient, as for the shares of the underlying scheme.
+    pub index: ShareIndex,
+    /// Share of every element of the key material.
+    pub key_shares: Vec<F::E>,
+    /// Encrypted payload, the same in every bundle.
+    pub ciphertext: Vec<u8>,
+}
+
+impl<F: Field + ElementBytes> EncryptedShareBundle<F> {
+    /// Serialize the bundle as the index and the number of key shares, both as
+    /// little-endian `u32`, followed by the key shares using `ElementBytes` and the
+    /// ciphertext.
+    pub fn to_bytes(&self, field: &F) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            8 + self.key_shares.len() * field.element_len() + self.ciphertext.len(),
+        );
+        bytes.extend_from_slice(&index_u32(self.index).to_le_bytes());
+        let count = u32::try_from(self.key_shares.len()).expect("too many key shares");
+        bytes.extend_from_slice(&count.to_le_bytes());
+        for share in &self.key_shares {
+            field.write_element(share, &mut bytes);
+        }
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    /// Bundle serialized by `to_bytes`, or `None` if `bytes` is malformed.
+    pub fn from_bytes(field: &F, bytes: &[u8]) -> Option<EncryptedShareBundle<F>> {
+        let read_u32 = |offset: usize| {
+            let word = bytes.get(offset..offset + 4)?;
+            Some(u32::from_le_bytes([word[0], word[1], word[2], word[3]]) as usize)
+        };
+        let index = read_u32(0)?;
+        let count = read_u32(4)?;
+        let element_len = field.element_len();
+        let end = count.checked_mul(element_len)?.checked_add(8)?;
+        let key_shares = bytes
+            .get(8..end)?
+            .chunks(element_len)
+            .map(|chunk| field.read_element(chunk))
+            .collect::<Option<Vec<F::E>>>()?;
+        Some(EncryptedShareBundle {
+            index,
+            key_shares,
+            ciphertext: bytes[end..].to_vec(),
+        })
+    }
+}
+
+element_impls!(Clone, PartialEq, Debug for EncryptedShareBundle { index, key_shares, ciphertext });
+
+/// Parameters for hybrid sharing, consisting of the Shamir scheme used for the key
+/// material and of the cryptographic primitives.
+///
+/// The field only needs to be large enough for the number of shares, since the key
+/// material is made of as many elements as needed for 256 bits of entropy.
+#[derive(Debug)]
+pub struct HybridSecretSharing<F, A, K>
+where
+    F: Field,
+    F::E: Clone,
+{
+    /// Scheme with which the key material is shared.
+    pub scheme: ShamirSecretSharing<F>,
+    /// Encryption of the payload.
+    pub aead: A,
+    /// Derivation of the key from the key material.
+    pub kdf: K,
+}
+
+impl<F, A, K> HybridSecretSharing<F, A, K>
+where
+    F: Field + Encode<u32> + ElementBytes,
+    F::E: Clone,
+    A: Aead,
+    K: KeyDerivation,
+{
+    /// Minimum number of bundles required to recover the payload.
+    pub fn reconstruct_limit(&self) -> usize {
+        self.scheme.reconstruct_limit()
+    }
+
+    /// Number of field elements making up the key material, enough for 256 bits of
+    /// entropy.
+    pub fn key_material_len(&self) -> usize {
+        // elements are uniform below p, and p - 1 has as many bits as p
+        let field = &self.scheme.field;
+        let mut bytes = vec![];
+        field.write_element(field.sub(field.zero(), field.one()), &mut bytes);
+        let bits = bytes
+            .iter()
+            .rposition(|&byte| byte != 0)
+            .map_or(0, |i| 8 * i + 8 - bytes[i].leading_zeros() as usize);
+        assert!(bits >= 2, "field too small");
+        KEY_MATERIAL_BITS.div_ceil(bits - 1)
+    }
+
+    /// Encrypt `payload` and generate a bundle for each of the `share_count`
+    /// recipients.
+    pub fn share(&self, payload: &[u8]) -> Vec<EncryptedShareBundle<F>> {
+        let mut rng = ForwardSecureRng::new();
+        self.share_with_rng(payload, &mut rng)
+    }
+
+    /// Encrypt `payload` and generate a bundle for each recipient, sampling the key
+    /// material with `rng`.
+    pub fn share_with_rng<R: Rng>(
+        &self,
+        payload: &[u8],
+        rng: &mut R,
+    ) -> Vec<EncryptedShareBundle<F>> {
+        let field = &self.scheme.field;
+        let material: Vec<F::E> = (0..self.key_material_len())
+            .map(|_| field.sample(rng))
+            .collect();
+        let ciphertext = self.aead.seal(&self.derive_key(&material), payload, &[]);
+        let sharings: Vec<Vec<F::E>> = material.into_iter().map(|e| self.scheme.share(e)).collect();
+        (0..self.scheme.share_count)
+            .map(|index| EncryptedShareBundle {
+                index,
+                key_shares: sharings
+                    .iter()
+                    .map(|shares| shares[index].clone())
+                    .collect(),
+                ciphertext: ciphertext.clone(),
+            })
+            .collect()
+    }
+
+    /// Recover the payload from at least `reconstruct_limit` bundles.
+    pub fn reconstruct(&self, bundles: &[EncryptedShareBundle<F>]) -> Result<Vec<u8>, HybridError> {
+        if bundles.len() < self.reconstruct_limit() {
+            return Err(HybridError::NotEnoughShares {
+                required: self.reconstruct_limit(),
+                got: bundles.len(),
+            });
+        }
+        let first = &bundles[0];
+        let consistent = bundles.iter().enumerate().all(|(i, bundle)| {
+            bundle.key_shares.len() == first.key_shares.len()
+                && bundle.ciphertext == first.ciphertext
+                && bundles[..i].iter().all(|other| other.index != bundle.index)
+        });
+        if !consistent || first.key_shares.len() != self.key_material_len() {
+            return Err(HybridError::InconsistentBundles);
+        }
+        let indices: Vec<usize> = bundles.iter().map(|bundle| bundle.index).collect();
+        let material: Vec<F::E> = (0..first.key_shares.len())
+            .map(|j| {
+                let shares: Vec<F::E> = bundles
+                    .iter()
+                    .map(|bundle| bundle.key_shares[j].clone())
+                    .collect();
+                self.scheme.reconstruct(&indices, &shares)
+            })
+            .collect();
+        self.aead
+            .open(&self.derive_key(&material), &first.ciphertext, &[])
+            .ok_or(HybridError::Decryption)
+    }
+
+    fn derive_key(&self, material: &[F::E]) -> Vec<u8> {
+        let mut bytes = vec![];
+        for e in material {
+            self.scheme.field.write_element(e, &mut bytes);
+        }
+        self.kdf.derive(&bytes, KEY_INFO, self.aead.key_len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Fp61, NaturalPrimeField};
+    use rand::{ChaChaRng, SeedableRng};
+
+    /// Insecure stand-ins for the primitives, only exercising the plumbing.
+    struct XorAead;
+
+    impl Aead for XorAead {
+        fn key_len(&self) -> usize {
+            16
+        }
+
+        fn seal(&self, key: &[u8], plaintext: &[u8], associated_data: &[u8]) -> Vec<u8> {
+            let mut ciphertext = keystream(key, plaintext, associated_data);
+            ciphertext.push(tag(key, plaintext));
+            ciphertext
+        }
+
+        fn open(&self, key: &[u8], ciphertext: &[u8], associated_data: &[u8]) -> Option<Vec<u8>> {
+            let (&t, body) = ciphertext.split_last()?;
+            let plaintext = keystream(key, body, associated_data);
+            if tag(key, &plaintext) == t {
+                Some(plaintext)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn keystream(key: &[u8], data: &[u8], associated_data: &[u8]) -> Vec<u8> {
+        let seed: Vec<u32> = key
+            .iter()
+            .chain(associated_data)
+            .map(|&b| b as u32)
+            .collect();
+        let mut rng = ChaChaRng::from_seed(&seed[..]);
+        data.iter().map(|b| b ^ rng.gen::<u8>()).collect()
+    }
+
+    fn tag(key: &[u8], plaintext: &[u8]) -> u8 {
+        key.iter()
+            .chain(plaintext)
+            .fold(0x5a, |acc: u8, &b| acc.rotate_left(3) ^ b)
+    }
+
+    struct XorKdf;
+
+    impl KeyDerivation for XorKdf {
+        fn derive(&self, material: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+            let mut key = vec![0; len];
+            for (i, &b) in material.iter().chain(info).enumerate() {
+                key[i % len] ^= b.rotate_left(i as u32);
+            }
+            key
+        }
+    }
+
+    fn scheme<F: Field>(field: F) -> HybridSecretSharing<F, XorAead, XorKdf>
+    where
+        F::E: Clone,
+    {
+        HybridSecretSharing {
+            scheme: ShamirSecretSharing {
+                threshold: 2,
+                share_count: 5,
+                field,
+            },
+            aead: XorAead,
+            kdf: XorKdf,
+        }
+    }
+
+    #[test]
+    fn test_key_material_len() {
+        assert_eq!(scheme(Fp61).key_material_len(), 5);
+        assert_eq!(scheme(NaturalPrimeField(433)).key_material_len(), 32);
+        assert_eq!(scheme(NaturalPrimeField(2147483647)).key_material_len(), 9);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let hss = scheme(Fp61);
+        let payload: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+        let bundles = hss.share(&payload);
+        assert_eq!(bundles.len(), 5);
+        assert!(bundles.iter().all(|b| b.key_shares.len() == 5));
+        assert_eq!(hss.reconstruct(&bundles[2..]), Ok(payload.clone()));
+        let subset = [bundles[4].clone(), bundles[0].clone(), bundles[3].clone()];
+        assert_eq!(hss.reconstruct(&subset), Ok(payload.clone()));
+
+        let hss = scheme(NaturalPrimeField(433));
+        let mut rng = ChaChaRng::from_seed(&[7]);
+        let bundles = hss.share_with_rng(b"", &mut rng);
+        assert_eq!(hss.reconstruct(&bundles[..3]), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let hss = scheme(Fp61);
+        let bundles = hss.share(b"attack at dawn");
+
+        assert_eq!(
+            hss.reconstruct(&bundles[..2]),
+            Err(HybridError::NotEnoughShares {
+                required: 3,
+                got: 2
+            })
+        );
+        assert_eq!(
+            hss.reconstruct(&[]),
+            Err(HybridError::NotEnoughShares {
+                required: 3,
+                got: 0
+            })
+        );
+        let mut tampered = bundles[..3].to_vec();
+        tampered[1].ciphertext[0] ^= 1;
+        assert_eq!(
+            hss.reconstruct(&tampered),
+            Err(HybridError::InconsistentBundles)
+        );
+        let repeated = [bundles[0].clone(), bundles[1].clone(), bundles[0].clone()];
+        assert_eq!(
+            hss.reconstruct(&repeated),
+            Err(HybridError::InconsistentBundles)
+        );
+        let mut truncated = bundles[..3].to_vec();
+        for bundle in truncated.iter_mut() {
+            bundle.key_shares.pop();
+        }
+        assert_eq!(
+            hss.reconstruct(&truncated),
+            Err(HybridError::InconsistentBundles)
+        );
+
+        // key shares from another sharing
+        let other = hss.share(b"attack at dawn");
+        let mut mixed = bundles[..3].to_vec();
+        mixed[2].key_shares = other[2].key_shares.clone();
+        assert_eq!(hss.reconstruct(&mixed), Err(HybridError::Decryption));
+        let mut tampered = bundles[..3].to_vec();
+        for bundle in tampered.iter_mut() {
+            bundle.ciphertext[0] ^= 1;
+        }
+        assert_eq!(hss.reconstruct(&tampered), Err(HybridError::Decryption));
+    }
+
+    #[test]
+    fn test_bundle_bytes() {
+        let field = NaturalPrimeField(17);
+        let bundle: EncryptedShareBundle<NaturalPrimeField<i64>> = EncryptedShareBundle {
+            index: 3,
+            key_shares: vec![16, 2],
+            ciphertext: vec![0xaa, 0xbb],
+        };
+        let bytes = bundle.to_bytes(&field);
+        assert_eq!(
+            bytes,
+            [3, 0, 0, 0, 2, 0, 0, 0, 16, 0, 0, 0, 2, 0, 0, 0, 0xaa, 0xbb]
+        );
+        assert_eq!(
+            EncryptedShareBundle::from_bytes(&field, &bytes),
+            Some(bundle)
+        );
+        assert_eq!(EncryptedShareBundle::from_bytes(&field, &bytes[..15]), None);
+        let mut invalid = bytes.clone();
+        invalid[8] = 17;
+        assert_eq!(EncryptedShareBundle::from_bytes(&field, &invalid), None);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_hkdf_sha256() {
+        fn hex(bytes: &[u8]) -> String {
+            bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+        }
+        // test cases 1 and 3 of RFC 5869
+        let salt: Vec<u8> = (0..13).collect();
+        let info: Vec<u8> = (0xf0..0xfa).collect();
+        assert_eq!(
+            hex(&hkdf_sha256(&salt, &[0x0b; 22], &info, 42)),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+        assert_eq!(
+            hex(&HkdfSha256.derive(&[0x0b; 22], &[], 42)),
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"
+        );
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Information checking protocol (ICP), giving information-theoretic signatures
+//! as used to authenticate values in asynchronous verifiable secret sharing.
+//!
+//! A dealer signs values for an intermediary, who can later reveal them to anyone
+//! along with a proof that they come from the dealer, checked with the help of
+//! `verifier_count` verifiers. Following Patra, Choudhary and Rangan:
+//!
+//! 1. with `ic_sign` the dealer embeds the values as the lowest coefficients of a
+//!    random polynomial of degree `values.len() + threshold - 1`, given to the
+//!    intermediary as an `IcSignature`, and gives every verifier an `IcKey` holding
+//!    the value of the polynomial at a random secret point;
+//! 2. to make sure the keys are consistent with its signature, the intermediary
+//!    picks a random challenge and publishes the `blind`ed signature, which every
+//!    verifier checks against its key with `ic_verify`;
+//! 3. to reveal the values, the intermediary publishes the polynomial, every
+//!    verifier checks it against its key with `IcKey::check`, and `ic_reveal`
+//!    accepts the values if more than `threshold` verifiers agree.
+//!
+//! As long as at most `threshold` verifiers are corrupted, they learn nothing about
+//! the values before they are revealed, and the intermediary can only make a
+//! verifier accept other values with probability about `degree / p`, so the field
+//! must be large enough for that to be negligible. Resolving complaints raised
+//! during verification is left to the protocol using these primitives.
+
+use rand::Rng;
+use std::error::Error;
+use std::fmt;
+
+use fields::Field;
+use numtheory::mod_evaluate_polynomial;
+
+/// Signature on values held by the intermediary.
+pub struct IcSignature<F: Field> {
+    /// Coefficients of the signing polynomial, lowest degree first, starting with
+    /// the signed values.
+    pub polynomial: Vec<F::E>,
+    /// Coefficients of the random polynomial masking the signing polynomial during
+    /// verification.
+    pub masking: Vec<F::E>,
+}
+
+impl<F: Field> IcSignature<F> {
+    /// Combination of the signing and masking polynomials for the random
+    /// `challenge`, published by the intermediary to have the keys checked with
+    /// `ic_verify` without revealing the values.
+    pub fn blind(&self, field: &F, challenge: &F::E) -> Vec<F::E> {
+        self.polynomial
+            .iter()
+            .zip(&self.masking)
+            .map(|(coefficient, mask)| field.add(field.mul(challenge, coefficient), mask))
+            .collect()
+    }
+}
+
+element_impls!(Clone, PartialEq, Debug for IcSignature { polynomial, masking });
+
+/// Verification information of a single verifier.
+pub struct IcKey<F: Field> {
+    /// Secret evaluation point of the verifier.
+    pub point: F::E,
+    /// Value of the signing polynomial at `point`.
+    pub value: F::E,
+    /// Value of the masking polynomial at `point`.
+    pub masking_value: F::E,
+}
+
+impl<F: Field> IcKey<F> {
+    /// Whether the `polynomial` revealed by the intermediary agrees with this key.
+    pub fn check(&self, field: &F, polynomial: &[F::E]) -> bool {
+        field.eq(
+            mod_evaluate_polynomial(polynomial, &self.point, field),
+            &self.value,
+        )
+    }
+}
+
+element_impls!(Clone, PartialEq for IcKey { point, value, masking_value });
+
+impl<F: Field> fmt::Debug for IcKey<F> {
+    /// Keys are secret and are not printed.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("IcKey")
+    }
+}
+
+/// Rejection of revealed values by `ic_reveal`, with the number of verifiers that
+/// accepted them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IcRejected(pub usize);
+
+impl fmt::Display for IcRejected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "revealed values accepted by only {} verifiers", self.0)
+    }
+}
+
+impl Error for IcRejected {}
+
+/// Sign `values` as the dealer, returning the signature for the intermediary and
+/// the keys for `verifier_count` verifiers, sampling the randomness from `rng`.
+///
+/// The values stay hidden from any `threshold` of the verifiers.
+pub fn ic_sign<F, R>(
+    field: &F,
+    values: &[F::E],
+    threshold: usize,
+    verifier_count: usize,
+    rng: &mut R,
+) -> (IcSignature<F>, Vec<IcKey<F>>)
+where
+    F: Field,
+    F::E: Clone,
+    R: Rng,
+{
+    assert!(verifier_count > threshold);
+    let mut polynomial = values.to_vec();
+    polynomial.extend(field.sample_with_replacement(threshold, rng));
+    let masking = field.sample_with_replacement(polynomial.len(), rng);
+    let keys = field
+        .sample_nonzero_with_replacement(verifier_count, rng)
+        .into_iter()
+        .map(|point| IcKey {
+            value: mod_evaluate_polynomial(&polynomial, &point, field),
+            masking_value: mod_evaluate_polynomial(&masking, &point, field),
+            point,
+        })
+        .collect();
+    (
+        IcSignature {
+            polynomial,
+            masking,
+        },
+        keys,
+    )
+}
+
+/// Check as a verifier that the polynomial `blinded` published by the intermediary
+/// for `challenge` agrees with `key`, ie. that the dealer gave consistent
+/// information to both.
+pub fn ic_verify<F: Field>(field: &F, key: &IcKey<F>, challenge: &F::E, blinded: &[F::E]) -> bool {
+    let expected = field.add(field.mul(challenge, &key.value), &key.masking_value);
+    field.eq(
+        mod_evaluate_polynomial(blinded, &key.point, field),
+        expected,
+    )
+}
+
+/// Values revealed by the intermediary as the signing `polynomial`, if more than
+/// `threshold` of the verifiers holding `keys` accept it.
+///
+/// `value_count` is the number of signed values; polynomials of another degree are
+/// rejected by all verifiers.
+pub fn ic_reveal<F>(
+    field: &F,
+    threshold: usize,
+    value_count: usize,
+    polynomial: &[F::E],
+    keys: &[IcKey<F>],
+) -> Result<Vec<F::E>, IcRejected>
+where
+    F: Field,
+    F::E: Clone,
+{
+    let accepted = if polynomial.len() == value_count + threshold {
+        keys.iter()
+            .filter(|key| key.check(field, polynomial))
+            .count()
+    } else {
+        0
+    };
+    if accepted <= threshold {
+        return Err(IcRejected(accepted));
+    }
+    Ok(polynomial[..value_count].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::Fp61;
+    use rand::{ChaChaRng, SeedableRng};
+
+    #[test]
+    fn test_sign_reveal() {
+        let mut rng = ChaChaRng::from_seed(&[49]);
+        let (signature, keys) = ic_sign(&Fp61, &[5, 6, 7], 2, 7, &mut rng);
+        assert_eq!(signature.polynomial.len(), 5);
+        assert_eq!(keys.len(), 7);
+
+        let blinded = signature.blind(&Fp61, &12345);
+        assert!(keys
+            .iter()
+            .all(|key| ic_verify(&Fp61, key, &12345, &blinded)));
+        assert_eq!(
+            ic_reveal(&Fp61, 2, 3, &signature.polynomial, &keys),
+            Ok(vec![5, 6, 7])
+        );
+        // enough verifiers must take part
+        assert_eq!(
+            ic_reveal(&Fp61, 2, 3, &signature.polynomial, &keys[..2]),
+            Err(IcRejected(2))
+        );
+        assert_eq!(
+            ic_reveal(&Fp61, 2, 3, &signature.polynomial, &keys[4..]),
+            Ok(vec![5, 6, 7])
+        );
+    }
+
+    #[test]
+    fn test_forgery() {
+        let mut rng = ChaChaRng::from_seed(&[50]);
+        let (signature, keys) = ic_sign(&Fp61, &[5, 6, 7], 2, 7, &mut rng);
+
+        // changed values are rejected by all honest verifiers
+        let mut forged = signature.polynomial.clone();
+        forged[1] = 8;
+        assert!(keys.iter().all(|key| !key.check(&Fp61, &forged)));
+        assert_eq!(ic_reveal(&Fp61, 2, 3, &forged, &keys), Err(IcRejected(0)));
+        // as are polynomials of the wrong degree
+        assert_eq!(
+            ic_reveal(&Fp61, 2, 2, &signature.polynomial, &keys),
+            Err(IcRejected(0))
+        );
+        // and the accepting verifiers must outnumber the corrupted ones
+        let mut corrupted = keys[..3].to_vec();
+        corrupted[0].value = mod_evaluate_polynomial(&forged, corrupted[0].point, &Fp61);
+        corrupted[1].value = mod_evaluate_polynomial(&forged, corrupted[1].point, &Fp61);
+        assert_eq!(
+            ic_reveal(&Fp61, 2, 3, &forged, &corrupted),
+            Err(IcRejected(2))
+        );
+    }
+
+    #[test]
+    fn test_inconsistent_dealer() {
+        let mut rng = ChaChaRng::from_seed(&[51]);
+        let (signature, mut keys) = ic_sign(&Fp61, &[5, 6, 7], 2, 7, &mut rng);
+        keys[3].value = Fp61.add(keys[3].value, 1);
+        let blinded = signature.blind(&Fp61, &999);
+        let complaints: Vec<usize> = (0..keys.len())
+            .filter(|&i| !ic_verify(&Fp61, &keys[i], &999, &blinded))
+            .collect();
+        assert_eq!(complaints, [3]);
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Shamir secret sharing over the integers, for secrets such as RSA private exponents
+//! that do not live in a field known to the dealer.
+//!
+//! Since divisions are not available, the polynomial is scaled by `delta = share_count!`
+//! which makes all Lagrange coefficients integral, and statistical hiding is obtained
+//! by sampling the coefficients from a range much larger than the secrets.
+
+extern crate framp as ramp;
+
+use self::ramp::RandomInt;
+use fields::ForwardSecureRng;
+
+/// Parameters for the integer Shamir scheme.
+#[derive(Clone, Debug)]
+pub struct IntegerShamirSecretSharing {
+    /// Maximum number of shares that can be known without exposing the secret.
+    pub threshold: usize,
+    /// Number of shares to split the secret into.
+    pub share_count: usize,
+    /// Secrets must lie in `[0, secret_bound)`.
+    pub secret_bound: ramp::Int,
+    /// Statistical distance of the shares of any two secrets is below
+    /// `threshold.2^-statistical_security`.
+    pub statistical_security: usize,
+}
+
+impl IntegerShamirSecretSharing {
+    /// Minimum number of shares required to reconstruct secret.
+    ///
+    /// For this scheme this is always `threshold + 1`.
+    pub fn reconstruct_limit(&self) -> usize {
+        self.threshold + 1
+    }
+
+    /// The scaling factor `share_count!`.
+    pub fn delta(&self) -> ramp::Int {
+        (1..self.share_count + 1).fold(ramp::Int::one(), |acc, i| acc * ramp::Int::from(i))
+    }
+
+    /// Generate `share_count` shares from `secret`.
+    ///
+    /// The shares are the evaluations at `1, .., share_count` of a polynomial with
+    /// constant term `delta.secret`, and all other coefficients sampled below
+    /// `delta^2.secret_bound.2^statistical_security`.
+    pub fn share(&self, secret: &ramp::Int) -> Vec<ramp::Int> {
+        assert!(*secret >= 0 && *secret < self.secret_bound);
+        let delta = self.delta();
+        let coefficient_bound = (&delta * &delta * &self.secret_bound) << self.statistical_security;
+
+        let mut rng = ForwardSecureRng::new();
+        let mut coefficients = vec![&delta * secret];
+        coefficients.extend((0..self.threshold).map(|_| rng.gen_uint_below(&coefficient_bound)));
+
+        (1..self.share_count + 1)
+            .map(|point| {
+                let point = ramp::Int::from(point);
+                // Horner's rule
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(ramp::Int::zero(), |acc, c| acc * &point + c)
+            })
+            .collect()
+    }
+
+    /// Integral Lagrange coefficients for interpolating at zero from the shares with the
+    /// given `indices`, scaled by `delta`.
+    ///
+    /// These are what parties need in protocols using the shares in the exponent, such
+    /// as threshold RSA signatures.
+    pub fn lagrange_coefficients(&self, indices: &[usize]) -> Vec<ramp::Int> {
+        let delta = self.delta();
+        indices
+            .iter()
+            .map(|&i| {
+                let xi = i as i64 + 1;
+                let mut numerator = delta.clone();
+                let mut denominator = ramp::Int::one();
+                for &j in indices.iter().filter(|&&j| j != i) {
+                    let xj = j as i64 + 1;
+                    numerator = numerator * ramp::Int::from(xj);
+                    denominator = denominator * ramp::Int::from(xj - xi);
+                }
+                // exact since `delta` times any Lagrange coefficient is integral
+                debug_assert!(&numerator % &denominator == 0);
+                numerator / denominator
+            })
+            .collect()
+    }
+
+    /// Reconstruct `secret` from a large enough subset of the shares.
+    ///
+    /// `indices` are the ranks of the known shares as output by the `share` method,
+    /// while `values` are the actual values of these shares.
+    /// Both must have the same number of elements, and at least `reconstruct_limit`.
+    pub fn reconstruct(&self, indices: &[usize], shares: &[ramp::Int]) -> ramp::Int {
+        assert!(shares.len() == indices.len());
+        assert!(shares.len() >= self.reconstruct_limit());
+        let limit = self.reconstruct_limit();
+        let coefficients = self.lagrange_coefficients(&indices[..limit]);
+        // the combination is `delta` times the constant term `delta.secret`
+        let combination = coefficients
+            .iter()
+            .zip(&shares[..limit])
+            .fold(ramp::Int::zero(), |acc, (c, s)| acc + c * s);
+        let delta = self.delta();
+        let delta_squared = &delta * &delta;
+        debug_assert!(&combination % &delta_squared == 0);
+        combination / delta_squared
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::str::FromStr;
+
+    fn scheme() -> IntegerShamirSecretSharing {
+        IntegerShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            secret_bound: ramp::Int::from(1_u64 << 60),
+            statistical_security: 40,
+        }
+    }
+
+    #[test]
+    fn test_delta() {
+        assert_eq!(scheme().delta(), ramp::Int::from(120));
+    }
+
+    #[test]
+    fn test_lagrange_coefficients() {
+        // delta times the usual coefficients 3, -3, 1 for points 1, 2, 3
+        let coefficients = scheme().lagrange_coefficients(&[0, 1, 2]);
+        assert_eq!(
+            coefficients,
+            vec![
+                ramp::Int::from(360),
+                ramp::Int::from(-360),
+                ramp::Int::from(120)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_share_reconstruct() {
+        let iss = scheme();
+        let secret = ramp::Int::from_str("1152921504606846975").unwrap();
+        let shares = iss.share(&secret);
+        assert_eq!(shares.len(), 5);
+        assert_eq!(iss.reconstruct(&[0, 1, 2], &shares[0..3]), secret);
+        assert_eq!(iss.reconstruct(&[2, 3, 4], &shares[2..5]), secret);
+        let indices = [4, 0, 3];
+        let values: Vec<ramp::Int> = indices.iter().map(|&i| shares[i].clone()).collect();
+        assert_eq!(iss.reconstruct(&indices, &values), secret);
+    }
+}
@@ -0,0 +1,522 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Backup of 32-byte keys, such as wallet seeds, as shares written down as words.
+//!
+//! The key is shared byte by byte over GF(256) as with `compat::GfShare`, and every
+//! share is packed together with its metadata and a CRC-32 checksum into
+//! `SHARE_LEN` bytes, which are spelled as `MNEMONIC_WORDS` five-letter
+//! [proquints](https://arxiv.org/html/0901.4016) such as `lusab babad`. Each word
+//! encodes two bytes as alternating consonants and vowels, so mnemonics are easy to
+//! read aloud and need no word list.
+//!
+//! The checksum catches typos in a single share as soon as it is entered with
+//! `BackupShare::from_mnemonic`, while `recover` reports shares from different
+//! backups, repeated shares and missing shares.
+//!
+//! # Example:
+//!
+//! ```
+//!    use threshold_secret_sharing::keybackup::{self, BackupShare};
+//!    let key = [7; 32];
+//!    let mnemonics: Vec<String> = keybackup::split(&key, 2, 3)
+//!        .iter()
+//!        .map(BackupShare::to_mnemonic)
+//!        .collect();
+//!    let shares = [
+//!        BackupShare::from_mnemonic(&mnemonics[2]).unwrap(),
+//!        BackupShare::from_mnemonic(&mnemonics[0]).unwrap(),
+//!    ];
+//!    assert_eq!(keybackup::recover(&shares), Ok(key));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use compat::GfShare;
+use fields::ForwardSecureRng;
+use rand::Rng;
+
+/// Length of the keys backed up.
+pub const KEY_LEN: usize = 32;
+
+/// Version of the share format written by this crate.
+pub const VERSION: u8 = 1;
+
+/// Length of a serialized share: version, required and total share counts, share
+/// number, backup identifier, creation time, share of the key and checksum.
+pub const SHARE_LEN: usize = 4 + 4 + 8 + KEY_LEN + 4;
+
+/// Number of words of a mnemonic, each encoding two bytes.
+pub const MNEMONIC_WORDS: usize = SHARE_LEN / 2;
+
+const CONSONANTS: &[u8; 16] = b"bdfghjklmnprstvz";
+const VOWELS: &[u8; 4] = b"aiou";
+
+/// Failure to read a share or recover a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupError {
+    /// The mnemonic has this many words instead of `MNEMONIC_WORDS`.
+    WrongLength(usize),
+    /// The word at this position of the mnemonic, starting from zero, is not a
+    /// proquint.
+    InvalidWord(usize),
+    /// The checksum of the share does not match, because of a typo or corruption.
+    Checksum,
+    /// The share was written by an unknown version of the format.
+    UnsupportedVersion(u8),
+    /// The share claims impossible share counts or share number.
+    InvalidShare,
+    /// The shares come from different backups.
+    MixedBackups,
+    /// Several different shares have this share number.
+    DuplicateShare(u8),
+    /// Only `given` distinct shares were given, while `required` are needed.
+    NotEnoughShares { given: usize, required: usize },
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BackupError::WrongLength(words) => write!(
+                f,
+                "mnemonic has {} words instead of {}",
+                words, MNEMONIC_WORDS
+            ),
+            BackupError::InvalidWord(position) => write!(f, "invalid word {}", position + 1),
+            BackupError::Checksum => write!(f, "checksum mismatch, check for typos"),
+            BackupError::UnsupportedVersion(version) => {
+                write!(f, "unsupported share format version {}", version)
+            }
+            BackupError::InvalidShare => write!(f, "invalid share counts or number"),
+            BackupError::MixedBackups => write!(f, "shares come from different backups"),
+            BackupError::DuplicateShare(number) => {
+                write!(f, "share {} is given with different contents", number)
+            }
+            BackupError::NotEnoughShares { given, required } => {
+                write!(f, "{} shares are needed but only {} given", required, given)
+            }
+        }
+    }
+}
+
+impl Error for BackupError {}
+
+/// A share of a backed up key together with its metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupShare {
+    /// Version of the share format.
+    pub version: u8,
+    /// Number of shares needed to recover the key.
+    pub required: u8,
+    /// Number of shares the key was split into.
+    pub share_count: u8,
+    /// Number of this share, from `1` to `share_count`.
+    pub number: u8,
+    /// Random identifier common to all shares of a backup.
+    pub backup_id: u32,
+    /// Creation time of the backup in seconds since the Unix epoch.
+    pub created: u64,
+    /// Share of the key.
+    pub data: [u8; KEY_LEN],
+}
+
+impl BackupShare {
+    /// Serialize the share in `SHARE_LEN` bytes, ending with the CRC-32 of the
+    /// others, with all integers big-endian.
+    pub fn to_bytes(&self) -> [u8; SHARE_LEN] {
+        let mut bytes = [0; SHARE_LEN];
+        bytes[..4].copy_from_slice(&[self.version, self.required, self.share_count, self.number]);
+        bytes[4..8].copy_from_slice(&self.backup_id.to_be_bytes());
+        bytes[8..16].copy_from_slice(&self.created.to_be_bytes());
+        bytes[16..16 + KEY_LEN].copy_from_slice(&self.data);
+        let checksum = crc32(&bytes[..SHARE_LEN - 4]);
+        bytes[SHARE_LEN - 4..].copy_from_slice(&checksum.to_be_bytes());
+        bytes
+    }
+
+    /// Share serialized by `to_bytes`, after verifying its checksum and metadata.
+    pub fn from_bytes(bytes: &[u8; SHARE_LEN]) -> Result<BackupShare, BackupError> {
+        let (content, checksum) = bytes.split_at(SHARE_LEN - 4);
+        if crc32(content).to_be_bytes() != checksum {
+            return Err(BackupError::Checksum);
+        }
+        let mut backup_id = [0; 4];
+        backup_id.copy_from_slice(&bytes[4..8]);
+        let mut created = [0; 8];
+        created.copy_from_slice(&bytes[8..16]);
+        let mut data = [0; KEY_LEN];
+        data.copy_from_slice(&bytes[16..16 + KEY_LEN]);
+        let share = BackupShare {
+            version: bytes[0],
+            required: bytes[1],
+            share_count: bytes[2],
+            number: bytes[3],
+            backup_id: u32::from_be_bytes(backup_id),
+            created: u64::from_be_bytes(created),
+            data,
+        };
+        share.check_metadata()?;
+        Ok(share)
+    }
+
+    /// Verify the version, share counts and share number of the share.
+    fn check_metadata(&self) -> Result<(), BackupError> {
+        if self.version != VERSION {
+            return Err(BackupError::UnsupportedVersion(self.version));
+        }
+        if self.required == 0
+            || self.required > self.share_count
+            || self.number == 0
+            || self.number > self.share_count
+        {
+            return Err(BackupError::InvalidShare);
+        }
+        Ok(())
+    }
+
+    /// The serialized share as `MNEMONIC_WORDS` proquints separated by spaces.
+    pub fn to_mnemonic(&self) -> String {
+        let words: Vec<String> = self
+            .to_bytes()
+            .chunks(2)
+            .map(|pair| proquint((pair[0] as u16) << 8 | pair[1] as u16))
+            .collect();
+        words.join(" ")
+    }
+
+    /// Share written by `to_mnemonic`, ignoring case and accepting any whitespace or
+    /// dashes between the words.
+    pub fn from_mnemonic(mnemonic: &str) -> Result<BackupShare, BackupError> {
+        let words: Vec<&str> = mnemonic
+            .split(|c: char| c.is_whitespace() || c == '-')
+            .filter(|word| !word.is_empty())
+            .collect();
+        if words.len() != MNEMONIC_WORDS {
+            return Err(BackupError::WrongLength(words.len()));
+        }
+        let mut bytes = [0; SHARE_LEN];
+        for (position, word) in words.iter().enumerate() {
+            let value = parse_proquint(word).ok_or(BackupError::InvalidWord(position))?;
+            bytes[2 * position..2 * position + 2].copy_from_slice(&value.to_be_bytes());
+        }
+        BackupShare::from_bytes(&bytes)
+    }
+}
+
+/// Split `key` into `share_count` shares, any `required` of which recover it.
+///
+/// The shares are stamped with the current time, and the randomness is sampled from
+/// a `ForwardSecureRng` seeded from the operating system.
+pub fn split(key: &[u8; KEY_LEN], required: u8, share_count: u8) -> Vec<BackupShare> {
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    split_with_rng(
+        key,
+        required,
+        share_count,
+        created,
+        &mut ForwardSecureRng::new(),
+    )
+}
+
+/// Split `key` like `split`, with the given creation time in seconds since the Unix
+/// epoch and sampling the randomness from `rng`.
+pub fn split_with_rng<R: Rng>(
+    key: &[u8; KEY_LEN],
+    required: u8,
+    share_count: u8,
+    created: u64,
+    rng: &mut R,
+) -> Vec<BackupShare> {
+    assert!(
+        required >= 1 && required <= share_count,
+        "required must be between 1 and share_count"
+    );
+    let backup_id = rng.next_u32();
+    let gfshare = GfShare::new(required as usize - 1, share_count as usize);
+    gfshare
+        .share_with_rng(key, rng)
+        .into_iter()
+        .zip(gfshare.share_numbers)
+        .map(|(share, number)| {
+            let mut data = [0; KEY_LEN];
+            data.copy_from_slice(&share);
+            BackupShare {
+                version: VERSION,
+                required,
+                share_count,
+                number,
+                backup_id,
+                created,
+                data,
+            }
+        })
+        .collect()
+}
+
+/// Recover the key from at least `required` shares of the same backup, in any
+/// order.
+///
+/// Shares given several times are only used once. Since the fields of the shares
+/// are public, their metadata is verified again as in `BackupShare::from_bytes`.
+pub fn recover(shares: &[BackupShare]) -> Result<[u8; KEY_LEN], BackupError> {
+    let first = match shares.first() {
+        Some(first) => first,
+        None => {
+            return Err(BackupError::NotEnoughShares {
+                given: 0,
+                required: 1,
+            })
+        }
+    };
+    let mut distinct: Vec<&BackupShare> = Vec::with_capacity(shares.len());
+    for share in shares {
+        share.check_metadata()?;
+        let same_backup = share.version == first.version
+            && share.required == first.required
+            && share.share_count == first.share_count
+            && share.backup_id == first.backup_id
+            && share.created == first.created;
+        if !same_backup {
+            return Err(BackupError::MixedBackups);
+        }
+        match distinct.iter().find(|other| other.number == share.number) {
+            Some(other) if other.data != share.data => {
+                return Err(BackupError::DuplicateShare(share.number))
+            }
+            Some(_) => {}
+            None => distinct.push(share),
+        }
+    }
+    if distinct.len() < first.required as usize {
+        return Err(BackupError::NotEnoughShares {
+            given: distinct.len(),
+            required: first.required as usize,
+        });
+    }
+
+    let distinct = &distinct[..first.required as usize];
+    let gfshare = GfShare {
+        threshold: first.required as usize - 1,
+        share_numbers: distinct.iter().map(|share| share.number).collect(),
+    };
+    let data: Vec<&[u8]> = distinct.iter().map(|share| &share.data[..]).collect();
+    let mut key = [0; KEY_LEN];
+    key.copy_from_slice(&gfshare.reconstruct(&gfshare.share_numbers, &data));
+    Ok(key)
+}
+
+/// The proquint spelling `value`.
+fn proquint(value: u16) -> String {
+    let consonant = |shift: u16| CONSONANTS[(value >> shift & 0xf) as usize] as char;
+    let vowel = |shift: u16| VOWELS[(value >> shift & 0x3) as usize] as char;
+    [
+        consonant(12),
+        vowel(10),
+        consonant(6),
+        vowel(4),
+        consonant(0),
+    ]
+    .iter()
+    .collect()
+}
+
+/// The value spelled by the proquint `word`, if it is one.
+fn parse_proquint(word: &str) -> Option<u16> {
+    let letters = word.as_bytes();
+    if letters.len() != 5 {
+        return None;
+    }
+    letters
+        .iter()
+        .enumerate()
+        .try_fold(0, |value, (i, letter)| {
+            let letter = letter.to_ascii_lowercase();
+            if i % 2 == 0 {
+                let digit = CONSONANTS.iter().position(|&c| c == letter)?;
+                Some(value << 4 | digit as u16)
+            } else {
+                let digit = VOWELS.iter().position(|&v| v == letter)?;
+                Some(value << 2 | digit as u16)
+            }
+        })
+}
+
+/// CRC-32 of `bytes` as used by zlib and PNG.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use seed::seeded_rng;
+
+    fn backup() -> Vec<BackupShare> {
+        let key: Vec<u8> = (0..KEY_LEN as u8).collect();
+        let mut key_array = [0; KEY_LEN];
+        key_array.copy_from_slice(&key);
+        split_with_rng(&key_array, 3, 5, 1_500_000_000, &mut seeded_rng(&[1; 32]))
+    }
+
+    #[test]
+    fn test_encodings() {
+        // examples from the proquint specification
+        assert_eq!(proquint(0x7f00), "lusab");
+        assert_eq!(proquint(0x0001), "babad");
+        assert_eq!(parse_proquint("GUTIH"), Some(0x3f54));
+        assert_eq!(parse_proquint("gutih"), Some(0x3f54));
+        assert_eq!(parse_proquint("gutiha"), None);
+        assert_eq!(parse_proquint("gxtih"), None);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_split_recover() {
+        let shares = backup();
+        let key = recover(&shares[..3]).unwrap();
+        assert_eq!(key[..4], [0, 1, 2, 3]);
+        assert_eq!(
+            recover(&[shares[4].clone(), shares[1].clone(), shares[3].clone()]),
+            Ok(key)
+        );
+        assert_eq!(recover(&shares), Ok(key));
+        for share in &shares {
+            assert_eq!(share.required, 3);
+            assert_eq!(share.share_count, 5);
+            assert_eq!(share.created, 1_500_000_000);
+            let mnemonic = share.to_mnemonic();
+            assert_eq!(mnemonic.split(' ').count(), MNEMONIC_WORDS);
+            assert_eq!(BackupShare::from_mnemonic(&mnemonic).as_ref(), Ok(share));
+            let dashed = mnemonic.to_uppercase().replace(' ', "-");
+            assert_eq!(BackupShare::from_mnemonic(&dashed).as_ref(), Ok(share));
+        }
+        // a single share suffices when required
+        let key = [9; KEY_LEN];
+        let shares = split(&key, 1, 2);
+        assert_eq!(recover(&shares[1..]), Ok(key));
+    }
+
+    #[test]
+    fn test_mnemonic_errors() {
+        let mnemonic = backup()[0].to_mnemonic();
+        let mut words: Vec<&str> = mnemonic.split(' ').collect();
+        assert_eq!(
+            BackupShare::from_mnemonic(&words[1..].join(" ")),
+            Err(BackupError::WrongLength(MNEMONIC_WORDS - 1))
+        );
+        let original = words[7];
+        words[7] = "hello";
+        assert_eq!(
+            BackupShare::from_mnemonic(&words.join(" ")),
+            Err(BackupError::InvalidWord(7))
+        );
+        // a typo still giving a proquint
+        let last = if original.ends_with('b') { "d" } else { "b" };
+        let typo = format!("{}{}", &original[..4], last);
+        words[7] = &typo;
+        assert_eq!(
+            BackupShare::from_mnemonic(&words.join(" ")),
+            Err(BackupError::Checksum)
+        );
+
+        let mut share = backup()[0].clone();
+        share.version = 2;
+        assert_eq!(
+            BackupShare::from_bytes(&share.to_bytes()),
+            Err(BackupError::UnsupportedVersion(2))
+        );
+        share.version = VERSION;
+        share.number = 6;
+        assert_eq!(
+            BackupShare::from_bytes(&share.to_bytes()),
+            Err(BackupError::InvalidShare)
+        );
+    }
+
+    #[test]
+    fn test_recover_errors() {
+        let shares = backup();
+        assert_eq!(
+            recover(&[shares[0].clone(), shares[2].clone(), shares[0].clone()]),
+            Err(BackupError::NotEnoughShares {
+                given: 2,
+                required: 3
+            })
+        );
+        assert_eq!(
+            recover(&[]),
+            Err(BackupError::NotEnoughShares {
+                given: 0,
+                required: 1
+            })
+        );
+        let mut tampered = shares[0].clone();
+        tampered.data[0] ^= 1;
+        assert_eq!(
+            recover(&[shares[0].clone(), shares[1].clone(), tampered]),
+            Err(BackupError::DuplicateShare(1))
+        );
+        let mut other = shares[2].clone();
+        other.backup_id ^= 1;
+        assert_eq!(
+            recover(&[shares[0].clone(), shares[1].clone(), other]),
+            Err(BackupError::MixedBackups)
+        );
+    }
+
+    #[test]
+    fn test_recover_invalid_metadata() {
+        let shares = backup();
+        let with = |change: &dyn Fn(&mut BackupShare)| {
+            let mut shares = shares.clone();
+            for share in &mut shares {
+                change(share);
+            }
+            recover(&shares)
+        };
+        assert_eq!(
+            with(&|share| share.required = 0),
+            Err(BackupError::InvalidShare)
+        );
+        assert_eq!(
+            with(&|share| share.required = 6),
+            Err(BackupError::InvalidShare)
+        );
+        assert_eq!(
+            with(&|share| share.version = 2),
+            Err(BackupError::UnsupportedVersion(2))
+        );
+        let mut zero = shares[1].clone();
+        zero.number = 0;
+        assert_eq!(
+            recover(&[shares[0].clone(), zero, shares[2].clone()]),
+            Err(BackupError::InvalidShare)
+        );
+        let mut beyond = shares[1].clone();
+        beyond.number = 6;
+        assert_eq!(
+            recover(&[shares[0].clone(), beyond, shares[2].clone()]),
+            Err(BackupError::InvalidShare)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "required must be between 1 and share_count")]
+    fn test_split_invalid() {
+        split(&[0; KEY_LEN], 4, 3);
+    }
+}
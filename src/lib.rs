@@ -13,13 +13,109 @@
 //! For now, secrets and shares are fixed as prime field elements
 //! represented by `i64` values.
 
+// divisibility is tested with `%` since `is_multiple_of` needs Rust 1.87
+#![allow(clippy::manual_is_multiple_of)]
+
+#[cfg(feature = "bench_utils")]
+extern crate criterion;
 extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(any(
+    feature = "bench_utils",
+    feature = "testvectors",
+    all(test, feature = "serde")
+))]
+extern crate serde_json;
+#[cfg(feature = "sha2")]
+extern crate sha2;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+
+#[macro_use]
+mod macros;
 
+mod asmuth_bloom;
+pub mod avss;
+#[cfg(feature = "bench_utils")]
+pub mod bench_utils;
+pub mod bgw;
+mod bivariate;
+#[cfg(feature = "tokio")]
+mod collector;
+pub mod compat;
+mod context;
+#[cfg(any(feature = "bls", feature = "ed25519"))]
+pub mod elgamal;
+mod envelope;
+mod fft_shamir;
 mod fields;
-pub mod numtheory; // only pub because of benches
+mod fingerprint;
+mod fixed;
+pub mod hybrid;
+pub mod icp;
+#[cfg(feature = "largefield")]
+mod integer_shamir;
+pub mod keybackup;
+mod mac;
+pub mod math;
+mod membership;
+pub mod ntt;
+#[doc(hidden)]
+pub mod numtheory; // only pub because of benches, use `math` instead
+#[cfg(feature = "observer")]
+mod observer;
 pub mod packed;
+pub mod prelude;
+pub mod protocol;
+pub mod prss;
+#[cfg(feature = "pvss")]
+pub mod pvss;
+mod recovery;
+pub mod ring;
+pub mod rs;
+mod scheme;
+mod seed;
+mod session;
 mod shamir;
+mod share;
+#[cfg(any(test, feature = "test_utils"))]
+pub mod test_utils;
+#[cfg(feature = "testvectors")]
+pub mod testvectors;
+#[cfg(any(feature = "bls", feature = "ed25519"))]
+pub mod threshold_sig;
+mod transcript;
 
+pub use asmuth_bloom::AsmuthBloomSecretSharing;
+pub use bivariate::BivariatePolynomial;
+#[cfg(feature = "tokio")]
+pub use collector::{deal, CollectorError, ShareCollector};
+pub use context::{ContextId, ContextShare, ShareContext};
+pub use envelope::{EnvelopeError, ShareEnvelope, ENVELOPE_VERSION};
+pub use fft_shamir::FftShamirSecretSharing;
 pub use fields::*;
-pub use packed::PackedSecretSharing;
-pub use shamir::ShamirSecretSharing;
+pub use fingerprint::{Fingerprint, SchemeId};
+pub use fixed::{FixedPoint, FixedPointError};
+pub use hybrid::{EncryptedShareBundle, HybridSecretSharing};
+#[cfg(feature = "largefield")]
+pub use integer_shamir::IntegerShamirSecretSharing;
+pub use mac::{MacKey, MacShare};
+pub use membership::Committee;
+pub use numtheory::InterpolationCache;
+#[cfg(feature = "observer")]
+pub use observer::{with_observer, SchemeInfo, SchemeKind, SharingObserver};
+pub use packed::{
+    ChunkError, PackedSecretSharing, PackedSecretSharingConst, PackedSharingContext, ParamError,
+    ReconstructStrategy, SecretLayout,
+};
+pub use ring::{GaloisRing64, RingShamirSecretSharing};
+pub use scheme::ThresholdScheme;
+pub use seed::Seed;
+pub use session::ReconstructionSession;
+pub use shamir::{DoubleSharing, ShamirSecretSharing};
+pub use share::{
+    IndexError, InvalidTags, PolynomialShares, ProductShares, RecipientBundle, RecipientShares,
+    Share, ShareIndex, ShareMatrix, TaggedShare,
+};
+pub use transcript::*;
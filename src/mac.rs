@@ -0,0 +1,213 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Information-checking MACs on shares, so that tampered shares are detected at
+//! reconstruction.
+//!
+//! The tag of a share with value `v` is `alpha.v + beta` under the key
+//! `(alpha, beta)` of its index. Without the key, a share can be modified so that
+//! its tag still verifies with probability at most `1 / p`, so the field must be
+//! large enough for that to be negligible. Every key must only be used for a single
+//! share; how keys are distributed, typically to the parties checking the shares,
+//! is left to the caller.
+
+use rand::Rng;
+use std::fmt;
+
+use fields::{Encode, Field};
+use packed::PackedSecretSharing;
+use shamir::ShamirSecretSharing;
+use share::{index_u32, InvalidTags, ShareIndex, TaggedShare};
+
+/// Key of the MAC of a single share.
+pub struct MacKey<F: Field> {
+    pub alpha: F::E,
+    pub beta: F::E,
+}
+
+impl<F: Field> MacKey<F> {
+    /// Uniformly random key.
+    pub fn generate<R: Rng>(field: &F, rng: &mut R) -> MacKey<F> {
+        MacKey {
+            alpha: field.sample(rng),
+            beta: field.sample(rng),
+        }
+    }
+
+    /// Uniformly random keys for all `count` shares of a sharing.
+    pub fn generate_all<R: Rng>(field: &F, count: usize, rng: &mut R) -> Vec<MacKey<F>> {
+        (0..count).map(|_| MacKey::generate(field, rng)).collect()
+    }
+
+    /// Tag of `value` under this key.
+    pub fn tag(&self, field: &F, value: &F::E) -> F::E {
+        field.add(field.mul(&self.alpha, value), &self.beta)
+    }
+
+    /// Check that `tag` is the tag of `value` under this key.
+    pub fn verify(&self, field: &F, value: &F::E, tag: &F::E) -> bool {
+        field.eq(self.tag(field, value), tag)
+    }
+}
+
+element_impls!(Clone, PartialEq for MacKey { alpha, beta });
+
+impl<F: Field> fmt::Debug for MacKey<F> {
+    /// Keys are secret and are not printed.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("MacKey")
+    }
+}
+
+/// A share together with its index, tagged with its MAC.
+pub type MacShare<F> = TaggedShare<F, <F as Field>::E>;
+
+/// Attach to every share its index and its tag under the key of that index.
+fn tag_shares<F: Field>(field: &F, values: Vec<F::E>, keys: &[MacKey<F>]) -> Vec<MacShare<F>> {
+    assert_eq!(values.len(), keys.len(), "one key is needed per share");
+    TaggedShare::tag_all(values, |index, value| keys[index].tag(field, value))
+}
+
+/// Indices and values of `shares` if all their tags verify.
+fn check_shares<F: Field>(
+    field: &F,
+    shares: &[MacShare<F>],
+    keys: &[MacKey<F>],
+) -> Result<(Vec<ShareIndex>, Vec<F::E>), InvalidTags>
+where
+    F::E: Clone,
+{
+    TaggedShare::check_all(shares, |share| {
+        keys.get(share.index)
+            .is_some_and(|key| key.verify(field, &share.value, &share.tag))
+    })
+}
+
+impl<F> ShamirSecretSharing<F>
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    /// Generate `share_count` shares from `secret`, each tagged under the key with
+    /// the same index in `keys`.
+    pub fn share_with_macs(&self, secret: F::E, keys: &[MacKey<F>]) -> Vec<MacShare<F>> {
+        tag_shares(&self.field, self.share(secret), keys)
+    }
+
+    /// Reconstruct the secret from tagged shares as with `reconstruct_indexed`,
+    /// after checking the tag of every share under the key with its index in `keys`.
+    ///
+    /// Fails with the indices of the shares whose tag does not verify.
+    pub fn reconstruct_checked(
+        &self,
+        shares: &[MacShare<F>],
+        keys: &[MacKey<F>],
+    ) -> Result<F::E, InvalidTags> {
+        let (indices, values) = check_shares(&self.field, shares, keys)?;
+        Ok(self.reconstruct(&indices, &values))
+    }
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Generate `share_count` shares for the `secrets` vector, each tagged under the
+    /// key with the same index in `keys`.
+    pub fn share_with_macs(&self, secrets: &[F::E], keys: &[MacKey<F>]) -> Vec<MacShare<F>> {
+        tag_shares(&self.field, self.share(secrets), keys)
+    }
+
+    /// Reconstruct the secrets from tagged shares as with `reconstruct_indexed`,
+    /// after checking the tag of every share under the key with its index in `keys`.
+    ///
+    /// Fails with the indices of the shares whose tag does not verify.
+    pub fn reconstruct_checked(
+        &self,
+        shares: &[MacShare<F>],
+        keys: &[MacKey<F>],
+    ) -> Result<Vec<F::E>, InvalidTags> {
+        let (indices, values) = check_shares(&self.field, shares, keys)?;
+        let indices: Vec<u32> = indices.into_iter().map(index_u32).collect();
+        Ok(self.reconstruct(&indices, &values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Fp61, SliceEncode};
+    use packed::PSS_4_26_3;
+    use rand::{ChaChaRng, SeedableRng};
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: Fp61,
+        };
+        let mut rng = ChaChaRng::from_seed(&[45]);
+        let keys = MacKey::generate_all(&Fp61, 6, &mut rng);
+        let shares = tss.share_with_macs(12345, &keys);
+        assert_eq!(shares[4].index, 4);
+        assert!(keys[4].verify(&Fp61, &shares[4].value, &shares[4].tag));
+        assert_eq!(tss.reconstruct_checked(&shares[3..], &keys), Ok(12345));
+
+        let mut tampered = shares[..4].to_vec();
+        tampered[1].value = Fp61.add(tampered[1].value, 1);
+        tampered[3].tag = Fp61.add(tampered[3].tag, 1);
+        assert_eq!(
+            tss.reconstruct_checked(&tampered, &keys),
+            Err(InvalidTags(vec![1, 3]))
+        );
+        // a share cannot be checked under the key of another index
+        let mut moved = shares[..3].to_vec();
+        moved[0].index = 5;
+        assert_eq!(
+            tss.reconstruct_checked(&moved, &keys),
+            Err(InvalidTags(vec![5]))
+        );
+        assert_eq!(
+            tss.reconstruct_checked(&shares[..3], &keys[..2]),
+            Err(InvalidTags(vec![2]))
+        );
+    }
+
+    #[test]
+    fn test_packed() {
+        let pss = &PSS_4_26_3;
+        let mut rng = ChaChaRng::from_seed(&[46]);
+        let keys = MacKey::generate_all(&pss.field, pss.share_count, &mut rng);
+        let secrets = pss.field.encode_slice([1, 2, 3]);
+        let shares = pss.share_with_macs(&secrets, &keys);
+        assert_eq!(shares.len(), pss.share_count);
+        assert_eq!(pss.reconstruct_checked(&shares[10..18], &keys), Ok(secrets));
+
+        let mut tampered = shares[..8].to_vec();
+        tampered[6].value = pss.field.add(tampered[6].value, pss.field.one());
+        assert_eq!(
+            pss.reconstruct_checked(&tampered, &keys),
+            Err(InvalidTags(vec![6]))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "one key is needed per share")]
+    fn test_missing_keys() {
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 3,
+            field: Fp61,
+        };
+        let keys = MacKey::generate_all(&Fp61, 2, &mut ChaChaRng::from_seed(&[47]));
+        tss.share_with_macs(1, &keys);
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Macros used throughout the crate.
+
+/// Implement the listed traits among `Clone`, `PartialEq` and `Debug` for a type
+/// generic over a field `F`, like `#[derive]` would.
+///
+/// `#[derive]` requires the traits of `F` itself rather than of the elements
+/// `F::E`, which most fields do not implement. The type is given with its fields,
+/// as `Name { a, b }` for a struct, `Name(0)` for a tuple struct, or
+/// `enum Name { Variant { a, b }, .. }` for an enum with named fields. Further type
+/// parameters after `F` are listed as in `Name<T> { a, b }`, and must implement
+/// the trait as well.
+macro_rules! element_impls {
+    ($($trait_:ident),+ for enum $name:ident $variants:tt) => {
+        $(element_impls!(@$trait_ enum $name [] $variants);)+
+    };
+    ($($trait_:ident),+ for $name:ident<$($param:ident),+> $fields:tt) => {
+        element_impls!(@each [$($trait_),+] $name [$($param),+] $fields);
+    };
+    ($($trait_:ident),+ for $name:ident $fields:tt) => {
+        $(element_impls!(@$trait_ $name [] $fields);)+
+    };
+    (@each [$($trait_:ident),+] $name:ident $params:tt $fields:tt) => {
+        $(element_impls!(@$trait_ $name $params $fields);)+
+    };
+
+    (@Clone $name:ident [$($param:ident),*] { $($field:ident),+ }) => {
+        impl<F: ::fields::Field $(, $param)*> Clone for $name<F $(, $param)*>
+        where
+            F::E: Clone,
+            $($param: Clone,)*
+        {
+            fn clone(&self) -> $name<F $(, $param)*> {
+                $name {
+                    $($field: self.$field.clone()),+
+                }
+            }
+        }
+    };
+    (@Clone $name:ident [$($param:ident),*] ($($index:tt),+)) => {
+        impl<F: ::fields::Field $(, $param)*> Clone for $name<F $(, $param)*>
+        where
+            F::E: Clone,
+            $($param: Clone,)*
+        {
+            fn clone(&self) -> $name<F $(, $param)*> {
+                $name($(self.$index.clone()),+)
+            }
+        }
+    };
+    (@Clone enum $name:ident [$($param:ident),*] { $($variant:ident { $($field:ident),+ }),+ }) => {
+        impl<F: ::fields::Field $(, $param)*> Clone for $name<F $(, $param)*>
+        where
+            F::E: Clone,
+            $($param: Clone,)*
+        {
+            fn clone(&self) -> $name<F $(, $param)*> {
+                match *self {
+                    $($name::$variant { $(ref $field),+ } => $name::$variant {
+                        $($field: $field.clone()),+
+                    },)+
+                }
+            }
+        }
+    };
+
+    (@PartialEq $name:ident [$($param:ident),*] { $($field:ident),+ }) => {
+        impl<F: ::fields::Field $(, $param)*> PartialEq for $name<F $(, $param)*>
+        where
+            F::E: PartialEq,
+            $($param: PartialEq,)*
+        {
+            fn eq(&self, other: &$name<F $(, $param)*>) -> bool {
+                ($(&self.$field,)+) == ($(&other.$field,)+)
+            }
+        }
+    };
+    (@PartialEq $name:ident [$($param:ident),*] ($($index:tt),+)) => {
+        impl<F: ::fields::Field $(, $param)*> PartialEq for $name<F $(, $param)*>
+        where
+            F::E: PartialEq,
+            $($param: PartialEq,)*
+        {
+            fn eq(&self, other: &$name<F $(, $param)*>) -> bool {
+                ($(&self.$index,)+) == ($(&other.$index,)+)
+            }
+        }
+    };
+    (@PartialEq enum $name:ident [$($param:ident),*] { $($variant:ident { $($field:ident),+ }),+ }) => {
+        impl<F: ::fields::Field $(, $param)*> PartialEq for $name<F $(, $param)*>
+        where
+            F::E: PartialEq,
+            $($param: PartialEq,)*
+        {
+            fn eq(&self, other: &$name<F $(, $param)*>) -> bool {
+                match *self {
+                    $($name::$variant { $(ref $field),+ } => {
+                        let fields = ($($field,)+);
+                        match *other {
+                            $name::$variant { $(ref $field),+ } => fields == ($($field,)+),
+                            #[allow(unreachable_patterns)]
+                            _ => false,
+                        }
+                    })+
+                }
+            }
+        }
+    };
+
+    (@Debug $name:ident [$($param:ident),*] { $($field:ident),+ }) => {
+        impl<F: ::fields::Field $(, $param)*> ::std::fmt::Debug for $name<F $(, $param)*>
+        where
+            F::E: ::std::fmt::Debug,
+            $($param: ::std::fmt::Debug,)*
+        {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    $(.field(stringify!($field), &self.$field))+
+                    .finish()
+            }
+        }
+    };
+    (@Debug $name:ident [$($param:ident),*] ($($index:tt),+)) => {
+        impl<F: ::fields::Field $(, $param)*> ::std::fmt::Debug for $name<F $(, $param)*>
+        where
+            F::E: ::std::fmt::Debug,
+            $($param: ::std::fmt::Debug,)*
+        {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.debug_tuple(stringify!($name))
+                    $(.field(&self.$index))+
+                    .finish()
+            }
+        }
+    };
+    (@Debug enum $name:ident [$($param:ident),*] { $($variant:ident { $($field:ident),+ }),+ }) => {
+        impl<F: ::fields::Field $(, $param)*> ::std::fmt::Debug for $name<F $(, $param)*>
+        where
+            F::E: ::std::fmt::Debug,
+            $($param: ::std::fmt::Debug,)*
+        {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match *self {
+                    $($name::$variant { $(ref $field),+ } => f
+                        .debug_struct(stringify!($variant))
+                        $(.field(stringify!($field), $field))+
+                        .finish(),)+
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use fields::{Field, NaturalPrimeField};
+
+    struct Pair<F: Field> {
+        index: usize,
+        values: Vec<F::E>,
+    }
+
+    struct Wrapper<F: Field>(F::E);
+
+    struct Tagged<F: Field, T> {
+        value: F::E,
+        tag: T,
+    }
+
+    enum Message<F: Field> {
+        One { value: F::E },
+        Two { first: F::E, second: F::E },
+    }
+
+    element_impls!(Clone, PartialEq, Debug for Pair { index, values });
+    element_impls!(Clone, PartialEq, Debug for Wrapper(0));
+    element_impls!(Clone, PartialEq, Debug for Tagged<T> { value, tag });
+    element_impls!(Clone, PartialEq, Debug for enum Message {
+        One { value },
+        Two { first, second }
+    });
+
+    type Zp = NaturalPrimeField<i64>;
+
+    #[test]
+    fn test_element_impls() {
+        let pair: Pair<Zp> = Pair {
+            index: 3,
+            values: vec![1, 2],
+        };
+        assert_eq!(pair.clone(), pair);
+        assert!(
+            pair != Pair {
+                index: 4,
+                ..pair.clone()
+            }
+        );
+        assert_eq!(format!("{:?}", pair), "Pair { index: 3, values: [1, 2] }");
+
+        let wrapper: Wrapper<Zp> = Wrapper(5);
+        assert_eq!(wrapper.clone(), Wrapper(5));
+        assert_eq!(format!("{:?}", wrapper), "Wrapper(5)");
+
+        let tagged: Tagged<Zp, &str> = Tagged { value: 5, tag: "a" };
+        assert_eq!(tagged.clone(), tagged);
+        assert!(tagged != Tagged { value: 5, tag: "b" });
+        assert_eq!(format!("{:?}", tagged), "Tagged { value: 5, tag: \"a\" }");
+
+        let one: Message<Zp> = Message::One { value: 1 };
+        let two: Message<Zp> = Message::Two {
+            first: 1,
+            second: 2,
+        };
+        assert_eq!(one.clone(), one);
+        assert_eq!(two.clone(), two);
+        assert!(one != two);
+        assert!(
+            two != Message::Two {
+                first: 1,
+                second: 3
+            }
+        );
+        assert_eq!(format!("{:?}", two), "Two { first: 1, second: 2 }");
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Number theory and polynomial arithmetic over the fields of this crate, as used
+//! by the sharing schemes.
+//!
+//! The items here follow semantic versioning like the rest of the public interface.
+//! The `numtheory` module they are taken from is organised around the needs of the
+//! schemes and may change in any release, so it is hidden from the documentation and
+//! should not be used directly.
+
+pub use ntt::Ntt;
+pub use numtheory::fft::{is_mixed_radix_len, mixed_radix_order};
+pub use numtheory::{
+    batch_inverse, find_generator, find_root_of_unity, gcd, lagrange_coefficients,
+    lagrange_coefficients_at_zero, lagrange_interpolation_at_point, lagrange_interpolation_at_zero,
+    mod_evaluate_polynomial, newton_interpolation_at_point, BarycentricWeights, InterpolationCache,
+    LagrangeConstants, NewtonPolynomial, Primality, PrimeModulus, SubproductTree,
+};
+
+/// Linear algebra over any field: matrix products and inverses, Vandermonde
+/// matrices, and Gaussian elimination. Matrices are given as vectors of their rows.
+pub mod linalg {
+    pub use numtheory::linalg::{
+        identity, invert, mul, mul_vector, rank, row_reduce, solve, vandermonde,
+        vandermonde_inverse,
+    };
+}
+
+/// Arithmetic on polynomials given by their coefficients, lowest degree first.
+pub mod poly {
+    pub use numtheory::poly::{add, degree, derivative, div_rem, mul, mul_fft, sub, trim};
+}
@@ -0,0 +1,272 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Management of the set of participants holding shares.
+//!
+//! A `Committee` keeps track of the indices of the current members. New members are
+//! enrolled at indices never used before, and obtain their share from a quorum of
+//! current members using the recovery protocol (`recovery_subshares`,
+//! `combine_recovery_subshares` and `recover_share_for`).
+//!
+//! Retiring a member requires the remaining ones to refresh their shares: each of
+//! them deals a random sharing of zero to the others (`refresh_subshares`), which
+//! they add to their shares (`apply_refresh`). The secrets are unchanged, but shares
+//! from before the refresh, such as those of the retired member, no longer combine
+//! with the refreshed ones.
+
+use fields::{Encode, Field};
+use packed::PackedSecretSharing;
+use shamir::ShamirSecretSharing;
+use share::ShareIndex;
+
+/// Indices of the participants currently holding shares.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Committee {
+    members: Vec<ShareIndex>,
+    capacity: usize,
+    next_index: ShareIndex,
+    epoch: u64,
+}
+
+impl Committee {
+    /// Committee of the participants with indices `0..member_count`, to which at
+    /// most `capacity` indices may ever be assigned.
+    ///
+    /// For the packed scheme the capacity is `share_count`, since shares can only be
+    /// issued at its fixed positions.
+    pub fn new(member_count: usize, capacity: usize) -> Committee {
+        assert!(member_count <= capacity);
+        Committee {
+            members: (0..member_count).collect(),
+            capacity,
+            next_index: member_count,
+            epoch: 0,
+        }
+    }
+
+    /// Indices of the current members, in order of enrolment.
+    pub fn members(&self) -> &[ShareIndex] {
+        &self.members
+    }
+
+    pub fn is_member(&self, index: ShareIndex) -> bool {
+        self.members.contains(&index)
+    }
+
+    /// Number of members retired so far, each requiring a refresh; shares from
+    /// different epochs must not be combined.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Assign a fresh index to a new participant, or `None` if all indices have been
+    /// used.
+    ///
+    /// Retired indices are never reassigned.
+    pub fn enrol(&mut self) -> Option<ShareIndex> {
+        if self.next_index >= self.capacity {
+            return None;
+        }
+        let index = self.next_index;
+        self.next_index += 1;
+        self.members.push(index);
+        Some(index)
+    }
+
+    /// Remove the member with `index`, starting a new epoch, and return whether it
+    /// was a member.
+    ///
+    /// The remaining members must then refresh their shares.
+    pub fn retire(&mut self, index: ShareIndex) -> bool {
+        match self.members.iter().position(|&member| member == index) {
+            Some(position) => {
+                self.members.remove(position);
+                self.epoch += 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<F> ShamirSecretSharing<F>
+where
+    F: Field,
+    F: Encode<u32>,
+    F::E: Clone,
+{
+    /// Random shares of zero for the `members`, dealt by one of them when refreshing;
+    /// the share at position `i` is to be sent to `members[i]`.
+    pub fn refresh_subshares(&self, members: &[ShareIndex]) -> Vec<F::E> {
+        let zero_shares = self.share(self.field.zero());
+        let limit = self.reconstruct_limit();
+        let indices: Vec<usize> = (0..limit).collect();
+        members
+            .iter()
+            .map(|&member| {
+                if member < self.share_count {
+                    zero_shares[member].clone()
+                } else {
+                    // enrolled beyond the initial shares
+                    let point = self.field.encode(member as u32 + 1);
+                    self.evaluate_at(&indices, &zero_shares[..limit], &point)
+                }
+            })
+            .collect()
+    }
+
+    /// Refreshed `share`, from the `subshares` received from every remaining member.
+    pub fn apply_refresh(&self, share: &F::E, subshares: &[F::E]) -> F::E {
+        subshares
+            .iter()
+            .fold(share.clone(), |acc, subshare| self.field.add(acc, subshare))
+    }
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field,
+    F: Encode<u32>,
+    F: Clone,
+    F::E: Clone,
+{
+    /// Random shares of a vector of zeros for the `members`, dealt by one of them
+    /// when refreshing; the share at position `i` is to be sent to `members[i]`.
+    pub fn refresh_subshares(&self, members: &[u32]) -> Vec<F::E> {
+        let zero_shares = self.share(&vec![self.field.zero(); self.secret_count]);
+        members
+            .iter()
+            .map(|&member| zero_shares[member as usize].clone())
+            .collect()
+    }
+
+    /// Refreshed `share`, from the `subshares` received from every remaining member.
+    pub fn apply_refresh(&self, share: &F::E, subshares: &[F::E]) -> F::E {
+        subshares
+            .iter()
+            .fold(share.clone(), |acc, subshare| self.field.add(acc, subshare))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::NaturalPrimeField;
+    use packed::PSS_4_26_3;
+
+    #[test]
+    fn test_committee() {
+        let mut committee = Committee::new(3, 5);
+        assert_eq!(committee.members(), [0, 1, 2]);
+        assert_eq!(committee.enrol(), Some(3));
+        assert!(committee.retire(1));
+        assert!(!committee.retire(1));
+        assert_eq!(committee.epoch(), 1);
+        assert_eq!(committee.enrol(), Some(4));
+        assert_eq!(committee.enrol(), None);
+        assert_eq!(committee.members(), [0, 2, 3, 4]);
+        assert!(!committee.is_member(1));
+    }
+
+    /// Run a refresh among the members of `committee`.
+    fn refresh<R, A>(committee: &Committee, shares: &mut [i64], refresh_subshares: R, apply: A)
+    where
+        R: Fn(&[usize]) -> Vec<i64>,
+        A: Fn(&i64, &[i64]) -> i64,
+    {
+        let members = committee.members();
+        let dealt: Vec<Vec<i64>> = members.iter().map(|_| refresh_subshares(members)).collect();
+        for (position, &member) in members.iter().enumerate() {
+            let received: Vec<i64> = dealt.iter().map(|d| d[position]).collect();
+            shares[member] = apply(&shares[member], &received);
+        }
+    }
+
+    #[test]
+    fn test_shamir_membership() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            field: NaturalPrimeField(2147483647),
+        };
+        let mut committee = Committee::new(tss.share_count, 100);
+        let mut shares = tss.share(1234);
+        shares.resize(100, 0);
+
+        // enrol a new member using the recovery protocol with members 0, 2 and 4
+        let new = committee.enrol().unwrap();
+        assert_eq!(new, 5);
+        let helpers = [0, 2, 4];
+        let helper_subshares = helpers
+            .iter()
+            .enumerate()
+            .map(|(position, &i)| tss.recovery_subshares(new, &helpers, position, &shares[i]))
+            .fold(vec![0; 3], |acc, pieces| tss.add_shares(&acc, &pieces));
+        shares[new] = tss.recover_share_for(new, &helpers, &helper_subshares);
+        assert_eq!(
+            tss.reconstruct(&[5, 1, 3], &[shares[5], shares[1], shares[3]]),
+            1234
+        );
+
+        // retire member 1 and refresh
+        let old_share = shares[1];
+        assert!(committee.retire(1));
+        refresh(
+            &committee,
+            &mut shares,
+            |members| tss.refresh_subshares(members),
+            |share, subshares| tss.apply_refresh(share, subshares),
+        );
+        assert_eq!(
+            tss.reconstruct(&[0, 3, 5], &[shares[0], shares[3], shares[5]]),
+            1234
+        );
+        assert!(tss.reconstruct(&[1, 3, 5], &[old_share, shares[3], shares[5]]) != 1234);
+    }
+
+    #[test]
+    fn test_packed_membership() {
+        let pss = &PSS_4_26_3;
+        // only the first 20 shares are issued initially
+        let mut committee = Committee::new(20, pss.share_count);
+        let mut shares = pss.share(&[5, 6, 7]);
+
+        let new = committee.enrol().unwrap() as u32;
+        assert_eq!(new, 20);
+        let helpers: Vec<u32> = (0..7).collect();
+        let helper_subshares = helpers
+            .iter()
+            .enumerate()
+            .map(|(position, &i)| {
+                pss.recovery_subshares(new, &helpers, position, &shares[i as usize])
+            })
+            .fold(vec![0; 7], |acc, pieces| pss.add_shares(&acc, &pieces));
+        assert_eq!(
+            pss.recover_share_for(new, &helpers, &helper_subshares),
+            shares[20]
+        );
+
+        let old_share = shares[0];
+        assert!(committee.retire(0));
+        refresh(
+            &committee,
+            &mut shares,
+            |members| {
+                let members: Vec<u32> = members.iter().map(|&m| m as u32).collect();
+                pss.refresh_subshares(&members)
+            },
+            |share, subshares| pss.apply_refresh(share, subshares),
+        );
+        let indices: Vec<u32> = (14..21).collect();
+        assert_eq!(pss.reconstruct(&indices, &shares[14..21]), [5, 6, 7]);
+        let mut stale = shares[1..7].to_vec();
+        stale.insert(0, old_share);
+        assert!(pss.reconstruct(&[0, 1, 2, 3, 4, 5, 6], &stale) != [5, 6, 7]);
+    }
+}
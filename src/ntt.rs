@@ -0,0 +1,310 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Number theoretic transforms over the fields of this crate.
+//!
+//! This is the FFT used internally by the sharing schemes, packaged for use on its
+//! own, for instance to multiply polynomials or evaluate them for commitments.
+//! Unlike the functions of `numtheory::fft`, this interface is stable.
+
+use fields::{Encode, Field};
+use numtheory::fft::{is_mixed_radix_len, FftPlan, NegacyclicFftPlan};
+
+/// Precomputed number theoretic transform of a fixed length.
+///
+/// The cyclic transform of `x` is `X_k = sum_j x_j.omega^(j.k)`, ie. the values of
+/// the polynomial with coefficients `x` at the powers of `omega`, and its pointwise
+/// products are products of polynomials modulo `x^len - 1`.
+///
+/// The negacyclic transform uses a root `psi` of order `2.len` and is
+/// `X_k = sum_j x_j.psi^(j.(2k + 1))`, ie. the values at the odd powers of `psi`,
+/// and its pointwise products are products of polynomials modulo `x^len + 1`.
+///
+/// # Example:
+///
+/// ```
+///    use threshold_secret_sharing::*;
+///    use threshold_secret_sharing::ntt::Ntt;
+///    let field = NaturalPrimeField::new(433);
+///    // 354 is a root of unity of order 8
+///    let ntt = Ntt::new(&field, &354, 8);
+///    let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+///    ntt.forward(&mut data);
+///    assert_eq!(data, [36, 303, 146, 3, 429, 422, 279, 122]);
+///    ntt.inverse(&mut data);
+///    assert_eq!(data, [1, 2, 3, 4, 5, 6, 7, 8]);
+///    // (1 + x)(1 + x^3) = 1 + x + x^3 + x^4 = x + x^3 modulo x^4 + 1
+///    let ntt = Ntt::negacyclic(&field, &354, 4);
+///    assert_eq!(ntt.multiply(&[1, 1], &[1, 0, 0, 1]), [0, 1, 0, 1]);
+/// ```
+pub struct Ntt<F: Field> {
+    field: F,
+    plan: Plan<F>,
+}
+
+enum Plan<F: Field> {
+    Cyclic(FftPlan<F>),
+    Negacyclic(NegacyclicFftPlan<F>),
+}
+
+impl<F> Ntt<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Cyclic transform of length `len`, which must be of the form `2^a.3^b`.
+    ///
+    /// Panics unless `omega` is a principal root of unity of order `len`.
+    pub fn new(field: &F, omega: &F::E, len: usize) -> Ntt<F> {
+        assert!(
+            is_mixed_radix_len(len),
+            "length must be of the form 2^a.3^b"
+        );
+        assert!(
+            is_principal_root(field, omega, len),
+            "omega must be a principal root of unity of order len"
+        );
+        Ntt {
+            field: field.clone(),
+            plan: Plan::Cyclic(FftPlan::new(field, len, omega)),
+        }
+    }
+
+    /// Negacyclic transform of length `len`, which must be of the form `2^a.3^b`.
+    ///
+    /// Panics unless `psi` is a principal root of unity of order `2.len`.
+    pub fn negacyclic(field: &F, psi: &F::E, len: usize) -> Ntt<F> {
+        assert!(
+            is_mixed_radix_len(len),
+            "length must be of the form 2^a.3^b"
+        );
+        assert!(
+            is_principal_root(field, psi, 2 * len),
+            "psi must be a principal root of unity of order 2.len"
+        );
+        Ntt {
+            field: field.clone(),
+            plan: Plan::Negacyclic(NegacyclicFftPlan::new(field, len, psi)),
+        }
+    }
+
+    /// Length of the transforms.
+    pub fn len(&self) -> usize {
+        match self.plan {
+            Plan::Cyclic(ref plan) => plan.len(),
+            Plan::Negacyclic(ref plan) => plan.len(),
+        }
+    }
+
+    /// Whether the transforms are empty, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether this is a negacyclic transform.
+    pub fn is_negacyclic(&self) -> bool {
+        match self.plan {
+            Plan::Cyclic(_) => false,
+            Plan::Negacyclic(_) => true,
+        }
+    }
+
+    /// Forward transform of `data` in place, which must have length `len`.
+    pub fn forward(&self, data: &mut [F::E]) {
+        assert_eq!(data.len(), self.len());
+        match self.plan {
+            Plan::Cyclic(ref plan) => plan.forward(data),
+            Plan::Negacyclic(ref plan) => plan.forward(data),
+        }
+    }
+
+    /// Inverse transform of `data` in place, which must have length `len`.
+    pub fn inverse(&self, data: &mut [F::E]) {
+        assert_eq!(data.len(), self.len());
+        match self.plan {
+            Plan::Cyclic(ref plan) => plan.inverse(data),
+            Plan::Negacyclic(ref plan) => plan.inverse(data),
+        }
+    }
+
+    /// Product of the polynomials with coefficients `a` and `b`, modulo `x^len - 1`
+    /// for cyclic transforms and `x^len + 1` for negacyclic ones.
+    ///
+    /// Both may have fewer than `len` coefficients, and the result has `len`.
+    pub fn multiply(&self, a: &[F::E], b: &[F::E]) -> Vec<F::E> {
+        let field = &self.field;
+        let len = self.len();
+        assert!(a.len() <= len && b.len() <= len);
+        let mut a = a.to_vec();
+        a.resize(len, field.zero());
+        let mut b = b.to_vec();
+        b.resize(len, field.zero());
+        self.forward(&mut a);
+        self.forward(&mut b);
+        field.mul_pairwise_slice(&mut a, &b);
+        self.inverse(&mut a);
+        a
+    }
+}
+
+/// Whether `root` has order exactly `order`, which is of the form `2^a.3^b`.
+fn is_principal_root<F: Field>(field: &F, root: &F::E, order: usize) -> bool {
+    let one = field.one();
+    field.eq(field.pow(root, order as u32), &one)
+        && [2, 3]
+            .iter()
+            .filter(|&&q| order % q == 0)
+            .all(|&q| field.neq(field.pow(root, (order / q) as u32), &one))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Decode, MontgomeryField32, NaturalPrimeField, New, SliceDecode};
+
+    /// The first `count` powers of `x`, starting from one.
+    fn powers<F: Field>(field: &F, x: F::E, count: usize) -> Vec<F::E>
+    where
+        F::E: Clone,
+    {
+        let mut powers = Vec::with_capacity(count);
+        let mut current = field.one();
+        for _ in 0..count {
+            let next = field.mul(&current, &x);
+            powers.push(current);
+            current = next;
+        }
+        powers
+    }
+
+    /// Values of the polynomial with coefficients `data` at `points`.
+    fn naive_evaluate<F: Field>(field: &F, data: &[F::E], points: &[F::E]) -> Vec<F::E> {
+        points
+            .iter()
+            .map(|point| {
+                data.iter()
+                    .rev()
+                    .fold(field.zero(), |acc, x| field.add(field.mul(acc, point), x))
+            })
+            .collect()
+    }
+
+    fn check_transforms<F>(field: &F, omega: u32, psi: u32, len: usize)
+    where
+        F: Field + Encode<u32> + Decode<u32> + Clone,
+        F::E: Clone,
+    {
+        let data: Vec<F::E> = (0..len as u32).map(|x| field.encode(x * x + 7)).collect();
+
+        let omega = field.encode(omega);
+        let ntt = Ntt::new(field, &omega, len);
+        assert!(!ntt.is_negacyclic());
+        let points = powers(field, omega, len);
+        let mut transformed = data.clone();
+        ntt.forward(&mut transformed);
+        assert_eq!(
+            field.decode_slice(&transformed),
+            field.decode_slice(naive_evaluate(field, &data, &points))
+        );
+        ntt.inverse(&mut transformed);
+        assert_eq!(field.decode_slice(transformed), field.decode_slice(&data));
+
+        let psi = field.encode(psi);
+        let ntt = Ntt::negacyclic(field, &psi, len);
+        assert!(ntt.is_negacyclic());
+        let odd_points: Vec<F::E> = powers(field, psi.clone(), 2 * len)
+            .into_iter()
+            .skip(1)
+            .step_by(2)
+            .collect();
+        let mut transformed = data.clone();
+        ntt.forward(&mut transformed);
+        assert_eq!(
+            field.decode_slice(&transformed),
+            field.decode_slice(naive_evaluate(field, &data, &odd_points))
+        );
+        ntt.inverse(&mut transformed);
+        assert_eq!(field.decode_slice(transformed), field.decode_slice(&data));
+    }
+
+    /// Product modulo `x^len - sign`.
+    fn naive_multiply<F: Field>(field: &F, a: &[F::E], b: &[F::E], sign: F::E) -> Vec<F::E>
+    where
+        F::E: Clone,
+    {
+        let len = a.len();
+        let mut product = vec![field.zero(); len];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                let mut term = field.mul(x, y);
+                if i + j >= len {
+                    term = field.mul(term, &sign);
+                }
+                product[(i + j) % len] = field.add(&product[(i + j) % len], term);
+            }
+        }
+        product
+    }
+
+    #[test]
+    fn test_transforms() {
+        // 354 has order 8, 150 order 9 and 17 order 27 in Z_433, while -1 has order 2
+        let field = NaturalPrimeField::new(433);
+        check_transforms(&field, 354 * 354 % 433, 354, 4);
+        check_transforms(&field, 1, 432, 1);
+        check_transforms(&field, 150, 433 - 150, 9);
+        check_transforms(&field, 17, 433 - 17, 27);
+        let omega_12 = field.mul(field.pow(354, 2), field.pow(150, 3));
+        let psi_12 = field.mul(354, field.pow(150, 3));
+        check_transforms(&field, omega_12 as u32, psi_12 as u32, 12);
+
+        let field = MontgomeryField32::new(433);
+        check_transforms(&field, 150, 433 - 150, 9);
+    }
+
+    #[test]
+    fn test_multiply() {
+        let field = NaturalPrimeField::new(433);
+        let a: Vec<i64> = (1..10).collect();
+        let b: Vec<i64> = (20..29).rev().collect();
+        let cyclic = Ntt::new(&field, &150, 9);
+        assert_eq!(cyclic.multiply(&a, &b), naive_multiply(&field, &a, &b, 1));
+        let negacyclic = Ntt::negacyclic(&field, &(433 - 150), 9);
+        assert_eq!(
+            negacyclic.multiply(&a, &b),
+            naive_multiply(&field, &a, &b, 432)
+        );
+        // shorter inputs are padded
+        assert_eq!(
+            negacyclic.multiply(&a[..3], &b[..2]),
+            naive_multiply(
+                &field,
+                &[1, 2, 3, 0, 0, 0, 0, 0, 0],
+                &[28, 27, 0, 0, 0, 0, 0, 0, 0],
+                432
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "omega must be a principal root of unity")]
+    fn test_non_principal_root() {
+        // 354^2 only has order 4
+        let field = NaturalPrimeField::new(433);
+        Ntt::new(&field, &field.pow(354, 2), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "psi must be a principal root of unity")]
+    fn test_negacyclic_root() {
+        // 354 has order 8 rather than 16
+        let field = NaturalPrimeField::new(433);
+        Ntt::negacyclic(&field, &354, 8);
+    }
+}
@@ -1,44 +1,36 @@
 // Copyright (c) 2017 rust-threshold-secret-sharing developers
 
-use fields::Field;
+use fields::{Exponent, Field};
 use std::borrow::Borrow;
 
 /// `x` to the power of `e` in the *Zp* field defined by `prime`.
-pub fn mod_pow(mut x: i64, mut e: u32, prime: i64) -> i64 {
+pub fn mod_pow<X: Exponent>(mut x: i64, e: X, prime: i64) -> i64 {
     let mut acc = 1;
-    while e > 0 {
-        if e % 2 == 0 {
-            // even
-            // no-op
-        } else {
-            // odd
+    for i in 0..e.bit_length() {
+        if e.bit(i) {
+            // bit i is set
             acc = (acc * x) % prime;
         }
         x = (x * x) % prime; // waste one of these by having it here but code is simpler (tiny bit)
-        e >>= 1;
     }
     acc
 }
 
-pub fn generic_mod_pow<F>(field: &F, a: F::E, e: u32) -> F::E
+pub fn generic_mod_pow<F, X>(field: &F, a: F::E, e: X) -> F::E
 where
     F: Field,
+    X: Exponent,
 {
     // TODO improve (or at least compare to non-generic)
 
     let mut x = a;
-    let mut e = e;
     let mut acc = field.one();
-    while e > 0 {
-        if e % 2 == 0 {
-            // even
-            // no-op
-        } else {
-            // odd
+    for i in 0..e.bit_length() {
+        if e.bit(i) {
+            // bit i is set
             acc = field.mul(&acc, &x);
         }
         x = field.mul(&x, &x); // waste one of these by having it here but code is simpler (tiny bit)
-        e >>= 1;
     }
     acc
 }
@@ -70,6 +62,72 @@ where
         .fold(field.zero(), |sum, term| field.add(sum, term))
 }
 
+/// Invert all `values` at once using Montgomery's trick.
+///
+/// Only a single field inversion is performed, at the cost of `3(k - 1)`
+/// multiplications. Panics if any value is zero, which would otherwise turn
+/// every inverse into zero.
+pub fn batch_inverse<F>(values: &[F::E], field: &F) -> Vec<F::E>
+where
+    F: Field + ?Sized,
+{
+    if values.is_empty() {
+        return vec![];
+    }
+
+    // prefix products `values[0] * ... * values[i]`
+    let mut prefixes = Vec::with_capacity(values.len());
+    prefixes.push(field.mul(field.one(), &values[0]));
+    for (i, value) in values.iter().enumerate().skip(1) {
+        let product = field.mul(&prefixes[i - 1], value);
+        prefixes.push(product);
+    }
+
+    // walk back, peeling off one value at a time from the inverted product
+    assert!(
+        field.neq(&prefixes[values.len() - 1], field.zero()),
+        "cannot invert zero"
+    );
+    let mut acc = field.inv(&prefixes[values.len() - 1]);
+    let mut inverses = Vec::with_capacity(values.len());
+    for i in (1..values.len()).rev() {
+        inverses.push(field.mul(&acc, &prefixes[i - 1]));
+        acc = field.mul(acc, &values[i]);
+    }
+    inverses.push(acc);
+    inverses.reverse();
+    inverses
+}
+
+/// Invert all `values` in place like `batch_inverse`, using the first
+/// `values.len()` elements of `scratch` instead of allocating.
+pub fn batch_inverse_in_place<F>(values: &mut [F::E], scratch: &mut [F::E], field: &F)
+where
+    F: Field + ?Sized,
+    F::E: Clone,
+{
+    if values.is_empty() {
+        return;
+    }
+    let prefixes = &mut scratch[..values.len()];
+    prefixes[0] = values[0].clone();
+    for i in 1..values.len() {
+        prefixes[i] = field.mul(&prefixes[i - 1], &values[i]);
+    }
+
+    assert!(
+        field.neq(&prefixes[values.len() - 1], field.zero()),
+        "cannot invert zero"
+    );
+    let mut acc = field.inv(&prefixes[values.len() - 1]);
+    for i in (1..values.len()).rev() {
+        let inverse = field.mul(&acc, &prefixes[i - 1]);
+        acc = field.mul(acc, &values[i]);
+        values[i] = inverse;
+    }
+    values[0] = acc;
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -85,6 +143,12 @@ mod tests {
         assert_eq!(mod_pow(-3, 0, 17), 1);
         assert_eq!(mod_pow(-3, 1, 17), -3);
         assert_eq!(mod_pow(-3, 15, 17), -6);
+
+        // (p - 1) / 2 does not fit in a u32 for this p
+        let p: i64 = 2305843009213693951; // 2^61 - 1
+        let e = ::fields::BigExponent::from((p as u64 - 1) / 2);
+        assert_eq!(mod_pow(1, &e, p), 1);
+        assert_eq!(mod_pow(-1, e, p), -1);
     }
 
     #[test]
@@ -94,4 +158,30 @@ mod tests {
         let field = fields::NaturalPrimeField(17);
         assert_eq!(mod_evaluate_polynomial(&poly, point, &field), 4);
     }
+
+    #[test]
+    fn test_batch_inverse() {
+        let field = fields::NaturalPrimeField(17);
+        assert_eq!(batch_inverse(&[], &field), [0_i64; 0]);
+        assert_eq!(batch_inverse(&[5], &field), [7]);
+        assert_eq!(batch_inverse(&[1, 2, 13, 9, 16], &field), [1, 9, 4, 2, 16]);
+
+        let mut values = [1, 2, 13, 9, 16];
+        batch_inverse_in_place(&mut values, &mut [0; 6], &field);
+        assert_eq!(values, [1, 9, 4, 2, 16]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot invert zero")]
+    fn test_batch_inverse_zero() {
+        let field = fields::NaturalPrimeField(17);
+        batch_inverse(&[1, 2, 0, 9], &field);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot invert zero")]
+    fn test_batch_inverse_in_place_zero() {
+        let field = fields::NaturalPrimeField(17);
+        batch_inverse_in_place(&mut [1, 2, 17, 9], &mut [0; 4], &field);
+    }
 }
@@ -62,12 +62,9 @@ pub fn binary_egcd(mut a: i64, mut b: i64) -> (i64, i64, i64) {
                 t = (t - alpha) >> 1;
             }
         } else if b < a {
-            a = b;
-            b = a;
-            u = s;
-            v = t;
-            s = u;
-            t = v;
+            ::std::mem::swap(&mut a, &mut b);
+            ::std::mem::swap(&mut u, &mut s);
+            ::std::mem::swap(&mut v, &mut t);
         } else {
             b -= a;
             s -= u;
@@ -7,7 +7,10 @@
 // modified, or distributed except according to those terms.
 
 //! This module implements in-place 2-radix and 3-radix numeric theory
-//! transformations (FFT on modular fields) by in-place Cooley-Tukey algorithms.
+//! transformations (FFT on modular fields) by in-place Cooley-Tukey algorithms,
+//! as well as a mixed-radix variant for lengths of the form `2^a.3^b` and
+//! Bluestein's algorithm for arbitrary lengths. Plans precompute the twiddle
+//! factors of either, and negacyclic plans twist them to multiply modulo `x^n + 1`.
 
 use fields::Encode;
 use fields::Field;
@@ -46,9 +49,7 @@ where
     let len = data.len();
     let len_inv = zp.inv(zp.encode(len as u32));
     fft2(zp, data, &omega_inv);
-    for x in data {
-        *x = zp.mul(&*x, &len_inv);
-    }
+    zp.mul_scalar_slice(data, &len_inv);
 }
 
 fn fft2_in_place_rearrange<F>(_zp: &F, data: &mut [F::E])
@@ -74,26 +75,23 @@ where
     F: Field,
     F::E: Clone,
 {
-    let mut depth = 0usize;
-    while 1usize << depth < data.len() {
-        let step = 1usize << depth;
+    let mut step = 1;
+    let mut twiddles = Vec::with_capacity(data.len() / 2);
+    while step < data.len() {
         let jump = 2 * step;
-        let factor_stride = zp.pow(omega, (data.len() / step / 2) as u32);
+        let factor_stride = zp.pow(omega, (data.len() / jump) as u32);
+        twiddles.clear();
         let mut factor = zp.one();
-        for group in 0usize..step {
-            let mut pair = group;
-            while pair < data.len() {
-                let x = data[pair].clone();
-                let y = zp.mul(&data[pair + step], &factor);
-
-                data[pair] = zp.add(&x, &y);
-                data[pair + step] = zp.sub(&x, &y);
-
-                pair += jump;
-            }
-            factor = zp.mul(factor, &factor_stride);
+        for _ in 0..step {
+            let next = zp.mul(&factor, &factor_stride);
+            twiddles.push(factor);
+            factor = next;
         }
-        depth += 1;
+        for block in data.chunks_mut(jump) {
+            let (lo, hi) = block.split_at_mut(step);
+            zp.fft2_butterflies(lo, hi, &twiddles);
+        }
+        step = jump;
     }
 }
 
@@ -130,9 +128,7 @@ where
     let omega_inv = zp.inv(omega);
     let len_inv = zp.inv(zp.encode(data.len() as u32));
     fft3(zp, data, &omega_inv);
-    for x in data {
-        *x = zp.mul(&*x, &len_inv);
-    }
+    zp.mul_scalar_slice(data, &len_inv);
 }
 
 fn trigits_len(n: usize) -> usize {
@@ -148,7 +144,7 @@ fn trigits_len(n: usize) -> usize {
 fn fft3_in_place_rearrange<F: Field>(_zp: &F, data: &mut [F::E]) {
     let mut target = 0isize;
     let trigits_len = trigits_len(data.len() - 1);
-    let mut trigits: Vec<u8> = ::std::iter::repeat(0).take(trigits_len).collect();
+    let mut trigits: Vec<u8> = vec![0; trigits_len];
     let powers: Vec<isize> = (0..trigits_len)
         .map(|x| 3isize.pow(x as u32))
         .rev()
@@ -178,34 +174,934 @@ where
     let mut step = 1;
     let big_omega = zp.pow(omega, data.len() as u32 / 3);
     let big_omega_sq = zp.mul(&big_omega, &big_omega);
+    let mut twiddles = Vec::with_capacity(data.len() / 3);
+    let mut twiddles_sq = Vec::with_capacity(data.len() / 3);
     while step < data.len() {
         let jump = 3 * step;
-        let factor_stride = zp.pow(omega, (data.len() / step / 3) as u32);
+        let factor_stride = zp.pow(omega, (data.len() / jump) as u32);
+        twiddles.clear();
+        twiddles_sq.clear();
         let mut factor = zp.one();
-        for group in 0usize..step {
-            let factor_sq = zp.mul(&factor, &factor);
-            let mut pair = group;
-            while pair < data.len() {
-                let x = data[pair].clone();
-                let y = zp.mul(&data[pair + step], &factor);
-                let z = zp.mul(&data[pair + 2 * step], &factor_sq);
-
-                data[pair] = zp.add(zp.add(&x, &y), &z);
-                data[pair + step] = zp.add(
-                    zp.add(&x, zp.mul(&big_omega, &y)),
-                    zp.mul(&big_omega_sq, &z),
-                );
-                data[pair + 2 * step] = zp.add(
-                    zp.add(&x, zp.mul(&big_omega_sq, &y)),
-                    zp.mul(&big_omega, &z),
-                );
+        for _ in 0..step {
+            let next = zp.mul(&factor, &factor_stride);
+            twiddles_sq.push(zp.mul(&factor, &factor));
+            twiddles.push(factor);
+            factor = next;
+        }
+        for block in data.chunks_mut(jump) {
+            let (a, rest) = block.split_at_mut(step);
+            let (b, c) = rest.split_at_mut(step);
+            zp.fft3_butterflies(a, b, c, &twiddles, &twiddles_sq, &big_omega, &big_omega_sq);
+        }
+        step = jump;
+    }
+}
+
+/// Mixed-radix FFT.
+///
+/// * zp is the modular field
+/// * data is the data to transform
+/// * omega is the root-of-unity to use
+///
+/// `data.len()` must be of the form `2^a.3^b`. omega must be a root of unity
+/// of order `data.len()`
+pub fn fft_mixed<F>(zp: &F, data: &mut [F::E], omega: &F::E)
+where
+    F: Field,
+    F::E: Clone,
+{
+    let radices = mixed_radices(data.len());
+    let stages = fft_mixed_stages(zp, omega, &radices);
+    let roots_of_three = fft_mixed_roots_of_three(zp, omega, data.len());
+    fft_mixed_rearrange(zp, data, &radices);
+    fft_mixed_apply(zp, data, &stages, roots_of_three.as_ref());
+}
+
+/// Mixed-radix inverse FFT.
+///
+/// * zp is the modular field
+/// * data is the data to transform
+/// * omega is the root-of-unity to use
+///
+/// `data.len()` must be of the form `2^a.3^b`. omega must be a root of unity
+/// of order `data.len()`
+pub fn fft_mixed_inverse<F>(zp: &F, data: &mut [F::E], omega: &F::E)
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    let omega_inv = zp.inv(omega);
+    let len_inv = zp.inv(zp.encode(data.len() as u32));
+    fft_mixed(zp, data, &omega_inv);
+    zp.mul_scalar_slice(data, &len_inv);
+}
+
+/// Whether `n` is of the form `2^a.3^b`, ie. a valid length for `fft_mixed`.
+pub const fn is_mixed_radix_len(mut n: usize) -> bool {
+    if n == 0 {
+        return false;
+    }
+    while n % 2 == 0 {
+        n /= 2;
+    }
+    while n % 3 == 0 {
+        n /= 3;
+    }
+    n == 1
+}
 
-                pair += jump;
+/// Factors of `n`, radix 2 stages first.
+fn mixed_radices(mut n: usize) -> Vec<usize> {
+    assert!(is_mixed_radix_len(n), "length must be of the form 2^a.3^b");
+    let mut radices = vec![];
+    while n % 2 == 0 {
+        radices.push(2);
+        n /= 2;
+    }
+    while n % 3 == 0 {
+        radices.push(3);
+        n /= 3;
+    }
+    radices
+}
+
+fn fft_mixed_rearrange<F>(zp: &F, data: &mut [F::E], radices: &[usize])
+where
+    F: Field,
+    F::E: Clone,
+{
+    // pure powers can be rearranged without a copy
+    if radices.iter().all(|&r| r == 2) {
+        fft2_in_place_rearrange(zp, data);
+    } else if radices.iter().all(|&r| r == 3) {
+        fft3_in_place_rearrange(zp, data);
+    } else {
+        fft_mixed_in_place_rearrange(zp, data, radices);
+    }
+}
+
+/// Mixed-radix digit reversal: the element at position `sum(d_s.r_0...r_{s-1})`
+/// is taken from index `sum(d_s.n / (r_0...r_s))`.
+fn fft_mixed_in_place_rearrange<F>(_zp: &F, data: &mut [F::E], radices: &[usize])
+where
+    F: Field,
+    F::E: Clone,
+{
+    let source = data.to_vec();
+    for (pos, target) in data.iter_mut().enumerate() {
+        let mut rest = pos;
+        let mut weight = source.len();
+        let mut index = 0;
+        for &radix in radices {
+            weight /= radix;
+            index += (rest % radix) * weight;
+            rest /= radix;
+        }
+        *target = source[index].clone();
+    }
+}
+
+/// Transpositions performing the mixed-radix digit reversal of
+/// `fft_mixed_in_place_rearrange` when applied in order, so that plans can
+/// rearrange without a copy.
+fn digit_reversal_swaps(radices: &[usize]) -> Vec<(usize, usize)> {
+    let len: usize = radices.iter().product();
+    let source = |pos: usize| {
+        let mut rest = pos;
+        let mut weight = len;
+        let mut index = 0;
+        for &radix in radices {
+            weight /= radix;
+            index += (rest % radix) * weight;
+            rest /= radix;
+        }
+        index
+    };
+    permutation_swaps(len, source)
+}
+
+/// Transpositions moving the element at position `source(pos)` to `pos` for every
+/// `pos` when applied in order.
+fn permutation_swaps<S: Fn(usize) -> usize>(len: usize, source: S) -> Vec<(usize, usize)> {
+    // walking along each cycle of the permutation, every swap moves one element
+    // to its final position
+    let mut visited = vec![false; len];
+    let mut swaps = vec![];
+    for start in 0..len {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut pos = start;
+        let mut next = source(pos);
+        while next != start {
+            swaps.push((pos, next));
+            visited[next] = true;
+            pos = next;
+            next = source(pos);
+        }
+    }
+    swaps
+}
+
+/// Twiddle factors of one layer of butterflies.
+struct Stage<E> {
+    radix: usize,
+    twiddles: Vec<E>,
+    twiddles_sq: Vec<E>,
+}
+
+fn fft_mixed_stages<F>(zp: &F, omega: &F::E, radices: &[usize]) -> Vec<Stage<F::E>>
+where
+    F: Field,
+    F::E: Clone,
+{
+    let len: usize = radices.iter().product();
+    let mut stages = Vec::with_capacity(radices.len());
+    let mut step = 1;
+    for &radix in radices {
+        let jump = radix * step;
+        let factor_stride = zp.pow(omega, (len / jump) as u32);
+        let mut twiddles = Vec::with_capacity(step);
+        let mut twiddles_sq = Vec::with_capacity(if radix == 3 { step } else { 0 });
+        let mut factor = zp.one();
+        for _ in 0..step {
+            let next = zp.mul(&factor, &factor_stride);
+            if radix == 3 {
+                twiddles_sq.push(zp.mul(&factor, &factor));
             }
-            factor = zp.mul(&factor, &factor_stride);
+            twiddles.push(factor);
+            factor = next;
         }
+        stages.push(Stage {
+            radix,
+            twiddles,
+            twiddles_sq,
+        });
         step = jump;
     }
+    stages
+}
+
+/// Principal third root of unity `omega^(len/3)` and its square, if needed.
+fn fft_mixed_roots_of_three<F>(zp: &F, omega: &F::E, len: usize) -> Option<(F::E, F::E)>
+where
+    F: Field,
+{
+    if len % 3 == 0 {
+        let big_omega = zp.pow(omega, (len / 3) as u32);
+        let big_omega_sq = zp.mul(&big_omega, &big_omega);
+        Some((big_omega, big_omega_sq))
+    } else {
+        None
+    }
+}
+
+fn fft_mixed_apply<F>(
+    zp: &F,
+    data: &mut [F::E],
+    stages: &[Stage<F::E>],
+    roots_of_three: Option<&(F::E, F::E)>,
+) where
+    F: Field,
+    F::E: Clone,
+{
+    let mut step = 1;
+    for stage in stages {
+        let jump = stage.radix * step;
+        if stage.radix == 2 {
+            for block in data.chunks_mut(jump) {
+                let (lo, hi) = block.split_at_mut(step);
+                zp.fft2_butterflies(lo, hi, &stage.twiddles);
+            }
+        } else {
+            let (big_omega, big_omega_sq) = roots_of_three.unwrap();
+            for block in data.chunks_mut(jump) {
+                let (a, rest) = block.split_at_mut(step);
+                let (b, c) = rest.split_at_mut(step);
+                zp.fft3_butterflies(
+                    a,
+                    b,
+                    c,
+                    &stage.twiddles,
+                    &stage.twiddles_sq,
+                    big_omega,
+                    big_omega_sq,
+                );
+            }
+        }
+        step = jump;
+    }
+}
+
+/// Bluestein FFT, for any length.
+///
+/// * zp is the modular field
+/// * data is the data to transform
+/// * omega is the root-of-unity to use
+/// * conv_omega is the root-of-unity to use for the inner convolution
+/// * conv_len is the order of `conv_omega`
+///
+/// omega must be a root of unity of order `data.len()`. `conv_len` must be of the
+/// form `2^a.3^b` and at least `2 * data.len() - 1`.
+pub fn fft_bluestein<F>(zp: &F, data: &mut [F::E], omega: &F::E, conv_omega: &F::E, conv_len: usize)
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    let len = data.len();
+    if len == 0 {
+        return;
+    }
+    assert!(conv_len >= 2 * len - 1);
+    assert!(is_mixed_radix_len(conv_len));
+
+    // using `j.k = C(j + k, 2) - C(j, 2) - C(k, 2)` the transform becomes a
+    // correlation with the chirp `c_t = omega^C(t, 2)`
+    let chirp = bluestein_chirp(zp, omega, 2 * len - 1);
+    let chirp_inv = bluestein_chirp(zp, &zp.inv(omega), len);
+
+    let mut a = vec![zp.zero(); conv_len];
+    for j in 0..len {
+        a[len - 1 - j] = zp.mul(&data[j], &chirp_inv[j]);
+    }
+    let mut b = chirp;
+    b.resize(conv_len, zp.zero());
+
+    fft_mixed(zp, &mut a, conv_omega);
+    fft_mixed(zp, &mut b, conv_omega);
+    zp.mul_pairwise_slice(&mut a, &b);
+    fft_mixed_inverse(zp, &mut a, conv_omega);
+
+    for (k, x) in data.iter_mut().enumerate() {
+        *x = zp.mul(&a[len - 1 + k], &chirp_inv[k]);
+    }
+}
+
+/// Bluestein inverse FFT, for any length.
+///
+/// * zp is the modular field
+/// * data is the data to transform
+/// * omega is the root-of-unity to use
+/// * conv_omega is the root-of-unity to use for the inner convolution
+/// * conv_len is the order of `conv_omega`
+///
+/// omega must be a root of unity of order `data.len()`. `conv_len` must be of the
+/// form `2^a.3^b` and at least `2 * data.len() - 1`.
+pub fn fft_bluestein_inverse<F>(
+    zp: &F,
+    data: &mut [F::E],
+    omega: &F::E,
+    conv_omega: &F::E,
+    conv_len: usize,
+) where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    let omega_inv = zp.inv(omega);
+    let len_inv = zp.inv(zp.encode(data.len() as u32));
+    fft_bluestein(zp, data, &omega_inv, conv_omega, conv_len);
+    zp.mul_scalar_slice(data, &len_inv);
+}
+
+/// The first `count` values of `omega^C(t, 2)`.
+fn bluestein_chirp<F>(zp: &F, omega: &F::E, count: usize) -> Vec<F::E>
+where
+    F: Field,
+    F::E: Clone,
+{
+    // C(t + 1, 2) = C(t, 2) + t
+    let mut chirp = Vec::with_capacity(count);
+    let mut current = zp.one();
+    let mut step = zp.one();
+    for _ in 0..count {
+        let next = zp.mul(&current, &step);
+        chirp.push(current);
+        current = next;
+        step = zp.mul(step, omega);
+    }
+    chirp
+}
+
+/// Order of `omega` if it is a root of unity of order `2^a.3^b`, up to `limit`.
+pub fn mixed_radix_order<F>(zp: &F, omega: &F::E, limit: u32) -> Option<usize>
+where
+    F: Field,
+{
+    let mut candidates = vec![];
+    let mut power_of_two = 1_u64;
+    while power_of_two <= limit as u64 {
+        let mut candidate = power_of_two;
+        while candidate <= limit as u64 {
+            candidates.push(candidate as u32);
+            candidate *= 3;
+        }
+        power_of_two *= 2;
+    }
+    candidates.sort_unstable();
+    candidates
+        .into_iter()
+        .find(|&order| zp.eq(zp.pow(omega, order), zp.one()))
+        .map(|order| order as usize)
+}
+
+/// Length from which `FftPlan::new` uses the four-step algorithm.
+///
+/// The four-step algorithm only works on blocks of about the square root of the
+/// length, but costs an extra multiplication and transposition per element. Since
+/// every layer of the in-place FFT streams through memory, the latter remained
+/// faster for `MontgomeryField32` at all lengths measured, taking between half and
+/// three quarters of the time of the four-step algorithm both for the 19683 points
+/// of the largest packed presets and for `2^20` up to `2^24` points, as compared by
+/// `cargo bench --bench numtheory`. The four-step algorithm is hence only used
+/// beyond.
+pub const FOUR_STEP_THRESHOLD: usize = 1 << 25;
+
+/// Number of columns gathered at once by the four-step algorithm, so that reading
+/// them touches whole cache lines of each row.
+const FOUR_STEP_BLOCK: usize = 16;
+
+/// Precomputed twiddle factors for transforms of a fixed length and root of unity.
+///
+/// Lengths of the form `2^a.3^b` use the mixed-radix FFT, or the four-step algorithm
+/// from `FOUR_STEP_THRESHOLD` on, while other lengths use Bluestein's algorithm, for
+/// which a plan for the inner convolution is kept as well.
+pub struct FftPlan<F: Field> {
+    field: F,
+    len: usize,
+    omega: F::E,
+    len_inv: F::E,
+    algorithm: Algorithm<F>,
+}
+
+enum Algorithm<F: Field> {
+    Mixed {
+        radices: Vec<usize>,
+        swaps: Vec<(usize, usize)>,
+        stages: Vec<Stage<F::E>>,
+        roots_of_three: Option<(F::E, F::E)>,
+    },
+    Bluestein {
+        chirp_inv: Vec<F::E>,
+        chirp_transformed: Vec<F::E>,
+        convolution: Box<FftPlan<F>>,
+    },
+    /// Transforms of the `n1` columns and then the `n2` rows of the data seen as a
+    /// row-major `n1 x n2` matrix, which are twiddled in between and transposed after.
+    FourStep {
+        columns: Box<FftPlan<F>>,
+        rows: Box<FftPlan<F>>,
+        /// Twiddle factors `omega^(j.k)` of the output `k` of every column `j`, in
+        /// column-major order.
+        twiddles: Vec<F::E>,
+        swaps: Vec<(usize, usize)>,
+    },
+}
+
+impl<F> FftPlan<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Plan for transforms of length `len`, which must be of the form `2^a.3^b`.
+    ///
+    /// omega must be a root of unity of order `len`.
+    pub fn new(field: &F, len: usize, omega: &F::E) -> FftPlan<F> {
+        if len >= FOUR_STEP_THRESHOLD {
+            FftPlan::with_four_step(field, len, omega)
+        } else {
+            FftPlan::with_mixed_radix(field, len, omega)
+        }
+    }
+
+    /// Plan for transforms of length `len` like `new`, always using the in-place
+    /// mixed-radix FFT.
+    pub fn with_mixed_radix(field: &F, len: usize, omega: &F::E) -> FftPlan<F> {
+        let radices = mixed_radices(len);
+        let stages = fft_mixed_stages(field, omega, &radices);
+        let roots_of_three = fft_mixed_roots_of_three(field, omega, len);
+        FftPlan {
+            field: field.clone(),
+            len,
+            omega: omega.clone(),
+            len_inv: field.inv(field.encode(len as u32)),
+            algorithm: Algorithm::Mixed {
+                swaps: digit_reversal_swaps(&radices),
+                radices,
+                stages,
+                roots_of_three,
+            },
+        }
+    }
+
+    /// Plan for transforms of length `len` like `new`, always using the four-step
+    /// algorithm.
+    ///
+    /// The length is split into `n1.n2` with `n1` at most its square root, and
+    /// the transforms of length `n1` and `n2` are planned with `new`.
+    pub fn with_four_step(field: &F, len: usize, omega: &F::E) -> FftPlan<F> {
+        let radices = mixed_radices(len);
+        assert!(
+            radices.len() >= 2,
+            "four-step transforms need a composite length"
+        );
+        let mut n1 = radices[0];
+        for &radix in &radices[1..] {
+            if n1 * radix * n1 * radix > len {
+                break;
+            }
+            n1 *= radix;
+        }
+        let n2 = len / n1;
+        let mut twiddles = Vec::with_capacity(len);
+        let mut root = field.one();
+        for _ in 0..n2 {
+            let mut factor = field.one();
+            for _ in 0..n1 {
+                let next = field.mul(&factor, &root);
+                twiddles.push(factor);
+                factor = next;
+            }
+            root = field.mul(root, omega);
+        }
+        FftPlan {
+            field: field.clone(),
+            len,
+            omega: omega.clone(),
+            len_inv: field.inv(field.encode(len as u32)),
+            algorithm: Algorithm::FourStep {
+                columns: Box::new(FftPlan::new(field, n1, &field.pow(omega, n2 as u32))),
+                rows: Box::new(FftPlan::new(field, n2, &field.pow(omega, n1 as u32))),
+                twiddles,
+                // the value at `k1 + n1.k2` ends up in row `k1` and column `k2`
+                swaps: permutation_swaps(len, |pos| (pos % n1) * n2 + pos / n1),
+            },
+        }
+    }
+
+    /// Plan for transforms of any length `len` using Bluestein's algorithm.
+    ///
+    /// omega must be a root of unity of order `len`, and `conv_omega` a root of
+    /// unity of order `conv_len`, which must be of the form `2^a.3^b` and at least
+    /// `2 * len - 1`.
+    pub fn with_bluestein(
+        field: &F,
+        len: usize,
+        omega: &F::E,
+        conv_omega: &F::E,
+        conv_len: usize,
+    ) -> FftPlan<F> {
+        assert!(len > 0);
+        assert!(conv_len >= 2 * len - 1);
+        let convolution = FftPlan::new(field, conv_len, conv_omega);
+        let mut chirp = bluestein_chirp(field, omega, 2 * len - 1);
+        chirp.resize(conv_len, field.zero());
+        convolution.forward(&mut chirp);
+        FftPlan {
+            field: field.clone(),
+            len,
+            omega: omega.clone(),
+            len_inv: field.inv(field.encode(len as u32)),
+            algorithm: Algorithm::Bluestein {
+                chirp_inv: bluestein_chirp(field, &field.inv(omega), len),
+                chirp_transformed: chirp,
+                convolution: Box::new(convolution),
+            },
+        }
+    }
+
+    /// Length of the transforms.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the plan is for empty transforms, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Root of unity used by the forward transform.
+    pub fn omega(&self) -> &F::E {
+        &self.omega
+    }
+
+    /// Number of elements of scratch space needed by `forward_with_scratch` and
+    /// `inverse_with_scratch`.
+    ///
+    /// This is zero for the in-place mixed-radix FFT, a few columns for the
+    /// four-step algorithm, and the length of the inner convolution for Bluestein's
+    /// algorithm.
+    pub fn scratch_len(&self) -> usize {
+        match self.algorithm {
+            Algorithm::Mixed { .. } => 0,
+            Algorithm::Bluestein {
+                ref convolution, ..
+            } => convolution.len() + convolution.scratch_len(),
+            Algorithm::FourStep {
+                ref columns,
+                ref rows,
+                ..
+            } => FOUR_STEP_BLOCK * columns.len() + columns.scratch_len().max(rows.scratch_len()),
+        }
+    }
+
+    /// Forward transform, equivalent to `fft_mixed` or `fft_bluestein` with the
+    /// parameters of the plan.
+    pub fn forward(&self, data: &mut [F::E]) {
+        let mut scratch = vec![self.field.zero(); self.scratch_len()];
+        self.forward_with_scratch(data, &mut scratch);
+    }
+
+    /// Forward transform like `forward`, without allocating.
+    ///
+    /// `scratch` must hold at least `scratch_len` elements, whose values are
+    /// overwritten.
+    pub fn forward_with_scratch(&self, data: &mut [F::E], scratch: &mut [F::E]) {
+        assert_eq!(data.len(), self.len);
+        let zp = &self.field;
+        match self.algorithm {
+            Algorithm::Mixed {
+                ref swaps,
+                ref stages,
+                ref roots_of_three,
+                ..
+            } => {
+                for &(i, j) in swaps {
+                    data.swap(i, j);
+                }
+                fft_mixed_apply(zp, data, stages, roots_of_three.as_ref());
+            }
+            Algorithm::Bluestein {
+                ref chirp_inv,
+                ref chirp_transformed,
+                ref convolution,
+            } => {
+                let len = self.len;
+                let (a, scratch) = scratch.split_at_mut(convolution.len());
+                for x in a.iter_mut() {
+                    *x = zp.zero();
+                }
+                for j in 0..len {
+                    a[len - 1 - j] = zp.mul(&data[j], &chirp_inv[j]);
+                }
+                convolution.forward_with_scratch(a, scratch);
+                zp.mul_pairwise_slice(a, chirp_transformed);
+                convolution.inverse_with_scratch(a, scratch);
+                for (k, x) in data.iter_mut().enumerate() {
+                    *x = zp.mul(&a[len - 1 + k], &chirp_inv[k]);
+                }
+            }
+            Algorithm::FourStep { .. } => self.forward_four_step(data, self.len, scratch),
+        }
+    }
+
+    /// Four-step forward transform of data where only the first `nonzero` elements
+    /// may be non-zero, which prunes the transforms of the columns.
+    fn forward_four_step(&self, data: &mut [F::E], nonzero: usize, scratch: &mut [F::E]) {
+        let (columns, rows, twiddles, swaps) = match self.algorithm {
+            Algorithm::FourStep {
+                ref columns,
+                ref rows,
+                ref twiddles,
+                ref swaps,
+            } => (columns, rows, twiddles, swaps),
+            _ => unreachable!(),
+        };
+        let zp = &self.field;
+        let (n1, n2) = (columns.len(), rows.len());
+        let (buffer, scratch) = scratch.split_at_mut(FOUR_STEP_BLOCK * n1);
+        for first in (0..n2).step_by(FOUR_STEP_BLOCK) {
+            let width = FOUR_STEP_BLOCK.min(n2 - first);
+            // gather a block of columns, reading each row contiguously
+            for j1 in 0..n1 {
+                for b in 0..width {
+                    buffer[b * n1 + j1] = data[n2 * j1 + first + b].clone();
+                }
+            }
+            for (b, column) in buffer.chunks_mut(n1).take(width).enumerate() {
+                // column `j` holds the elements at positions `n2.j1 + j`
+                let j = first + b;
+                let column_nonzero = (nonzero.saturating_sub(j)).div_ceil(n2);
+                if column_nonzero == 0 {
+                    continue;
+                } else if column_nonzero < n1 {
+                    columns.forward_pruned_with_scratch(column, column_nonzero, scratch);
+                } else {
+                    columns.forward_with_scratch(column, scratch);
+                }
+                zp.mul_pairwise_slice(column, &twiddles[j * n1..(j + 1) * n1]);
+            }
+            for k1 in 0..n1 {
+                for b in 0..width {
+                    data[n2 * k1 + first + b] = buffer[b * n1 + k1].clone();
+                }
+            }
+        }
+        for row in data.chunks_mut(n2) {
+            rows.forward_with_scratch(row, scratch);
+        }
+        for &(i, j) in swaps {
+            data.swap(i, j);
+        }
+    }
+
+    /// Forward transform of data where only the first `nonzero` elements may be non-zero.
+    ///
+    /// With `n = n1.n2` where `n2 >= nonzero` is the length of a prefix of the
+    /// layers of the plan, the output at positions `n1.t + s` is the transform of
+    /// length `n2` of `x_j.omega^(j.s)`. This saves `log(n1)` layers of butterflies
+    /// compared to `forward`, at the cost of one extra multiplication per element.
+    pub fn forward_pruned(&self, data: &mut [F::E], nonzero: usize) {
+        let mut scratch = vec![self.field.zero(); self.pruned_scratch_len()];
+        self.forward_pruned_with_scratch(data, nonzero, &mut scratch);
+    }
+
+    /// Number of elements of scratch space needed by `forward_pruned_with_scratch`.
+    pub fn pruned_scratch_len(&self) -> usize {
+        match self.algorithm {
+            Algorithm::FourStep {
+                ref columns,
+                ref rows,
+                ..
+            } => {
+                FOUR_STEP_BLOCK * columns.len()
+                    + columns.pruned_scratch_len().max(rows.scratch_len())
+            }
+            _ => self.scratch_len().max(2 * self.len),
+        }
+    }
+
+    /// Pruned forward transform like `forward_pruned`, without allocating.
+    ///
+    /// `scratch` must hold at least `pruned_scratch_len` elements, whose values
+    /// are overwritten.
+    pub fn forward_pruned_with_scratch(
+        &self,
+        data: &mut [F::E],
+        nonzero: usize,
+        scratch: &mut [F::E],
+    ) {
+        assert_eq!(data.len(), self.len);
+        let (radices, stages, roots_of_three) = match self.algorithm {
+            Algorithm::Mixed {
+                ref radices,
+                ref stages,
+                ref roots_of_three,
+                ..
+            } => (radices, stages, roots_of_three),
+            Algorithm::Bluestein { .. } => return self.forward_with_scratch(data, scratch),
+            Algorithm::FourStep { .. } => return self.forward_four_step(data, nonzero, scratch),
+        };
+
+        // shortest prefix of layers covering the non-zero elements
+        let mut layers = 0;
+        let mut sub_len = 1;
+        while sub_len < nonzero {
+            sub_len *= radices[layers];
+            layers += 1;
+        }
+        if sub_len == self.len {
+            return self.forward_with_scratch(data, scratch);
+        }
+        let count = self.len / sub_len;
+
+        let zp = &self.field;
+        let (input, rest) = scratch.split_at_mut(sub_len);
+        let buffer = &mut rest[..sub_len];
+        input.clone_from_slice(&data[..sub_len]);
+        let mut shift = zp.one();
+        for s in 0..count {
+            // scale each element and move it to its digit-reversed position, which
+            // is the rearrangement done by `fft_mixed_rearrange`
+            let mut factor = zp.one();
+            for (j, x) in input.iter().enumerate() {
+                let mut rest = j;
+                let mut weight = sub_len;
+                let mut pos = 0;
+                for &radix in radices[..layers].iter().rev() {
+                    weight /= radix;
+                    pos += (rest % radix) * weight;
+                    rest /= radix;
+                }
+                buffer[pos] = zp.mul(x, &factor);
+                factor = zp.mul(factor, &shift);
+            }
+            fft_mixed_apply(zp, buffer, &stages[..layers], roots_of_three.as_ref());
+            for (t, y) in buffer.iter().enumerate() {
+                data[count * t + s] = y.clone();
+            }
+            shift = zp.mul(shift, &self.omega);
+        }
+    }
+
+    /// Inverse transform.
+    pub fn inverse(&self, data: &mut [F::E]) {
+        let mut scratch = vec![self.field.zero(); self.scratch_len()];
+        self.inverse_with_scratch(data, &mut scratch);
+    }
+
+    /// Inverse transform like `inverse`, without allocating.
+    ///
+    /// `scratch` must hold at least `scratch_len` elements, whose values are
+    /// overwritten.
+    pub fn inverse_with_scratch(&self, data: &mut [F::E], scratch: &mut [F::E]) {
+        // transforming with omega^-1 is the same as transforming with omega
+        // and reading the result at negated indices
+        self.forward_with_scratch(data, scratch);
+        data[1..].reverse();
+        self.field.mul_scalar_slice(data, &self.len_inv);
+    }
+
+    /// Fill in the values at the powers of `omega` with the indices in `missing`,
+    /// given the others, of a polynomial of degree less than the number of known
+    /// values.
+    ///
+    /// `locator` holds the coefficients of the polynomial `L` vanishing at the
+    /// missing points. The product of `L` and the polynomial `P` is known at all
+    /// points, and hence can be interpolated with one inverse transform. Since
+    /// `(P.L)' = P'.L + P.L'` the missing values are then given by `(P.L)'/L'`,
+    /// which is again evaluated with transforms.
+    pub fn recover_erasures(&self, values: &mut [F::E], missing: &[usize], locator: &[F::E]) {
+        let field = &self.field;
+        assert_eq!(values.len(), self.len);
+        if missing.is_empty() {
+            return;
+        }
+        let mut locator_values = locator.to_vec();
+        locator_values.resize(self.len, field.zero());
+        self.forward_pruned(&mut locator_values, locator.len());
+
+        // the missing values are cancelled by the zeros of L, and P.L has degree
+        // less than `len`
+        let mut product = values.to_vec();
+        field.mul_pairwise_slice(&mut product, &locator_values);
+        self.inverse(&mut product);
+
+        let product_derivative = self.evaluate_derivative(&product);
+        let locator_derivative = self.evaluate_derivative(locator);
+        let mut denominators: Vec<F::E> = missing
+            .iter()
+            .map(|&i| locator_derivative[i].clone())
+            .collect();
+        field.batch_inv(&mut denominators);
+        for (&i, denominator) in missing.iter().zip(denominators) {
+            values[i] = field.mul(&product_derivative[i], denominator);
+        }
+    }
+
+    /// Evaluate the derivative of `coefficients` at all powers of `omega`.
+    pub(crate) fn evaluate_derivative(&self, coefficients: &[F::E]) -> Vec<F::E> {
+        let mut values = ::numtheory::poly::derivative(coefficients, &self.field);
+        let nonzero = values.len();
+        values.resize(self.len, self.field.zero());
+        self.forward_pruned(&mut values, nonzero);
+        values
+    }
+}
+
+/// Precomputed negacyclic transforms of a fixed length, whose pointwise products are
+/// products of polynomials modulo `x^len + 1`.
+///
+/// The transform of `x` is `X_k = sum_j x_j.psi^(j.(2k + 1))` for a root of unity
+/// `psi` of order `2.len`, computed as the cyclic transform for `omega = psi^2` of
+/// the input twisted by the powers of `psi`.
+pub struct NegacyclicFftPlan<F: Field> {
+    plan: FftPlan<F>,
+    psi: F::E,
+    psi_powers: Vec<F::E>,
+    psi_inv_powers: Vec<F::E>,
+}
+
+impl<F> NegacyclicFftPlan<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Plan for transforms of length `len`, which must be of the form `2^a.3^b`.
+    ///
+    /// psi must be a root of unity of order `2 * len`.
+    pub fn new(field: &F, len: usize, psi: &F::E) -> NegacyclicFftPlan<F> {
+        assert!(
+            is_mixed_radix_len(len),
+            "length must be of the form 2^a.3^b"
+        );
+        NegacyclicFftPlan {
+            plan: FftPlan::new(field, len, &field.mul(psi, psi)),
+            psi: psi.clone(),
+            psi_powers: powers(field, psi, len),
+            psi_inv_powers: powers(field, &field.inv(psi), len),
+        }
+    }
+
+    /// Length of the transforms.
+    pub fn len(&self) -> usize {
+        self.plan.len()
+    }
+
+    /// Whether the plan is for empty transforms, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        self.plan.is_empty()
+    }
+
+    /// Root of unity of order `2 * len` used by the forward transform.
+    pub fn psi(&self) -> &F::E {
+        &self.psi
+    }
+
+    /// Number of elements of scratch space needed by `forward_with_scratch` and
+    /// `inverse_with_scratch`, as for the cyclic plan.
+    pub fn scratch_len(&self) -> usize {
+        self.plan.scratch_len()
+    }
+
+    /// Forward transform.
+    pub fn forward(&self, data: &mut [F::E]) {
+        let mut scratch = vec![self.plan.field.zero(); self.scratch_len()];
+        self.forward_with_scratch(data, &mut scratch);
+    }
+
+    /// Forward transform like `forward`, without allocating.
+    ///
+    /// `scratch` must hold at least `scratch_len` elements, whose values are
+    /// overwritten.
+    pub fn forward_with_scratch(&self, data: &mut [F::E], scratch: &mut [F::E]) {
+        assert_eq!(data.len(), self.len());
+        self.plan.field.mul_pairwise_slice(data, &self.psi_powers);
+        self.plan.forward_with_scratch(data, scratch);
+    }
+
+    /// Inverse transform.
+    pub fn inverse(&self, data: &mut [F::E]) {
+        let mut scratch = vec![self.plan.field.zero(); self.scratch_len()];
+        self.inverse_with_scratch(data, &mut scratch);
+    }
+
+    /// Inverse transform like `inverse`, without allocating.
+    ///
+    /// `scratch` must hold at least `scratch_len` elements, whose values are
+    /// overwritten.
+    pub fn inverse_with_scratch(&self, data: &mut [F::E], scratch: &mut [F::E]) {
+        self.plan.inverse_with_scratch(data, scratch);
+        self.plan
+            .field
+            .mul_pairwise_slice(data, &self.psi_inv_powers);
+    }
+}
+
+/// The first `count` powers of `x`, starting from one.
+fn powers<F>(zp: &F, x: &F::E, count: usize) -> Vec<F::E>
+where
+    F: Field,
+    F::E: Clone,
+{
+    let mut powers = Vec::with_capacity(count);
+    let mut current = zp.one();
+    for _ in 0..count {
+        let next = zp.mul(&current, x);
+        powers.push(current);
+        current = next;
+    }
+    powers
 }
 
 #[cfg(test)]
@@ -243,7 +1139,7 @@ pub mod test {
         let omega = field.encode(354);
 
         let mut data = field.encode_slice([36, 303, 146, 3, 429, 422, 279, 122]);
-        fft2_inverse(&field, &mut *data, &omega);
+        fft2_inverse(&field, &mut data, &omega);
         assert_eq!(field.decode_slice(data), [1, 2, 3, 4, 5, 6, 7, 8])
     }
 
@@ -257,7 +1153,7 @@ pub mod test {
         let omega = field.encode(4318906);
 
         let mut data: Vec<_> = (0..256).map(|a| field.encode(a)).collect();
-        fft2(&field, &mut *data, &omega);
+        fft2(&field, &mut data, &omega);
         fft2_inverse(&field, &mut data, &omega);
         assert_eq!(field.decode_slice(data), (0..256).collect::<Vec<_>>());
     }
@@ -291,7 +1187,7 @@ pub mod test {
         let omega = field.encode(150);
 
         let mut data = field.encode_slice([45, 404, 407, 266, 377, 47, 158, 17, 20]);
-        fft3_inverse(&field, &mut *data, &omega);
+        fft3_inverse(&field, &mut data, &omega);
         assert_eq!(field.decode_slice(data), [1, 2, 3, 4, 5, 6, 7, 8, 9])
     }
 
@@ -309,4 +1205,271 @@ pub mod test {
         fft3_inverse(&field, &mut data, &omega);
         assert_eq!(field.decode_slice(data), (0..19683).collect::<Vec<_>>());
     }
+
+    pub fn test_fft_mixed<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::E: Clone,
+        F::P: From<u32>,
+    {
+        // field is Z_433 in which 64 is an 12th root of unity
+        let field = F::new(433);
+        let omega = field.encode(64);
+
+        let mut data = field.encode_slice([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+        fft_mixed(&field, &mut data, &omega);
+        assert_eq!(
+            field.decode_slice(&data),
+            [78, 227, 210, 219, 66, 228, 427, 193, 355, 202, 211, 194]
+        );
+        fft_mixed_inverse(&field, &mut data, &omega);
+        assert_eq!(
+            field.decode_slice(data),
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+        );
+
+        // pure powers agree with the dedicated transforms
+        let mut data = field.encode_slice([1, 2, 3, 4, 5, 6, 7, 8]);
+        fft_mixed(&field, &mut data, &field.encode(354));
+        assert_eq!(
+            field.decode_slice(data),
+            [36, 303, 146, 3, 429, 422, 279, 122]
+        );
+        let mut data = field.encode_slice([1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        fft_mixed(&field, &mut data, &field.encode(150));
+        assert_eq!(
+            field.decode_slice(data),
+            [45, 404, 407, 266, 377, 47, 158, 17, 20]
+        );
+    }
+
+    pub fn test_fft_mixed_big<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::E: Clone,
+        F::P: From<u32>,
+    {
+        // 4318906 has order 256 and 1814687 has order 19683 in Z_5038849
+        let field = F::new(5038849);
+        let omega = field.mul(
+            field.pow(field.encode(4318906), 256 / 16),
+            field.pow(field.encode(1814687), 19683 / 27),
+        );
+
+        let mut data: Vec<_> = (0..432).map(|a| field.encode(a)).collect();
+        fft_mixed(&field, &mut data, &omega);
+        fft_mixed_inverse(&field, &mut data, &omega);
+        assert_eq!(field.decode_slice(data), (0..432).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_is_mixed_radix_len() {
+        assert!(!is_mixed_radix_len(0));
+        assert!(is_mixed_radix_len(1));
+        assert!(is_mixed_radix_len(12));
+        assert!(is_mixed_radix_len(2 * 2 * 3 * 3 * 3));
+        assert!(!is_mixed_radix_len(10));
+        assert!(!is_mixed_radix_len(14));
+    }
+
+    pub fn test_fft_bluestein<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::E: Clone,
+        F::P: From<u32>,
+    {
+        // field is Z_1321 in which 133 is a 5th root of unity, 58 is an 11th
+        // root of unity, and 17 is a 24th root of unity
+        let field = F::new(1321);
+        let omega = field.encode(133);
+        let conv_omega = field.encode(17);
+
+        let mut data = field.encode_slice([1, 2, 3, 4, 5]);
+        fft_bluestein(&field, &mut data, &omega, &conv_omega, 24);
+        assert_eq!(field.decode_slice(&data), [15, 1271, 808, 508, 45]);
+        fft_bluestein_inverse(&field, &mut data, &omega, &conv_omega, 24);
+        assert_eq!(field.decode_slice(data), [1, 2, 3, 4, 5]);
+
+        // shorter convolutions work too
+        let mut data = field.encode_slice([1, 2, 3, 4, 5]);
+        let conv_omega_12 = field.mul(&conv_omega, &conv_omega);
+        fft_bluestein(&field, &mut data, &omega, &conv_omega_12, 12);
+        assert_eq!(field.decode_slice(&data), [15, 1271, 808, 508, 45]);
+
+        let omega = field.encode(58);
+        let mut data = field.encode_slice([3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5]);
+        fft_bluestein(&field, &mut data, &omega, &conv_omega, 24);
+        fft_bluestein_inverse(&field, &mut data, &omega, &conv_omega, 24);
+        assert_eq!(field.decode_slice(data), [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5]);
+
+        assert_eq!(mixed_radix_order(&field, &conv_omega, 1000), Some(24));
+        assert_eq!(mixed_radix_order(&field, &conv_omega, 20), None);
+        assert_eq!(mixed_radix_order(&field, &omega, 1000), None);
+    }
+
+    pub fn test_fft_plan<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32> + Clone,
+        F::E: Clone,
+        F::P: From<u32>,
+    {
+        // field is Z_433 in which 64 is an 12th root of unity
+        let field = F::new(433);
+        let plan = FftPlan::new(&field, 12, &field.encode(64));
+        assert_eq!(plan.len(), 12);
+        for _ in 0..2 {
+            let mut data = field.encode_slice([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+            plan.forward(&mut data);
+            assert_eq!(
+                field.decode_slice(&data),
+                [78, 227, 210, 219, 66, 228, 427, 193, 355, 202, 211, 194]
+            );
+            plan.inverse(&mut data);
+            assert_eq!(
+                field.decode_slice(data),
+                [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+            );
+        }
+
+        // field is Z_1321 in which 133 is a 5th root of unity and 17 is a 24th root of unity
+        let field = F::new(1321);
+        let plan = FftPlan::with_bluestein(&field, 5, &field.encode(133), &field.encode(17), 24);
+        let mut data = field.encode_slice([1, 2, 3, 4, 5]);
+        plan.forward(&mut data);
+        assert_eq!(field.decode_slice(&data), [15, 1271, 808, 508, 45]);
+        plan.inverse(&mut data);
+        assert_eq!(field.decode_slice(&data), [1, 2, 3, 4, 5]);
+
+        // scratch space may be reused without clearing it
+        assert_eq!(plan.scratch_len(), 24);
+        let mut scratch = field.encode_slice(vec![7; 24]);
+        for _ in 0..2 {
+            plan.forward_with_scratch(&mut data, &mut scratch);
+            assert_eq!(field.decode_slice(&data), [15, 1271, 808, 508, 45]);
+            plan.inverse_with_scratch(&mut data, &mut scratch);
+            assert_eq!(field.decode_slice(&data), [1, 2, 3, 4, 5]);
+        }
+    }
+
+    pub fn test_fft_plan_pruned<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32> + Clone,
+        F::E: Clone,
+        F::P: From<u32>,
+    {
+        // field is Z_433 in which 64 is an 12th root of unity, 3 is a 27th root of
+        // unity, and 70 is a 36th root of unity
+        let field = F::new(433);
+        for &(len, omega) in &[(12, 64), (27, 3), (36, 70)] {
+            let plan = FftPlan::new(&field, len, &field.encode(omega));
+            for nonzero in 0..len + 1 {
+                let values: Vec<u32> = (0..len as u32)
+                    .map(|i| if (i as usize) < nonzero { i * 7 + 1 } else { 0 })
+                    .collect();
+                let mut expected = field.encode_slice(&values);
+                plan.forward(&mut expected);
+                let mut data = field.encode_slice(&values);
+                plan.forward_pruned(&mut data, nonzero);
+                assert_eq!(field.decode_slice(data), field.decode_slice(&expected));
+
+                let mut data = field.encode_slice(&values);
+                let mut scratch = field.encode_slice(vec![5; plan.pruned_scratch_len()]);
+                plan.forward_pruned_with_scratch(&mut data, nonzero, &mut scratch);
+                assert_eq!(field.decode_slice(data), field.decode_slice(expected));
+            }
+        }
+    }
+
+    pub fn test_fft_plan_four_step<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32> + Clone,
+        F::E: Clone,
+        F::P: From<u32>,
+    {
+        // field is Z_433 in which 64 is an 12th root of unity, 3 is a 27th root of
+        // unity, and 70 is a 36th root of unity
+        let field = F::new(433);
+        for &(len, omega) in &[(12, 64), (27, 3), (36, 70)] {
+            let omega = field.encode(omega);
+            let mixed = FftPlan::with_mixed_radix(&field, len, &omega);
+            let four_step = FftPlan::with_four_step(&field, len, &omega);
+            let values: Vec<u32> = (0..len as u32).map(|i| i * 7 + 1).collect();
+            let mut expected = field.encode_slice(&values);
+            mixed.forward(&mut expected);
+            let mut data = field.encode_slice(&values);
+            four_step.forward(&mut data);
+            assert_eq!(field.decode_slice(&data), field.decode_slice(&expected));
+            four_step.inverse(&mut data);
+            assert_eq!(field.decode_slice(&data), values);
+
+            for nonzero in 0..len + 1 {
+                let values: Vec<u32> = (0..len as u32)
+                    .map(|i| if (i as usize) < nonzero { i * 7 + 1 } else { 0 })
+                    .collect();
+                let mut expected = field.encode_slice(&values);
+                mixed.forward(&mut expected);
+                let mut data = field.encode_slice(&values);
+                let mut scratch = field.encode_slice(vec![5; four_step.pruned_scratch_len()]);
+                four_step.forward_pruned_with_scratch(&mut data, nonzero, &mut scratch);
+                assert_eq!(field.decode_slice(data), field.decode_slice(expected));
+            }
+        }
+
+        let field = F::new(5038849);
+        let omega = field.encode(1814687);
+        let plan = FftPlan::with_four_step(&field, 19683, &omega);
+        assert!(plan.scratch_len() > 0);
+        let mixed = FftPlan::new(&field, 19683, &omega);
+        assert_eq!(mixed.scratch_len(), 0);
+        let values: Vec<u32> = (0..19683).collect();
+        let mut expected = field.encode_slice(&values);
+        mixed.forward(&mut expected);
+        let mut data = field.encode_slice(&values);
+        plan.forward(&mut data);
+        assert_eq!(field.decode_slice(&data), field.decode_slice(expected));
+        plan.inverse(&mut data);
+        assert_eq!(field.decode_slice(data), values);
+    }
+
+    pub fn test_fft_negacyclic_plan<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32> + Clone,
+        F::E: Clone,
+        F::P: From<u32>,
+    {
+        // field is Z_433 in which 354 is an 8th root of unity and 8 a 24th root of unity
+        let field = F::new(433);
+        let plan = NegacyclicFftPlan::new(&field, 4, &field.encode(354));
+        assert_eq!(plan.len(), 4);
+        let mut data = field.encode_slice([1, 2, 3, 4]);
+        plan.forward(&mut data);
+        assert_eq!(field.decode_slice(&data), [106, 310, 104, 350]);
+        plan.inverse(&mut data);
+        assert_eq!(field.decode_slice(&data), [1, 2, 3, 4]);
+
+        let plan = NegacyclicFftPlan::new(&field, 12, &field.encode(8));
+        let mut data = field.encode_slice([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+        plan.forward(&mut data);
+        assert_eq!(
+            field.decode_slice(&data),
+            [16, 115, 317, 143, 228, 194, 135, 236, 26, 426, 307, 34]
+        );
+        plan.inverse(&mut data);
+        assert_eq!(
+            field.decode_slice(&data),
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+        );
+
+        // (1 + x).x^11 = x^11 + x^12 = x^11 - 1 modulo x^12 + 1
+        let mut a = field.encode_slice([1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let mut b = field.encode_slice([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        plan.forward(&mut a);
+        plan.forward(&mut b);
+        field.mul_pairwise_slice(&mut a, &b);
+        plan.inverse(&mut a);
+        assert_eq!(
+            field.decode_slice(&a),
+            [432, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]
+        );
+    }
 }
@@ -8,13 +8,29 @@
 
 //! Algorithms for Lagrange interpolation.
 
-use fields::Field;
+use fields::{Encode, Field};
+use fingerprint::Fingerprint;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use share::{index_u32, ShareIndex};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
+/// Constants `l_i` for interpolating at a fixed point from the values at fixed
+/// points, as `sum(l_i.v_i)`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(bound(serialize = "F::E: Serialize", deserialize = "F::E: Deserialize<'de>"))
+)]
 pub struct LagrangeConstants<F: Field>(Vec<F::E>);
 
+element_impls!(Clone, PartialEq, Debug for LagrangeConstants(0));
+
 impl<F: Field> LagrangeConstants<F> {
     pub fn compute(point: &F::E, points: &[F::E], field: &F) -> LagrangeConstants<F> {
-        let mut constants = Vec::with_capacity(points.len());
+        let mut nums = Vec::with_capacity(points.len());
+        let mut denums = Vec::with_capacity(points.len());
         for i in 0..points.len() {
             let xi = &points[i];
             let mut num = field.one();
@@ -25,13 +41,31 @@ impl<F: Field> LagrangeConstants<F> {
                     denum = field.mul(denum, field.sub(xj, xi));
                 }
             }
-            let coef = field.mul(num, field.inv(denum));
-            constants.push(coef);
+            nums.push(num);
+            denums.push(denum);
         }
 
+        // invert all denominators at once
+        let constants = nums
+            .into_iter()
+            .zip(::numtheory::batch_inverse(&denums, field))
+            .map(|(num, denum_inv)| field.mul(num, denum_inv))
+            .collect();
+
         LagrangeConstants(constants)
     }
 
+    /// The constant of each point, in order.
+    pub fn constants(&self) -> &[F::E] {
+        &self.0
+    }
+
+    /// Constants of all points but the first, whose value is known to be zero.
+    pub(crate) fn without_first(mut self) -> LagrangeConstants<F> {
+        self.0.remove(0);
+        self
+    }
+
     /// Note that care must be taken to provide the same `field` as the one used
     /// for computing the constants!
     pub fn interpolate(&self, values: &[F::E], field: &F) -> F::E {
@@ -42,6 +76,227 @@ impl<F: Field> LagrangeConstants<F> {
     }
 }
 
+/// Barycentric weights `w_i = 1 / prod_{j != i} (x_i - x_j)` of a set of points.
+///
+/// Allows interpolation at any point in time `O(k)`, and adding or removing a
+/// point in time `O(k)` rather than recomputing everything from scratch.
+pub struct BarycentricWeights<F: Field> {
+    points: Vec<F::E>,
+    weights: Vec<F::E>,
+}
+
+impl<F: Field> BarycentricWeights<F>
+where
+    F::E: Clone,
+{
+    pub fn compute(points: &[F::E], field: &F) -> BarycentricWeights<F> {
+        let mut denums = Vec::with_capacity(points.len());
+        for (i, xi) in points.iter().enumerate() {
+            let mut denum = field.one();
+            for (j, xj) in points.iter().enumerate() {
+                if j != i {
+                    denum = field.mul(denum, field.sub(xi, xj));
+                }
+            }
+            denums.push(denum);
+        }
+        BarycentricWeights {
+            points: points.to_vec(),
+            weights: ::numtheory::batch_inverse(&denums, field),
+        }
+    }
+
+    pub fn points(&self) -> &[F::E] {
+        &self.points
+    }
+
+    pub fn weights(&self) -> &[F::E] {
+        &self.weights
+    }
+
+    /// Add `point`, which must differ from all current points.
+    pub fn add_point(&mut self, point: F::E, field: &F) {
+        // w_i <- w_i / (x_i - x) for existing points, computed with a single inversion
+        // together with the new weight 1 / prod (x - x_i)
+        let mut denums: Vec<F::E> = self.points.iter().map(|xi| field.sub(xi, &point)).collect();
+        let mut new_denum = field.one();
+        for diff in &denums {
+            new_denum = field.sub(field.zero(), field.mul(new_denum, diff));
+        }
+        denums.push(new_denum);
+        let inverses = ::numtheory::batch_inverse(&denums, field);
+        self.weights = self
+            .weights
+            .iter()
+            .zip(&inverses)
+            .map(|(w, inverse)| field.mul(w, inverse))
+            .collect();
+        self.weights.push(inverses[self.points.len()].clone());
+        self.points.push(point);
+    }
+
+    /// Remove the point at position `index`.
+    pub fn remove_point(&mut self, index: usize, field: &F) {
+        let point = self.points.remove(index);
+        self.weights.remove(index);
+        // w_i <- w_i . (x_i - x)
+        for (w, xi) in self.weights.iter_mut().zip(&self.points) {
+            *w = field.mul(&*w, field.sub(xi, &point));
+        }
+    }
+
+    /// Lagrange constants for interpolation at `point`.
+    pub fn lagrange_constants(&self, point: &F::E, field: &F) -> LagrangeConstants<F> {
+        if let Some(i) = self.points.iter().position(|x| field.eq(x, point)) {
+            // the polynomial is simply the value at the point
+            let constants = (0..self.points.len())
+                .map(|j| if j == i { field.one() } else { field.zero() })
+                .collect();
+            return LagrangeConstants(constants);
+        }
+        // l_i(x) = l(x) . w_i / (x - x_i) where l(x) = prod (x - x_i)
+        let diffs: Vec<F::E> = self.points.iter().map(|xi| field.sub(point, xi)).collect();
+        let product = diffs
+            .iter()
+            .fold(field.one(), |acc, diff| field.mul(acc, diff));
+        let constants = ::numtheory::batch_inverse(&diffs, field)
+            .into_iter()
+            .zip(&self.weights)
+            .map(|(inverse, w)| field.mul(field.mul(&product, w), inverse))
+            .collect();
+        LagrangeConstants(constants)
+    }
+
+    /// Value at `point` of the polynomial taking `values` at the points.
+    pub fn interpolate(&self, point: &F::E, values: &[F::E], field: &F) -> F::E {
+        assert_eq!(values.len(), self.points.len());
+        self.lagrange_constants(point, field)
+            .interpolate(values, field)
+    }
+}
+
+/// Bounded cache of the `LagrangeConstants` used for reconstruction, keyed by the
+/// fingerprint of the scheme and the sorted set of indices of the shares
+/// interpolated from.
+///
+/// Computing the constants takes time quadratic in the number of shares while using
+/// them is linear, so this pays off when the same committees reconstruct repeatedly.
+/// When full, the least recently used entry is evicted. The cache may be shared
+/// between threads and between schemes, whose entries are kept apart by their
+/// fingerprints.
+pub struct InterpolationCache<F: Field> {
+    capacity: usize,
+    state: Mutex<CacheState<F>>,
+}
+
+struct CacheState<F: Field> {
+    clock: u64,
+    entries: HashMap<(Fingerprint, Vec<ShareIndex>), CacheEntry<F>>,
+}
+
+struct CacheEntry<F: Field> {
+    last_used: u64,
+    constants: Arc<Vec<LagrangeConstants<F>>>,
+}
+
+impl<F: Field> InterpolationCache<F> {
+    /// Empty cache holding constants for at most `capacity` index sets.
+    pub fn new(capacity: usize) -> InterpolationCache<F> {
+        assert!(capacity > 0);
+        InterpolationCache {
+            capacity,
+            state: Mutex::new(CacheState {
+                clock: 0,
+                entries: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Number of scheme and index set pairs currently cached.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+
+    /// Constants of the scheme with fingerprint `scheme` for the sorted index set
+    /// `indices`, using `compute` if they are not cached yet.
+    pub fn get_or_compute<C>(
+        &self,
+        scheme: Fingerprint,
+        indices: &[ShareIndex],
+        compute: C,
+    ) -> Arc<Vec<LagrangeConstants<F>>>
+    where
+        C: FnOnce() -> Vec<LagrangeConstants<F>>,
+    {
+        debug_assert!(indices.windows(2).all(|pair| pair[0] < pair[1]));
+        let key = (scheme, indices.to_vec());
+        {
+            let state = &mut *self.state.lock().unwrap();
+            state.clock += 1;
+            if let Some(entry) = state.entries.get_mut(&key) {
+                entry.last_used = state.clock;
+                return entry.constants.clone();
+            }
+        }
+        // compute without holding the lock, so other index sets can be served meanwhile
+        let constants = Arc::new(compute());
+        let state = &mut *self.state.lock().unwrap();
+        if state.entries.len() >= self.capacity && !state.entries.contains_key(&key) {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        state.clock += 1;
+        let entry = CacheEntry {
+            last_used: state.clock,
+            constants: constants.clone(),
+        };
+        state.entries.insert(key, entry);
+        constants
+    }
+
+    /// Interpolate from `values` at `indices`, given in any order, using for each
+    /// target point the constants of the scheme with fingerprint `scheme` computed
+    /// by `compute` from the sorted indices.
+    pub fn interpolate<C>(
+        &self,
+        scheme: Fingerprint,
+        indices: &[ShareIndex],
+        values: &[F::E],
+        field: &F,
+        compute: C,
+    ) -> Vec<F::E>
+    where
+        C: FnOnce(&[ShareIndex]) -> Vec<LagrangeConstants<F>>,
+        F::E: Clone,
+    {
+        assert_eq!(indices.len(), values.len());
+        let mut pairs: Vec<(ShareIndex, &F::E)> = indices.iter().cloned().zip(values).collect();
+        pairs.sort_by_key(|&(index, _)| index);
+        let (sorted_indices, sorted_values): (Vec<ShareIndex>, Vec<F::E>) = pairs
+            .into_iter()
+            .map(|(index, value)| (index, value.clone()))
+            .unzip();
+        self.get_or_compute(scheme, &sorted_indices, || compute(&sorted_indices))
+            .iter()
+            .map(|constants| constants.interpolate(&sorted_values, field))
+            .collect()
+    }
+}
+
 /// Performs Lagrange interpolation at the specified point,
 /// for a polynomial defined by `points` and `values`.
 ///
@@ -76,6 +331,36 @@ where
     lagrange_interpolation_at_point(&field.zero(), points, values, field)
 }
 
+/// Lagrange coefficients at `point` of the shares with the given `indices`, as
+/// output by the Shamir schemes, i.e. for the points `index + 1`.
+///
+/// The value of the sharing polynomial at `point` is the sum of the shares weighted
+/// by these coefficients. Only the coefficients are returned since protocols in
+/// threshold cryptography apply them to other values computed from the shares,
+/// such as group elements, rather than to the shares themselves.
+pub fn lagrange_coefficients<F>(point: &F::E, indices: &[ShareIndex], field: &F) -> Vec<F::E>
+where
+    F: Field + Encode<u32>,
+{
+    let points: Vec<F::E> = indices
+        .iter()
+        .map(|&i| field.encode(index_u32(i) + 1))
+        .collect();
+    LagrangeConstants::compute(point, &points, field).0
+}
+
+/// Lagrange coefficients at the origin of the shares with the given `indices`, so
+/// that the weighted sum of the shares is the secret.
+///
+/// This allows reconstructing "in the exponent" of a group from elements computed
+/// from each share, as in threshold decryption and signatures.
+pub fn lagrange_coefficients_at_zero<F>(indices: &[ShareIndex], field: &F) -> Vec<F::E>
+where
+    F: Field + Encode<u32>,
+{
+    lagrange_coefficients(&field.zero(), indices, field)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -88,7 +373,7 @@ mod tests {
         F::P: From<u32>,
         F::E: Clone,
     {
-        let ref field = F::new(17);
+        let field = &F::new(17);
 
         let poly = field.encode_slice([4, 3, 2, 1]);
         let points = field.encode_slice([5, 6, 7, 8, 9]);
@@ -109,7 +394,7 @@ mod tests {
         F::P: From<u32>,
         F::E: Clone,
     {
-        let ref field = F::new(17);
+        let field = &F::new(17);
 
         let poly = field.encode_slice([4, 3, 2, 1]);
         let points = field.encode_slice([5, 6, 7, 8, 9]);
@@ -126,8 +411,145 @@ mod tests {
         );
     }
 
+    fn test_barycentric_weights<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        let field = &F::new(17);
+
+        let poly = field.encode_slice([4, 3, 2, 1]);
+        let evaluate = |x: u32| ::numtheory::mod_evaluate_polynomial(&poly, field.encode(x), field);
+        let points = field.encode_slice([5, 6, 7, 8, 9]);
+        let values = field.encode_slice([7, 4, 7, 5, 4]);
+
+        let mut weights = BarycentricWeights::compute(&points, field);
+        assert_eq!(
+            field.decode(weights.interpolate(&field.zero(), &values, field)),
+            4
+        );
+        assert_eq!(
+            field.decode(weights.interpolate(&field.encode(7), &values, field)),
+            7
+        );
+        assert_eq!(
+            field.decode(weights.interpolate(&field.encode(10), &values, field)),
+            10
+        );
+        assert_eq!(field.decode(evaluate(10)), 10);
+
+        // replace point 5 by point 10
+        weights.add_point(field.encode(10), field);
+        weights.remove_point(0, field);
+        let values = field.encode_slice([4, 7, 5, 4, 10]);
+        assert_eq!(
+            field.decode(weights.interpolate(&field.zero(), &values, field)),
+            4
+        );
+        assert_eq!(
+            field.decode(weights.interpolate(&field.encode(5), &values, field)),
+            7
+        );
+
+        let expected = BarycentricWeights::compute(&field.encode_slice([6, 7, 8, 9, 10]), field);
+        assert_eq!(
+            field.decode_slice(weights.points()),
+            field.decode_slice(expected.points())
+        );
+        assert_eq!(
+            field.decode_slice(weights.weights()),
+            field.decode_slice(expected.weights())
+        );
+    }
+
+    #[test]
+    fn test_interpolation_cache() {
+        let field = &NaturalPrimeField(17);
+        let cache = InterpolationCache::new(2);
+        let compute = |point: i64| move || vec![LagrangeConstants::compute(&point, &[1, 2], field)];
+
+        let constants = cache.get_or_compute(1, &[0, 1], compute(0));
+        assert_eq!(constants[0].interpolate(&[3, 5], field), 1);
+        cache.get_or_compute(1, &[1, 2], compute(0));
+        assert_eq!(cache.len(), 2);
+
+        // cached constants are returned even though `compute` would give other ones
+        let constants = cache.get_or_compute(1, &[0, 1], compute(3));
+        assert_eq!(constants[0].interpolate(&[3, 5], field), 1);
+
+        // [1, 2] is now the least recently used, so it is evicted
+        cache.get_or_compute(1, &[2, 3], compute(3));
+        assert_eq!(cache.len(), 2);
+        let constants = cache.get_or_compute(1, &[0, 1], compute(3));
+        assert_eq!(constants[0].interpolate(&[3, 5], field), 1);
+        let constants = cache.get_or_compute(1, &[1, 2], compute(3));
+        assert_eq!(constants[0].interpolate(&[3, 5], field), 7);
+
+        // entries of another scheme are kept apart
+        let constants = cache.get_or_compute(2, &[1, 2], compute(0));
+        assert_eq!(constants[0].interpolate(&[3, 5], field), 1);
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_inspect() {
+        let field = &NaturalPrimeField(17);
+        let constants = LagrangeConstants::compute(&0, &[1, 2], field);
+        assert_eq!(constants.constants(), [2, 16]);
+        assert_eq!(constants.clone(), constants);
+        assert_eq!(format!("{:?}", constants), "LagrangeConstants([2, 16])");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_json;
+
+        let field = &NaturalPrimeField(17);
+        let constants = LagrangeConstants::compute(&0, &[1, 2], field);
+        let json = serde_json::to_string(&constants).unwrap();
+        let restored: LagrangeConstants<NaturalPrimeField<i64>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, constants);
+        assert_eq!(restored.interpolate(&[3, 5], field), 1);
+    }
+
+    #[test]
+    fn test_lagrange_coefficients_at_zero() {
+        let field = &NaturalPrimeField(17);
+        // shares of 4 + 3x + 2x^2 at 1, 3, and 4
+        let shares = [9, 14, 14];
+        let coefficients = lagrange_coefficients_at_zero(&[0, 2, 3], field);
+        assert_eq!(::numtheory::weighted_sum(&shares, &coefficients, field), 4);
+        // the constants sum to one since they interpolate the constant polynomial
+        assert_eq!(coefficients.iter().fold(0, |sum, c| field.add(sum, c)), 1);
+    }
+
+    #[test]
+    fn test_lagrange_coefficients() {
+        let field = &NaturalPrimeField(17);
+        // shares of 4 + 3x + 2x^2 at 1, 3, and 4, and its value 1 at 5
+        let shares = [9, 14, 14];
+        let coefficients = lagrange_coefficients(&5, &[0, 2, 3], field);
+        assert_eq!(::numtheory::weighted_sum(&shares, &coefficients, field), 1);
+        assert_eq!(
+            lagrange_coefficients(&0, &[0, 2, 3], field),
+            lagrange_coefficients_at_zero(&[0, 2, 3], field)
+        );
+        // at the point of a share, only that share counts
+        assert_eq!(lagrange_coefficients(&3, &[0, 2, 3], field), [0, 1, 0]);
+    }
+
     macro_rules! all_tests {
         ($field:ty) => {
+            #[test]
+            fn test_barycentric_weights() {
+                super::test_barycentric_weights::<$field>();
+            }
             #[test]
             fn test_interpolation_from_constants() {
                 super::test_interpolation_from_constants::<$field>();
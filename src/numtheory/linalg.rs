@@ -0,0 +1,288 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Linear algebra over fields.
+//!
+//! Matrices are given as vectors of their rows, all of the same length, and
+//! vectors as slices of their elements.
+
+use fields::Field;
+use numtheory::{batch_inverse, mod_evaluate_polynomial, weighted_sum};
+
+/// Product of `matrix` and the column vector `vector`.
+pub fn mul_vector<F>(matrix: &[Vec<F::E>], vector: &[F::E], field: &F) -> Vec<F::E>
+where
+    F: Field,
+{
+    matrix
+        .iter()
+        .map(|row| {
+            assert_eq!(row.len(), vector.len());
+            weighted_sum(row, vector, field)
+        })
+        .collect()
+}
+
+/// Product of the matrices `a` and `b`.
+pub fn mul<F>(a: &[Vec<F::E>], b: &[Vec<F::E>], field: &F) -> Vec<Vec<F::E>>
+where
+    F: Field,
+{
+    let columns = b.first().map_or(0, |row| row.len());
+    a.iter()
+        .map(|row| {
+            assert_eq!(row.len(), b.len());
+            (0..columns)
+                .map(|j| {
+                    row.iter().zip(b).fold(field.zero(), |sum, (x, b_row)| {
+                        field.add(sum, field.mul(x, &b_row[j]))
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Identity matrix of size `size`.
+pub fn identity<F>(size: usize, field: &F) -> Vec<Vec<F::E>>
+where
+    F: Field,
+{
+    (0..size)
+        .map(|i| {
+            (0..size)
+                .map(|j| if i == j { field.one() } else { field.zero() })
+                .collect()
+        })
+        .collect()
+}
+
+/// Vandermonde matrix with `columns` columns for `points`, whose row `i` holds the
+/// powers `1, x_i, x_i^2, ...` of the point `x_i`.
+///
+/// Multiplying it with the coefficients of a polynomial evaluates the polynomial
+/// at `points`.
+pub fn vandermonde<F>(points: &[F::E], columns: usize, field: &F) -> Vec<Vec<F::E>>
+where
+    F: Field,
+    F::E: Clone,
+{
+    points
+        .iter()
+        .map(|point| {
+            let mut row = Vec::with_capacity(columns);
+            let mut power = field.one();
+            for _ in 0..columns {
+                row.push(power.clone());
+                power = field.mul(power, point);
+            }
+            row
+        })
+        .collect()
+}
+
+/// Inverse of the square Vandermonde matrix for `points`, or `None` if the points
+/// are not distinct.
+///
+/// Multiplying it with the values of a polynomial at `points` interpolates its
+/// coefficients. Column `i` holds the coefficients of the Lagrange basis
+/// polynomial for `x_i`, computed in quadratic time rather than by elimination.
+pub fn vandermonde_inverse<F>(points: &[F::E], field: &F) -> Option<Vec<Vec<F::E>>>
+where
+    F: Field,
+    F::E: Clone,
+{
+    let n = points.len();
+    // coefficients of the product of all `x - x_i`
+    let mut master = vec![field.one()];
+    for point in points {
+        let mut next = vec![field.zero(); master.len() + 1];
+        for (k, coefficient) in master.iter().enumerate() {
+            next[k + 1] = field.add(&next[k + 1], coefficient);
+            next[k] = field.sub(&next[k], field.mul(coefficient, point));
+        }
+        master = next;
+    }
+    // divide out `x - x_i` for every point using synthetic division
+    let quotients: Vec<Vec<F::E>> = points
+        .iter()
+        .map(|point| {
+            let mut quotient = vec![field.zero(); n];
+            let mut carry = field.zero();
+            for k in (1..=n).rev() {
+                carry = field.add(field.mul(carry, point), &master[k]);
+                quotient[k - 1] = carry.clone();
+            }
+            quotient
+        })
+        .collect();
+    let denominators: Vec<F::E> = quotients
+        .iter()
+        .zip(points)
+        .map(|(quotient, point)| mod_evaluate_polynomial(quotient, point, field))
+        .collect();
+    if denominators.iter().any(|d| field.eq(d, field.zero())) {
+        return None;
+    }
+    let inverses = batch_inverse(&denominators, field);
+    Some(
+        (0..n)
+            .map(|j| {
+                (0..n)
+                    .map(|i| field.mul(&quotients[i][j], &inverses[i]))
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+/// Bring `matrix` into reduced row echelon form in place using Gaussian
+/// elimination, returning its rank.
+pub fn row_reduce<F>(matrix: &mut [Vec<F::E>], field: &F) -> usize
+where
+    F: Field,
+    F::E: Clone,
+{
+    let columns = matrix.first().map_or(0, |row| row.len());
+    let mut rank = 0;
+    for column in 0..columns {
+        if rank == matrix.len() {
+            break;
+        }
+        let pivot =
+            match (rank..matrix.len()).find(|&i| field.neq(&matrix[i][column], field.zero())) {
+                Some(pivot) => pivot,
+                None => continue,
+            };
+        matrix.swap(rank, pivot);
+        let inverse = field.inv(&matrix[rank][column]);
+        field.mul_scalar_slice(&mut matrix[rank], &inverse);
+        let pivot_row = matrix[rank].clone();
+        for (i, row) in matrix.iter_mut().enumerate() {
+            if i == rank || field.eq(&row[column], field.zero()) {
+                continue;
+            }
+            let factor = row[column].clone();
+            for (x, y) in row.iter_mut().zip(&pivot_row) {
+                *x = field.sub(&*x, field.mul(&factor, y));
+            }
+        }
+        rank += 1;
+    }
+    rank
+}
+
+/// Rank of `matrix`.
+pub fn rank<F>(matrix: &[Vec<F::E>], field: &F) -> usize
+where
+    F: Field,
+    F::E: Clone,
+{
+    row_reduce(&mut matrix.to_vec(), field)
+}
+
+/// Row reduce the square `matrix` augmented by the columns of `extension`, returning
+/// the reduced extension, or `None` if `matrix` is singular.
+fn eliminate<F>(matrix: &[Vec<F::E>], extension: &[Vec<F::E>], field: &F) -> Option<Vec<Vec<F::E>>>
+where
+    F: Field,
+    F::E: Clone,
+{
+    let n = matrix.len();
+    assert_eq!(extension.len(), n);
+    let mut augmented: Vec<Vec<F::E>> = matrix
+        .iter()
+        .zip(extension)
+        .map(|(row, extra)| {
+            assert_eq!(row.len(), n);
+            row.iter().chain(extra).cloned().collect()
+        })
+        .collect();
+    row_reduce(&mut augmented, field);
+    // the left block is the identity exactly when every pivot is on the diagonal
+    if (0..n).any(|i| field.neq(&augmented[i][i], field.one())) {
+        return None;
+    }
+    Some(augmented.into_iter().map(|row| row[n..].to_vec()).collect())
+}
+
+/// Solution `x` of `matrix * x = rhs` for a square `matrix`, or `None` if it is
+/// singular.
+pub fn solve<F>(matrix: &[Vec<F::E>], rhs: &[F::E], field: &F) -> Option<Vec<F::E>>
+where
+    F: Field,
+    F::E: Clone,
+{
+    let extension: Vec<Vec<F::E>> = rhs.iter().map(|value| vec![value.clone()]).collect();
+    let solution = eliminate(matrix, &extension, field)?;
+    Some(solution.into_iter().map(|mut row| row.remove(0)).collect())
+}
+
+/// Inverse of the square `matrix`, or `None` if it is singular.
+pub fn invert<F>(matrix: &[Vec<F::E>], field: &F) -> Option<Vec<Vec<F::E>>>
+where
+    F: Field,
+    F::E: Clone,
+{
+    eliminate(matrix, &identity(matrix.len(), field), field)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{self, Encode, U64Field};
+
+    #[test]
+    fn test_vandermonde() {
+        let field = fields::NaturalPrimeField(17);
+        let points = [2, 3, 5, 16];
+        let poly = [1, 2, 3];
+        let matrix = vandermonde(&points, poly.len(), &field);
+        assert_eq!(matrix[1], [1, 3, 9]);
+        let values = mul_vector(&matrix, &poly, &field);
+        for (value, point) in values.iter().zip(&points) {
+            assert_eq!(*value, mod_evaluate_polynomial(&poly, point, &field));
+        }
+
+        let square = vandermonde(&points, points.len(), &field);
+        let inverse = vandermonde_inverse(&points, &field).unwrap();
+        assert_eq!(mul(&inverse, &square, &field), identity(4, &field));
+        assert_eq!(Some(inverse), invert(&square, &field));
+        assert_eq!(vandermonde_inverse(&[2, 3, 2], &field), None);
+    }
+
+    #[test]
+    fn test_solve() {
+        let field = U64Field(433);
+        let matrix: Vec<Vec<_>> = [[2_u32, 1, 1], [1, 3, 2], [1, 0, 0]]
+            .iter()
+            .map(|row| row.iter().map(|&x| field.encode(x)).collect())
+            .collect();
+        let x: Vec<_> = [4_u32, 5, 6].iter().map(|&x| field.encode(x)).collect();
+        let rhs = mul_vector(&matrix, &x, &field);
+        assert_eq!(solve(&matrix, &rhs, &field), Some(x));
+        let inverse = invert(&matrix, &field).unwrap();
+        assert_eq!(mul(&matrix, &inverse, &field), identity(3, &field));
+        assert_eq!(rank(&matrix, &field), 3);
+    }
+
+    #[test]
+    fn test_singular() {
+        let field = fields::NaturalPrimeField(17);
+        let matrix = vec![vec![1, 2, 3], vec![2, 4, 6], vec![0, 1, 1]];
+        assert_eq!(rank(&matrix, &field), 2);
+        assert_eq!(invert(&matrix, &field), None);
+        assert_eq!(solve(&matrix, &[1, 2, 3], &field), None);
+
+        let mut reduced = matrix.clone();
+        assert_eq!(row_reduce(&mut reduced, &field), 2);
+        assert_eq!(reduced, [vec![1, 0, 1], vec![0, 1, 1], vec![0, 0, 0]]);
+        assert_eq!(rank::<fields::NaturalPrimeField<i64>>(&[], &field), 0);
+    }
+}
@@ -21,3 +21,16 @@ pub use self::newton::*;
 
 pub mod lagrange;
 pub use self::lagrange::*;
+
+pub mod linalg;
+
+pub mod poly;
+
+pub mod primality;
+pub use self::primality::*;
+
+pub mod roots;
+pub use self::roots::*;
+
+pub mod subproduct;
+pub use self::subproduct::*;
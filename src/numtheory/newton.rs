@@ -9,9 +9,24 @@
 //! Algorithms for Newton interpolation.
 
 use fields::Field;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
 
 /// Holds together points and Newton-interpolated coefficients for fast evaluation.
+///
+/// The polynomial is `sum(c_i.prod_(j < i)(x - x_j))` for the `points` `x_j` and
+/// the `coefficients` `c_i`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(
+        bound(serialize = "F::E: Serialize", deserialize = "F::E: Deserialize<'de>"),
+        try_from = "NewtonParts<F>"
+    )
+)]
 pub struct NewtonPolynomial<F>
 where
     F: Field,
@@ -20,6 +35,32 @@ where
     coefficients: Vec<F::E>,
 }
 
+element_impls!(Clone, PartialEq, Debug for NewtonPolynomial { points, coefficients });
+
+/// Deserialized fields of a `NewtonPolynomial`, before checking that they match.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "F::E: Deserialize<'de>"))]
+struct NewtonParts<F: Field> {
+    points: Vec<F::E>,
+    coefficients: Vec<F::E>,
+}
+
+#[cfg(feature = "serde")]
+impl<F: Field> TryFrom<NewtonParts<F>> for NewtonPolynomial<F> {
+    type Error = &'static str;
+
+    fn try_from(parts: NewtonParts<F>) -> Result<NewtonPolynomial<F>, &'static str> {
+        if parts.points.len() != parts.coefficients.len() {
+            return Err("points and coefficients have different lengths");
+        }
+        Ok(NewtonPolynomial {
+            points: parts.points,
+            coefficients: parts.coefficients,
+        })
+    }
+}
+
 impl<F> NewtonPolynomial<F>
 where
     F: Field,
@@ -39,15 +80,23 @@ where
             .map(|(index, value)| (index, index, value.clone()))
             .collect();
 
+        // at step `j` the point differences are `points[i] - points[i - j]`;
+        // collect them in iteration order so they can be inverted at once
+        let mut point_diffs = Vec::with_capacity(store.len() * store.len() / 2);
+        for j in 1..store.len() {
+            for i in (j..store.len()).rev() {
+                point_diffs.push(field.sub(&points[i], &points[i - j]));
+            }
+        }
+        let mut point_diff_inverses = ::numtheory::batch_inverse(&point_diffs, field).into_iter();
+
         for j in 1..store.len() {
             for i in (j..store.len()).rev() {
                 let index_lower = store[i - 1].0;
                 let index_upper = store[i].1;
+                debug_assert_eq!(index_upper - index_lower, j);
 
-                let point_lower = &points[index_lower];
-                let point_upper = &points[index_upper];
-                let point_diff = field.sub(point_upper, point_lower);
-                let point_diff_inverse = field.inv(point_diff);
+                let point_diff_inverse = point_diff_inverses.next().unwrap();
 
                 let coef_lower = store[i - 1].2.clone();
                 let coef_upper = store[i].2.clone();
@@ -67,6 +116,102 @@ where
         }
     }
 
+    /// Newton interpolation for points in geometric progression, `first.ratio^i`.
+    ///
+    /// Since `x_(i+j) - x_i = x_i.(ratio^j - 1)` all differences can be inverted
+    /// from the inverses of the points and of `ratio^j - 1`, using only a few field
+    /// inversions in total instead of one per pair of points.
+    pub fn compute_geometric(
+        first: &F::E,
+        ratio: &F::E,
+        values: &[F::E],
+        field: &F,
+    ) -> NewtonPolynomial<F> {
+        let count = values.len();
+
+        let mut points = Vec::with_capacity(count);
+        let mut point_inverses = Vec::with_capacity(count);
+        let mut point = first.clone();
+        let mut point_inverse = field.inv(first);
+        let ratio_inverse = field.inv(ratio);
+        for _ in 0..count {
+            let next = field.mul(&point, ratio);
+            let next_inverse = field.mul(&point_inverse, &ratio_inverse);
+            points.push(point);
+            point_inverses.push(point_inverse);
+            point = next;
+            point_inverse = next_inverse;
+        }
+
+        // (ratio^j - 1)^-1 for j in 1..count
+        let mut ratio_powers = Vec::with_capacity(count);
+        let mut ratio_power = field.one();
+        for _ in 1..count {
+            ratio_power = field.mul(ratio_power, ratio);
+            ratio_powers.push(field.sub(&ratio_power, field.one()));
+        }
+        let gap_inverses = ::numtheory::batch_inverse(&ratio_powers, field);
+
+        let mut coefficients = values.to_vec();
+        for j in 1..count {
+            for i in (j..count).rev() {
+                let point_diff_inverse = field.mul(&point_inverses[i - j], &gap_inverses[j - 1]);
+                let coef_diff = field.sub(&coefficients[i], &coefficients[i - 1]);
+                coefficients[i] = field.mul(coef_diff, point_diff_inverse);
+            }
+        }
+
+        NewtonPolynomial {
+            points,
+            coefficients,
+        }
+    }
+
+    /// Polynomial through no points, to be extended with `add_point`.
+    pub fn empty() -> NewtonPolynomial<F> {
+        NewtonPolynomial {
+            points: Vec::new(),
+            coefficients: Vec::new(),
+        }
+    }
+
+    /// Number of points interpolated through.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Points interpolated through, in the order they were added.
+    pub fn points(&self) -> &[F::E] {
+        &self.points
+    }
+
+    /// Newton coefficients, one per point.
+    pub fn coefficients(&self) -> &[F::E] {
+        &self.coefficients
+    }
+
+    /// Extend the polynomial to also take `value` at `point`, which must differ from
+    /// all current points.
+    ///
+    /// The existing coefficients are left untouched and the new one is
+    /// `(value - p(point)) / prod (point - x_i)`, so this takes time `O(k)` for `k`
+    /// points and a single inversion.
+    pub fn add_point(&mut self, point: F::E, value: F::E, field: &F) {
+        let mut current = field.zero();
+        let mut newton_point = field.one();
+        for (xi, coefficient) in self.points.iter().zip(&self.coefficients) {
+            current = field.add(current, field.mul(coefficient, &newton_point));
+            newton_point = field.mul(newton_point, field.sub(&point, xi));
+        }
+        let coefficient = field.mul(field.sub(value, current), field.inv(newton_point));
+        self.points.push(point);
+        self.coefficients.push(coefficient);
+    }
+
     /// Note that care must be taken to ensure that the `field` is used here is the same
     /// as the one used in `compute`.
     pub fn evaluate<P: Borrow<F::E>>(&self, point: P, field: &F) -> F::E {
@@ -111,7 +256,7 @@ mod tests {
         F::P: From<u32>,
         F::E: Clone,
     {
-        let ref field = F::new(17);
+        let field = &F::new(17);
 
         let poly = field.encode_slice([1, 2, 3, 4]);
         let points = field.encode_slice([5, 6, 7, 8, 9]);
@@ -152,7 +297,7 @@ mod tests {
         F::P: From<u32>,
         F::E: Clone,
     {
-        let ref field = F::new(17);
+        let field = &F::new(17);
 
         let points = field.encode_slice([5, 6, 7, 8, 9]);
         let values = field.encode_slice([8, 16, 4, 13, 16]);
@@ -161,8 +306,99 @@ mod tests {
         assert_eq!(field.decode_slice(poly.coefficients), vec![8, 8, 7, 4, 0]);
     }
 
+    fn test_compute_geometric<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        let field = &F::new(17);
+
+        // 5, 10, 3, 6, 12
+        let points = field.encode_slice([5, 10, 3, 6, 12]);
+        let values = field.encode_slice([8, 16, 4, 13, 0]);
+
+        let expected = NewtonPolynomial::compute(&points, &values, field);
+        let poly =
+            NewtonPolynomial::compute_geometric(&field.encode(5), &field.encode(2), &values, field);
+        assert_eq!(
+            field.decode_slice(&poly.points),
+            field.decode_slice(&points)
+        );
+        assert_eq!(
+            field.decode_slice(poly.coefficients),
+            field.decode_slice(expected.coefficients)
+        );
+    }
+
+    fn test_add_point<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        let field = &F::new(17);
+
+        let points = field.encode_slice([5, 6, 7, 8, 9]);
+        let values = field.encode_slice([8, 16, 4, 13, 16]);
+
+        let mut poly = NewtonPolynomial::empty();
+        assert!(poly.is_empty());
+        for (point, value) in points.iter().zip(&values) {
+            poly.add_point(point.clone(), value.clone(), field);
+        }
+        assert_eq!(poly.len(), 5);
+        assert_eq!(field.decode_slice(poly.coefficients), vec![8, 8, 7, 4, 0]);
+    }
+
+    #[test]
+    fn test_inspect() {
+        let field = &NaturalPrimeField(17);
+        let poly = NewtonPolynomial::compute(&[5, 6, 7], &[8, 16, 4], field);
+        assert_eq!(poly.points(), [5, 6, 7]);
+        assert_eq!(poly.coefficients(), [8, 8, 7]);
+        let copy = poly.clone();
+        assert_eq!(copy, poly);
+        assert_ne!(
+            copy,
+            NewtonPolynomial::compute(&[5, 6, 7], &[8, 16, 5], field)
+        );
+        assert_eq!(
+            format!("{:?}", poly),
+            "NewtonPolynomial { points: [5, 6, 7], coefficients: [8, 8, 7] }"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_json;
+
+        let field = &NaturalPrimeField(17);
+        let poly = NewtonPolynomial::compute(&[5, 6, 7], &[8, 16, 4], field);
+        let json = serde_json::to_string(&poly).unwrap();
+        let restored: NewtonPolynomial<NaturalPrimeField<i64>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, poly);
+        assert_eq!(restored.evaluate(8, field), 6);
+
+        assert_eq!(json, r#"{"points":[5,6,7],"coefficients":[8,8,7]}"#);
+        let malformed = r#"{"points":[5,6,7],"coefficients":[8,8]}"#;
+        assert!(
+            serde_json::from_str::<NewtonPolynomial<NaturalPrimeField<i64>>>(malformed).is_err()
+        );
+    }
+
     macro_rules! all_tests {
         ($field:ty) => {
+            #[test]
+            fn test_add_point() {
+                super::test_add_point::<$field>();
+            }
+            #[test]
+            fn test_compute_geometric() {
+                super::test_compute_geometric::<$field>();
+            }
             #[test]
             fn test_newton_interpolation_general() {
                 super::test_newton_interpolation_general::<$field>();
@@ -0,0 +1,323 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Arithmetic on polynomials in coefficient representation.
+//!
+//! Polynomials are given by their coefficients, lowest degree first. Results are
+//! always trimmed, so that the zero polynomial is represented by an empty vector.
+
+use fields::{Encode, Field};
+use numtheory::fft;
+
+/// Below this length `mul_fft` falls back to schoolbook multiplication.
+pub const SCHOOLBOOK_THRESHOLD: usize = 32;
+
+/// Remove leading zero coefficients.
+pub fn trim<F>(mut coefficients: Vec<F::E>, field: &F) -> Vec<F::E>
+where
+    F: Field,
+{
+    while coefficients
+        .last()
+        .is_some_and(|c| field.eq(c, field.zero()))
+    {
+        coefficients.pop();
+    }
+    coefficients
+}
+
+/// Degree of the polynomial, or `None` for the zero polynomial.
+pub fn degree<F>(coefficients: &[F::E], field: &F) -> Option<usize>
+where
+    F: Field,
+{
+    coefficients
+        .iter()
+        .rposition(|c| field.neq(c, field.zero()))
+}
+
+pub fn add<F>(a: &[F::E], b: &[F::E], field: &F) -> Vec<F::E>
+where
+    F: Field,
+    F::E: Clone,
+{
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = long.to_vec();
+    field.add_assign_slice(&mut result[..short.len()], short);
+    trim(result, field)
+}
+
+pub fn sub<F>(a: &[F::E], b: &[F::E], field: &F) -> Vec<F::E>
+where
+    F: Field,
+    F::E: Clone,
+{
+    let mut result = a.to_vec();
+    if result.len() < b.len() {
+        result.resize(b.len(), field.zero());
+    }
+    for (x, y) in result.iter_mut().zip(b) {
+        *x = field.sub(&*x, y);
+    }
+    trim(result, field)
+}
+
+/// Product of `a` and `b` in quadratic time.
+pub fn mul<F>(a: &[F::E], b: &[F::E], field: &F) -> Vec<F::E>
+where
+    F: Field,
+    F::E: Clone,
+{
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut result = vec![field.zero(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            result[i + j] = field.add(&result[i + j], field.mul(x, y));
+        }
+    }
+    trim(result, field)
+}
+
+/// Product of `a` and `b` in quasilinear time.
+///
+/// `omega` must be a principal root of unity of order `order`, which must be of the
+/// form `2^a.3^b` and at least `a.len() + b.len() - 1`. The shortest suitable
+/// transform length dividing `order` is used. Short inputs fall back to `mul`.
+pub fn mul_fft<F>(a: &[F::E], b: &[F::E], omega: &F::E, order: usize, field: &F) -> Vec<F::E>
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    if a.len().min(b.len()) < SCHOOLBOOK_THRESHOLD {
+        return mul(a, b, field);
+    }
+    let len = a.len() + b.len() - 1;
+    let transform_len =
+        shortest_divisor(order, len).expect("order of omega too small for the product");
+    let omega = field.pow(omega, (order / transform_len) as u32);
+
+    let mut a = a.to_vec();
+    a.resize(transform_len, field.zero());
+    let mut b = b.to_vec();
+    b.resize(transform_len, field.zero());
+    fft::fft_mixed(field, &mut a, &omega);
+    fft::fft_mixed(field, &mut b, &omega);
+    field.mul_pairwise_slice(&mut a, &b);
+    fft::fft_mixed_inverse(field, &mut a, &omega);
+    a.truncate(len);
+    trim(a, field)
+}
+
+/// Smallest divisor of `order` of the form `2^a.3^b` that is at least `min`.
+fn shortest_divisor(order: usize, min: usize) -> Option<usize> {
+    assert!(fft::is_mixed_radix_len(order));
+    let mut best = None;
+    let mut power_of_two = 1;
+    while order % power_of_two == 0 {
+        let mut candidate = power_of_two;
+        while order % candidate == 0 {
+            if candidate >= min && best.is_none_or(|best| candidate < best) {
+                best = Some(candidate);
+            }
+            candidate *= 3;
+        }
+        power_of_two *= 2;
+    }
+    best
+}
+
+/// Quotient and remainder of the division of `a` by `b`.
+///
+/// `b` must not be the zero polynomial.
+pub fn div_rem<F>(a: &[F::E], b: &[F::E], field: &F) -> (Vec<F::E>, Vec<F::E>)
+where
+    F: Field,
+    F::E: Clone,
+{
+    let b = trim(b.to_vec(), field);
+    assert!(!b.is_empty(), "division by the zero polynomial");
+    let mut remainder = trim(a.to_vec(), field);
+    if remainder.len() < b.len() {
+        return (vec![], remainder);
+    }
+
+    let lead_inv = field.inv(&b[b.len() - 1]);
+    let mut quotient = vec![field.zero(); remainder.len() - b.len() + 1];
+    for i in (0..quotient.len()).rev() {
+        let coef = field.mul(&remainder[i + b.len() - 1], &lead_inv);
+        for (j, y) in b.iter().enumerate() {
+            remainder[i + j] = field.sub(&remainder[i + j], field.mul(&coef, y));
+        }
+        quotient[i] = coef;
+    }
+    remainder.truncate(b.len() - 1);
+    (trim(quotient, field), trim(remainder, field))
+}
+
+/// Formal derivative.
+pub fn derivative<F>(a: &[F::E], field: &F) -> Vec<F::E>
+where
+    F: Field + Encode<u32>,
+{
+    let result = a
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| field.mul(c, field.encode(i as u32)))
+        .collect();
+    trim(result, field)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::*;
+
+    fn test_add_sub<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        let field = &F::new(17);
+        let a = field.encode_slice([1, 2, 3]);
+        let b = field.encode_slice([4, 5, 14]);
+
+        assert_eq!(field.decode_slice(add(&a, &b, field)), [5, 7]);
+        assert_eq!(field.decode_slice(add(&a[..1], &b, field)), [5, 5, 14]);
+        assert_eq!(field.decode_slice(sub(&a, &b, field)), [14, 14, 6]);
+        assert_eq!(sub(&a, &a, field).len(), 0);
+        assert_eq!(degree(&a, field), Some(2));
+        assert_eq!(degree(&field.encode_slice([1, 0, 0]), field), Some(0));
+        assert_eq!(degree(&field.encode_slice([0]), field), None);
+    }
+
+    fn test_mul<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        let field = &F::new(17);
+        let a = field.encode_slice([1, 2, 3]);
+        let b = field.encode_slice([4, 5]);
+        assert_eq!(field.decode_slice(mul(&a, &b, field)), [4, 13, 5, 15]);
+        assert_eq!(mul(&a, &[], field).len(), 0);
+    }
+
+    fn test_mul_fft<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        // field is Z_433 in which 5 is a 432th root of unity
+        let field = &F::new(433);
+        let omega = field.encode(5);
+
+        let a: Vec<_> = (0..40).map(|i| field.encode(i * 7 + 1)).collect();
+        let b: Vec<_> = (0..40).map(|i| field.encode(i * i + 3)).collect();
+        assert_eq!(
+            field.decode_slice(mul_fft(&a, &b, &omega, 432, field)),
+            field.decode_slice(mul(&a, &b, field))
+        );
+        assert_eq!(
+            field.decode_slice(mul_fft(&a[..3], &b, &omega, 432, field)),
+            field.decode_slice(mul(&a[..3], &b, field))
+        );
+    }
+
+    fn test_div_rem<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        let field = &F::new(17);
+
+        // x^2 - 1 = (x - 1)(x + 1)
+        let (q, r) = div_rem(
+            &field.encode_slice([16, 0, 1]),
+            &field.encode_slice([16, 1]),
+            field,
+        );
+        assert_eq!(field.decode_slice(q), [1, 1]);
+        assert_eq!(r.len(), 0);
+
+        let q = field.encode_slice([5, 1, 2]);
+        let b = field.encode_slice([3, 0, 7]);
+        let r = field.encode_slice([4, 1]);
+        let a = add(&mul(&q, &b, field), &r, field);
+        let (quotient, remainder) = div_rem(&a, &b, field);
+        assert_eq!(field.decode_slice(quotient), [5, 1, 2]);
+        assert_eq!(field.decode_slice(remainder), [4, 1]);
+
+        let (quotient, remainder) = div_rem(&r, &b, field);
+        assert_eq!(quotient.len(), 0);
+        assert_eq!(field.decode_slice(remainder), [4, 1]);
+    }
+
+    fn test_derivative<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        let field = &F::new(17);
+        let a = field.encode_slice([1, 2, 3, 4]);
+        assert_eq!(field.decode_slice(derivative(&a, field)), [2, 6, 12]);
+        assert_eq!(derivative(&a[..1], field).len(), 0);
+    }
+
+    #[test]
+    fn test_shortest_divisor() {
+        assert_eq!(shortest_divisor(432, 79), Some(108));
+        assert_eq!(shortest_divisor(432, 8), Some(8));
+        assert_eq!(shortest_divisor(432, 433), None);
+        assert_eq!(shortest_divisor(27, 4), Some(9));
+    }
+
+    macro_rules! all_tests {
+        ($field:ty) => {
+            #[test]
+            fn test_add_sub() {
+                super::test_add_sub::<$field>();
+            }
+            #[test]
+            fn test_mul() {
+                super::test_mul::<$field>();
+            }
+            #[test]
+            fn test_mul_fft() {
+                super::test_mul_fft::<$field>();
+            }
+            #[test]
+            fn test_div_rem() {
+                super::test_div_rem::<$field>();
+            }
+            #[test]
+            fn test_derivative() {
+                super::test_derivative::<$field>();
+            }
+        };
+    }
+
+    mod natural {
+        all_tests!(::fields::NaturalPrimeField<i64>);
+    }
+    mod montgomery {
+        all_tests!(::fields::MontgomeryField32);
+    }
+    #[cfg(feature = "largefield")]
+    mod large {
+        all_tests!(::fields::LargePrimeField);
+    }
+}
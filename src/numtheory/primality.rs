@@ -0,0 +1,187 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Primality testing for the integer types used as field moduli.
+
+/// Primes used both for trial division and as Miller-Rabin witnesses.
+const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Integers that can be tested for primality.
+pub trait Primality {
+    /// Whether the number is (probably) prime.
+    ///
+    /// Implementations for machine integers are deterministic, while those for
+    /// arbitrary-precision integers err with probability at most `2^-80`.
+    fn is_probable_prime(&self) -> bool;
+
+    /// Single round of the Miller-Rabin test: whether the number, which must be odd and
+    /// larger than `base`, is a strong probable prime to `base`.
+    ///
+    /// Primes pass for all bases, while odd composites fail for at least three
+    /// quarters of them.
+    fn is_strong_probable_prime(&self, base: u64) -> bool;
+}
+
+impl Primality for u32 {
+    fn is_probable_prime(&self) -> bool {
+        (*self as u64).is_probable_prime()
+    }
+
+    fn is_strong_probable_prime(&self, base: u64) -> bool {
+        (*self as u64).is_strong_probable_prime(base)
+    }
+}
+
+impl Primality for u64 {
+    /// Deterministic Miller-Rabin test, using the first twelve primes as witnesses.
+    fn is_probable_prime(&self) -> bool {
+        let n = *self;
+        if n < 2 {
+            return false;
+        }
+        for &p in &SMALL_PRIMES {
+            if n % p == 0 {
+                return n == p;
+            }
+        }
+        SMALL_PRIMES.iter().all(|&a| n.is_strong_probable_prime(a))
+    }
+
+    fn is_strong_probable_prime(&self, base: u64) -> bool {
+        let n = *self;
+        let s = (n - 1).trailing_zeros();
+        let d = (n - 1) >> s;
+        let mut x = pow_mod(base, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(feature = "largefield")]
+mod large {
+
+    extern crate framp as ramp;
+
+    use self::ramp::RandomInt;
+    use super::{Primality, SMALL_PRIMES};
+    use rand;
+
+    /// Number of Miller-Rabin rounds, giving error probability at most `2^-80`.
+    const MILLER_RABIN_ROUNDS: usize = 40;
+
+    impl Primality for ramp::Int {
+        /// Miller-Rabin test with random witnesses, after trial division by small primes.
+        fn is_probable_prime(&self) -> bool {
+            if *self < 2 {
+                return false;
+            }
+            for &p in &SMALL_PRIMES {
+                if self % ramp::Int::from(p) == 0 {
+                    return *self == ramp::Int::from(p);
+                }
+            }
+            let mut rng = rand::OsRng::new().unwrap();
+            let bound = self - 4;
+            (0..MILLER_RABIN_ROUNDS).all(|_| {
+                // witnesses in `[2, n - 2)`
+                let witness = rng.gen_uint_below(&bound) + 2;
+                is_strong_probable_prime(self, &witness)
+            })
+        }
+
+        fn is_strong_probable_prime(&self, base: u64) -> bool {
+            is_strong_probable_prime(self, &ramp::Int::from(base))
+        }
+    }
+
+    fn is_strong_probable_prime(n: &ramp::Int, base: &ramp::Int) -> bool {
+        let n_minus_one = n - 1;
+        let s = n_minus_one.trailing_zeros() as usize;
+        let d = &n_minus_one >> s;
+        let mut x = base.pow_mod(&d, n);
+        if x == 1 || x == n_minus_one {
+            return true;
+        }
+        for _ in 1..s {
+            x = (&x * &x) % n;
+            if x == n_minus_one {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+fn pow_mod(mut x: u64, mut e: u64, m: u64) -> u64 {
+    let mut acc = 1;
+    while e > 0 {
+        if e & 1 == 1 {
+            acc = mul_mod(acc, x, m);
+        }
+        x = mul_mod(x, x, m);
+        e >>= 1;
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[cfg(feature = "largefield")]
+    extern crate framp as ramp;
+
+    use super::*;
+
+    #[test]
+    fn test_is_probable_prime() {
+        let primes: Vec<u64> = (0..50).filter(|n| n.is_probable_prime()).collect();
+        assert_eq!(
+            primes,
+            [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47]
+        );
+        assert!(2305843009213693951_u64.is_probable_prime()); // 2^61 - 1
+        assert!(!3215031751_u64.is_probable_prime()); // strong pseudoprime to bases 2, 3, 5, 7
+        assert!(!4611686014132420609_u64.is_probable_prime()); // (2^31 - 1)^2
+        assert!(433_u32.is_probable_prime());
+        assert!(!u32::MAX.is_probable_prime());
+    }
+
+    #[test]
+    fn test_is_strong_probable_prime() {
+        // 2047 = 23 * 89 is the smallest strong pseudoprime to base 2
+        assert!(2047_u64.is_strong_probable_prime(2));
+        assert!(!2047_u64.is_strong_probable_prime(3));
+        assert!(!2047_u64.is_probable_prime());
+        assert!(433_u32.is_strong_probable_prime(5));
+    }
+
+    #[cfg(feature = "largefield")]
+    #[test]
+    fn test_is_probable_prime_large() {
+        let mersenne = (ramp::Int::one() << 127) - 1;
+        assert!(mersenne.is_probable_prime());
+        assert!(mersenne.is_strong_probable_prime(3));
+        assert!(!((ramp::Int::one() << 128) + 1).is_probable_prime());
+        // Carmichael number
+        assert!(!ramp::Int::from(561).is_probable_prime());
+        assert!(ramp::Int::from(433).is_probable_prime());
+        assert!(!ramp::Int::from(1).is_probable_prime());
+    }
+}
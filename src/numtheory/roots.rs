@@ -0,0 +1,337 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Roots of unity and generators of the multiplicative group of prime fields.
+
+use fields::{BigExponent, KnownPrime, PrimeField};
+
+/// Arithmetic on the prime of a `PrimeField` needed to work with its multiplicative
+/// group, of order `prime - 1`.
+///
+/// Exponents are given as `BigExponent` since they may exceed `u32`.
+pub trait PrimeModulus {
+    /// `(self - 1) / divisor`, or `None` if `divisor` does not divide `self - 1`.
+    fn cofactor(&self, divisor: usize) -> Option<BigExponent>;
+
+    /// `(self - 1) / q` for each prime `q` dividing `self - 1`, or `None` if
+    /// `self - 1` could not be factored.
+    fn prime_cofactors(&self) -> Option<Vec<BigExponent>>;
+}
+
+/// Distinct prime factors of `x`, by trial division.
+fn prime_factors(mut x: u64) -> Vec<u64> {
+    let mut factors = vec![];
+    let mut q = 2;
+    while q <= x / q {
+        if x % q == 0 {
+            factors.push(q);
+            while x % q == 0 {
+                x /= q;
+            }
+        }
+        q += 1;
+    }
+    if x > 1 {
+        factors.push(x);
+    }
+    factors
+}
+
+impl PrimeModulus for u64 {
+    fn cofactor(&self, divisor: usize) -> Option<BigExponent> {
+        let order = self.checked_sub(1)?;
+        let divisor = divisor as u64;
+        if divisor == 0 || order % divisor != 0 {
+            return None;
+        }
+        Some(BigExponent::from(order / divisor))
+    }
+
+    /// Trial division, taking time up to the square root of the largest prime factor
+    /// of `self - 1`.
+    fn prime_cofactors(&self) -> Option<Vec<BigExponent>> {
+        let order = self.checked_sub(1)?;
+        Some(
+            prime_factors(order)
+                .into_iter()
+                .map(|q| BigExponent::from(order / q))
+                .collect(),
+        )
+    }
+}
+
+impl PrimeModulus for u32 {
+    fn cofactor(&self, divisor: usize) -> Option<BigExponent> {
+        (*self as u64).cofactor(divisor)
+    }
+
+    fn prime_cofactors(&self) -> Option<Vec<BigExponent>> {
+        (*self as u64).prime_cofactors()
+    }
+}
+
+/// Quotient and remainder of the division of the little-endian limbs `x` by `divisor`.
+fn div_limbs(x: &[u64; 4], divisor: u64) -> ([u64; 4], u64) {
+    let mut quotient = [0; 4];
+    let mut rest = 0_u128;
+    for i in (0..4).rev() {
+        let current = rest << 64 | x[i] as u128;
+        quotient[i] = (current / divisor as u128) as u64;
+        rest = current % divisor as u128;
+    }
+    (quotient, rest as u64)
+}
+
+fn limbs_exponent(x: &[u64; 4]) -> BigExponent {
+    BigExponent::from_bits((0..256).map(|i| (x[i / 64] >> (i % 64)) & 1 == 1).collect())
+}
+
+/// Primes given as the little-endian limbs of `U256Field`.
+impl PrimeModulus for [u64; 4] {
+    fn cofactor(&self, divisor: usize) -> Option<BigExponent> {
+        if *self == [0; 4] || divisor == 0 {
+            return None;
+        }
+        let mut order = *self;
+        // p is odd, so subtracting one never borrows
+        order[0] -= 1;
+        match div_limbs(&order, divisor as u64) {
+            (quotient, 0) => Some(limbs_exponent(&quotient)),
+            _ => None,
+        }
+    }
+
+    /// Trial division by numbers up to `2^20`, failing unless the remaining
+    /// cofactor is then known to be prime.
+    fn prime_cofactors(&self) -> Option<Vec<BigExponent>> {
+        const BOUND: u64 = 1 << 20;
+        if *self == [0; 4] {
+            return None;
+        }
+        let mut order = *self;
+        order[0] -= 1;
+        let mut rest = order;
+        let mut factors = vec![];
+        for q in 2..BOUND {
+            if div_limbs(&rest, q).1 == 0 {
+                while let (quotient, 0) = div_limbs(&rest, q) {
+                    rest = quotient;
+                }
+                factors.push(q);
+            }
+        }
+        if rest != [1, 0, 0, 0] {
+            if rest[1..] != [0, 0, 0] || rest[0] >= BOUND * BOUND {
+                return None;
+            }
+            factors.push(rest[0]);
+        }
+        Some(
+            factors
+                .iter()
+                .map(|&q| limbs_exponent(&div_limbs(&order, q).0))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(feature = "largefield")]
+mod large {
+
+    extern crate framp as ramp;
+
+    use super::PrimeModulus;
+    use fields::BigExponent;
+
+    /// Bound on the trial division used for factoring `self - 1`.
+    const TRIAL_DIVISION_BOUND: usize = 1 << 20;
+
+    fn int_exponent(x: &ramp::Int) -> BigExponent {
+        BigExponent::from_bits((0..x.bit_length()).map(|i| x.bit(i)).collect())
+    }
+
+    impl PrimeModulus for ramp::Int {
+        fn cofactor(&self, divisor: usize) -> Option<BigExponent> {
+            let order = self - 1;
+            if divisor == 0 || order < 1 || &order % ramp::Int::from(divisor) != 0 {
+                return None;
+            }
+            Some(int_exponent(&(order / ramp::Int::from(divisor))))
+        }
+
+        /// Trial division by numbers up to `2^20`, failing unless the remaining
+        /// cofactor is then known to be prime.
+        fn prime_cofactors(&self) -> Option<Vec<BigExponent>> {
+            let order = self - 1;
+            if order < 1 {
+                return None;
+            }
+            let mut rest = order.clone();
+            let mut factors = vec![];
+            for q in 2..TRIAL_DIVISION_BOUND {
+                let q = ramp::Int::from(q);
+                if &rest % &q == 0 {
+                    while &rest % &q == 0 {
+                        rest = rest / &q;
+                    }
+                    factors.push(q);
+                }
+            }
+            if rest > 1 {
+                let bound = ramp::Int::from(TRIAL_DIVISION_BOUND);
+                if rest >= &bound * &bound {
+                    return None;
+                }
+                factors.push(rest);
+            }
+            Some(
+                factors
+                    .iter()
+                    .map(|q| int_exponent(&(&order / q)))
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// Smallest element `x` (in the order `1, 2, ..`) such that `select(x)` holds, if any.
+fn find_element<F, S>(field: &F, select: S) -> Option<F::E>
+where
+    F: PrimeField,
+    S: Fn(&F::E) -> Option<F::E>,
+{
+    let mut x = field.one();
+    loop {
+        if let Some(found) = select(&x) {
+            return Some(found);
+        }
+        x = field.add(&x, field.one());
+        if field.eq(&x, field.zero()) {
+            return None;
+        }
+    }
+}
+
+/// Principal root of unity of order `order` in `field`, ie. an element `omega` such
+/// that `order` is the smallest positive exponent with `omega^order = 1`.
+///
+/// Such a root exists exactly when `order` divides `prime - 1`; `None` is returned
+/// otherwise. The result is deterministic, being derived from the smallest base that
+/// gives a principal root.
+///
+/// # Example:
+///
+/// ```
+///    use threshold_secret_sharing::*;
+///    use threshold_secret_sharing::numtheory::find_root_of_unity;
+///    let field = NaturalPrimeField::new(433);
+///    let omega = find_root_of_unity(&field, 8).unwrap();
+///    assert_eq!(field.pow(omega, 8), 1);
+///    assert!(field.pow(omega, 4) != 1);
+///    assert_eq!(find_root_of_unity(&field, 5), None);
+/// ```
+pub fn find_root_of_unity<F>(field: &F, order: usize) -> Option<F::E>
+where
+    F: KnownPrime,
+    F::P: PrimeModulus,
+{
+    let cofactor = field.prime().cofactor(order)?;
+    let subgroup_orders: Vec<BigExponent> = prime_factors(order as u64)
+        .into_iter()
+        .map(|q| BigExponent::from(order as u64 / q))
+        .collect();
+    find_element(field, |x| {
+        let omega = field.pow(x, &cofactor);
+        let principal = subgroup_orders
+            .iter()
+            .all(|e| field.neq(field.pow(&omega, e), field.one()));
+        if principal {
+            Some(omega)
+        } else {
+            None
+        }
+    })
+}
+
+/// Smallest generator of the multiplicative group of `field`, ie. a principal root of
+/// unity of order `prime - 1`.
+///
+/// This requires factoring `prime - 1`, and `None` is returned if that fails.
+pub fn find_generator<F>(field: &F) -> Option<F::E>
+where
+    F: KnownPrime,
+    F::P: PrimeModulus,
+{
+    let cofactors = field.prime().prime_cofactors()?;
+    find_element(field, |x| {
+        let generator = cofactors
+            .iter()
+            .all(|e| field.neq(field.pow(x, e), field.one()));
+        if generator {
+            Some(field.mul(x, field.one()))
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Field, Fp61, MontgomeryField32, NaturalPrimeField, New, U256Field};
+
+    #[test]
+    fn test_prime_factors() {
+        assert_eq!(prime_factors(432), [2, 3]);
+        assert_eq!(prime_factors(433), [433]);
+        assert_eq!(
+            prime_factors(Fp61::MODULUS - 1),
+            [2, 3, 5, 7, 11, 13, 31, 41, 61, 151, 331, 1321]
+        );
+    }
+
+    #[test]
+    fn test_find_generator() {
+        let field = NaturalPrimeField(433);
+        assert_eq!(find_generator(&field), Some(5));
+        assert_eq!(find_generator(&NaturalPrimeField(17)), Some(3));
+        assert_eq!(find_generator(&NaturalPrimeField(2)), Some(1));
+        assert_eq!(find_generator(&Fp61), Some(37));
+    }
+
+    #[test]
+    fn test_find_root_of_unity() {
+        let field = NaturalPrimeField(433);
+        for &order in &[1, 2, 3, 8, 9, 27, 48, 432] {
+            let omega = find_root_of_unity(&field, order).unwrap();
+            assert_eq!(field.pow(omega, order as u32), 1);
+            assert!((1..order as u32).all(|e| field.pow(omega, e) != 1));
+        }
+        assert_eq!(find_root_of_unity(&field, 5), None);
+        assert_eq!(find_root_of_unity(&field, 864), None);
+
+        let field = MontgomeryField32::new(433);
+        let omega = find_root_of_unity(&field, 27).unwrap();
+        assert!(field.eq(field.pow(omega, 27), field.one()));
+        assert!(field.neq(field.pow(omega, 9), field.one()));
+
+        let omega = find_root_of_unity(&Fp61, 1 << 1).unwrap();
+        assert_eq!(omega, Fp61::MODULUS - 1);
+        let omega = find_root_of_unity(&Fp61, 1321).unwrap();
+        assert_eq!(Fp61.pow(omega, 1321), 1);
+        assert!(Fp61.pow(omega, 1) != 1);
+
+        let field = U256Field::new(433_u32);
+        let omega = find_root_of_unity(&field, 27).unwrap();
+        assert_eq!(field.pow(omega, 27), field.one());
+        assert!(field.neq(field.pow(omega, 9), field.one()));
+        assert_eq!(find_root_of_unity(&field, 5), None);
+        assert_eq!(find_generator(&field), Some([5, 0, 0, 0]));
+    }
+}
@@ -0,0 +1,285 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Fast multipoint evaluation and interpolation using subproduct trees.
+//!
+//! With FFT-based multiplication both run in time `O(k.log^2(k))` for `k` points,
+//! compared to `O(k^2)` for Newton and Lagrange interpolation.
+
+use fields::{Encode, Field};
+use numtheory::poly;
+
+/// Products of the linear factors `x - x_i` for a set of points, arranged in a
+/// binary tree.
+pub struct SubproductTree<F: Field> {
+    /// `levels[0]` holds the linear factors and the last level their product.
+    levels: Vec<Vec<Vec<F::E>>>,
+    /// Root of unity and its order, used for FFT-based multiplication.
+    root_of_unity: Option<(F::E, usize)>,
+}
+
+impl<F> SubproductTree<F>
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    /// Build the tree using schoolbook multiplication.
+    pub fn new(points: &[F::E], field: &F) -> SubproductTree<F> {
+        SubproductTree::build(points, None, field)
+    }
+
+    /// Build the tree using FFT-based multiplication where possible.
+    ///
+    /// `omega` must be a principal root of unity of order `order`, which must
+    /// be of the form `2^a.3^b`. Products too large for `order` fall back to
+    /// schoolbook multiplication.
+    pub fn with_root_of_unity(
+        points: &[F::E],
+        omega: &F::E,
+        order: usize,
+        field: &F,
+    ) -> SubproductTree<F> {
+        SubproductTree::build(points, Some((omega.clone(), order)), field)
+    }
+
+    fn build(
+        points: &[F::E],
+        root_of_unity: Option<(F::E, usize)>,
+        field: &F,
+    ) -> SubproductTree<F> {
+        assert!(!points.is_empty());
+        let mut tree = SubproductTree {
+            levels: vec![points
+                .iter()
+                .map(|x| vec![field.sub(field.zero(), x), field.one()])
+                .collect()],
+            root_of_unity,
+        };
+        while tree.levels[tree.levels.len() - 1].len() > 1 {
+            let next = {
+                let level = &tree.levels[tree.levels.len() - 1];
+                level
+                    .chunks(2)
+                    .map(|pair| match *pair {
+                        [ref left, ref right] => tree.mul(left, right, field),
+                        _ => pair[0].clone(),
+                    })
+                    .collect()
+            };
+            tree.levels.push(next);
+        }
+        tree
+    }
+
+    /// Number of points.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Whether the tree has no points, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// The product of all `x - x_i`, ie. the polynomial vanishing on all points.
+    pub fn product(&self) -> &[F::E] {
+        &self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Evaluate the polynomial given by `coefficients` at all points.
+    pub fn evaluate(&self, coefficients: &[F::E], field: &F) -> Vec<F::E> {
+        // go down the tree reducing modulo each node
+        let mut remainders = vec![self.rem(coefficients, self.product(), field)];
+        for level in self.levels.iter().rev().skip(1) {
+            remainders = level
+                .iter()
+                .enumerate()
+                .map(|(i, node)| self.rem(&remainders[i / 2], node, field))
+                .collect();
+        }
+        // remainders modulo `x - x_i` are the constants `p(x_i)`
+        remainders
+            .into_iter()
+            .map(|r| r.into_iter().next().unwrap_or_else(|| field.zero()))
+            .collect()
+    }
+
+    /// Find the polynomial of degree less than `len()` taking `values` at the points.
+    pub fn interpolate(&self, values: &[F::E], field: &F) -> Vec<F::E> {
+        let derivative = poly::derivative(self.product(), field);
+        let derivative_values = self.evaluate(&derivative, field);
+        self.interpolate_with_derivative(values, &derivative_values, field)
+    }
+
+    /// Like `interpolate`, but with the values at the points of the derivative of
+    /// `product()` given, for when these can be computed faster by other means.
+    pub fn interpolate_with_derivative(
+        &self,
+        values: &[F::E],
+        derivative_values: &[F::E],
+        field: &F,
+    ) -> Vec<F::E> {
+        assert_eq!(values.len(), self.len());
+        assert_eq!(derivative_values.len(), self.len());
+        let mut weights = derivative_values.to_vec();
+        field.batch_inv(&mut weights);
+        field.mul_pairwise_slice(&mut weights, values);
+
+        // go up the tree computing the sums of `w_i.M(x)/(x - x_i)` below each node
+        let mut combinations: Vec<Vec<F::E>> = weights.into_iter().map(|w| vec![w]).collect();
+        for level in &self.levels[..self.levels.len() - 1] {
+            combinations = combinations
+                .chunks(2)
+                .zip(level.chunks(2))
+                .map(|(combination, nodes)| match (combination, nodes) {
+                    ([ref c_left, ref c_right], [ref m_left, ref m_right]) => poly::add(
+                        &self.mul(c_left, m_right, field),
+                        &self.mul(c_right, m_left, field),
+                        field,
+                    ),
+                    _ => combination[0].clone(),
+                })
+                .collect();
+        }
+        combinations.pop().unwrap()
+    }
+
+    fn mul(&self, a: &[F::E], b: &[F::E], field: &F) -> Vec<F::E> {
+        match self.root_of_unity {
+            Some((ref omega, order)) if a.len() + b.len() - 1 <= order => {
+                poly::mul_fft(a, b, omega, order, field)
+            }
+            _ => poly::mul(a, b, field),
+        }
+    }
+
+    /// `a` modulo the non-zero polynomial `b`, using Newton iteration for the
+    /// inverse of reversed `b` when large enough.
+    fn rem(&self, a: &[F::E], b: &[F::E], field: &F) -> Vec<F::E> {
+        let a = poly::trim(a.to_vec(), field);
+        let b = poly::trim(b.to_vec(), field);
+        if a.len() < b.len() {
+            return a;
+        }
+        let quotient_len = a.len() - b.len() + 1;
+        if quotient_len < poly::SCHOOLBOOK_THRESHOLD || self.root_of_unity.is_none() {
+            return poly::div_rem(&a, &b, field).1;
+        }
+
+        let reversed_a: Vec<F::E> = a.iter().rev().take(quotient_len).cloned().collect();
+        let reversed_b: Vec<F::E> = b.iter().rev().cloned().collect();
+        let inverse = self.inverse_mod_power(&reversed_b, quotient_len, field);
+        let mut quotient = self.mul(&reversed_a, &inverse, field);
+        quotient.resize(quotient_len, field.zero());
+        quotient.reverse();
+
+        let mut remainder = poly::sub(&a, &self.mul(&quotient, &b, field), field);
+        remainder.truncate(b.len() - 1);
+        poly::trim(remainder, field)
+    }
+
+    /// Inverse of `f` modulo `x^len`, where `f[0]` must be non-zero.
+    fn inverse_mod_power(&self, f: &[F::E], len: usize, field: &F) -> Vec<F::E> {
+        let mut g = vec![field.inv(&f[0])];
+        let mut precision = 1;
+        while precision < len {
+            precision = ::std::cmp::min(2 * precision, len);
+            // g <- g.(2 - f.g) mod x^precision
+            let mut e = self.mul(&f[..::std::cmp::min(f.len(), precision)], &g, field);
+            e.resize(precision, field.zero());
+            for x in e.iter_mut() {
+                *x = field.sub(field.zero(), &*x);
+            }
+            e[0] = field.add(&e[0], field.encode(2));
+            g = self.mul(&g, &e, field);
+            g.resize(precision, field.zero());
+        }
+        g
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::*;
+    use numtheory;
+
+    fn test_evaluate<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        let field = &F::new(17);
+
+        let poly = field.encode_slice([1, 2, 3, 4]);
+        let points = field.encode_slice([5, 6, 7, 8, 9]);
+
+        let tree = SubproductTree::new(&points, field);
+        assert_eq!(tree.len(), 5);
+        assert_eq!(
+            field.decode_slice(tree.evaluate(&poly, field)),
+            vec![8, 16, 4, 13, 16]
+        );
+        assert_eq!(
+            field.decode_slice(tree.interpolate(&field.encode_slice([8, 16, 4, 13, 16]), field)),
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    fn test_fast<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32>,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        // field is Z_433 in which 5 is a 432th root of unity
+        let field = &F::new(433);
+        let omega = field.encode(5);
+
+        let poly: Vec<_> = (0..150).map(|i| field.encode(i * i + 7)).collect();
+        let points: Vec<_> = (0..200).map(|i| field.encode(2 * i + 1)).collect();
+        let expected: Vec<_> = points
+            .iter()
+            .map(|point| numtheory::mod_evaluate_polynomial(&poly, point, field))
+            .collect();
+
+        let tree = SubproductTree::with_root_of_unity(&points, &omega, 432, field);
+        let values = tree.evaluate(&poly, field);
+        assert_eq!(field.decode_slice(&values), field.decode_slice(&expected));
+        assert_eq!(
+            field.decode_slice(tree.interpolate(&values, field)),
+            field.decode_slice(&poly)
+        );
+    }
+
+    macro_rules! all_tests {
+        ($field:ty) => {
+            #[test]
+            fn test_evaluate() {
+                super::test_evaluate::<$field>();
+            }
+            #[test]
+            fn test_fast() {
+                super::test_fast::<$field>();
+            }
+        };
+    }
+
+    mod natural {
+        all_tests!(::fields::NaturalPrimeField<i64>);
+    }
+    mod montgomery {
+        all_tests!(::fields::MontgomeryField32);
+    }
+    #[cfg(feature = "largefield")]
+    mod large {
+        all_tests!(::fields::LargePrimeField);
+    }
+}
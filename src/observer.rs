@@ -0,0 +1,169 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Hooks for logging what the schemes do, for instance for compliance purposes.
+//!
+//! While an observer is attached with `with_observer`, sharing and reconstruction
+//! on the same thread notify it with metadata about the operation. Secrets, shares
+//! and randomness are never passed to it.
+
+use share::ShareIndex;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+/// Scheme performing an observed operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemeKind {
+    Shamir,
+    Packed,
+}
+
+/// Public parameters of the scheme performing an observed operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchemeInfo {
+    pub kind: SchemeKind,
+    pub threshold: usize,
+    pub share_count: usize,
+    /// Number of secrets shared together, which is one for Shamir.
+    pub secret_count: usize,
+}
+
+/// Callbacks invoked by the schemes when attached using `with_observer`.
+///
+/// All methods do nothing by default.
+pub trait SharingObserver: Send + Sync {
+    /// A random sharing polynomial was sampled.
+    fn on_poly_sampled(&self, _info: &SchemeInfo) {}
+
+    /// The share with `index` was generated.
+    fn on_share_emitted(&self, _info: &SchemeInfo, _index: ShareIndex) {}
+
+    /// Secrets were reconstructed from the shares with `indices`.
+    fn on_reconstruct(&self, _info: &SchemeInfo, _indices: &[ShareIndex]) {}
+}
+
+thread_local! {
+    static OBSERVER: RefCell<Option<Arc<dyn SharingObserver>>> = RefCell::new(None);
+}
+
+/// Restores the previously attached observer when dropped, also on panics.
+struct Restore(Option<Arc<dyn SharingObserver>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        OBSERVER.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Run `f` with `observer` attached to all schemes used on the current thread,
+/// shadowing any observer attached further out.
+///
+/// Work that `f` hands off to other threads, such as parallel batch sharing, is
+/// not observed.
+pub fn with_observer<R, C: FnOnce() -> R>(observer: Arc<dyn SharingObserver>, f: C) -> R {
+    let previous = OBSERVER.with(|current| current.borrow_mut().replace(observer));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Invoke `callback` on the observer attached to the current thread, if any.
+pub fn notify<C: FnOnce(&dyn SharingObserver)>(callback: C) {
+    // release the borrow first, so that the callback may use the schemes itself
+    let observer = OBSERVER.with(|current| current.borrow().clone());
+    if let Some(observer) = observer {
+        callback(&*observer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::NaturalPrimeField;
+    use packed::PackedSecretSharing;
+    use shamir::ShamirSecretSharing;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl SharingObserver for Recorder {
+        fn on_poly_sampled(&self, info: &SchemeInfo) {
+            self.record(info, "sampled".to_string());
+        }
+
+        fn on_share_emitted(&self, info: &SchemeInfo, index: ShareIndex) {
+            self.record(info, format!("emitted {}", index));
+        }
+
+        fn on_reconstruct(&self, info: &SchemeInfo, indices: &[ShareIndex]) {
+            self.record(info, format!("reconstruct {:?}", indices));
+        }
+    }
+
+    impl Recorder {
+        fn record(&self, info: &SchemeInfo, event: String) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{:?} {}", info.kind, event));
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let recorder = Arc::new(Recorder::default());
+        let tss = ShamirSecretSharing {
+            threshold: 3,
+            share_count: 11,
+            field: NaturalPrimeField(41),
+        };
+        let pss = PackedSecretSharing::with_roots(3, 4, 8, NaturalPrimeField(433), 354, 150);
+
+        with_observer(recorder.clone(), || {
+            let shares = tss.share(7);
+            let indices = [2, 0, 9, 4];
+            let subset: Vec<i64> = indices.iter().map(|&i| shares[i]).collect();
+            assert_eq!(tss.reconstruct(&indices, &subset), 7);
+
+            let shares = pss.share(&[1, 2, 3, 4]);
+            assert_eq!(
+                pss.reconstruct(&[0, 1, 2, 3, 4, 5, 6, 7], &shares),
+                [1, 2, 3, 4]
+            );
+        });
+        tss.share(7);
+
+        let events = recorder.0.lock().unwrap().clone();
+        let mut expected = vec!["Shamir sampled".to_string()];
+        expected.extend((0..11).map(|i| format!("Shamir emitted {}", i)));
+        expected.push("Shamir reconstruct [2, 0, 9, 4]".to_string());
+        expected.push("Packed sampled".to_string());
+        expected.extend((0..8).map(|i| format!("Packed emitted {}", i)));
+        expected.push("Packed reconstruct [0, 1, 2, 3, 4, 5, 6, 7]".to_string());
+        assert_eq!(events, expected);
+    }
+
+    #[test]
+    fn test_nested_observers() {
+        let outer = Arc::new(Recorder::default());
+        let inner = Arc::new(Recorder::default());
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 2,
+            field: NaturalPrimeField(41),
+        };
+        with_observer(outer.clone(), || {
+            with_observer(inner.clone(), || tss.share(1));
+            tss.share(2);
+        });
+        assert_eq!(inner.0.lock().unwrap().len(), 3);
+        assert_eq!(outer.0.lock().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,402 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Sharing and reconstruction in caller-provided buffers.
+//!
+//! Once the FFT plans of the scheme are built, `share_into` and `reconstruct_into`
+//! perform no allocation as long as the field elements themselves do not allocate,
+//! which matters when sharing many small batches of secrets. `PackedSharingContext`
+//! wraps them with buffers of its own.
+
+use super::PackedSecretSharing;
+use fields::ForwardSecureRng;
+use fields::{Encode, Field};
+use numtheory::batch_inverse_in_place;
+#[cfg(feature = "observer")]
+use observer::notify;
+use rand::Rng;
+
+/// Buffers and randomness generator for repeatedly sharing and reconstructing with
+/// the same `PackedSecretSharing` instance without allocating.
+///
+/// The FFT plans with their twiddle factors are cached by the scheme itself, and
+/// built when creating the context, so that `share` and `reconstruct` only do the
+/// actual work.
+pub struct PackedSharingContext<'a, F: Field + 'a, R: Rng = ForwardSecureRng> {
+    pss: &'a PackedSecretSharing<F>,
+    rng: R,
+    randomness: Vec<F::E>,
+    scratch: Vec<F::E>,
+    shares: Vec<F::E>,
+    secrets: Vec<F::E>,
+}
+
+impl<'a, F> PackedSharingContext<'a, F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Context for `pss`, sampling randomness from a `ForwardSecureRng` seeded
+    /// from the operating system.
+    pub fn new(pss: &'a PackedSecretSharing<F>) -> PackedSharingContext<'a, F> {
+        PackedSharingContext::with_rng(pss, ForwardSecureRng::new())
+    }
+}
+
+impl<'a, F, R> PackedSharingContext<'a, F, R>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+    R: Rng,
+{
+    /// Context for `pss`, sampling randomness from `rng`.
+    pub fn with_rng(pss: &'a PackedSecretSharing<F>, rng: R) -> PackedSharingContext<'a, F, R> {
+        let zero = pss.field.zero();
+        PackedSharingContext {
+            pss,
+            rng,
+            randomness: vec![zero.clone(); pss.threshold],
+            scratch: vec![zero.clone(); pss.scratch_len()],
+            shares: vec![zero.clone(); pss.share_count],
+            secrets: vec![zero; pss.secret_count],
+        }
+    }
+
+    /// Generate `share_count` shares for the `secrets` vector like
+    /// `PackedSecretSharing::share`, with the scheme the context was created for.
+    ///
+    /// The shares are overwritten by the next call.
+    pub fn share(&mut self, secrets: &[F::E]) -> &[F::E] {
+        for value in &mut self.randomness {
+            *value = self.pss.field.sample(&mut self.rng);
+        }
+        self.pss.share_into(
+            secrets,
+            &self.randomness,
+            &mut self.scratch,
+            &mut self.shares,
+        );
+        &self.shares
+    }
+
+    /// Reconstruct the secrets like `PackedSecretSharing::reconstruct`, with the
+    /// scheme the context was created for.
+    ///
+    /// The secrets are overwritten by the next call.
+    pub fn reconstruct(&mut self, indices: &[u32], shares: &[F::E]) -> &[F::E] {
+        self.pss
+            .reconstruct_into(indices, shares, &mut self.scratch, &mut self.secrets);
+        &self.secrets
+    }
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Number of elements of scratch space needed by `share_into` and
+    /// `reconstruct_into`.
+    ///
+    /// This also builds the FFT plans of the scheme, after which neither method
+    /// allocates.
+    pub fn scratch_len(&self) -> usize {
+        let share_points = self.share_count + 1;
+        let plan_scratch = self
+            .shares_plan()
+            .pruned_scratch_len()
+            .max(self.secrets_plan().scratch_len());
+        // points, weights, and differences with their prefix products when
+        // interpolating from at most `share_count` shares plus the zero at point 1
+        (share_points + plan_scratch).max(4 * share_points)
+    }
+
+    /// Generate `share_count` shares for the `secrets` vector into `out`, like
+    /// `share` but using the `threshold` elements of `randomness` instead of
+    /// sampling them.
+    ///
+    /// The randomness must be uniformly random and never reused, since the privacy
+    /// of the secrets relies on it. `scratch` must hold at least `scratch_len`
+    /// elements, whose values are overwritten.
+    pub fn share_into(
+        &self,
+        secrets: &[F::E],
+        randomness: &[F::E],
+        scratch: &mut [F::E],
+        out: &mut [F::E],
+    ) {
+        assert_eq!(secrets.len(), self.secret_count);
+        assert_eq!(randomness.len(), self.threshold);
+        assert_eq!(out.len(), self.share_count);
+        assert!(scratch.len() >= self.scratch_len());
+        let field = &self.field;
+        let secret_points = self.reconstruct_limit() + 1;
+        let (values, scratch) = scratch.split_at_mut(self.share_count + 1);
+
+        // the value at point 1 is zero, the secrets are placed according to the
+        // layout, and the randomness fills in the remaining points
+        let mut randomness = randomness.iter();
+        for (e, value) in values[..secret_points].iter_mut().enumerate() {
+            *value = match self.layout.secret_index(e, self.secret_count) {
+                _ if e == 0 => field.zero(),
+                Some(i) => secrets[i].clone(),
+                None => randomness.next().unwrap().clone(),
+            };
+        }
+        self.secrets_plan()
+            .inverse_with_scratch(&mut values[..secret_points], scratch);
+        #[cfg(feature = "observer")]
+        notify(|observer| observer.on_poly_sampled(&self.observer_info()));
+
+        // extend the polynomial with zeroes and evaluate it at all share points,
+        // the first of which is always zero and not a share
+        for value in &mut values[secret_points..] {
+            *value = field.zero();
+        }
+        self.shares_plan()
+            .forward_pruned_with_scratch(values, secret_points, scratch);
+        assert!(field.eq(&values[0], field.zero()));
+        out.clone_from_slice(&values[1..]);
+        #[cfg(feature = "observer")]
+        self.notify_emitted();
+    }
+
+    /// Reconstruct the secrets into `out` like `reconstruct`, using `scratch`
+    /// instead of allocating.
+    ///
+    /// All shares given in order are interpolated with FFTs, and any other subset
+    /// with barycentric interpolation. `out` must hold `secret_count` elements,
+    /// and `scratch` at least `scratch_len`, whose values are overwritten.
+    pub fn reconstruct_into(
+        &self,
+        indices: &[u32],
+        shares: &[F::E],
+        scratch: &mut [F::E],
+        out: &mut [F::E],
+    ) {
+        assert!(shares.len() == indices.len());
+        assert!(shares.len() >= self.reconstruct_limit());
+        assert!(shares.len() <= self.share_count);
+        assert_eq!(out.len(), self.secret_count);
+        assert!(scratch.len() >= self.scratch_len());
+        #[cfg(feature = "observer")]
+        self.notify_reconstruct(indices);
+        let complete = shares.len() == self.share_count
+            && indices
+                .iter()
+                .enumerate()
+                .all(|(i, &index)| index as usize == i);
+        if complete {
+            self.interpolate_codeword_into(shares, scratch, out);
+        } else {
+            self.interpolate_barycentric_into(indices, shares, scratch, out);
+        }
+    }
+
+    /// Secrets from all shares, like `interpolate_codeword`.
+    fn interpolate_codeword_into(&self, shares: &[F::E], scratch: &mut [F::E], out: &mut [F::E]) {
+        let field = &self.field;
+        let share_points = self.share_count + 1;
+        let secret_points = self.reconstruct_limit() + 1;
+        let (values, scratch) = scratch.split_at_mut(share_points);
+        values[0] = field.zero();
+        values[1..].clone_from_slice(shares);
+        self.shares_plan().inverse_with_scratch(values, scratch);
+        // reduce modulo `x^m - 1` in place, as done by `evaluate_secrets`
+        for i in secret_points..share_points {
+            let coefficient = values[i].clone();
+            let folded = &mut values[i % secret_points];
+            *folded = field.add(&*folded, coefficient);
+        }
+        let values = &mut values[..secret_points];
+        self.secrets_plan().forward_with_scratch(values, scratch);
+        for (i, secret) in out.iter_mut().enumerate() {
+            *secret = values[self.layout.exponent(i)].clone();
+        }
+    }
+
+    /// Secrets from any subset of the shares, using the barycentric formula
+    /// `P(z) = L(z).sum(w_j.y_j / (z - x_j))` where `L` vanishes at all points.
+    fn interpolate_barycentric_into(
+        &self,
+        indices: &[u32],
+        shares: &[F::E],
+        scratch: &mut [F::E],
+        out: &mut [F::E],
+    ) {
+        let field = &self.field;
+        // the value zero at point 1 is interpolated from as an extra share
+        let count = shares.len() + 1;
+        let (points, scratch) = scratch.split_at_mut(count);
+        let (weights, scratch) = scratch.split_at_mut(count);
+        let (differences, scratch) = scratch.split_at_mut(count);
+        points[0] = field.one();
+        for (point, &index) in points[1..].iter_mut().zip(indices) {
+            *point = field.pow(&self.omega_shares, index + 1);
+        }
+
+        // weights `w_j = 1 / prod(x_j - x_l)` over `l != j`, already multiplied by
+        // the values
+        for (j, weight) in weights.iter_mut().enumerate() {
+            *weight = field.one();
+            for (l, point) in points.iter().enumerate() {
+                if l != j {
+                    *weight = field.mul(&*weight, field.sub(&points[j], point));
+                }
+            }
+        }
+        batch_inverse_in_place(weights, scratch, field);
+        for (weight, share) in weights[1..].iter_mut().zip(shares) {
+            *weight = field.mul(&*weight, share);
+        }
+
+        for (i, secret) in out.iter_mut().enumerate() {
+            let z = field.pow(&self.omega_secrets, self.layout.exponent(i) as u32);
+            let mut vanishing = field.one();
+            for (difference, point) in differences.iter_mut().zip(&*points) {
+                *difference = field.sub(&z, point);
+                vanishing = field.mul(vanishing, &*difference);
+            }
+            batch_inverse_in_place(differences, scratch, field);
+            let mut sum = field.zero();
+            for (weight, difference) in weights[1..].iter().zip(&differences[1..]) {
+                sum = field.add(sum, field.mul(weight, difference));
+            }
+            *secret = field.mul(vanishing, sum);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{NaturalPrimeField, SliceEncode};
+    use packed::ReconstructStrategy::Auto;
+    use packed::PSS_155_728_100;
+    use packed::{SecretLayout, PSS_4_26_3};
+    use seed::seeded_rng;
+
+    #[test]
+    fn test_share_into() {
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([5, 6, 7]);
+        let randomness = pss.field.encode_slice([11, 12, 13, 14]);
+        let mut scratch = vec![0; pss.scratch_len()];
+        let mut shares = vec![0; pss.share_count];
+        pss.share_into(&secrets, &randomness, &mut scratch, &mut shares);
+        let mut again = vec![0; pss.share_count];
+        pss.share_into(&secrets, &randomness, &mut scratch, &mut again);
+        assert_eq!(shares, again);
+
+        // the randomness fills in the points after the secrets
+        let exponents: Vec<usize> = (1..8).collect();
+        let mut values = secrets.clone();
+        values.extend_from_slice(&randomness);
+        assert_eq!(
+            pss.reconstruct_points(Auto, &[0, 1, 2, 3, 4, 5, 6], &shares[..7], &exponents),
+            values
+        );
+        assert_eq!(
+            pss.reconstruct(&[0, 1, 2, 3, 4, 5, 6], &shares[..7]),
+            secrets
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_into() {
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([5, 6, 7]);
+        let shares = pss.share(&secrets);
+        let mut scratch = vec![0; pss.scratch_len()];
+        let mut out = vec![0; pss.secret_count];
+
+        let all: Vec<u32> = (0..26).collect();
+        pss.reconstruct_into(&all, &shares, &mut scratch, &mut out);
+        assert_eq!(out, secrets);
+
+        let subsets: [Vec<u32>; 3] = [
+            (3..10).collect(),
+            vec![25, 0, 9, 13, 17, 21, 4],
+            (1..26).collect(),
+        ];
+        for indices in &subsets {
+            let values: Vec<i64> = indices.iter().map(|&i| shares[i as usize]).collect();
+            out.clear();
+            out.resize(pss.secret_count, 0);
+            pss.reconstruct_into(indices, &values, &mut scratch, &mut out);
+            assert_eq!(out, secrets);
+        }
+    }
+
+    #[test]
+    fn test_layout_and_bluestein() {
+        // secrets on 8th roots of unity, shares on 11th roots of unity,
+        // and convolutions on 24th roots of unity
+        let field = NaturalPrimeField(1321);
+        let pss = PackedSecretSharing::with_roots(4, 3, 10, field, 235, 58)
+            .with_convolution(17)
+            .with_layout(SecretLayout::Custom(vec![6, 2, 5]))
+            .unwrap();
+        let secrets = [5, 6, 7];
+        let mut scratch = vec![0; pss.scratch_len()];
+        let mut shares = vec![0; 10];
+        pss.share_into(&secrets, &[1, 2, 3, 4], &mut scratch, &mut shares);
+        // the randomness fills in the points not holding secrets
+        let exponents: Vec<usize> = (1..8).collect();
+        assert_eq!(
+            pss.reconstruct_points(Auto, &[2, 3, 4, 5, 6, 7, 8], &shares[2..9], &exponents),
+            [1, 6, 2, 3, 7, 5, 4]
+        );
+
+        let mut out = vec![0; 3];
+        let all: Vec<u32> = (0..10).collect();
+        pss.reconstruct_into(&all, &shares, &mut scratch, &mut out);
+        assert_eq!(out, secrets);
+        pss.reconstruct_into(
+            &[9, 1, 0, 2, 4, 7, 5],
+            &[
+                shares[9], shares[1], shares[0], shares[2], shares[4], shares[7], shares[5],
+            ],
+            &mut scratch,
+            &mut out,
+        );
+        assert_eq!(out, secrets);
+    }
+
+    #[test]
+    fn test_context() {
+        let pss = &PSS_155_728_100;
+        let seed = [1; 32];
+        let mut context = PackedSharingContext::with_rng(pss, seeded_rng(&seed));
+        let indices: Vec<u32> = (100..400).collect();
+        for round in 0_u32..3 {
+            let secrets = pss.field.encode_slice(vec![round; pss.secret_count]);
+            let shares = context.share(&secrets).to_vec();
+            assert_eq!(shares.len(), pss.share_count);
+            assert_eq!(
+                context.reconstruct(&indices, &shares[100..400]),
+                &secrets[..]
+            );
+            if round == 0 {
+                // the randomness is sampled in the same order as by `share_with_rng`
+                assert_eq!(shares, pss.share_seeded(&secrets, &seed));
+            }
+        }
+
+        let pss = &PSS_4_26_3;
+        let mut context = PackedSharingContext::new(pss);
+        let secrets = pss.field.encode_slice([1, 2, 3]);
+        let shares = context.share(&secrets).to_vec();
+        assert_ne!(context.share(&secrets), &shares[..]);
+        assert_eq!(
+            pss.reconstruct(&[0, 1, 2, 3, 4, 5, 6], &shares[..7]),
+            secrets
+        );
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Sharing of arbitrarily many secrets as a chain of packed sharings.
+
+use std::error::Error;
+use std::fmt;
+
+use super::PackedSecretSharing;
+use fields::{Encode, Field};
+
+/// Base of the polynomial hash computing the checksums.
+const CHECKSUM_BASE: u32 = 0x9e37_79b9;
+
+/// Failure to reconstruct secrets shared with `share_many`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkError {
+    /// No chunks were given.
+    Empty,
+    /// The checksum of the chunk at this position does not match the chunks up
+    /// to it, because chunks are missing, in the wrong order, or corrupted.
+    Checksum(usize),
+    /// The padding at the end of the last chunk is malformed.
+    Padding,
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ChunkError::Empty => write!(f, "no chunks to reconstruct from"),
+            ChunkError::Checksum(chunk) => write!(f, "checksum mismatch in chunk {}", chunk),
+            ChunkError::Padding => write!(f, "malformed padding in last chunk"),
+        }
+    }
+}
+
+impl Error for ChunkError {}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Share any number of `secrets` by splitting them into chunks of
+    /// `secret_count - 1`, returning the `share_count` shares of each chunk in
+    /// order.
+    ///
+    /// The remaining secret of every chunk is a running checksum of all chunks up to
+    /// it, including their positions and whether they are the last one, so that
+    /// `reconstruct_many` detects chunks that are missing or out of order. The
+    /// secrets are padded with a one and then zeros to fill the last chunk, which
+    /// may hence be entirely padding.
+    ///
+    /// The checksum is an unkeyed polynomial hash, which catches accidents but not
+    /// deliberate forgeries by parties able to reconstruct anyway.
+    pub fn share_many(&self, secrets: &[F::E]) -> Vec<Vec<F::E>> {
+        assert!(
+            self.secret_count >= 2,
+            "secret_count must leave room for the checksum"
+        );
+        let field = &self.field;
+        let per_chunk = self.secret_count - 1;
+        let mut padded = secrets.to_vec();
+        padded.push(field.one());
+        while padded.len() % per_chunk != 0 {
+            padded.push(field.zero());
+        }
+
+        let chunk_count = padded.len() / per_chunk;
+        let mut checksum = field.zero();
+        let chunks: Vec<Vec<F::E>> = padded
+            .chunks(per_chunk)
+            .enumerate()
+            .map(|(position, data)| {
+                checksum = self.chain_checksum(checksum.clone(), position, chunk_count, data);
+                let mut chunk = Vec::with_capacity(self.secret_count);
+                chunk.push(checksum.clone());
+                chunk.extend_from_slice(data);
+                chunk
+            })
+            .collect();
+        let chunks: Vec<&[F::E]> = chunks.iter().map(|chunk| &chunk[..]).collect();
+        self.share_batch(&chunks)
+    }
+
+    /// Reconstruct the secrets shared with `share_many` from the shares of each
+    /// chunk in order, with `indices` the ranks of the shares known for all chunks.
+    ///
+    /// All checksums are verified, and the padding removed.
+    pub fn reconstruct_many<S>(
+        &self,
+        indices: &[u32],
+        chunks: &[S],
+    ) -> Result<Vec<F::E>, ChunkError>
+    where
+        S: AsRef<[F::E]>,
+    {
+        if chunks.is_empty() {
+            return Err(ChunkError::Empty);
+        }
+        let field = &self.field;
+        let mut secrets = Vec::with_capacity(chunks.len() * (self.secret_count - 1));
+        let mut checksum = field.zero();
+        for (position, shares) in chunks.iter().enumerate() {
+            let chunk = self.reconstruct(indices, shares.as_ref());
+            checksum = self.chain_checksum(checksum, position, chunks.len(), &chunk[1..]);
+            if field.neq(&checksum, &chunk[0]) {
+                return Err(ChunkError::Checksum(position));
+            }
+            secrets.extend_from_slice(&chunk[1..]);
+        }
+
+        // strip the padding
+        while secrets
+            .last()
+            .is_some_and(|last| field.eq(last, field.zero()))
+        {
+            secrets.pop();
+        }
+        match secrets.pop() {
+            Some(ref one) if field.eq(one, field.one()) => Ok(secrets),
+            _ => Err(ChunkError::Padding),
+        }
+    }
+
+    /// Checksum of the chunk at `position` among `chunk_count` holding `data`,
+    /// following the `previous` one.
+    fn chain_checksum(
+        &self,
+        previous: F::E,
+        position: usize,
+        chunk_count: usize,
+        data: &[F::E],
+    ) -> F::E {
+        let field = &self.field;
+        let base = field.encode(CHECKSUM_BASE);
+        let last = if position + 1 == chunk_count {
+            field.one()
+        } else {
+            field.zero()
+        };
+        let header = [field.encode(position as u32), last];
+        header.iter().chain(data).fold(previous, |checksum, x| {
+            field.add(field.mul(checksum, &base), x)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::SliceEncode;
+    use packed::{PSS_4_26_3, PSS_4_8_3};
+
+    #[test]
+    fn test_share_reconstruct_many() {
+        let pss = &PSS_4_26_3;
+        let indices: Vec<u32> = (3..10).collect();
+        for count in 0..8_u32 {
+            let secrets = pss.field.encode_slice((1..count + 1).collect::<Vec<_>>());
+            let chunks = pss.share_many(&secrets);
+            // two secrets per chunk, followed by the padding
+            assert_eq!(chunks.len(), count as usize / 2 + 1);
+            let known: Vec<Vec<i64>> = chunks
+                .iter()
+                .map(|shares| indices.iter().map(|&i| shares[i as usize]).collect())
+                .collect();
+            assert_eq!(pss.reconstruct_many(&indices, &known), Ok(secrets));
+        }
+
+        // trailing zeros and ones are kept
+        let pss = &PSS_4_8_3;
+        let secrets = vec![0, 1, 0, 0];
+        let chunks = pss.share_many(&secrets);
+        let indices: Vec<u32> = (0..8).collect();
+        assert_eq!(pss.reconstruct_many(&indices, &chunks), Ok(secrets));
+    }
+
+    #[test]
+    fn test_reconstruct_many_errors() {
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([1, 2, 3, 4, 5, 6, 7]);
+        let chunks = pss.share_many(&secrets);
+        assert_eq!(chunks.len(), 4);
+        let indices: Vec<u32> = (0..26).collect();
+        let empty: &[Vec<i64>] = &[];
+        assert_eq!(
+            pss.reconstruct_many(&indices, empty),
+            Err(ChunkError::Empty)
+        );
+
+        // swapped chunks
+        let mut swapped = chunks.clone();
+        swapped.swap(1, 2);
+        assert_eq!(
+            pss.reconstruct_many(&indices, &swapped),
+            Err(ChunkError::Checksum(1))
+        );
+        // truncated, even at a chunk boundary
+        assert_eq!(
+            pss.reconstruct_many(&indices, &chunks[..3]),
+            Err(ChunkError::Checksum(2))
+        );
+        // dropped chunk
+        let dropped = [&chunks[0], &chunks[2], &chunks[3]];
+        assert_eq!(
+            pss.reconstruct_many(&indices, &dropped),
+            Err(ChunkError::Checksum(1))
+        );
+        // chunk from another sharing differing in an earlier chunk
+        let other = pss.share_many(&pss.field.encode_slice([9, 2, 3, 4, 5, 6, 7]));
+        let mixed = [&chunks[0], &chunks[1], &other[2], &chunks[3]];
+        assert_eq!(
+            pss.reconstruct_many(&indices, &mixed),
+            Err(ChunkError::Checksum(2))
+        );
+        // tampered share values
+        let mut tampered: Vec<&[i64]> = chunks.iter().map(|shares| &shares[..7]).collect();
+        let mut share = tampered[1].to_vec();
+        share[5] += 1;
+        tampered[1] = &share;
+        assert_eq!(
+            pss.reconstruct_many(&indices[..7], &tampered),
+            Err(ChunkError::Checksum(1))
+        );
+    }
+}
@@ -0,0 +1,310 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Packed scheme with its parameters fixed at compile time.
+
+use std::array;
+
+use super::PackedSecretSharing;
+use fields::ForwardSecureRng;
+use fields::{Encode, Field};
+use numtheory::batch_inverse_in_place;
+use numtheory::fft::is_mixed_radix_len;
+use rand::Rng;
+use seed::{seeded_rng, Seed};
+
+/// Panic unless `threshold`, `share_count` and `secret_count` satisfy the
+/// constraints listed for `PackedSecretSharing` without Bluestein's algorithm,
+/// which fails compilation when evaluated in a constant.
+const fn check_params(threshold: usize, share_count: usize, secret_count: usize) {
+    let m = secret_count + threshold + 1;
+    let n = share_count + 1;
+    assert!(secret_count > 0, "secret_count must be positive");
+    assert!(
+        share_count >= secret_count + threshold,
+        "share_count must be at least secret_count + threshold"
+    );
+    assert!(
+        is_mixed_radix_len(m),
+        "secret_count + threshold + 1 must be of the form 2^a.3^b"
+    );
+    assert!(
+        is_mixed_radix_len(n),
+        "share_count + 1 must be of the form 2^a.3^b"
+    );
+    assert!(
+        gcd(m, n) == 1,
+        "secret_count + threshold + 1 and share_count + 1 must be coprime"
+    );
+}
+
+/// Greatest common divisor of `a` and `b`, usable in constants.
+const fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Packed secret sharing with threshold `T`, `N` shares and `K` secrets fixed at
+/// compile time.
+///
+/// The constraints between these parameters are checked when compiling, and
+/// secrets, shares, and all intermediate values live in arrays on the stack.
+/// Since the FFT is not worth it at these sizes, the polynomial is instead
+/// interpolated and evaluated directly, making this the cheapest option for the
+/// tiny schemes of interactive protocols but not for large ones.
+///
+/// Secrets and shares are positioned as with the `Consecutive` layout of the
+/// equivalent `PackedSecretSharing`, given by `to_dynamic`, so that both can be
+/// used together.
+///
+/// ```
+/// # extern crate threshold_secret_sharing as tss;
+/// # use tss::{NaturalPrimeField, PackedSecretSharingConst};
+/// // two secrets in 8 shares with threshold 1, in Z_433 where 179 is a 4th root
+/// // of unity and 150 a 9th root of unity
+/// let pss = PackedSecretSharingConst::<_, 1, 8, 2>::new(NaturalPrimeField(433), 179, 150);
+/// let shares = pss.share(&[5, 6]);
+/// assert_eq!(pss.reconstruct(&[0, 4, 7], &[shares[0], shares[4], shares[7]]), [5, 6]);
+/// ```
+///
+/// Invalid parameters are rejected when compiling, such as `7 + 1` shares and
+/// `3 + 4 + 1` secret points which are not coprime:
+///
+/// ```compile_fail
+/// # extern crate threshold_secret_sharing as tss;
+/// # use tss::{NaturalPrimeField, PackedSecretSharingConst};
+/// let pss = PackedSecretSharingConst::<_, 4, 7, 3>::new(NaturalPrimeField(433), 354, 354);
+/// ```
+///
+/// The same holds for `5 + 1` shares and `2 + 1 + 1` secret points, which share the
+/// factor 2:
+///
+/// ```compile_fail
+/// # extern crate threshold_secret_sharing as tss;
+/// # use tss::{NaturalPrimeField, PackedSecretSharingConst};
+/// let pss = PackedSecretSharingConst::<_, 1, 5, 2>::new(NaturalPrimeField(433), 179, 280);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackedSecretSharingConst<F: Field, const T: usize, const N: usize, const K: usize> {
+    /// Finite field in which computation is taking place.
+    pub field: F,
+    /// `(K + T + 1)`-th principal root of unity.
+    pub omega_secrets: F::E,
+    /// `(N + 1)`-th principal root of unity.
+    pub omega_shares: F::E,
+}
+
+impl<F, const T: usize, const N: usize, const K: usize> PackedSecretSharingConst<F, T, N, K>
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    /// Scheme over `field` with the given roots of unity, failing to compile
+    /// if `T`, `N`, and `K` are not valid parameters.
+    pub fn new(field: F, omega_secrets: F::E, omega_shares: F::E) -> Self {
+        const { check_params(T, N, K) };
+        PackedSecretSharingConst {
+            field,
+            omega_secrets,
+            omega_shares,
+        }
+    }
+
+    /// Equivalent `PackedSecretSharing` instance.
+    pub fn to_dynamic(&self) -> PackedSecretSharing<F>
+    where
+        F: Clone,
+    {
+        PackedSecretSharing::with_roots(
+            T,
+            K,
+            N,
+            self.field.clone(),
+            self.omega_secrets.clone(),
+            self.omega_shares.clone(),
+        )
+    }
+
+    /// Generate `N` shares for the `secrets`.
+    pub fn share(&self, secrets: &[F::E; K]) -> [F::E; N] {
+        let mut rng = ForwardSecureRng::new();
+        self.share_with_rng(secrets, &mut rng)
+    }
+
+    /// Generate `N` shares for the `secrets`, deriving all randomness from `seed`
+    /// like `PackedSecretSharing::share_seeded`.
+    pub fn share_seeded(&self, secrets: &[F::E; K], seed: &Seed) -> [F::E; N] {
+        self.share_with_rng(secrets, &mut seeded_rng(seed))
+    }
+
+    /// Generate `N` shares for the `secrets`, sampling the randomness from `rng`.
+    pub fn share_with_rng<R: Rng>(&self, secrets: &[F::E; K], rng: &mut R) -> [F::E; N] {
+        let randomness = array::from_fn(|_| self.field.sample(rng));
+        self.share_with_randomness(secrets, &randomness)
+    }
+
+    /// Generate `N` shares for the `secrets`, using `randomness` instead of
+    /// sampling it, like `PackedSecretSharing::share_into`.
+    ///
+    /// The randomness must be uniformly random and never reused, since the privacy
+    /// of the secrets relies on it.
+    pub fn share_with_randomness(&self, secrets: &[F::E; K], randomness: &[F::E; T]) -> [F::E; N] {
+        const { check_params(T, N, K) };
+        let field = &self.field;
+        let m = K + T + 1;
+        // the values at the powers of omega_secrets are zero, then the secrets,
+        // then the randomness
+        let value = |j: usize| {
+            if j == 0 {
+                field.zero()
+            } else if j <= K {
+                secrets[j - 1].clone()
+            } else {
+                randomness[j - K - 1].clone()
+            }
+        };
+
+        // coefficients `c_k = sum(v_j.omega^(-jk)) / m` by the inverse DFT, using
+        // that `m <= N` for all valid parameters
+        let omega_inv = field.inv(&self.omega_secrets);
+        let m_inv = field.inv(field.encode(m as u32));
+        let mut coefficients: [F::E; N] = array::from_fn(|_| field.zero());
+        let mut step = field.one();
+        for coefficient in coefficients[..m].iter_mut() {
+            let mut sum = field.zero();
+            let mut factor = field.one();
+            for j in 0..m {
+                sum = field.add(sum, field.mul(value(j), &factor));
+                factor = field.mul(factor, &step);
+            }
+            *coefficient = field.mul(sum, &m_inv);
+            step = field.mul(step, &omega_inv);
+        }
+
+        // evaluate with Horner's rule at the powers of omega_shares
+        let mut point = field.one();
+        array::from_fn(|_| {
+            point = field.mul(&point, &self.omega_shares);
+            coefficients[..m]
+                .iter()
+                .rev()
+                .fold(field.zero(), |acc, c| field.add(field.mul(acc, &point), c))
+        })
+    }
+
+    /// Reconstruct the secrets from at least `K + T` of the shares, with `indices`
+    /// their ranks as output by `share`.
+    pub fn reconstruct(&self, indices: &[u32], shares: &[F::E]) -> [F::E; K] {
+        const { check_params(T, N, K) };
+        assert_eq!(shares.len(), indices.len());
+        assert!(shares.len() >= K + T);
+        assert!(shares.len() <= N);
+        let field = &self.field;
+        let count = shares.len();
+        let zeros = || -> [F::E; N] { array::from_fn(|_| field.zero()) };
+        let (mut points, mut weights, mut differences, mut scratch) =
+            (zeros(), zeros(), zeros(), zeros());
+        let (points, weights, differences) = (
+            &mut points[..count],
+            &mut weights[..count],
+            &mut differences[..count],
+        );
+        for (point, &index) in points.iter_mut().zip(indices) {
+            *point = field.pow(&self.omega_shares, index + 1);
+        }
+
+        // barycentric interpolation as in `PackedSecretSharing::reconstruct_into`,
+        // where the point 1 at which the polynomial is zero only contributes to the
+        // weights and the vanishing polynomial
+        for (j, weight) in weights.iter_mut().enumerate() {
+            *weight = field.sub(&points[j], field.one());
+            for (l, point) in points.iter().enumerate() {
+                if l != j {
+                    *weight = field.mul(&*weight, field.sub(&points[j], point));
+                }
+            }
+        }
+        batch_inverse_in_place(weights, &mut scratch, field);
+        for (weight, share) in weights.iter_mut().zip(shares) {
+            *weight = field.mul(&*weight, share);
+        }
+
+        let mut z = field.one();
+        array::from_fn(|_| {
+            z = field.mul(&z, &self.omega_secrets);
+            let mut vanishing = field.sub(&z, field.one());
+            for (difference, point) in differences.iter_mut().zip(&*points) {
+                *difference = field.sub(&z, point);
+                vanishing = field.mul(vanishing, &*difference);
+            }
+            batch_inverse_in_place(differences, &mut scratch, field);
+            let sum = weights
+                .iter()
+                .zip(&*differences)
+                .fold(field.zero(), |sum, (w, d)| field.add(sum, field.mul(w, d)));
+            field.mul(vanishing, sum)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{MontgomeryField32, NaturalPrimeField, New, SliceEncode};
+    use packed::PSS_4_26_3;
+
+    #[test]
+    fn test_share_reconstruct() {
+        // field is Z_433 in which 179 is a 4th root of unity and 150 a 9th root of
+        // unity
+        let pss = PackedSecretSharingConst::<_, 2, 8, 1>::new(NaturalPrimeField(433), 179, 150);
+        let shares = pss.share(&[42]);
+        assert_eq!(
+            pss.reconstruct(&[1, 5, 6], &[shares[1], shares[5], shares[6]]),
+            [42]
+        );
+        let indices: Vec<u32> = (0..8).collect();
+        assert_eq!(pss.reconstruct(&indices, &shares), [42]);
+
+        let field = MontgomeryField32::new(433);
+        let pss = PackedSecretSharingConst::<_, 1, 8, 2>::new(
+            field.clone(),
+            field.encode(179),
+            field.encode(150),
+        );
+        let secrets = [field.encode(5), field.encode(6)];
+        let shares = pss.share(&secrets);
+        let recovered = pss.reconstruct(&[7, 2, 3], &[shares[7], shares[2], shares[3]]);
+        assert!(field.eq(&recovered[0], &secrets[0]) && field.eq(&recovered[1], &secrets[1]));
+    }
+
+    #[test]
+    fn test_dynamic() {
+        let dynamic = &PSS_4_26_3;
+        let pss = PackedSecretSharingConst::<_, 4, 26, 3>::new(
+            dynamic.field.clone(),
+            dynamic.omega_secrets,
+            dynamic.omega_shares,
+        );
+        assert_eq!(pss.to_dynamic(), *dynamic);
+
+        // the randomness is sampled and placed in the same order
+        let seed = [4; 32];
+        let secrets = dynamic.field.encode_slice([1, 2, 3]);
+        let shares = pss.share_seeded(&[1, 2, 3], &seed);
+        assert_eq!(shares.to_vec(), dynamic.share_seeded(&secrets, &seed));
+        let indices: Vec<u32> = (10..20).collect();
+        assert_eq!(pss.reconstruct(&indices, &shares[10..20]), [1, 2, 3]);
+        assert_eq!(dynamic.reconstruct(&indices, &shares[10..20]), secrets);
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Resharing between a packed sharing of `secret_count` secrets and as many
+//! independent Shamir sharings of them.
+//!
+//! This cannot be done locally. A packed share is the value of a polynomial of
+//! degree `reconstruct_limit - 1` at a power of `omega_shares`, whereas a Shamir
+//! share is the value of a polynomial of degree `threshold` at its index plus one,
+//! with the secret at zero; no party can move its value to another point or lower
+//! the degree on its own. Each conversion therefore takes one round of resharing,
+//! and both directions are split into a step run by the senders and a step run by
+//! the recipients: every party of the source scheme shares
+//! what it holds under the target scheme, and every party of the target scheme then
+//! combines the subshares it received. Since reconstruction is linear in the shares,
+//! combining amounts to running the reconstruction of the source scheme on the
+//! subshares, which interpolates them from the share positions to the secret
+//! positions of the source scheme.
+//!
+//! Fresh randomness is used for resharing, so the resulting sharing is independent of
+//! the original one except for the secrets.
+
+use super::PackedSecretSharing;
+use fields::{Encode, Field};
+use shamir::ShamirSecretSharing;
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field,
+    F: Encode<u32>,
+    F: Clone,
+    F::E: Clone,
+{
+    /// First step of converting to Shamir sharings: reshare our packed `share` with
+    /// `tss`, sending the subshare with index `i` to the party with Shamir index `i`.
+    ///
+    /// At least `reconstruct_limit` parties must do so.
+    pub fn reshare_to_shamir(&self, share: &F::E, tss: &ShamirSecretSharing<F>) -> Vec<F::E> {
+        tss.share(share.clone())
+    }
+
+    /// Second step of converting to Shamir sharings: combine the `subshares` received
+    /// from the parties with packed `indices` into our Shamir shares of each of the
+    /// `secret_count` secrets.
+    pub fn combine_to_shamir(&self, indices: &[u32], subshares: &[F::E]) -> Vec<F::E> {
+        self.reconstruct(indices, subshares)
+    }
+
+    /// First step of converting from Shamir sharings: pack our Shamir `shares` of
+    /// each of the `secret_count` secrets, sending the subshare with index `i` to the
+    /// party with packed index `i`.
+    ///
+    /// At least `tss.reconstruct_limit()` parties must do so.
+    pub fn reshare_from_shamir(&self, shares: &[F::E]) -> Vec<F::E> {
+        self.share(shares)
+    }
+
+    /// Second step of converting from Shamir sharings: combine the `subshares`
+    /// received from the parties with Shamir `indices` into our packed share of the
+    /// secrets.
+    pub fn combine_from_shamir(
+        &self,
+        tss: &ShamirSecretSharing<F>,
+        indices: &[usize],
+        subshares: &[F::E],
+    ) -> F::E {
+        tss.reconstruct(indices, subshares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::PSS_4_26_3;
+    use fields::NaturalPrimeField;
+    use shamir::ShamirSecretSharing;
+
+    fn shamir() -> ShamirSecretSharing<NaturalPrimeField<i64>> {
+        ShamirSecretSharing {
+            threshold: 3,
+            share_count: 10,
+            field: NaturalPrimeField(433),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let pss = &PSS_4_26_3;
+        let tss = shamir();
+        let packed_shares = pss.share(&[5, 6, 7]);
+
+        // packed parties 3 to 9 reshare to the Shamir parties
+        let senders: Vec<u32> = (3..3 + pss.reconstruct_limit() as u32).collect();
+        let subshares: Vec<Vec<i64>> = senders
+            .iter()
+            .map(|&i| pss.reshare_to_shamir(&packed_shares[i as usize], &tss))
+            .collect();
+        // shamir_shares[p][j] is the share of party p of secret j
+        let shamir_shares: Vec<Vec<i64>> = (0..tss.share_count)
+            .map(|p| {
+                let received: Vec<i64> = subshares.iter().map(|s| s[p]).collect();
+                pss.combine_to_shamir(&senders, &received)
+            })
+            .collect();
+        for (j, &secret) in [5, 6, 7].iter().enumerate() {
+            let shares: Vec<i64> = shamir_shares.iter().map(|s| s[j]).collect();
+            assert_eq!(tss.reconstruct(&[0, 1, 2, 3], &shares[..4]), secret);
+            assert_eq!(
+                tss.reconstruct(&[9, 2, 5, 7], &[shares[9], shares[2], shares[5], shares[7]]),
+                secret
+            );
+        }
+
+        // Shamir parties 6 to 9 reshare back to the packed parties
+        let senders: Vec<usize> = (6..10).collect();
+        let subshares: Vec<Vec<i64>> = senders
+            .iter()
+            .map(|&p| pss.reshare_from_shamir(&shamir_shares[p]))
+            .collect();
+        let packed_shares: Vec<i64> = (0..pss.share_count)
+            .map(|i| {
+                let received: Vec<i64> = subshares.iter().map(|s| s[i]).collect();
+                pss.combine_from_shamir(&tss, &senders, &received)
+            })
+            .collect();
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+        assert_eq!(pss.verify_shares(&indices, &packed_shares), Ok(()));
+        assert_eq!(
+            pss.reconstruct(&indices[..7], &packed_shares[..7]),
+            [5, 6, 7]
+        );
+        assert_eq!(
+            pss.reconstruct(&indices[19..], &packed_shares[19..]),
+            [5, 6, 7]
+        );
+    }
+}
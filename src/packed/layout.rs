@@ -0,0 +1,115 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Positions at which the packed scheme embeds the secrets.
+
+/// Choice of the powers of `omega_secrets` at which the secrets of a
+/// `PackedSecretSharing` are embedded.
+///
+/// The sharing polynomial is always zero at `omega_secrets^0 = 1`, and takes the
+/// secrets and fresh randomness as values at the remaining powers up to
+/// `omega_secrets^(secret_count + threshold)`. The layout picks the exponents holding
+/// the secrets, while the randomness fills the others in increasing order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SecretLayout {
+    /// Secret `i` at `omega_secrets^(i + 1)`, followed by the randomness.
+    #[default]
+    Consecutive,
+    /// Secret `i` at `omega_secrets^(stride * (i + 1))`, interleaved with randomness.
+    ///
+    /// With stride `s` the secrets lie at the powers of `omega_secrets^s`, ie. at the
+    /// positions of the consecutive secrets of a scheme using that root instead,
+    /// allowing sharings of the two schemes to be combined.
+    Strided(usize),
+    /// Secret `i` at `omega_secrets^exponents[i]`.
+    Custom(Vec<usize>),
+}
+
+impl SecretLayout {
+    /// Exponents of `omega_secrets` at which `secret_count` secrets are embedded.
+    pub fn exponents(&self, secret_count: usize) -> Vec<usize> {
+        match *self {
+            SecretLayout::Consecutive => (1..secret_count + 1).collect(),
+            SecretLayout::Strided(stride) => (1..secret_count + 1).map(|i| stride * i).collect(),
+            SecretLayout::Custom(ref exponents) => exponents.clone(),
+        }
+    }
+
+    /// Exponent of `omega_secrets` at which secret `index` is embedded.
+    pub fn exponent(&self, index: usize) -> usize {
+        match *self {
+            SecretLayout::Consecutive => index + 1,
+            SecretLayout::Strided(stride) => stride * (index + 1),
+            SecretLayout::Custom(ref exponents) => exponents[index],
+        }
+    }
+
+    /// Index of the secret embedded at `omega_secrets^exponent`, if any.
+    pub fn secret_index(&self, exponent: usize, secret_count: usize) -> Option<usize> {
+        let index = match *self {
+            SecretLayout::Consecutive => exponent.checked_sub(1)?,
+            SecretLayout::Strided(stride) => {
+                if stride == 0 || exponent % stride != 0 {
+                    return None;
+                }
+                (exponent / stride).checked_sub(1)?
+            }
+            SecretLayout::Custom(ref exponents) => exponents.iter().position(|&e| e == exponent)?,
+        };
+        Some(index).filter(|&i| i < secret_count)
+    }
+
+    /// Whether `secret_count` secrets are embedded at distinct exponents between
+    /// `1` and `limit`.
+    pub fn is_valid(&self, secret_count: usize, limit: usize) -> bool {
+        let exponents = self.exponents(secret_count);
+        let mut used = vec![false; limit + 1];
+        exponents.len() == secret_count
+            && exponents.iter().all(|&e| {
+                let fresh = (1..=limit).contains(&e) && !used[e];
+                if fresh {
+                    used[e] = true;
+                }
+                fresh
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_exponents() {
+        assert_eq!(SecretLayout::Consecutive.exponents(3), [1, 2, 3]);
+        assert_eq!(SecretLayout::Strided(2).exponents(3), [2, 4, 6]);
+        assert_eq!(SecretLayout::Custom(vec![5, 1]).exponents(2), [5, 1]);
+
+        for layout in &[
+            SecretLayout::Consecutive,
+            SecretLayout::Strided(2),
+            SecretLayout::Custom(vec![5, 1, 3]),
+        ] {
+            for (i, &e) in layout.exponents(3).iter().enumerate() {
+                assert_eq!(layout.exponent(i), e);
+                assert_eq!(layout.secret_index(e, 3), Some(i));
+            }
+            assert_eq!(layout.secret_index(0, 3), None);
+            assert_eq!(layout.secret_index(7, 3), None);
+        }
+        assert_eq!(SecretLayout::Strided(2).secret_index(3, 3), None);
+
+        assert!(SecretLayout::Consecutive.is_valid(3, 7));
+        assert!(SecretLayout::Strided(2).is_valid(3, 6));
+        assert!(!SecretLayout::Strided(2).is_valid(3, 5));
+        assert!(!SecretLayout::Strided(0).is_valid(3, 7));
+        assert!(!SecretLayout::Custom(vec![2, 2]).is_valid(2, 7));
+        assert!(!SecretLayout::Custom(vec![2]).is_valid(2, 7));
+    }
+}
@@ -9,8 +9,25 @@
 //! Packed (or ramp) variant of Shamir secret sharing,
 //! allowing efficient sharing of several secrets together.
 
-use fields::{Encode, Field};
-use rand;
+use fields::ForwardSecureRng;
+use fields::{Decode, ElementBytes, Encode, Field, SliceDecode, SliceEncode};
+use numtheory::fft::FftPlan;
+use numtheory::{
+    mod_evaluate_polynomial, BarycentricWeights, InterpolationCache, NewtonPolynomial,
+};
+#[cfg(feature = "observer")]
+use observer::{notify, SchemeInfo, SchemeKind};
+use rand::Rng;
+use scheme::ThresholdScheme;
+use seed::{seeded_rng, Seed};
+use share::{
+    check_indices, check_lengths, deduplicate_shares, index_u32, IndexError, PolynomialShares,
+    ProductShares, Share, ShareIndex,
+};
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use transcript::{ShareHasher, SharingTranscript};
 
 /// Parameters for the packed variant of Shamir secret sharing,
 /// specifying number of secrets shared together, total number of shares, and privacy threshold.
@@ -24,19 +41,32 @@ use rand;
 /// allowing most operations run to quasilinear time `O(n.log(n))` in `share_count`.
 /// An implication of this is that secrets and shares are positioned on positive powers of
 /// respectively an `n`-th and `m`-th principal root of unity,
-/// where `n` is a power of 2 and `m` a power of 3.
+/// where `n` and `m` are of the form `2^a.3^b`.
 ///
 /// As a result there exist several constraints between the various parameters:
 ///
 /// * `prime` must be a prime large enough to hold the secrets we plan to share
 /// * `share_count` must be at least `secret_count + threshold` (the reconstruction limit)
-/// * `secret_count + threshold + 1` must be a power of 2
-/// * `share_count + 1` must be a power of 3
+/// * `secret_count + threshold + 1` and `share_count + 1` must both be of the form `2^a.3^b`
+/// * `secret_count + threshold + 1` and `share_count + 1` must be coprime, so that
+///   secrets and shares are never positioned on the same points
 /// * `omega_secrets` must be a `(secret_count + threshold + 1)`-th root of unity
 /// * `omega_shares` must be a `(share_count + 1)`-th root of unity
 ///
+/// In practice this means that one of them is a power of 2 and the other a power of 3.
+///
+/// Alternatively, `share_count + 1` may be any number coprime with `secret_count + threshold + 1`
+/// when a convolution root is given with `with_convolution`, in which case shares are computed
+/// using Bluestein's algorithm. This allows using exactly the number of shares needed by the
+/// application.
+///
 /// An optional `paramgen` feature provides methods for finding suitable parameters satisfying
 /// these somewhat complex requirements, in addition to several fixed parameter choices.
+/// Instances constructed by hand with `with_roots` can be checked against them using `validate`.
+///
+/// The FFT plans for the roots of unity are built on first use and cached. Changing
+/// `omega_secrets` or `omega_shares` afterwards is allowed: the next call plans
+/// afresh for the new root and caches that plan instead.
 #[derive(Debug, Clone, PartialEq)]
 pub struct PackedSecretSharing<F: Field> {
     // abstract properties
@@ -52,16 +82,164 @@ pub struct PackedSecretSharing<F: Field> {
     /// Finite field in which computation is taking place.
     pub field: F,
     /// `m`-th principal root of unity in Zp, where `m = secret_count + threshold + 1`
-    /// must be a power of 2.
+    /// must be of the form `2^a.3^b`.
     pub omega_secrets: F::E,
-    /// `n`-th principal root of unity in Zp, where `n = share_count + 1` must be a power of 3.
+    /// `n`-th principal root of unity in Zp, where `n = share_count + 1` must be of the
+    /// form `2^a.3^b` and coprime with `m`.
     pub omega_shares: F::E,
+    /// Principal root of unity in Zp of order at least `2n - 1` and of the form `2^a.3^b`,
+    /// only needed when `n = share_count + 1` is not itself of the form `2^a.3^b`.
+    omega_convolution: Option<F::E>,
+    /// Powers of `omega_secrets` at which the secrets are embedded.
+    layout: SecretLayout,
+    plans: FftPlans<F>,
+}
+
+/// FFT plans of a `PackedSecretSharing` instance, built on first use and reused by
+/// subsequent calls to `share` and `reconstruct`.
+///
+/// Since the roots of unity of the scheme are public fields, every use checks that
+/// the cached plan was built for the current root and length, and otherwise plans
+/// afresh and replaces the cached plan, so that only the first use after changing
+/// a root pays for planning.
+struct FftPlans<F: Field> {
+    secrets: Mutex<Option<Arc<FftPlan<F>>>>,
+    shares: Mutex<Option<Arc<FftPlan<F>>>>,
+}
+
+impl<F: Field> FftPlans<F> {
+    const fn new() -> FftPlans<F> {
+        FftPlans {
+            secrets: Mutex::new(None),
+            shares: Mutex::new(None),
+        }
+    }
+}
+
+/// Clones share the plans built so far.
+impl<F: Field> Clone for FftPlans<F> {
+    fn clone(&self) -> FftPlans<F> {
+        FftPlans {
+            secrets: Mutex::new(self.secrets.lock().unwrap().clone()),
+            shares: Mutex::new(self.shares.lock().unwrap().clone()),
+        }
+    }
+}
+
+/// Plans are derived from the other parameters, and hence always equal.
+impl<F: Field> PartialEq for FftPlans<F> {
+    fn eq(&self, _other: &FftPlans<F>) -> bool {
+        true
+    }
+}
+
+impl<F: Field> fmt::Debug for FftPlans<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FftPlans")
+            .field("secrets", &self.secrets.lock().unwrap().is_some())
+            .field("shares", &self.shares.lock().unwrap().is_some())
+            .finish()
+    }
+}
+
+/// Failure of `verify_shares`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The indices of the shares are invalid.
+    Index(IndexError),
+    /// The shares with these indices do not lie on the polynomial interpolated from
+    /// the first `reconstruct_limit` shares.
+    Inconsistent(Vec<u32>),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerifyError::Index(ref err) => err.fmt(f),
+            VerifyError::Inconsistent(ref indices) => {
+                write!(f, "shares with indices {:?} are inconsistent", indices)
+            }
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+impl From<IndexError> for VerifyError {
+    fn from(err: IndexError) -> VerifyError {
+        VerifyError::Index(err)
+    }
+}
+
+impl<F: Field> PackedSecretSharing<F> {
+    /// Scheme with the given parameters, which must satisfy the constraints listed
+    /// above, embedding the secrets at `omega_secrets^1` up to
+    /// `omega_secrets^secret_count`.
+    pub const fn with_roots(
+        threshold: usize,
+        secret_count: usize,
+        share_count: usize,
+        field: F,
+        omega_secrets: F::E,
+        omega_shares: F::E,
+    ) -> PackedSecretSharing<F> {
+        PackedSecretSharing {
+            threshold,
+            secret_count,
+            share_count,
+            field,
+            omega_secrets,
+            omega_shares,
+            omega_convolution: None,
+            layout: SecretLayout::Consecutive,
+            plans: FftPlans::new(),
+        }
+    }
+
+    /// Use `omega_convolution` for Bluestein's algorithm, allowing any `share_count`
+    /// such that `share_count + 1` is coprime with `secret_count + threshold + 1`.
+    ///
+    /// `omega_convolution` must be a principal root of unity of order at least
+    /// `2.share_count + 1` and of the form `2^a.3^b`.
+    pub fn with_convolution(mut self, omega_convolution: F::E) -> PackedSecretSharing<F> {
+        self.omega_convolution = Some(omega_convolution);
+        self.plans = FftPlans::new();
+        self
+    }
+
+    /// Embed the secrets at the powers of `omega_secrets` given by `layout` instead
+    /// of consecutively.
+    ///
+    /// Fails with `ParamError::InvalidLayout` unless the layout embeds
+    /// `secret_count` secrets at distinct powers `omega_secrets^1` up to
+    /// `omega_secrets^(secret_count + threshold)`.
+    pub fn with_layout(
+        mut self,
+        layout: SecretLayout,
+    ) -> Result<PackedSecretSharing<F>, ParamError> {
+        if !layout.is_valid(self.secret_count, self.secret_count + self.threshold) {
+            return Err(ParamError::InvalidLayout);
+        }
+        self.layout = layout;
+        Ok(self)
+    }
+
+    /// Root of unity used by Bluestein's algorithm, if any.
+    pub fn omega_convolution(&self) -> Option<&F::E> {
+        self.omega_convolution.as_ref()
+    }
+
+    /// Powers of `omega_secrets` at which the secrets are embedded.
+    pub fn layout(&self) -> &SecretLayout {
+        &self.layout
+    }
 }
 
 impl<F> PackedSecretSharing<F>
 where
     F: Field,
     F: Encode<u32>,
+    F: Clone,
     F::E: Clone,
 {
     /// Minimum number of shares required to reconstruct secrets.
@@ -71,14 +249,177 @@ where
         self.threshold + self.secret_count
     }
 
+    /// Points at which the secrets are embedded, ie. the powers of `omega_secrets`
+    /// given by `layout`.
+    ///
+    /// The sharing polynomial is also zero at `omega_secrets^0 = 1`, which is not a
+    /// secret position.
+    pub fn secret_positions(&self) -> Vec<F::E> {
+        self.exponent_powers(&self.secret_exponents())
+    }
+
+    /// Exponents of `omega_secrets` at which the secrets are embedded.
+    fn secret_exponents(&self) -> Vec<usize> {
+        self.layout.exponents(self.secret_count)
+    }
+
+    /// The powers of `omega_secrets` with the given `exponents`.
+    fn exponent_powers(&self, exponents: &[usize]) -> Vec<F::E> {
+        exponents
+            .iter()
+            .map(|&e| self.field.pow(&self.omega_secrets, e as u32))
+            .collect()
+    }
+
+    /// Points at which the sharing polynomial is evaluated to give the shares, ie.
+    /// the share with index `i` is its value at `omega_shares^(i + 1)`.
+    pub fn share_positions(&self) -> Vec<F::E> {
+        self.powers(&self.omega_shares, self.share_count)
+    }
+
+    /// The powers `omega^1` up to `omega^count`.
+    fn powers(&self, omega: &F::E, count: usize) -> Vec<F::E> {
+        let mut point = self.field.one();
+        (0..count)
+            .map(|_| {
+                point = self.field.mul(&point, omega);
+                point.clone()
+            })
+            .collect()
+    }
+
     /// Generate `share_count` shares for the `secrets` vector.
     ///
     /// The length of `secrets` must be `secret_count`.
     /// It is safe to pad with anything, including zeros.
+    ///
+    /// The randomness is sampled from a `ForwardSecureRng` seeded from the operating
+    /// system.
     pub fn share(&self, secrets: &[F::E]) -> Vec<F::E> {
+        let mut rng = ForwardSecureRng::new();
+        self.share_with_rng(secrets, &mut rng)
+    }
+
+    /// Generate `share_count` shares for `secrets` given as plain values, encoding
+    /// them into the field first.
+    pub fn share_values<T: Copy>(&self, secrets: &[T]) -> Vec<F::E>
+    where
+        F: Encode<T>,
+    {
+        self.share(&self.field.encode_slice(secrets))
+    }
+
+    /// Generate `share_count` shares for the `secrets` vector, deriving all
+    /// randomness from `seed`.
+    ///
+    /// The same secrets and seed always give the same shares, which allows the
+    /// sharing to be checked later using `verify_sharing`.
+    pub fn share_seeded(&self, secrets: &[F::E], seed: &Seed) -> Vec<F::E> {
+        self.share_with_rng(secrets, &mut seeded_rng(seed))
+    }
+
+    /// Check that `claimed_shares` are the shares of `secrets` generated by
+    /// `share_seeded` with `seed`, returning the indices of any that are not.
+    ///
+    /// This lets a dealer prove to an auditor, given the secrets and the seed, that
+    /// the issued shares are consistent with the declared secrets.
+    pub fn verify_sharing(
+        &self,
+        secrets: &[F::E],
+        seed: &Seed,
+        claimed_shares: &[F::E],
+    ) -> Result<(), Vec<ShareIndex>> {
+        assert_eq!(claimed_shares.len(), self.share_count);
+        let shares = self.share_seeded(secrets, seed);
+        let mismatches: Vec<ShareIndex> = (0..self.share_count)
+            .filter(|&i| self.field.neq(&shares[i], &claimed_shares[i]))
+            .collect();
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    /// Generate `share_count` shares for the `secrets` vector like `share`, also
+    /// returning the `reconstruct_limit + 1` coefficients of the sharing polynomial.
+    ///
+    /// The polynomial is zero at `1`, takes the secrets as values at
+    /// `secret_positions`, and share `i` is its value at `omega_shares^(i + 1)`.
+    pub fn share_returning_polynomial(&self, secrets: &[F::E]) -> PolynomialShares<F> {
+        let mut rng = ForwardSecureRng::new();
+        let coefficients = self.sample_polynomial(secrets, &mut rng);
+        PolynomialShares {
+            shares: self.shares_from_polynomial(coefficients.clone()),
+            coefficients,
+        }
+    }
+
+    /// Generate `share_count` shares for the `secrets` vector, sampling the
+    /// randomness from `rng`.
+    pub fn share_with_rng<R: Rng>(&self, secrets: &[F::E], rng: &mut R) -> Vec<F::E> {
         assert_eq!(secrets.len(), self.secret_count);
         // sample polynomial
-        let mut poly = self.sample_polynomial(secrets);
+        let poly = self.sample_polynomial(secrets, rng);
+        self.shares_from_polynomial(poly)
+    }
+
+    /// Generate `share_count` shares of `secret_count` zeros.
+    ///
+    /// Like any other sharing these are randomized, so adding them to the shares of
+    /// secrets rerandomizes their sharing without changing the secrets.
+    pub fn share_zero(&self) -> Vec<F::E> {
+        self.share(&vec![self.field.zero(); self.secret_count])
+    }
+
+    /// Generate `share_count` shares of `secret_count` fresh random values, sampling
+    /// the values and the randomness from `rng`, and return the values along with
+    /// their shares.
+    pub fn share_random<R: Rng>(&self, rng: &mut R) -> (Vec<F::E>, Vec<F::E>) {
+        let values = self.field.sample_with_replacement(self.secret_count, rng);
+        let shares = self.share_with_rng(&values, rng);
+        (values, shares)
+    }
+
+    /// Generate `share_count` randomized shares of the public `constant` in every
+    /// one of the `secret_count` positions.
+    pub fn share_constant(&self, constant: F::E) -> Vec<F::E> {
+        self.share(&vec![constant; self.secret_count])
+    }
+
+    /// Generate the `share_count` shares for the `secrets` vector lazily, as pairs
+    /// of their index and value.
+    ///
+    /// Only the `reconstruct_limit + 1` coefficients of the sharing polynomial are
+    /// kept, from which each share is evaluated when requested. This takes time
+    /// `O(reconstruct_limit)` per share instead of sharing the cost of an FFT, but
+    /// lets a dealer stream the shares to their recipients without holding all of
+    /// them.
+    pub fn share_iter<'a>(&'a self, secrets: &[F::E]) -> impl Iterator<Item = (u32, F::E)> + 'a {
+        self.share_iter_with_rng(secrets, &mut ForwardSecureRng::new())
+    }
+
+    /// Generate shares lazily like `share_iter`, sampling the randomness from `rng`.
+    pub fn share_iter_with_rng<'a, R: Rng>(
+        &'a self,
+        secrets: &[F::E],
+        rng: &mut R,
+    ) -> impl Iterator<Item = (u32, F::E)> + 'a {
+        assert_eq!(secrets.len(), self.secret_count);
+        let poly = self.sample_polynomial(secrets, rng);
+        let field = &self.field;
+        let mut point = field.one();
+        (0..self.share_count as u32).map(move |index| {
+            point = field.mul(&point, &self.omega_shares);
+            #[cfg(feature = "observer")]
+            notify(|observer| observer.on_share_emitted(&self.observer_info(), index as usize));
+            (index, mod_evaluate_polynomial(&poly, &point, field))
+        })
+    }
+
+    /// Shares given by the polynomial with the `reconstruct_limit + 1` coefficients
+    /// `poly`.
+    fn shares_from_polynomial(&self, mut poly: Vec<F::E>) -> Vec<F::E> {
         assert_eq!(poly.len(), self.reconstruct_limit() + 1);
         // .. and extend it (with zeroes)
         poly.extend(vec![
@@ -93,68 +434,278 @@ where
         shares.remove(0);
         // return
         assert_eq!(shares.len(), self.share_count);
+        #[cfg(feature = "observer")]
+        self.notify_emitted();
         shares
     }
 
+    /// Generate shares for each of the `secrets_chunks` vectors, as done by `share`.
+    ///
+    /// The FFT plans are built once and reused for all chunks.
+    pub fn share_batch(&self, secrets_chunks: &[&[F::E]]) -> Vec<Vec<F::E>> {
+        let mut rng = ForwardSecureRng::new();
+        secrets_chunks
+            .iter()
+            .map(|secrets| self.share_with_rng(secrets, &mut rng))
+            .collect()
+    }
+
+    /// Generate shares for each of the `secrets_chunks` vectors like `share_batch`,
+    /// spreading the chunks across `thread_count` threads.
+    #[cfg(feature = "parallel")]
+    pub fn share_batch_parallel(
+        &self,
+        secrets_chunks: &[&[F::E]],
+        thread_count: usize,
+    ) -> Vec<Vec<F::E>>
+    where
+        F: Send + Sync,
+        F::E: Send + Sync,
+    {
+        assert!(thread_count > 0);
+        let chunks_per_thread = secrets_chunks.len().div_ceil(thread_count).max(1);
+        ::std::thread::scope(|scope| {
+            let handles: Vec<_> = secrets_chunks
+                .chunks(chunks_per_thread)
+                .map(|chunks| scope.spawn(move || self.share_batch(chunks)))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+
+    /// Generate `share_count` shares for the `secrets` vector, each carrying its index.
+    pub fn share_indexed(&self, secrets: &[F::E]) -> Vec<Share<F>> {
+        Share::enumerate(self.share(secrets))
+    }
+
+    /// Generate shares for the `secrets` vector together with commitments to each
+    /// of them.
+    pub fn share_audited<H>(
+        &self,
+        secrets: &[F::E],
+        hasher: H,
+    ) -> (Vec<F::E>, SharingTranscript<F, H>)
+    where
+        F: ElementBytes + Clone,
+        H: ShareHasher,
+    {
+        let shares = self.share(secrets);
+        let transcript = SharingTranscript::new(self.field.clone(), hasher, &shares);
+        (shares, transcript)
+    }
+
+    /// Shares of the pointwise sum of two vectors of secrets, from their shares `a`
+    /// and `b`.
+    ///
+    /// Like the other share arithmetic this works pointwise, so any subset of the
+    /// shares may be given, such as the single share held by a party, as long as `a`
+    /// and `b` hold shares with the same indices.
+    pub fn add_shares(&self, a: &[F::E], b: &[F::E]) -> Vec<F::E> {
+        ::share::add_shares(&self.field, a, b)
+    }
+
+    /// Shares of the pointwise difference of two vectors of secrets, from their
+    /// shares `a` and `b`.
+    pub fn sub_shares(&self, a: &[F::E], b: &[F::E]) -> Vec<F::E> {
+        ::share::sub_shares(&self.field, a, b)
+    }
+
+    /// Shares of a vector of secrets multiplied by the public `scalar`, from its
+    /// shares `a`.
+    pub fn scale_shares(&self, a: &[F::E], scalar: &F::E) -> Vec<F::E> {
+        ::share::scale_shares(&self.field, a, scalar)
+    }
+
+    /// Shares of the secrets plus the public `constants`, from all of their shares.
+    ///
+    /// Since the secrets are positioned at different points, this is not a matter of
+    /// adding the same value to each share; instead the shares of the sharing of
+    /// `constants` with all randomness set to zero are added.
+    pub fn add_constant_shares(&self, shares: &[F::E], constants: &[F::E]) -> Vec<F::E> {
+        assert_eq!(shares.len(), self.share_count);
+        let zeros = vec![self.field.zero(); self.threshold];
+        let mut poly = self.recover_polynomial(constants, zeros);
+        poly.resize(self.share_count + 1, self.field.zero());
+        let mut offsets = self.evaluate_polynomial(poly);
+        offsets.remove(0);
+        self.add_shares(shares, &offsets)
+    }
+
+    /// Share with the given `index` of the secrets plus the public `constants`, from
+    /// the corresponding share of the secrets.
+    ///
+    /// Equivalent to `add_constant_shares` but for a single share, as held by a party.
+    /// Returns `IndexError::UnknownShare` if `index` is not below `share_count`.
+    pub fn add_constant(
+        &self,
+        index: u32,
+        share: &F::E,
+        constants: &[F::E],
+    ) -> Result<F::E, IndexError> {
+        let unknown = IndexError::UnknownShare(index as ShareIndex);
+        if index as ShareIndex >= self.share_count {
+            return Err(unknown);
+        }
+        let exponent = index.checked_add(1).ok_or(unknown)?;
+        let zeros = vec![self.field.zero(); self.threshold];
+        let poly = self.recover_polynomial(constants, zeros);
+        let point = self.field.pow(&self.omega_shares, exponent);
+        let offset = ::numtheory::mod_evaluate_polynomial(&poly, point, &self.field);
+        Ok(self.field.add(share, offset))
+    }
+
+    /// Share of the secrets multiplied by the public `scalar`, from one of their shares.
+    pub fn scale(&self, share: &F::E, scalar: &F::E) -> F::E {
+        self.field.mul(share, scalar)
+    }
+
+    /// Shares of the pointwise product of two vectors of secrets, from their shares
+    /// `a` and `b` as output by `share`.
+    ///
+    /// The product polynomial has twice the degree, so `2 * reconstruct_limit`
+    /// shares are needed to reconstruct it; if this exceeds `share_count` the
+    /// product can no longer be recovered.
+    pub fn mul_shares(&self, a: &[F::E], b: &[F::E]) -> ProductShares<F> {
+        ::share::mul_shares(&self.field, a, b, 2 * self.reconstruct_limit())
+    }
+
     #[cfg(feature = "safety_override")]
     pub fn deterministic_share(&self, secrets_and_randomness: &[F::E]) -> Vec<F::E> {
         let mut values = secrets_and_randomness.to_vec();
         values.insert(0, self.field.zero());
         assert_eq!(values.len(), self.reconstruct_limit() + 1);
-        ::numtheory::fft::fft2_inverse(&self.field, &mut *values, &self.omega_secrets);
-        let mut poly = values;
-
-        // TODO unify this with `share`
-
-        assert_eq!(poly.len(), self.reconstruct_limit() + 1);
-        // .. and extend it (with zeroes)
-        poly.extend(vec![
-            self.field.zero();
-            self.share_count - self.reconstruct_limit()
-        ]);
-        assert_eq!(poly.len(), self.share_count + 1);
-        // evaluate polynomial to generate shares
-        let mut shares = self.evaluate_polynomial(poly);
-        // .. but remove first element since it should not be used as a share (it's always zero)
-        assert!(self.field.eq(&shares[0], self.field.zero()));
-        shares.remove(0);
-        // return
-        assert_eq!(shares.len(), self.share_count);
-        shares
+        self.secrets_plan().inverse(&mut values);
+        self.shares_from_polynomial(values)
     }
 
-    fn sample_polynomial(&self, secrets: &[F::E]) -> Vec<F::E> {
+    fn sample_polynomial<R: Rng>(&self, secrets: &[F::E], rng: &mut R) -> Vec<F::E> {
         assert_eq!(secrets.len(), self.secret_count);
-        // sample randomness using secure randomness
-        let mut rng = rand::OsRng::new().unwrap();
-        let randomness = self.field.sample_with_replacement(self.threshold, &mut rng);
-        debug_assert!(self.field.neq(&randomness[0], &randomness[1])); // small probability for false negative
-                                                                       // recover polynomial
+        // sample randomness
+        let randomness = self.field.sample_with_replacement(self.threshold, rng);
+        // recover polynomial
         let coefficients = self.recover_polynomial(secrets, randomness);
         assert_eq!(coefficients.len(), self.reconstruct_limit() + 1);
+        #[cfg(feature = "observer")]
+        notify(|observer| observer.on_poly_sampled(&self.observer_info()));
         coefficients
     }
 
+    #[cfg(feature = "observer")]
+    fn observer_info(&self) -> SchemeInfo {
+        SchemeInfo {
+            kind: SchemeKind::Packed,
+            threshold: self.threshold,
+            share_count: self.share_count,
+            secret_count: self.secret_count,
+        }
+    }
+
+    #[cfg(feature = "observer")]
+    fn notify_emitted(&self) {
+        notify(|observer| {
+            let info = self.observer_info();
+            for index in 0..self.share_count {
+                observer.on_share_emitted(&info, index);
+            }
+        });
+    }
+
+    #[cfg(feature = "observer")]
+    fn notify_reconstruct(&self, indices: &[u32]) {
+        notify(|observer| {
+            let indices: Vec<usize> = indices.iter().map(|&i| i as usize).collect();
+            observer.on_reconstruct(&self.observer_info(), &indices);
+        });
+    }
+
     fn recover_polynomial(&self, secrets: &[F::E], randomness: Vec<F::E>) -> Vec<F::E> {
-        // fix the value corresponding to point 1 (zero)
-        let mut values = vec![self.field.zero()];
-        // let the subsequent values correspond to the secrets
-        values.extend(secrets.to_vec()); // TODO can we do without to_vec?
-                                         // fill in with random values
-        values.extend(randomness);
-        // run backward FFT to recover polynomial in coefficient representation
-        assert_eq!(values.len(), self.reconstruct_limit() + 1);
+        let limit = self.reconstruct_limit();
+        assert_eq!(secrets.len() + randomness.len(), limit);
+        // the value corresponding to point 1 is fixed to zero
+        let mut values = vec![self.field.zero(); limit + 1];
+        let mut is_secret = vec![false; limit + 1];
+        // let the values at the layout's points correspond to the secrets
+        for (&e, secret) in self.secret_exponents().iter().zip(secrets) {
+            values[e] = secret.clone();
+            is_secret[e] = true;
+        }
+        // .. and fill in the remaining ones with random values
+        let mut randomness = randomness.into_iter();
+        for e in 1..limit + 1 {
+            if !is_secret[e] {
+                values[e] = randomness.next().unwrap();
+            }
+        }
         // in-place FFT to turn values into coefficients
-        ::numtheory::fft::fft2_inverse(&self.field, &mut *values, &self.omega_secrets);
+        self.secrets_plan().inverse(&mut values);
         values
     }
 
     fn evaluate_polynomial(&self, mut coefficients: Vec<F::E>) -> Vec<F::E> {
         assert_eq!(coefficients.len(), self.share_count + 1);
-        ::numtheory::fft::fft3(&self.field, &mut coefficients, &self.omega_shares);
+        // only the first `reconstruct_limit + 1` coefficients may be non-zero
+        self.shares_plan()
+            .forward_pruned(&mut coefficients, self.reconstruct_limit() + 1);
         coefficients
     }
 
+    /// Plan for transforms of length `secret_count + threshold + 1`.
+    fn secrets_plan(&self) -> Arc<FftPlan<F>> {
+        let len = self.reconstruct_limit() + 1;
+        let build = || Arc::new(FftPlan::new(&self.field, len, &self.omega_secrets));
+        self.cached_plan(&self.plans.secrets, len, &self.omega_secrets, build)
+    }
+
+    /// Plan for transforms of length `share_count + 1`.
+    fn shares_plan(&self) -> Arc<FftPlan<F>> {
+        let len = self.share_count + 1;
+        let build = || {
+            if ::numtheory::fft::is_mixed_radix_len(len) {
+                return Arc::new(FftPlan::new(&self.field, len, &self.omega_shares));
+            }
+            let conv_omega = self.omega_convolution.as_ref().expect(
+                "omega_convolution is needed when share_count + 1 is not of the form 2^a.3^b",
+            );
+            let conv_len = ::numtheory::fft::mixed_radix_order(&self.field, conv_omega, u32::MAX)
+                .expect("omega_convolution must have order of the form 2^a.3^b");
+            Arc::new(FftPlan::with_bluestein(
+                &self.field,
+                len,
+                &self.omega_shares,
+                conv_omega,
+                conv_len,
+            ))
+        };
+        self.cached_plan(&self.plans.shares, len, &self.omega_shares, build)
+    }
+
+    /// Plan from `cache` if it was built for `len` and `omega`, which may have
+    /// changed since, and otherwise from `build`, in which case the new plan
+    /// replaces the cached one.
+    fn cached_plan<B>(
+        &self,
+        cache: &Mutex<Option<Arc<FftPlan<F>>>>,
+        len: usize,
+        omega: &F::E,
+        build: B,
+    ) -> Arc<FftPlan<F>>
+    where
+        B: Fn() -> Arc<FftPlan<F>>,
+    {
+        let mut cached = cache.lock().unwrap();
+        if let Some(ref plan) = *cached {
+            if plan.len() == len && self.field.eq(plan.omega(), omega) {
+                return plan.clone();
+            }
+        }
+        let plan = build();
+        *cached = Some(plan.clone());
+        plan
+    }
+
     /// Reconstruct the secrets from a large enough subset of the shares.
     ///
     /// `indices` are the ranks of the known shares as output by the `share` method,
@@ -163,87 +714,467 @@ where
     ///
     /// The resulting vector is of length `secret_count`.
     pub fn reconstruct(&self, indices: &[u32], shares: &[F::E]) -> Vec<F::E> {
+        #[cfg(feature = "observer")]
+        self.notify_reconstruct(indices);
+        self.reconstruct_points(
+            ReconstructStrategy::Auto,
+            indices,
+            shares,
+            &self.secret_exponents(),
+        )
+    }
+
+    /// Reconstruct the secrets like `reconstruct`, decoding them from the field
+    /// into plain values.
+    pub fn reconstruct_decoded<U>(&self, indices: &[u32], shares: &[F::E]) -> Vec<U>
+    where
+        F: Decode<U>,
+    {
+        self.field.decode_slice(self.reconstruct(indices, shares))
+    }
+
+    /// Reconstruct the secrets like `reconstruct`, using the given interpolation
+    /// `strategy` instead of the one chosen by the cost model.
+    ///
+    /// All strategies give the same result, only their running times differ.
+    pub fn reconstruct_with(
+        &self,
+        strategy: ReconstructStrategy,
+        indices: &[u32],
+        shares: &[F::E],
+    ) -> Vec<F::E> {
+        #[cfg(feature = "observer")]
+        self.notify_reconstruct(indices);
+        self.reconstruct_points(strategy, indices, shares, &self.secret_exponents())
+    }
+
+    /// Reconstruct the secrets like `reconstruct`, after checking that `indices` are
+    /// distinct ranks of shares and that there are at least `reconstruct_limit`.
+    ///
+    /// With invalid indices, `reconstruct` instead returns meaningless secrets or
+    /// panics while interpolating.
+    pub fn try_reconstruct(
+        &self,
+        indices: &[u32],
+        shares: &[F::E],
+    ) -> Result<Vec<F::E>, IndexError> {
+        check_lengths(indices, shares)?;
+        check_indices(
+            self.share_count,
+            self.reconstruct_limit(),
+            indices.iter().map(|&i| i as usize),
+        )?;
+        Ok(self.reconstruct(indices, shares))
+    }
+
+    /// Reconstruct the secrets like `try_reconstruct`, accepting shares given several
+    /// times with the same value, such as when collected through several relays.
+    ///
+    /// Shares given several times with different values are rejected with
+    /// `IndexError::ConflictingShare`.
+    pub fn reconstruct_deduplicated(
+        &self,
+        indices: &[u32],
+        shares: &[F::E],
+    ) -> Result<Vec<F::E>, IndexError> {
+        let indices: Vec<usize> = indices.iter().map(|&i| i as usize).collect();
+        let (indices, shares) = deduplicate_shares(
+            &self.field,
+            self.share_count,
+            self.reconstruct_limit(),
+            &indices,
+            shares,
+        )?;
+        let indices: Vec<u32> = indices.into_iter().map(index_u32).collect();
+        Ok(self.reconstruct(&indices, &shares))
+    }
+
+    /// Reconstruct the `secret_count` secrets embedded according to `layout` rather
+    /// than the scheme's own, in the order of the layout.
+    ///
+    /// This recovers the secrets of sharings whose secrets are interleaved with the
+    /// randomness differently, such as sums with the shares of another scheme, without
+    /// reconstructing and filtering all positions as with `fully_reconstruct`.
+    /// The exponents of `layout` must be distinct and at most `reconstruct_limit`.
+    pub fn reconstruct_layout(
+        &self,
+        indices: &[u32],
+        shares: &[F::E],
+        layout: &SecretLayout,
+    ) -> Vec<F::E> {
+        assert!(
+            layout.is_valid(self.secret_count, self.reconstruct_limit()),
+            "layout exponents must be distinct and at most reconstruct_limit"
+        );
+        #[cfg(feature = "observer")]
+        self.notify_reconstruct(indices);
+        self.reconstruct_points(
+            ReconstructStrategy::Auto,
+            indices,
+            shares,
+            &layout.exponents(self.secret_count),
+        )
+    }
+
+    /// Reconstruct the secrets like `reconstruct`, reusing the interpolation constants
+    /// for `indices` from `cache` when available.
+    ///
+    /// Once cached, reconstruction takes time `O(secret_count.k)` for `k` shares,
+    /// which is faster than `reconstruct` for small committees that repeat.
+    pub fn reconstruct_cached(
+        &self,
+        cache: &InterpolationCache<F>,
+        indices: &[u32],
+        shares: &[F::E],
+    ) -> Vec<F::E>
+    where
+        F: ElementBytes,
+    {
         assert!(shares.len() == indices.len());
         assert!(shares.len() >= self.reconstruct_limit());
-        if shares.len() == self.share_count {
-            // we're in the special case where we can use the FFTs for interpolation
-            let mut values = shares.to_vec();
-            values.insert(0, self.field.zero());
-            ::numtheory::fft::fft3_inverse(&self.field, &mut values, &self.omega_shares);
-            let mut coefficients = values
-                .into_iter()
-                .take(self.reconstruct_limit() + 1)
-                .collect::<Vec<_>>();
-            ::numtheory::fft::fft2(&self.field, &mut coefficients, &self.omega_secrets);
-            coefficients
-                .into_iter()
-                .skip(1)
-                .take(self.secret_count)
-                .collect()
-            // TODO replace with truncate
-        } else {
-            // we cannot use the FFT so default to Newton interpolation
-            let mut points: Vec<F::E> = indices
+        #[cfg(feature = "observer")]
+        self.notify_reconstruct(indices);
+        let field = &self.field;
+        let indices: Vec<ShareIndex> = indices.iter().map(|&i| i as ShareIndex).collect();
+        cache.interpolate(self.fingerprint(), &indices, shares, field, |sorted| {
+            // the polynomial is also known to be zero at `omega_shares^0`, so this
+            // point is interpolated from as well but its constant is then dropped
+            let positions = self.share_positions();
+            let mut points = vec![field.one()];
+            points.extend(sorted.iter().map(|&i| positions[i].clone()));
+            let weights = BarycentricWeights::compute(&points, field);
+            self.secret_positions()
                 .iter()
-                .map(|x| self.field.pow(&self.omega_shares, x + 1))
-                .collect();
-            let mut values = shares.to_vec();
-            // insert missing value for point 1 (zero)
-            points.insert(0, self.field.one());
-            values.insert(0, self.field.zero());
-            // interpolate using Newton's method
-            // TODO optimise by using Newton-equally-space variant
-            let poly = ::numtheory::NewtonPolynomial::compute(&points, &values, &self.field);
-            // evaluate at omega_secrets points to recover secrets
-            // TODO optimise to avoid re-computation of power
-            (1..self.reconstruct_limit())
-                .map(|e| self.field.pow(&self.omega_secrets, e as u32))
-                .map(|point| poly.evaluate(&point, &self.field))
-                .take(self.secret_count)
+                .map(|point| weights.lagrange_constants(point, field).without_first())
                 .collect()
-        }
+        })
     }
 
-    #[cfg(feature = "safety_override")]
-    pub fn fully_reconstruct(&self, indices: &[u32], shares: &[F::E]) -> Vec<F::E> {
-        // TODO unify code with `reconstruct` (only difference is how much is removed at end)
+    /// Reconstruct the secrets from a large enough collection of indexed shares.
+    pub fn reconstruct_indexed<'a, I>(&self, shares: I) -> Vec<F::E>
+    where
+        I: IntoIterator<Item = &'a Share<F>>,
+        F: 'a,
+    {
+        let (indices, values) = Share::unzip(shares);
+        let indices: Vec<u32> = indices.into_iter().map(index_u32).collect();
+        self.reconstruct(&indices, &values)
+    }
 
+    /// Values of the sharing polynomial at the powers of `omega_secrets` with the
+    /// given `exponents`, which must be between `1` and `reconstruct_limit`.
+    fn reconstruct_points(
+        &self,
+        strategy: ReconstructStrategy,
+        indices: &[u32],
+        shares: &[F::E],
+        exponents: &[usize],
+    ) -> Vec<F::E> {
         assert!(shares.len() == indices.len());
         assert!(shares.len() >= self.reconstruct_limit());
-        if shares.len() == self.share_count {
-            // we're in the special case where we can use the FFTs for interpolation
-            let mut values = shares.to_vec();
-            values.insert(0, self.field.zero());
-            ::numtheory::fft::fft3_inverse(&self.field, &mut values, &self.omega_shares);
-            let mut coefficients = values
-                .into_iter()
-                .take(self.reconstruct_limit() + 1)
-                .collect::<Vec<_>>();
-            ::numtheory::fft::fft2(&self.field, &mut coefficients, &self.omega_secrets);
-            let mut secrets = coefficients;
-            secrets.remove(0);
-            secrets
+        let strategy = match strategy {
+            ReconstructStrategy::Auto => self.reconstruct_strategy(shares.len()),
+            strategy => strategy,
+        };
+        // values at all secret points skipping the zeroth, if computed using the FFT
+        let values = match strategy {
+            ReconstructStrategy::FftErasure => {
+                // recover any missing shares first and then use the FFTs
+                let values = self.erasure_decode(indices, shares);
+                self.interpolate_codeword(values)
+            }
+            ReconstructStrategy::SubproductTree => self.interpolate_secrets(indices, shares),
+            ReconstructStrategy::Barycentric => {
+                let field = &self.field;
+                let mut points = vec![field.one()];
+                points.extend(indices.iter().map(|x| field.pow(&self.omega_shares, x + 1)));
+                let mut values = vec![field.zero()];
+                values.extend_from_slice(shares);
+                let weights = BarycentricWeights::compute(&points, field);
+                return self
+                    .exponent_powers(exponents)
+                    .iter()
+                    .map(|point| weights.interpolate(point, &values, field))
+                    .collect();
+            }
+            ReconstructStrategy::Auto => unreachable!(),
+            ReconstructStrategy::Newton => {
+                let poly = self.newton_interpolate(indices, shares);
+                // evaluate at omega_secrets points to recover secrets
+                return self
+                    .exponent_powers(exponents)
+                    .iter()
+                    .map(|point| poly.evaluate(point, &self.field))
+                    .collect();
+            }
+        };
+        exponents.iter().map(|&e| values[e - 1].clone()).collect()
+    }
+
+    /// Check whether the shares are consistent with a sharing polynomial of the
+    /// correct degree.
+    ///
+    /// Indices that are out of range or given more than once, or fewer than
+    /// `reconstruct_limit` shares, are reported as `VerifyError::Index`.
+    ///
+    /// The polynomial is interpolated from the first `reconstruct_limit` shares, and
+    /// the indices of all remaining shares not lying on it are returned as
+    /// `VerifyError::Inconsistent`. Note that this only detects inconsistencies: if
+    /// one of the first shares is wrong then typically all remaining ones are reported.
+    pub fn verify_shares(&self, indices: &[u32], shares: &[F::E]) -> Result<(), VerifyError> {
+        check_lengths(indices, shares)?;
+        let limit = self.reconstruct_limit();
+        check_indices(self.share_count, limit, indices.iter().map(|&i| i as usize))?;
+        let poly = self.newton_interpolate(&indices[..limit], &shares[..limit]);
+        let inconsistent: Vec<u32> = indices[limit..]
+            .iter()
+            .zip(&shares[limit..])
+            .filter(|&(&index, share)| {
+                let point = self.field.pow(&self.omega_shares, index + 1);
+                self.field.neq(poly.evaluate(&point, &self.field), share)
+            })
+            .map(|(&index, _)| index)
+            .collect();
+        if inconsistent.is_empty() {
+            Ok(())
         } else {
-            // we cannot use the FFT so default to Newton interpolation
-            let mut points: Vec<F::E> = indices
-                .iter()
-                .map(|x| self.field.pow(&self.omega_shares, x + 1))
-                .collect();
+            Err(VerifyError::Inconsistent(inconsistent))
+        }
+    }
+
+    /// Interpolate from the values at all powers of `omega_shares`, and evaluate the
+    /// resulting polynomial at all secret points (including randomness).
+    fn interpolate_codeword(&self, mut values: Vec<F::E>) -> Vec<F::E> {
+        self.shares_plan().inverse(&mut values);
+        self.evaluate_secrets(&values)
+    }
+
+    /// Evaluate the polynomial given by `coefficients` at all secret points
+    /// (including randomness).
+    fn evaluate_secrets(&self, coefficients: &[F::E]) -> Vec<F::E> {
+        // reduce modulo `x^m - 1`, as the degree may be larger than that of the
+        // sharing polynomial, for instance after multiplying shares
+        let field = &self.field;
+        let secret_points = self.reconstruct_limit() + 1;
+        let mut folded = vec![field.zero(); secret_points];
+        for (i, c) in coefficients.iter().enumerate() {
+            let x = &mut folded[i % secret_points];
+            *x = field.add(&*x, c);
+        }
+        self.secrets_plan().forward(&mut folded);
+        folded.remove(0);
+        folded
+    }
+
+    /// Recover the values at all powers of `omega_shares` from a subset of them.
+    fn erasure_decode(&self, indices: &[u32], shares: &[F::E]) -> Vec<F::E> {
+        let field = &self.field;
+        let share_points = self.share_count + 1;
+        let mut values = vec![field.zero(); share_points];
+        let mut known = vec![false; share_points];
+        // the value at point 1 is zero
+        known[0] = true;
+        for (&index, share) in indices.iter().zip(shares) {
+            values[index as usize + 1] = share.clone();
+            known[index as usize + 1] = true;
+        }
+        let missing: Vec<usize> = (0..share_points).filter(|&i| !known[i]).collect();
+        if missing.is_empty() {
+            return values;
+        }
+
+        let missing_points: Vec<F::E> = missing
+            .iter()
+            .map(|&i| field.pow(&self.omega_shares, i as u32))
+            .collect();
+        let locator = self.subproduct_tree(&missing_points).product().to_vec();
+        self.shares_plan()
+            .recover_erasures(&mut values, &missing, &locator);
+        values
+    }
+
+    /// Subproduct tree for powers of `omega_shares`, using FFT multiplication if possible.
+    fn subproduct_tree(&self, points: &[F::E]) -> ::numtheory::SubproductTree<F> {
+        let share_points = self.share_count + 1;
+        if ::numtheory::fft::is_mixed_radix_len(share_points) {
+            ::numtheory::SubproductTree::with_root_of_unity(
+                points,
+                &self.omega_shares,
+                share_points,
+                &self.field,
+            )
+        } else {
+            ::numtheory::SubproductTree::new(points, &self.field)
+        }
+    }
+
+    /// Interpolate from the shares using Newton's method.
+    fn newton_interpolate(&self, indices: &[u32], shares: &[F::E]) -> NewtonPolynomial<F> {
+        let field = &self.field;
+        // share `i` is positioned at `omega_shares^(i + 1)`, and the value at
+        // `omega_shares^0 = omega_shares^(share_count + 1)` is zero
+        let positions: Vec<u32> = indices.iter().map(|x| x + 1).collect();
+        let consecutive = positions.windows(2).all(|pair| pair[1] == pair[0] + 1);
+        if consecutive && positions.first() == Some(&1) {
             let mut values = shares.to_vec();
-            // insert missing value for point 1 (zero)
-            points.insert(0, self.field.one());
-            values.insert(0, self.field.zero());
-            // interpolate using Newton's method
-            // TODO optimise by using Newton-equally-space variant
-            let poly = ::numtheory::NewtonPolynomial::compute(&points, &values, &self.field);
-            // evaluate at omega_secrets points to recover secrets
-            // TODO optimise to avoid re-computation of power
-            let secrets = (1..self.reconstruct_limit())
-                .map(|e| self.field.pow(&self.omega_secrets, e as u32))
-                .map(|point| poly.evaluate(point, &self.field))
-                .collect();
-            secrets
+            values.insert(0, field.zero());
+            return NewtonPolynomial::compute_geometric(
+                &field.one(),
+                &self.omega_shares,
+                &values,
+                field,
+            );
+        }
+        if consecutive && positions.last() == Some(&(self.share_count as u32)) {
+            let mut values = shares.to_vec();
+            values.push(field.zero());
+            let first = field.pow(&self.omega_shares, positions[0]);
+            return NewtonPolynomial::compute_geometric(&first, &self.omega_shares, &values, field);
+        }
+
+        let mut points: Vec<F::E> = positions
+            .iter()
+            .map(|&x| field.pow(&self.omega_shares, x))
+            .collect();
+        let mut values = shares.to_vec();
+        // insert missing value for point 1 (zero)
+        points.insert(0, field.one());
+        values.insert(0, field.zero());
+        NewtonPolynomial::compute(&points, &values, field)
+    }
+
+    /// Interpolate from the shares using a subproduct tree, and evaluate the resulting
+    /// polynomial at all secret points (including randomness).
+    fn interpolate_secrets(&self, indices: &[u32], shares: &[F::E]) -> Vec<F::E> {
+        let field = &self.field;
+        let mut points: Vec<F::E> = indices
+            .iter()
+            .map(|x| field.pow(&self.omega_shares, x + 1))
+            .collect();
+        let mut values = shares.to_vec();
+        // insert missing value for point 1 (zero)
+        points.insert(0, field.one());
+        values.insert(0, field.zero());
+
+        let tree = self.subproduct_tree(&points);
+
+        // since all points are powers of omega_shares the derivative of their
+        // vanishing polynomial can be evaluated at all of them with one FFT
+        let derivative_values = self.shares_plan().evaluate_derivative(tree.product());
+        let derivative_values: Vec<F::E> = ::std::iter::once(0)
+            .chain(indices.iter().map(|&x| x as usize + 1))
+            .map(|position| derivative_values[position].clone())
+            .collect();
+        let poly = tree.interpolate_with_derivative(&values, &derivative_values, field);
+        self.evaluate_secrets(&poly)
+    }
+
+    #[cfg(feature = "safety_override")]
+    pub fn fully_reconstruct(&self, indices: &[u32], shares: &[F::E]) -> Vec<F::E> {
+        let exponents: Vec<usize> = (1..self.reconstruct_limit() + 1).collect();
+        self.reconstruct_points(ReconstructStrategy::Auto, indices, shares, &exponents)
+    }
+
+    /// Values at all powers of `omega_secrets` like `fully_reconstruct`, from all
+    /// `share_count` shares in order as for `reconstruct_owned`.
+    #[cfg(feature = "safety_override")]
+    pub fn fully_reconstruct_owned(&self, shares: Vec<F::E>) -> Vec<F::E> {
+        let mut values = self.interpolate_owned_codeword(shares);
+        // the last value is the zero at `omega_secrets^0`
+        values.pop();
+        values
+    }
+
+    /// Reconstruct the secrets from all `share_count` shares in the order output by
+    /// `share`, taking ownership of them to interpolate in place.
+    ///
+    /// When `share_count + 1` is of the form `2^a.3^b` the transforms need no memory
+    /// besides the shares and the FFT plans, roughly halving the peak memory of
+    /// `reconstruct` for the largest schemes. Other lengths use Bluestein's
+    /// algorithm, whose scratch space of at least twice the number of shares
+    /// outweighs the saving. A single element is appended to `shares`, which does
+    /// not reallocate if they were output by `share`.
+    pub fn reconstruct_owned(&self, shares: Vec<F::E>) -> Vec<F::E> {
+        let values = self.interpolate_owned_codeword(shares);
+        (0..self.secret_count)
+            .map(|i| values[self.layout.exponent(i) - 1].clone())
+            .collect()
+    }
+
+    /// Reconstruct the secrets like `reconstruct_owned`, collecting the shares from
+    /// `shares` directly into the buffer interpolated in.
+    pub fn reconstruct_from_iter<I>(&self, shares: I) -> Vec<F::E>
+    where
+        I: IntoIterator<Item = F::E>,
+    {
+        let mut values = Vec::with_capacity(self.share_count + 1);
+        values.extend(shares);
+        self.reconstruct_owned(values)
+    }
+
+    /// Values at the powers `1..=m` of `omega_secrets` from all shares in order, the
+    /// last of which is the value at point 1.
+    ///
+    /// Rather than inserting the zero at point 1 in front of the shares, which
+    /// shifts all of them, it is appended: the values are then those of `Q(x) =
+    /// P(omega_shares.x)` at all powers of `omega_shares`, and the coefficients of
+    /// `P` are those of `Q` multiplied by powers of `omega_shares^-1`. The
+    /// coefficients are likewise multiplied by powers of `omega_secrets`, so that
+    /// the values come out shifted by one power rather than starting at point 1.
+    fn interpolate_owned_codeword(&self, mut values: Vec<F::E>) -> Vec<F::E> {
+        assert_eq!(values.len(), self.share_count);
+        #[cfg(feature = "observer")]
+        notify(|observer| {
+            let indices: Vec<usize> = (0..self.share_count).collect();
+            observer.on_reconstruct(&self.observer_info(), &indices);
+        });
+        let field = &self.field;
+        values.push(field.zero());
+        self.shares_plan().inverse(&mut values);
+        let step = field.mul(field.inv(&self.omega_shares), &self.omega_secrets);
+        let mut twist = field.one();
+        for coefficient in values.iter_mut() {
+            *coefficient = field.mul(&*coefficient, &twist);
+            twist = field.mul(twist, &step);
         }
+
+        // reduce modulo `x^m - 1` in place, as done by `evaluate_secrets`
+        let secret_points = self.reconstruct_limit() + 1;
+        for i in secret_points..values.len() {
+            let coefficient = values[i].clone();
+            let folded = &mut values[i % secret_points];
+            *folded = field.add(&*folded, coefficient);
+        }
+        values.truncate(secret_points);
+        self.secrets_plan().forward(&mut values);
+        values
+    }
+}
+
+impl<F> ThresholdScheme for PackedSecretSharing<F>
+where
+    F: Field,
+    F: Encode<u32>,
+    F: Clone,
+    F::E: Clone,
+{
+    type Field = F;
+    type Secret = Vec<F::E>;
+
+    fn share_count(&self) -> usize {
+        self.share_count
+    }
+
+    fn reconstruct_limit(&self) -> usize {
+        self.threshold + self.secret_count
+    }
+
+    fn share(&self, secrets: &Vec<F::E>) -> Vec<Share<F>> {
+        self.share_indexed(secrets)
+    }
+
+    fn reconstruct(&self, shares: &[Share<F>]) -> Vec<F::E> {
+        self.reconstruct_indexed(shares)
     }
 }
 
@@ -260,6 +1191,9 @@ mod instances {
         field: NaturalPrimeField(433), // TODO
         omega_secrets: 354,
         omega_shares: 150,
+        omega_convolution: None,
+        layout: SecretLayout::Consecutive,
+        plans: FftPlans::new(),
     };
 
     /// Example of small PSS settings, for sharing 3 secrets into 26 shares, with
@@ -271,6 +1205,9 @@ mod instances {
         field: NaturalPrimeField(433), // TODO
         omega_secrets: 354,
         omega_shares: 17,
+        omega_convolution: None,
+        layout: SecretLayout::Consecutive,
+        plans: FftPlans::new(),
     };
 
     /// Example of PSS settings, for sharing 100 secrets into 728 shares, with
@@ -282,6 +1219,9 @@ mod instances {
         field: NaturalPrimeField(746497), // TODO
         omega_secrets: 95660,
         omega_shares: 610121,
+        omega_convolution: None,
+        layout: SecretLayout::Consecutive,
+        plans: FftPlans::new(),
     };
 
     /// Example of PSS settings, for sharing 100 secrets into 19682 shares, with
@@ -294,10 +1234,30 @@ mod instances {
             field: NaturalPrimeField(5038849), // TODO
             omega_secrets: 4318906,
             omega_shares: 1814687,
+            omega_convolution: None,
+            layout: SecretLayout::Consecutive,
+            plans: FftPlans::new(),
         };
 }
 pub use self::instances::*;
 
+mod presets;
+pub use self::presets::*;
+
+mod buffers;
+pub use self::buffers::PackedSharingContext;
+mod chunked;
+pub use self::chunked::ChunkError;
+mod const_generic;
+pub use self::const_generic::PackedSecretSharingConst;
+mod convert;
+mod layout;
+pub use self::layout::SecretLayout;
+mod strategy;
+pub use self::strategy::ReconstructStrategy;
+mod validate;
+pub use self::validate::ParamError;
+
 #[cfg(test)]
 mod tests {
 
@@ -311,14 +1271,14 @@ mod tests {
         F::E: Clone,
     {
         let field = F::new(433);
-        let pss = PackedSecretSharing {
-            threshold: 4,
-            share_count: 8,
-            secret_count: 3,
-            omega_secrets: field.encode(354),
-            omega_shares: field.encode(150),
-            field: field.clone(),
-        };
+        let pss = PackedSecretSharing::with_roots(
+            4,
+            3,
+            8,
+            field.clone(),
+            field.encode(354),
+            field.encode(150),
+        );
 
         let secrets = vec![1, 2, 3];
         let randomness = vec![8, 8, 8, 8]; // use fixed randomness
@@ -335,14 +1295,7 @@ mod tests {
     where F: PrimeField + New<u32> + Encode<u32> + Decode<u32> + Clone, F::P: From<u32>, F::E: Clone
     {
         let field = F::new(433);
-        let pss = PackedSecretSharing {
-            threshold: 4,
-            share_count: 26,
-            secret_count: 3,
-            omega_secrets: field.encode(354),
-            omega_shares: field.encode(17),
-            field: field.clone(),
-        };
+        let pss = PackedSecretSharing::with_roots(4, 3, 26, field.clone(), field.encode(354), field.encode(17));
         
         let poly = field.encode_slice([113,  51, 261, 267, 108, 432, 388, 112,   0,
                                          0,   0,   0,   0,   0,   0,   0,   0,   0,
@@ -355,6 +1308,79 @@ mod tests {
                51, 60, 305, 395,  84, 156, 160, 112, 422]
         );
     }
+
+    pub fn test_share_reconstruct_swapped<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32> + Clone,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        // secrets on 9th roots of unity, shares on 16th roots of unity
+        let field = F::new(433);
+        let pss = PackedSecretSharing::with_roots(
+            4,
+            4,
+            15,
+            field.clone(),
+            field.encode(150),
+            field.encode(151),
+        );
+
+        let secrets = field.encode_slice([1, 2, 3, 4]);
+        let shares = pss.share(&secrets);
+        assert_eq!(shares.len(), 15);
+
+        let indices: Vec<u32> = (0..15).collect();
+        let recovered = pss.reconstruct(&indices, &shares);
+        assert_eq!(field.decode_slice(recovered), [1, 2, 3, 4]);
+
+        let indices: Vec<u32> = (3..11).collect();
+        let recovered = pss.reconstruct(&indices, &shares[3..11]);
+        assert_eq!(field.decode_slice(recovered), [1, 2, 3, 4]);
+    }
+
+    pub fn test_share_reconstruct_bluestein<F>()
+    where
+        F: PrimeField + New<u32> + Encode<u32> + Decode<u32> + Clone,
+        F::P: From<u32>,
+        F::E: Clone,
+    {
+        // secrets on 8th roots of unity, shares on 11th roots of unity,
+        // and convolutions on 24th roots of unity
+        let field = F::new(1321);
+        let pss = PackedSecretSharing::with_roots(
+            4,
+            3,
+            10,
+            field.clone(),
+            field.encode(235),
+            field.encode(58),
+        )
+        .with_convolution(field.encode(17));
+
+        let secrets = field.encode_slice([5, 6, 7]);
+        let shares = pss.share(&secrets);
+        assert_eq!(shares.len(), 10);
+
+        let indices: Vec<u32> = (0..10).collect();
+        let recovered = pss.reconstruct(&indices, &shares);
+        assert_eq!(field.decode_slice(recovered), [5, 6, 7]);
+
+        let indices: Vec<u32> = (2..9).collect();
+        let recovered = pss.reconstruct(&indices, &shares[2..9]);
+        assert_eq!(field.decode_slice(recovered), [5, 6, 7]);
+
+        for &strategy in &ReconstructStrategy::EXPLICIT {
+            let recovered = pss.reconstruct_with(strategy, &indices, &shares[2..9]);
+            assert_eq!(field.decode_slice(recovered), [5, 6, 7]);
+        }
+
+        // another 24th root of unity replaces the plans built for the first
+        let other = pss.clone().with_convolution(field.encode(1103));
+        assert!(field.eq(other.omega_convolution().unwrap(), field.encode(1103)));
+        let recovered = other.reconstruct(&indices, &other.share(&secrets)[2..9]);
+        assert_eq!(field.decode_slice(recovered), [5, 6, 7]);
+    }
 }
 
 #[allow(unused_macros)]
@@ -368,6 +1394,14 @@ macro_rules! all_packed_tests {
         fn test_evaluate_polynomial() {
             ::packed::tests::test_evaluate_polynomial::<$field>();
         }
+        #[test]
+        fn test_share_reconstruct_swapped() {
+            ::packed::tests::test_share_reconstruct_swapped::<$field>();
+        }
+        #[test]
+        fn test_share_reconstruct_bluestein() {
+            ::packed::tests::test_share_reconstruct_bluestein::<$field>();
+        }
     };
 }
 
@@ -393,8 +1427,8 @@ mod old_tests {
     #[test]
     #[rustfmt::skip]
     fn test_share() {
-        let ref pss = PSS_4_26_3;
-        let ref field = pss.field;
+        let pss = &PSS_4_26_3;
+        let field = &pss.field;
 
         // do sharing
         let secrets = field.encode_slice([5, 6, 7]);
@@ -403,7 +1437,7 @@ mod old_tests {
         // manually recover secrets
         use numtheory::mod_evaluate_polynomial;
         shares.insert(0, 0);
-        ::numtheory::fft::fft3_inverse(field, &mut *shares, &pss.omega_shares);
+        ::numtheory::fft::fft3_inverse(field, &mut shares, &pss.omega_shares);
         let poly = shares;
         let recovered_secrets: Vec<i64> = (1..secrets.len() + 1)
             .map(|i| pss.field.pow(field.encode(pss.omega_secrets as u32), i as u32))
@@ -418,7 +1452,7 @@ mod old_tests {
 
     #[test]
     fn test_large_share() {
-        let ref pss = PSS_155_19682_100;
+        let pss = &PSS_155_19682_100;
         let secrets = vec![5; pss.secret_count];
         let shares = pss.share(&secrets);
         assert_eq!(shares.len(), pss.share_count);
@@ -426,25 +1460,481 @@ mod old_tests {
 
     #[test]
     fn test_share_reconstruct() {
-        let ref pss = PSS_4_26_3;
+        let pss = &PSS_4_26_3;
         let secrets = vec![5, 6, 7];
-        let shares = pss.share(&pss.field.encode_slice(&secrets));
+        let shares = pss.share_values(&secrets);
 
         // reconstruction must work for all shares
         let indices: Vec<u32> = (0..shares.len() as u32).collect();
-        let recovered_secrets = pss.reconstruct(&indices, &shares);
-        assert_eq!(pss.field.decode_slice(recovered_secrets), secrets);
+        assert_eq!(pss.reconstruct_decoded::<u32>(&indices, &shares), secrets);
 
         // .. and for only sufficient shares
         let indices: Vec<u32> = (0..pss.reconstruct_limit() as u32).collect();
-        let recovered_secrets = pss.reconstruct(&indices, &shares[0..pss.reconstruct_limit()]);
-        print!("lenght is {:?}", indices.len());
+        let shares = &shares[0..pss.reconstruct_limit()];
+        assert_eq!(pss.reconstruct_decoded::<u32>(&indices, shares), secrets);
+    }
+
+    #[test]
+    fn test_share_values() {
+        let field = MontgomeryField32::new(433);
+        let pss = PackedSecretSharing::with_roots(
+            4,
+            3,
+            26,
+            field.clone(),
+            field.encode(354),
+            field.encode(17),
+        );
+        let shares = pss.share_values(&[428, 6, 426]);
+        let indices: Vec<u32> = (3..11).collect();
+        let values = &shares[3..11];
+        assert_eq!(
+            pss.reconstruct_decoded::<u32>(&indices, values),
+            [428, 6, 426]
+        );
+        let signed: Vec<i64> = pss
+            .reconstruct(&indices, values)
+            .iter()
+            .map(|secret| field.decode_signed(secret))
+            .collect();
+        assert_eq!(signed, [-5, 6, -7]);
+    }
+
+    #[test]
+    fn test_large_reconstruct() {
+        let pss = &PSS_155_728_100;
+        let secrets: Vec<u32> = (0..pss.secret_count as u32).collect();
+        let shares = pss.share(&pss.field.encode_slice(&secrets));
+
+        for &count in &[pss.reconstruct_limit(), 400, pss.share_count - 1] {
+            // take every other share first so that indices are not consecutive
+            let mut indices: Vec<u32> = (0..pss.share_count as u32)
+                .filter(|i| i % 2 == 1)
+                .chain((0..pss.share_count as u32).filter(|i| i % 2 == 0))
+                .take(count)
+                .collect();
+            indices.sort();
+            let values: Vec<i64> = indices.iter().map(|&i| shares[i as usize]).collect();
+            let recovered_secrets = pss.reconstruct(&indices, &values);
+            assert_eq!(pss.field.decode_slice(recovered_secrets), secrets);
+            let recovered_secrets =
+                pss.reconstruct_with(ReconstructStrategy::SubproductTree, &indices, &values);
+            assert_eq!(pss.field.decode_slice(recovered_secrets), secrets);
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_owned() {
+        let pss = &PSS_155_19682_100;
+        let secrets: Vec<u32> = (0..pss.secret_count as u32).collect();
+        let shares = pss.share(&pss.field.encode_slice(&secrets));
+        // shares output by `share` have room for the zero appended
+        assert!(shares.capacity() > shares.len());
+        let recovered_secrets = pss.reconstruct_from_iter(shares.iter().cloned());
+        assert_eq!(pss.field.decode_slice(recovered_secrets), secrets);
+        let recovered_secrets = pss.reconstruct_owned(shares);
+        assert_eq!(pss.field.decode_slice(recovered_secrets), secrets);
+
+        // with another layout, and Bluestein's algorithm for the 11 share points
+        let pss = PackedSecretSharing::with_roots(4, 3, 10, NaturalPrimeField(1321), 235, 58)
+            .with_convolution(17)
+            .with_layout(SecretLayout::Custom(vec![6, 2, 5]))
+            .unwrap();
+        let secrets = vec![5, 6, 7];
+        assert_eq!(pss.reconstruct_owned(pss.share(&secrets)), secrets);
+    }
+
+    #[cfg(feature = "safety_override")]
+    #[test]
+    fn test_fully_reconstruct_owned() {
+        let pss = &PSS_4_26_3;
+        let values = pss.field.encode_slice([5, 6, 7, 1, 2, 3, 4]);
+        let shares = pss.deterministic_share(&values);
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+        assert_eq!(pss.fully_reconstruct(&indices, &shares), values);
+        assert_eq!(pss.fully_reconstruct_owned(shares), values);
+    }
+
+    #[test]
+    fn test_reconstruct_erasures() {
+        let pss = &PSS_4_26_3;
+        let secrets = vec![5, 6, 7];
+        let shares = pss.share(&pss.field.encode_slice(&secrets));
+
+        for count in pss.share_count / 2..pss.share_count {
+            // drop shares spread over the whole range
+            let mut indices: Vec<u32> = (0..pss.share_count as u32)
+                .filter(|i| i % 3 == 2)
+                .chain((0..pss.share_count as u32).filter(|i| i % 3 != 2))
+                .skip(pss.share_count - count)
+                .collect();
+            indices.sort();
+            let values: Vec<i64> = indices.iter().map(|&i| shares[i as usize]).collect();
+            let recovered_secrets =
+                pss.reconstruct_with(ReconstructStrategy::FftErasure, &indices, &values);
+            assert_eq!(pss.field.decode_slice(recovered_secrets), secrets);
+        }
+    }
+
+    #[test]
+    fn test_verify_shares() {
+        let pss = &PSS_4_26_3;
+        let mut shares = pss.share(&pss.field.encode_slice([5, 6, 7]));
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+        assert_eq!(pss.verify_shares(&indices, &shares), Ok(()));
+        assert_eq!(
+            pss.verify_shares(
+                &indices[..pss.reconstruct_limit()],
+                &shares[..pss.reconstruct_limit()]
+            ),
+            Ok(())
+        );
+
+        shares[10] = pss.field.add(shares[10], 1);
+        shares[20] = pss.field.add(shares[20], 5);
+        assert_eq!(
+            pss.verify_shares(&indices, &shares),
+            Err(VerifyError::Inconsistent(vec![10, 20]))
+        );
+
+        let mut invalid = indices.clone();
+        invalid[12] = u32::MAX;
+        assert_eq!(
+            pss.verify_shares(&invalid, &shares),
+            Err(VerifyError::Index(IndexError::UnknownShare(
+                u32::MAX as usize
+            )))
+        );
+        invalid[12] = 11;
+        assert_eq!(
+            pss.verify_shares(&invalid, &shares),
+            Err(VerifyError::Index(IndexError::DuplicateShare(11)))
+        );
+        assert_eq!(
+            pss.verify_shares(&indices[..3], &shares[..3]),
+            Err(VerifyError::Index(IndexError::TooFewShares(3)))
+        );
+    }
+
+    #[test]
+    fn test_replaced_plan() {
+        let mut pss = PSS_4_26_3.clone();
+        let secrets = pss.field.encode_slice([5, 6, 7]);
+        pss.share(&secrets);
+
+        // another 27th root of unity, whose plan replaces the cached one
+        pss.omega_shares = pss.field.mul(pss.omega_shares, pss.omega_shares);
+        let shares = pss.share(&secrets);
+        let plan = pss.plans.shares.lock().unwrap().clone().unwrap();
+        assert!(Field::eq(&pss.field, plan.omega(), pss.omega_shares));
+
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+        let recovered = pss.reconstruct(&indices, &shares);
+        assert_eq!(pss.field.decode_slice(recovered), [5, 6, 7]);
+    }
+
+    #[test]
+    fn test_positions() {
+        let pss = &PSS_4_26_3;
+        assert_eq!(pss.secret_positions(), [354, 179, 148]);
+        let share_positions = pss.share_positions();
+        assert_eq!(share_positions.len(), 26);
+        assert_eq!(share_positions[..3], [17, 289, 150]);
+        assert_eq!(pss.field.mul(share_positions[25], 17), 1);
+
+        // shares are the values of the sharing polynomial at these positions
+        let shares = pss.share(&[5, 6, 7]);
+        let poly = pss.newton_interpolate(&[0, 1, 2, 3, 4, 5, 6], &shares[..7]);
+        for (position, share) in share_positions.iter().zip(&shares) {
+            assert_eq!(poly.evaluate(position, &pss.field), *share);
+        }
+        let secrets: Vec<i64> = pss
+            .secret_positions()
+            .iter()
+            .map(|position| poly.evaluate(position, &pss.field))
+            .collect();
+        assert_eq!(secrets, [5, 6, 7]);
+
+        // without shares only the zero at `omega_shares^0` is known
+        let poly = pss.newton_interpolate(&[], &[]);
+        assert_eq!(poly.evaluate(share_positions[0], &pss.field), 0);
+    }
+
+    #[test]
+    fn test_share_returning_polynomial() {
+        let pss = &PSS_4_26_3;
+        let dealt = pss.share_returning_polynomial(&[5, 6, 7]);
+        assert_eq!(dealt.coefficients.len(), 8);
+        let evaluate = |point: &i64| {
+            ::numtheory::mod_evaluate_polynomial(&dealt.coefficients, *point, &pss.field)
+        };
+        assert_eq!(evaluate(&1), 0);
+        let secrets: Vec<i64> = pss.secret_positions().iter().map(&evaluate).collect();
+        assert_eq!(secrets, [5, 6, 7]);
+        let shares: Vec<i64> = pss.share_positions().iter().map(&evaluate).collect();
+        assert_eq!(shares, dealt.shares);
+    }
+
+    #[test]
+    fn test_share_iter() {
+        let pss = &PSS_4_26_3;
+        let seed = [7; 32];
+        let shares: Vec<(u32, i64)> = pss
+            .share_iter_with_rng(&[5, 6, 7], &mut seeded_rng(&seed))
+            .collect();
+        let expected = pss.share_with_rng(&[5, 6, 7], &mut seeded_rng(&seed));
+        assert_eq!(shares.len(), pss.share_count);
+        for (i, &(index, share)) in shares.iter().enumerate() {
+            assert_eq!(index as usize, i);
+            assert_eq!(share, expected[i]);
+        }
+
+        let (indices, values): (Vec<u32>, Vec<i64>) =
+            pss.share_iter(&[5, 6, 7]).skip(10).take(7).unzip();
+        assert_eq!(pss.reconstruct(&indices, &values), [5, 6, 7]);
+    }
+
+    #[test]
+    fn test_secret_layout() {
+        let pss = PSS_4_26_3
+            .clone()
+            .with_layout(SecretLayout::Custom(vec![6, 2, 3]))
+            .unwrap();
+        assert_eq!(pss.secret_positions(), [pss.field.pow(354, 6), 179, 148]);
+        let shares = pss.share(&[5, 6, 7]);
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+        // all of the FFT, erasure decoding and Newton paths
+        assert_eq!(pss.reconstruct(&indices, &shares), [5, 6, 7]);
+        assert_eq!(pss.reconstruct(&indices[5..], &shares[5..]), [5, 6, 7]);
+        assert_eq!(pss.reconstruct(&indices[5..12], &shares[5..12]), [5, 6, 7]);
+        let cache = InterpolationCache::new(1);
+        assert_eq!(
+            pss.reconstruct_cached(&cache, &indices[..7], &shares[..7]),
+            [5, 6, 7]
+        );
+
+        // secrets of `sparse` sit at the same points as those of `dense`, so the
+        // sum of their sharings is a sharing of the sums
+        let dense = PackedSecretSharing {
+            threshold: 1,
+            secret_count: 2,
+            omega_secrets: 179,
+            ..PSS_4_26_3.clone()
+        };
+        let sparse = PackedSecretSharing {
+            threshold: 5,
+            secret_count: 2,
+            layout: SecretLayout::Strided(2),
+            ..PSS_4_26_3.clone()
+        };
+        assert_eq!(dense.secret_positions(), sparse.secret_positions());
+        let sum = sparse.add_shares(&dense.share(&[1, 2]), &sparse.share(&[10, 20]));
+        assert_eq!(sparse.reconstruct(&indices, &sum), [11, 22]);
+        assert_eq!(sparse.reconstruct(&indices[..7], &sum[..7]), [11, 22]);
+
+        // only 7 points are available to the secrets and the randomness
+        for layout in [
+            SecretLayout::Strided(3),
+            SecretLayout::Custom(vec![1, 1, 2]),
+        ] {
+            assert_eq!(
+                PSS_4_26_3.clone().with_layout(layout),
+                Err(ParamError::InvalidLayout)
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_reconstruct() {
+        let pss = &PSS_4_26_3;
+        let shares = pss.share(&[5, 6, 7]);
+        let indices: Vec<u32> = vec![3, 8, 1, 20, 14, 25, 9];
+        let values: Vec<i64> = indices.iter().map(|&i| shares[i as usize]).collect();
+        assert_eq!(pss.try_reconstruct(&indices, &values), Ok(vec![5, 6, 7]));
+        assert_eq!(
+            pss.try_reconstruct(&[3, 8, 1, 26, 14, 25, 9], &values),
+            Err(IndexError::UnknownShare(26))
+        );
+        assert_eq!(
+            pss.try_reconstruct(&[3, 8, 1, 20, 14, 8, 9], &values),
+            Err(IndexError::DuplicateShare(8))
+        );
+        assert_eq!(
+            pss.try_reconstruct(&indices[..6], &values[..6]),
+            Err(IndexError::TooFewShares(6))
+        );
+        assert_eq!(
+            pss.try_reconstruct(&indices[..7], &values[..6]),
+            Err(IndexError::MismatchedLengths(7, 6))
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_deduplicated() {
+        let pss = &PSS_4_26_3;
+        let shares = pss.share(&[5, 6, 7]);
+        let indices: Vec<u32> = vec![3, 8, 3, 1, 20, 14, 25, 3, 9];
+        let mut values: Vec<i64> = indices.iter().map(|&i| shares[i as usize]).collect();
+        assert_eq!(
+            pss.reconstruct_deduplicated(&indices, &values),
+            Ok(vec![5, 6, 7])
+        );
+        values[7] += 1;
+        assert_eq!(
+            pss.reconstruct_deduplicated(&indices, &values),
+            Err(IndexError::ConflictingShare(3))
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_layout() {
+        let custom = PackedSecretSharing {
+            layout: SecretLayout::Custom(vec![6, 2, 3]),
+            ..PSS_4_26_3.clone()
+        };
+        let shares = custom.share(&[5, 6, 7]);
+        let indices: Vec<u32> = (0..custom.share_count as u32).collect();
+        let pss = &PSS_4_26_3;
+        assert_eq!(
+            pss.reconstruct_layout(&indices, &shares, &custom.layout),
+            [5, 6, 7]
+        );
+        assert_eq!(
+            pss.reconstruct_layout(&indices[4..11], &shares[4..11], &custom.layout),
+            [5, 6, 7]
+        );
+        // the order of the layout is kept
+        let reversed = SecretLayout::Custom(vec![3, 2, 6]);
+        assert_eq!(
+            pss.reconstruct_layout(&indices, &shares, &reversed),
+            [7, 6, 5]
+        );
+        let shares = pss.share(&[5, 6, 7]);
+        assert_eq!(
+            pss.reconstruct_layout(&indices, &shares, &SecretLayout::Consecutive),
+            [5, 6, 7]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "layout exponents must be distinct")]
+    fn test_reconstruct_layout_invalid() {
+        let pss = &PSS_4_26_3;
+        let shares = pss.share(&[5, 6, 7]);
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+        pss.reconstruct_layout(&indices, &shares, &SecretLayout::Strided(3));
+    }
+
+    #[test]
+    fn test_reconstruct_cached() {
+        let pss = &PSS_4_26_3;
+        let cache = InterpolationCache::new(4);
+        let shares = pss.share(&[5, 6, 7]);
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+        assert_eq!(
+            pss.reconstruct_cached(&cache, &indices[3..10], &shares[3..10]),
+            [5, 6, 7]
+        );
+        assert_eq!(pss.reconstruct_cached(&cache, &indices, &shares), [5, 6, 7]);
+        assert_eq!(cache.len(), 2);
+
+        let shares = pss.share(&[8, 9, 10]);
+        let mut committee: Vec<u32> = (3..10).rev().collect();
+        committee.swap(2, 5);
+        let committee_shares: Vec<i64> = committee.iter().map(|&i| shares[i as usize]).collect();
+        assert_eq!(
+            pss.reconstruct_cached(&cache, &committee, &committee_shares),
+            [8, 9, 10]
+        );
+        assert_eq!(cache.len(), 2);
+
+        // the same committee of a scheme with other secret points gets its own entry
+        let strided = PSS_4_26_3
+            .clone()
+            .with_layout(SecretLayout::Strided(2))
+            .unwrap();
+        let shares = strided.share(&[8, 9, 10]);
+        let committee_shares: Vec<i64> = committee.iter().map(|&i| shares[i as usize]).collect();
+        assert_eq!(
+            strided.reconstruct_cached(&cache, &committee, &committee_shares),
+            [8, 9, 10]
+        );
+    }
+
+    #[test]
+    fn test_share_batch() {
+        let pss = &PSS_4_26_3;
+        let chunks: Vec<Vec<i64>> = (0..5).map(|i| vec![i, i + 1, i + 2]).collect();
+        let chunk_refs: Vec<&[i64]> = chunks.iter().map(|c| &c[..]).collect();
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+
+        let batch = pss.share_batch(&chunk_refs);
+        assert_eq!(batch.len(), 5);
+        for (shares, secrets) in batch.iter().zip(&chunks) {
+            assert_eq!(pss.reconstruct(&indices[..7], &shares[..7]), *secrets);
+        }
+
+        #[cfg(feature = "parallel")]
+        for &thread_count in &[1, 2, 8] {
+            let batch = pss.share_batch_parallel(&chunk_refs, thread_count);
+            assert_eq!(batch.len(), 5);
+            for (shares, secrets) in batch.iter().zip(&chunks) {
+                assert_eq!(pss.reconstruct(&indices[19..], &shares[19..]), *secrets);
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_sharing() {
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([5, 6, 7]);
+        let seed = [3; 32];
+        let mut shares = pss.share_seeded(&secrets, &seed);
+        assert_eq!(pss.share_seeded(&secrets, &seed), shares);
+        let indices: Vec<u32> = (0..pss.share_count as u32).collect();
+        assert_eq!(
+            pss.field.decode_slice(pss.reconstruct(&indices, &shares)),
+            [5, 6, 7]
+        );
+        assert_eq!(pss.verify_sharing(&secrets, &seed, &shares), Ok(()));
+        assert!(pss.verify_sharing(&secrets, &[4; 32], &shares).is_err());
+
+        shares[10] = pss.field.add(shares[10], 1);
+        shares[20] = pss.field.add(shares[20], 5);
+        assert_eq!(
+            pss.verify_sharing(&secrets, &seed, &shares),
+            Err(vec![10, 20])
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_consecutive() {
+        let pss = &PSS_4_26_3;
+        let secrets = vec![5, 6, 7];
+        let shares = pss.share(&pss.field.encode_slice(&secrets));
+        let limit = pss.reconstruct_limit();
+
+        // runs at the start, at the end, and in the middle
+        for &start in &[0, pss.share_count - limit, 3] {
+            let indices: Vec<u32> = (start as u32..(start + limit) as u32).collect();
+            let recovered_secrets = pss.reconstruct(&indices, &shares[start..start + limit]);
+            assert_eq!(pss.field.decode_slice(recovered_secrets), secrets);
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_indexed() {
+        let pss = &PSS_4_26_3;
+        let secrets = vec![5, 6, 7];
+        let shares = pss.share_indexed(&pss.field.encode_slice(&secrets));
+        let limit = pss.reconstruct_limit();
+        let recovered_secrets = pss.reconstruct_indexed(shares.iter().rev().take(limit));
         assert_eq!(pss.field.decode_slice(recovered_secrets), secrets);
     }
 
     #[test]
     fn test_share_additive_homomorphism() {
-        let ref pss = PSS_4_26_3;
+        let pss = &PSS_4_26_3;
 
         let secrets_1 = vec![1, 2, 3];
         let secrets_2 = vec![4, 5, 6];
@@ -452,11 +1942,7 @@ mod old_tests {
         let shares_2 = pss.share(&secrets_2);
 
         // add shares pointwise
-        let shares_sum: Vec<i64> = shares_1
-            .iter()
-            .zip(shares_2)
-            .map(|(a, b)| (a + b) % pss.field.0)
-            .collect(); // TODO
+        let shares_sum = pss.add_shares(&shares_1, &shares_2);
 
         // reconstruct sum, using same reconstruction limit
         let reconstruct_limit = pss.reconstruct_limit();
@@ -469,7 +1955,7 @@ mod old_tests {
 
     #[test]
     fn test_share_multiplicative_homomorphism() {
-        let ref pss = PSS_4_26_3;
+        let pss = &PSS_4_26_3;
 
         let secrets_1 = vec![1, 2, 3];
         let secrets_2 = vec![4, 5, 6];
@@ -477,20 +1963,77 @@ mod old_tests {
         let shares_2 = pss.share(&secrets_2);
 
         // multiply shares pointwise
-        let shares_product: Vec<i64> = shares_1
-            .iter()
-            .zip(shares_2)
-            .map(|(a, b)| (a * b) % pss.field.0)
-            .collect(); // TODO
+        let product = pss.mul_shares(&shares_1, &shares_2);
 
         // reconstruct product, using double reconstruction limit
-        let reconstruct_limit = pss.reconstruct_limit() * 2;
+        let reconstruct_limit = product.reconstruct_limit;
+        assert_eq!(reconstruct_limit, pss.reconstruct_limit() * 2);
         let indices: Vec<u32> = (0..reconstruct_limit as u32).collect();
-        let shares = &shares_product[0..reconstruct_limit];
+        let shares = &product.shares[0..reconstruct_limit];
         let recovered_secrets = pss.reconstruct(&indices, shares);
 
         assert_eq!(pss.field.decode_slice(recovered_secrets), [4, 10, 18]);
     }
+
+    #[test]
+    fn test_affine() {
+        let pss = &PSS_4_26_3;
+        let shares = pss.share(&[1, 2, 3]);
+
+        // 2 * secrets + [10, 20, 30]
+        let scaled: Vec<i64> = shares.iter().map(|share| pss.scale(share, &2)).collect();
+        let shifted = pss.add_constant_shares(&scaled, &[10, 20, 30]);
+        for (i, share) in shifted.iter().enumerate() {
+            let expected = pss.add_constant(i as u32, &scaled[i], &[10, 20, 30]);
+            assert_eq!(Ok(*share), expected);
+        }
+        assert_eq!(
+            pss.add_constant(26, &scaled[0], &[10, 20, 30]),
+            Err(IndexError::UnknownShare(26))
+        );
+        assert_eq!(
+            pss.add_constant(u32::MAX, &scaled[0], &[10, 20, 30]),
+            Err(IndexError::UnknownShare(u32::MAX as ShareIndex))
+        );
+
+        let limit = pss.reconstruct_limit();
+        let indices: Vec<u32> = (0..limit as u32).map(|i| i * 2).collect();
+        let values: Vec<i64> = indices.iter().map(|&i| shifted[i as usize]).collect();
+        let recovered_secrets = pss.reconstruct(&indices, &values);
+        assert_eq!(pss.field.decode_slice(recovered_secrets), [12, 24, 36]);
+    }
+
+    #[test]
+    fn test_special_sharings() {
+        use rand::{ChaChaRng, SeedableRng};
+        let pss = &PSS_4_26_3;
+        let limit = pss.reconstruct_limit();
+        let indices: Vec<u32> = (0..limit as u32).collect();
+        let zero = pss.share_zero();
+        assert_eq!(pss.reconstruct(&indices, &zero[..limit]), [0, 0, 0]);
+        let constant = pss.share_constant(17);
+        assert_eq!(pss.reconstruct(&indices, &constant[..limit]), [17, 17, 17]);
+
+        let mut rng = ChaChaRng::from_seed(&[56]);
+        let (values, shares) = pss.share_random(&mut rng);
+        assert_eq!(values.len(), 3);
+        assert_eq!(pss.reconstruct(&indices, &shares[..limit]), values);
+    }
+
+    #[test]
+    fn test_share_linear_combination() {
+        let pss = &PSS_4_26_3;
+        let shares_1 = pss.share(&[1, 2, 3]);
+        let shares_2 = pss.share(&[4, 5, 6]);
+
+        // 3 * (secrets_2 - secrets_1), recovered from the shares with odd indices
+        let shares = pss.scale_shares(&pss.sub_shares(&shares_2, &shares_1), &3);
+        let indices: Vec<u32> = (0..pss.share_count as u32).filter(|i| i % 2 == 1).collect();
+        let values: Vec<i64> = indices.iter().map(|&i| shares[i as usize]).collect();
+        let recovered_secrets = pss.reconstruct(&indices, &values);
+
+        assert_eq!(pss.field.decode_slice(recovered_secrets), [9, 9, 9]);
+    }
 }
 
 #[cfg(feature = "paramgen")]
@@ -6,10 +6,11 @@ extern crate num_traits;
 extern crate primal;
 
 use self::num_traits::{One, Zero};
+use fields::BigExponent;
 use std::borrow::Borrow;
 use std::ops::*;
 
-#[cfg_attr(rustfmt, rustfmt_skip)]
+#[rustfmt::skip]
 fn check_prime_form<I, J: Borrow<I>>(min_p: J, n: J, m: J, p: J) -> bool 
 where 
     I: PartialOrd + Zero + One,
@@ -28,7 +29,7 @@ where
     if &k % n.borrow() == I::zero() { return false; }
     if &k % m.borrow() == I::zero() { return false; }
 
-    return true;
+    true
 }
 
 #[test]
@@ -70,7 +71,8 @@ fn find_field(min_p: usize, n: usize, m: usize) -> Option<(i64, i64)> {
         // test generator against all factors of p-1
         let is_generator = factors.iter().all(|f| {
             let e = (p - 1) / f;
-            ::numtheory::mod_pow(g as i64, e as u32, p as i64) != 1 // TODO check for negative value
+            ::numtheory::mod_pow(g as i64, BigExponent::from(e as u64), p as i64) != 1
+            // TODO check for negative value
         });
         // return
         if is_generator {
@@ -105,8 +107,8 @@ fn test_find_field() {
 }
 
 fn find_roots(n: usize, m: usize, p: i64, g: i64) -> (i64, i64) {
-    let omega_secrets = ::numtheory::mod_pow(g, ((p - 1) / n as i64) as u32, p);
-    let omega_shares = ::numtheory::mod_pow(g, ((p - 1) / m as i64) as u32, p);
+    let omega_secrets = ::numtheory::mod_pow(g, BigExponent::from((p - 1) as u64 / n as u64), p);
+    let omega_shares = ::numtheory::mod_pow(g, BigExponent::from((p - 1) as u64 / m as u64), p);
     (omega_secrets, omega_shares)
 }
 
@@ -143,28 +145,29 @@ fn is_power_of(x: usize, e: usize) -> bool {
 
 #[test]
 fn test_is_power_of() {
-    assert_eq!(is_power_of(4, 2), true);
-    assert_eq!(is_power_of(5, 2), false);
-    assert_eq!(is_power_of(6, 2), false);
-    assert_eq!(is_power_of(7, 2), false);
-    assert_eq!(is_power_of(8, 2), true);
+    assert!(is_power_of(4, 2));
+    assert!(!is_power_of(5, 2));
+    assert!(!is_power_of(6, 2));
+    assert!(!is_power_of(7, 2));
+    assert!(is_power_of(8, 2));
 
-    assert_eq!(is_power_of(4, 3), false);
-    assert_eq!(is_power_of(5, 3), false);
-    assert_eq!(is_power_of(6, 3), false);
-    assert_eq!(is_power_of(7, 3), false);
-    assert_eq!(is_power_of(8, 3), false);
-    assert_eq!(is_power_of(9, 3), true);
+    assert!(!is_power_of(4, 3));
+    assert!(!is_power_of(5, 3));
+    assert!(!is_power_of(6, 3));
+    assert!(!is_power_of(7, 3));
+    assert!(!is_power_of(8, 3));
+    assert!(is_power_of(9, 3));
 }
 
+use self::num_traits::Num;
 use super::*;
-use fields::PrimeField;
+use fields::{KnownPrime, New, PrimeField};
+use numtheory::{find_root_of_unity, Primality, PrimeModulus};
 
 impl<F> PackedSecretSharing<F>
 where
-    F: PrimeField,
-    F: Encode<u32>,
-    F::P: From<u32>,
+    F: PrimeField + New<u32> + Encode<u32>,
+    F::E: Clone,
 {
     /// Find suitable parameters with as small a prime field as possible.
     pub fn new(
@@ -187,19 +190,21 @@ where
         let n = share_count + 1;
         assert!(is_power_of(m, 2));
         assert!(is_power_of(n, 3));
-        assert!(min_size >= share_count + secret_count + threshold + 1);
+        assert!(min_size > share_count + secret_count + threshold);
 
         let (prime, omega_secrets, omega_shares) = generate_parameters(min_size, m, n);
 
-        let field = F::new((prime as u32).into());
-        PackedSecretSharing {
-            threshold: threshold,
-            share_count: share_count,
-            secret_count: secret_count,
-            omega_secrets: field.encode(omega_secrets as u32),
-            omega_shares: field.encode(omega_shares as u32),
-            field: field,
-        }
+        let field = F::new(prime as u32);
+        let omega_secrets = field.encode(omega_secrets as u32);
+        let omega_shares = field.encode(omega_shares as u32);
+        PackedSecretSharing::with_roots(
+            threshold,
+            secret_count,
+            share_count,
+            field,
+            omega_secrets,
+            omega_shares,
+        )
     }
 }
 
@@ -218,3 +223,203 @@ fn test_new() {
         super::PSS_4_26_3
     );
 }
+
+/// Smallest prime `p >= min_prime` such that `p - 1` is divisible by `m.n`, as a
+/// field together with principal `m`-th and `n`-th roots of unity in it, for `m` a
+/// power of 2 and `n` a power of 3.
+///
+/// The prime may be of any type with a primality test, from `u64` to the
+/// arbitrary-precision integers of `LargePrimeField`.
+#[doc(hidden)]
+pub fn generate_large_parameters<F>(min_prime: F::P, m: usize, n: usize) -> (F, F::E, F::E)
+where
+    F: KnownPrime + New<F::P>,
+    F::P: Num + Clone + PartialOrd + From<u64> + Primality + PrimeModulus,
+{
+    let mn = F::P::from(m as u64 * n as u64);
+    // p = k.mn + 1 for the smallest k with p >= min_prime
+    let k = (min_prime - F::P::from(2)) / mn.clone() + F::P::one();
+    let mut prime = k * mn.clone() + F::P::one();
+    while !prime.is_probable_prime() {
+        prime = prime + mn.clone();
+    }
+    let field = F::new(prime);
+    let omega_secrets = find_root_of_unity(&field, m).unwrap();
+    let omega_shares = find_root_of_unity(&field, n).unwrap();
+    (field, omega_secrets, omega_shares)
+}
+
+#[test]
+fn test_generate_large_parameters() {
+    use fields::U64Field;
+    // same prime as the small version, but roots derived from different bases
+    let (field, omega_secrets, omega_shares) =
+        generate_large_parameters::<U64Field>(200, 2usize.pow(3), 3usize.pow(2));
+    assert_eq!(
+        (field, omega_secrets, omega_shares),
+        (U64Field(433), 354, 256)
+    );
+    let (field, _, _) =
+        generate_large_parameters::<U64Field>(1 << 40, 2usize.pow(4), 3usize.pow(5));
+    assert!(field.0 >= 1 << 40);
+    assert_eq!((field.0 - 1) % (16 * 243), 0);
+    assert!(field.0.is_probable_prime());
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: KnownPrime + New<F::P>,
+    F::P: Num + Clone + PartialOrd + From<u64> + Primality + PrimeModulus,
+    F::E: Clone,
+{
+    /// Find suitable parameters with the smallest suitable prime of at least
+    /// `prime_bits` bits, which must fit in the prime type of the field.
+    ///
+    /// This is the arbitrary-precision counterpart of `new_with_min_size`, and like it
+    /// always gives the same parameters.
+    pub fn new_with_prime_bits(
+        threshold: usize,
+        secret_count: usize,
+        share_count: usize,
+        prime_bits: usize,
+    ) -> PackedSecretSharing<F> {
+        let m = threshold + secret_count + 1;
+        let n = share_count + 1;
+        assert!(is_power_of(m, 2));
+        assert!(is_power_of(n, 3));
+        assert!(prime_bits >= 2);
+
+        let min_prime = num_traits::pow(F::P::from(2), prime_bits - 1);
+        let (field, omega_secrets, omega_shares) = generate_large_parameters(min_prime, m, n);
+        PackedSecretSharing::with_roots(
+            threshold,
+            secret_count,
+            share_count,
+            field,
+            omega_secrets,
+            omega_shares,
+        )
+    }
+}
+
+#[test]
+fn test_new_with_prime_bits() {
+    use fields::{SliceEncode, U64Field};
+    let pss: PackedSecretSharing<U64Field> = PackedSecretSharing::new_with_prime_bits(4, 3, 26, 60);
+    assert!(pss.field.0 >= 1 << 59);
+    let secrets = pss.field.encode_slice(vec![1_u64, 2, 3]);
+    let shares = pss.share(&secrets);
+    let indices: Vec<u32> = (0..pss.reconstruct_limit() as u32).collect();
+    let recovered = pss.reconstruct(&indices, &shares[0..pss.reconstruct_limit()]);
+    assert_eq!(recovered, secrets);
+}
+
+#[cfg(feature = "largefield")]
+mod security {
+
+    extern crate framp as ramp;
+
+    use self::ramp::{Int, RandomInt};
+    use super::*;
+    use fields::New;
+    use numtheory::Primality;
+    use rand;
+
+    /// Uniformly random value in `[low, high)`.
+    fn sample_range<R: rand::Rng>(low: &Int, high: &Int, rng: &mut R) -> Int {
+        rng.gen_uint_below(&(high - low)) + low
+    }
+
+    /// Random prime of exactly `bits` bits such that `p - 1` is divisible by `m.n`.
+    pub fn find_prime<R: rand::Rng>(bits: usize, m: usize, n: usize, rng: &mut R) -> Int {
+        assert!(bits >= 2, "primes have at least 2 bits");
+        let mn = Int::from(m) * Int::from(n);
+        let low = Int::one() << (bits - 1);
+        let high = Int::one() << bits;
+        // p = k.mn + 1 with low <= p < high
+        let k_low = (&low - 1) / &mn + 1;
+        let k_high = (&high - 1) / &mn;
+        assert!(
+            k_low < k_high,
+            "too few bits for the required roots of unity"
+        );
+        loop {
+            let k = sample_range(&k_low, &k_high, rng);
+            let p = k * &mn + 1;
+            if p.is_probable_prime() {
+                return p;
+            }
+        }
+    }
+
+    /// Random principal root of unity of order `order`, a power of `factor`.
+    pub fn find_root<R: rand::Rng>(p: &Int, order: usize, factor: usize, rng: &mut R) -> Int {
+        let cofactor = (p - 1) / Int::from(order);
+        let two = Int::from(2);
+        let p_minus_one = p - 1;
+        loop {
+            let x = sample_range(&two, &p_minus_one, rng);
+            let omega = x.pow_mod(&cofactor, p);
+            if order == 1 || omega.pow_mod(&Int::from(order / factor), p) != 1 {
+                return omega;
+            }
+        }
+    }
+
+    impl<F> PackedSecretSharing<F>
+    where
+        F: Field + New<Int> + Encode<Int>,
+        F::E: Clone,
+    {
+        /// Find suitable parameters over a random prime field of `secret_bits` bits.
+        ///
+        /// Unlike `new`, candidate primes are sampled at random and tested with
+        /// Miller-Rabin, so that fields of 128 bits and more can be targeted.
+        pub fn for_security(
+            secret_bits: usize,
+            threshold: usize,
+            secret_count: usize,
+            share_count: usize,
+        ) -> PackedSecretSharing<F> {
+            let m = threshold + secret_count + 1;
+            let n = share_count + 1;
+            assert!(is_power_of(m, 2));
+            assert!(is_power_of(n, 3));
+
+            let mut rng = rand::OsRng::new().unwrap();
+            let prime = find_prime(secret_bits, m, n, &mut rng);
+            let omega_secrets = find_root(&prime, m, 2, &mut rng);
+            let omega_shares = find_root(&prime, n, 3, &mut rng);
+
+            let field = F::new(prime);
+            let omega_secrets = field.encode(omega_secrets);
+            let omega_shares = field.encode(omega_shares);
+            PackedSecretSharing::with_roots(
+                threshold,
+                secret_count,
+                share_count,
+                field,
+                omega_secrets,
+                omega_shares,
+            )
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "primes have at least 2 bits")]
+    fn test_find_prime_without_bits() {
+        find_prime(0, 8, 9, &mut rand::thread_rng());
+    }
+
+    #[test]
+    fn test_for_security() {
+        use fields::{LargePrimeField, SliceEncode};
+        let pss: PackedSecretSharing<LargePrimeField> =
+            PackedSecretSharing::for_security(128, 4, 3, 8);
+        let secrets = pss.field.encode_slice(vec![1_u64, 2, 3]);
+        let shares = pss.share(&secrets);
+        let indices: Vec<u32> = (0..pss.reconstruct_limit() as u32).collect();
+        let recovered = pss.reconstruct(&indices, &shares[0..pss.reconstruct_limit()]);
+        assert_eq!(recovered, secrets);
+    }
+}
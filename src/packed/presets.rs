@@ -0,0 +1,213 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Vetted 62-bit FFT-friendly primes for the packed scheme.
+
+use super::*;
+use fields::New;
+
+/// A prime `p` such that `p - 1` is divisible by both `2^two_adicity` and
+/// `3^three_adicity`, together with principal roots of unity of these orders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrimePreset {
+    /// The prime itself, between `2^61` and `2^62`.
+    pub prime: u64,
+    /// Largest power of 2 supported for `secret_count + threshold + 1`.
+    pub two_adicity: u32,
+    /// Principal root of unity of order `2^two_adicity`.
+    pub omega_two: u64,
+    /// Largest power of 3 supported for `share_count + 1`.
+    pub three_adicity: u32,
+    /// Principal root of unity of order `3^three_adicity`.
+    pub omega_three: u64,
+}
+
+/// Identifiers for the available presets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresetId {
+    /// Up to `2^40 - 1` secrets and randomness, and `3^12 - 1` shares.
+    Prime62TwoHeavy,
+    /// Up to `2^28 - 1` secrets and randomness, and `3^19 - 1` shares.
+    Prime62Balanced,
+    /// Up to `2^20 - 1` secrets and randomness, and `3^24 - 1` shares.
+    Prime62ThreeHeavy,
+}
+
+pub static PRIME62_TWO_HEAVY: PrimePreset = PrimePreset {
+    prime: 2921627794884526081, // 5 * 2^40 * 3^12 + 1
+    two_adicity: 40,
+    omega_two: 2688000070288850813,
+    three_adicity: 12,
+    omega_three: 1465858639816566489,
+};
+
+pub static PRIME62_BALANCED: PrimePreset = PrimePreset {
+    prime: 4367890616395235329, // 14 * 2^28 * 3^19 + 1
+    two_adicity: 28,
+    omega_two: 2599233450045712991,
+    three_adicity: 19,
+    omega_three: 2095551766271323012,
+};
+
+pub static PRIME62_THREE_HEAVY: PrimePreset = PrimePreset {
+    prime: 3257637170096111617, // 11 * 2^20 * 3^24 + 1
+    two_adicity: 20,
+    omega_two: 1744231876400332916,
+    three_adicity: 24,
+    omega_three: 2526797386164861497,
+};
+
+impl PresetId {
+    pub fn preset(&self) -> &'static PrimePreset {
+        match *self {
+            PresetId::Prime62TwoHeavy => &PRIME62_TWO_HEAVY,
+            PresetId::Prime62Balanced => &PRIME62_BALANCED,
+            PresetId::Prime62ThreeHeavy => &PRIME62_THREE_HEAVY,
+        }
+    }
+}
+
+/// Exponent `k` such that `x = base^k`, if any.
+fn exact_log(mut x: usize, base: usize) -> Option<u32> {
+    let mut k = 0;
+    while x > 1 && x % base == 0 {
+        x /= base;
+        k += 1;
+    }
+    if x == 1 {
+        Some(k)
+    } else {
+        None
+    }
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field + New<u64> + Encode<u64>,
+    F::E: Clone,
+{
+    /// Instantiate the scheme over one of the preset 62-bit primes.
+    ///
+    /// Note that the field must be able to hold 62-bit values, as `U64Field` does,
+    /// which excludes `NaturalPrimeField<i64>` and `MontgomeryField32`.
+    pub fn with_preset(
+        preset: PresetId,
+        threshold: usize,
+        secret_count: usize,
+        share_count: usize,
+    ) -> PackedSecretSharing<F> {
+        let preset = preset.preset();
+        let two_exponent = exact_log(threshold + secret_count + 1, 2)
+            .expect("secret_count + threshold + 1 must be a power of 2");
+        let three_exponent =
+            exact_log(share_count + 1, 3).expect("share_count + 1 must be a power of 3");
+        assert!(two_exponent <= preset.two_adicity);
+        assert!(three_exponent <= preset.three_adicity);
+
+        let field = F::new(preset.prime);
+        // derive roots of the right orders by repeated squaring and cubing
+        let mut omega_secrets = field.encode(preset.omega_two);
+        for _ in two_exponent..preset.two_adicity {
+            omega_secrets = field.mul(&omega_secrets, &omega_secrets);
+        }
+        let mut omega_shares = field.encode(preset.omega_three);
+        for _ in three_exponent..preset.three_adicity {
+            omega_shares = field.mul(field.mul(&omega_shares, &omega_shares), &omega_shares);
+        }
+
+        PackedSecretSharing::with_roots(
+            threshold,
+            secret_count,
+            share_count,
+            field,
+            omega_secrets,
+            omega_shares,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn mod_pow(mut x: u64, mut e: u64, p: u64) -> u64 {
+        let mut acc = 1_u64;
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = (acc as u128 * x as u128 % p as u128) as u64;
+            }
+            x = (x as u128 * x as u128 % p as u128) as u64;
+            e >>= 1;
+        }
+        acc
+    }
+
+    #[test]
+    fn test_presets_are_principal() {
+        for id in &[
+            PresetId::Prime62TwoHeavy,
+            PresetId::Prime62Balanced,
+            PresetId::Prime62ThreeHeavy,
+        ] {
+            let preset = id.preset();
+            let p = preset.prime;
+            assert!(p > 1 << 61 && p < 1 << 62);
+            assert_eq!((p - 1) % (1 << preset.two_adicity), 0);
+            assert_eq!((p - 1) % 3_u64.pow(preset.three_adicity), 0);
+            // Fermat witnesses
+            assert_eq!(mod_pow(2, p - 1, p), 1);
+            assert_eq!(mod_pow(3, p - 1, p), 1);
+
+            let order_two = 1_u64 << preset.two_adicity;
+            assert_eq!(mod_pow(preset.omega_two, order_two, p), 1);
+            assert!(mod_pow(preset.omega_two, order_two / 2, p) != 1);
+            let order_three = 3_u64.pow(preset.three_adicity);
+            assert_eq!(mod_pow(preset.omega_three, order_three, p), 1);
+            assert!(mod_pow(preset.omega_three, order_three / 3, p) != 1);
+        }
+    }
+
+    #[test]
+    fn test_exact_log() {
+        assert_eq!(exact_log(1, 2), Some(0));
+        assert_eq!(exact_log(8, 2), Some(3));
+        assert_eq!(exact_log(12, 2), None);
+        assert_eq!(exact_log(27, 3), Some(3));
+        assert_eq!(exact_log(28, 3), None);
+    }
+
+    #[test]
+    fn test_with_preset() {
+        use fields::{SliceEncode, U64Field};
+        let pss: PackedSecretSharing<U64Field> =
+            PackedSecretSharing::with_preset(PresetId::Prime62TwoHeavy, 4, 3, 26);
+        assert_eq!(pss.field, U64Field(PRIME62_TWO_HEAVY.prime));
+        let field = &pss.field;
+        let secrets = field.encode_slice(vec![1_u64 << 61, 2, 3]);
+        let shares = pss.share(&secrets);
+        assert_eq!(shares.len(), 26);
+        let indices: Vec<u32> = (0..pss.reconstruct_limit() as u32).collect();
+        let recovered = pss.reconstruct(&indices, &shares[0..pss.reconstruct_limit()]);
+        assert_eq!(recovered, secrets);
+    }
+
+    #[cfg(feature = "largefield")]
+    #[test]
+    fn test_with_preset_large() {
+        use fields::{LargePrimeField, SliceEncode};
+        let pss: PackedSecretSharing<LargePrimeField> =
+            PackedSecretSharing::with_preset(PresetId::Prime62Balanced, 4, 3, 26);
+        let field = &pss.field;
+        let secrets = field.encode_slice(vec![1_u64 << 61, 2, 3]);
+        let shares = pss.share(&secrets);
+        let indices: Vec<u32> = (0..pss.reconstruct_limit() as u32).collect();
+        let recovered = pss.reconstruct(&indices, &shares[0..pss.reconstruct_limit()]);
+        assert_eq!(recovered, secrets);
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Choice of the interpolation algorithm used for reconstruction.
+//!
+//! The cost of every algorithm is estimated from the number of field operations it
+//! performs, weighted by the `OpCosts` of the field backend. The estimates only
+//! need to be good enough to rank the algorithms, not to predict running times.
+
+use super::PackedSecretSharing;
+use fields::{Encode, Field};
+use numtheory::fft::is_mixed_radix_len;
+
+/// Interpolation algorithm used by `reconstruct_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReconstructStrategy {
+    /// The cheapest of the other strategies according to `estimate_reconstruct_cost`.
+    #[default]
+    Auto,
+    /// Recover the missing shares by erasure decoding, then interpolate with FFTs.
+    ///
+    /// Takes time quasilinear in `share_count` whatever the number of shares, and is
+    /// the fastest when few shares are missing.
+    FftErasure,
+    /// Interpolate with a subproduct tree, then evaluate with an FFT.
+    ///
+    /// Takes time quasilinear in the number of shares when `share_count + 1` is of
+    /// the form `2^a.3^b`, and quadratic otherwise.
+    SubproductTree,
+    /// Barycentric Lagrange interpolation at every secret point.
+    ///
+    /// Takes time quadratic in the number of shares, with a smaller constant than
+    /// `Newton` but an inversion for every secret.
+    Barycentric,
+    /// Newton interpolation, evaluating the resulting polynomial at every secret point.
+    Newton,
+}
+
+impl ReconstructStrategy {
+    /// The strategies `Auto` chooses from, in the order in which ties are broken.
+    pub const EXPLICIT: [ReconstructStrategy; 4] = [
+        ReconstructStrategy::FftErasure,
+        ReconstructStrategy::SubproductTree,
+        ReconstructStrategy::Barycentric,
+        ReconstructStrategy::Newton,
+    ];
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Estimated cost in nanoseconds of reconstructing the secrets from `count` shares
+    /// using `strategy`, based on the `op_costs` of the field.
+    ///
+    /// `Auto` is estimated as the strategy it chooses.
+    pub fn estimate_reconstruct_cost(&self, strategy: ReconstructStrategy, count: usize) -> u64 {
+        (self.reconstruct_cost(strategy, count) / 1000.0) as u64
+    }
+
+    /// The strategy used by `reconstruct` for `count` shares.
+    pub fn reconstruct_strategy(&self, count: usize) -> ReconstructStrategy {
+        let mut best = ReconstructStrategy::EXPLICIT[0];
+        let mut best_cost = self.reconstruct_cost(best, count);
+        for &strategy in &ReconstructStrategy::EXPLICIT[1..] {
+            let cost = self.reconstruct_cost(strategy, count);
+            if cost < best_cost {
+                best = strategy;
+                best_cost = cost;
+            }
+        }
+        best
+    }
+
+    /// Estimated cost of `strategy` for reconstructing from `count` shares.
+    fn reconstruct_cost(&self, strategy: ReconstructStrategy, count: usize) -> f64 {
+        let costs = self.field.op_costs();
+        let (add, mul, inv) = (costs.add as f64, costs.mul as f64, costs.inv as f64);
+        // the zero at point 1 is interpolated from as an extra share
+        let points = count as f64 + 1.0;
+        let share_points = self.share_count + 1;
+        let secret_points = (self.reconstruct_limit() + 1) as f64;
+        let evaluations = self.secret_count as f64;
+        // computing the powers of the roots of unity at which the shares and
+        // secrets lie
+        let powers = |count: f64, order: f64| count * order.log2().ceil() * 1.5 * mul;
+        let share_powers = powers(points, share_points as f64);
+        let secret_powers = powers(evaluations, secret_points);
+        // folding and evaluating at all secret points
+        let evaluate_secrets = secret_points * add + self.fft_cost(secret_points, true);
+        match strategy {
+            ReconstructStrategy::Auto => {
+                self.reconstruct_cost(self.reconstruct_strategy(count), count)
+            }
+            ReconstructStrategy::FftErasure => {
+                let n = share_points as f64;
+                let smooth = is_mixed_radix_len(share_points);
+                let interpolate = self.fft_cost(n, smooth) + evaluate_secrets;
+                let missing = n - points;
+                if missing <= 0.0 {
+                    return interpolate;
+                }
+                // locator, product and derivatives, plus the division at the
+                // missing points
+                powers(missing, n)
+                    + self.tree_cost(missing)
+                    + 4.0 * self.fft_cost(n, smooth)
+                    + n * mul
+                    + missing * 4.0 * mul
+                    + inv
+                    + interpolate
+            }
+            ReconstructStrategy::SubproductTree => {
+                let n = share_points as f64;
+                // building the tree and going back up it with the values
+                share_powers
+                    + 2.0 * self.tree_cost(points)
+                    + self.fft_cost(n, is_mixed_radix_len(share_points))
+                    + points * 3.0 * mul
+                    + inv
+                    + evaluate_secrets
+            }
+            ReconstructStrategy::Barycentric => {
+                let weights = points * points * 2.0 * (add + mul) + points * 3.0 * mul + inv;
+                let constants = points * (2.0 * add + 8.0 * mul) + inv;
+                let interpolate = points * (add + mul);
+                share_powers + weights + evaluations * (constants + interpolate) + secret_powers
+            }
+            ReconstructStrategy::Newton => {
+                // including the batched inversion of all point differences
+                let coefficients = points * points * (2.0 * add + 5.0 * mul) + inv;
+                let evaluate = points * (add + mul);
+                share_powers + coefficients + evaluations * evaluate + secret_powers
+            }
+        }
+    }
+
+    /// Estimated cost of an FFT of length `len`, using Bluestein's algorithm unless
+    /// `smooth`.
+    fn fft_cost(&self, len: f64, smooth: bool) -> f64 {
+        let costs = self.field.op_costs();
+        let butterfly = costs.mul as f64 + 2.0 * costs.add as f64;
+        let direct = |len: f64| len * len.log2() / 2.0 * butterfly;
+        if smooth {
+            direct(len)
+        } else {
+            // three FFTs over a convolution of at least twice the length, and
+            // pointwise scaling by the chirp
+            3.0 * direct(2.0 * len) + 3.0 * len * costs.mul as f64
+        }
+    }
+
+    /// Estimated cost of building a subproduct tree over `len` powers of
+    /// `omega_shares`.
+    fn tree_cost(&self, len: f64) -> f64 {
+        let costs = self.field.op_costs();
+        if is_mixed_radix_len(self.share_count + 1) {
+            // FFT multiplications, each level costing about three FFTs over all points
+            let levels = len.log2().max(1.0);
+            3.0 * levels * self.fft_cost(2.0 * len, true)
+        } else {
+            // schoolbook multiplications, dominated by the root
+            len * len / 2.0 * (costs.add + costs.mul) as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Fp61, MontgomeryField32, New, SliceEncode};
+    use packed::{PSS_155_728_100, PSS_4_26_3};
+
+    #[test]
+    fn test_strategies_agree() {
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([5, 6, 7]);
+        let shares = pss.share(&secrets);
+        let subsets: [Vec<u32>; 3] = [
+            (0..26).collect(),
+            (3..10).collect(),
+            vec![0, 4, 9, 13, 17, 21, 24, 25],
+        ];
+        for indices in &subsets {
+            let values: Vec<i64> = indices.iter().map(|&i| shares[i as usize]).collect();
+            assert_eq!(pss.reconstruct(indices, &values), secrets);
+            for &strategy in &ReconstructStrategy::EXPLICIT {
+                assert_eq!(pss.reconstruct_with(strategy, indices, &values), secrets);
+            }
+        }
+    }
+
+    #[test]
+    fn test_choice() {
+        let pss = &PSS_4_26_3;
+        // all shares only need FFTs, while few of them are cheaper to interpolate
+        assert_eq!(
+            pss.reconstruct_strategy(pss.share_count),
+            ReconstructStrategy::FftErasure
+        );
+        assert_ne!(
+            pss.reconstruct_strategy(pss.reconstruct_limit()),
+            ReconstructStrategy::FftErasure
+        );
+
+        let pss = &PSS_155_728_100;
+        assert_eq!(
+            pss.reconstruct_strategy(pss.share_count - 1),
+            ReconstructStrategy::FftErasure
+        );
+        assert_ne!(
+            pss.reconstruct_strategy(pss.reconstruct_limit()),
+            ReconstructStrategy::Newton
+        );
+
+        for count in pss.reconstruct_limit()..pss.share_count + 1 {
+            let cost = |strategy| pss.estimate_reconstruct_cost(strategy, count);
+            let auto = cost(ReconstructStrategy::Auto);
+            assert_eq!(auto, cost(pss.reconstruct_strategy(count)));
+            assert!(ReconstructStrategy::EXPLICIT
+                .iter()
+                .all(|&strategy| auto <= cost(strategy)));
+        }
+    }
+
+    #[test]
+    fn test_backends() {
+        let pss = PackedSecretSharing::with_roots(10, 2, 728, Fp61, 0, 0);
+        let field = MontgomeryField32::new(746497);
+        let zero = field.zero();
+        let montgomery = PackedSecretSharing::with_roots(10, 2, 728, field, zero, zero);
+        // barycentric interpolation needs an inversion for every secret, which is
+        // slow in Fp61 but fast in small Montgomery fields
+        assert_eq!(pss.reconstruct_strategy(12), ReconstructStrategy::Newton);
+        assert_eq!(
+            montgomery.reconstruct_strategy(12),
+            ReconstructStrategy::Barycentric
+        );
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Checking the constraints between the parameters of the packed scheme.
+
+use super::PackedSecretSharing;
+use fields::{KnownPrime, PrimeField};
+use numtheory::fft::{is_mixed_radix_len, mixed_radix_order};
+use numtheory::Primality;
+use std::error::Error;
+use std::fmt;
+
+/// Violation of one of the constraints listed for `PackedSecretSharing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamError {
+    /// The modulus of the field is not prime.
+    CompositeModulus,
+    /// `share_count` is smaller than the reconstruction limit `secret_count + threshold`.
+    TooFewShares,
+    /// `m = secret_count + threshold + 1` is not of the form `2^a.3^b`.
+    InvalidSecretsOrder(usize),
+    /// `n = share_count + 1` is not of the form `2^a.3^b` and no `omega_convolution`
+    /// was given.
+    MissingConvolutionRoot(usize),
+    /// `omega_convolution` is not a root of unity of order `2^a.3^b` at least `2n - 1`.
+    InvalidConvolutionRoot,
+    /// `m` and `n` are not coprime, so secrets and shares may share points.
+    OverlappingDomains(usize, usize),
+    /// `omega_secrets` is not an `m`-th principal root of unity.
+    InvalidSecretsRoot,
+    /// `omega_shares` is not an `n`-th principal root of unity.
+    InvalidSharesRoot,
+    /// `layout` does not embed the secrets at distinct powers `omega_secrets^1` up
+    /// to `omega_secrets^(m - 1)`.
+    InvalidLayout,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParamError::CompositeModulus => write!(f, "field modulus is not prime"),
+            ParamError::TooFewShares => {
+                write!(f, "share_count is below secret_count + threshold")
+            }
+            ParamError::InvalidSecretsOrder(m) => {
+                write!(
+                    f,
+                    "secret_count + threshold + 1 = {} is not of the form 2^a.3^b",
+                    m
+                )
+            }
+            ParamError::MissingConvolutionRoot(n) => write!(
+                f,
+                "share_count + 1 = {} is not of the form 2^a.3^b and omega_convolution is missing",
+                n
+            ),
+            ParamError::InvalidConvolutionRoot => write!(
+                f,
+                "omega_convolution is not a root of unity of order 2^a.3^b at least 2n - 1"
+            ),
+            ParamError::OverlappingDomains(m, n) => {
+                write!(
+                    f,
+                    "orders {} and {} of the evaluation domains are not coprime",
+                    m, n
+                )
+            }
+            ParamError::InvalidSecretsRoot => {
+                write!(
+                    f,
+                    "omega_secrets is not a principal root of unity of order m"
+                )
+            }
+            ParamError::InvalidSharesRoot => {
+                write!(
+                    f,
+                    "omega_shares is not a principal root of unity of order n"
+                )
+            }
+            ParamError::InvalidLayout => {
+                write!(f, "layout does not embed the secrets at distinct points")
+            }
+        }
+    }
+}
+
+impl Error for ParamError {}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: KnownPrime,
+    F::P: Primality,
+{
+    /// Check that the parameters satisfy all the constraints listed for
+    /// `PackedSecretSharing`, reporting the first one violated.
+    ///
+    /// Instances constructed by hand should be checked with this, since the other
+    /// methods silently produce meaningless results on invalid parameters.
+    pub fn validate(&self) -> Result<(), ParamError> {
+        if !self.field.prime().is_probable_prime() {
+            return Err(ParamError::CompositeModulus);
+        }
+        if self.share_count < self.threshold + self.secret_count {
+            return Err(ParamError::TooFewShares);
+        }
+        let m = self.secret_count + self.threshold + 1;
+        let n = self.share_count + 1;
+        if !is_mixed_radix_len(m) {
+            return Err(ParamError::InvalidSecretsOrder(m));
+        }
+        if !is_mixed_radix_len(n) {
+            let conv_omega = self
+                .omega_convolution
+                .as_ref()
+                .ok_or(ParamError::MissingConvolutionRoot(n))?;
+            match mixed_radix_order(&self.field, conv_omega, u32::MAX) {
+                Some(order) if order >= 2 * n - 1 => {}
+                _ => return Err(ParamError::InvalidConvolutionRoot),
+            }
+        }
+        if ::numtheory::gcd(m as i64, n as i64).0 != 1 {
+            return Err(ParamError::OverlappingDomains(m, n));
+        }
+        if !is_principal_root(&self.field, &self.omega_secrets, m) {
+            return Err(ParamError::InvalidSecretsRoot);
+        }
+        if !is_principal_root(&self.field, &self.omega_shares, n) {
+            return Err(ParamError::InvalidSharesRoot);
+        }
+        if !self.layout.is_valid(self.secret_count, m - 1) {
+            return Err(ParamError::InvalidLayout);
+        }
+        Ok(())
+    }
+}
+
+/// Whether `omega` has multiplicative order exactly `order`.
+fn is_principal_root<F: PrimeField>(field: &F, omega: &F::E, order: usize) -> bool {
+    if field.neq(field.pow(omega, order as u32), field.one()) {
+        return false;
+    }
+    // the order of omega divides `order`, so it is smaller only if it divides
+    // `order / q` for some prime factor `q`
+    let mut rest = order;
+    let mut q = 2;
+    while rest > 1 {
+        if q * q > rest {
+            q = rest;
+        }
+        if rest % q == 0 {
+            if field.eq(field.pow(omega, (order / q) as u32), field.one()) {
+                return false;
+            }
+            while rest % q == 0 {
+                rest /= q;
+            }
+        }
+        q += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Encode, NaturalPrimeField};
+    use packed::*;
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(PSS_4_8_3.validate(), Ok(()));
+        assert_eq!(PSS_4_26_3.validate(), Ok(()));
+        assert_eq!(PSS_155_728_100.validate(), Ok(()));
+        assert_eq!(PSS_155_19682_100.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let valid = PSS_4_26_3.clone();
+        let field = valid.field.clone();
+
+        let composite = PackedSecretSharing {
+            field: NaturalPrimeField(435),
+            ..valid.clone()
+        };
+        assert_eq!(composite.validate(), Err(ParamError::CompositeModulus));
+
+        let too_few = PackedSecretSharing {
+            threshold: 24,
+            ..valid.clone()
+        };
+        assert_eq!(too_few.validate(), Err(ParamError::TooFewShares));
+
+        let bad_secrets_order = PackedSecretSharing {
+            threshold: 6,
+            ..valid.clone()
+        };
+        assert_eq!(
+            bad_secrets_order.validate(),
+            Err(ParamError::InvalidSecretsOrder(10))
+        );
+
+        let missing_conv = PackedSecretSharing {
+            share_count: 25,
+            ..valid.clone()
+        };
+        assert_eq!(
+            missing_conv.validate(),
+            Err(ParamError::MissingConvolutionRoot(26))
+        );
+
+        let overlapping = PackedSecretSharing {
+            secret_count: 2,
+            threshold: 3,
+            ..valid.clone()
+        };
+        assert_eq!(
+            overlapping.validate(),
+            Err(ParamError::OverlappingDomains(6, 27))
+        );
+
+        let not_principal = PackedSecretSharing {
+            omega_secrets: field.pow(valid.omega_secrets, 2),
+            ..valid.clone()
+        };
+        assert_eq!(
+            not_principal.validate(),
+            Err(ParamError::InvalidSecretsRoot)
+        );
+
+        let not_root = PackedSecretSharing {
+            omega_shares: field.encode(2),
+            ..valid.clone()
+        };
+        assert_eq!(not_root.validate(), Err(ParamError::InvalidSharesRoot));
+
+        let bad_layout = PackedSecretSharing {
+            layout: SecretLayout::Strided(3),
+            ..valid.clone()
+        };
+        assert_eq!(bad_layout.validate(), Err(ParamError::InvalidLayout));
+        let strided = PackedSecretSharing {
+            layout: SecretLayout::Strided(2),
+            ..valid.clone()
+        };
+        assert_eq!(strided.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! The items needed by most users of the crate, to be imported all at once.
+//!
+//! This covers the main schemes, the fields with their encoding traits, the share
+//! types and the errors of reconstruction. Less common schemes and the arithmetic
+//! of the `math` module are left to explicit imports.
+//!
+//! # Example:
+//!
+//! ```
+//!    use threshold_secret_sharing::prelude::*;
+//!    let tss = ShamirSecretSharing {
+//!        threshold: 2,
+//!        share_count: 5,
+//!        field: NaturalPrimeField(41),
+//!    };
+//!    let shares = Share::enumerate(tss.share(7));
+//!    assert_eq!(tss.reconstruct_indexed(&shares[1..4]), 7);
+//! ```
+
+pub use fft_shamir::FftShamirSecretSharing;
+#[cfg(feature = "largefield")]
+pub use fields::LargePrimeField;
+pub use fields::{
+    Decode, ElementBytes, Encode, Field, Fp61, IntegerCoding, MontgomeryField32, NaturalPrimeField,
+    New, PrimeField, SliceDecode, SliceEncode, U256Field, U64Field,
+};
+pub use packed::{ChunkError, PackedSecretSharing, ParamError, VerifyError};
+pub use scheme::ThresholdScheme;
+pub use seed::Seed;
+pub use shamir::ShamirSecretSharing;
+pub use share::{IndexError, PolynomialShares, ProductShares, Share, ShareIndex};
@@ -0,0 +1,859 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Messages and state machines for a dealer distributing shares to participants.
+//!
+//! A `Dealer` shares a batch of secrets with `deal`, producing one
+//! `DealerMessage::Shares` per participant, each of which answers with
+//! `ParticipantMessage::Ack` once its `Participant` has stored them. To reconstruct,
+//! the dealer broadcasts a `DealerMessage::ShareRequest` and feeds the
+//! `ParticipantMessage::ShareResponse`s back into `handle` until enough arrived.
+//!
+//! In between, participants may proactively refresh their shares without the
+//! dealer: each deals sharings of zero to the others in `RefreshMessage`s, and once
+//! a participant has received those of all participants it adds them to its shares,
+//! which are then independent of the previous ones while still sharing the same
+//! secrets. Since shares of different epochs do not combine, the dealer requests
+//! the shares of a given epoch, and participants only answer when they hold them.
+//!
+//! Every message is wrapped in a `Versioned` envelope, and all of them implement
+//! `Serialize` and `Deserialize` when the `serde` feature is activated. Transport,
+//! authentication and encryption of the messages are left to the application.
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use fields::{Encode, Field};
+use packed::PackedSecretSharing;
+use scheme::ThresholdScheme;
+use shamir::ShamirSecretSharing;
+use share::{Share, ShareIndex};
+
+/// Type of the shares of a scheme.
+type Element<S> = <<S as ThresholdScheme>::Field as Field>::E;
+
+/// Version of the protocol implemented by this module.
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// Failure to process a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// The message was sent with another version of the protocol.
+    UnsupportedVersion(u16),
+    /// The message belongs to another session.
+    WrongSession(u64),
+    /// The message belongs to another refresh epoch.
+    WrongEpoch(u64),
+    /// The message is from or to a participant it should not be.
+    WrongParticipant(ShareIndex),
+    /// The message does not carry one value per sharing.
+    WrongLength(usize),
+    /// The message was already received, or does not fit the current state.
+    Unexpected,
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProtocolError::UnsupportedVersion(version) => {
+                write!(f, "unsupported protocol version {}", version)
+            }
+            ProtocolError::WrongSession(session) => write!(f, "wrong session {}", session),
+            ProtocolError::WrongEpoch(epoch) => write!(f, "wrong epoch {}", epoch),
+            ProtocolError::WrongParticipant(index) => write!(f, "wrong participant {}", index),
+            ProtocolError::WrongLength(len) => write!(f, "wrong number of values {}", len),
+            ProtocolError::Unexpected => f.write_str("unexpected message"),
+        }
+    }
+}
+
+impl Error for ProtocolError {}
+
+/// A message together with the version of the protocol it was sent with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Versioned<M> {
+    pub version: u16,
+    pub message: M,
+}
+
+impl<M> Versioned<M> {
+    /// Wrap `message` with the current version.
+    pub fn new(message: M) -> Versioned<M> {
+        Versioned {
+            version: PROTOCOL_VERSION,
+            message,
+        }
+    }
+
+    /// The message, if it was sent with the current version.
+    pub fn open(self) -> Result<M, ProtocolError> {
+        if self.version == PROTOCOL_VERSION {
+            Ok(self.message)
+        } else {
+            Err(ProtocolError::UnsupportedVersion(self.version))
+        }
+    }
+}
+
+/// Message from the dealer to a participant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DealerMessage<E> {
+    /// The shares of `participant` in every sharing of `session`.
+    Shares {
+        session: u64,
+        participant: ShareIndex,
+        values: Vec<E>,
+    },
+    /// Request for the shares of `session` after `epoch` refreshes, to reconstruct
+    /// the secrets.
+    ShareRequest { session: u64, epoch: u64 },
+}
+
+/// Message from a participant to the dealer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ParticipantMessage<E> {
+    /// The shares of `session` were stored by `participant`.
+    Ack {
+        session: u64,
+        participant: ShareIndex,
+    },
+    /// The shares of `participant` in every sharing of `session` after `epoch`
+    /// refreshes, in answer to a `DealerMessage::ShareRequest`.
+    ShareResponse {
+        session: u64,
+        epoch: u64,
+        participant: ShareIndex,
+        values: Vec<E>,
+    },
+}
+
+/// Message between participants refreshing their shares.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RefreshMessage<E> {
+    /// The shares of `to` in sharings of zero dealt by `from`, one per sharing of
+    /// `session`, to be added to the shares of `to` at `epoch`.
+    ZeroShares {
+        session: u64,
+        epoch: u64,
+        from: ShareIndex,
+        to: ShareIndex,
+        values: Vec<E>,
+    },
+}
+
+/// Schemes whose shares can be refreshed by adding sharings of zero.
+pub trait Refreshable: ThresholdScheme {
+    /// The secret made of zeros.
+    fn zero_secret(&self) -> Self::Secret;
+
+    /// Pointwise sums of the shares in `a` and `b`, as with `add_shares`.
+    fn add_shares(&self, a: &[Element<Self>], b: &[Element<Self>]) -> Vec<Element<Self>>;
+}
+
+impl<F> Refreshable for ShamirSecretSharing<F>
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    fn zero_secret(&self) -> F::E {
+        self.field.zero()
+    }
+
+    fn add_shares(&self, a: &[F::E], b: &[F::E]) -> Vec<F::E> {
+        ShamirSecretSharing::add_shares(self, a, b)
+    }
+}
+
+impl<F> Refreshable for PackedSecretSharing<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    fn zero_secret(&self) -> Vec<F::E> {
+        vec![self.field.zero(); self.secret_count]
+    }
+
+    fn add_shares(&self, a: &[F::E], b: &[F::E]) -> Vec<F::E> {
+        PackedSecretSharing::add_shares(self, a, b)
+    }
+}
+
+/// Stage of a `Dealer`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DealerState {
+    /// Nothing was dealt yet.
+    Idle,
+    /// Shares were dealt, and some participants have not acknowledged them yet.
+    AwaitingAcks,
+    /// All participants acknowledged their shares.
+    Dealt,
+    /// Shares were requested, and not enough of them arrived yet.
+    Reconstructing,
+    /// The secrets were reconstructed.
+    Done,
+}
+
+/// Dealer side of a session, sharing a batch of secrets and later reconstructing
+/// them.
+pub struct Dealer<'a, S: 'a + ThresholdScheme> {
+    scheme: &'a S,
+    session: u64,
+    epoch: u64,
+    state: DealerState,
+    sharing_count: usize,
+    acknowledged: Vec<bool>,
+    responses: Vec<(ShareIndex, Vec<Element<S>>)>,
+}
+
+impl<'a, S> Dealer<'a, S>
+where
+    S: 'a + ThresholdScheme,
+    Element<S>: Clone,
+{
+    /// Dealer of `session`, which identifies it to the participants.
+    pub fn new(scheme: &'a S, session: u64) -> Dealer<'a, S> {
+        Dealer {
+            scheme,
+            session,
+            epoch: 0,
+            state: DealerState::Idle,
+            sharing_count: 0,
+            acknowledged: vec![false; scheme.share_count()],
+            responses: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> DealerState {
+        self.state
+    }
+
+    /// Share every secret of `secrets`, returning the message to send to each
+    /// participant in order of index.
+    pub fn deal(
+        &mut self,
+        secrets: &[S::Secret],
+    ) -> Result<Vec<Versioned<DealerMessage<Element<S>>>>, ProtocolError> {
+        if self.state != DealerState::Idle {
+            return Err(ProtocolError::Unexpected);
+        }
+        let mut values: Vec<Vec<_>> =
+            vec![Vec::with_capacity(secrets.len()); self.acknowledged.len()];
+        for secret in secrets {
+            for share in self.scheme.share(secret) {
+                values[share.index].push(share.value);
+            }
+        }
+        self.sharing_count = secrets.len();
+        self.state = DealerState::AwaitingAcks;
+        Ok(values
+            .into_iter()
+            .enumerate()
+            .map(|(participant, values)| {
+                Versioned::new(DealerMessage::Shares {
+                    session: self.session,
+                    participant,
+                    values,
+                })
+            })
+            .collect())
+    }
+
+    /// Request the shares after `epoch` refreshes from the participants, returning
+    /// the message to broadcast.
+    ///
+    /// Participants that are refreshing or at another epoch reject the request. It
+    /// may be repeated for another epoch until the secrets are reconstructed, which
+    /// discards the responses received so far.
+    pub fn request_shares(
+        &mut self,
+        epoch: u64,
+    ) -> Result<Versioned<DealerMessage<Element<S>>>, ProtocolError> {
+        if self.state != DealerState::Dealt && self.state != DealerState::Reconstructing {
+            return Err(ProtocolError::Unexpected);
+        }
+        self.state = DealerState::Reconstructing;
+        self.epoch = epoch;
+        self.responses.clear();
+        Ok(Versioned::new(DealerMessage::ShareRequest {
+            session: self.session,
+            epoch,
+        }))
+    }
+
+    /// Process a message from a participant, returning the secrets once enough
+    /// shares arrived to reconstruct them.
+    pub fn handle(
+        &mut self,
+        message: Versioned<ParticipantMessage<Element<S>>>,
+    ) -> Result<Option<Vec<S::Secret>>, ProtocolError> {
+        match message.open()? {
+            ParticipantMessage::Ack {
+                session,
+                participant,
+            } => {
+                self.check(session, participant, DealerState::AwaitingAcks)?;
+                if self.acknowledged[participant] {
+                    return Err(ProtocolError::Unexpected);
+                }
+                self.acknowledged[participant] = true;
+                if self.acknowledged.iter().all(|&ack| ack) {
+                    self.state = DealerState::Dealt;
+                }
+                Ok(None)
+            }
+            ParticipantMessage::ShareResponse {
+                session,
+                epoch,
+                participant,
+                values,
+            } => {
+                self.check(session, participant, DealerState::Reconstructing)?;
+                if epoch != self.epoch {
+                    return Err(ProtocolError::WrongEpoch(epoch));
+                }
+                if values.len() != self.sharing_count {
+                    return Err(ProtocolError::WrongLength(values.len()));
+                }
+                if self
+                    .responses
+                    .iter()
+                    .any(|&(index, _)| index == participant)
+                {
+                    return Err(ProtocolError::Unexpected);
+                }
+                self.responses.push((participant, values));
+                if self.responses.len() < self.scheme.reconstruct_limit() {
+                    return Ok(None);
+                }
+                self.state = DealerState::Done;
+                let secrets = (0..self.sharing_count)
+                    .map(|sharing| {
+                        let shares: Vec<Share<S::Field>> = self
+                            .responses
+                            .iter()
+                            .map(|&(index, ref values)| Share::new(index, values[sharing].clone()))
+                            .collect();
+                        self.scheme.reconstruct(&shares)
+                    })
+                    .collect();
+                Ok(Some(secrets))
+            }
+        }
+    }
+
+    fn check(
+        &self,
+        session: u64,
+        participant: ShareIndex,
+        state: DealerState,
+    ) -> Result<(), ProtocolError> {
+        if session != self.session {
+            Err(ProtocolError::WrongSession(session))
+        } else if participant >= self.acknowledged.len() {
+            Err(ProtocolError::WrongParticipant(participant))
+        } else if self.state != state {
+            Err(ProtocolError::Unexpected)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Stage of a `Participant`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticipantState {
+    /// No shares were received yet.
+    AwaitingShares,
+    /// Shares are held, and no refresh is in progress.
+    Holding,
+    /// Shares are held, and some participants have not sent their sharings of zero
+    /// for the current epoch yet.
+    Refreshing,
+}
+
+/// Participant side of a session, holding one share of each sharing.
+pub struct Participant<'a, S: 'a + ThresholdScheme> {
+    scheme: &'a S,
+    index: ShareIndex,
+    session: Option<u64>,
+    epoch: u64,
+    values: Vec<Element<S>>,
+    zero_shares: Vec<Option<Vec<Element<S>>>>,
+}
+
+impl<'a, S> Participant<'a, S>
+where
+    S: 'a + ThresholdScheme,
+    Element<S>: Clone,
+{
+    /// Participant receiving the shares with `index`.
+    pub fn new(scheme: &'a S, index: ShareIndex) -> Participant<'a, S> {
+        assert!(index < scheme.share_count());
+        Participant {
+            scheme,
+            index,
+            session: None,
+            epoch: 0,
+            values: Vec::new(),
+            zero_shares: vec![None; scheme.share_count()],
+        }
+    }
+
+    pub fn state(&self) -> ParticipantState {
+        if self.session.is_none() {
+            ParticipantState::AwaitingShares
+        } else if self.zero_shares.iter().any(Option::is_some) {
+            ParticipantState::Refreshing
+        } else {
+            ParticipantState::Holding
+        }
+    }
+
+    /// Number of refreshes completed so far.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// The shares held, one per sharing, once received.
+    pub fn shares(&self) -> Option<&[Element<S>]> {
+        self.session.map(|_| &self.values[..])
+    }
+
+    /// Process a message from the dealer, returning the answer to send back.
+    ///
+    /// Requests for shares are rejected during a refresh, and for any epoch but the
+    /// current one.
+    pub fn handle(
+        &mut self,
+        message: Versioned<DealerMessage<Element<S>>>,
+    ) -> Result<Versioned<ParticipantMessage<Element<S>>>, ProtocolError> {
+        match message.open()? {
+            DealerMessage::Shares {
+                session,
+                participant,
+                values,
+            } => {
+                if self.session.is_some() {
+                    return Err(ProtocolError::Unexpected);
+                }
+                if participant != self.index {
+                    return Err(ProtocolError::WrongParticipant(participant));
+                }
+                self.session = Some(session);
+                self.values = values;
+                Ok(Versioned::new(ParticipantMessage::Ack {
+                    session,
+                    participant,
+                }))
+            }
+            DealerMessage::ShareRequest { session, epoch } => {
+                self.check_session(session)?;
+                if self.state() == ParticipantState::Refreshing {
+                    return Err(ProtocolError::Unexpected);
+                }
+                if epoch != self.epoch {
+                    return Err(ProtocolError::WrongEpoch(epoch));
+                }
+                Ok(Versioned::new(ParticipantMessage::ShareResponse {
+                    session,
+                    epoch,
+                    participant: self.index,
+                    values: self.values.clone(),
+                }))
+            }
+        }
+    }
+
+    fn check_session(&self, session: u64) -> Result<(), ProtocolError> {
+        match self.session {
+            None => Err(ProtocolError::Unexpected),
+            Some(current) if current != session => Err(ProtocolError::WrongSession(session)),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+impl<'a, S> Participant<'a, S>
+where
+    S: 'a + Refreshable,
+    Element<S>: Clone,
+{
+    /// Deal sharings of zero for the current epoch, returning the message to send
+    /// to each other participant.
+    ///
+    /// If the sharings of all other participants already arrived, this completes
+    /// the refresh.
+    pub fn start_refresh(
+        &mut self,
+    ) -> Result<Vec<Versioned<RefreshMessage<Element<S>>>>, ProtocolError> {
+        let session = self.session.ok_or(ProtocolError::Unexpected)?;
+        if self.zero_shares[self.index].is_some() {
+            return Err(ProtocolError::Unexpected);
+        }
+        let zero = self.scheme.zero_secret();
+        let mut values = vec![Vec::with_capacity(self.values.len()); self.zero_shares.len()];
+        for _ in 0..self.values.len() {
+            for share in self.scheme.share(&zero) {
+                values[share.index].push(share.value);
+            }
+        }
+        let mut outgoing = Vec::with_capacity(values.len() - 1);
+        for (to, values) in values.into_iter().enumerate() {
+            if to == self.index {
+                self.add_zero_shares(to, values);
+            } else {
+                outgoing.push(Versioned::new(RefreshMessage::ZeroShares {
+                    session,
+                    epoch: self.epoch,
+                    from: self.index,
+                    to,
+                    values,
+                }));
+            }
+        }
+        Ok(outgoing)
+    }
+
+    /// Process a message from another participant refreshing its shares, returning
+    /// whether the refresh is complete and the epoch incremented.
+    ///
+    /// Messages may arrive before this participant called `start_refresh`.
+    pub fn handle_refresh(
+        &mut self,
+        message: Versioned<RefreshMessage<Element<S>>>,
+    ) -> Result<bool, ProtocolError> {
+        let RefreshMessage::ZeroShares {
+            session,
+            epoch,
+            from,
+            to,
+            values,
+        } = message.open()?;
+        self.check_session(session)?;
+        if epoch != self.epoch {
+            return Err(ProtocolError::WrongEpoch(epoch));
+        }
+        if to != self.index {
+            return Err(ProtocolError::WrongParticipant(to));
+        }
+        if from >= self.zero_shares.len() || from == self.index {
+            return Err(ProtocolError::WrongParticipant(from));
+        }
+        if values.len() != self.values.len() {
+            return Err(ProtocolError::WrongLength(values.len()));
+        }
+        if self.zero_shares[from].is_some() {
+            return Err(ProtocolError::Unexpected);
+        }
+        Ok(self.add_zero_shares(from, values))
+    }
+
+    /// Record the shares of zero dealt by `from`, and add all of them to the shares
+    /// once every participant dealt its own.
+    fn add_zero_shares(&mut self, from: ShareIndex, values: Vec<Element<S>>) -> bool {
+        self.zero_shares[from] = Some(values);
+        if self.zero_shares.iter().any(Option::is_none) {
+            return false;
+        }
+        for zero_shares in &mut self.zero_shares {
+            self.values = self
+                .scheme
+                .add_shares(&self.values, &zero_shares.take().unwrap());
+        }
+        self.epoch += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Fp61, SliceEncode};
+    use packed::PSS_4_26_3;
+
+    /// Run a refresh among all `participants`, delivering every message.
+    fn refresh<S>(participants: &mut [Participant<S>])
+    where
+        S: Refreshable,
+        Element<S>: Clone,
+    {
+        let mut messages = Vec::new();
+        for participant in participants.iter_mut() {
+            messages.extend(participant.start_refresh().unwrap());
+        }
+        for message in messages {
+            let to = match message.message {
+                RefreshMessage::ZeroShares { to, .. } => to,
+            };
+            participants[to].handle_refresh(message).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            field: Fp61,
+        };
+        let mut dealer = Dealer::new(&tss, 7);
+        let mut participants: Vec<_> = (0..5).map(|i| Participant::new(&tss, i)).collect();
+        assert_eq!(participants[0].state(), ParticipantState::AwaitingShares);
+
+        for message in dealer.deal(&[11, 22]).unwrap() {
+            let index = match message.message {
+                DealerMessage::Shares { participant, .. } => participant,
+                _ => unreachable!(),
+            };
+            let ack = participants[index].handle(message).unwrap();
+            assert_eq!(dealer.state(), DealerState::AwaitingAcks);
+            assert_eq!(dealer.handle(ack), Ok(None));
+        }
+        assert_eq!(dealer.state(), DealerState::Dealt);
+        assert_eq!(participants[3].shares().unwrap().len(), 2);
+
+        let before = participants[3].shares().unwrap().to_vec();
+        refresh(&mut participants);
+        assert!(participants.iter().all(|p| p.epoch() == 1));
+        assert_eq!(participants[3].state(), ParticipantState::Holding);
+        assert_ne!(participants[3].shares().unwrap(), &before[..]);
+
+        let request = dealer.request_shares(1).unwrap();
+        for &i in &[4, 1] {
+            let response = participants[i].handle(request.clone()).unwrap();
+            assert_eq!(dealer.handle(response), Ok(None));
+        }
+        let response = participants[2].handle(request).unwrap();
+        assert_eq!(dealer.handle(response), Ok(Some(vec![11, 22])));
+        assert_eq!(dealer.state(), DealerState::Done);
+    }
+
+    #[test]
+    fn test_packed() {
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([1, 2, 3]);
+        let mut dealer = Dealer::new(pss, 1);
+        let mut participants: Vec<_> = (0..pss.share_count)
+            .map(|i| Participant::new(pss, i))
+            .collect();
+        let messages = dealer.deal(::std::slice::from_ref(&secrets)).unwrap();
+        for (participant, message) in participants.iter_mut().zip(messages) {
+            dealer.handle(participant.handle(message).unwrap()).unwrap();
+        }
+        refresh(&mut participants);
+        refresh(&mut participants);
+        assert_eq!(participants[0].epoch(), 2);
+
+        let request = dealer.request_shares(2).unwrap();
+        let mut result = None;
+        for participant in &mut participants[10..10 + pss.reconstruct_limit()] {
+            result = dealer
+                .handle(participant.handle(request.clone()).unwrap())
+                .unwrap();
+        }
+        assert_eq!(result, Some(vec![secrets]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 3,
+            field: Fp61,
+        };
+        let mut dealer = Dealer::new(&tss, 7);
+        assert_eq!(dealer.request_shares(0), Err(ProtocolError::Unexpected));
+        let mut messages = dealer.deal(&[5]).unwrap();
+        assert_eq!(dealer.deal(&[5]), Err(ProtocolError::Unexpected));
+
+        let mut participant = Participant::new(&tss, 1);
+        assert_eq!(
+            participant.handle(Versioned::new(DealerMessage::ShareRequest {
+                session: 7,
+                epoch: 0
+            })),
+            Err(ProtocolError::Unexpected)
+        );
+        assert_eq!(
+            participant.handle(messages[0].clone()),
+            Err(ProtocolError::WrongParticipant(0))
+        );
+        messages[1].version = 2;
+        assert_eq!(
+            participant.handle(messages[1].clone()),
+            Err(ProtocolError::UnsupportedVersion(2))
+        );
+        messages[1].version = PROTOCOL_VERSION;
+        let ack = participant.handle(messages[1].clone()).unwrap();
+        assert_eq!(
+            participant.handle(messages[1].clone()),
+            Err(ProtocolError::Unexpected)
+        );
+        assert_eq!(
+            participant.handle(Versioned::new(DealerMessage::ShareRequest {
+                session: 8,
+                epoch: 0
+            })),
+            Err(ProtocolError::WrongSession(8))
+        );
+        assert_eq!(
+            participant.handle(Versioned::new(DealerMessage::ShareRequest {
+                session: 7,
+                epoch: 1
+            })),
+            Err(ProtocolError::WrongEpoch(1))
+        );
+
+        assert_eq!(dealer.handle(ack.clone()), Ok(None));
+        assert_eq!(dealer.handle(ack), Err(ProtocolError::Unexpected));
+        assert_eq!(
+            dealer.handle(Versioned::new(ParticipantMessage::Ack {
+                session: 7,
+                participant: 3,
+            })),
+            Err(ProtocolError::WrongParticipant(3))
+        );
+        assert_eq!(
+            dealer.handle(Versioned::new(ParticipantMessage::ShareResponse {
+                session: 7,
+                epoch: 0,
+                participant: 0,
+                values: vec![1],
+            })),
+            Err(ProtocolError::Unexpected)
+        );
+
+        let zero_shares = |epoch, from, values| {
+            Versioned::new(RefreshMessage::ZeroShares {
+                session: 7,
+                epoch,
+                from,
+                to: 1,
+                values,
+            })
+        };
+        assert_eq!(
+            participant.handle_refresh(zero_shares(1, 0, vec![0])),
+            Err(ProtocolError::WrongEpoch(1))
+        );
+        assert_eq!(
+            participant.handle_refresh(zero_shares(0, 1, vec![0])),
+            Err(ProtocolError::WrongParticipant(1))
+        );
+        assert_eq!(
+            participant.handle_refresh(zero_shares(0, 0, vec![0, 0])),
+            Err(ProtocolError::WrongLength(2))
+        );
+        assert_eq!(
+            participant.handle_refresh(zero_shares(0, 0, vec![0])),
+            Ok(false)
+        );
+        assert_eq!(participant.state(), ParticipantState::Refreshing);
+        assert_eq!(
+            participant.handle_refresh(zero_shares(0, 0, vec![0])),
+            Err(ProtocolError::Unexpected)
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_during_refresh() {
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 3,
+            field: Fp61,
+        };
+        let mut dealer = Dealer::new(&tss, 7);
+        let mut participants: Vec<_> = (0..3).map(|i| Participant::new(&tss, i)).collect();
+        for message in dealer.deal(&[42]).unwrap() {
+            let index = match message.message {
+                DealerMessage::Shares { participant, .. } => participant,
+                _ => unreachable!(),
+            };
+            dealer
+                .handle(participants[index].handle(message).unwrap())
+                .unwrap();
+        }
+
+        // participants 0 and 1 complete a refresh, while participant 2 has not
+        // received the sharings of zero of participant 1 yet
+        let mut messages = Vec::new();
+        for participant in &mut participants {
+            messages.extend(participant.start_refresh().unwrap());
+        }
+        let mut pending = Vec::new();
+        for message in messages {
+            match message.message {
+                RefreshMessage::ZeroShares { from: 1, to: 2, .. } => pending.push(message),
+                RefreshMessage::ZeroShares { to, .. } => {
+                    participants[to].handle_refresh(message).unwrap();
+                }
+            }
+        }
+        assert_eq!(participants[0].epoch(), 1);
+        assert_eq!(participants[2].state(), ParticipantState::Refreshing);
+
+        // the old shares are gone, and the refreshing participant answers nothing
+        let request = dealer.request_shares(0).unwrap();
+        assert_eq!(
+            participants[0].handle(request.clone()),
+            Err(ProtocolError::WrongEpoch(0))
+        );
+        assert_eq!(
+            participants[2].handle(request),
+            Err(ProtocolError::Unexpected)
+        );
+
+        // responses must be for the requested epoch
+        let request = dealer.request_shares(1).unwrap();
+        let stale = Versioned::new(ParticipantMessage::ShareResponse {
+            session: 7,
+            epoch: 0,
+            participant: 2,
+            values: participants[2].shares().unwrap().to_vec(),
+        });
+        assert_eq!(dealer.handle(stale), Err(ProtocolError::WrongEpoch(0)));
+
+        // once participant 2 completes its refresh, all shares are of epoch 1
+        for message in pending {
+            assert_eq!(participants[2].handle_refresh(message), Ok(true));
+        }
+        let response = participants[0].handle(request.clone()).unwrap();
+        assert_eq!(dealer.handle(response), Ok(None));
+        let response = participants[2].handle(request).unwrap();
+        assert_eq!(dealer.handle(response), Ok(Some(vec![42])));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_json;
+
+        let message = Versioned::new(DealerMessage::Shares {
+            session: 3,
+            participant: 1,
+            values: vec![5_u64, 6],
+        });
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            json,
+            r#"{"version":1,"message":{"Shares":{"session":3,"participant":1,"values":[5,6]}}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Versioned<_>>(&json).unwrap(),
+            message
+        );
+    }
+}
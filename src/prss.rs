@@ -0,0 +1,230 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Pseudorandom secret sharing (PRSS), allowing parties to non-interactively derive
+//! Shamir shares of fresh random values from replicated keys.
+//!
+//! Following Cramer, Damgård and Ishai, a key is associated with every set `A` of
+//! `threshold` parties and given to all parties outside of `A`. The random value
+//! for a given counter is the sum over all sets `A` of the PRF evaluated under their
+//! keys, and each party derives its share by weighting the values it knows with
+//! the polynomial of degree `threshold` that is one at zero and vanishes on `A`.
+//!
+//! Since there are `share_count choose threshold` keys this is only practical for
+//! small numbers of parties.
+
+use fields::{Encode, Field};
+use rand::{ChaChaRng, Rng, SeedableRng};
+use shamir::ShamirSecretSharing;
+use share::ShareIndex;
+
+/// Pseudorandom function used for deriving random field elements from keys.
+pub trait Prf {
+    type Key: Clone;
+    type Output: Rng;
+
+    /// Sample a fresh key.
+    fn generate_key<R: Rng>(&self, rng: &mut R) -> Self::Key;
+
+    /// Pseudorandom generator determined by `key` and `input`, from which field
+    /// elements are sampled.
+    fn evaluate(&self, key: &Self::Key, input: u64) -> Self::Output;
+}
+
+/// The ChaCha20 stream cipher as a `Prf`, with the input selecting the stream.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChaChaPrf;
+
+impl Prf for ChaChaPrf {
+    type Key = [u32; 8];
+    type Output = ChaChaRng;
+
+    fn generate_key<R: Rng>(&self, rng: &mut R) -> [u32; 8] {
+        let mut key = [0; 8];
+        for word in key.iter_mut() {
+            *word = rng.next_u32();
+        }
+        key
+    }
+
+    fn evaluate(&self, key: &[u32; 8], input: u64) -> ChaChaRng {
+        let mut rng = ChaChaRng::from_seed(&key[..]);
+        rng.set_counter(0, input);
+        rng
+    }
+}
+
+/// Keys held by a single party.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrssKeys<K> {
+    /// Index of the party, matching the index of its Shamir shares.
+    pub party: ShareIndex,
+    /// Keys of all sets of `threshold` parties not containing `party`, together
+    /// with these sets.
+    pub keys: Vec<(Vec<ShareIndex>, K)>,
+}
+
+/// All sets of `size` parties out of `share_count`, in lexicographic order.
+fn subsets(share_count: usize, size: usize) -> Vec<Vec<ShareIndex>> {
+    let mut subsets = vec![];
+    if size > share_count {
+        return subsets;
+    }
+    let mut current: Vec<ShareIndex> = (0..size).collect();
+    loop {
+        subsets.push(current.clone());
+        // advance the rightmost index that can still move
+        let position = match (0..size)
+            .rev()
+            .find(|&i| current[i] < share_count - size + i)
+        {
+            Some(position) => position,
+            None => return subsets,
+        };
+        current[position] += 1;
+        for i in position + 1..size {
+            current[i] = current[i - 1] + 1;
+        }
+    }
+}
+
+/// Generate the keys of all parties of `tss`, as done by a trusted dealer.
+///
+/// Alternatively the keys may be agreed upon by the parties outside of each set,
+/// in which case the same key must be given to all of them.
+pub fn deal_keys<F, P, R>(
+    tss: &ShamirSecretSharing<F>,
+    prf: &P,
+    rng: &mut R,
+) -> Vec<PrssKeys<P::Key>>
+where
+    F: Field,
+    F::E: Clone,
+    P: Prf,
+    R: Rng,
+{
+    let sets: Vec<(Vec<ShareIndex>, P::Key)> = subsets(tss.share_count, tss.threshold)
+        .into_iter()
+        .map(|set| (set, prf.generate_key(rng)))
+        .collect();
+    (0..tss.share_count)
+        .map(|party| PrssKeys {
+            party,
+            keys: sets
+                .iter()
+                .filter(|(set, _)| !set.contains(&party))
+                .cloned()
+                .collect(),
+        })
+        .collect()
+}
+
+/// Shamir share for `keys.party` of the random value associated with `counter`.
+///
+/// All parties must use the same counter, and each counter must only be used once.
+pub fn derive_share<F, P>(
+    tss: &ShamirSecretSharing<F>,
+    prf: &P,
+    keys: &PrssKeys<P::Key>,
+    counter: u64,
+) -> F::E
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+    P: Prf,
+{
+    let field = &tss.field;
+    let point = field.encode(keys.party as u32 + 1);
+    keys.keys
+        .iter()
+        .map(|(set, key)| {
+            let value = field.sample(&mut prf.evaluate(key, counter));
+            // evaluate at our point the polynomial that is one at zero and zero on `set`
+            let weight = set.iter().fold(field.one(), |acc, &i| {
+                let other = field.encode(i as u32 + 1);
+                let factor = field.mul(field.sub(&other, &point), field.inv(&other));
+                field.mul(acc, factor)
+            });
+            field.mul(value, weight)
+        })
+        .fold(field.zero(), |acc, term| field.add(acc, term))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::NaturalPrimeField;
+
+    fn scheme() -> ShamirSecretSharing<NaturalPrimeField<i64>> {
+        ShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            field: NaturalPrimeField(2147483647),
+        }
+    }
+
+    #[test]
+    fn test_subsets() {
+        assert_eq!(
+            subsets(4, 2),
+            [[0, 1], [0, 2], [0, 3], [1, 2], [1, 3], [2, 3]]
+        );
+        assert_eq!(subsets(3, 0), [[] as [usize; 0]]);
+        assert_eq!(subsets(2, 3), [] as [Vec<usize>; 0]);
+    }
+
+    #[test]
+    fn test_deal_keys() {
+        let tss = scheme();
+        let mut rng = rand::OsRng::new().unwrap();
+        let keys = deal_keys(&tss, &ChaChaPrf, &mut rng);
+        assert_eq!(keys.len(), 5);
+        for party_keys in &keys {
+            // sets of 2 out of the other 4 parties
+            assert_eq!(party_keys.keys.len(), 6);
+            assert!(party_keys
+                .keys
+                .iter()
+                .all(|(set, _)| !set.contains(&party_keys.party)));
+        }
+    }
+
+    #[test]
+    fn test_derive_share() {
+        let tss = scheme();
+        let field = &tss.field;
+        let mut rng = rand::OsRng::new().unwrap();
+        let keys = deal_keys(&tss, &ChaChaPrf, &mut rng);
+
+        for counter in 0..3 {
+            let shares: Vec<i64> = keys
+                .iter()
+                .map(|k| derive_share(&tss, &ChaChaPrf, k, counter))
+                .collect();
+            let value = tss.reconstruct(&[0, 1, 2], &shares[0..3]);
+            assert_eq!(tss.reconstruct(&[2, 3, 4], &shares[2..5]), value);
+            assert_eq!(
+                tss.reconstruct(&[4, 1, 3], &[shares[4], shares[1], shares[3]]),
+                value
+            );
+
+            // the shared value is the sum of the PRF values of all sets
+            let mut sets: Vec<(Vec<usize>, [u32; 8])> =
+                keys.iter().flat_map(|k| k.keys.clone()).collect();
+            sets.sort_by(|a, b| a.0.cmp(&b.0));
+            sets.dedup_by(|a, b| a.0 == b.0);
+            assert_eq!(sets.len(), 10);
+            let expected = sets.iter().fold(0, |acc, (_, key)| {
+                let value = field.sample(&mut ChaChaPrf.evaluate(key, counter));
+                field.add(acc, value)
+            });
+            assert_eq!(value, expected);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Publicly verifiable sharing following Schoenmakers, with the shares encrypted
+//! under the public keys of their holders.
+//!
+//! The dealer publishes a `Dealing` holding commitments `a_j.H` to the coefficients
+//! of the sharing polynomial `p`, and the share of each holder in the exponent,
+//! `p(i + 1).Y_i`, encrypted ElGamal-style under its public key `Y_i = x_i.B`.
+//! Proofs of discrete log equality show that each encrypted share has the same
+//! discrete log as the commitment `p(i + 1).H` derived from the coefficients, so
+//! that anyone can check with `verify_dealing` that the holders received shares of
+//! a single polynomial of degree `threshold`, without learning any of them.
+//!
+//! Each holder recovers `p(i + 1).B` with `decrypt_share`, again along with a proof
+//! that it decrypted correctly, and any `threshold + 1` of these are combined into
+//! `secret.B`. The secret itself is never recovered, so it should be random, with
+//! `secret.B` used for instance to derive a key by hashing.
+
+use super::curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use super::curve25519_dalek::ristretto::RistrettoPoint;
+use super::{blinding_generator, scalar_from_hash, Scalar};
+use fields::ForwardSecureRng;
+use fields::{Field, ScalarField};
+use numtheory::mod_evaluate_polynomial;
+use rand::Rng;
+use sha2::{Digest, Sha512};
+use shamir::ShamirSecretSharing;
+
+/// Domain separation tag of the challenges of the proofs of discrete log equality.
+const DLEQ_DST: &[u8] = b"threshold-secret-sharing pvss dleq";
+
+/// Public key of `private_key`, under which shares are encrypted.
+pub fn public_key(private_key: &Scalar) -> RistrettoPoint {
+    RistrettoPoint::mul_base(private_key)
+}
+
+/// Non-interactive Chaum-Pedersen proof that two points have the same discrete log
+/// with respect to two bases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DleqProof {
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+fn dleq_challenge(
+    bases: [&RistrettoPoint; 2],
+    points: [&RistrettoPoint; 2],
+    nonce_commitments: [&RistrettoPoint; 2],
+) -> Scalar {
+    let mut hash = Sha512::new();
+    hash.update(DLEQ_DST);
+    for i in 0..2 {
+        hash.update(bases[i].compress().as_bytes());
+        hash.update(points[i].compress().as_bytes());
+        hash.update(nonce_commitments[i].compress().as_bytes());
+    }
+    scalar_from_hash(hash)
+}
+
+impl DleqProof {
+    /// Proof that `points[k] = log.bases[k]` for both `k`.
+    fn prove<R: Rng>(
+        bases: [&RistrettoPoint; 2],
+        points: [&RistrettoPoint; 2],
+        log: &Scalar,
+        rng: &mut R,
+    ) -> DleqProof {
+        let nonce = ScalarField::<Scalar>::new().sample(rng);
+        let challenge = dleq_challenge(bases, points, [&(bases[0] * nonce), &(bases[1] * nonce)]);
+        DleqProof {
+            challenge,
+            response: nonce - challenge * log,
+        }
+    }
+
+    fn verify(&self, bases: [&RistrettoPoint; 2], points: [&RistrettoPoint; 2]) -> bool {
+        let nonce_commitments = [
+            bases[0] * self.response + points[0] * self.challenge,
+            bases[1] * self.response + points[1] * self.challenge,
+        ];
+        self.challenge
+            == dleq_challenge(
+                bases,
+                points,
+                [&nonce_commitments[0], &nonce_commitments[1]],
+            )
+    }
+}
+
+/// Everything published by the dealer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dealing {
+    /// Commitment `a_j.H` to each coefficient of the sharing polynomial, starting
+    /// with the secret.
+    pub coefficient_commitments: Vec<RistrettoPoint>,
+    /// Share of each holder encrypted under its public key, in the order output by
+    /// `share`.
+    pub encrypted_shares: Vec<RistrettoPoint>,
+    /// Proof that each encrypted share matches its commitment.
+    pub proofs: Vec<DleqProof>,
+}
+
+impl Dealing {
+    /// Commitment `p(index + 1).H` to the share with rank `index`.
+    fn share_commitment(&self, index: usize) -> RistrettoPoint {
+        let point = Scalar::from(index as u64 + 1);
+        self.coefficient_commitments
+            .iter()
+            .rev()
+            .fold(RistrettoPoint::default(), |sum, commitment| {
+                sum * point + commitment
+            })
+    }
+}
+
+/// Share decrypted by its holder, with a proof that it was decrypted correctly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecryptedShare {
+    /// The basepoint multiplied by the share.
+    pub share: RistrettoPoint,
+    pub proof: DleqProof,
+}
+
+/// Share `secret` with `tss` between the holders of `public_keys`, one for each
+/// share, returning the dealing to be published.
+pub fn deal(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    public_keys: &[RistrettoPoint],
+    secret: &Scalar,
+) -> Dealing {
+    deal_with_rng(tss, public_keys, secret, &mut ForwardSecureRng::new())
+}
+
+/// Like `deal`, sampling the randomness from `rng`.
+pub fn deal_with_rng<R: Rng>(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    public_keys: &[RistrettoPoint],
+    secret: &Scalar,
+    rng: &mut R,
+) -> Dealing {
+    assert_eq!(public_keys.len(), tss.share_count);
+    let mut coefficients = vec![*secret];
+    coefficients.extend(tss.field.sample_with_replacement(tss.threshold, rng));
+    let h = blinding_generator();
+
+    let mut dealing = Dealing {
+        coefficient_commitments: coefficients.iter().map(|a| h * a).collect(),
+        encrypted_shares: Vec::with_capacity(tss.share_count),
+        proofs: Vec::with_capacity(tss.share_count),
+    };
+    for (index, public_key) in public_keys.iter().enumerate() {
+        let point = Scalar::from(index as u64 + 1);
+        let share = mod_evaluate_polynomial(&coefficients, point, &tss.field);
+        let encrypted_share = public_key * share;
+        let proof = DleqProof::prove(
+            [&h, public_key],
+            [&(h * share), &encrypted_share],
+            &share,
+            rng,
+        );
+        dealing.encrypted_shares.push(encrypted_share);
+        dealing.proofs.push(proof);
+    }
+    dealing
+}
+
+/// Whether `dealing` gives every holder of `public_keys` an encrypted share of a
+/// single polynomial of degree `threshold`.
+///
+/// This only needs public data, so that anyone can check the dealing.
+pub fn verify_dealing(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    public_keys: &[RistrettoPoint],
+    dealing: &Dealing,
+) -> bool {
+    if dealing.coefficient_commitments.len() != tss.threshold + 1
+        || public_keys.len() != tss.share_count
+        || dealing.encrypted_shares.len() != tss.share_count
+        || dealing.proofs.len() != tss.share_count
+    {
+        return false;
+    }
+    let h = blinding_generator();
+    public_keys.iter().enumerate().all(|(index, public_key)| {
+        dealing.proofs[index].verify(
+            [&h, public_key],
+            [
+                &dealing.share_commitment(index),
+                &dealing.encrypted_shares[index],
+            ],
+        )
+    })
+}
+
+/// Decrypt the share with rank `index` in `dealing` with `private_key`, or `None` if
+/// the dealing has no such share.
+pub fn decrypt_share(
+    dealing: &Dealing,
+    index: usize,
+    private_key: &Scalar,
+) -> Option<DecryptedShare> {
+    decrypt_share_with_rng(dealing, index, private_key, &mut ForwardSecureRng::new())
+}
+
+/// Like `decrypt_share`, sampling the nonce of the proof from `rng`.
+pub fn decrypt_share_with_rng<R: Rng>(
+    dealing: &Dealing,
+    index: usize,
+    private_key: &Scalar,
+    rng: &mut R,
+) -> Option<DecryptedShare> {
+    let encrypted_share = dealing.encrypted_shares.get(index)?;
+    let share = encrypted_share * private_key.invert();
+    let proof = DleqProof::prove(
+        [&RISTRETTO_BASEPOINT_POINT, &share],
+        [&public_key(private_key), encrypted_share],
+        private_key,
+        rng,
+    );
+    Some(DecryptedShare { share, proof })
+}
+
+/// Whether `share` is the correct decryption of the share with rank `index` in
+/// `dealing` by the holder of `public_key`, which is false if the dealing has no
+/// such share.
+pub fn verify_decrypted_share(
+    dealing: &Dealing,
+    index: usize,
+    public_key: &RistrettoPoint,
+    share: &DecryptedShare,
+) -> bool {
+    dealing
+        .encrypted_shares
+        .get(index)
+        .is_some_and(|encrypted_share| {
+            share.proof.verify(
+                [&RISTRETTO_BASEPOINT_POINT, &share.share],
+                [public_key, encrypted_share],
+            )
+        })
+}
+
+/// Combine the decrypted shares with the given `indices` into `secret.B`.
+///
+/// Incorrect decryptions yield a wrong result, so they should be checked with
+/// `verify_decrypted_share` first when holders may misbehave.
+pub fn combine(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    indices: &[usize],
+    shares: &[DecryptedShare],
+) -> RistrettoPoint {
+    let shares: Vec<RistrettoPoint> = shares.iter().map(|share| share.share).collect();
+    ::threshold_sig::combine_in_exponent(tss, indices, &shares)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use threshold_sig::ed25519;
+
+    fn holders(count: usize) -> (Vec<Scalar>, Vec<RistrettoPoint>) {
+        let private_keys: Vec<Scalar> = (0..count as u64).map(|i| Scalar::from(1000 + i)).collect();
+        let public_keys = private_keys.iter().map(public_key).collect();
+        (private_keys, public_keys)
+    }
+
+    #[test]
+    fn test_deal() {
+        let tss = ed25519::scheme(2, 5);
+        let (private_keys, public_keys) = holders(5);
+        let secret = Scalar::from(1234_u64);
+        let dealing = deal(&tss, &public_keys, &secret);
+        assert!(verify_dealing(&tss, &public_keys, &dealing));
+
+        let indices = [4, 0, 2];
+        let shares: Vec<DecryptedShare> = indices
+            .iter()
+            .map(|&i| decrypt_share(&dealing, i, &private_keys[i]).unwrap())
+            .collect();
+        for (&i, share) in indices.iter().zip(&shares) {
+            assert!(verify_decrypted_share(&dealing, i, &public_keys[i], share));
+        }
+        assert_eq!(combine(&tss, &indices, &shares), public_key(&secret));
+
+        // a malformed dealing is rejected rather than panicking
+        let mut truncated = dealing.clone();
+        truncated.encrypted_shares.truncate(2);
+        assert_eq!(decrypt_share(&truncated, 2, &private_keys[2]), None);
+        assert!(!verify_decrypted_share(
+            &truncated,
+            2,
+            &public_keys[2],
+            &shares[2]
+        ));
+    }
+
+    #[test]
+    fn test_cheating_dealer() {
+        let tss = ed25519::scheme(2, 5);
+        let (_, public_keys) = holders(5);
+        let dealing = deal(&tss, &public_keys, &Scalar::from(1234_u64));
+
+        // an encrypted share off the polynomial
+        let mut forged = dealing.clone();
+        forged.encrypted_shares[3] += public_keys[3];
+        assert!(!verify_dealing(&tss, &public_keys, &forged));
+
+        // commitments to a polynomial of higher degree
+        let mut forged = dealing.clone();
+        forged.coefficient_commitments.push(blinding_generator());
+        assert!(!verify_dealing(&tss, &public_keys, &forged));
+
+        // shares encrypted under other keys
+        let (_, other_keys) = holders(6);
+        assert!(!verify_dealing(&tss, &other_keys[1..], &dealing));
+        assert!(!verify_dealing(
+            &ed25519::scheme(2, 6),
+            &other_keys,
+            &dealing
+        ));
+    }
+
+    #[test]
+    fn test_cheating_holder() {
+        let tss = ed25519::scheme(1, 3);
+        let (private_keys, public_keys) = holders(3);
+        let dealing = deal(&tss, &public_keys, &Scalar::from(1234_u64));
+
+        // decrypting with the wrong key
+        let share = decrypt_share(&dealing, 1, &private_keys[2]).unwrap();
+        assert!(!verify_decrypted_share(
+            &dealing,
+            1,
+            &public_keys[1],
+            &share
+        ));
+
+        // passing off the share of another holder
+        let share = decrypt_share(&dealing, 2, &private_keys[2]).unwrap();
+        assert!(verify_decrypted_share(&dealing, 2, &public_keys[2], &share));
+        assert!(!verify_decrypted_share(
+            &dealing,
+            1,
+            &public_keys[1],
+            &share
+        ));
+    }
+}
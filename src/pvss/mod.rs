@@ -0,0 +1,334 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Publicly verifiable sharing of scalars, where the dealer proves that all shares
+//! lie on a single polynomial of degree at most `threshold`.
+//!
+//! The dealer publishes a `PublicSharing` holding a Pedersen commitment `s.B + t.H`
+//! to every share `s`, where `H` is a second generator of the Ristretto group whose
+//! discrete log nobody knows and `t` a random blinding. Each holder privately
+//! receives its share together with its blinding as a `BlindedShare`.
+//!
+//! The proof follows the low-degree test of SCRAPE: the shares of a polynomial of
+//! degree at most `threshold` are orthogonal to every codeword of the dual
+//! Reed-Solomon code. A codeword derived from the commitments by Fiat-Shamir thus
+//! turns the sum of the commitments weighted by it into a multiple of `H` alone,
+//! and the dealer proves knowledge of its discrete log with a Schnorr proof, which
+//! it could not do if the `B` components did not cancel out. Anyone can check the
+//! proof from the public sharing, while each holder additionally checks that its
+//! share opens its commitment.
+//!
+//! The `encrypted` module instead implements the scheme of Schoenmakers, where the
+//! shares themselves are published, encrypted under the public keys of their
+//! holders.
+
+extern crate curve25519_dalek;
+
+pub mod encrypted;
+
+use self::curve25519_dalek::ristretto::RistrettoPoint;
+use fields::ForwardSecureRng;
+use fields::{Field, ScalarField};
+use rand::Rng;
+use sha2::{Digest, Sha512};
+use shamir::ShamirSecretSharing;
+
+pub use self::curve25519_dalek::Scalar;
+
+/// Domain separation tag of the generator `H`.
+const GENERATOR_DST: &[u8] = b"threshold-secret-sharing pvss generator";
+/// Domain separation tag of the dual codeword.
+const CODEWORD_DST: &[u8] = b"threshold-secret-sharing pvss codeword";
+/// Domain separation tag of the challenge of the Schnorr proof.
+const CHALLENGE_DST: &[u8] = b"threshold-secret-sharing pvss challenge";
+
+/// Second generator `H` of the commitments, hashed to the group so that its
+/// discrete log with respect to the basepoint is unknown.
+pub fn blinding_generator() -> RistrettoPoint {
+    let mut bytes = [0; 64];
+    bytes.copy_from_slice(&Sha512::digest(GENERATOR_DST));
+    RistrettoPoint::from_uniform_bytes(&bytes)
+}
+
+/// Pedersen commitment `value.B + blinding.H`.
+pub fn commit(value: &Scalar, blinding: &Scalar) -> RistrettoPoint {
+    RistrettoPoint::mul_base(value) + blinding_generator() * blinding
+}
+
+/// Share sent privately to a holder, along with the blinding of its commitment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindedShare {
+    pub value: Scalar,
+    pub blinding: Scalar,
+}
+
+/// Schnorr proof of knowledge of the discrete log with respect to `H` of the
+/// commitments weighted by the dual codeword.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DealerProof {
+    /// The generator `H` multiplied by the nonce of the proof.
+    pub nonce_commitment: RistrettoPoint,
+    pub response: Scalar,
+}
+
+/// Everything published by the dealer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicSharing {
+    /// Commitment to each share, in the order output by `share`.
+    pub share_commitments: Vec<RistrettoPoint>,
+    pub proof: DealerProof,
+}
+
+fn scalar_from_hash(hash: Sha512) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&hash.finalize().into())
+}
+
+/// Dual codeword derived from the parameters and the commitments, orthogonal to
+/// the shares of every polynomial of degree at most `threshold`.
+///
+/// Its entries are `w_i.m(i)` at the points `i` of the shares, where `w_i` are
+/// their barycentric weights and `m` a polynomial of degree
+/// `share_count - threshold - 2` with the powers of a hashed challenge as
+/// coefficients. The values of the product of `m` with the sharing polynomial
+/// weighted by `w_i` sum to its coefficient of degree `share_count - 1`, which is
+/// zero.
+fn codeword(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    commitments: &[RistrettoPoint],
+) -> Vec<Scalar> {
+    let mut hash = Sha512::new();
+    hash.update(CODEWORD_DST);
+    hash.update((tss.threshold as u64).to_le_bytes());
+    hash.update((tss.share_count as u64).to_le_bytes());
+    for commitment in commitments {
+        hash.update(commitment.compress().as_bytes());
+    }
+    let challenge = scalar_from_hash(hash);
+
+    let points: Vec<Scalar> = (1..tss.share_count as u64 + 1).map(Scalar::from).collect();
+    let terms = tss.share_count.saturating_sub(tss.threshold + 1);
+    points
+        .iter()
+        .map(|x| {
+            let weight = points
+                .iter()
+                .filter(|&y| y != x)
+                .fold(Scalar::ONE, |product, y| product * (x - y))
+                .invert();
+            // m(x) is the sum of the powers of challenge.x
+            let step = challenge * x;
+            let (m, _) = (0..terms).fold((Scalar::ZERO, Scalar::ONE), |(sum, power), _| {
+                (sum + power, power * step)
+            });
+            weight * m
+        })
+        .collect()
+}
+
+/// Challenge of the Schnorr proof, binding it to the dual codeword and hence to the
+/// commitments.
+fn challenge(codeword: &[Scalar], nonce_commitment: &RistrettoPoint) -> Scalar {
+    let mut hash = Sha512::new();
+    hash.update(CHALLENGE_DST);
+    for weight in codeword {
+        hash.update(weight.as_bytes());
+    }
+    hash.update(nonce_commitment.compress().as_bytes());
+    scalar_from_hash(hash)
+}
+
+impl PublicSharing {
+    /// Whether the commitments are to the shares of a single polynomial of degree
+    /// at most `threshold`, as shown by the proof.
+    ///
+    /// This only needs public data, so that anyone can check the sharing.
+    pub fn verify(&self, tss: &ShamirSecretSharing<ScalarField<Scalar>>) -> bool {
+        if self.share_commitments.len() != tss.share_count {
+            return false;
+        }
+        let codeword = codeword(tss, &self.share_commitments);
+        let combined: RistrettoPoint = self
+            .share_commitments
+            .iter()
+            .zip(&codeword)
+            .map(|(commitment, weight)| commitment * weight)
+            .sum();
+        let challenge = challenge(&codeword, &self.proof.nonce_commitment);
+        blinding_generator() * self.proof.response
+            == self.proof.nonce_commitment + combined * challenge
+    }
+
+    /// Whether `share` opens the commitment to the share with rank `index`, and the
+    /// sharing as a whole is valid.
+    ///
+    /// This is false for an `index` that is not that of a share of `tss`.
+    pub fn verify_share(
+        &self,
+        tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+        index: usize,
+        share: &BlindedShare,
+    ) -> bool {
+        self.verify(tss)
+            && self
+                .share_commitments
+                .get(index)
+                .is_some_and(|commitment| *commitment == commit(&share.value, &share.blinding))
+    }
+
+    /// Reconstruct the secret from the shares with the given `indices`, or `None` if
+    /// any of them does not open its commitment or the indices are rejected by
+    /// `try_reconstruct`.
+    pub fn reconstruct(
+        &self,
+        tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+        indices: &[usize],
+        shares: &[BlindedShare],
+    ) -> Option<Scalar> {
+        for (&index, share) in indices.iter().zip(shares) {
+            if *self.share_commitments.get(index)? != commit(&share.value, &share.blinding) {
+                return None;
+            }
+        }
+        let values: Vec<Scalar> = shares.iter().map(|share| share.value).collect();
+        tss.try_reconstruct(indices, &values).ok()
+    }
+}
+
+/// Share `secret` with `tss`, returning the public sharing to be published along
+/// with the blinded shares to be sent privately to each holder.
+pub fn deal(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    secret: &Scalar,
+) -> (PublicSharing, Vec<BlindedShare>) {
+    deal_with_rng(tss, secret, &mut ForwardSecureRng::new())
+}
+
+/// Like `deal`, sampling the randomness from `rng`.
+pub fn deal_with_rng<R: Rng>(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    secret: &Scalar,
+    rng: &mut R,
+) -> (PublicSharing, Vec<BlindedShare>) {
+    let shares: Vec<BlindedShare> = tss
+        .share_with_rng(*secret, rng)
+        .into_iter()
+        .map(|value| BlindedShare {
+            value,
+            blinding: tss.field.sample(rng),
+        })
+        .collect();
+    let share_commitments: Vec<RistrettoPoint> = shares
+        .iter()
+        .map(|share| commit(&share.value, &share.blinding))
+        .collect();
+
+    // the weighted commitments sum to `combined_blinding.H`, since the values
+    // cancel out
+    let codeword = codeword(tss, &share_commitments);
+    let combined_blinding: Scalar = shares
+        .iter()
+        .zip(&codeword)
+        .map(|(share, weight)| share.blinding * weight)
+        .sum();
+    let nonce = tss.field.sample(rng);
+    let nonce_commitment = blinding_generator() * nonce;
+    let challenge = challenge(&codeword, &nonce_commitment);
+    let proof = DealerProof {
+        nonce_commitment,
+        response: nonce + challenge * combined_blinding,
+    };
+    (
+        PublicSharing {
+            share_commitments,
+            proof,
+        },
+        shares,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use threshold_sig::ed25519;
+
+    #[test]
+    fn test_deal() {
+        let tss = ed25519::scheme(2, 6);
+        let secret = Scalar::from(1234_u64);
+        let (sharing, shares) = deal(&tss, &secret);
+        assert!(sharing.verify(&tss));
+        for (index, share) in shares.iter().enumerate() {
+            assert!(sharing.verify_share(&tss, index, share));
+        }
+        assert!(!sharing.verify_share(&tss, 0, &shares[1]));
+        assert_eq!(
+            sharing.reconstruct(&tss, &[1, 3, 5], &[shares[1], shares[3], shares[5]]),
+            Some(secret)
+        );
+        assert_eq!(
+            sharing.reconstruct(&tss, &[1, 3, 5], &[shares[1], shares[5], shares[3]]),
+            None
+        );
+
+        // untrusted indices are rejected rather than panicking
+        assert!(!sharing.verify_share(&tss, 6, &shares[0]));
+        assert_eq!(
+            sharing.reconstruct(&tss, &[1, 3, 6], &[shares[1], shares[3], shares[5]]),
+            None
+        );
+        assert_eq!(
+            sharing.reconstruct(&tss, &[1, 3], &[shares[1], shares[3], shares[5]]),
+            None
+        );
+
+        // the proof only holds for its own parameters
+        assert!(!sharing.verify(&ed25519::scheme(3, 6)));
+        assert!(!sharing.verify(&ed25519::scheme(2, 5)));
+    }
+
+    #[test]
+    fn test_cheating_dealer() {
+        let tss = ed25519::scheme(2, 6);
+        let (sharing, mut shares) = deal(&tss, &Scalar::from(1234_u64));
+
+        // changing a single share moves it off the polynomial
+        shares[4].value += Scalar::ONE;
+        let mut forged = sharing.clone();
+        forged.share_commitments[4] = commit(&shares[4].value, &shares[4].blinding);
+        assert!(!forged.verify(&tss));
+        assert!(!forged.verify_share(&tss, 4, &shares[4]));
+
+        // a dealer sharing a polynomial of higher degree cannot prove it, even when
+        // following the protocol otherwise
+        let wide = ed25519::scheme(3, 6);
+        let (sharing_wide, shares_wide) = deal(&wide, &Scalar::from(1234_u64));
+        let codeword = codeword(&tss, &sharing_wide.share_commitments);
+        let combined_blinding: Scalar = shares_wide
+            .iter()
+            .zip(&codeword)
+            .map(|(share, weight)| share.blinding * weight)
+            .sum();
+        let nonce = Scalar::from(5_u64);
+        let nonce_commitment = blinding_generator() * nonce;
+        let forged = PublicSharing {
+            share_commitments: sharing_wide.share_commitments,
+            proof: DealerProof {
+                nonce_commitment,
+                response: nonce + challenge(&codeword, &nonce_commitment) * combined_blinding,
+            },
+        };
+        assert!(!forged.verify(&tss));
+
+        // and the proof cannot be replayed with other commitments
+        let (other, _) = deal(&tss, &Scalar::from(1234_u64));
+        let mut forged = other.clone();
+        forged.proof = sharing.proof;
+        assert!(!forged.verify(&tss));
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Recovery of a lost Shamir share by a quorum of helpers, without any of them
+//! learning the secret or the recovered share.
+//!
+//! The lost share is the value of the sharing polynomial at the point of the
+//! missing participant, ie. a linear combination of the shares of the helpers with
+//! Lagrange coefficients. Each helper splits its term of this combination into
+//! random additive pieces, one for each helper (`recovery_subshares`). Each helper
+//! then sums the pieces it received (`combine_recovery_subshares`), which reveals
+//! nothing since they are blinded by the pieces of the others, and sends the sum to
+//! the missing participant, who adds these up (`recover_share_for`).
+//!
+//! The same protocol issues shares to new participants, at a fresh index for the
+//! Shamir scheme or at an unused index for the packed scheme; see `Committee`.
+
+use fields::ForwardSecureRng;
+use fields::{Encode, Field};
+use numtheory::LagrangeConstants;
+use packed::PackedSecretSharing;
+use shamir::ShamirSecretSharing;
+use share::index_u32;
+
+/// Split `term` into `count` random pieces summing to it.
+fn blinded_pieces<F: Field>(field: &F, term: F::E, count: usize, position: usize) -> Vec<F::E> {
+    let mut rng = ForwardSecureRng::new();
+    let mut pieces = field.sample_with_replacement(count - 1, &mut rng);
+    let sum = pieces
+        .iter()
+        .fold(field.zero(), |acc, piece| field.add(acc, piece));
+    pieces.insert(position, field.sub(term, sum));
+    pieces
+}
+
+fn sum<F: Field>(field: &F, values: &[F::E]) -> F::E {
+    values
+        .iter()
+        .fold(field.zero(), |acc, value| field.add(acc, value))
+}
+
+impl<F> ShamirSecretSharing<F>
+where
+    F: Field,
+    F: Encode<u32>,
+    F::E: Clone,
+{
+    /// First step of recovering the share of `missing_index`, run by the helper with
+    /// index `helper_indices[position]` holding `share`.
+    ///
+    /// Returns one piece for each helper, in the order of `helper_indices`, with the
+    /// piece at `position` being kept by this helper.
+    pub fn recovery_subshares(
+        &self,
+        missing_index: usize,
+        helper_indices: &[usize],
+        position: usize,
+        share: &F::E,
+    ) -> Vec<F::E> {
+        self.check_helpers(missing_index, helper_indices);
+        let field = &self.field;
+        let points: Vec<F::E> = helper_indices
+            .iter()
+            .map(|&i| field.encode(index_u32(i) + 1))
+            .collect();
+        let target = field.encode(index_u32(missing_index) + 1);
+        // our term is the share weighted by its Lagrange coefficient for the target
+        let constants = LagrangeConstants::compute(&target, &points, field);
+        let term = field.mul(share, &constants.constants()[position]);
+        blinded_pieces(field, term, helper_indices.len(), position)
+    }
+
+    /// Second step of recovering a share, run by each helper on the pieces it
+    /// received from all helpers (including itself); the result is sent to the
+    /// missing participant.
+    pub fn combine_recovery_subshares(&self, pieces: &[F::E]) -> F::E {
+        sum(&self.field, pieces)
+    }
+
+    /// Last step of recovering the share of `missing_index`, run by the missing
+    /// participant on the `helper_subshares` received from the helpers with
+    /// `helper_indices`.
+    pub fn recover_share_for(
+        &self,
+        missing_index: usize,
+        helper_indices: &[usize],
+        helper_subshares: &[F::E],
+    ) -> F::E {
+        self.check_helpers(missing_index, helper_indices);
+        assert_eq!(helper_indices.len(), helper_subshares.len());
+        self.combine_recovery_subshares(helper_subshares)
+    }
+
+    fn check_helpers(&self, missing_index: usize, helper_indices: &[usize]) {
+        assert!(helper_indices.len() >= self.reconstruct_limit());
+        assert!(!helper_indices.contains(&missing_index));
+    }
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field,
+    F: Encode<u32>,
+    F: Clone,
+    F::E: Clone,
+{
+    /// First step of recovering the share of `missing_index`, run by the helper with
+    /// index `helper_indices[position]` holding `share`; see the Shamir version.
+    pub fn recovery_subshares(
+        &self,
+        missing_index: u32,
+        helper_indices: &[u32],
+        position: usize,
+        share: &F::E,
+    ) -> Vec<F::E> {
+        self.check_helpers(missing_index, helper_indices);
+        let field = &self.field;
+        let positions = self.share_positions();
+        // the sharing polynomial is also known to be zero at one
+        let mut points: Vec<F::E> = helper_indices
+            .iter()
+            .map(|&i| positions[i as usize].clone())
+            .collect();
+        points.push(field.one());
+        let target = &positions[missing_index as usize];
+        let constants = LagrangeConstants::compute(target, &points, field);
+        let term = field.mul(share, &constants.constants()[position]);
+        blinded_pieces(field, term, helper_indices.len(), position)
+    }
+
+    /// Second step of recovering a share, run by each helper on the pieces it
+    /// received from all helpers (including itself).
+    pub fn combine_recovery_subshares(&self, pieces: &[F::E]) -> F::E {
+        sum(&self.field, pieces)
+    }
+
+    /// Last step of recovering the share of `missing_index`, run by the missing
+    /// participant on the `helper_subshares` received from the helpers with
+    /// `helper_indices`.
+    pub fn recover_share_for(
+        &self,
+        missing_index: u32,
+        helper_indices: &[u32],
+        helper_subshares: &[F::E],
+    ) -> F::E {
+        self.check_helpers(missing_index, helper_indices);
+        assert_eq!(helper_indices.len(), helper_subshares.len());
+        sum(&self.field, helper_subshares)
+    }
+
+    fn check_helpers(&self, missing_index: u32, helper_indices: &[u32]) {
+        assert!(helper_indices.len() >= self.reconstruct_limit());
+        assert!((missing_index as usize) < self.share_count);
+        assert!(!helper_indices.contains(&missing_index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use fields::NaturalPrimeField;
+    use packed::PSS_4_26_3;
+    use shamir::ShamirSecretSharing;
+
+    #[test]
+    fn test_recover_share() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(2147483647),
+        };
+        let shares = tss.share(1234);
+        let missing = 4;
+        let helpers = [5, 0, 2];
+
+        // pieces[j][k] is sent by helper j to helper k
+        let pieces: Vec<Vec<i64>> = helpers
+            .iter()
+            .enumerate()
+            .map(|(position, &i)| tss.recovery_subshares(missing, &helpers, position, &shares[i]))
+            .collect();
+        assert!(pieces.iter().all(|p| p.len() == helpers.len()));
+
+        let helper_subshares: Vec<i64> = (0..helpers.len())
+            .map(|k| {
+                let received: Vec<i64> = pieces.iter().map(|p| p[k]).collect();
+                tss.combine_recovery_subshares(&received)
+            })
+            .collect();
+        let recovered = tss.recover_share_for(missing, &helpers, &helper_subshares);
+        assert_eq!(recovered, shares[missing]);
+
+        // more helpers than needed
+        let helpers = [0, 1, 2, 3];
+        let helper_subshares: Vec<i64> = helpers
+            .iter()
+            .enumerate()
+            .map(|(position, &i)| tss.recovery_subshares(missing, &helpers, position, &shares[i]))
+            .fold(vec![0; 4], |acc, p| tss.add_shares(&acc, &p));
+        assert_eq!(
+            tss.recover_share_for(missing, &helpers, &helper_subshares),
+            shares[missing]
+        );
+    }
+
+    #[test]
+    fn test_recover_packed_share() {
+        let pss = &PSS_4_26_3;
+        let shares = pss.share(&[5, 6, 7]);
+        let missing = 20;
+        let helpers: Vec<u32> = (0..pss.reconstruct_limit() as u32).rev().collect();
+
+        let pieces: Vec<Vec<i64>> = helpers
+            .iter()
+            .enumerate()
+            .map(|(position, &i)| {
+                pss.recovery_subshares(missing, &helpers, position, &shares[i as usize])
+            })
+            .collect();
+        let helper_subshares: Vec<i64> = (0..helpers.len())
+            .map(|k| {
+                let received: Vec<i64> = pieces.iter().map(|p| p[k]).collect();
+                pss.combine_recovery_subshares(&received)
+            })
+            .collect();
+        let recovered = pss.recover_share_for(missing, &helpers, &helper_subshares);
+        assert_eq!(recovered, shares[missing as usize]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_few_helpers() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(41),
+        };
+        tss.recovery_subshares(4, &[0, 1], 0, &7);
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Galois rings `GR(2^64, d)`.
+
+use rand;
+use std::borrow::Borrow;
+
+use super::Ring;
+
+/// Polynomials irreducible over GF(2) of degrees 1 to 16, as bitmasks including the
+/// leading term.
+const IRREDUCIBLE: [u32; 16] = [
+    0x2, 0x7, 0xb, 0x13, 0x25, 0x43, 0x83, 0x11b, 0x211, 0x409, 0x805, 0x1053, 0x201b, 0x4443,
+    0x8003, 0x1100b,
+];
+
+/// Number of Newton iterations lifting an inverse modulo 2 to one modulo `2^64`,
+/// each doubling the number of correct bits.
+const NEWTON_ITERATIONS: usize = 6;
+
+/// Galois ring `GR(2^64, d) = Z_{2^64}[X] / (f(X))` for a monic `f` of degree `d`
+/// that is irreducible modulo 2.
+///
+/// Elements are vectors of `d` coefficients modulo `2^64`, starting with the
+/// constant one, and values of `Z_{2^64}` are embedded as constants. Reduction
+/// modulo 2 maps the ring onto GF(2^d), and an element is a unit exactly when its
+/// image there is non-zero; the `2^d - 1` non-zero polynomials with binary
+/// coefficients thus form an exceptional sequence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GaloisRing64 {
+    /// Coefficients of `f`, without the leading one.
+    modulus: Vec<u64>,
+}
+
+impl GaloisRing64 {
+    /// Galois ring of degree `degree`, which must be between 1 and 16.
+    pub fn new(degree: usize) -> GaloisRing64 {
+        assert!(
+            degree >= 1 && degree <= IRREDUCIBLE.len(),
+            "unsupported degree"
+        );
+        let f = IRREDUCIBLE[degree - 1];
+        GaloisRing64 {
+            modulus: (0..degree).map(|i| ((f >> i) & 1) as u64).collect(),
+        }
+    }
+
+    /// Galois ring of the smallest degree with an exceptional sequence of
+    /// `share_count` points, for sharing among that many parties.
+    pub fn for_share_count(share_count: usize) -> GaloisRing64 {
+        let degree = (1..IRREDUCIBLE.len() + 1)
+            .find(|&d| share_count < 1 << d)
+            .expect("too many shares");
+        GaloisRing64::new(degree)
+    }
+
+    /// Degree `d` of the extension.
+    pub fn degree(&self) -> usize {
+        self.modulus.len()
+    }
+
+    /// Element representing `x` of `Z_{2^64}`.
+    pub fn embed(&self, x: u64) -> Vec<u64> {
+        let mut e = vec![0; self.degree()];
+        e[0] = x;
+        e
+    }
+
+    /// Constant coefficient of `e`, which is the value it represents when it lies in
+    /// `Z_{2^64}`.
+    pub fn project<E: Borrow<Vec<u64>>>(&self, e: E) -> u64 {
+        e.borrow()[0]
+    }
+
+    fn pow(&self, a: &[u64], mut e: u64) -> Vec<u64> {
+        let mut x = a.to_vec();
+        let mut acc = self.one();
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = self.mul(&acc, &x);
+            }
+            x = self.mul(&x, &x);
+            e >>= 1;
+        }
+        acc
+    }
+}
+
+impl Ring for GaloisRing64 {
+    type E = Vec<u64>;
+
+    fn zero(&self) -> Self::E {
+        vec![0; self.degree()]
+    }
+
+    fn one(&self) -> Self::E {
+        self.embed(1)
+    }
+
+    fn add<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        a.borrow()
+            .iter()
+            .zip(b.borrow())
+            .map(|(x, y)| x.wrapping_add(*y))
+            .collect()
+    }
+
+    fn sub<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        a.borrow()
+            .iter()
+            .zip(b.borrow())
+            .map(|(x, y)| x.wrapping_sub(*y))
+            .collect()
+    }
+
+    fn mul<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        let d = self.degree();
+        let mut product = vec![0_u64; 2 * d - 1];
+        for (i, x) in a.borrow().iter().enumerate() {
+            for (j, y) in b.borrow().iter().enumerate() {
+                product[i + j] = product[i + j].wrapping_add(x.wrapping_mul(*y));
+            }
+        }
+        // X^d = -(f_0 + f_1 X + .. + f_{d-1} X^{d-1})
+        for i in (d..2 * d - 1).rev() {
+            let c = product[i];
+            for (j, f) in self.modulus.iter().enumerate() {
+                product[i - d + j] = product[i - d + j].wrapping_sub(c.wrapping_mul(*f));
+            }
+        }
+        product.truncate(d);
+        product
+    }
+
+    fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool {
+        lhs.borrow() == rhs.borrow()
+    }
+
+    /// Inverse modulo 2 by exponentiation in GF(2^d), lifted by Newton iteration.
+    fn unit_inv<A: Borrow<Self::E>>(&self, a: A) -> Option<Self::E> {
+        let a = a.borrow();
+        if a.iter().all(|x| x & 1 == 0) {
+            return None;
+        }
+        // a^(2^d - 1) = 1 modulo 2
+        let mut x = self.pow(a, (1 << self.degree()) - 2);
+        let two = self.embed(2);
+        for _ in 0..NEWTON_ITERATIONS {
+            x = self.mul(&x, self.sub(&two, self.mul(a, &x)));
+        }
+        debug_assert_eq!(self.mul(a, &x), self.one());
+        Some(x)
+    }
+
+    /// The non-zero polynomials with binary coefficients, in the order given by
+    /// reading their coefficients as the bits of `1, 2, .., len`.
+    fn exceptional_sequence(&self, len: usize) -> Option<Vec<Self::E>> {
+        let d = self.degree();
+        if len >= 1 << d {
+            return None;
+        }
+        Some(
+            (1..len + 1)
+                .map(|i| (0..d).map(|j| ((i >> j) & 1) as u64).collect())
+                .collect(),
+        )
+    }
+
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        (0..self.degree()).map(|_| rng.next_u64()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::{ChaChaRng, SeedableRng};
+
+    /// Remainder of the division of `a` by `b` as polynomials over GF(2).
+    fn gf2_rem(mut a: u32, b: u32) -> u32 {
+        let db = 31 - b.leading_zeros();
+        while a != 0 && 31 - a.leading_zeros() >= db {
+            a ^= b << (31 - a.leading_zeros() - db);
+        }
+        a
+    }
+
+    #[test]
+    fn test_irreducible() {
+        for (i, &f) in IRREDUCIBLE.iter().enumerate() {
+            let degree = i + 1;
+            assert_eq!(31 - f.leading_zeros() as usize, degree);
+            // no factor of degree at least 1 and at most half the degree
+            let bound = 1 << (degree / 2 + 1);
+            assert!((2..bound).all(|g| gf2_rem(f, g) != 0));
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let ring = GaloisRing64::new(3);
+        let mut rng = ChaChaRng::from_seed(&[3]);
+        for _ in 0..20 {
+            let a = ring.sample(&mut rng);
+            let b = ring.sample(&mut rng);
+            let c = ring.sample(&mut rng);
+            assert_eq!(
+                ring.mul(ring.mul(&a, &b), &c),
+                ring.mul(&a, ring.mul(&b, &c))
+            );
+            assert_eq!(
+                ring.mul(&a, ring.add(&b, &c)),
+                ring.add(ring.mul(&a, &b), ring.mul(&a, &c))
+            );
+            assert_eq!(ring.mul(&a, ring.one()), a);
+            assert_eq!(ring.add(ring.sub(&a, &b), &b), a);
+        }
+        // X^3 = X + 1 modulo 2, here X^3 = -X - 1
+        let x = vec![0, 1, 0];
+        assert_eq!(ring.pow(&x, 3), vec![u64::MAX, u64::MAX, 0]);
+    }
+
+    #[test]
+    fn test_degree_one() {
+        // Z_{2^64} itself
+        let ring = GaloisRing64::new(1);
+        let a = ring.embed(u64::MAX - 4);
+        let b = ring.embed(12345);
+        assert_eq!(
+            ring.project(ring.mul(&a, &b)),
+            (u64::MAX - 4).wrapping_mul(12345)
+        );
+        assert_eq!(ring.exceptional_sequence(1), Some(vec![vec![1]]));
+        assert_eq!(ring.exceptional_sequence(2), None);
+    }
+
+    #[test]
+    fn test_unit_inv() {
+        let ring = GaloisRing64::new(4);
+        let mut rng = ChaChaRng::from_seed(&[4]);
+        for _ in 0..20 {
+            let a = ring.sample(&mut rng);
+            match ring.unit_inv(&a) {
+                Some(inverse) => assert_eq!(ring.mul(&a, inverse), ring.one()),
+                None => assert!(a.iter().all(|x| x % 2 == 0)),
+            }
+        }
+        assert_eq!(ring.unit_inv(ring.embed(2)), None);
+        assert_eq!(ring.unit_inv(ring.zero()), None);
+        let three = ring.embed(3);
+        let inverse = ring.unit_inv(&three).unwrap();
+        assert_eq!(ring.project(inverse).wrapping_mul(3), 1);
+    }
+
+    #[test]
+    fn test_exceptional_sequence() {
+        let ring = GaloisRing64::for_share_count(7);
+        assert_eq!(ring.degree(), 3);
+        let points = ring.exceptional_sequence(7).unwrap();
+        assert_eq!(points[4], vec![1, 0, 1]);
+        assert!(ring.exceptional_sequence(8).is_none());
+        for (i, x) in points.iter().enumerate() {
+            assert!(ring.unit_inv(x).is_some());
+            for y in &points[..i] {
+                assert!(ring.unit_inv(ring.sub(x, y)).is_some());
+            }
+        }
+        assert_eq!(GaloisRing64::for_share_count(8).degree(), 4);
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Secret sharing over rings such as `Z_{2^64}`, as used by SPDZ2k-style protocols.
+//!
+//! Shamir sharing only needs the differences between evaluation points to be
+//! invertible, so it carries over to any ring given an exceptional sequence: points
+//! whose pairwise differences, and differences with zero, are all units. `Z_{2^k}`
+//! itself only has one such non-zero point, so secrets are shared in the Galois ring
+//! `GR(2^64, d)`, an extension of degree `d` of `Z_{2^64}` with `2^d - 1` of them.
+//!
+//! Every `Field` is a `Ring`, so with both traits in scope calls such as
+//! `field.add(a, b)` are ambiguous. Import only one of them, or call the methods
+//! as `Ring::add(&field, a, b)`; `unit_inv` and `exceptional_sequence` are unique
+//! to `Ring` and can always be called directly.
+
+use rand;
+use std::borrow::Borrow;
+
+use fields::Field;
+
+mod galois;
+mod shamir;
+
+pub use self::galois::GaloisRing64;
+pub use self::shamir::RingShamirSecretSharing;
+
+/// Commutative ring, like `Field` but where only units can be inverted.
+pub trait Ring {
+    type E;
+
+    fn zero(&self) -> Self::E;
+
+    fn one(&self) -> Self::E;
+
+    fn add<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E;
+
+    fn sub<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E;
+
+    fn mul<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E;
+
+    fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool;
+
+    /// Inverse of `a`, or `None` if it is not a unit.
+    fn unit_inv<A: Borrow<Self::E>>(&self, a: A) -> Option<Self::E>;
+
+    /// `len` non-zero elements forming an exceptional set together with zero, ie.
+    /// such that the difference of any two of them is a unit, or `None` if the ring
+    /// has no such set of that size.
+    fn exceptional_sequence(&self, len: usize) -> Option<Vec<Self::E>>;
+
+    /// Uniformly random element.
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E;
+}
+
+impl<F: Field> Ring for F {
+    type E = F::E;
+
+    fn zero(&self) -> Self::E {
+        Field::zero(self)
+    }
+
+    fn one(&self) -> Self::E {
+        Field::one(self)
+    }
+
+    fn add<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        Field::add(self, a, b)
+    }
+
+    fn sub<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        Field::sub(self, a, b)
+    }
+
+    fn mul<A: Borrow<Self::E>, B: Borrow<Self::E>>(&self, a: A, b: B) -> Self::E {
+        Field::mul(self, a, b)
+    }
+
+    fn eq<L: Borrow<Self::E>, R: Borrow<Self::E>>(&self, lhs: L, rhs: R) -> bool {
+        Field::eq(self, lhs, rhs)
+    }
+
+    fn unit_inv<A: Borrow<Self::E>>(&self, a: A) -> Option<Self::E> {
+        if Field::eq(self, a.borrow(), Field::zero(self)) {
+            None
+        } else {
+            Some(Field::inv(self, a))
+        }
+    }
+
+    /// The points `1, 2, .., len`, as long as `len` is below the characteristic.
+    fn exceptional_sequence(&self, len: usize) -> Option<Vec<Self::E>> {
+        let mut points = Vec::with_capacity(len);
+        let mut point = Field::one(self);
+        for _ in 0..len {
+            if Field::eq(self, &point, Field::zero(self)) {
+                return None;
+            }
+            let next = Field::add(self, &point, Field::one(self));
+            points.push(point);
+            point = next;
+        }
+        Some(points)
+    }
+
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Self::E {
+        Field::sample(self, rng)
+    }
+}
+
+/// Lagrange interpolation at zero over a ring, for a polynomial defined by `points`
+/// and `values`.
+///
+/// The points must be non-zero with pairwise differences that are units, such as
+/// a subset of an exceptional sequence; `None` is returned otherwise.
+pub fn interpolate_at_zero<R>(ring: &R, points: &[R::E], values: &[R::E]) -> Option<R::E>
+where
+    R: Ring,
+{
+    assert_eq!(points.len(), values.len());
+    let mut acc = ring.zero();
+    for (i, (xi, yi)) in points.iter().zip(values).enumerate() {
+        // prod_{j != i} x_j / (x_j - x_i)
+        let mut numerator = ring.one();
+        let mut denominator = ring.one();
+        for (j, xj) in points.iter().enumerate() {
+            if i != j {
+                numerator = ring.mul(&numerator, xj);
+                denominator = ring.mul(&denominator, ring.sub(xj, xi));
+            }
+        }
+        let constant = ring.mul(numerator, ring.unit_inv(denominator)?);
+        acc = ring.add(acc, ring.mul(constant, yi));
+    }
+    Some(acc)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::NaturalPrimeField;
+
+    #[test]
+    fn test_field_as_ring() {
+        let field = NaturalPrimeField(5);
+        assert_eq!(field.unit_inv(2), Some(3));
+        assert_eq!(field.unit_inv(0), None);
+        assert_eq!(field.exceptional_sequence(4), Some(vec![1, 2, 3, 4]));
+        assert_eq!(field.exceptional_sequence(5), None);
+    }
+
+    #[test]
+    fn test_interpolate_at_zero() {
+        // p(x) = 3 + 2x + x^2 over Z_17
+        let field = NaturalPrimeField(17);
+        let points = [1, 2, 3];
+        let values = [6, 11, 18 % 17];
+        assert_eq!(interpolate_at_zero(&field, &points, &values), Some(3));
+        // repeated points have a non-invertible difference
+        assert_eq!(interpolate_at_zero(&field, &[1, 1], &[6, 6]), None);
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Shamir sharing over a ring, with shares at the points of an exceptional sequence.
+
+use fields::ForwardSecureRng;
+use rand::Rng;
+
+use super::{interpolate_at_zero, Ring};
+
+/// Parameters for the Shamir scheme over a ring, specifying privacy threshold and
+/// total number of shares.
+///
+/// The ring must have an exceptional sequence of `share_count` points, at which the
+/// shares are evaluated; for `GaloisRing64` this requires `share_count < 2^d`.
+///
+/// # Example:
+///
+/// ```
+///    use threshold_secret_sharing::*;
+///    let ring = GaloisRing64::for_share_count(5);
+///    let tss = RingShamirSecretSharing {
+///        threshold: 2,
+///        share_count: 5,
+///        ring: ring.clone(),
+///    };
+///
+///    let secret = u64::MAX - 7;
+///    let shares = tss.share(ring.embed(secret));
+///    let recovered = tss.reconstruct(&[0, 3, 4], &[shares[0].clone(), shares[3].clone(), shares[4].clone()]);
+///    assert_eq!(ring.project(recovered), secret);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RingShamirSecretSharing<R: Ring> {
+    /// Maximum number of shares that can be known without exposing the secret.
+    pub threshold: usize,
+    /// Number of shares to split the secret into.
+    pub share_count: usize,
+    /// Ring in which computation takes place.
+    pub ring: R,
+}
+
+impl<R> RingShamirSecretSharing<R>
+where
+    R: Ring,
+    R::E: Clone,
+{
+    /// Minimum number of shares required to reconstruct secret.
+    ///
+    /// For this scheme this is always `threshold + 1`.
+    pub fn reconstruct_limit(&self) -> usize {
+        self.threshold + 1
+    }
+
+    /// Evaluation points of the shares, in order.
+    ///
+    /// Panics if the ring has no exceptional sequence of `share_count` points.
+    pub fn share_points(&self) -> Vec<R::E> {
+        self.ring
+            .exceptional_sequence(self.share_count)
+            .expect("ring too small for share_count")
+    }
+
+    /// Generate `share_count` shares from `secret`.
+    pub fn share(&self, secret: R::E) -> Vec<R::E> {
+        let mut rng = ForwardSecureRng::new();
+        self.share_with_rng(secret, &mut rng)
+    }
+
+    /// Generate `share_count` shares from `secret`, sampling the polynomial with `rng`.
+    pub fn share_with_rng<G: Rng>(&self, secret: R::E, rng: &mut G) -> Vec<R::E> {
+        let mut coefficients = vec![secret];
+        coefficients.extend((0..self.threshold).map(|_| self.ring.sample(rng)));
+        self.share_points()
+            .iter()
+            .map(|point| {
+                // Horner's rule
+                coefficients.iter().rev().fold(self.ring.zero(), |acc, c| {
+                    self.ring.add(self.ring.mul(acc, point), c)
+                })
+            })
+            .collect()
+    }
+
+    /// Reconstruct `secret` from a large enough subset of the shares.
+    ///
+    /// `indices` are the ranks of the known shares as output by the `share` method,
+    /// while `values` are the actual values of these shares.
+    /// Both must have the same number of elements, and at least `reconstruct_limit`.
+    pub fn reconstruct(&self, indices: &[usize], shares: &[R::E]) -> R::E {
+        assert!(shares.len() == indices.len());
+        assert!(shares.len() >= self.reconstruct_limit());
+        let all_points = self.share_points();
+        let points: Vec<R::E> = indices.iter().map(|&i| all_points[i].clone()).collect();
+        interpolate_at_zero(&self.ring, &points, shares).expect("indices must be distinct")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::NaturalPrimeField;
+    use ring::GaloisRing64;
+
+    #[test]
+    fn test_galois_ring() {
+        let ring = GaloisRing64::for_share_count(10);
+        let tss = RingShamirSecretSharing {
+            threshold: 3,
+            share_count: 10,
+            ring: ring.clone(),
+        };
+        for &secret in &[0, 1, 1 << 63, u64::MAX] {
+            let shares = tss.share(ring.embed(secret));
+            assert_eq!(
+                ring.project(tss.reconstruct(&[0, 1, 2, 3], &shares[..4])),
+                secret
+            );
+            let indices = [9, 2, 5, 7, 0];
+            let subset: Vec<Vec<u64>> = indices.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(tss.reconstruct(&indices, &subset), ring.embed(secret));
+        }
+    }
+
+    #[test]
+    fn test_linearity() {
+        let ring = GaloisRing64::new(3);
+        let tss = RingShamirSecretSharing {
+            threshold: 2,
+            share_count: 7,
+            ring: ring.clone(),
+        };
+        let a = tss.share(ring.embed(u64::MAX));
+        let b = tss.share(ring.embed(5));
+        let sum: Vec<Vec<u64>> = a.iter().zip(&b).map(|(x, y)| ring.add(x, y)).collect();
+        let indices = [1, 4, 6];
+        let subset: Vec<Vec<u64>> = indices.iter().map(|&i| sum[i].clone()).collect();
+        assert_eq!(ring.project(tss.reconstruct(&indices, &subset)), 4);
+    }
+
+    #[test]
+    fn test_field() {
+        let tss = RingShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            ring: NaturalPrimeField(41),
+        };
+        let shares = tss.share(12);
+        assert_eq!(
+            tss.reconstruct(&[5, 0, 3], &[shares[5], shares[0], shares[3]]),
+            12
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ring too small")]
+    fn test_too_many_shares() {
+        let tss = RingShamirSecretSharing {
+            threshold: 2,
+            share_count: 8,
+            ring: GaloisRing64::new(3),
+        };
+        tss.share(tss.ring.zero());
+    }
+}
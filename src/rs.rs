@@ -0,0 +1,255 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Reed-Solomon erasure codes over the fields of this crate.
+//!
+//! A message of `k` symbols is read as the coefficients of a polynomial of degree
+//! less than `k`, and encoded as its values at the `n` powers of a root of unity
+//! `omega` of order `n` with one FFT. Any `k` symbols of the codeword determine the
+//! message, and decoding fills in the erased ones with the same FFT plans as the
+//! reconstruction of packed sharings.
+//!
+//! Conversely, the shares of a packed sharing preceded by a zero are a codeword of
+//! the code of length `share_count + 1` for `omega_shares` and dimension
+//! `reconstruct_limit + 1`, whose message is the sharing polynomial.
+
+use std::error::Error;
+use std::fmt;
+
+use fields::{Encode, Field};
+use numtheory::fft::{is_mixed_radix_len, mixed_radix_order, FftPlan};
+use numtheory::SubproductTree;
+
+/// Reed-Solomon code of a fixed length and dimension.
+///
+/// # Example:
+///
+/// ```
+///    use threshold_secret_sharing::*;
+///    use threshold_secret_sharing::rs::ReedSolomon;
+///    let field = NaturalPrimeField::new(433);
+///    // 354 is a root of unity of order 8
+///    let code = ReedSolomon::new(&field, &354, 8, 3);
+///    let codeword = code.encode(&[1, 2, 3]);
+///    // any 3 of the 8 symbols suffice
+///    let mut received: Vec<Option<i64>> = codeword.into_iter().map(Some).collect();
+///    for i in &[0, 2, 3, 5, 6] {
+///        received[*i] = None;
+///    }
+///    assert_eq!(code.decode(&received), Ok(vec![1, 2, 3]));
+/// ```
+pub struct ReedSolomon<F: Field> {
+    field: F,
+    omega: F::E,
+    dimension: usize,
+    plan: FftPlan<F>,
+}
+
+/// Failure to decode a codeword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Only this many symbols were received, fewer than the dimension of the code.
+    TooFewSymbols(usize),
+    /// The received symbols are not all part of the same codeword.
+    Inconsistent,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::TooFewSymbols(count) => {
+                write!(f, "only {} symbols received, not enough to decode", count)
+            }
+            DecodeError::Inconsistent => write!(f, "received symbols are inconsistent"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+impl<F> ReedSolomon<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Code of length `n` and dimension `k`, evaluating at the powers of `omega`.
+    ///
+    /// Panics unless `n` is of the form `2^a.3^b`, `omega` is a principal root of
+    /// unity of order `n`, and `k` is between one and `n`.
+    pub fn new(field: &F, omega: &F::E, n: usize, k: usize) -> ReedSolomon<F> {
+        assert!(is_mixed_radix_len(n), "length must be of the form 2^a.3^b");
+        assert!(
+            mixed_radix_order(field, omega, n as u32) == Some(n),
+            "omega must be a principal root of unity of order n"
+        );
+        assert!(
+            0 < k && k <= n,
+            "dimension must be between one and the length"
+        );
+        ReedSolomon {
+            field: field.clone(),
+            omega: omega.clone(),
+            dimension: k,
+            plan: FftPlan::new(field, n, omega),
+        }
+    }
+
+    /// Number `n` of symbols of a codeword.
+    pub fn length(&self) -> usize {
+        self.plan.len()
+    }
+
+    /// Number `k` of symbols of a message.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Codeword of the message `data`, which may be shorter than the dimension, in
+    /// which case it is padded with zeros.
+    pub fn encode(&self, data: &[F::E]) -> Vec<F::E> {
+        assert!(data.len() <= self.dimension);
+        let mut codeword = data.to_vec();
+        codeword.resize(self.length(), self.field.zero());
+        self.plan.forward_pruned(&mut codeword, data.len());
+        codeword
+    }
+
+    /// Message of the codeword of which the symbols in `received` are known, erased
+    /// symbols being `None`.
+    ///
+    /// Any received symbols beyond the dimension are checked against the others.
+    pub fn decode(&self, received: &[Option<F::E>]) -> Result<Vec<F::E>, DecodeError> {
+        let field = &self.field;
+        assert_eq!(received.len(), self.length());
+        let missing: Vec<usize> = (0..received.len())
+            .filter(|&i| received[i].is_none())
+            .collect();
+        let count = received.len() - missing.len();
+        if count < self.dimension {
+            return Err(DecodeError::TooFewSymbols(count));
+        }
+
+        let mut values: Vec<F::E> = received
+            .iter()
+            .map(|symbol| symbol.clone().unwrap_or_else(|| field.zero()))
+            .collect();
+        if !missing.is_empty() {
+            let missing_points: Vec<F::E> = missing
+                .iter()
+                .map(|&i| field.pow(&self.omega, i as u32))
+                .collect();
+            let tree = SubproductTree::with_root_of_unity(
+                &missing_points,
+                &self.omega,
+                self.length(),
+                field,
+            );
+            self.plan
+                .recover_erasures(&mut values, &missing, tree.product());
+        }
+
+        // the known symbols are kept, so they are consistent exactly when the
+        // completed codeword is one
+        self.plan.inverse(&mut values);
+        if values[self.dimension..]
+            .iter()
+            .any(|c| field.neq(c, field.zero()))
+        {
+            return Err(DecodeError::Inconsistent);
+        }
+        values.truncate(self.dimension);
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{MontgomeryField32, NaturalPrimeField, New, SliceDecode, SliceEncode};
+    use numtheory::mod_evaluate_polynomial;
+    use packed::PSS_4_26_3;
+
+    #[test]
+    fn test_erasures() {
+        let field = NaturalPrimeField::new(433);
+        let code = ReedSolomon::new(&field, &354, 8, 3);
+        let codeword = code.encode(&[1, 2, 3]);
+        assert_eq!(codeword.len(), 8);
+        assert_eq!(codeword[0], 6);
+        let mut received: Vec<Option<i64>> = codeword.iter().cloned().map(Some).collect();
+        assert_eq!(code.decode(&received), Ok(vec![1, 2, 3]));
+
+        received[1] = None;
+        received[4] = None;
+        received[7] = None;
+        assert_eq!(code.decode(&received), Ok(vec![1, 2, 3]));
+        // a wrong symbol is noticed as long as there is redundancy left
+        received[0] = Some(7);
+        assert_eq!(code.decode(&received), Err(DecodeError::Inconsistent));
+        received[0] = None;
+        received[2] = None;
+        assert_eq!(code.decode(&received), Ok(vec![1, 2, 3]));
+        received[3] = None;
+        assert_eq!(code.decode(&received), Err(DecodeError::TooFewSymbols(2)));
+    }
+
+    #[test]
+    fn test_montgomery() {
+        let field = MontgomeryField32::new(433);
+        let code = ReedSolomon::new(&field, &field.encode(17), 27, 10);
+        let data: Vec<u32> = (1..11).collect();
+        let codeword = code.encode(&field.encode_slice(&data[..]));
+        let erase = |keep: &dyn Fn(usize) -> bool| -> Vec<_> {
+            codeword
+                .iter()
+                .enumerate()
+                .map(|(i, symbol)| if keep(i) { Some(*symbol) } else { None })
+                .collect()
+        };
+        let received = erase(&|i| i % 3 == 0);
+        assert_eq!(
+            code.decode(&received).err(),
+            Some(DecodeError::TooFewSymbols(9))
+        );
+        let received = erase(&|i| i % 3 == 0 || i == 1);
+        let decoded = code.decode(&received).unwrap();
+        assert_eq!(field.decode_slice(decoded), data);
+    }
+
+    #[test]
+    fn test_packed_sharing() {
+        // shares of a packed sharing are a codeword once the zero at one is added
+        let pss = &PSS_4_26_3;
+        let code = ReedSolomon::new(
+            &pss.field,
+            &pss.omega_shares,
+            pss.share_count + 1,
+            pss.reconstruct_limit() + 1,
+        );
+        let mut received = vec![Some(0)];
+        received.extend(pss.share(&[5, 6, 7]).into_iter().map(Some));
+        for symbol in received.iter_mut().skip(8) {
+            *symbol = None;
+        }
+        let poly = code.decode(&received).unwrap();
+        let secrets: Vec<i64> = pss
+            .secret_positions()
+            .iter()
+            .map(|point| mod_evaluate_polynomial(&poly, point, &pss.field))
+            .collect();
+        assert_eq!(secrets, [5, 6, 7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "omega must be a principal root of unity")]
+    fn test_non_principal_root() {
+        let field = NaturalPrimeField::new(433);
+        ReedSolomon::new(&field, &field.pow(354, 2), 8, 3);
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Common interface of the field-based schemes, for code generic over them.
+
+use fields::Field;
+use share::Share;
+
+/// Threshold secret sharing scheme over a field, such as `ShamirSecretSharing` and
+/// `PackedSecretSharing`.
+pub trait ThresholdScheme {
+    /// Field in which computation takes place.
+    type Field: Field;
+    /// What is shared at once: a single element for Shamir and a vector of
+    /// `secret_count` elements for the packed scheme.
+    type Secret;
+
+    /// Number of shares generated by `share`.
+    fn share_count(&self) -> usize;
+
+    /// Minimum number of shares required to reconstruct a secret.
+    fn reconstruct_limit(&self) -> usize;
+
+    /// Generate `share_count` shares from `secret`.
+    fn share(&self, secret: &Self::Secret) -> Vec<Share<Self::Field>>;
+
+    /// Reconstruct a secret from at least `reconstruct_limit` of its shares.
+    fn reconstruct(&self, shares: &[Share<Self::Field>]) -> Self::Secret;
+}
+
+#[cfg(test)]
+mod tests {
+
+    use fields::{NaturalPrimeField, SliceEncode};
+    use packed::PSS_4_26_3;
+    use shamir::ShamirSecretSharing;
+    use test_utils::check_scheme_roundtrip;
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 3,
+            share_count: 7,
+            field: NaturalPrimeField(41),
+        };
+        check_scheme_roundtrip(&tss, 12);
+    }
+
+    #[test]
+    fn test_packed() {
+        let pss = &PSS_4_26_3;
+        check_scheme_roundtrip(pss, pss.field.encode_slice([5, 6, 7]));
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Seeds for deterministic sharing.
+
+use rand::{ChaChaRng, SeedableRng};
+
+/// Seed from which all randomness of a sharing is derived.
+///
+/// Anyone knowing the seed and the secrets can recompute all shares, so it must be
+/// sampled uniformly at random and kept as secret as the secrets themselves.
+pub type Seed = [u8; 32];
+
+/// ChaCha20 generator keyed by `seed`.
+pub fn seeded_rng(seed: &Seed) -> ChaChaRng {
+    let key: Vec<u32> = seed
+        .chunks(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        .collect();
+    ChaChaRng::from_seed(&key[..])
+}
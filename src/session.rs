@@ -0,0 +1,345 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Reconstruction from shares arriving one at a time, for instance over the network.
+//!
+//! The sharing polynomial is interpolated in Newton form as shares are added, each
+//! share extending it by one coefficient in time `O(k)` for the `k` shares so far.
+//! Once `reconstruct_limit` shares are present the polynomial is fully determined,
+//! and finishing only evaluates it at the secret positions instead of interpolating
+//! from scratch.
+//!
+//! Every further share is checked against the polynomial as it is added, so that a
+//! faulty party is noticed as soon as its share arrives.
+
+use fields::{Encode, Field};
+use numtheory::NewtonPolynomial;
+#[cfg(feature = "observer")]
+use observer::{notify, SchemeInfo, SchemeKind};
+use packed::PackedSecretSharing;
+use scheme::ThresholdScheme;
+use shamir::ShamirSecretSharing;
+use share::{index_u32, IndexError, ShareIndex};
+
+/// Reconstruction of a secret from shares added one at a time, obtained from
+/// `reconstruction_session` on either scheme.
+///
+/// The result is determined by the first `reconstruct_limit` shares, against which
+/// every further share is checked: `add_share` rejects those that disagree with
+/// `IndexError::InconsistentShare`, and records them in `inconsistent_indices`.
+/// Note that a mismatch only shows that some share is faulty, either the new one or
+/// one of the first ones; in the latter case most further shares will mismatch.
+pub struct ReconstructionSession<'a, S: 'a + ThresholdScheme> {
+    scheme: &'a S,
+    indices: Vec<ShareIndex>,
+    inconsistent: Vec<ShareIndex>,
+    polynomial: NewtonPolynomial<S::Field>,
+}
+
+impl<'a, S> ReconstructionSession<'a, S>
+where
+    S: 'a + ThresholdScheme,
+    <S::Field as Field>::E: Clone,
+{
+    fn new(scheme: &'a S, polynomial: NewtonPolynomial<S::Field>) -> ReconstructionSession<'a, S> {
+        ReconstructionSession {
+            scheme,
+            indices: Vec::new(),
+            inconsistent: Vec::new(),
+            polynomial,
+        }
+    }
+
+    /// Indices of the shares added so far, in order, excluding inconsistent ones.
+    pub fn indices(&self) -> &[ShareIndex] {
+        &self.indices
+    }
+
+    /// Number of shares added so far, excluding inconsistent ones.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Indices of the shares rejected as inconsistent so far, in order.
+    pub fn inconsistent_indices(&self) -> &[ShareIndex] {
+        &self.inconsistent
+    }
+
+    /// Number of shares needed for `try_finish` to succeed, ie. the
+    /// `reconstruct_limit` of the scheme.
+    pub fn reconstruct_limit(&self) -> usize {
+        self.scheme.reconstruct_limit()
+    }
+
+    /// Number of shares of the scheme.
+    pub fn share_count(&self) -> usize {
+        self.scheme.share_count()
+    }
+
+    /// Whether enough shares were added for `try_finish` to succeed.
+    pub fn is_ready(&self) -> bool {
+        self.indices.len() >= self.reconstruct_limit()
+    }
+
+    /// Add the share with `index`, whose value the polynomial takes at `point`, or
+    /// check it against the polynomial if it is already determined.
+    fn insert(
+        &mut self,
+        index: ShareIndex,
+        point: <S::Field as Field>::E,
+        value: <S::Field as Field>::E,
+        field: &S::Field,
+    ) -> Result<(), IndexError> {
+        if !self.is_ready() {
+            self.polynomial.add_point(point, value, field);
+        } else if !field.eq(self.polynomial.evaluate(point, field), value) {
+            self.inconsistent.push(index);
+            return Err(IndexError::InconsistentShare(index));
+        }
+        self.indices.push(index);
+        Ok(())
+    }
+
+    /// Check that `index` is that of a share not added yet.
+    fn check_index(&self, index: ShareIndex) -> Result<(), IndexError> {
+        if index >= self.scheme.share_count() {
+            Err(IndexError::UnknownShare(index))
+        } else if self.indices.contains(&index) || self.inconsistent.contains(&index) {
+            Err(IndexError::DuplicateShare(index))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<F> ShamirSecretSharing<F>
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    /// Start reconstructing the secret from shares added one at a time.
+    pub fn reconstruction_session(&self) -> ReconstructionSession<'_, ShamirSecretSharing<F>> {
+        ReconstructionSession::new(self, NewtonPolynomial::empty())
+    }
+}
+
+impl<'a, F> ReconstructionSession<'a, ShamirSecretSharing<F>>
+where
+    F: Field + Encode<u32>,
+    F::E: Clone,
+{
+    /// Add the share with rank `index` as output by the `share` method, and value
+    /// `value`.
+    ///
+    /// Beyond `reconstruct_limit` shares, fails if it disagrees with the others.
+    pub fn add_share(&mut self, index: ShareIndex, value: F::E) -> Result<(), IndexError> {
+        self.check_index(index)?;
+        let field = &self.scheme.field;
+        self.insert(index, field.encode(index_u32(index) + 1), value, field)
+    }
+
+    /// The secret if at least `reconstruct_limit` shares were added, in time `O(k)`
+    /// for `k = reconstruct_limit`.
+    pub fn try_finish(&self) -> Option<F::E> {
+        if !self.is_ready() {
+            return None;
+        }
+        #[cfg(feature = "observer")]
+        notify(|observer| {
+            let info = SchemeInfo {
+                kind: SchemeKind::Shamir,
+                threshold: self.scheme.threshold,
+                share_count: self.scheme.share_count,
+                secret_count: 1,
+            };
+            observer.on_reconstruct(&info, &self.indices);
+        });
+        let field = &self.scheme.field;
+        Some(self.polynomial.evaluate(field.zero(), field))
+    }
+}
+
+impl<F> PackedSecretSharing<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Start reconstructing the secrets from shares added one at a time.
+    pub fn reconstruction_session(&self) -> ReconstructionSession<'_, PackedSecretSharing<F>> {
+        // the sharing polynomial is known to be zero at `omega_shares^0 = 1`
+        let mut polynomial = NewtonPolynomial::empty();
+        polynomial.add_point(self.field.one(), self.field.zero(), &self.field);
+        ReconstructionSession::new(self, polynomial)
+    }
+}
+
+impl<'a, F> ReconstructionSession<'a, PackedSecretSharing<F>>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Add the share with rank `index` as output by the `share` method, and value
+    /// `value`.
+    ///
+    /// Beyond `reconstruct_limit` shares, fails if it disagrees with the others.
+    pub fn add_share(&mut self, index: ShareIndex, value: F::E) -> Result<(), IndexError> {
+        self.check_index(index)?;
+        let field = &self.scheme.field;
+        let point = field.pow(&self.scheme.omega_shares, index_u32(index) + 1);
+        self.insert(index, point, value, field)
+    }
+
+    /// The secrets if at least `reconstruct_limit` shares were added, in time
+    /// `O(secret_count.k)` for `k = reconstruct_limit`.
+    pub fn try_finish(&self) -> Option<Vec<F::E>> {
+        if !self.is_ready() {
+            return None;
+        }
+        #[cfg(feature = "observer")]
+        notify(|observer| {
+            let info = SchemeInfo {
+                kind: SchemeKind::Packed,
+                threshold: self.scheme.threshold,
+                share_count: self.scheme.share_count,
+                secret_count: self.scheme.secret_count,
+            };
+            observer.on_reconstruct(&info, &self.indices);
+        });
+        let field = &self.scheme.field;
+        Some(
+            self.scheme
+                .secret_positions()
+                .iter()
+                .map(|point| self.polynomial.evaluate(point, field))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::{Fp61, SliceEncode};
+    use packed::PSS_4_26_3;
+
+    #[test]
+    fn test_shamir() {
+        let tss = ShamirSecretSharing {
+            threshold: 3,
+            share_count: 8,
+            field: Fp61,
+        };
+        let shares = tss.share(987654321);
+        let mut session = tss.reconstruction_session();
+        assert!(session.is_empty());
+        for &i in &[6, 1, 4] {
+            session.add_share(i, shares[i]).unwrap();
+            assert_eq!(session.try_finish(), None);
+        }
+        session.add_share(0, shares[0]).unwrap();
+        assert!(session.is_ready());
+        assert_eq!(session.try_finish(), Some(987654321));
+        assert_eq!(session.indices(), [6, 1, 4, 0]);
+
+        session.add_share(7, shares[7]).unwrap();
+        assert_eq!(session.len(), 5);
+        assert_eq!(session.try_finish(), Some(987654321));
+    }
+
+    #[test]
+    fn test_packed() {
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([5, 6, 7]);
+        let shares = pss.share(&secrets);
+        let mut session = pss.reconstruction_session();
+        for &i in &[25, 3, 11, 0, 17, 8] {
+            session.add_share(i, shares[i]).unwrap();
+            assert_eq!(session.try_finish(), None);
+        }
+        session.add_share(20, shares[20]).unwrap();
+        assert_eq!(session.try_finish(), Some(secrets));
+    }
+
+    #[test]
+    fn test_errors() {
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 3,
+            field: Fp61,
+        };
+        let mut session = tss.reconstruction_session();
+        assert_eq!(session.add_share(1, 5), Ok(()));
+        assert_eq!(session.add_share(1, 5), Err(IndexError::DuplicateShare(1)));
+        assert_eq!(session.add_share(3, 5), Err(IndexError::UnknownShare(3)));
+        assert_eq!(session.len(), 1);
+
+        let pss = &PSS_4_26_3;
+        let mut session = pss.reconstruction_session();
+        assert_eq!(session.add_share(26, 0), Err(IndexError::UnknownShare(26)));
+    }
+
+    #[test]
+    fn test_inconsistent() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 7,
+            field: Fp61,
+        };
+        let mut shares = tss.share(424242);
+        shares[3] = Fp61.add(shares[3], 1);
+        let mut session = tss.reconstruction_session();
+        for (i, &share) in shares[..3].iter().enumerate() {
+            assert_eq!(session.add_share(i, share), Ok(()));
+        }
+        assert_eq!(
+            session.add_share(3, shares[3]),
+            Err(IndexError::InconsistentShare(3))
+        );
+        assert_eq!(
+            session.add_share(3, shares[3]),
+            Err(IndexError::DuplicateShare(3))
+        );
+        assert_eq!(session.add_share(4, shares[4]), Ok(()));
+        assert_eq!(session.indices(), [0, 1, 2, 4]);
+        assert_eq!(session.inconsistent_indices(), [3]);
+        assert_eq!(session.try_finish(), Some(424242));
+
+        // a faulty share among the first ones makes the later ones mismatch
+        let mut session = tss.reconstruction_session();
+        for &i in &[3, 0, 1] {
+            session.add_share(i, shares[i]).unwrap();
+        }
+        for (i, &share) in shares.iter().enumerate().skip(4) {
+            assert_eq!(
+                session.add_share(i, share),
+                Err(IndexError::InconsistentShare(i))
+            );
+        }
+        assert_eq!(session.inconsistent_indices(), [4, 5, 6]);
+
+        let pss = &PSS_4_26_3;
+        let secrets = pss.field.encode_slice([5, 6, 7]);
+        let mut shares = pss.share(&secrets);
+        shares[9] = pss.field.add(shares[9], pss.field.one());
+        let mut session = pss.reconstruction_session();
+        for (i, &share) in shares[..pss.reconstruct_limit()].iter().enumerate() {
+            session.add_share(i, share).unwrap();
+        }
+        assert_eq!(session.add_share(8, shares[8]), Ok(()));
+        assert_eq!(
+            session.add_share(9, shares[9]),
+            Err(IndexError::InconsistentShare(9))
+        );
+        assert_eq!(session.try_finish(), Some(secrets));
+    }
+}
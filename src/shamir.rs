@@ -9,10 +9,23 @@
 //! Standard [Shamir secret sharing](https://en.wikipedia.org/wiki/Shamir%27s_Secret_Sharing)
 //! for a single secret.
 
-use rand;
+use fields::ForwardSecureRng;
+use rand::Rng;
 
+use fields::Decode;
+use fields::ElementBytes;
 use fields::Encode;
 use fields::Field;
+use numtheory::{InterpolationCache, LagrangeConstants};
+#[cfg(feature = "observer")]
+use observer::{notify, SchemeInfo, SchemeKind};
+use scheme::ThresholdScheme;
+use seed::{seeded_rng, Seed};
+use share::{
+    check_indices, check_lengths, deduplicate_shares, IndexError, PolynomialShares, ProductShares,
+    Share, ShareIndex,
+};
+use transcript::{ShareHasher, SharingTranscript};
 
 /// Parameters for the Shamir scheme, specifying privacy threshold and total number of shares.
 ///
@@ -43,7 +56,7 @@ use fields::Field;
 ///    println!("The recovered secret is {}", recovered_secret);
 ///    assert_eq!(recovered_secret, secret);
 /// ```
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ShamirSecretSharing<F>
 where
     F: Field,
@@ -71,18 +84,257 @@ where
     }
 
     /// Generate `share_count` shares from `secret`.
+    ///
+    /// The randomness is sampled from a `ForwardSecureRng` seeded from the operating
+    /// system.
     pub fn share(&self, secret: F::E) -> Vec<F::E> {
-        let poly = self.sample_polynomial(secret);
+        self.share_with_rng(secret, &mut ForwardSecureRng::new())
+    }
+
+    /// Generate `share_count` shares from `secret` given as a plain value, encoding
+    /// it into the field first.
+    pub fn share_value<T>(&self, secret: T) -> Vec<F::E>
+    where
+        F: Encode<T>,
+    {
+        self.share(self.field.encode(secret))
+    }
+
+    /// Generate `share_count` shares from `secret`, sampling the randomness from
+    /// `rng`.
+    pub fn share_with_rng<R: Rng>(&self, secret: F::E, rng: &mut R) -> Vec<F::E> {
+        let poly = self.sample_polynomial(secret, rng);
         self.evaluate_polynomial(&poly)
     }
 
-    fn sample_polynomial(&self, zero_value: F::E) -> Vec<F::E> {
+    /// Generate `share_count` shares of zero.
+    ///
+    /// Like any other sharing these are randomized, so adding them to the shares of
+    /// a secret rerandomizes its sharing without changing the secret.
+    pub fn share_zero(&self) -> Vec<F::E> {
+        self.share(self.field.zero())
+    }
+
+    /// Generate `share_count` shares of a fresh random value, sampling the value and
+    /// the randomness from `rng`, and return the value along with its shares.
+    pub fn share_random<R: Rng>(&self, rng: &mut R) -> (F::E, Vec<F::E>) {
+        let value = self.field.sample(rng);
+        let shares = self.share_with_rng(value.clone(), rng);
+        (value, shares)
+    }
+
+    /// Generate `share_count` randomized shares of the public `constant`.
+    ///
+    /// Unlike the trivial sharing in which every share is the constant itself, the
+    /// shares look like those of any other secret, so that a protocol can use them
+    /// in place of a secret input without revealing that it is public.
+    pub fn share_constant(&self, constant: F::E) -> Vec<F::E> {
+        self.share(constant)
+    }
+
+    /// Generate the `share_count` shares from `secret` lazily, as pairs of their
+    /// index and value.
+    ///
+    /// Only the `threshold + 1` coefficients of the sharing polynomial are kept, and
+    /// each share is evaluated from them when requested, so that a dealer can stream
+    /// the shares to their recipients without holding all of them.
+    pub fn share_iter<'a>(&'a self, secret: F::E) -> impl Iterator<Item = (u32, F::E)> + 'a {
+        self.share_iter_with_rng(secret, &mut ForwardSecureRng::new())
+    }
+
+    /// Generate shares lazily like `share_iter`, sampling the randomness from `rng`.
+    pub fn share_iter_with_rng<'a, R: Rng>(
+        &'a self,
+        secret: F::E,
+        rng: &mut R,
+    ) -> impl Iterator<Item = (u32, F::E)> + 'a {
+        let poly = self.sample_polynomial(secret, rng);
+        (0..self.share_count as u32).map(move |index| {
+            #[cfg(feature = "observer")]
+            notify(|observer| observer.on_share_emitted(&self.observer_info(), index as usize));
+            let point = self.field.encode(index + 1);
+            (
+                index,
+                ::numtheory::mod_evaluate_polynomial(&poly, point, &self.field),
+            )
+        })
+    }
+
+    /// Generate shares from `secret` at the given evaluation `points` instead of at
+    /// `1..share_count + 1`, such as the identifiers of the parties.
+    ///
+    /// There must be `share_count` points, which must be distinct and non-zero since
+    /// the value at zero is the secret. Share `i` is the value at `points[i]`, and the
+    /// secret can be recovered with `reconstruct_at`.
+    pub fn share_at(&self, secret: F::E, points: &[F::E]) -> Vec<F::E> {
+        assert_eq!(points.len(), self.share_count);
+        assert!(
+            points
+                .iter()
+                .all(|point| self.field.neq(point, self.field.zero())),
+            "evaluation points must be non-zero"
+        );
+        self.assert_distinct(points);
+        let poly = self.sample_polynomial(secret, &mut ForwardSecureRng::new());
+        let shares: Vec<F::E> = points
+            .iter()
+            .map(|point| ::numtheory::mod_evaluate_polynomial(&poly, point, &self.field))
+            .collect();
+        #[cfg(feature = "observer")]
+        notify(|observer| {
+            for index in 0..shares.len() {
+                observer.on_share_emitted(&self.observer_info(), index);
+            }
+        });
+        shares
+    }
+
+    /// Generate `share_count` shares from `secret` like `share`, also returning the
+    /// `threshold + 1` coefficients of the sharing polynomial.
+    ///
+    /// The constant coefficient is `secret`, and share `i` is the value of the
+    /// polynomial at `i + 1`.
+    pub fn share_returning_polynomial(&self, secret: F::E) -> PolynomialShares<F> {
+        let mut rng = ForwardSecureRng::new();
+        let coefficients = self.sample_polynomial(secret, &mut rng);
+        PolynomialShares {
+            shares: self.evaluate_polynomial(&coefficients),
+            coefficients,
+        }
+    }
+
+    /// Generate `share_count` shares from `secret`, deriving all randomness from `seed`.
+    ///
+    /// The same secret and seed always give the same shares, which allows the
+    /// sharing to be checked later using `verify_sharing`.
+    pub fn share_seeded(&self, secret: F::E, seed: &Seed) -> Vec<F::E> {
+        let poly = self.sample_polynomial(secret, &mut seeded_rng(seed));
+        self.evaluate_polynomial(&poly)
+    }
+
+    /// Check that `claimed_shares` are the shares of `secret` generated by
+    /// `share_seeded` with `seed`, returning the indices of any that are not.
+    ///
+    /// This lets a dealer prove to an auditor, given the secret and the seed, that
+    /// the issued shares are consistent with the declared secret.
+    pub fn verify_sharing(
+        &self,
+        secret: F::E,
+        seed: &Seed,
+        claimed_shares: &[F::E],
+    ) -> Result<(), Vec<ShareIndex>> {
+        assert_eq!(claimed_shares.len(), self.share_count);
+        let shares = self.share_seeded(secret, seed);
+        let mismatches: Vec<ShareIndex> = (0..self.share_count)
+            .filter(|&i| self.field.neq(&shares[i], &claimed_shares[i]))
+            .collect();
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    /// Generate `share_count` shares from `secret`, each carrying its index.
+    pub fn share_indexed(&self, secret: F::E) -> Vec<Share<F>> {
+        Share::enumerate(self.share(secret))
+    }
+
+    /// Generate shares from `secret` together with commitments to each of them.
+    pub fn share_audited<H>(&self, secret: F::E, hasher: H) -> (Vec<F::E>, SharingTranscript<F, H>)
+    where
+        F: ElementBytes + Clone,
+        H: ShareHasher,
+    {
+        let shares = self.share(secret);
+        let transcript = SharingTranscript::new(self.field.clone(), hasher, &shares);
+        (shares, transcript)
+    }
+
+    /// Split one of our shares into subshares for a sub-committee.
+    ///
+    /// Since Shamir sharing is linear, the share can later be recovered from enough
+    /// of its subshares using `sub_scheme.reconstruct`, and the secret from enough
+    /// recovered shares using `reconstruct`; see `reconstruct_from_subshares`.
+    /// Both schemes must be over the same field.
+    pub fn subshare(&self, share: F::E, sub_scheme: &ShamirSecretSharing<F>) -> Vec<F::E> {
+        sub_scheme.share(share)
+    }
+
+    /// Shares of the sum of two secrets, from their shares `a` and `b`.
+    ///
+    /// Like the other share arithmetic this works pointwise, so any subset of the
+    /// shares may be given, such as the single share held by a party, as long as `a`
+    /// and `b` hold shares with the same indices.
+    pub fn add_shares(&self, a: &[F::E], b: &[F::E]) -> Vec<F::E> {
+        ::share::add_shares(&self.field, a, b)
+    }
+
+    /// Shares of the difference of two secrets, from their shares `a` and `b`.
+    pub fn sub_shares(&self, a: &[F::E], b: &[F::E]) -> Vec<F::E> {
+        ::share::sub_shares(&self.field, a, b)
+    }
+
+    /// Shares of a secret multiplied by the public `scalar`, from its shares `a`.
+    pub fn scale_shares(&self, a: &[F::E], scalar: &F::E) -> Vec<F::E> {
+        ::share::scale_shares(&self.field, a, scalar)
+    }
+
+    /// Share of the secret plus the public `constant`, from one of its shares.
+    ///
+    /// Adding the constant to every share shifts the whole sharing polynomial, and
+    /// hence its value at zero, by the same amount.
+    pub fn add_constant(&self, share: &F::E, constant: &F::E) -> F::E {
+        self.field.add(share, constant)
+    }
+
+    /// Share of the secret multiplied by the public `scalar`, from one of its shares.
+    ///
+    /// Together with `add_constant` this gives shares of any affine function
+    /// `scalar.secret + constant` without interaction.
+    pub fn scale(&self, share: &F::E, scalar: &F::E) -> F::E {
+        self.field.mul(share, scalar)
+    }
+
+    /// Shares of the product of two secrets, from their shares `a` and `b` as output
+    /// by `share`.
+    ///
+    /// The product is shared with threshold `2 * threshold`, and hence needs
+    /// `2 * threshold + 1` shares to be reconstructed.
+    pub fn mul_shares(&self, a: &[F::E], b: &[F::E]) -> ProductShares<F> {
+        ::share::mul_shares(&self.field, a, b, 2 * self.threshold + 1)
+    }
+
+    /// Reconstruct `secret` in a hierarchical access structure, where each share
+    /// was split using `subshare` with `sub_scheme`.
+    ///
+    /// `indices` are the ranks of the known shares, and for each of these
+    /// `subshare_indices` and `subshares` give the known subshares.
+    pub fn reconstruct_from_subshares(
+        &self,
+        sub_scheme: &ShamirSecretSharing<F>,
+        indices: &[usize],
+        subshare_indices: &[Vec<usize>],
+        subshares: &[Vec<F::E>],
+    ) -> F::E {
+        assert_eq!(indices.len(), subshare_indices.len());
+        assert_eq!(indices.len(), subshares.len());
+        let shares: Vec<F::E> = subshare_indices
+            .iter()
+            .zip(subshares)
+            .map(|(sub_indices, sub_values)| sub_scheme.reconstruct(sub_indices, sub_values))
+            .collect();
+        self.reconstruct(indices, &shares)
+    }
+
+    fn sample_polynomial<R: Rng>(&self, zero_value: F::E, rng: &mut R) -> Vec<F::E> {
         // fix the first coefficient (corresponding to the evaluation at zero)
         let mut coefficients = vec![zero_value];
-        // sample the remaining coefficients randomly using secure randomness
-        let mut rng = rand::OsRng::new().unwrap();
-        let random_coefficients = self.field.sample_with_replacement(self.threshold, &mut rng);
+        // sample the remaining coefficients randomly
+        let random_coefficients = self.field.sample_with_replacement(self.threshold, rng);
         coefficients.extend(random_coefficients);
+        #[cfg(feature = "observer")]
+        notify(|observer| observer.on_poly_sampled(&self.observer_info()));
         // return
         coefficients
     }
@@ -91,6 +343,8 @@ where
         // evaluate at all points
         (1..self.share_count + 1)
             .map(|point| {
+                #[cfg(feature = "observer")]
+                notify(|observer| observer.on_share_emitted(&self.observer_info(), point - 1));
                 ::numtheory::mod_evaluate_polynomial(
                     coefficients,
                     self.field.encode(point as u32),
@@ -100,12 +354,77 @@ where
             .collect()
     }
 
+    #[cfg(feature = "observer")]
+    fn observer_info(&self) -> SchemeInfo {
+        SchemeInfo {
+            kind: SchemeKind::Shamir,
+            threshold: self.threshold,
+            share_count: self.share_count,
+            secret_count: 1,
+        }
+    }
+
     /// Reconstruct `secret` from a large enough subset of the shares.
     ///
     /// `indices` are the ranks of the known shares as output by the `share` method,
     /// while `values` are the actual values of these shares.
     /// Both must have the same number of elements, and at least `reconstruct_limit`.
     pub fn reconstruct(&self, indices: &[usize], shares: &[F::E]) -> F::E {
+        #[cfg(feature = "observer")]
+        notify(|observer| observer.on_reconstruct(&self.observer_info(), indices));
+        self.evaluate_at(indices, shares, &self.field.zero())
+    }
+
+    /// Reconstruct `secret` like `reconstruct`, decoding it from the field into a
+    /// plain value.
+    pub fn reconstruct_decoded<U>(&self, indices: &[usize], shares: &[F::E]) -> U
+    where
+        F: Decode<U>,
+    {
+        self.field.decode(self.reconstruct(indices, shares))
+    }
+
+    /// Reconstruct `secret` like `reconstruct`, after checking that `indices` are
+    /// distinct ranks of shares and that there are at least `reconstruct_limit`.
+    ///
+    /// With invalid indices, `reconstruct` instead returns a meaningless secret or
+    /// panics while interpolating.
+    pub fn try_reconstruct(&self, indices: &[usize], shares: &[F::E]) -> Result<F::E, IndexError> {
+        check_lengths(indices, shares)?;
+        check_indices(
+            self.share_count,
+            self.reconstruct_limit(),
+            indices.iter().cloned(),
+        )?;
+        Ok(self.reconstruct(indices, shares))
+    }
+
+    /// Reconstruct `secret` like `try_reconstruct`, accepting shares given several
+    /// times with the same value, such as when collected through several relays.
+    ///
+    /// Shares given several times with different values are rejected with
+    /// `IndexError::ConflictingShare`.
+    pub fn reconstruct_deduplicated(
+        &self,
+        indices: &[usize],
+        shares: &[F::E],
+    ) -> Result<F::E, IndexError> {
+        let (indices, shares) = deduplicate_shares(
+            &self.field,
+            self.share_count,
+            self.reconstruct_limit(),
+            indices,
+            shares,
+        )?;
+        Ok(self.reconstruct(&indices, &shares))
+    }
+
+    /// Value at `point` of the sharing polynomial, from a large enough subset of the
+    /// shares given as for `reconstruct`.
+    ///
+    /// The secret is the value at zero, while the share with index `i` is the value
+    /// at `i + 1`; other points give shares for additional participants.
+    pub fn evaluate_at(&self, indices: &[usize], shares: &[F::E], point: &F::E) -> F::E {
         assert!(shares.len() == indices.len());
         assert!(shares.len() >= self.reconstruct_limit());
         // add one to indices to get points
@@ -117,7 +436,241 @@ where
             })
             .collect();
         // interpolate
-        ::numtheory::lagrange_interpolation_at_zero(&*points, shares, &self.field)
+        ::numtheory::lagrange_interpolation_at_point(point, &points, shares, &self.field)
+    }
+
+    /// Reconstruct `secret` from a large enough subset of the shares generated by
+    /// `share_at`, with `points` the evaluation points of these shares.
+    pub fn reconstruct_at(&self, points: &[F::E], shares: &[F::E]) -> F::E {
+        assert!(shares.len() == points.len());
+        assert!(shares.len() >= self.reconstruct_limit());
+        self.assert_distinct(points);
+        #[cfg(feature = "observer")]
+        notify(|observer| {
+            let positions: Vec<usize> = (0..points.len()).collect();
+            observer.on_reconstruct(&self.observer_info(), &positions)
+        });
+        ::numtheory::lagrange_interpolation_at_zero(points, shares, &self.field)
+    }
+
+    fn assert_distinct(&self, points: &[F::E]) {
+        for (i, point) in points.iter().enumerate() {
+            assert!(
+                points[..i].iter().all(|other| self.field.neq(other, point)),
+                "evaluation points must be distinct"
+            );
+        }
+    }
+
+    /// Reconstruct `secret` like `reconstruct`, reusing the interpolation constants
+    /// for `indices` from `cache` when available.
+    pub fn reconstruct_cached(
+        &self,
+        cache: &InterpolationCache<F>,
+        indices: &[usize],
+        shares: &[F::E],
+    ) -> F::E
+    where
+        F: ElementBytes,
+    {
+        assert!(shares.len() == indices.len());
+        assert!(shares.len() >= self.reconstruct_limit());
+        #[cfg(feature = "observer")]
+        notify(|observer| observer.on_reconstruct(&self.observer_info(), indices));
+        let scheme = self.fingerprint();
+        let secret = cache.interpolate(scheme, indices, shares, &self.field, |sorted| {
+            let points: Vec<F::E> = sorted
+                .iter()
+                .map(|&i| self.field.encode(i as u32 + 1))
+                .collect();
+            vec![LagrangeConstants::compute(
+                &self.field.zero(),
+                &points,
+                &self.field,
+            )]
+        });
+        secret
+            .into_iter()
+            .next()
+            .expect("a single value is interpolated")
+    }
+
+    /// Reconstruct `secret` from a large enough collection of indexed shares.
+    pub fn reconstruct_indexed<'a, I>(&self, shares: I) -> F::E
+    where
+        I: IntoIterator<Item = &'a Share<F>>,
+        F: 'a,
+    {
+        let (indices, values) = Share::unzip(shares);
+        self.reconstruct(&indices, &values)
+    }
+}
+
+impl<F> ThresholdScheme for ShamirSecretSharing<F>
+where
+    F: Field,
+    F: Encode<u32>,
+    F::E: Clone,
+{
+    type Field = F;
+    type Secret = F::E;
+
+    fn share_count(&self) -> usize {
+        self.share_count
+    }
+
+    fn reconstruct_limit(&self) -> usize {
+        self.threshold + 1
+    }
+
+    fn share(&self, secret: &F::E) -> Vec<Share<F>> {
+        self.share_indexed(secret.clone())
+    }
+
+    fn reconstruct(&self, shares: &[Share<F>]) -> F::E {
+        self.reconstruct_indexed(shares)
+    }
+}
+
+/// Sharings of the same random value with thresholds `threshold` and
+/// `2 * threshold`, as output by `share_double_random`.
+pub struct DoubleSharing<F: Field> {
+    /// Shares of the value with threshold `threshold`.
+    pub low: Vec<F::E>,
+    /// Shares of the value with threshold `2 * threshold`.
+    pub high: Vec<F::E>,
+}
+
+element_impls!(Clone, PartialEq, Debug for DoubleSharing { low, high });
+
+impl<F> ShamirSecretSharing<F>
+where
+    F: Field + Encode<u32> + Clone,
+    F::E: Clone,
+{
+    /// Sharings of a fresh random value with thresholds `threshold` and
+    /// `2 * threshold`, sampling the value and the randomness from `rng`.
+    ///
+    /// Following Damgård and Nielsen, the parties reduce a product of two sharings
+    /// by opening the product minus the high sharing, `xy - r`, and adding the
+    /// opened value to their shares of the low sharing. `None` if there are not
+    /// more than `2 * threshold` shares, so that `xy - r` could not be opened.
+    pub fn share_double_random<R: Rng>(&self, rng: &mut R) -> Option<DoubleSharing<F>> {
+        if self.share_count <= 2 * self.threshold {
+            return None;
+        }
+        let value = self.field.sample(rng);
+        Some(DoubleSharing {
+            low: self.share_with_rng(value.clone(), rng),
+            high: ShamirSecretSharing {
+                threshold: 2 * self.threshold,
+                ..self.clone()
+            }
+            .share_with_rng(value, rng),
+        })
+    }
+
+    /// Whether all of `shares` lie on a polynomial of degree at most `degree`, that
+    /// is whether they are a sharing with threshold `degree`.
+    ///
+    /// Trivially true if there are at most `degree + 1` shares.
+    pub fn has_degree(&self, shares: &[F::E], degree: usize) -> bool {
+        if shares.len() <= degree + 1 {
+            return true;
+        }
+        let scheme = ShamirSecretSharing {
+            threshold: degree,
+            ..self.clone()
+        };
+        let indices: Vec<usize> = (0..=degree).collect();
+        (degree + 1..shares.len()).all(|i| {
+            let point = self.field.encode(i as u32 + 1);
+            let expected = scheme.evaluate_at(&indices, &shares[..=degree], &point);
+            self.field.eq(&expected, &shares[i])
+        })
+    }
+
+    /// Whether `double` is a consistent double sharing for this scheme: two
+    /// complete sharings with thresholds `threshold` and `2 * threshold` of the
+    /// same value.
+    ///
+    /// This needs all shares, and can hence only be checked by the dealer or, for
+    /// instance, when opening double sharings sacrificed to check the others.
+    /// Always false if there are not more than `2 * threshold` shares.
+    pub fn verify_double_sharing(&self, double: &DoubleSharing<F>) -> bool {
+        if self.share_count <= 2 * self.threshold
+            || double.low.len() != self.share_count
+            || double.high.len() != self.share_count
+        {
+            return false;
+        }
+        let high_threshold = 2 * self.threshold;
+        let indices: Vec<usize> = (0..=high_threshold).collect();
+        let low = self.reconstruct(&indices[..=self.threshold], &double.low[..=self.threshold]);
+        let high = ShamirSecretSharing {
+            threshold: high_threshold,
+            ..self.clone()
+        }
+        .reconstruct(&indices, &double.high[..=high_threshold]);
+        self.has_degree(&double.low, self.threshold)
+            && self.has_degree(&double.high, high_threshold)
+            && self.field.eq(&low, &high)
+    }
+}
+
+#[cfg(feature = "curves")]
+mod curves {
+
+    use super::*;
+    use fields::{Curve, U256Field};
+
+    impl ShamirSecretSharing<U256Field> {
+        /// Scheme over the scalar field of `curve`.
+        pub fn for_curve(curve: Curve, threshold: usize, share_count: usize) -> Self {
+            ShamirSecretSharing {
+                threshold,
+                share_count,
+                field: curve.scalar_field(),
+            }
+        }
+
+        /// Scheme over the scalar field of secp256k1.
+        pub fn for_secp256k1(threshold: usize, share_count: usize) -> Self {
+            Self::for_curve(Curve::Secp256k1, threshold, share_count)
+        }
+
+        /// Scheme over the scalar field of NIST P-256.
+        pub fn for_p256(threshold: usize, share_count: usize) -> Self {
+            Self::for_curve(Curve::P256, threshold, share_count)
+        }
+
+        /// Scheme over the scalar field of ed25519.
+        pub fn for_ed25519(threshold: usize, share_count: usize) -> Self {
+            Self::for_curve(Curve::Ed25519, threshold, share_count)
+        }
+
+        /// Scheme over the scalar field of BLS12-381.
+        pub fn for_bls12_381(threshold: usize, share_count: usize) -> Self {
+            Self::for_curve(Curve::Bls12_381, threshold, share_count)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+
+        use super::*;
+
+        #[test]
+        fn test_secp256k1_roundtrip() {
+            let tss = ShamirSecretSharing::for_secp256k1(2, 5);
+            let mut key = vec![0xff_u8; 32];
+            key[0] = 0x7f;
+            let secret = Curve::Secp256k1.encode_scalar(&tss.field, &key);
+            let shares = tss.share(secret);
+            let subset = [shares[1], shares[3], shares[4]];
+            let recovered = tss.reconstruct(&[1, 3, 4], &subset);
+            assert_eq!(Curve::Secp256k1.decode_scalar(&recovered), key);
+        }
     }
 }
 
@@ -125,7 +678,8 @@ where
 mod tests {
 
     use super::*;
-    use fields::NaturalPrimeField;
+    use fields::{IntegerCoding, MontgomeryField32, NaturalPrimeField, New};
+    use rand::{ChaChaRng, SeedableRng};
 
     // Small preset parameters for tests.
     pub static SHAMIR_5_20: ShamirSecretSharing<NaturalPrimeField<i64>> = ShamirSecretSharing {
@@ -136,7 +690,7 @@ mod tests {
 
     #[test]
     fn test_evaluate_polynomial() {
-        let ref tss = SHAMIR_5_20;
+        let tss = &SHAMIR_5_20;
         let poly = vec![1, 2, 0];
         let values = tss.evaluate_polynomial(&poly);
         assert_eq!(
@@ -174,4 +728,427 @@ mod tests {
         assert_eq!(tss.reconstruct(&[1, 2, 3], &shares[1..4]), secret);
         assert_eq!(tss.reconstruct(&[2, 3, 4, 5], &shares[2..6]), secret);
     }
+
+    #[test]
+    fn test_share_value() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: MontgomeryField32::new(41),
+        };
+        let shares = tss.share_value(38);
+        let values = [shares[0], shares[2], shares[4]];
+        assert_eq!(tss.reconstruct_decoded::<u32>(&[0, 2, 4], &values), 38);
+
+        // negative values are encoded explicitly
+        let shares = tss.share(tss.field.encode_signed(-3));
+        let secret = tss.reconstruct(&[1, 2, 3], &shares[1..4]);
+        assert_eq!(tss.field.decode_signed(secret), -3);
+        assert_eq!(tss.field.decode(secret), 38);
+    }
+
+    #[test]
+    fn test_share_returning_polynomial() {
+        let tss = &SHAMIR_5_20;
+        let dealt = tss.share_returning_polynomial(17);
+        assert_eq!(dealt.coefficients.len(), 6);
+        assert_eq!(dealt.coefficients[0], 17);
+        assert_eq!(dealt.shares, tss.evaluate_polynomial(&dealt.coefficients));
+        let indices = [3, 7, 8, 9, 12, 19];
+        let values: Vec<i64> = indices.iter().map(|&i| dealt.shares[i]).collect();
+        assert_eq!(tss.reconstruct(&indices, &values), 17);
+    }
+
+    #[test]
+    fn test_share_iter() {
+        let tss = &SHAMIR_5_20;
+        let seed = [7; 32];
+        let shares: Vec<(u32, i64)> = tss
+            .share_iter_with_rng(17, &mut seeded_rng(&seed))
+            .collect();
+        let expected = tss.share_with_rng(17, &mut seeded_rng(&seed));
+        assert_eq!(
+            shares,
+            expected
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| (i as u32, x))
+                .collect::<Vec<_>>()
+        );
+
+        let (indices, values): (Vec<usize>, Vec<i64>) = tss
+            .share_iter(17)
+            .skip(4)
+            .take(6)
+            .map(|(i, x)| (i as usize, x))
+            .unzip();
+        assert_eq!(indices, [4, 5, 6, 7, 8, 9]);
+        assert_eq!(tss.reconstruct(&indices, &values), 17);
+    }
+
+    #[test]
+    fn test_share_at() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            field: NaturalPrimeField(1613),
+        };
+        let points = [101, 7, 33, 250, 1612];
+        let shares = tss.share_at(1234, &points);
+        assert_eq!(tss.reconstruct_at(&points[..3], &shares[..3]), 1234);
+        assert_eq!(
+            tss.reconstruct_at(&[1612, 7, 250], &[shares[4], shares[1], shares[3]]),
+            1234
+        );
+
+        // the default points interoperate with `reconstruct`
+        let shares = tss.share_at(1234, &[1, 2, 3, 4, 5]);
+        assert_eq!(
+            tss.reconstruct(&[0, 2, 4], &[shares[0], shares[2], shares[4]]),
+            1234
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "evaluation points must be distinct")]
+    fn test_share_at_duplicate() {
+        let tss = &SHAMIR_5_20;
+        let mut points: Vec<i64> = (1..21).collect();
+        points[7] = 3;
+        tss.share_at(17, &points);
+    }
+
+    #[test]
+    #[should_panic(expected = "evaluation points must be non-zero")]
+    fn test_share_at_zero() {
+        let tss = &SHAMIR_5_20;
+        let points: Vec<i64> = (0..20).collect();
+        tss.share_at(17, &points);
+    }
+
+    #[test]
+    fn test_try_reconstruct() {
+        let tss = &SHAMIR_5_20;
+        let shares = tss.share(17);
+        let indices = [1, 4, 6, 9, 12, 17];
+        let values: Vec<i64> = indices.iter().map(|&i| shares[i]).collect();
+        assert_eq!(tss.try_reconstruct(&indices, &values), Ok(17));
+        assert_eq!(
+            tss.try_reconstruct(&[1, 4, 6, 9, 20, 17], &values),
+            Err(IndexError::UnknownShare(20))
+        );
+        assert_eq!(
+            tss.try_reconstruct(&[1, 4, 6, 4, 12, 17], &values),
+            Err(IndexError::DuplicateShare(4))
+        );
+        assert_eq!(
+            tss.try_reconstruct(&indices[..5], &values[..5]),
+            Err(IndexError::TooFewShares(5))
+        );
+        assert_eq!(
+            tss.try_reconstruct(&indices[..5], &values[..6]),
+            Err(IndexError::MismatchedLengths(5, 6))
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_deduplicated() {
+        let tss = &SHAMIR_5_20;
+        let shares = tss.share(17);
+        let indices = [1, 4, 1, 6, 9, 12, 4, 17];
+        let mut values: Vec<i64> = indices.iter().map(|&i| shares[i]).collect();
+        assert_eq!(tss.reconstruct_deduplicated(&indices, &values), Ok(17));
+        assert_eq!(
+            tss.reconstruct_deduplicated(&indices[..7], &values[..7]),
+            Err(IndexError::TooFewShares(5))
+        );
+        values[6] += 1;
+        assert_eq!(
+            tss.reconstruct_deduplicated(&indices, &values),
+            Err(IndexError::ConflictingShare(4))
+        );
+    }
+
+    #[test]
+    fn test_shamir_indexed() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(41),
+        };
+        let secret = 17;
+        let shares = tss.share_indexed(secret);
+        assert_eq!(shares[4].index, 4);
+        assert_eq!(tss.reconstruct_indexed(&shares[1..4]), secret);
+        assert_eq!(
+            tss.reconstruct_indexed(shares.iter().rev().step_by(2)),
+            secret
+        );
+    }
+
+    #[test]
+    fn test_verify_sharing() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(2147483647),
+        };
+        let seed = [7; 32];
+        let mut shares = tss.share_seeded(1234, &seed);
+        assert_eq!(tss.share_seeded(1234, &seed), shares);
+        assert_eq!(
+            tss.reconstruct(&[1, 3, 5], &[shares[1], shares[3], shares[5]]),
+            1234
+        );
+        assert_eq!(tss.verify_sharing(1234, &seed, &shares), Ok(()));
+        assert_eq!(
+            tss.verify_sharing(1234, &[8; 32], &shares),
+            Err(vec![0, 1, 2, 3, 4, 5])
+        );
+        assert_eq!(
+            tss.verify_sharing(1235, &seed, &shares),
+            Err(vec![0, 1, 2, 3, 4, 5])
+        );
+
+        shares[3] = tss.field.add(shares[3], 1);
+        assert_eq!(tss.verify_sharing(1234, &seed, &shares), Err(vec![3]));
+    }
+
+    #[test]
+    fn test_evaluate_at() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(41),
+        };
+        let shares = tss.share(17);
+        assert_eq!(
+            tss.evaluate_at(&[0, 2, 4], &[shares[0], shares[2], shares[4]], &0),
+            17
+        );
+        // recover missing shares
+        for i in 0..6 {
+            assert_eq!(
+                tss.evaluate_at(
+                    &[1, 3, 5],
+                    &[shares[1], shares[3], shares[5]],
+                    &(i as i64 + 1)
+                ),
+                shares[i]
+            );
+        }
+        // a share for an additional participant is consistent with the others
+        let extra = tss.evaluate_at(&[0, 1, 2], &shares[0..3], &7);
+        assert_eq!(
+            tss.reconstruct(&[6, 4, 5], &[extra, shares[4], shares[5]]),
+            17
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_cached() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(41),
+        };
+        let cache = InterpolationCache::new(4);
+        let shares = tss.share(17);
+        assert_eq!(
+            tss.reconstruct_cached(&cache, &[0, 1, 2], &shares[0..3]),
+            17
+        );
+        assert_eq!(
+            tss.reconstruct_cached(&cache, &[2, 3, 4, 5], &shares[2..6]),
+            17
+        );
+        assert_eq!(cache.len(), 2);
+
+        // same committees in another order
+        let shares = tss.share(30);
+        assert_eq!(
+            tss.reconstruct_cached(&cache, &[2, 0, 1], &[shares[2], shares[0], shares[1]]),
+            30
+        );
+        assert_eq!(
+            tss.reconstruct_cached(
+                &cache,
+                &[5, 2, 4, 3],
+                &[shares[5], shares[2], shares[4], shares[3]]
+            ),
+            30
+        );
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_share_arithmetic() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(41),
+        };
+        let shares_1 = tss.share(7);
+        let shares_2 = tss.share(38);
+        let indices = [0, 2, 5];
+        let pick = |shares: &[i64]| -> Vec<i64> { indices.iter().map(|&i| shares[i]).collect() };
+
+        let sum = tss.add_shares(&pick(&shares_1), &pick(&shares_2));
+        assert_eq!(tss.reconstruct(&indices, &sum), 4);
+        let difference = tss.sub_shares(&pick(&shares_1), &pick(&shares_2));
+        assert_eq!(tss.reconstruct(&indices, &difference), 10);
+        let scaled = tss.scale_shares(&pick(&shares_1), &3);
+        assert_eq!(tss.reconstruct(&indices, &scaled), 21);
+
+        let product = tss.mul_shares(&shares_1, &shares_2);
+        assert_eq!(product.reconstruct_limit, 5);
+        assert_eq!(tss.reconstruct(&[1, 2, 3, 4, 5], &product.shares[1..6]), 20);
+    }
+
+    #[test]
+    fn test_affine() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 6,
+            field: NaturalPrimeField(41),
+        };
+        let shares = tss.share(11);
+        // 3 * 11 + 5
+        let shares: Vec<i64> = shares
+            .iter()
+            .map(|share| tss.add_constant(&tss.scale(share, &3), &5))
+            .collect();
+        assert_eq!(
+            tss.reconstruct(&[0, 3, 4], &[shares[0], shares[3], shares[4]]),
+            38
+        );
+    }
+
+    #[test]
+    fn test_special_sharings() {
+        let tss = &SHAMIR_5_20;
+        let indices: Vec<usize> = (3..9).collect();
+        let zero = tss.share_zero();
+        assert_eq!(tss.reconstruct(&indices, &zero[3..9]), 0);
+        let constant = tss.share_constant(17);
+        assert_eq!(tss.reconstruct(&indices, &constant[3..9]), 17);
+        // randomized rather than the constant polynomial
+        assert!(constant.iter().any(|&share| share != 17));
+
+        let mut rng = ChaChaRng::from_seed(&[55]);
+        let (value, shares) = tss.share_random(&mut rng);
+        assert_eq!(tss.reconstruct(&indices, &shares[3..9]), value);
+    }
+
+    #[test]
+    fn test_subshare() {
+        // 2-of-3 departments, each 3-of-5 people
+        let tss = ShamirSecretSharing {
+            threshold: 1,
+            share_count: 3,
+            field: NaturalPrimeField(41),
+        };
+        let sub_tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            field: NaturalPrimeField(41),
+        };
+        let secret = 17;
+        let subshares: Vec<Vec<i64>> = tss
+            .share(secret)
+            .into_iter()
+            .map(|share| tss.subshare(share, &sub_tss))
+            .collect();
+
+        let recovered = tss.reconstruct_from_subshares(
+            &sub_tss,
+            &[0, 2],
+            &[vec![0, 1, 2], vec![1, 3, 4]],
+            &[
+                subshares[0][0..3].to_vec(),
+                vec![subshares[2][1], subshares[2][3], subshares[2][4]],
+            ],
+        );
+        assert_eq!(recovered, secret);
+    }
+
+    #[test]
+    fn test_double_sharing() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 7,
+            field: NaturalPrimeField(41),
+        };
+        let mut rng = ChaChaRng::from_seed(&[48]);
+        let double = tss.share_double_random(&mut rng).unwrap();
+        assert!(tss.verify_double_sharing(&double));
+        assert!(tss.has_degree(&double.low, 2));
+        assert!(!tss.has_degree(&double.high, 2));
+        assert!(tss.has_degree(&double.high, 4));
+
+        // multiply following DN07: open `xy - r` and add it to the shares of `r`
+        let x = tss.share_with_rng(5, &mut rng);
+        let y = tss.share_with_rng(6, &mut rng);
+        let product = tss.mul_shares(&x, &y);
+        let masked = tss.sub_shares(&product.shares, &double.high);
+        let indices: Vec<usize> = (0..product.reconstruct_limit).collect();
+        let opened = ShamirSecretSharing {
+            threshold: 4,
+            ..tss.clone()
+        }
+        .reconstruct(&indices, &masked[..product.reconstruct_limit]);
+        let shares: Vec<i64> = double
+            .low
+            .iter()
+            .map(|share| tss.add_constant(share, &opened))
+            .collect();
+        assert!(tss.has_degree(&shares, 2));
+        assert_eq!(
+            tss.reconstruct(&[1, 4, 6], &[shares[1], shares[4], shares[6]]),
+            30
+        );
+    }
+
+    #[test]
+    fn test_inconsistent_double_sharing() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 7,
+            field: NaturalPrimeField(41),
+        };
+        let mut rng = ChaChaRng::from_seed(&[48]);
+        let double = tss.share_double_random(&mut rng).unwrap();
+
+        let mut other = double.clone();
+        other.high = ShamirSecretSharing {
+            threshold: 4,
+            ..tss.clone()
+        }
+        .share_with_rng(3, &mut rng);
+        assert_ne!(other, double);
+        assert!(!tss.verify_double_sharing(&other));
+        let mut other = double.clone();
+        other.low[5] = tss.field.add(other.low[5], 1);
+        assert!(!tss.verify_double_sharing(&other));
+        let mut other = double.clone();
+        other.high.pop();
+        assert!(!tss.verify_double_sharing(&other));
+    }
+
+    #[test]
+    fn test_double_sharing_too_few_shares() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 4,
+            field: NaturalPrimeField(41),
+        };
+        let mut rng = ChaChaRng::from_seed(&[48]);
+        assert_eq!(tss.share_double_random(&mut rng), None);
+
+        let double = DoubleSharing {
+            low: tss.share_with_rng(3, &mut rng),
+            high: tss.share_with_rng(3, &mut rng),
+        };
+        assert!(!tss.verify_double_sharing(&double));
+    }
 }
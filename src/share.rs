@@ -0,0 +1,733 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Shares carrying their own index, as an alternative to passing parallel slices of
+//! indices and values.
+
+use fields::{ElementBytes, Field};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::slice;
+
+/// Rank of a share as output by the `share` methods, starting from zero.
+pub type ShareIndex = usize;
+
+/// `index` as the `u32` taken by the packed scheme and used in serializations.
+///
+/// Panics if it does not fit, since no scheme has that many shares.
+pub(crate) fn index_u32(index: ShareIndex) -> u32 {
+    u32::try_from(index).expect("share index does not fit in a u32")
+}
+
+/// A share together with its index.
+pub struct Share<F: Field> {
+    /// Rank of the share as output by the `share` methods.
+    pub index: ShareIndex,
+    /// Value of the share.
+    pub value: F::E,
+}
+
+impl<F: Field> Share<F> {
+    pub fn new(index: ShareIndex, value: F::E) -> Share<F> {
+        Share { index, value }
+    }
+
+    /// Attach indices to all the shares output by one of the `share` methods.
+    pub fn enumerate(values: Vec<F::E>) -> Vec<Share<F>> {
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| Share { index, value })
+            .collect()
+    }
+
+    /// Split `shares` into the parallel slices of indices and values expected by
+    /// the `reconstruct` methods.
+    pub fn unzip<'a, I>(shares: I) -> (Vec<ShareIndex>, Vec<F::E>)
+    where
+        I: IntoIterator<Item = &'a Share<F>>,
+        F: 'a,
+        F::E: Clone,
+    {
+        shares
+            .into_iter()
+            .map(|share| (share.index, share.value.clone()))
+            .unzip()
+    }
+}
+
+impl<F: ElementBytes> Share<F> {
+    /// Serialize the share as its index, as a little-endian `u32`, followed by its
+    /// value using `ElementBytes`.
+    ///
+    /// All shares of a field thus serialize to the same length.
+    pub fn to_bytes(&self, field: &F) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + field.element_len());
+        bytes.extend_from_slice(&index_u32(self.index).to_le_bytes());
+        field.write_element(&self.value, &mut bytes);
+        bytes
+    }
+
+    /// Share serialized by `to_bytes`, or `None` if `bytes` is malformed.
+    pub fn from_bytes(field: &F, bytes: &[u8]) -> Option<Share<F>> {
+        if bytes.len() != 4 + field.element_len() {
+            return None;
+        }
+        let index = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        let value = field.read_element(&bytes[4..])?;
+        Some(Share { index, value })
+    }
+
+    /// Lowercase hexadecimal string of `to_bytes`, for instance to be written down
+    /// as a paper backup.
+    pub fn to_hex(&self, field: &F) -> String {
+        self.to_bytes(field)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Share written by `to_hex`, in either case and ignoring surrounding
+    /// whitespace, or `None` if `hex` is malformed.
+    pub fn from_hex(field: &F, hex: &str) -> Option<Share<F>> {
+        let hex = hex.trim().as_bytes();
+        if hex.len() % 2 != 0 {
+            return None;
+        }
+        let bytes = hex
+            .chunks(2)
+            .map(|pair| Some(hex_digit(pair[0])? << 4 | hex_digit(pair[1])?))
+            .collect::<Option<Vec<u8>>>()?;
+        Share::from_bytes(field, &bytes)
+    }
+
+    /// Padded base64 string of `to_bytes`, using the standard alphabet.
+    pub fn to_base64(&self, field: &F) -> String {
+        let bytes = self.to_bytes(field);
+        let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let word = chunk
+                .iter()
+                .enumerate()
+                .fold(0_u32, |acc, (i, &byte)| acc | (byte as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    text.push(BASE64_ALPHABET[(word >> (18 - 6 * i) & 0x3f) as usize] as char);
+                } else {
+                    text.push('=');
+                }
+            }
+        }
+        text
+    }
+
+    /// Share written by `to_base64`, ignoring surrounding whitespace, or `None` if
+    /// `text` is malformed.
+    pub fn from_base64(field: &F, text: &str) -> Option<Share<F>> {
+        let text = text.trim().as_bytes();
+        if text.len() % 4 != 0 {
+            return None;
+        }
+        let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+        for (n, chunk) in text.chunks(4).enumerate() {
+            let last = n + 1 == text.len() / 4;
+            let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+            if padding > 2 || (padding > 0 && !last) {
+                return None;
+            }
+            let mut word = 0_u32;
+            for &c in &chunk[..4 - padding] {
+                let digit = BASE64_ALPHABET.iter().position(|&a| a == c)?;
+                word = word << 6 | digit as u32;
+            }
+            word <<= 6 * padding;
+            // the bits left over by the padding must be zero
+            if word & ((1 << (8 * padding)) - 1) != 0 {
+                return None;
+            }
+            bytes.extend_from_slice(&word.to_be_bytes()[1..4 - padding]);
+        }
+        Share::from_bytes(field, &bytes)
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn hex_digit(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+impl<F: Field> From<(ShareIndex, F::E)> for Share<F> {
+    fn from((index, value): (ShareIndex, F::E)) -> Share<F> {
+        Share { index, value }
+    }
+}
+
+element_impls!(Clone, PartialEq, Debug for Share { index, value });
+
+/// A share together with its index and a tag binding it to where it comes from,
+/// such as a MAC or the identifier of a context.
+pub struct TaggedShare<F: Field, T> {
+    /// Rank of the share as output by the `share` methods.
+    pub index: ShareIndex,
+    /// Value of the share.
+    pub value: F::E,
+    /// Tag of the share, checked at reconstruction.
+    pub tag: T,
+}
+
+impl<F: Field, T> TaggedShare<F, T> {
+    /// Attach to every share output by one of the `share` methods its index and the
+    /// tag computed from both.
+    pub(crate) fn tag_all<G>(values: Vec<F::E>, mut tag: G) -> Vec<TaggedShare<F, T>>
+    where
+        G: FnMut(ShareIndex, &F::E) -> T,
+    {
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| TaggedShare {
+                index,
+                tag: tag(index, &value),
+                value,
+            })
+            .collect()
+    }
+
+    /// Indices and values of `shares` if `valid` accepts all of them.
+    pub(crate) fn check_all<V>(
+        shares: &[TaggedShare<F, T>],
+        valid: V,
+    ) -> Result<(Vec<ShareIndex>, Vec<F::E>), InvalidTags>
+    where
+        V: Fn(&TaggedShare<F, T>) -> bool,
+        F::E: Clone,
+    {
+        let invalid: Vec<ShareIndex> = shares
+            .iter()
+            .filter(|share| !valid(share))
+            .map(|share| share.index)
+            .collect();
+        if !invalid.is_empty() {
+            return Err(InvalidTags(invalid));
+        }
+        Ok(shares
+            .iter()
+            .map(|share| (share.index, share.value.clone()))
+            .unzip())
+    }
+}
+
+element_impls!(Clone, PartialEq, Debug for TaggedShare<T> { index, value, tag });
+
+/// Tagged shares rejected at reconstruction, by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTags(pub Vec<ShareIndex>);
+
+impl fmt::Display for InvalidTags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid tags on shares {:?}", self.0)
+    }
+}
+
+impl Error for InvalidTags {}
+
+/// Invalid shares given to the `try_reconstruct` methods or added to a
+/// `ReconstructionSession`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexError {
+    /// The index is not that of a share of the scheme.
+    UnknownShare(ShareIndex),
+    /// The index is given more than once.
+    DuplicateShare(ShareIndex),
+    /// The index is given more than once with different values.
+    ConflictingShare(ShareIndex),
+    /// Only this many distinct shares were given, fewer than `reconstruct_limit`.
+    TooFewShares(usize),
+    /// The share does not agree with the polynomial interpolated from the first
+    /// `reconstruct_limit` shares of a session.
+    InconsistentShare(ShareIndex),
+    /// This many indices were given for this many shares.
+    MismatchedLengths(usize, usize),
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IndexError::UnknownShare(index) => write!(f, "no share with index {}", index),
+            IndexError::DuplicateShare(index) => {
+                write!(f, "share with index {} is given more than once", index)
+            }
+            IndexError::ConflictingShare(index) => {
+                write!(
+                    f,
+                    "share with index {} is given with different values",
+                    index
+                )
+            }
+            IndexError::TooFewShares(count) => {
+                write!(f, "only {} distinct shares are given", count)
+            }
+            IndexError::InconsistentShare(index) => {
+                write!(
+                    f,
+                    "share with index {} is inconsistent with the others",
+                    index
+                )
+            }
+            IndexError::MismatchedLengths(indices, shares) => {
+                write!(f, "{} indices are given for {} shares", indices, shares)
+            }
+        }
+    }
+}
+
+impl Error for IndexError {}
+
+/// Check that there are as many `indices` as `shares`.
+pub(crate) fn check_lengths<I, S>(indices: &[I], shares: &[S]) -> Result<(), IndexError> {
+    if indices.len() != shares.len() {
+        return Err(IndexError::MismatchedLengths(indices.len(), shares.len()));
+    }
+    Ok(())
+}
+
+/// Check that `indices` are distinct ranks of shares among `share_count`, and that
+/// there are at least `limit` of them.
+pub fn check_indices<I>(share_count: usize, limit: usize, indices: I) -> Result<(), IndexError>
+where
+    I: IntoIterator<Item = ShareIndex>,
+{
+    let mut seen = vec![false; share_count];
+    let mut count = 0;
+    for index in indices {
+        let seen = seen.get_mut(index).ok_or(IndexError::UnknownShare(index))?;
+        if *seen {
+            return Err(IndexError::DuplicateShare(index));
+        }
+        *seen = true;
+        count += 1;
+    }
+    if count < limit {
+        return Err(IndexError::TooFewShares(count));
+    }
+    Ok(())
+}
+
+/// Drop the repetitions of shares given several times in `indices` and `shares`,
+/// after checking that they have the same value, and check the remaining ones as
+/// with `check_indices`.
+pub fn deduplicate_shares<F: Field>(
+    field: &F,
+    share_count: usize,
+    limit: usize,
+    indices: &[ShareIndex],
+    shares: &[F::E],
+) -> Result<(Vec<ShareIndex>, Vec<F::E>), IndexError>
+where
+    F::E: Clone,
+{
+    check_lengths(indices, shares)?;
+    // position of each share among the distinct ones
+    let mut positions = vec![None; share_count];
+    let mut distinct_indices = Vec::with_capacity(indices.len());
+    let mut distinct_shares: Vec<F::E> = Vec::with_capacity(shares.len());
+    for (&index, share) in indices.iter().zip(shares) {
+        let position = positions
+            .get_mut(index)
+            .ok_or(IndexError::UnknownShare(index))?;
+        match *position {
+            None => {
+                *position = Some(distinct_shares.len());
+                distinct_indices.push(index);
+                distinct_shares.push(share.clone());
+            }
+            Some(previous) if field.eq(&distinct_shares[previous], share) => {}
+            Some(_) => return Err(IndexError::ConflictingShare(index)),
+        }
+    }
+    if distinct_shares.len() < limit {
+        return Err(IndexError::TooFewShares(distinct_shares.len()));
+    }
+    Ok((distinct_indices, distinct_shares))
+}
+
+/// Pointwise sum of the shares `a` and `b` of two sharings.
+pub(crate) fn add_shares<F: Field>(field: &F, a: &[F::E], b: &[F::E]) -> Vec<F::E>
+where
+    F::E: Clone,
+{
+    let mut sum = a.to_vec();
+    field.add_assign_slice(&mut sum, b);
+    sum
+}
+
+/// Pointwise difference of the shares `a` and `b` of two sharings.
+pub(crate) fn sub_shares<F: Field>(field: &F, a: &[F::E], b: &[F::E]) -> Vec<F::E> {
+    assert_eq!(a.len(), b.len());
+    a.iter().zip(b).map(|(x, y)| field.sub(x, y)).collect()
+}
+
+/// Shares `a` of a sharing, each multiplied by `scalar`.
+pub(crate) fn scale_shares<F: Field>(field: &F, a: &[F::E], scalar: &F::E) -> Vec<F::E>
+where
+    F::E: Clone,
+{
+    let mut scaled = a.to_vec();
+    field.mul_scalar_slice(&mut scaled, scalar);
+    scaled
+}
+
+/// Pointwise product of the shares `a` and `b` of two sharings, which together
+/// share the product with a polynomial of the sum of their degrees.
+pub(crate) fn mul_shares<F: Field>(
+    field: &F,
+    a: &[F::E],
+    b: &[F::E],
+    reconstruct_limit: usize,
+) -> ProductShares<F>
+where
+    F::E: Clone,
+{
+    let mut product = a.to_vec();
+    field.mul_pairwise_slice(&mut product, b);
+    ProductShares {
+        shares: product,
+        reconstruct_limit,
+    }
+}
+
+/// Shares of the pointwise product of two sharings, as output by the `mul_shares`
+/// methods.
+///
+/// The product is shared by a polynomial of twice the degree, so more shares are
+/// needed to reconstruct it than for the inputs.
+pub struct ProductShares<F: Field> {
+    /// Product of each pair of shares, in the order of the inputs.
+    pub shares: Vec<F::E>,
+    /// Minimum number of these shares required to reconstruct the product.
+    pub reconstruct_limit: usize,
+}
+
+element_impls!(Clone, Debug for ProductShares { shares, reconstruct_limit });
+
+/// Shares together with the coefficients of the polynomial they were evaluated from,
+/// as output by the `share_returning_polynomial` methods.
+///
+/// The coefficients are secret, as they determine the shared secrets; they are meant
+/// for the dealer, for instance to commit to them.
+pub struct PolynomialShares<F: Field> {
+    /// Shares in the order output by `share`.
+    pub shares: Vec<F::E>,
+    /// Coefficients of the sharing polynomial, lowest degree first.
+    pub coefficients: Vec<F::E>,
+}
+
+element_impls!(Clone for PolynomialShares { shares, coefficients });
+
+impl<F: Field> fmt::Debug for PolynomialShares<F>
+where
+    F::E: fmt::Debug,
+{
+    /// The coefficients are secret and are not printed.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PolynomialShares")
+            .field("shares", &self.shares)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Shares of several sharings, such as output by `PackedSecretSharing::share_batch`,
+/// viewable both per chunk of secrets and per recipient.
+///
+/// Shares are stored chunk by chunk, and the per-recipient views borrow from this
+/// storage instead of copying it.
+pub struct ShareMatrix<F: Field> {
+    share_count: usize,
+    values: Vec<F::E>,
+}
+
+impl<F: Field> ShareMatrix<F> {
+    /// Matrix of the shares of each chunk, which must all have the same number of
+    /// shares.
+    pub fn from_chunks(chunks: Vec<Vec<F::E>>) -> ShareMatrix<F> {
+        let share_count = chunks.first().map_or(0, |chunk| chunk.len());
+        assert!(chunks.iter().all(|chunk| chunk.len() == share_count));
+        ShareMatrix {
+            share_count,
+            values: chunks.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Number of sharings.
+    pub fn chunk_count(&self) -> usize {
+        self.values.len().checked_div(self.share_count).unwrap_or(0)
+    }
+
+    /// Number of shares in each sharing, and hence of recipients.
+    pub fn share_count(&self) -> usize {
+        self.share_count
+    }
+
+    /// Shares of the sharing with index `chunk`.
+    pub fn chunk(&self, chunk: usize) -> &[F::E] {
+        &self.values[chunk * self.share_count..(chunk + 1) * self.share_count]
+    }
+
+    /// Shares of every sharing in turn.
+    pub fn by_chunk(&self) -> slice::Chunks<'_, F::E> {
+        self.values.chunks(self.share_count.max(1))
+    }
+
+    /// Shares of the recipient with index `recipient`, one for each sharing.
+    pub fn recipient(&self, recipient: ShareIndex) -> RecipientShares<'_, F> {
+        assert!(recipient < self.share_count);
+        RecipientShares {
+            values: &self.values,
+            share_count: self.share_count,
+            recipient,
+        }
+    }
+
+    /// Shares of every recipient in turn, ie. the transposed matrix.
+    pub fn by_recipient(&self) -> Vec<RecipientShares<'_, F>> {
+        (0..self.share_count).map(|r| self.recipient(r)).collect()
+    }
+
+    /// Back to the shares of each sharing.
+    pub fn into_chunks(self) -> Vec<Vec<F::E>>
+    where
+        F::E: Clone,
+    {
+        self.by_chunk().map(|chunk| chunk.to_vec()).collect()
+    }
+}
+
+element_impls!(Clone, Debug for ShareMatrix { share_count, values });
+
+/// Shares of a single recipient in a `ShareMatrix`, borrowed from it.
+pub struct RecipientShares<'a, F: Field + 'a> {
+    values: &'a [F::E],
+    share_count: usize,
+    recipient: ShareIndex,
+}
+
+impl<'a, F: Field> RecipientShares<'a, F> {
+    /// Index of the recipient.
+    pub fn index(&self) -> ShareIndex {
+        self.recipient
+    }
+
+    /// Number of shares, one for each sharing.
+    pub fn len(&self) -> usize {
+        self.values.len() / self.share_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Share of the sharing with index `chunk`.
+    pub fn get(&self, chunk: usize) -> &'a F::E {
+        &self.values[chunk * self.share_count + self.recipient]
+    }
+
+    pub fn iter(&self) -> ::std::iter::StepBy<::std::iter::Skip<slice::Iter<'a, F::E>>> {
+        self.values
+            .iter()
+            .skip(self.recipient)
+            .step_by(self.share_count)
+    }
+
+    /// Copy of the shares, to be sent to the recipient.
+    pub fn bundle(&self) -> RecipientBundle<F>
+    where
+        F::E: Clone,
+    {
+        RecipientBundle {
+            index: self.recipient,
+            shares: self.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Shares of a single recipient, one for each sharing, as sent to it.
+pub struct RecipientBundle<F: Field> {
+    /// Index of the recipient.
+    pub index: ShareIndex,
+    /// Share of each sharing, in order.
+    pub shares: Vec<F::E>,
+}
+
+impl<F: Field> RecipientBundle<F> {
+    /// Serialize the bundle as the index and the number of shares, both as
+    /// little-endian `u32`, followed by the shares using `ElementBytes`.
+    pub fn to_bytes(&self, field: &F) -> Vec<u8>
+    where
+        F: ElementBytes,
+    {
+        let mut bytes = Vec::with_capacity(8 + self.shares.len() * field.element_len());
+        let count = u32::try_from(self.shares.len()).expect("too many shares in the bundle");
+        bytes.extend_from_slice(&index_u32(self.index).to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        for share in &self.shares {
+            field.write_element(share, &mut bytes);
+        }
+        bytes
+    }
+
+    /// Bundle serialized by `to_bytes`, or `None` if `bytes` is malformed.
+    pub fn from_bytes(field: &F, bytes: &[u8]) -> Option<RecipientBundle<F>>
+    where
+        F: ElementBytes,
+    {
+        let read_u32 = |offset: usize| {
+            let word = bytes.get(offset..offset + 4)?;
+            Some(u32::from_le_bytes([word[0], word[1], word[2], word[3]]) as usize)
+        };
+        let index = read_u32(0)?;
+        let count = read_u32(4)?;
+        let element_len = field.element_len();
+        if bytes.len() - 8 != count.checked_mul(element_len)? {
+            return None;
+        }
+        let shares = bytes[8..]
+            .chunks(element_len)
+            .map(|chunk| field.read_element(chunk))
+            .collect::<Option<Vec<F::E>>>()?;
+        Some(RecipientBundle { index, shares })
+    }
+}
+
+element_impls!(Clone, PartialEq, Debug for RecipientBundle { index, shares });
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::NaturalPrimeField;
+
+    #[test]
+    fn test_enumerate_unzip() {
+        let shares: Vec<Share<NaturalPrimeField<i64>>> = Share::enumerate(vec![4, 8, 15]);
+        assert_eq!(shares[2], Share::new(2, 15));
+        assert_eq!(shares[1], Share::from((1, 8)));
+        let (indices, values) = Share::unzip(&[shares[2].clone(), shares[0].clone()]);
+        assert_eq!(indices, [2, 0]);
+        assert_eq!(values, [15, 4]);
+    }
+
+    #[test]
+    fn test_string_formats() {
+        let field = NaturalPrimeField(2147483647);
+        let share: Share<NaturalPrimeField<i64>> = Share::new(3, 0x1234abcd);
+        assert_eq!(share.to_bytes(&field), [3, 0, 0, 0, 0xcd, 0xab, 0x34, 0x12]);
+        assert_eq!(share.to_hex(&field), "03000000cdab3412");
+        assert_eq!(
+            Share::from_hex(&field, " 03000000CDAB3412\n"),
+            Some(share.clone())
+        );
+        assert_eq!(share.to_base64(&field), "AwAAAM2rNBI=");
+        assert_eq!(
+            Share::from_base64(&field, "AwAAAM2rNBI=\n"),
+            Some(share.clone())
+        );
+
+        // same length for every share of a field
+        let small: Share<NaturalPrimeField<i64>> = Share::new(0, 1);
+        assert_eq!(small.to_hex(&field), "0000000001000000");
+        assert_eq!(
+            Share::from_hex(&field, &small.to_hex(&field)),
+            Some(small.clone())
+        );
+        assert_eq!(
+            Share::from_base64(&field, &small.to_base64(&field)),
+            Some(small)
+        );
+
+        // wrong length, invalid digits, or values outside the field
+        assert_eq!(Share::from_hex(&field, "03000000cdab34"), None);
+        assert_eq!(Share::from_hex(&field, "03000000cdab341"), None);
+        assert_eq!(Share::from_hex(&field, "03000000cdab34g2"), None);
+        assert_eq!(Share::from_hex(&field, "03000000ffffffff"), None);
+        assert_eq!(Share::from_base64(&field, "AwAAAM2rNBI"), None);
+        assert_eq!(Share::from_base64(&field, "AwAAAM2rNBJ="), None);
+        assert_eq!(Share::from_base64(&field, "AwAA=M2rNBI="), None);
+        assert_eq!(Share::from_base64(&field, "AwAAAM2r*BI="), None);
+    }
+
+    #[test]
+    fn test_polynomial_shares_debug() {
+        let shares: PolynomialShares<NaturalPrimeField<i64>> = PolynomialShares {
+            shares: vec![12, 34],
+            coefficients: vec![5678, 9],
+        };
+        assert_eq!(
+            format!("{:?}", shares),
+            "PolynomialShares { shares: [12, 34], .. }"
+        );
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    #[should_panic(expected = "share index does not fit in a u32")]
+    fn test_index_too_large() {
+        let share: Share<NaturalPrimeField<i64>> = Share::new(1 << 32, 1);
+        share.to_bytes(&NaturalPrimeField(433));
+    }
+
+    #[test]
+    fn test_share_matrix() {
+        let chunks = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let matrix: ShareMatrix<NaturalPrimeField<i64>> = ShareMatrix::from_chunks(chunks.clone());
+        assert_eq!(matrix.chunk_count(), 2);
+        assert_eq!(matrix.share_count(), 3);
+        assert_eq!(matrix.chunk(1), [4, 5, 6]);
+        assert_eq!(
+            matrix.by_chunk().collect::<Vec<_>>(),
+            [[1, 2, 3], [4, 5, 6]]
+        );
+
+        let recipients = matrix.by_recipient();
+        assert_eq!(recipients.len(), 3);
+        assert_eq!(recipients[1].index(), 1);
+        assert_eq!(recipients[1].len(), 2);
+        assert_eq!(*recipients[2].get(1), 6);
+        let transposed: Vec<Vec<i64>> = recipients
+            .iter()
+            .map(|r| r.iter().cloned().collect())
+            .collect();
+        assert_eq!(transposed, [[1, 4], [2, 5], [3, 6]]);
+        assert_eq!(matrix.into_chunks(), chunks);
+
+        let empty: ShareMatrix<NaturalPrimeField<i64>> = ShareMatrix::from_chunks(vec![]);
+        assert_eq!(empty.chunk_count(), 0);
+        assert!(empty.by_recipient().is_empty());
+    }
+
+    #[test]
+    fn test_recipient_bundle() {
+        let field = NaturalPrimeField(17);
+        let matrix: ShareMatrix<NaturalPrimeField<i64>> =
+            ShareMatrix::from_chunks(vec![vec![1, 2, 3], vec![4, 5, 16]]);
+        let bundle = matrix.recipient(2).bundle();
+        assert_eq!(bundle.index, 2);
+        assert_eq!(bundle.shares, [3, 16]);
+
+        let bytes = bundle.to_bytes(&field);
+        assert_eq!(bytes, [2, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 16, 0, 0, 0]);
+        assert_eq!(RecipientBundle::from_bytes(&field, &bytes), Some(bundle));
+        assert_eq!(RecipientBundle::from_bytes(&field, &bytes[..15]), None);
+        assert_eq!(RecipientBundle::from_bytes(&field, &bytes[..6]), None);
+        let mut invalid = bytes.clone();
+        invalid[12] = 17;
+        assert_eq!(RecipientBundle::from_bytes(&field, &invalid), None);
+    }
+}
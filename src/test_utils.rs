@@ -0,0 +1,170 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Checks for validating `Field` backends and schemes, available with the
+//! `test_utils` feature.
+//!
+//! These are the checks the backends of this crate are tested with, so that
+//! downstream implementations of `Field` can be held to the same laws. All of them
+//! panic with a description of the violated law on failure.
+
+use std::fmt::Debug;
+
+use fields::{Decode, ElementBytes, Encode, Field, KnownPrime};
+use numtheory::PrimeModulus;
+use rand::Rng;
+use scheme::ThresholdScheme;
+
+/// `count` random elements of `field`, preceded by the edge cases `0`, `1`, `2`,
+/// `-1` and `-2`.
+pub fn sample_elements<F, R>(field: &F, count: usize, rng: &mut R) -> Vec<F::E>
+where
+    F: Field,
+    R: Rng,
+{
+    let two = field.add(field.one(), field.one());
+    let mut elements = vec![
+        field.zero(),
+        field.one(),
+        field.sub(field.zero(), field.one()),
+        field.sub(field.zero(), &two),
+        two,
+    ];
+    elements.extend(field.sample_with_replacement(count, rng));
+    elements
+}
+
+/// Check the field axioms, Fermat's little theorem, the compatibility of `Encode`
+/// with the arithmetic, and the byte representation, on `count` random elements.
+pub fn check_field_laws<F, R>(field: &F, count: usize, rng: &mut R)
+where
+    F: KnownPrime + Encode<u32> + ElementBytes,
+    F::P: PrimeModulus,
+    F::E: Clone,
+    R: Rng,
+{
+    let zero = field.zero();
+    let one = field.one();
+    let order = field.prime().cofactor(1).expect("the prime is at least 2");
+
+    let elements = sample_elements(field, count, rng);
+    for a in &elements {
+        let b = field.sample(rng);
+        let c = field.sample(rng);
+
+        assert!(
+            field.eq(
+                field.add(field.add(a, &b), &c),
+                field.add(a, field.add(&b, &c))
+            ),
+            "addition is not associative"
+        );
+        assert!(
+            field.eq(
+                field.mul(field.mul(a, &b), &c),
+                field.mul(a, field.mul(&b, &c))
+            ),
+            "multiplication is not associative"
+        );
+        assert!(
+            field.eq(field.add(a, &b), field.add(&b, a)),
+            "addition is not commutative"
+        );
+        assert!(
+            field.eq(field.mul(a, &b), field.mul(&b, a)),
+            "multiplication is not commutative"
+        );
+        assert!(
+            field.eq(
+                field.mul(a, field.add(&b, &c)),
+                field.add(field.mul(a, &b), field.mul(a, &c))
+            ),
+            "multiplication does not distribute over addition"
+        );
+        assert!(field.eq(field.add(a, &zero), a), "zero is not neutral");
+        assert!(field.eq(field.mul(a, &one), a), "one is not neutral");
+        assert!(
+            field.eq(field.add(field.sub(a, &b), &b), a),
+            "subtraction does not invert addition"
+        );
+        assert!(
+            field.eq(field.normalize(a), a),
+            "normalize changes the element"
+        );
+        assert!(
+            field.eq(field.pow(a, 3), field.mul(a, field.mul(a, a))),
+            "pow disagrees with multiplication"
+        );
+
+        if field.neq(a, &zero) {
+            assert!(
+                field.eq(field.mul(a, field.inv(a)), &one),
+                "inv is not a multiplicative inverse"
+            );
+            assert!(
+                field.eq(field.pow(a, &order), &one),
+                "Fermat's little theorem does not hold"
+            );
+        }
+
+        let mut bytes = vec![];
+        field.write_element(a, &mut bytes);
+        assert_eq!(bytes.len(), field.element_len(), "wrong element length");
+        let read = field.read_element(&bytes).expect("element is not readable");
+        assert!(field.eq(read, a), "bytes do not roundtrip");
+    }
+
+    for x in 0_u32..16 {
+        for y in 0_u32..16 {
+            assert!(
+                field.eq(
+                    field.add(field.encode(x), field.encode(y)),
+                    field.encode(x + y)
+                ),
+                "encoding is not additive"
+            );
+            assert!(
+                field.eq(
+                    field.mul(field.encode(x), field.encode(y)),
+                    field.encode(x * y)
+                ),
+                "encoding is not multiplicative"
+            );
+        }
+    }
+}
+
+/// Check that `values`, which must be below the prime of `field`, survive encoding
+/// and decoding.
+pub fn check_encode_decode<F, T>(field: &F, values: &[T])
+where
+    F: Field + Encode<T> + Decode<T>,
+    T: Copy + PartialEq + Debug,
+{
+    for &x in values {
+        let decoded: T = field.decode(field.encode(x));
+        assert_eq!(decoded, x, "encoding does not roundtrip");
+    }
+}
+
+/// Check that `secret` is recovered from the first and last `reconstruct_limit`
+/// shares produced by `scheme`, as well as from all of them.
+pub fn check_scheme_roundtrip<S>(scheme: &S, secret: S::Secret)
+where
+    S: ThresholdScheme,
+    S::Secret: PartialEq + Debug,
+    <S::Field as Field>::E: Clone,
+{
+    let shares = scheme.share(&secret);
+    assert_eq!(shares.len(), scheme.share_count(), "wrong number of shares");
+    let limit = scheme.reconstruct_limit();
+    assert_eq!(scheme.reconstruct(&shares[..limit]), secret);
+    let tail = shares.len() - limit;
+    assert_eq!(scheme.reconstruct(&shares[tail..]), secret);
+    assert_eq!(scheme.reconstruct(&shares), secret);
+}
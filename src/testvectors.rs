@@ -0,0 +1,632 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Deterministic test vectors for checking other implementations of the schemes
+//! against this crate, available with the `testvectors` feature.
+//!
+//! Each `TestVector` records the parameters of a sharing, its secrets, the random
+//! field elements drawn for it and the resulting shares. In JSON all field elements
+//! are decimal strings of their value in `0..prime`, so that they survive parsers
+//! limited to 53-bit numbers. The randomness is listed in the order it is drawn:
+//!
+//! * for Shamir sharing, the coefficients of `x^1` up to `x^threshold` of the
+//!   sharing polynomial, whose constant term is the secret and whose value at
+//!   `i + 1` is share `i`;
+//! * for packed sharing, the values of the sharing polynomial at
+//!   `omega_secrets^(secret_count + 1)` up to `omega_secrets^(secret_count + threshold)`.
+//!   The polynomial is zero at `1`, takes secret `i` at `omega_secrets^(i + 1)`, and
+//!   its value at `omega_shares^(i + 1)` is share `i`.
+//!
+//! `generate` produces vectors for both schemes over every field backend, which the
+//! `testvectors` example writes out with `write_json`. The `seed` of a vector only
+//! reproduces its secrets and randomness with the generator of this crate, so other
+//! implementations should start from the randomness instead. `read_json` loads
+//! vectors back, and `replay` checks them against the crate.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::iter;
+
+use serde_json::{self, Value};
+
+use fields::{
+    Encode, Field, Fp61, IntegerCoding, MontgomeryField32, NaturalPrimeField, New, U64Field,
+};
+use numtheory::fft::is_mixed_radix_len;
+use numtheory::{lagrange_interpolation_at_point, mod_evaluate_polynomial};
+use packed::PackedSecretSharing;
+use seed::{seeded_rng, Seed};
+use shamir::ShamirSecretSharing;
+
+/// Sharing scheme of a test vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorScheme {
+    Shamir,
+    Packed,
+}
+
+impl VectorScheme {
+    /// Name of the scheme in JSON.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            VectorScheme::Shamir => "shamir",
+            VectorScheme::Packed => "packed",
+        }
+    }
+
+    /// Scheme with the given `name`, if any.
+    pub fn from_name(name: &str) -> Option<VectorScheme> {
+        [VectorScheme::Shamir, VectorScheme::Packed]
+            .iter()
+            .cloned()
+            .find(|scheme| scheme.name() == name)
+    }
+}
+
+/// Field implementation a test vector was generated with.
+///
+/// All backends give the same shares for the same secrets and randomness, but draw
+/// different randomness from the same seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// `NaturalPrimeField<i64>`.
+    Natural,
+    /// `MontgomeryField32`.
+    Montgomery32,
+    /// `U64Field`.
+    U64,
+    /// `Fp61`, whose prime is always `2^61 - 1`.
+    Fp61,
+}
+
+impl Backend {
+    /// Name of the backend in JSON.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Backend::Natural => "natural",
+            Backend::Montgomery32 => "montgomery32",
+            Backend::U64 => "u64",
+            Backend::Fp61 => "fp61",
+        }
+    }
+
+    /// Backend with the given `name`, if any.
+    pub fn from_name(name: &str) -> Option<Backend> {
+        [
+            Backend::Natural,
+            Backend::Montgomery32,
+            Backend::U64,
+            Backend::Fp61,
+        ]
+        .iter()
+        .cloned()
+        .find(|backend| backend.name() == name)
+    }
+}
+
+/// A sharing together with everything needed to reproduce it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    pub scheme: VectorScheme,
+    pub backend: Backend,
+    pub prime: u64,
+    pub threshold: usize,
+    pub share_count: usize,
+    /// Number of secrets, always one for Shamir sharing.
+    pub secret_count: usize,
+    /// Root of unity of order `secret_count + threshold + 1` for packed sharing.
+    pub omega_secrets: Option<u64>,
+    /// Root of unity of order `share_count + 1` for packed sharing.
+    pub omega_shares: Option<u64>,
+    /// Seed from which the secrets and then the randomness are sampled.
+    pub seed: Seed,
+    pub secrets: Vec<u64>,
+    pub randomness: Vec<u64>,
+    pub shares: Vec<u64>,
+}
+
+/// Failure to load or replay a test vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorError {
+    /// The entry with this key is missing or malformed.
+    Malformed(&'static str),
+    /// The parameters do not describe a scheme over the backend.
+    InvalidParameters,
+    /// The shares do not follow from the secrets and randomness.
+    Shares,
+    /// Sampling from the seed gives different secrets, randomness or shares.
+    Sampling,
+    /// Reconstructing from the shares gives different secrets.
+    Reconstruction,
+}
+
+impl fmt::Display for VectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VectorError::Malformed(key) => write!(f, "missing or malformed {}", key),
+            VectorError::InvalidParameters => write!(f, "invalid scheme parameters"),
+            VectorError::Shares => write!(f, "shares do not follow from the randomness"),
+            VectorError::Sampling => write!(f, "seed does not reproduce the sharing"),
+            VectorError::Reconstruction => write!(f, "shares do not reconstruct the secrets"),
+        }
+    }
+}
+
+impl Error for VectorError {}
+
+/// Evaluate `$body` with `$field` bound to the field of `$vector`, if the backend
+/// supports its prime.
+macro_rules! with_backend {
+    ($vector:expr, |$field:ident| $body:expr) => {{
+        let vector: &TestVector = $vector;
+        match vector.backend {
+            Backend::Natural if vector.prime < 1 << 31 => {
+                let $field = NaturalPrimeField(vector.prime as i64);
+                Ok($body)
+            }
+            Backend::Montgomery32 if vector.prime < 1 << 31 => {
+                let $field = MontgomeryField32::new(vector.prime as u32);
+                Ok($body)
+            }
+            Backend::U64 if vector.prime < 1 << 63 => {
+                let $field = U64Field(vector.prime);
+                Ok($body)
+            }
+            Backend::Fp61 if vector.prime == Fp61::MODULUS => {
+                let $field = Fp61;
+                Ok($body)
+            }
+            _ => Err(VectorError::InvalidParameters),
+        }
+    }};
+}
+
+/// Parameters of the generated Shamir vectors: backend, prime, threshold and
+/// share count.
+const SHAMIR_PARAMS: [(Backend, u64, usize, usize); 5] = [
+    (Backend::Natural, 41, 2, 5),
+    (Backend::Natural, 746497, 9, 20),
+    (Backend::Montgomery32, 746497, 9, 20),
+    (Backend::U64, Fp61::MODULUS, 9, 20),
+    (Backend::Fp61, Fp61::MODULUS, 9, 20),
+];
+
+/// Parameters of the generated packed vectors: backend, prime, threshold, share
+/// count, secret count, `omega_secrets` and `omega_shares`.
+const PACKED_PARAMS: [(Backend, u64, usize, usize, usize, u64, u64); 4] = [
+    (Backend::Natural, 433, 4, 26, 3, 354, 17),
+    (Backend::Montgomery32, 433, 4, 26, 3, 354, 17),
+    (Backend::U64, 433, 4, 26, 3, 354, 17),
+    (Backend::Natural, 746497, 20, 80, 11, 341448, 69177),
+];
+
+/// The test vectors of both schemes over all backends.
+pub fn generate() -> Vec<TestVector> {
+    let shamir = SHAMIR_PARAMS
+        .iter()
+        .map(|&(backend, prime, threshold, share_count)| {
+            (
+                VectorScheme::Shamir,
+                backend,
+                prime,
+                threshold,
+                share_count,
+                1,
+                None,
+                None,
+            )
+        });
+    let packed = PACKED_PARAMS.iter().map(
+        |&(backend, prime, threshold, share_count, secret_count, omega_secrets, omega_shares)| {
+            (
+                VectorScheme::Packed,
+                backend,
+                prime,
+                threshold,
+                share_count,
+                secret_count,
+                Some(omega_secrets),
+                Some(omega_shares),
+            )
+        },
+    );
+    shamir
+        .chain(packed)
+        .enumerate()
+        .map(|(i, params)| {
+            let (
+                scheme,
+                backend,
+                prime,
+                threshold,
+                share_count,
+                secret_count,
+                omega_secrets,
+                omega_shares,
+            ) = params;
+            let mut vector = TestVector {
+                scheme,
+                backend,
+                prime,
+                threshold,
+                share_count,
+                secret_count,
+                omega_secrets,
+                omega_shares,
+                seed: [i as u8 + 1; 32],
+                secrets: vec![],
+                randomness: vec![],
+                shares: vec![],
+            };
+            let (secrets, randomness, shares) = check_parameters(&vector)
+                .and_then(|_| with_backend!(&vector, |field| sample(&vector, &field)))
+                .expect("invalid parameters for test vector");
+            vector.secrets = secrets;
+            vector.randomness = randomness;
+            vector.shares = shares;
+            vector
+        })
+        .collect()
+}
+
+/// Check that the crate reproduces `vector`: that its shares follow from its secrets
+/// and randomness, that both are sampled from its seed, and that the shares
+/// reconstruct the secrets.
+pub fn replay(vector: &TestVector) -> Result<(), VectorError> {
+    check_parameters(vector)?;
+    let elements = [
+        (&vector.secrets, vector.secret_count, "secrets"),
+        (&vector.randomness, vector.threshold, "randomness"),
+        (&vector.shares, vector.share_count, "shares"),
+    ];
+    for &(values, count, key) in &elements {
+        if values.len() != count || values.iter().any(|&value| value >= vector.prime) {
+            return Err(VectorError::Malformed(key));
+        }
+    }
+    with_backend!(vector, |field| {
+        check_shares(vector, &field)?;
+        let sampled = sample(vector, &field);
+        if sampled
+            != (
+                vector.secrets.clone(),
+                vector.randomness.clone(),
+                vector.shares.clone(),
+            )
+        {
+            return Err(VectorError::Sampling);
+        }
+        check_reconstruction(vector, &field)?
+    })
+}
+
+/// Replay all `vectors`, returning the position of the first failing one.
+pub fn replay_all(vectors: &[TestVector]) -> Result<(), (usize, VectorError)> {
+    for (position, vector) in vectors.iter().enumerate() {
+        replay(vector).map_err(|err| (position, err))?;
+    }
+    Ok(())
+}
+
+/// Write `vectors` as a JSON array.
+pub fn write_json<W: io::Write>(vectors: &[TestVector], out: &mut W) -> io::Result<()> {
+    let vectors: Vec<Value> = vectors.iter().map(to_json).collect();
+    serde_json::to_writer_pretty(&mut *out, &vectors)?;
+    writeln!(out)
+}
+
+/// Read vectors written by `write_json`.
+pub fn read_json<R: io::Read>(input: R) -> io::Result<Vec<TestVector>> {
+    let vectors: Vec<Value> = serde_json::from_reader(input)?;
+    vectors
+        .iter()
+        .map(|vector| {
+            from_json(vector).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        })
+        .collect()
+}
+
+fn to_json(vector: &TestVector) -> Value {
+    let strings = |values: &[u64]| -> Vec<String> { values.iter().map(u64::to_string).collect() };
+    let seed: String = vector
+        .seed
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    serde_json::json!({
+        "scheme": vector.scheme.name(),
+        "field": vector.backend.name(),
+        "prime": vector.prime.to_string(),
+        "threshold": vector.threshold,
+        "share_count": vector.share_count,
+        "secret_count": vector.secret_count,
+        "omega_secrets": vector.omega_secrets.map(|omega| omega.to_string()),
+        "omega_shares": vector.omega_shares.map(|omega| omega.to_string()),
+        "seed": seed,
+        "secrets": strings(&vector.secrets),
+        "randomness": strings(&vector.randomness),
+        "shares": strings(&vector.shares),
+    })
+}
+
+fn from_json(vector: &Value) -> Result<TestVector, VectorError> {
+    let string = |key| vector[key].as_str().ok_or(VectorError::Malformed(key));
+    let element = |key| {
+        string(key)?
+            .parse::<u64>()
+            .map_err(|_| VectorError::Malformed(key))
+    };
+    let optional_element = |key| match vector[key] {
+        Value::Null => Ok(None),
+        _ => element(key).map(Some),
+    };
+    let count = |key| {
+        vector[key]
+            .as_u64()
+            .map(|count| count as usize)
+            .ok_or(VectorError::Malformed(key))
+    };
+    let elements = |key| {
+        vector[key]
+            .as_array()
+            .ok_or(VectorError::Malformed(key))?
+            .iter()
+            .map(|value| {
+                value
+                    .as_str()
+                    .and_then(|value| value.parse::<u64>().ok())
+                    .ok_or(VectorError::Malformed(key))
+            })
+            .collect::<Result<Vec<u64>, VectorError>>()
+    };
+
+    let seed_hex = string("seed")?;
+    if seed_hex.len() != 64 || !seed_hex.is_ascii() {
+        return Err(VectorError::Malformed("seed"));
+    }
+    let mut seed = [0; 32];
+    for (byte, digits) in seed.iter_mut().zip(seed_hex.as_bytes().chunks(2)) {
+        let digits = ::std::str::from_utf8(digits).unwrap();
+        *byte = u8::from_str_radix(digits, 16).map_err(|_| VectorError::Malformed("seed"))?;
+    }
+
+    Ok(TestVector {
+        scheme: VectorScheme::from_name(string("scheme")?)
+            .ok_or(VectorError::Malformed("scheme"))?,
+        backend: Backend::from_name(string("field")?).ok_or(VectorError::Malformed("field"))?,
+        prime: element("prime")?,
+        threshold: count("threshold")?,
+        share_count: count("share_count")?,
+        secret_count: count("secret_count")?,
+        omega_secrets: optional_element("omega_secrets")?,
+        omega_shares: optional_element("omega_shares")?,
+        seed,
+        secrets: elements("secrets")?,
+        randomness: elements("randomness")?,
+        shares: elements("shares")?,
+    })
+}
+
+/// Check the constraints on the parameters of the scheme that would otherwise make
+/// sharing panic.
+fn check_parameters(vector: &TestVector) -> Result<(), VectorError> {
+    let limit = vector.threshold + vector.secret_count;
+    let valid = match vector.scheme {
+        VectorScheme::Shamir => vector.secret_count == 1,
+        VectorScheme::Packed => {
+            if vector.omega_secrets.is_none() {
+                return Err(VectorError::Malformed("omega_secrets"));
+            }
+            if vector.omega_shares.is_none() {
+                return Err(VectorError::Malformed("omega_shares"));
+            }
+            is_mixed_radix_len(limit + 1) && is_mixed_radix_len(vector.share_count + 1)
+        }
+    };
+    if valid && vector.share_count >= limit && (vector.share_count as u64) < vector.prime {
+        Ok(())
+    } else {
+        Err(VectorError::InvalidParameters)
+    }
+}
+
+/// Secrets, randomness and shares sampled from the seed of `vector`.
+fn sample<F>(vector: &TestVector, field: &F) -> (Vec<u64>, Vec<u64>, Vec<u64>)
+where
+    F: Field + Encode<u32> + IntegerCoding + Clone,
+    F::E: Clone,
+{
+    let mut rng = seeded_rng(&vector.seed);
+    let secrets = field.sample_with_replacement(vector.secret_count, &mut rng);
+    // the sharing draws its randomness first
+    let randomness = field.sample_with_replacement(vector.threshold, &mut rng.clone());
+    let shares = match vector.scheme {
+        VectorScheme::Shamir => {
+            shamir(vector, field.clone()).share_with_rng(secrets[0].clone(), &mut rng)
+        }
+        VectorScheme::Packed => packed(vector, field.clone()).share_with_rng(&secrets, &mut rng),
+    };
+    (
+        decode_u64(field, &secrets),
+        decode_u64(field, &randomness),
+        decode_u64(field, &shares),
+    )
+}
+
+/// Check the shares of `vector` against its secrets and randomness, computing them
+/// as described in the module documentation rather than with the schemes.
+fn check_shares<F>(vector: &TestVector, field: &F) -> Result<(), VectorError>
+where
+    F: Field + Encode<u32> + IntegerCoding,
+    F::E: Clone,
+{
+    let secrets = vector.secrets.iter().map(|&x| encode_u64(field, x));
+    let randomness = vector.randomness.iter().map(|&x| encode_u64(field, x));
+    let shares: Vec<F::E> = match vector.scheme {
+        VectorScheme::Shamir => {
+            let coefficients: Vec<F::E> = secrets.chain(randomness).collect();
+            (1..vector.share_count as u32 + 1)
+                .map(|point| mod_evaluate_polynomial(&coefficients, field.encode(point), field))
+                .collect()
+        }
+        VectorScheme::Packed => {
+            let omega_secrets = encode_u64(field, vector.omega_secrets.unwrap());
+            let omega_shares = encode_u64(field, vector.omega_shares.unwrap());
+            let limit = vector.threshold + vector.secret_count;
+            let points: Vec<F::E> = (0..limit as u32 + 1)
+                .map(|e| field.pow(&omega_secrets, e))
+                .collect();
+            let values: Vec<F::E> = iter::once(field.zero())
+                .chain(secrets)
+                .chain(randomness)
+                .collect();
+            (1..vector.share_count as u32 + 1)
+                .map(|i| {
+                    let point = field.pow(&omega_shares, i);
+                    lagrange_interpolation_at_point(&point, &points, &values, field)
+                })
+                .collect()
+        }
+    };
+    if decode_u64(field, &shares) == vector.shares {
+        Ok(())
+    } else {
+        Err(VectorError::Shares)
+    }
+}
+
+/// Check that the last `reconstruct_limit` shares of `vector` reconstruct its
+/// secrets.
+fn check_reconstruction<F>(vector: &TestVector, field: &F) -> Result<(), VectorError>
+where
+    F: Field + Encode<u32> + IntegerCoding + Clone,
+    F::E: Clone,
+{
+    let first = vector.share_count - vector.threshold - vector.secret_count;
+    let shares: Vec<F::E> = vector.shares[first..]
+        .iter()
+        .map(|&x| encode_u64(field, x))
+        .collect();
+    let secrets = match vector.scheme {
+        VectorScheme::Shamir => {
+            let indices: Vec<usize> = (first..vector.share_count).collect();
+            shamir(vector, field.clone())
+                .try_reconstruct(&indices, &shares)
+                .map(|secret| vec![secret])
+        }
+        VectorScheme::Packed => {
+            let indices: Vec<u32> = (first as u32..vector.share_count as u32).collect();
+            packed(vector, field.clone()).try_reconstruct(&indices, &shares)
+        }
+    };
+    match secrets {
+        Ok(ref secrets) if decode_u64(field, secrets) == vector.secrets => Ok(()),
+        _ => Err(VectorError::Reconstruction),
+    }
+}
+
+fn shamir<F: Field>(vector: &TestVector, field: F) -> ShamirSecretSharing<F>
+where
+    F::E: Clone,
+{
+    ShamirSecretSharing {
+        threshold: vector.threshold,
+        share_count: vector.share_count,
+        field,
+    }
+}
+
+fn packed<F: Field + Encode<u32>>(vector: &TestVector, field: F) -> PackedSecretSharing<F> {
+    let omega_secrets = encode_u64(&field, vector.omega_secrets.unwrap());
+    let omega_shares = encode_u64(&field, vector.omega_shares.unwrap());
+    PackedSecretSharing::with_roots(
+        vector.threshold,
+        vector.secret_count,
+        vector.share_count,
+        field,
+        omega_secrets,
+        omega_shares,
+    )
+}
+
+/// Element with canonical value `value`, which may not fit in a `u32`.
+fn encode_u64<F: Field + Encode<u32>>(field: &F, value: u64) -> F::E {
+    let shift = field.encode(1 << 16);
+    let high = field.encode((value >> 32) as u32);
+    let high = field.mul(field.mul(high, &shift), &shift);
+    field.add(high, field.encode(value as u32))
+}
+
+/// Canonical values of `elements`.
+fn decode_u64<F: IntegerCoding>(field: &F, elements: &[F::E]) -> Vec<u64> {
+    elements.iter().map(|e| field.decode_u64(e)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_fixtures() {
+        let fixtures: &[u8] = include_bytes!("../testvectors/vectors.json");
+        let vectors = read_json(fixtures).unwrap();
+        assert_eq!(replay_all(&vectors), Ok(()));
+        assert_eq!(vectors, generate());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let vectors = generate();
+        let mut json = vec![];
+        write_json(&vectors, &mut json).unwrap();
+        assert_eq!(read_json(&json[..]).unwrap(), vectors);
+    }
+
+    #[test]
+    fn test_replay_errors() {
+        let vectors = generate();
+        let shamir = &vectors[1];
+        let packed = &vectors[5];
+        assert_eq!(packed.scheme, VectorScheme::Packed);
+
+        let mut vector = packed.clone();
+        vector.shares[3] = (vector.shares[3] + 1) % vector.prime;
+        assert_eq!(replay(&vector), Err(VectorError::Shares));
+        let mut vector = shamir.clone();
+        vector.randomness[0] = (vector.randomness[0] + 1) % vector.prime;
+        assert_eq!(replay(&vector), Err(VectorError::Shares));
+
+        // consistent with the randomness, but not sampled from the seed
+        let mut vector = packed.clone();
+        vector.seed[0] ^= 1;
+        assert_eq!(replay(&vector), Err(VectorError::Sampling));
+
+        let mut vector = shamir.clone();
+        vector.shares.pop();
+        assert_eq!(replay(&vector), Err(VectorError::Malformed("shares")));
+        let mut vector = shamir.clone();
+        vector.secrets[0] = vector.prime;
+        assert_eq!(replay(&vector), Err(VectorError::Malformed("secrets")));
+        let mut vector = packed.clone();
+        vector.share_count = 25;
+        assert_eq!(replay(&vector), Err(VectorError::InvalidParameters));
+        let mut vector = shamir.clone();
+        vector.backend = Backend::Fp61;
+        assert_eq!(replay(&vector), Err(VectorError::InvalidParameters));
+    }
+
+    #[test]
+    fn test_malformed_json() {
+        let json = br#"[{"scheme": "shamir", "field": "natural"}]"#;
+        let err = read_json(&json[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "missing or malformed seed");
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Threshold BLS signatures over BLS12-381, with public keys in G1 and signatures
+//! in G2.
+//!
+//! Since signing is linear in the key, partial signatures are simply signatures
+//! under the shares, each verifiable under the verification key of its share,
+//! and `combine` interpolates them into the signature under the full key.
+
+extern crate bls12_381;
+extern crate sha2_09;
+
+use self::bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use self::bls12_381::{pairing, G1Affine, G1Projective, G2Affine, G2Projective};
+use fields::ScalarField;
+use shamir::ShamirSecretSharing;
+
+pub use self::bls12_381::Scalar;
+
+/// Domain separation tag of the ciphersuite, as for the basic scheme of the
+/// IETF BLS signature draft.
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Scheme for splitting BLS signing keys.
+pub fn scheme(threshold: usize, share_count: usize) -> ShamirSecretSharing<ScalarField<Scalar>> {
+    ShamirSecretSharing::for_scalars(threshold, share_count)
+}
+
+/// Public key of `secret_key`, or verification key of a share of it.
+pub fn public_key(secret_key: &Scalar) -> G1Projective {
+    G1Projective::generator() * secret_key
+}
+
+/// Point of G2 that `message` is signed as.
+pub fn hash_to_point(message: &[u8]) -> G2Projective {
+    <G2Projective as HashToCurve<ExpandMsgXmd<sha2_09::Sha256>>>::hash_to_curve(message, DST)
+}
+
+/// Signature of `message` under `secret_key`, or partial signature when given a
+/// share of it.
+pub fn sign(secret_key: &Scalar, message: &[u8]) -> G2Projective {
+    hash_to_point(message) * secret_key
+}
+
+/// Whether `signature` is a valid signature of `message` under `public_key`, or a
+/// valid partial signature under the verification key of a share.
+pub fn verify(public_key: &G1Projective, message: &[u8], signature: &G2Projective) -> bool {
+    let lhs = pairing(
+        &G1Affine::from(public_key),
+        &G2Affine::from(hash_to_point(message)),
+    );
+    let rhs = pairing(&G1Affine::generator(), &G2Affine::from(signature));
+    lhs == rhs
+}
+
+/// Combine the partial signatures of at least `threshold + 1` signers, with
+/// `indices` their ranks as output by `share`, into the signature under the full
+/// key.
+///
+/// Invalid partial signatures yield an invalid signature, so they should be checked
+/// with `verify` first when signers may misbehave.
+pub fn combine(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    indices: &[usize],
+    partial_signatures: &[G2Projective],
+) -> G2Projective {
+    super::combine_in_exponent(tss, indices, partial_signatures)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::Field;
+    use rand;
+
+    #[test]
+    fn test_threshold_signature() {
+        let tss = scheme(2, 5);
+        let secret_key = tss.field.sample(&mut rand::OsRng::new().unwrap());
+        let pk = public_key(&secret_key);
+        let shares = tss.share(secret_key);
+        let verification_keys: Vec<G1Projective> = ::threshold_sig::verification_keys(&shares);
+
+        let message = b"threshold";
+        let indices = [4, 1, 2];
+        let partials: Vec<G2Projective> =
+            indices.iter().map(|&i| sign(&shares[i], message)).collect();
+        for (&i, partial) in indices.iter().zip(&partials) {
+            assert!(verify(&verification_keys[i], message, partial));
+            assert!(!verify(&pk, message, partial));
+        }
+
+        let signature = combine(&tss, &indices, &partials);
+        assert_eq!(signature, sign(&secret_key, message));
+        assert!(verify(&pk, message, &signature));
+        assert!(!verify(&pk, b"other message", &signature));
+
+        // a single bad partial signature spoils the result
+        let mut partials = partials;
+        partials[1] = sign(&shares[0], message);
+        assert!(!verify(&pk, message, &combine(&tss, &indices, &partials)));
+    }
+}
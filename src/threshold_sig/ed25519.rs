@@ -0,0 +1,325 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Threshold Schnorr signatures over edwards25519 that verify as ordinary Ed25519
+//! signatures, following the two rounds of FROST.
+//!
+//! Unlike BLS, a Schnorr signature depends on a nonce that the signers must agree
+//! on. In the first round every signer runs `commit`, keeping the nonces secret and
+//! sending the commitments to the others. In the second round all signers build
+//! the same `SigningPackage` from the message and the commitments, and compute
+//! their partial signatures with `partial_sign`, which already carry their
+//! Lagrange coefficients so that `combine` only needs to add them up.
+//!
+//! Note that the signing key is a scalar rather than an Ed25519 seed, since seeds
+//! are hashed into keys in a way that cannot be distributed.
+
+extern crate curve25519_dalek;
+
+use self::curve25519_dalek::edwards::EdwardsPoint;
+use fields::{Field, ForwardSecureRng, ScalarField};
+use rand::Rng;
+use sha2::{Digest, Sha512};
+use shamir::ShamirSecretSharing;
+
+pub use self::curve25519_dalek::Scalar;
+
+/// Domain separation tag of the binding factors.
+const BINDING_DST: &[u8] = b"threshold-secret-sharing ed25519 binding factor";
+
+/// Scheme for splitting Ed25519 signing keys.
+pub fn scheme(threshold: usize, share_count: usize) -> ShamirSecretSharing<ScalarField<Scalar>> {
+    ShamirSecretSharing::for_scalars(threshold, share_count)
+}
+
+/// Public key of `secret_key`, or verification key of a share of it.
+pub fn public_key(secret_key: &Scalar) -> EdwardsPoint {
+    EdwardsPoint::mul_base(secret_key)
+}
+
+/// Secret nonces of a signer for a single signing session.
+///
+/// These are consumed by `partial_sign` since using them twice reveals the share.
+pub struct SigningNonces {
+    hiding: Scalar,
+    binding: Scalar,
+}
+
+/// Public commitments to the `SigningNonces` of a signer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SigningCommitments {
+    pub hiding: EdwardsPoint,
+    pub binding: EdwardsPoint,
+}
+
+/// Sample fresh nonces for a signing session, along with their commitments.
+pub fn commit() -> (SigningNonces, SigningCommitments) {
+    commit_with_rng(&mut ForwardSecureRng::new())
+}
+
+/// Like `commit`, sampling the nonces from `rng`.
+pub fn commit_with_rng<R: Rng>(rng: &mut R) -> (SigningNonces, SigningCommitments) {
+    let field = ScalarField::<Scalar>::new();
+    let nonces = SigningNonces {
+        hiding: field.sample_nonzero(rng),
+        binding: field.sample_nonzero(rng),
+    };
+    let commitments = SigningCommitments {
+        hiding: EdwardsPoint::mul_base(&nonces.hiding),
+        binding: EdwardsPoint::mul_base(&nonces.binding),
+    };
+    (nonces, commitments)
+}
+
+/// Everything the signers of a session must agree on.
+#[derive(Clone, Copy, Debug)]
+pub struct SigningPackage<'a> {
+    /// Public key of the shared signing key.
+    pub public_key: EdwardsPoint,
+    /// Message to sign.
+    pub message: &'a [u8],
+    /// Ranks of the signers as output by `share`, at least `threshold + 1` of them.
+    pub signers: &'a [usize],
+    /// Commitments of each signer, in the same order.
+    pub commitments: &'a [SigningCommitments],
+}
+
+impl<'a> SigningPackage<'a> {
+    fn position(&self, index: usize) -> usize {
+        assert_eq!(self.signers.len(), self.commitments.len());
+        self.signers
+            .iter()
+            .position(|&signer| signer == index)
+            .expect("index is not among the signers")
+    }
+
+    /// Binding factor of each signer, tying its nonces to the message and to the
+    /// commitments of all signers.
+    fn binding_factors(&self) -> Vec<Scalar> {
+        let mut prefix = Sha512::new();
+        prefix.update(BINDING_DST);
+        prefix.update(self.public_key.compress().as_bytes());
+        prefix.update((self.message.len() as u64).to_le_bytes());
+        prefix.update(self.message);
+        for (&signer, commitments) in self.signers.iter().zip(self.commitments) {
+            prefix.update((signer as u64).to_le_bytes());
+            prefix.update(commitments.hiding.compress().as_bytes());
+            prefix.update(commitments.binding.compress().as_bytes());
+        }
+        self.signers
+            .iter()
+            .map(|&signer| {
+                let mut hash = prefix.clone();
+                hash.update((signer as u64).to_le_bytes());
+                scalar_from_hash(hash)
+            })
+            .collect()
+    }
+
+    /// The commitment to the combined nonce, which is the `R` of the signature.
+    fn group_commitment(&self, binding_factors: &[Scalar]) -> EdwardsPoint {
+        self.commitments
+            .iter()
+            .zip(binding_factors)
+            .map(|(commitments, factor)| commitments.hiding + commitments.binding * factor)
+            .sum()
+    }
+}
+
+fn scalar_from_hash(hash: Sha512) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&hash.finalize().into())
+}
+
+/// Ed25519 challenge `H(R || A || M)`.
+fn challenge(r: &EdwardsPoint, public_key: &EdwardsPoint, message: &[u8]) -> Scalar {
+    let mut hash = Sha512::new();
+    hash.update(r.compress().as_bytes());
+    hash.update(public_key.compress().as_bytes());
+    hash.update(message);
+    scalar_from_hash(hash)
+}
+
+/// Partial signature of the signer with rank `index`, holding `share` and the
+/// `nonces` behind its commitments in `package`.
+pub fn partial_sign(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    package: &SigningPackage,
+    index: usize,
+    share: &Scalar,
+    nonces: SigningNonces,
+) -> Scalar {
+    let position = package.position(index);
+    let binding_factors = package.binding_factors();
+    let r = package.group_commitment(&binding_factors);
+    let c = challenge(&r, &package.public_key, package.message);
+    let lambda = super::lagrange_coefficients(tss, package.signers)[position];
+    nonces.hiding + nonces.binding * binding_factors[position] + lambda * share * c
+}
+
+/// Whether `partial_signature` is valid for the signer with rank `index`, under
+/// the verification key of its share.
+pub fn verify_partial(
+    tss: &ShamirSecretSharing<ScalarField<Scalar>>,
+    package: &SigningPackage,
+    index: usize,
+    verification_key: &EdwardsPoint,
+    partial_signature: &Scalar,
+) -> bool {
+    let position = package.position(index);
+    let binding_factors = package.binding_factors();
+    let r = package.group_commitment(&binding_factors);
+    let c = challenge(&r, &package.public_key, package.message);
+    let lambda = super::lagrange_coefficients(tss, package.signers)[position];
+    let commitments = &package.commitments[position];
+    EdwardsPoint::mul_base(partial_signature)
+        == commitments.hiding
+            + commitments.binding * binding_factors[position]
+            + verification_key * (lambda * c)
+}
+
+/// Combine the partial signatures of all signers in `package`, in the same
+/// order, into an Ed25519 signature `R || s` under its public key.
+///
+/// Invalid partial signatures yield an invalid signature, so they should be checked
+/// with `verify_partial` first when signers may misbehave.
+pub fn combine(package: &SigningPackage, partial_signatures: &[Scalar]) -> [u8; 64] {
+    assert_eq!(partial_signatures.len(), package.signers.len());
+    let r = package.group_commitment(&package.binding_factors());
+    let s: Scalar = partial_signatures.iter().sum();
+    let mut signature = [0; 64];
+    signature[..32].copy_from_slice(r.compress().as_bytes());
+    signature[32..].copy_from_slice(s.as_bytes());
+    signature
+}
+
+/// Whether `signature` is a valid Ed25519 signature of `message` under
+/// `public_key`, checking that `s.B - c.A = R` without cofactor.
+pub fn verify(public_key: &EdwardsPoint, message: &[u8], signature: &[u8; 64]) -> bool {
+    let mut r = [0; 32];
+    let mut s = [0; 32];
+    r.copy_from_slice(&signature[..32]);
+    s.copy_from_slice(&signature[32..]);
+    let s = match Option::<Scalar>::from(Scalar::from_canonical_bytes(s)) {
+        Some(s) => s,
+        None => return false,
+    };
+    let mut hash = Sha512::new();
+    hash.update(r);
+    hash.update(public_key.compress().as_bytes());
+    hash.update(message);
+    let c = scalar_from_hash(hash);
+    let expected = EdwardsPoint::vartime_double_scalar_mul_basepoint(&-c, public_key, &s);
+    expected.compress().to_bytes() == r
+}
+
+#[cfg(test)]
+mod tests {
+
+    extern crate ed25519_dalek;
+
+    use self::ed25519_dalek::{Signature, Verifier, VerifyingKey};
+    use super::*;
+    use rand::{ChaChaRng, SeedableRng};
+
+    #[test]
+    fn test_threshold_signature() {
+        let tss = scheme(2, 5);
+        let mut rng = ChaChaRng::from_seed(&[1]);
+        let secret_key = tss.field.sample(&mut rng);
+        let pk = public_key(&secret_key);
+        let shares = tss.share_with_rng(secret_key, &mut rng);
+        let verification_keys: Vec<EdwardsPoint> = ::threshold_sig::verification_keys(&shares);
+
+        // first round
+        let signers = [3, 0, 4];
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(|_| commit_with_rng(&mut rng)).unzip();
+
+        // second round
+        let message = b"threshold";
+        let package = SigningPackage {
+            public_key: pk,
+            message,
+            signers: &signers,
+            commitments: &commitments,
+        };
+        let partials: Vec<Scalar> = signers
+            .iter()
+            .zip(nonces)
+            .map(|(&i, nonces)| partial_sign(&tss, &package, i, &shares[i], nonces))
+            .collect();
+        for (&i, partial) in signers.iter().zip(&partials) {
+            assert!(verify_partial(
+                &tss,
+                &package,
+                i,
+                &verification_keys[i],
+                partial
+            ));
+            assert!(!verify_partial(
+                &tss,
+                &package,
+                i,
+                &verification_keys[1],
+                partial
+            ));
+        }
+
+        let signature = combine(&package, &partials);
+        assert!(verify(&pk, message, &signature));
+        assert!(!verify(&pk, b"other message", &signature));
+
+        // the signature is an ordinary Ed25519 signature
+        let verifying_key = VerifyingKey::from_bytes(pk.compress().as_bytes()).unwrap();
+        let signature = Signature::from_bytes(&signature);
+        assert!(verifying_key.verify(message, &signature).is_ok());
+        assert!(verifying_key.verify(b"other message", &signature).is_err());
+    }
+
+    #[test]
+    fn test_bad_partial_signature() {
+        let tss = scheme(1, 3);
+        let mut rng = ChaChaRng::from_seed(&[2]);
+        let secret_key = tss.field.sample(&mut rng);
+        let shares = tss.share_with_rng(secret_key, &mut rng);
+        let signers = [0, 2];
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(|_| commit_with_rng(&mut rng)).unzip();
+        let package = SigningPackage {
+            public_key: public_key(&secret_key),
+            message: b"threshold",
+            signers: &signers,
+            commitments: &commitments,
+        };
+        // the second signer uses the share of another one
+        let partials: Vec<Scalar> = signers
+            .iter()
+            .zip(nonces)
+            .map(|(&i, nonces)| partial_sign(&tss, &package, i, &shares[i / 2], nonces))
+            .collect();
+        assert!(verify_partial(
+            &tss,
+            &package,
+            0,
+            &public_key(&shares[0]),
+            &partials[0]
+        ));
+        assert!(!verify_partial(
+            &tss,
+            &package,
+            2,
+            &public_key(&shares[2]),
+            &partials[1]
+        ));
+        assert!(!verify(
+            &package.public_key,
+            b"threshold",
+            &combine(&package, &partials)
+        ));
+    }
+}
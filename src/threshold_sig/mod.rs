@@ -0,0 +1,82 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Threshold signatures from signing keys split with Shamir's scheme.
+//!
+//! The signing key is shared over the scalar field of the curve, using a
+//! `ShamirSecretSharing<ScalarField<_>>` as given by `for_scalars`. Each signer
+//! then produces a partial signature with its share, and any `threshold + 1` of
+//! them are combined into a signature valid under the original public key by
+//! Lagrange interpolation in the exponent, so that the key is never reconstructed.
+//!
+//! The `bls` module provides BLS signatures over BLS12-381 when the `bls`
+//! feature is enabled, and the `ed25519` module FROST-style Schnorr signatures
+//! verifiable as ordinary Ed25519 signatures when the `ed25519` feature is.
+
+extern crate ff;
+extern crate group;
+
+#[cfg(feature = "bls")]
+pub mod bls;
+#[cfg(feature = "ed25519")]
+pub mod ed25519;
+
+use self::ff::PrimeField;
+use self::group::Group;
+use fields::ScalarField;
+use numtheory::lagrange_coefficients_at_zero;
+use shamir::ShamirSecretSharing;
+
+impl<S: PrimeField> ShamirSecretSharing<ScalarField<S>> {
+    /// Scheme over the scalars `S` of a curve backend, for splitting signing keys.
+    pub fn for_scalars(threshold: usize, share_count: usize) -> Self {
+        ShamirSecretSharing {
+            threshold,
+            share_count,
+            field: ScalarField::new(),
+        }
+    }
+}
+
+/// Lagrange coefficients at zero of the signers with the given `indices`, in
+/// order, such that the weighted sum of their shares is the secret.
+pub fn lagrange_coefficients<S: PrimeField>(
+    tss: &ShamirSecretSharing<ScalarField<S>>,
+    indices: &[usize],
+) -> Vec<S> {
+    assert!(indices.len() >= tss.reconstruct_limit());
+    lagrange_coefficients_at_zero(indices, &tss.field)
+}
+
+/// Combine the `elements` computed by the signers with the given `indices`, each
+/// from its share, into the element that the secret would have given.
+///
+/// This is `reconstruct` carried out in the exponent: if every element is the
+/// generator multiplied by a share, the result is the generator multiplied by the
+/// secret, and likewise for any other base such as the hash of a message.
+pub fn combine_in_exponent<G>(
+    tss: &ShamirSecretSharing<ScalarField<G::Scalar>>,
+    indices: &[usize],
+    elements: &[G],
+) -> G
+where
+    G: Group,
+{
+    assert_eq!(elements.len(), indices.len());
+    lagrange_coefficients(tss, indices)
+        .iter()
+        .zip(elements)
+        .fold(G::identity(), |sum, (lambda, element)| {
+            sum + *element * lambda
+        })
+}
+
+/// Public verification key of each share, i.e. the generator multiplied by it.
+pub fn verification_keys<G: Group>(shares: &[G::Scalar]) -> Vec<G> {
+    shares.iter().map(|share| G::generator() * share).collect()
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2017 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Hash commitments to the shares issued by a dealer, so that recipients can later
+//! prove they submitted the share they were given.
+
+use std::fmt;
+
+use fields::ElementBytes;
+
+/// Hash function used for committing to shares.
+pub trait ShareHasher {
+    type Digest: Clone + PartialEq + fmt::Debug;
+
+    fn hash(&self, data: &[u8]) -> Self::Digest;
+}
+
+/// SHA-256 as a `ShareHasher`.
+#[cfg(feature = "sha2")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha256Hasher;
+
+#[cfg(feature = "sha2")]
+impl ShareHasher for Sha256Hasher {
+    type Digest = [u8; 32];
+
+    fn hash(&self, data: &[u8]) -> [u8; 32] {
+        use sha2::Digest;
+        sha2::Sha256::digest(data).into()
+    }
+}
+
+/// Commitments to every share of a sharing, in order of their indices.
+///
+/// The commitment to a share is the hash of its index, as four little-endian
+/// bytes, followed by the canonical bytes of its value given by `ElementBytes`, so
+/// that commitments do not depend on the platform or on the representation of
+/// the field. They are binding
+/// but not hiding: since shares in small fields can be found by exhaustive search,
+/// the transcript should only be given to parties allowed to learn the shares.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharingTranscript<F, H>
+where
+    H: ShareHasher,
+{
+    field: F,
+    hasher: H,
+    commitments: Vec<H::Digest>,
+}
+
+impl<F, H> SharingTranscript<F, H>
+where
+    F: ElementBytes,
+    H: ShareHasher,
+{
+    /// Commit to all `shares`, the share at position `i` having index `i`.
+    pub fn new(field: F, hasher: H, shares: &[F::E]) -> SharingTranscript<F, H> {
+        let commitments = shares
+            .iter()
+            .enumerate()
+            .map(|(index, share)| commit(&field, &hasher, index as u32, share))
+            .collect();
+        SharingTranscript {
+            field,
+            hasher,
+            commitments,
+        }
+    }
+
+    /// Commitments to the shares, in order of their indices.
+    pub fn commitments(&self) -> &[H::Digest] {
+        &self.commitments
+    }
+
+    /// Check that `share` is the share issued with the given index.
+    pub fn verify(&self, index: u32, share: &F::E) -> bool {
+        self.commitments
+            .get(index as usize)
+            .is_some_and(|c| *c == commit(&self.field, &self.hasher, index, share))
+    }
+}
+
+fn commit<F, H>(field: &F, hasher: &H, index: u32, share: &F::E) -> H::Digest
+where
+    F: ElementBytes,
+    H: ShareHasher,
+{
+    let mut bytes = index.to_le_bytes().to_vec();
+    field.write_element(share, &mut bytes);
+    hasher.hash(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fields::*;
+    use shamir::ShamirSecretSharing;
+
+    /// FNV-1a, which is of course not collision resistant.
+    struct Fnv;
+
+    impl ShareHasher for Fnv {
+        type Digest = u64;
+
+        fn hash(&self, data: &[u8]) -> u64 {
+            data.iter().fold(0xcbf29ce484222325, |h, &b| {
+                (h ^ b as u64).wrapping_mul(0x100000001b3)
+            })
+        }
+    }
+
+    #[test]
+    fn test_shamir_transcript() {
+        let tss = ShamirSecretSharing {
+            threshold: 2,
+            share_count: 5,
+            field: NaturalPrimeField(41),
+        };
+        let (shares, transcript) = tss.share_audited(7, Fnv);
+        assert_eq!(transcript.commitments().len(), 5);
+        for (index, share) in shares.iter().enumerate() {
+            assert!(transcript.verify(index as u32, share));
+            assert!(!transcript.verify(index as u32, &((share + 1) % 41)));
+        }
+        assert!(!transcript.verify(5, &shares[0]));
+    }
+
+    #[test]
+    fn test_packed_transcript() {
+        let pss = &::packed::PSS_4_8_3;
+        let (shares, transcript) = pss.share_audited(&[1, 2, 3], Fnv);
+        assert!(transcript.verify(3, &shares[3]));
+        assert!(!transcript.verify(3, &shares[4]));
+    }
+
+    #[test]
+    fn test_canonical_commitments() {
+        // Montgomery form stores elements scaled, but commits to the same bytes
+        let natural = NaturalPrimeField(433);
+        let montgomery = MontgomeryField32::new(433);
+        let shares = [5_u32, 432, 0, 17];
+        let natural_shares = natural.encode_slice(shares);
+        let montgomery_shares = montgomery.encode_slice(shares);
+        let expected = SharingTranscript::new(natural, Fnv, &natural_shares);
+        let transcript = SharingTranscript::new(montgomery.clone(), Fnv, &montgomery_shares);
+        assert_eq!(transcript.commitments(), expected.commitments());
+        assert!(transcript.verify(1, &montgomery.encode(432)));
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_sha256_hasher() {
+        let digest = Sha256Hasher.hash(b"abc");
+        assert_eq!(digest[..4], [0xba, 0x78, 0x16, 0xbf]);
+    }
+}